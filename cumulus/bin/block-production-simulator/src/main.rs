@@ -0,0 +1,153 @@
+// This file is part of Cumulus.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use clap::Parser;
+use cumulus_client_block_production_simulator::{
+	simulate, CollatorConfig, ConsensusHookParams, RelayChainConfig, SimulationConfig,
+};
+use std::time::Duration;
+use tracing_subscriber::filter::LevelFilter;
+
+/// Tool for simulating the block production of a parachain, to tune its elastic scaling
+/// parameters.
+///
+/// The slot-based collator is run against a simulated relay chain and parachain, and the
+/// throughput and latency of the parachain are reported. All the durations are in milliseconds.
+#[derive(Parser)]
+struct Cli {
+	/// The slot duration of the relay chain.
+	#[arg(long, default_value_t = 6000)]
+	relay_slot_duration: u64,
+
+	/// The time it takes for the relay chain blocks to be imported by the collator, since the
+	/// start of their slot.
+	///
+	/// The slots of the relay chain are given the entries of this comma separated pattern in turn,
+	/// `none` leaving the slot without block.
+	#[arg(long, value_delimiter = ',', default_value = "0")]
+	relay_block_times: Vec<String>,
+
+	/// The number of cores assigned to the parachain in the relay chain blocks.
+	///
+	/// The relay chain blocks are given the entries of this comma separated pattern in turn.
+	#[arg(long, value_delimiter = ',', default_value = "1")]
+	cores: Vec<u16>,
+
+	/// How old the relay parent of a parachain block may be to be backed, in blocks before the
+	/// parent of the relay chain block backing it.
+	#[arg(long, default_value_t = 2)]
+	allowed_ancestry_len: u32,
+
+	/// The slot duration of the parachain.
+	#[arg(long, default_value_t = 6000)]
+	slot_duration: u64,
+
+	/// The amount of time to spend authoring each block.
+	#[arg(long, default_value_t = 2000)]
+	authoring_duration: u64,
+
+	/// The time it takes to build a block, unless the authoring duration is shorter.
+	#[arg(long, default_value_t = 2000)]
+	block_build_time: u64,
+
+	/// Offset of the block production attempts, as passed to the slot-based collator.
+	#[arg(long, default_value_t = 1000)]
+	slot_offset: u64,
+
+	/// The time it takes for the `PoV` of a block to be imported by the backing group, once built.
+	#[arg(long, default_value_t = 500)]
+	pov_import_latency: u64,
+
+	/// The number of Aura authorities taking turns to author the blocks.
+	#[arg(long, default_value_t = 1)]
+	authorities: u32,
+
+	/// The `BLOCK_PROCESSING_VELOCITY` of the consensus hook of the runtime.
+	#[arg(long, default_value_t = 1)]
+	velocity: u32,
+
+	/// The `UNINCLUDED_SEGMENT_CAPACITY` of the consensus hook of the runtime.
+	#[arg(long, default_value_t = 3)]
+	unincluded_segment_capacity: u32,
+
+	/// The relay parent offset of the runtime.
+	#[arg(long, default_value_t = 0)]
+	relay_parent_offset: u32,
+
+	/// The simulated duration, in seconds.
+	#[arg(long, default_value_t = 600)]
+	duration: u64,
+}
+
+impl Cli {
+	fn config(&self) -> anyhow::Result<SimulationConfig> {
+		let block_times = self
+			.relay_block_times
+			.iter()
+			.map(|block_time| match block_time.trim() {
+				"none" => Ok(None),
+				block_time => block_time
+					.parse()
+					.map(|millis| Some(Duration::from_millis(millis)))
+					.map_err(|error| {
+						anyhow::anyhow!("Invalid relay chain block time `{block_time}`: {error}")
+					}),
+			})
+			.collect::<anyhow::Result<_>>()?;
+
+		Ok(SimulationConfig {
+			relay_chain: RelayChainConfig {
+				slot_duration: Duration::from_millis(self.relay_slot_duration),
+				block_times,
+				core_assignments: self.cores.clone(),
+				allowed_ancestry_len: self.allowed_ancestry_len,
+			},
+			collator: CollatorConfig {
+				slot_duration: Duration::from_millis(self.slot_duration),
+				authoring_duration: Duration::from_millis(self.authoring_duration),
+				block_build_time: Duration::from_millis(self.block_build_time),
+				slot_offset: Duration::from_millis(self.slot_offset),
+				pov_import_latency: Duration::from_millis(self.pov_import_latency),
+				authorities: self.authorities,
+			},
+			consensus_hook: ConsensusHookParams {
+				velocity: self.velocity,
+				unincluded_segment_capacity: self.unincluded_segment_capacity,
+				relay_parent_offset: self.relay_parent_offset,
+			},
+			duration: Duration::from_secs(self.duration),
+		})
+	}
+}
+
+fn main() -> anyhow::Result<()> {
+	// The collator logs every block it builds, only its warnings are shown by default.
+	let _ = tracing_subscriber::fmt()
+		.with_env_filter(
+			tracing_subscriber::EnvFilter::from_default_env()
+				.add_directive(LevelFilter::WARN.into()),
+		)
+		.with_writer(std::io::stderr)
+		.try_init();
+
+	let config = Cli::parse().config()?;
+	let report = simulate(&config)?;
+	print!("{report}");
+
+	Ok(())
+}
@@ -0,0 +1,240 @@
+// This file is part of Cumulus.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+use clap::Parser;
+use codec::Decode;
+use cumulus_client_pov_analyzer::{replay_blocks, Labels, StorageComposition};
+use cumulus_primitives_core::ParachainBlockData;
+use polkadot_node_primitives::{PoV, POV_BOMB_LIMIT};
+use polkadot_primitives::PersistedValidationData;
+use sc_executor::WasmExecutor;
+use sp_core::traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode};
+use sp_runtime::{
+	generic,
+	traits::{BlakeTwo256, Header as _},
+	OpaqueExtrinsic,
+};
+use std::{fs, path::PathBuf};
+use tracing::level_filters::LevelFilter;
+
+// This is now determined by the chain, call `validation_code_bomb_limit` API.
+// max_code_size * 10 = 30MB currently. Update constant if needed.
+const VALIDATION_CODE_BOMB_LIMIT: usize = 30 * 1024 * 1024;
+
+/// The parachain block, with the header used by all the parachains of the SDK.
+type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+type HostFunctions = (
+	sp_io::SubstrateHostFunctions,
+	cumulus_primitives_proof_size_hostfunction::storage_proof_size::HostFunctions,
+);
+
+/// Tool for analyzing what a `PoV` is made of.
+#[derive(Parser)]
+struct Cli {
+	/// The path to the `PoV` to analyze.
+	///
+	/// The `PoV`'s can be obtained by running `polkadot-parachains --collator --chain YOUR_CHAIN
+	/// --export-pov-to-path PATH_TO_EXPORT` and then choose one of the exported `PoV`'s.
+	#[arg(long)]
+	pov: PathBuf,
+
+	/// The path to the runtime of the parachain, as a WASM binary.
+	///
+	/// When given, storage items and calls are named after the metadata of the runtime and the
+	/// blocks are replayed to attribute the storage proof to their extrinsics.
+	#[arg(long)]
+	runtime: Option<PathBuf>,
+
+	/// The number of storage items to show, largest first.
+	#[arg(long, default_value_t = 20)]
+	items: usize,
+}
+
+fn main() -> anyhow::Result<()> {
+	let _ = tracing_subscriber::fmt()
+		.with_env_filter(
+			tracing_subscriber::EnvFilter::from_default_env()
+				.add_directive(LevelFilter::INFO.into()),
+		)
+		.with_writer(std::io::stderr)
+		.try_init();
+
+	let cli = Cli::parse();
+
+	let pov_file = fs::read(&cli.pov).map_err(|error| {
+		tracing::error!(%error, path = %cli.pov.display(), "Failed to read PoV");
+		anyhow::anyhow!("Failed to read PoV")
+	})?;
+
+	let pov_file_ptr = &mut &pov_file[..];
+	let pov = PoV::decode(pov_file_ptr).map_err(|error| {
+		tracing::error!(%error, "Failed to decode `PoV`");
+		anyhow::anyhow!("Failed to decode `PoV`")
+	})?;
+
+	let pvd: PersistedValidationData = Decode::decode(pov_file_ptr).map_err(|error| {
+		tracing::error!(%error, "Failed to `PersistedValidationData`");
+		anyhow::anyhow!("Failed to decode `PersistedValidationData`")
+	})?;
+
+	let block_data = sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT)
+		.map_err(|error| {
+			tracing::error!(%error, "Failed to decompress `PoV`");
+			anyhow::anyhow!("Failed to decompress `PoV`")
+		})?;
+
+	let block_data =
+		ParachainBlockData::<Block>::decode(&mut &block_data[..]).map_err(|error| {
+			tracing::error!(%error, "Failed to decode `ParachainBlockData`");
+			anyhow::anyhow!("Failed to decode `ParachainBlockData`")
+		})?;
+
+	let parent_header = <Block as sp_runtime::traits::Block>::Header::decode(
+		&mut &pvd.parent_head.0[..],
+	)
+	.map_err(|error| {
+		tracing::error!(%error, "Failed to decode the parent header");
+		anyhow::anyhow!("Failed to decode the parent header")
+	})?;
+	let parent_state_root = *parent_header.state_root();
+
+	let runtime_code = cli.runtime.as_ref().map(read_runtime).transpose()?;
+	let executor = WasmExecutor::<HostFunctions>::builder()
+		.with_allow_missing_host_functions(true)
+		.build();
+	let runtime_code = runtime_code.as_ref().map(|code| RuntimeCode {
+		code_fetcher: code,
+		heap_pages: None,
+		// The hash is used for caching, which we need here, but we only use one wasm file. So, the
+		// actual hash is not that important.
+		hash: vec![1, 2, 3],
+	});
+
+	let labels = match &runtime_code {
+		Some(runtime_code) => labels(&executor, runtime_code)?,
+		None => Labels::default(),
+	};
+
+	let composition = StorageComposition::from_compact_proof::<BlakeTwo256>(
+		block_data.proof(),
+		parent_state_root,
+	)
+	.map_err(|error| {
+		tracing::error!(%error, "Failed to analyze the storage proof");
+		anyhow::anyhow!("Failed to analyze the storage proof")
+	})?;
+
+	println!(
+		"PoV of {} block(s): {} bytes of blocks, {} bytes of compact storage proof, max {} bytes",
+		block_data.blocks().len(),
+		codec::Encode::encoded_size(&block_data.blocks()),
+		composition.compact_size,
+		pvd.max_pov_size,
+	);
+	println!();
+	println!(
+		"Storage proof: {} nodes, {} bytes before compaction",
+		composition.total.nodes, composition.total.bytes
+	);
+	for (item, usage) in composition.sorted_items().into_iter().take(cli.items) {
+		println!(
+			"{:>10} bytes {:>5.1}% {:>6} nodes  {}",
+			usage.bytes,
+			usage.bytes as f64 * 100.0 / composition.total.bytes.max(1) as f64,
+			usage.nodes,
+			labels.item(item),
+		);
+	}
+
+	let Some(runtime_code) = runtime_code else { return Ok(()) };
+
+	let blocks =
+		replay_blocks(&executor, &runtime_code, &block_data, parent_state_root, &labels).map_err(
+			|error| {
+				tracing::error!(%error, "Failed to replay the blocks");
+				anyhow::anyhow!("Failed to replay the blocks")
+			},
+		)?;
+
+	for block in blocks {
+		println!();
+		println!("Block {:?}: estimated storage proof size by step", block.hash);
+		println!("{:>10} bytes  initialize", block.initialize);
+		for extrinsic in block.extrinsics {
+			println!(
+				"{:>10} bytes  extrinsic {} ({} bytes): {}{}",
+				extrinsic.proof_size,
+				extrinsic.index,
+				extrinsic.encoded_size,
+				extrinsic.call.map_or_else(
+					|| "undecodable call".into(),
+					|(pallet, call)| labels.call(pallet, call)
+				),
+				if extrinsic.success { "" } else { " (failed)" },
+			);
+		}
+		println!("{:>10} bytes  finalize", block.finalize);
+	}
+
+	Ok(())
+}
+
+fn read_runtime(path: &PathBuf) -> anyhow::Result<WrappedRuntimeCode<'static>> {
+	let code = fs::read(path).map_err(|error| {
+		tracing::error!(%error, path = %path.display(), "Failed to read runtime");
+		anyhow::anyhow!("Failed to read runtime")
+	})?;
+
+	let code = sp_maybe_compressed_blob::decompress(&code, VALIDATION_CODE_BOMB_LIMIT).map_err(
+		|error| {
+			tracing::error!(%error, "Failed to decompress runtime");
+			anyhow::anyhow!("Failed to decompress runtime")
+		},
+	)?;
+
+	Ok(WrappedRuntimeCode(code.into_owned().into()))
+}
+
+/// Takes the names of storage items and calls from the metadata of the runtime.
+fn labels(
+	executor: &WasmExecutor<HostFunctions>,
+	runtime_code: &RuntimeCode,
+) -> anyhow::Result<Labels> {
+	let metadata = executor
+		.call(
+			&mut sp_io::TestExternalities::default().ext(),
+			runtime_code,
+			"Metadata_metadata",
+			&[],
+			CallContext::Offchain,
+		)
+		.0
+		.map_err(|error| {
+			tracing::error!(%error, "Failed to get the metadata of the runtime");
+			anyhow::anyhow!("Failed to get the metadata of the runtime")
+		})?;
+
+	Vec::<u8>::decode(&mut &metadata[..])
+		.map_err(Into::into)
+		.and_then(|metadata| Labels::from_metadata(&metadata))
+		.map_err(|error| {
+			tracing::error!(%error, "Failed to decode the metadata of the runtime");
+			anyhow::anyhow!("Failed to decode the metadata of the runtime")
+		})
+}
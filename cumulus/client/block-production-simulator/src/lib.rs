@@ -0,0 +1,367 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Simulates the block production of the slot-based collator, to tune the elastic scaling
+//! parameters of a parachain.
+//!
+//! Choosing the `BLOCK_PROCESSING_VELOCITY` and `UNINCLUDED_SEGMENT_CAPACITY` of the consensus
+//! hook, the relay parent offset and the number of cores of a parachain depends on how its blocks
+//! are produced, backed and included. [`simulate`] runs the slot-based collator of
+//! [`cumulus_client_consensus_aura`] against a simulated relay chain and parachain, on the paused
+//! clock of tokio, and reports the resulting throughput and latency:
+//!
+//! - The relay chain authors its blocks in the slots and assigns cores to the parachain following
+//!   the patterns of the [`RelayChainConfig`].
+//! - The collator builds empty blocks, taking the configured block build time, until the
+//!   [`ConsensusHookParams`] of the runtime forbid it, as checked by
+//!   [`cumulus_pallet_aura_ext::consensus_hook::can_build_upon`].
+//! - A block is backed in the first relay chain block authored after its `PoV` was imported by the
+//!   backing group, as long as its core is assigned to the parachain and its parent is backed. It
+//!   is included in the next relay chain block.
+//!
+//! The simulation is deterministic: the same [`SimulationConfig`] always results in the same
+//! [`Report`]. A single collator holds the keys of all the authorities and authors all the blocks.
+//!
+//! ```
+//! use cumulus_client_block_production_simulator::{simulate, SimulationConfig};
+//! use std::time::Duration;
+//!
+//! let mut config = SimulationConfig::default();
+//! config.relay_chain.core_assignments = vec![3];
+//! config.collator.slot_duration = Duration::from_secs(2);
+//! config.collator.authoring_duration = Duration::from_millis(1500);
+//! config.collator.block_build_time = Duration::from_millis(1500);
+//! config.consensus_hook.velocity = 3;
+//! config.consensus_hook.unincluded_segment_capacity = 7;
+//!
+//! let report = simulate(&config).unwrap();
+//! println!("{report}");
+//! ```
+
+mod parachain;
+mod relay_chain;
+mod report;
+mod simulation;
+
+pub use report::{Latency, Report, SkipReason};
+
+use std::time::Duration;
+
+/// Errors of an invalid [`SimulationConfig`].
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("The slot duration of the relay chain and the parachain must be at least 1ms")]
+	SlotDurationTooShort,
+	#[error("The relay chain block times must contain at least one block")]
+	NoRelayChainBlocks,
+	#[error("The relay chain block times must be shorter than its slot duration")]
+	BlockTimeTooLong,
+	#[error("The relay chain core assignments must not be empty")]
+	NoCoreAssignments,
+	#[error("The unincluded segment capacity must not be zero, or no block can ever be built")]
+	ZeroUnincludedSegmentCapacity,
+	#[error("The parachain must have at least one authority")]
+	NoAuthorities,
+}
+
+/// The simulated relay chain.
+#[derive(Clone, Debug)]
+pub struct RelayChainConfig {
+	/// The slot duration of the relay chain.
+	pub slot_duration: Duration,
+	/// The time it takes for the relay chain blocks to be imported by the collator, since the
+	/// start of their slot.
+	///
+	/// The slots of the relay chain are given the entries of this pattern in turn, `None` leaving
+	/// the slot without block.
+	pub block_times: Vec<Option<Duration>>,
+	/// The number of cores assigned to the parachain in the relay chain blocks.
+	///
+	/// The relay chain blocks are given the entries of this pattern in turn.
+	pub core_assignments: Vec<u16>,
+	/// How old the relay parent of a parachain block may be to be backed, in blocks before the
+	/// parent of the relay chain block backing it.
+	pub allowed_ancestry_len: u32,
+}
+
+impl Default for RelayChainConfig {
+	fn default() -> Self {
+		Self {
+			slot_duration: Duration::from_secs(6),
+			block_times: vec![Some(Duration::ZERO)],
+			core_assignments: vec![1],
+			allowed_ancestry_len: 2,
+		}
+	}
+}
+
+/// The simulated collator.
+#[derive(Clone, Debug)]
+pub struct CollatorConfig {
+	/// The slot duration of the parachain.
+	pub slot_duration: Duration,
+	/// The amount of time to spend authoring each block.
+	pub authoring_duration: Duration,
+	/// The time it takes to build a block, unless the authoring duration is shorter.
+	pub block_build_time: Duration,
+	/// Offset of the block production attempts, as passed to the slot-based collator.
+	pub slot_offset: Duration,
+	/// The time it takes for the `PoV` of a block to be imported by the backing group, once built.
+	pub pov_import_latency: Duration,
+	/// The number of Aura authorities taking turns to author the blocks.
+	///
+	/// The simulated collator holds the keys of all the authorities, and shortens the authoring
+	/// duration before handing over to the next one.
+	pub authorities: u32,
+}
+
+impl Default for CollatorConfig {
+	fn default() -> Self {
+		Self {
+			slot_duration: Duration::from_secs(6),
+			authoring_duration: Duration::from_secs(2),
+			block_build_time: Duration::from_secs(2),
+			slot_offset: Duration::from_secs(1),
+			pov_import_latency: Duration::from_millis(500),
+			authorities: 1,
+		}
+	}
+}
+
+/// The parameters of the consensus hook of the parachain runtime, i.e. of a
+/// `FixedVelocityConsensusHook`.
+#[derive(Clone, Debug)]
+pub struct ConsensusHookParams {
+	/// The velocity of the parachain: at most `velocity + 1` blocks are built on relay parents of
+	/// the same relay chain slot.
+	pub velocity: u32,
+	/// The maximum number of blocks in the unincluded segment.
+	///
+	/// Must not be zero: the runtime never allows building on a full unincluded segment, so no
+	/// block could be built after the genesis block.
+	pub unincluded_segment_capacity: u32,
+	/// The number of relay chain blocks between the best block and the relay parent, as returned
+	/// by the `RelayParentOffsetApi`.
+	pub relay_parent_offset: u32,
+}
+
+impl Default for ConsensusHookParams {
+	fn default() -> Self {
+		Self { velocity: 1, unincluded_segment_capacity: 3, relay_parent_offset: 0 }
+	}
+}
+
+/// The configuration of a simulation.
+#[derive(Clone, Debug)]
+pub struct SimulationConfig {
+	/// The simulated relay chain.
+	pub relay_chain: RelayChainConfig,
+	/// The simulated collator.
+	pub collator: CollatorConfig,
+	/// The parameters of the consensus hook of the parachain runtime.
+	pub consensus_hook: ConsensusHookParams,
+	/// The simulated duration.
+	pub duration: Duration,
+}
+
+impl Default for SimulationConfig {
+	fn default() -> Self {
+		Self {
+			relay_chain: Default::default(),
+			collator: Default::default(),
+			consensus_hook: Default::default(),
+			duration: Duration::from_secs(600),
+		}
+	}
+}
+
+impl SimulationConfig {
+	fn validate(&self) -> Result<(), Error> {
+		let relay_chain = &self.relay_chain;
+		// The slot durations are given to the collator in milliseconds.
+		let min_slot_duration = Duration::from_millis(1);
+		if relay_chain.slot_duration < min_slot_duration ||
+			self.collator.slot_duration < min_slot_duration
+		{
+			return Err(Error::SlotDurationTooShort)
+		}
+		if relay_chain.block_times.iter().all(Option::is_none) {
+			return Err(Error::NoRelayChainBlocks)
+		}
+		if relay_chain
+			.block_times
+			.iter()
+			.flatten()
+			.any(|time| *time >= relay_chain.slot_duration)
+		{
+			return Err(Error::BlockTimeTooLong)
+		}
+		if relay_chain.core_assignments.is_empty() {
+			return Err(Error::NoCoreAssignments)
+		}
+		if self.consensus_hook.unincluded_segment_capacity == 0 {
+			return Err(Error::ZeroUnincludedSegmentCapacity)
+		}
+		if self.collator.authorities == 0 {
+			return Err(Error::NoAuthorities)
+		}
+		Ok(())
+	}
+}
+
+/// Simulates the block production of a parachain with the given `config`.
+pub fn simulate(config: &SimulationConfig) -> Result<Report, Error> {
+	config.validate()?;
+	Ok(simulation::run(config))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn elastic_scaling_config() -> SimulationConfig {
+		let mut config = SimulationConfig::default();
+		config.relay_chain.core_assignments = vec![3];
+		config.collator.slot_duration = Duration::from_secs(2);
+		config.collator.authoring_duration = Duration::from_millis(1500);
+		config.collator.block_build_time = Duration::from_millis(1500);
+		config.consensus_hook.velocity = 3;
+		config.consensus_hook.unincluded_segment_capacity = 7;
+		config
+	}
+
+	#[test]
+	fn one_block_per_relay_chain_block_with_one_core() {
+		let report = simulate(&SimulationConfig::default()).unwrap();
+
+		assert_eq!(report.relay_blocks, 100);
+		assert_eq!(report.built_blocks, 100);
+		// The last block awaits backing and the one before it is still pending availability.
+		assert_eq!(report.backed_blocks, 99);
+		assert_eq!(report.included_blocks, 98);
+		assert_eq!(report.dropped_blocks, 0);
+		assert!(report.skipped_attempts.is_empty());
+		assert_eq!(report.max_unincluded_segment_len, 2);
+		let latency = Duration::from_secs(11);
+		assert_eq!(report.inclusion_latency, Latency { min: latency, mean: latency, max: latency });
+	}
+
+	#[test]
+	fn elastic_scaling_builds_on_all_cores() {
+		let report = simulate(&elastic_scaling_config()).unwrap();
+
+		assert_eq!(report.built_blocks, 299);
+		assert_eq!(report.included_blocks, 293);
+		assert!(report.skipped_attempts.is_empty());
+		assert_eq!(report.max_unincluded_segment_len, 7);
+		assert!(report.block_time().unwrap() < Duration::from_millis(2100));
+	}
+
+	#[test]
+	fn consensus_hook_limits_throughput() {
+		let mut config = elastic_scaling_config();
+		config.consensus_hook.unincluded_segment_capacity = 3;
+		let report = simulate(&config).unwrap();
+		assert_eq!(report.included_blocks, 147);
+		assert_eq!(report.skipped_attempts, [(SkipReason::UnincludedSegmentFull, 150)].into());
+
+		let mut config = elastic_scaling_config();
+		config.consensus_hook.velocity = 1;
+		let report = simulate(&config).unwrap();
+		assert_eq!(report.included_blocks, 196);
+		assert_eq!(report.skipped_attempts, [(SkipReason::VelocityReached, 100)].into());
+	}
+
+	#[test]
+	fn missed_relay_chain_slots_leave_no_core() {
+		let mut config = elastic_scaling_config();
+		config.relay_chain.block_times =
+			vec![Some(Duration::ZERO), Some(Duration::from_millis(1500)), None];
+		let report = simulate(&config).unwrap();
+
+		assert_eq!(report.relay_blocks, 67);
+		assert_eq!(report.included_blocks, 194);
+		assert_eq!(report.skipped_attempts, [(SkipReason::NoCore, 99)].into());
+	}
+
+	#[test]
+	fn blocks_are_dropped_when_their_relay_parent_expires() {
+		let mut config = SimulationConfig::default();
+		config.collator.pov_import_latency = Duration::from_secs(4);
+		let report = simulate(&config).unwrap();
+		assert_eq!(report.dropped_blocks, 0);
+		assert_eq!(report.backing_latency.max, Duration::from_secs(11));
+
+		config.relay_chain.allowed_ancestry_len = 0;
+		let report = simulate(&config).unwrap();
+		assert_eq!(report.built_blocks, 100);
+		assert_eq!(report.dropped_blocks, 99);
+		assert_eq!(report.block_time(), None);
+	}
+
+	#[test]
+	fn rotating_authors_skip_the_end_of_their_slot() {
+		let mut config = SimulationConfig::default();
+		config.relay_chain.core_assignments = vec![6];
+		config.collator.authoring_duration = Duration::from_millis(500);
+		config.collator.block_build_time = Duration::from_millis(400);
+		config.consensus_hook.velocity = 6;
+		config.consensus_hook.unincluded_segment_capacity = 12;
+		let report = simulate(&config).unwrap();
+		assert!(report.skipped_attempts.is_empty());
+
+		config.collator.authorities = 3;
+		let report = simulate(&config).unwrap();
+		assert_eq!(report.built_blocks, 500);
+		assert_eq!(report.skipped_attempts, [(SkipReason::AuthoringDeadline, 99)].into());
+	}
+
+	#[test]
+	fn simulation_is_deterministic() {
+		let mut config = elastic_scaling_config();
+		config.relay_chain.core_assignments = vec![1, 2, 3];
+		config.consensus_hook.relay_parent_offset = 1;
+
+		assert_eq!(simulate(&config).unwrap(), simulate(&config).unwrap());
+	}
+
+	#[test]
+	fn invalid_config_is_rejected() {
+		let mut config = SimulationConfig::default();
+		config.collator.slot_duration = Duration::from_micros(500);
+		assert!(matches!(simulate(&config), Err(Error::SlotDurationTooShort)));
+
+		config.collator.slot_duration = Duration::from_secs(6);
+		config.relay_chain.block_times = vec![None];
+		assert!(matches!(simulate(&config), Err(Error::NoRelayChainBlocks)));
+
+		config.relay_chain.block_times = vec![Some(Duration::from_secs(6))];
+		assert!(matches!(simulate(&config), Err(Error::BlockTimeTooLong)));
+
+		config.relay_chain.block_times = vec![Some(Duration::ZERO)];
+		config.relay_chain.core_assignments = vec![];
+		assert!(matches!(simulate(&config), Err(Error::NoCoreAssignments)));
+
+		config.relay_chain.core_assignments = vec![1];
+		config.consensus_hook.unincluded_segment_capacity = 0;
+		assert!(matches!(simulate(&config), Err(Error::ZeroUnincludedSegmentCapacity)));
+
+		config.consensus_hook.unincluded_segment_capacity = 3;
+		config.collator.authorities = 0;
+		assert!(matches!(simulate(&config), Err(Error::NoAuthorities)));
+	}
+}
@@ -0,0 +1,439 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! The simulated parachain: the client, runtime, proposer and block import the collator builds
+//! its blocks with.
+//!
+//! The blocks are empty and their state is never executed, only the consensus hook of the runtime
+//! is simulated.
+
+use crate::simulation::Simulation;
+use async_trait::async_trait;
+use cumulus_client_consensus_common::ParachainBlockImportMarker;
+use cumulus_pallet_aura_ext::consensus_hook;
+use cumulus_primitives_aura::{AuraUnincludedSegmentApi, Slot};
+use cumulus_primitives_core::{
+	rpsr_digest, CollationInfo, CollectCollationInfo, RelayParentOffsetApi,
+};
+use cumulus_primitives_parachain_inherent::{ParachainInherentData, INHERENT_IDENTIFIER};
+use futures::{future, FutureExt};
+use sc_client_api::{
+	backend::{AuxStore, NewBlockState},
+	in_mem, Backend as _, BlockBackend, BlockOf, ClientInfo, UsageProvider,
+};
+use sc_consensus::{BlockCheckParams, BlockImport, BlockImportParams, ImportResult};
+use sp_api::{ApiError, ApiRef, ProvideRuntimeApi};
+use sp_blockchain::{BlockStatus, HeaderBackend, Info};
+use sp_consensus::{Environment, Proposal, ProposeArgs, Proposer};
+use sp_consensus_aura::{ed25519::AuthorityId, SlotDuration};
+use sp_core::{
+	traits::{SpawnEssentialNamed, SpawnNamed},
+	Encode,
+};
+use sp_runtime::{
+	generic,
+	traits::{BlakeTwo256, Block as _, Hash as _, Header as _},
+	Justifications, OpaqueExtrinsic, StateVersion,
+};
+use std::{future::Future, pin::Pin, sync::Arc};
+
+/// The header of the simulated parachain.
+pub(crate) type Header = generic::Header<u32, BlakeTwo256>;
+/// The block of the simulated parachain.
+pub(crate) type Block = generic::Block<Header, OpaqueExtrinsic>;
+/// The hash of the simulated parachain blocks.
+pub(crate) type Hash = <BlakeTwo256 as sp_runtime::traits::Hash>::Output;
+
+/// Returns the state root of the blocks, which all have an empty state.
+fn empty_state_root() -> Hash {
+	BlakeTwo256::trie_root(Vec::new(), StateVersion::V1)
+}
+
+/// Returns the genesis header of the parachain.
+pub(crate) fn genesis_header() -> Header {
+	Header::new(0, Default::default(), empty_state_root(), Default::default(), Default::default())
+}
+
+/// The client of the simulated parachain, storing its blocks in memory.
+pub(crate) struct ParaClient {
+	backend: Arc<in_mem::Backend<Block>>,
+	simulation: Arc<Simulation>,
+}
+
+impl ParaClient {
+	/// Creates the client of a parachain only made of its genesis block.
+	pub fn new(simulation: Arc<Simulation>) -> Self {
+		let backend = Arc::new(in_mem::Backend::new());
+		let genesis = genesis_header();
+		backend
+			.blockchain()
+			.insert(genesis.hash(), genesis, None, Some(Vec::new()), NewBlockState::Final)
+			.expect("Inserting the genesis block in an empty backend never fails; qed");
+
+		Self { backend, simulation }
+	}
+
+	/// Returns the backend the blocks are stored in.
+	pub fn backend(&self) -> Arc<in_mem::Backend<Block>> {
+		self.backend.clone()
+	}
+}
+
+impl HeaderBackend<Block> for ParaClient {
+	fn header(&self, hash: Hash) -> sp_blockchain::Result<Option<Header>> {
+		self.backend.blockchain().header(hash)
+	}
+
+	fn info(&self) -> Info<Block> {
+		self.backend.blockchain().info()
+	}
+
+	fn status(&self, hash: Hash) -> sp_blockchain::Result<BlockStatus> {
+		self.backend.blockchain().status(hash)
+	}
+
+	fn number(&self, hash: Hash) -> sp_blockchain::Result<Option<u32>> {
+		self.backend.blockchain().number(hash)
+	}
+
+	fn hash(&self, number: u32) -> sp_blockchain::Result<Option<Hash>> {
+		self.backend.blockchain().hash(number)
+	}
+}
+
+impl BlockBackend<Block> for ParaClient {
+	fn block_body(&self, hash: Hash) -> sp_blockchain::Result<Option<Vec<OpaqueExtrinsic>>> {
+		sp_blockchain::Backend::body(self.backend.blockchain(), hash)
+	}
+
+	fn block_indexed_body(&self, _: Hash) -> sp_blockchain::Result<Option<Vec<Vec<u8>>>> {
+		Ok(None)
+	}
+
+	fn block(&self, _: Hash) -> sp_blockchain::Result<Option<generic::SignedBlock<Block>>> {
+		Ok(None)
+	}
+
+	fn block_status(&self, hash: Hash) -> sp_blockchain::Result<sp_consensus::BlockStatus> {
+		Ok(match self.header(hash)? {
+			Some(_) => sp_consensus::BlockStatus::InChainWithState,
+			None => sp_consensus::BlockStatus::Unknown,
+		})
+	}
+
+	fn justifications(&self, _: Hash) -> sp_blockchain::Result<Option<Justifications>> {
+		Ok(None)
+	}
+
+	fn block_hash(&self, number: u32) -> sp_blockchain::Result<Option<Hash>> {
+		self.hash(number)
+	}
+
+	fn indexed_transaction(&self, _: Hash) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		Ok(None)
+	}
+
+	fn requires_full_sync(&self) -> bool {
+		false
+	}
+}
+
+impl AuxStore for ParaClient {
+	fn insert_aux<
+		'a,
+		'b: 'a,
+		'c: 'a,
+		I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+		D: IntoIterator<Item = &'a &'b [u8]>,
+	>(
+		&self,
+		insert: I,
+		delete: D,
+	) -> sp_blockchain::Result<()> {
+		AuxStore::insert_aux(&*self.backend, insert, delete)
+	}
+
+	fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		AuxStore::get_aux(&*self.backend, key)
+	}
+}
+
+impl UsageProvider<Block> for ParaClient {
+	fn usage_info(&self) -> ClientInfo<Block> {
+		ClientInfo { chain: self.info(), usage: None }
+	}
+}
+
+impl BlockOf for ParaClient {
+	type Type = Block;
+}
+
+impl ProvideRuntimeApi<Block> for ParaClient {
+	type Api = RuntimeApi;
+
+	fn runtime_api(&self) -> ApiRef<'_, Self::Api> {
+		RuntimeApi { backend: self.backend.clone(), simulation: self.simulation.clone() }.into()
+	}
+}
+
+/// The runtime of the simulated parachain, with a `FixedVelocityConsensusHook`.
+pub(crate) struct RuntimeApi {
+	backend: Arc<in_mem::Backend<Block>>,
+	simulation: Arc<Simulation>,
+}
+
+impl RuntimeApi {
+	fn header(&self, hash: Hash) -> Result<Header, ApiError> {
+		self.backend.blockchain().header(hash).ok().flatten().ok_or_else(|| {
+			ApiError::Application(format!("Unknown parachain block {hash}").into())
+		})
+	}
+
+	/// Returns the relay chain slot of the relay parent of the given parachain block.
+	fn relay_slot(&self, header: &Header) -> Option<Slot> {
+		let (_, number) = rpsr_digest::extract_relay_parent_storage_root(header.digest())?;
+		let relay_chain = &self.simulation.relay_chain;
+		Some(relay_chain.block(relay_chain.index_of_number(number)?).slot)
+	}
+
+	/// Returns the `RelaySlotInfo` stored by the consensus hook at the given parachain block: the
+	/// relay chain slot of its relay parent and the number of blocks authored in that slot.
+	fn relay_slot_info(&self, header: &Header) -> Result<Option<(Slot, u32)>, ApiError> {
+		let Some(relay_slot) = self.relay_slot(header) else { return Ok(None) };
+
+		let mut authored = 0;
+		let mut header = header.clone();
+		while header.number > 0 && self.relay_slot(&header) == Some(relay_slot) {
+			authored += 1;
+			header = self.header(header.parent_hash)?;
+		}
+		Ok(Some((relay_slot, authored)))
+	}
+}
+
+sp_api::mock_impl_runtime_apis! {
+	impl sp_consensus_aura::AuraApi<Block, AuthorityId> for RuntimeApi {
+		fn slot_duration(&self) -> SlotDuration {
+			SlotDuration::from_millis(self.simulation.config.collator.slot_duration.as_millis() as u64)
+		}
+
+		fn authorities(&self) -> Vec<AuthorityId> {
+			self.simulation.authorities.clone()
+		}
+	}
+
+	impl AuraUnincludedSegmentApi<Block> for RuntimeApi {
+		#[advanced]
+		fn can_build_upon(
+			&self,
+			at: Hash,
+			included_hash: Hash,
+			slot: Slot,
+		) -> Result<bool, ApiError> {
+			let hook = &self.simulation.config.consensus_hook;
+			let parent = self.header(at)?;
+			let included = self.header(included_hash)?;
+			let size = parent.number - included.number;
+			let relay_slot_info = self.relay_slot_info(&parent)?;
+
+			let can_build_upon = consensus_hook::can_build_upon(
+				hook.velocity,
+				hook.unincluded_segment_capacity,
+				relay_slot_info,
+				slot,
+				|| size,
+			);
+			if !can_build_upon {
+				self.simulation.note_can_build_upon_rejected(size);
+			}
+			Ok(can_build_upon)
+		}
+	}
+
+	impl RelayParentOffsetApi<Block> for RuntimeApi {
+		fn relay_parent_offset(&self) -> u32 {
+			self.simulation.config.consensus_hook.relay_parent_offset
+		}
+	}
+
+	impl CollectCollationInfo<Block> for RuntimeApi {
+		fn collect_collation_info(header: &Header) -> CollationInfo {
+			CollationInfo {
+				upward_messages: Vec::new(),
+				horizontal_messages: Vec::new(),
+				new_validation_code: None,
+				processed_downward_messages: 0,
+				hrmp_watermark: 0,
+				head_data: header.encode().into(),
+			}
+		}
+	}
+}
+
+/// Imports the blocks built by the collator as the new best blocks.
+pub(crate) struct SimulatedBlockImport {
+	backend: Arc<in_mem::Backend<Block>>,
+	simulation: Arc<Simulation>,
+}
+
+impl SimulatedBlockImport {
+	pub fn new(client: &ParaClient) -> Self {
+		Self { backend: client.backend.clone(), simulation: client.simulation.clone() }
+	}
+}
+
+#[async_trait]
+impl BlockImport<Block> for SimulatedBlockImport {
+	type Error = sp_consensus::Error;
+
+	async fn check_block(&self, _: BlockCheckParams<Block>) -> Result<ImportResult, Self::Error> {
+		Ok(ImportResult::imported(false))
+	}
+
+	async fn import_block(
+		&self,
+		block: BlockImportParams<Block>,
+	) -> Result<ImportResult, Self::Error> {
+		let hash = block.post_hash();
+		self.simulation.note_imported(block.header.hash(), hash);
+		self.backend
+			.blockchain()
+			.insert(hash, block.post_header(), None, block.body, NewBlockState::Best)
+			.map_err(|error| sp_consensus::Error::ClientImport(error.to_string()))?;
+
+		Ok(ImportResult::imported(true))
+	}
+}
+
+impl ParachainBlockImportMarker for SimulatedBlockImport {}
+
+/// Creates a [`SimulatedProposer`] for each block.
+pub(crate) struct SimulatedProposerFactory {
+	simulation: Arc<Simulation>,
+}
+
+impl SimulatedProposerFactory {
+	pub fn new(simulation: Arc<Simulation>) -> Self {
+		Self { simulation }
+	}
+}
+
+impl Environment<Block> for SimulatedProposerFactory {
+	type Proposer = SimulatedProposer;
+	type CreateProposer = future::Ready<Result<SimulatedProposer, sp_consensus::Error>>;
+	type Error = sp_consensus::Error;
+
+	fn init(&mut self, parent_header: &Header) -> Self::CreateProposer {
+		future::ready(Ok(SimulatedProposer {
+			simulation: self.simulation.clone(),
+			parent_header: parent_header.clone(),
+		}))
+	}
+}
+
+/// Builds an empty block, taking the block build time of the collator.
+pub(crate) struct SimulatedProposer {
+	simulation: Arc<Simulation>,
+	parent_header: Header,
+}
+
+impl Proposer<Block> for SimulatedProposer {
+	type Error = sp_consensus::Error;
+	type Proposal = Pin<Box<dyn Future<Output = Result<Proposal<Block>, Self::Error>> + Send>>;
+
+	fn propose(self, args: ProposeArgs<Block>) -> Self::Proposal {
+		async move {
+			let parachain_inherent_data = args
+				.inherent_data
+				.get_data::<ParachainInherentData>(&INHERENT_IDENTIFIER)
+				.ok()
+				.flatten()
+				.ok_or_else(|| {
+					sp_consensus::Error::ClientImport("Missing parachain inherent data".into())
+				})?;
+			let validation_data = parachain_inherent_data.validation_data;
+
+			let started_at = self
+				.simulation
+				.note_proposed(self.parent_header.number, validation_data.relay_parent_number);
+			let build_time = self.simulation.config.collator.block_build_time;
+			tokio::time::sleep(build_time.min(args.max_duration)).await;
+
+			let mut digest = args.inherent_digests;
+			// Deposited by `cumulus-pallet-parachain-system` when the block is built.
+			digest.push(rpsr_digest::relay_parent_storage_root_item(
+				validation_data.relay_parent_storage_root,
+				validation_data.relay_parent_number,
+			));
+			let header = Header::new(
+				self.parent_header.number + 1,
+				Default::default(),
+				empty_state_root(),
+				self.parent_header.hash(),
+				digest,
+			);
+			self.simulation.note_built(header.hash(), started_at);
+
+			Ok(Proposal { block: Block::new(header, Vec::new()), storage_changes: Default::default() })
+		}
+		.boxed()
+	}
+}
+
+/// Spawns the tasks of the collator on the tokio runtime of the simulation.
+#[derive(Clone)]
+pub(crate) struct Spawner;
+
+impl SpawnNamed for Spawner {
+	fn spawn_blocking(
+		&self,
+		_: &'static str,
+		_: Option<&'static str>,
+		future: futures::future::BoxFuture<'static, ()>,
+	) {
+		tokio::spawn(future);
+	}
+
+	fn spawn(
+		&self,
+		_: &'static str,
+		_: Option<&'static str>,
+		future: futures::future::BoxFuture<'static, ()>,
+	) {
+		tokio::spawn(future);
+	}
+}
+
+impl SpawnEssentialNamed for Spawner {
+	fn spawn_essential_blocking(
+		&self,
+		name: &'static str,
+		group: Option<&'static str>,
+		future: futures::future::BoxFuture<'static, ()>,
+	) {
+		self.spawn_blocking(name, group, future)
+	}
+
+	fn spawn_essential(
+		&self,
+		name: &'static str,
+		group: Option<&'static str>,
+		future: futures::future::BoxFuture<'static, ()>,
+	) {
+		self.spawn(name, group, future)
+	}
+}
@@ -0,0 +1,393 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! The simulated relay chain and the [`RelayChainInterface`] the collator uses to follow it.
+
+use crate::{simulation::Simulation, RelayChainConfig};
+use async_trait::async_trait;
+use codec::Encode;
+use cumulus_primitives_aura::Slot;
+use cumulus_primitives_core::relay_chain::{BlockId, CandidateEvent};
+use cumulus_relay_chain_interface::{
+	BlockNumber, CommittedCandidateReceipt, CoreIndex, CoreState, InboundDownwardMessage,
+	InboundHrmpMessage, OccupiedCoreAssumption, OverseerHandle, PHash, PHeader, ParaId,
+	PersistedValidationData, RelayChainError, RelayChainInterface, RelayChainResult, SessionIndex,
+	StorageValue, ValidationCodeHash, ValidatorId,
+};
+use futures::{Stream, StreamExt};
+use polkadot_node_subsystem::{messages::CollationGenerationMessage, AllMessages};
+use polkadot_node_subsystem_util::metered::MeteredReceiver;
+use polkadot_overseer::Event;
+use polkadot_primitives::{runtime_api::ParachainHost, Block as PBlock, MAX_POV_SIZE};
+use sc_client_api::StorageProof;
+use sp_api::RuntimeApiInfo;
+use sp_consensus_babe::digests::{CompatibleDigestItem, PreDigest, SecondaryPlainPreDigest};
+use sp_core::H256;
+use sp_runtime::{generic::Digest, DigestItem};
+use sp_version::RuntimeVersion;
+use std::{
+	collections::{BTreeMap, HashMap, VecDeque},
+	pin::Pin,
+	sync::Arc,
+	time::Duration,
+};
+
+/// The para the claim queue assigns the cores not assigned to the simulated parachain to.
+const OTHER_PARA_ID: ParaId = ParaId::new(u32::MAX);
+
+/// A block of the simulated relay chain.
+pub(crate) struct RelayBlock {
+	pub header: PHeader,
+	pub hash: PHash,
+	pub slot: Slot,
+	/// When the block is authored, at the start of its slot.
+	pub authored_at: Duration,
+	/// When the block is imported by the collator.
+	pub imported_at: Duration,
+}
+
+/// A relay chain producing its blocks and assigning cores to the parachain following the patterns
+/// of a [`RelayChainConfig`].
+///
+/// The blocks are identified by their index, starting with the block the simulation starts on.
+pub(crate) struct RelayChain {
+	blocks: Vec<RelayBlock>,
+	indexes: HashMap<PHash, usize>,
+	core_assignments: Vec<u16>,
+	max_cores: u16,
+	scheduling_lookahead: u32,
+}
+
+impl RelayChain {
+	/// Builds the relay chain authored in the slots from `first_slot` until `end`, on top of a
+	/// block authored in the slot before.
+	pub fn new(config: &RelayChainConfig, first_slot: u64, end: Duration) -> Self {
+		let slot_start = |slot: u64| config.slot_duration * slot as u32;
+		let mut blocks = vec![RelayBlock::new(None, first_slot - 1, slot_start(first_slot - 1))];
+		for (slot, block_time) in (first_slot..).zip(config.block_times.iter().cycle()) {
+			let authored_at = slot_start(slot);
+			if authored_at >= end {
+				break
+			}
+
+			if let Some(block_time) = block_time {
+				let mut block = RelayBlock::new(blocks.last(), slot, authored_at);
+				block.imported_at += *block_time;
+				blocks.push(block);
+			}
+		}
+
+		Self {
+			indexes: blocks.iter().enumerate().map(|(index, block)| (block.hash, index)).collect(),
+			blocks,
+			core_assignments: config.core_assignments.clone(),
+			max_cores: config.core_assignments.iter().copied().max().unwrap_or_default(),
+			scheduling_lookahead: config.allowed_ancestry_len + 1,
+		}
+	}
+
+	/// Returns the number of blocks of the relay chain.
+	pub fn len(&self) -> usize {
+		self.blocks.len()
+	}
+
+	/// Returns the block with the given `index`.
+	pub fn block(&self, index: usize) -> &RelayBlock {
+		&self.blocks[index]
+	}
+
+	/// Returns the index of the block with the given `hash`.
+	pub fn index(&self, hash: &PHash) -> Option<usize> {
+		self.indexes.get(hash).copied()
+	}
+
+	/// Returns the index of the block with the given `number`.
+	pub fn index_of_number(&self, number: BlockNumber) -> Option<usize> {
+		let index = number.checked_sub(self.blocks[0].header.number)? as usize;
+		(index < self.blocks.len()).then_some(index)
+	}
+
+	/// Returns the number of cores assigned to the parachain in the block with the given `index`.
+	pub fn assigned_cores(&self, index: usize) -> u16 {
+		self.core_assignments[index % self.core_assignments.len()]
+	}
+
+	/// Returns the claim queue of the block with the given `index`, the core assigned to the
+	/// parachain in a later block being claimed at its depth.
+	fn claim_queue(&self, index: usize, para_id: ParaId) -> BTreeMap<CoreIndex, VecDeque<ParaId>> {
+		(0..self.max_cores)
+			.map(|core| {
+				let claims = (0..self.scheduling_lookahead as usize)
+					.map(|depth| {
+						if core < self.assigned_cores(index + 1 + depth) {
+							para_id
+						} else {
+							OTHER_PARA_ID
+						}
+					})
+					.collect();
+				(CoreIndex(core.into()), claims)
+			})
+			.collect()
+	}
+}
+
+impl RelayBlock {
+	/// Creates the block of the given `slot`, on top of `parent`.
+	fn new(parent: Option<&RelayBlock>, slot: u64, authored_at: Duration) -> Self {
+		let slot = Slot::from(slot);
+		let pre_digest =
+			PreDigest::SecondaryPlain(SecondaryPlainPreDigest { authority_index: 0, slot });
+		// The first block is not the genesis block, which has no pre-digest.
+		let number = parent.map_or(1, |parent| parent.header.number + 1);
+		let header = PHeader {
+			parent_hash: parent.map(|parent| parent.hash).unwrap_or_default(),
+			number,
+			// The relay parent storage roots identify the relay parents of the parachain blocks.
+			state_root: H256::from_low_u64_be(number.into()),
+			extrinsics_root: Default::default(),
+			digest: Digest { logs: vec![DigestItem::babe_pre_digest(pre_digest)] },
+		};
+
+		Self { hash: header.hash(), header, slot, authored_at, imported_at: authored_at }
+	}
+}
+
+/// The [`RelayChainInterface`] of the simulated relay chain.
+///
+/// The collator sees the relay chain blocks once they are authored and imported. The collations
+/// it submits through the overseer are received by [`receive_collations`].
+#[derive(Clone)]
+pub(crate) struct SimulatedRelayChainInterface {
+	simulation: Arc<Simulation>,
+	overseer_handle: OverseerHandle,
+}
+
+impl SimulatedRelayChainInterface {
+	pub fn new(simulation: Arc<Simulation>, overseer_handle: OverseerHandle) -> Self {
+		Self { simulation, overseer_handle }
+	}
+
+	fn index(&self, hash: PHash) -> RelayChainResult<usize> {
+		self.simulation
+			.relay_chain
+			.index(&hash)
+			.ok_or_else(|| RelayChainError::GenericError(format!("Unknown relay chain block {hash}")))
+	}
+}
+
+#[async_trait]
+impl RelayChainInterface for SimulatedRelayChainInterface {
+	async fn get_storage_by_key(
+		&self,
+		_: PHash,
+		_: &[u8],
+	) -> RelayChainResult<Option<StorageValue>> {
+		Ok(None)
+	}
+
+	async fn validators(&self, _: PHash) -> RelayChainResult<Vec<ValidatorId>> {
+		Ok(Vec::new())
+	}
+
+	async fn best_block_hash(&self) -> RelayChainResult<PHash> {
+		// The block builder fetches the best block first when attempting to build a block.
+		self.simulation.start_attempt();
+		Ok(self.simulation.relay_chain.block(self.simulation.best_relay_block()).hash)
+	}
+
+	async fn header(&self, block_id: BlockId) -> RelayChainResult<Option<PHeader>> {
+		let relay_chain = &self.simulation.relay_chain;
+		let index = match block_id {
+			BlockId::Hash(hash) => relay_chain.index(&hash),
+			BlockId::Number(number) => relay_chain.index_of_number(number),
+		};
+		Ok(index.map(|index| relay_chain.block(index).header.clone()))
+	}
+
+	async fn finalized_block_hash(&self) -> RelayChainResult<PHash> {
+		Ok(self.simulation.relay_chain.block(0).hash)
+	}
+
+	async fn call_runtime_api(
+		&self,
+		method_name: &'static str,
+		_: PHash,
+		_: &[u8],
+	) -> RelayChainResult<Vec<u8>> {
+		Err(RelayChainError::GenericError(format!("Runtime API {method_name} is not simulated")))
+	}
+
+	async fn retrieve_dmq_contents(
+		&self,
+		_: ParaId,
+		_: PHash,
+	) -> RelayChainResult<Vec<InboundDownwardMessage>> {
+		Ok(Vec::new())
+	}
+
+	async fn retrieve_all_inbound_hrmp_channel_contents(
+		&self,
+		_: ParaId,
+		_: PHash,
+	) -> RelayChainResult<BTreeMap<ParaId, Vec<InboundHrmpMessage>>> {
+		Ok(BTreeMap::new())
+	}
+
+	async fn persisted_validation_data(
+		&self,
+		block_id: PHash,
+		_: ParaId,
+		assumption: OccupiedCoreAssumption,
+	) -> RelayChainResult<Option<PersistedValidationData>> {
+		let index = self.index(block_id)?;
+		let Some(heads) = self.simulation.para_heads(index) else { return Ok(None) };
+		let parent_head = match assumption {
+			OccupiedCoreAssumption::Included => heads.backed,
+			OccupiedCoreAssumption::TimedOut | OccupiedCoreAssumption::Free => heads.included,
+		};
+		let header = &self.simulation.relay_chain.block(index).header;
+
+		Ok(Some(PersistedValidationData {
+			parent_head: parent_head.encode().into(),
+			relay_parent_number: header.number,
+			relay_parent_storage_root: header.state_root,
+			max_pov_size: MAX_POV_SIZE,
+		}))
+	}
+
+	#[allow(deprecated)]
+	async fn candidate_pending_availability(
+		&self,
+		_: PHash,
+		_: ParaId,
+	) -> RelayChainResult<Option<CommittedCandidateReceipt>> {
+		Ok(None)
+	}
+
+	async fn session_index_for_child(&self, _: PHash) -> RelayChainResult<SessionIndex> {
+		Ok(0)
+	}
+
+	async fn import_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		Ok(futures::stream::pending().boxed())
+	}
+
+	async fn new_best_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		Ok(futures::stream::pending().boxed())
+	}
+
+	async fn wait_for_block(&self, _: PHash) -> RelayChainResult<()> {
+		Ok(())
+	}
+
+	async fn finality_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		Ok(futures::stream::pending().boxed())
+	}
+
+	async fn is_major_syncing(&self) -> RelayChainResult<bool> {
+		Ok(false)
+	}
+
+	fn overseer_handle(&self) -> RelayChainResult<OverseerHandle> {
+		Ok(self.overseer_handle.clone())
+	}
+
+	async fn prove_read(&self, _: PHash, _: &Vec<Vec<u8>>) -> RelayChainResult<StorageProof> {
+		Ok(StorageProof::empty())
+	}
+
+	async fn validation_code_hash(
+		&self,
+		_: PHash,
+		_: ParaId,
+		_: OccupiedCoreAssumption,
+	) -> RelayChainResult<Option<ValidationCodeHash>> {
+		// The block builder checks the validation code right before authoring the block.
+		self.simulation.note_validation_code_checked();
+		Ok(Some(crate::simulation::validation_code_hash()))
+	}
+
+	async fn candidates_pending_availability(
+		&self,
+		_: PHash,
+		_: ParaId,
+	) -> RelayChainResult<Vec<CommittedCandidateReceipt>> {
+		Ok(Vec::new())
+	}
+
+	async fn version(&self, _: PHash) -> RelayChainResult<RuntimeVersion> {
+		let parachain_host_version = polkadot_node_subsystem::messages::RuntimeApiRequest::SCHEDULING_LOOKAHEAD_RUNTIME_REQUIREMENT;
+		Ok(RuntimeVersion {
+			apis: vec![(<dyn ParachainHost<PBlock>>::ID, parachain_host_version)].into(),
+			..Default::default()
+		})
+	}
+
+	async fn availability_cores(
+		&self,
+		_: PHash,
+	) -> RelayChainResult<Vec<CoreState<PHash, BlockNumber>>> {
+		Ok(Vec::new())
+	}
+
+	async fn claim_queue(
+		&self,
+		relay_parent: PHash,
+	) -> RelayChainResult<BTreeMap<CoreIndex, VecDeque<ParaId>>> {
+		let index = self.index(relay_parent)?;
+		Ok(self.simulation.relay_chain.claim_queue(index, crate::simulation::PARA_ID))
+	}
+
+	async fn scheduling_lookahead(&self, _: PHash) -> RelayChainResult<u32> {
+		Ok(self.simulation.relay_chain.scheduling_lookahead)
+	}
+
+	async fn candidate_events(&self, _: PHash) -> RelayChainResult<Vec<CandidateEvent>> {
+		Ok(Vec::new())
+	}
+}
+
+/// Authors the blocks of the relay chain at the start of their slots.
+pub(crate) async fn author_blocks(simulation: Arc<Simulation>) {
+	for index in 1..simulation.relay_chain.len() {
+		simulation.sleep_until(simulation.relay_chain.block(index).authored_at).await;
+		simulation.author_relay_block(index);
+	}
+}
+
+/// Receives the collations submitted by the collator through the overseer.
+pub(crate) async fn receive_collations(
+	simulation: Arc<Simulation>,
+	mut overseer: MeteredReceiver<Event>,
+) {
+	while let Some(event) = overseer.next().await {
+		if let Event::MsgToSubsystem {
+			msg: AllMessages::CollationGeneration(CollationGenerationMessage::SubmitCollation(params)),
+			..
+		} = event
+		{
+			simulation.receive_collation(params);
+		}
+	}
+}
@@ -0,0 +1,164 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! The report of a simulation.
+
+use std::{collections::BTreeMap, fmt, time::Duration};
+
+/// Why a block production attempt did not build a block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SkipReason {
+	/// No core is left to build on at the relay parent, or no relay parent or parent block was
+	/// found to build on.
+	NoCore,
+	/// The unincluded segment is full.
+	UnincludedSegmentFull,
+	/// The velocity of the parachain was reached for the relay chain slot of the relay parent.
+	VelocityReached,
+	/// Not enough time is left in the slot to author a block.
+	AuthoringDeadline,
+}
+
+impl fmt::Display for SkipReason {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let reason = match self {
+			Self::NoCore => "no core",
+			Self::UnincludedSegmentFull => "unincluded segment full",
+			Self::VelocityReached => "velocity reached",
+			Self::AuthoringDeadline => "authoring deadline",
+		};
+		f.write_str(reason)
+	}
+}
+
+/// Statistics of a latency over the parachain blocks.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Latency {
+	/// The lowest latency.
+	pub min: Duration,
+	/// The mean latency.
+	pub mean: Duration,
+	/// The highest latency.
+	pub max: Duration,
+}
+
+impl Latency {
+	pub(crate) fn new(samples: impl IntoIterator<Item = Duration>) -> Self {
+		let (mut count, mut sum) = (0u32, Duration::ZERO);
+		let mut latency = Self { min: Duration::MAX, ..Default::default() };
+		for sample in samples {
+			count += 1;
+			sum += sample;
+			latency.min = latency.min.min(sample);
+			latency.max = latency.max.max(sample);
+		}
+
+		if count == 0 {
+			return Self::default()
+		}
+		latency.mean = sum / count;
+		latency
+	}
+}
+
+impl fmt::Display for Latency {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "min {:?}, mean {:?}, max {:?}", self.min, self.mean, self.max)
+	}
+}
+
+/// The throughput and latency of the parachain over a simulation.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Report {
+	/// The simulated duration.
+	pub duration: Duration,
+	/// The number of relay chain blocks authored.
+	pub relay_blocks: u32,
+	/// The number of cores assigned to the parachain over all the relay chain blocks.
+	pub assigned_cores: u32,
+	/// The number of parachain blocks built.
+	pub built_blocks: u32,
+	/// The number of parachain blocks backed on the relay chain.
+	pub backed_blocks: u32,
+	/// The number of parachain blocks included on the relay chain.
+	pub included_blocks: u32,
+	/// The number of parachain blocks dropped because their relay parent expired before they were
+	/// backed, along with their descendants.
+	pub dropped_blocks: u32,
+	/// The number of block production attempts that did not build a block, by reason.
+	pub skipped_attempts: BTreeMap<SkipReason, u32>,
+	/// The maximum length of the unincluded segment when building a block.
+	pub max_unincluded_segment_len: u32,
+	/// The time from the start of the authoring of the parachain blocks to their backing.
+	pub backing_latency: Latency,
+	/// The time from the start of the authoring of the parachain blocks to their inclusion.
+	pub inclusion_latency: Latency,
+}
+
+impl Report {
+	/// Returns the mean time between two included parachain blocks.
+	pub fn block_time(&self) -> Option<Duration> {
+		(self.included_blocks > 0).then(|| self.duration / self.included_blocks)
+	}
+
+	/// Returns the share of the cores assigned to the parachain which were used to back its
+	/// blocks.
+	pub fn core_utilization(&self) -> f64 {
+		if self.assigned_cores == 0 {
+			return 0.0
+		}
+		self.backed_blocks as f64 / self.assigned_cores as f64
+	}
+}
+
+impl fmt::Display for Report {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(
+			f,
+			"Simulated {:?} over {} relay chain blocks.",
+			self.duration, self.relay_blocks
+		)?;
+		writeln!(
+			f,
+			"Blocks: {} built, {} backed, {} included, {} dropped.",
+			self.built_blocks, self.backed_blocks, self.included_blocks, self.dropped_blocks
+		)?;
+		match self.block_time() {
+			Some(block_time) => writeln!(f, "Block time: {block_time:?}.")?,
+			None => writeln!(f, "Block time: no block included.")?,
+		}
+		writeln!(
+			f,
+			"Core utilization: {:.1}% of {} assigned cores.",
+			self.core_utilization() * 100.0,
+			self.assigned_cores
+		)?;
+		writeln!(f, "Maximum unincluded segment length: {}.", self.max_unincluded_segment_len)?;
+		writeln!(f, "Backing latency: {}.", self.backing_latency)?;
+		writeln!(f, "Inclusion latency: {}.", self.inclusion_latency)?;
+		if self.skipped_attempts.is_empty() {
+			return Ok(())
+		}
+
+		let skipped_attempts = self
+			.skipped_attempts
+			.iter()
+			.map(|(reason, count)| format!("{count} {reason}"))
+			.collect::<Vec<_>>();
+		writeln!(f, "Skipped attempts: {}.", skipped_attempts.join(", "))
+	}
+}
@@ -0,0 +1,416 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! The simulation of the block production, running the slot-based collator against the simulated
+//! relay chain and parachain on the paused clock of tokio.
+
+use crate::{
+	parachain::{
+		genesis_header, Block, Hash, Header, ParaClient, SimulatedBlockImport,
+		SimulatedProposerFactory, Spawner,
+	},
+	relay_chain::{self, RelayChain, SimulatedRelayChainInterface},
+	report::{Latency, Report, SkipReason},
+	SimulationConfig,
+};
+use codec::Decode;
+use cumulus_client_collator::service::CollatorService;
+use cumulus_client_consensus_aura::collators::slot_based::{
+	self, SlotBasedBlockImport, TimeSource,
+};
+use cumulus_relay_chain_interface::{OverseerHandle, ParaId, ValidationCodeHash};
+use parking_lot::Mutex;
+use polkadot_node_primitives::SubmitCollationParams;
+use polkadot_node_subsystem_util::metered;
+use polkadot_primitives::{CollatorPair, CoreIndex};
+use sc_network_types::PeerId;
+use sp_application_crypto::key_types::AURA;
+use sp_consensus_aura::ed25519::{AuthorityId, AuthorityPair};
+use sp_core::{Pair, H256};
+use sp_keystore::{testing::MemoryKeystore, Keystore};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio::time::Instant;
+
+/// The id of the simulated parachain.
+pub(crate) const PARA_ID: ParaId = ParaId::new(2000);
+
+/// The relay chain slot the simulation starts at.
+const FIRST_RELAY_SLOT: u64 = 1_000;
+
+/// The capacity of the channel the collator sends its messages to the overseer with.
+const OVERSEER_CHANNEL_CAPACITY: usize = 1024;
+
+/// Returns the hash of the validation code of the simulated parachain.
+pub(crate) fn validation_code_hash() -> ValidationCodeHash {
+	H256::repeat_byte(1).into()
+}
+
+/// The heads of the parachain as of a relay chain block.
+#[derive(Clone)]
+pub(crate) struct ParaHeads {
+	/// The last included block.
+	pub included: Header,
+	/// The last backed block, the included one if no block is pending availability.
+	pub backed: Header,
+}
+
+/// A collation received by the backing group.
+struct Collation {
+	header: Header,
+	hash: Hash,
+	/// The index of the relay parent.
+	relay_parent: usize,
+	core: CoreIndex,
+	/// When the authoring of the block started.
+	started_at: Duration,
+	/// When the `PoV` of the block is imported by the backing group.
+	received_at: Duration,
+}
+
+/// What happened during a block production attempt.
+#[derive(Default)]
+struct Attempt {
+	proposed: bool,
+	rejected: Option<SkipReason>,
+	checked_validation_code: bool,
+}
+
+impl Attempt {
+	/// Returns why the attempt did not build a block, if it did not.
+	///
+	/// The block builder checks the validation code right before adjusting the authoring duration,
+	/// so an attempt checking it without proposing a block ran out of time.
+	fn skip_reason(&self) -> Option<SkipReason> {
+		if self.proposed {
+			None
+		} else if let Some(reason) = self.rejected {
+			Some(reason)
+		} else if self.checked_validation_code {
+			Some(SkipReason::AuthoringDeadline)
+		} else {
+			Some(SkipReason::NoCore)
+		}
+	}
+}
+
+#[derive(Default)]
+struct State {
+	/// The current block production attempt.
+	attempt: Option<Attempt>,
+	/// When the authoring of the blocks started, by hash before their seal until they are
+	/// imported.
+	started_at: HashMap<Hash, Duration>,
+	/// The parachain heads as of each authored relay chain block.
+	heads: Vec<ParaHeads>,
+	/// The collations waiting to be backed, in the order they were received.
+	collations: Vec<Collation>,
+	/// When the authoring of the blocks pending availability started.
+	pending_availability: Vec<Duration>,
+	backing_latencies: Vec<Duration>,
+	inclusion_latencies: Vec<Duration>,
+	report: Report,
+}
+
+/// The state of a simulation, shared by the simulated relay chain and parachain.
+pub(crate) struct Simulation {
+	pub config: SimulationConfig,
+	pub relay_chain: RelayChain,
+	/// The Aura authorities of the parachain, all authoring with the simulated collator.
+	pub authorities: Vec<AuthorityId>,
+	/// The time since the Unix epoch the simulation starts at.
+	start: Duration,
+	/// The instant of the tokio clock the simulation starts at.
+	clock: Instant,
+	state: Mutex<State>,
+}
+
+impl Simulation {
+	/// Creates the simulation, starting now on the tokio clock.
+	fn new(config: &SimulationConfig, authorities: Vec<AuthorityId>) -> Self {
+		let start = config.relay_chain.slot_duration * FIRST_RELAY_SLOT as u32;
+		let relay_chain =
+			RelayChain::new(&config.relay_chain, FIRST_RELAY_SLOT, start + config.duration);
+		let genesis = genesis_header();
+		let state = State {
+			heads: vec![ParaHeads { included: genesis.clone(), backed: genesis }],
+			report: Report { duration: config.duration, ..Default::default() },
+			..Default::default()
+		};
+
+		Self {
+			config: config.clone(),
+			relay_chain,
+			authorities,
+			start,
+			clock: Instant::now(),
+			state: Mutex::new(state),
+		}
+	}
+
+	/// Returns the current time, as a duration since the Unix epoch.
+	pub fn now(&self) -> Duration {
+		self.start + self.clock.elapsed()
+	}
+
+	/// Returns the source of the current time of the collator.
+	fn time_source(self: &Arc<Self>) -> TimeSource {
+		let simulation = self.clone();
+		Arc::new(move || simulation.now())
+	}
+
+	/// Sleeps until the given time since the Unix epoch.
+	pub async fn sleep_until(&self, time: Duration) {
+		tokio::time::sleep(time.saturating_sub(self.now())).await
+	}
+
+	/// Starts a block production attempt, ending the previous one.
+	pub fn start_attempt(&self) {
+		let mut state = self.state.lock();
+		Self::end_attempt(&mut state);
+		state.attempt = Some(Attempt::default());
+	}
+
+	fn end_attempt(state: &mut State) {
+		if let Some(reason) = state.attempt.take().and_then(|attempt| attempt.skip_reason()) {
+			*state.report.skipped_attempts.entry(reason).or_default() += 1;
+		}
+	}
+
+	/// Notes the runtime rejected building on an unincluded segment of `size` blocks.
+	pub fn note_can_build_upon_rejected(&self, size: u32) {
+		let reason = if size >= self.config.consensus_hook.unincluded_segment_capacity {
+			SkipReason::UnincludedSegmentFull
+		} else {
+			SkipReason::VelocityReached
+		};
+		if let Some(attempt) = &mut self.state.lock().attempt {
+			attempt.rejected = Some(reason);
+		}
+	}
+
+	/// Notes the validation code was checked.
+	pub fn note_validation_code_checked(&self) {
+		if let Some(attempt) = &mut self.state.lock().attempt {
+			attempt.checked_validation_code = true;
+		}
+	}
+
+	/// Notes a block is proposed on top of the block `parent_number`, returning when its
+	/// authoring started.
+	pub fn note_proposed(&self, parent_number: u32, relay_parent_number: u32) -> Duration {
+		let mut state = self.state.lock();
+		if let Some(attempt) = &mut state.attempt {
+			attempt.proposed = true;
+		}
+
+		let included = self
+			.relay_chain
+			.index_of_number(relay_parent_number)
+			.and_then(|index| state.heads.get(index))
+			.map_or(0, |heads| heads.included.number);
+		let segment_len = parent_number + 1 - included;
+		let report = &mut state.report;
+		report.max_unincluded_segment_len = report.max_unincluded_segment_len.max(segment_len);
+
+		self.now()
+	}
+
+	/// Notes the block with the given hash, before its seal, was built.
+	pub fn note_built(&self, pre_hash: Hash, started_at: Duration) {
+		self.state.lock().started_at.insert(pre_hash, started_at);
+	}
+
+	/// Notes the block with the given hash, before and after its seal, was imported.
+	pub fn note_imported(&self, pre_hash: Hash, hash: Hash) {
+		let mut state = self.state.lock();
+		if let Some(started_at) = state.started_at.remove(&pre_hash) {
+			state.started_at.insert(hash, started_at);
+			state.report.built_blocks += 1;
+		}
+	}
+
+	/// Receives a collation submitted by the collator, to be backed once its `PoV` is imported by
+	/// the backing group.
+	pub fn receive_collation(&self, params: SubmitCollationParams) {
+		let Ok(header) = Header::decode(&mut &params.collation.head_data.0[..]) else { return };
+		let Some(relay_parent) = self.relay_chain.index(&params.relay_parent) else { return };
+		let hash = header.hash();
+
+		let mut state = self.state.lock();
+		let Some(started_at) = state.started_at.remove(&hash) else { return };
+		state.collations.push(Collation {
+			header,
+			hash,
+			relay_parent,
+			core: params.core_index,
+			started_at,
+			received_at: self.now() + self.config.collator.pov_import_latency,
+		});
+	}
+
+	/// Authors the relay chain block with the given `index`, including and backing the parachain
+	/// blocks.
+	pub fn author_relay_block(&self, index: usize) {
+		let authored_at = self.relay_chain.block(index).authored_at;
+		let assigned_cores = self.relay_chain.assigned_cores(index);
+		let mut state = self.state.lock();
+		let state = &mut *state;
+		state.report.relay_blocks += 1;
+		state.report.assigned_cores += u32::from(assigned_cores);
+
+		// The blocks backed in the previous relay chain block are available.
+		let pending_availability = std::mem::take(&mut state.pending_availability);
+		state.report.included_blocks += pending_availability.len() as u32;
+		state
+			.inclusion_latencies
+			.extend(pending_availability.into_iter().map(|started_at| authored_at - started_at));
+
+		// The blocks are backed in order, each on its own core, as long as their relay parent is
+		// in the allowed ancestry of the relay chain block.
+		let included = state.heads[index - 1].backed.clone();
+		let mut tip = included.clone();
+		let min_relay_parent =
+			(index - 1).saturating_sub(self.config.relay_chain.allowed_ancestry_len as usize);
+		let mut used_cores = Vec::new();
+		while let Some(position) = state.collations.iter().position(|collation| {
+			collation.header.parent_hash == tip.hash() &&
+				(min_relay_parent..index).contains(&collation.relay_parent) &&
+				collation.received_at <= authored_at &&
+				collation.core.0 < u32::from(assigned_cores) &&
+				!used_cores.contains(&collation.core)
+		}) {
+			let collation = state.collations.remove(position);
+			used_cores.push(collation.core);
+			state.report.backed_blocks += 1;
+			state.backing_latencies.push(authored_at - collation.started_at);
+			state.pending_availability.push(collation.started_at);
+			tip = collation.header;
+		}
+		state.heads.push(ParaHeads { included, backed: tip.clone() });
+
+		// The collations can't be backed once their relay parent left the allowed ancestry, or
+		// once they don't descend from the backed block anymore, and neither can their
+		// descendants.
+		let min_relay_parent =
+			index.saturating_sub(self.config.relay_chain.allowed_ancestry_len as usize);
+		let mut dropped = Vec::new();
+		state.collations.retain(|collation| {
+			let drop = collation.relay_parent < min_relay_parent ||
+				collation.header.number <= tip.number ||
+				dropped.contains(&collation.header.parent_hash);
+			if drop {
+				dropped.push(collation.hash);
+			}
+			!drop
+		});
+		state.report.dropped_blocks += dropped.len() as u32;
+	}
+
+	/// Returns the index of the best relay chain block imported by the collator.
+	pub fn best_relay_block(&self) -> usize {
+		let now = self.now();
+		let authored = self.state.lock().heads.len();
+		(0..authored)
+			.rev()
+			.find(|index| self.relay_chain.block(*index).imported_at <= now)
+			.unwrap_or_default()
+	}
+
+	/// Returns the parachain heads as of the relay chain block with the given `index`.
+	pub fn para_heads(&self, index: usize) -> Option<ParaHeads> {
+		self.state.lock().heads.get(index).cloned()
+	}
+
+	/// Ends the simulation, returning its report.
+	fn finish(&self) -> Report {
+		let mut state = self.state.lock();
+		Self::end_attempt(&mut state);
+
+		let mut report = state.report.clone();
+		report.backing_latency = Latency::new(state.backing_latencies.iter().copied());
+		report.inclusion_latency = Latency::new(state.inclusion_latencies.iter().copied());
+		report
+	}
+}
+
+/// Runs the simulation with the given `config` until the end of the simulated duration.
+pub(crate) fn run(config: &SimulationConfig) -> Report {
+	tokio::runtime::Builder::new_current_thread()
+		.enable_time()
+		.start_paused(true)
+		.build()
+		.expect("Building a runtime without I/O driver does not fail; qed")
+		.block_on(simulate(config))
+}
+
+async fn simulate(config: &SimulationConfig) -> Report {
+	// A single collator holds the keys of all the authorities.
+	let keystore = MemoryKeystore::new();
+	let authorities = (0..config.collator.authorities)
+		.map(|index| {
+			let seed = format!("//Authority{index}");
+			keystore
+				.ed25519_generate_new(AURA, Some(&seed))
+				.expect("The memory keystore generates keys from any seed; qed")
+				.into()
+		})
+		.collect();
+
+	let simulation = Arc::new(Simulation::new(config, authorities));
+	let (overseer_sender, overseer_receiver) = metered::channel(OVERSEER_CHANNEL_CAPACITY);
+	let relay_client =
+		SimulatedRelayChainInterface::new(simulation.clone(), OverseerHandle::new(overseer_sender));
+	let para_client = Arc::new(ParaClient::new(simulation.clone()));
+	let (_, block_import_handle) = SlotBasedBlockImport::<Block, (), ()>::new((), Arc::new(()));
+
+	slot_based::run::<Block, AuthorityPair, _, _, _, _, _, _, _, _, _>(slot_based::Params {
+		create_inherent_data_providers: |_, ()| async { Ok(()) },
+		block_import: SimulatedBlockImport::new(&para_client),
+		para_client: para_client.clone(),
+		para_backend: para_client.backend(),
+		relay_client,
+		code_hash_provider: |_| Some(validation_code_hash()),
+		keystore: Arc::new(keystore),
+		collator_key: CollatorPair::from_seed(&[1; 32]),
+		collator_peer_id: PeerId::random(),
+		para_id: PARA_ID,
+		proposer: SimulatedProposerFactory::new(simulation.clone()),
+		collator_service: CollatorService::new(
+			para_client.clone(),
+			Arc::new(Spawner),
+			Arc::new(|_, _| {}),
+			para_client.clone(),
+		),
+		authoring_duration: config.collator.authoring_duration,
+		reinitialize: false,
+		slot_offset: config.collator.slot_offset,
+		block_import_handle,
+		spawner: Spawner,
+		relay_chain_slot_duration: config.relay_chain.slot_duration,
+		export_pov: None,
+		max_pov_percentage: None,
+		authoring_lease: None,
+		core_scarcity: None,
+		pov_metrics: None,
+		time_source: Some(simulation.time_source()),
+	});
+	tokio::spawn(relay_chain::author_blocks(simulation.clone()));
+	tokio::spawn(relay_chain::receive_collations(simulation.clone(), overseer_receiver));
+
+	simulation.sleep_until(simulation.start + config.duration).await;
+	simulation.finish()
+}
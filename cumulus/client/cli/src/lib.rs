@@ -304,6 +304,21 @@ pub struct RunCmd {
 	)]
 	pub relay_chain_rpc_urls: Vec<Url>,
 
+	/// Verify the relay chain data retrieved from the RPC endpoints like a light client, starting
+	/// from the checkpoint stored in this JSON file.
+	///
+	/// The checkpoint must be obtained from a trusted source and contains the relay chain
+	/// `genesisHash`, a finalized `header`, and the `setId` and `authorities` of the GRANDPA
+	/// authority set finalizing its descendants. The RPC endpoints must support the
+	/// `grandpa_proveFinality` method.
+	///
+	/// The headers, storage and the runtime API results used to build collations are verified.
+	/// The runtime version, availability cores, candidate events and other runtime API calls
+	/// can't be verified without executing the relay chain runtime and are taken unverified from
+	/// the RPC endpoints.
+	#[arg(long, requires = "relay_chain_rpc_urls")]
+	pub relay_chain_light_client_checkpoint: Option<PathBuf>,
+
 	/// EXPERIMENTAL: This is meant to be used only if collator is overshooting the PoV size, and
 	/// building blocks that do not fit in the max_pov_size. It is a percentage of the max_pov_size
 	/// configuration of the relay-chain.
@@ -340,6 +355,11 @@ impl RunCmd {
 	pub fn collator_options(&self) -> CollatorOptions {
 		let relay_chain_mode = if self.relay_chain_rpc_urls.is_empty() {
 			RelayChainMode::Embedded
+		} else if let Some(checkpoint) = &self.relay_chain_light_client_checkpoint {
+			RelayChainMode::LightClient {
+				rpc_urls: self.relay_chain_rpc_urls.clone(),
+				checkpoint: checkpoint.clone(),
+			}
 		} else {
 			RelayChainMode::ExternalRpc(self.relay_chain_rpc_urls.clone())
 		};
@@ -359,6 +379,14 @@ pub enum RelayChainMode {
 	Embedded,
 	/// Connect to remote relay chain node via websocket RPC
 	ExternalRpc(Vec<Url>),
+	/// Connect to untrusted remote relay chain nodes via websocket RPC, verifying their data like
+	/// a light client
+	LightClient {
+		/// The URLs of the remote relay chain nodes
+		rpc_urls: Vec<Url>,
+		/// The file storing the checkpoint from which the relay chain data is verified
+		checkpoint: PathBuf,
+	},
 }
 
 /// Options only relevant for collator/parachain nodes
@@ -0,0 +1,149 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the blocks built by the collator.
+
+use cumulus_client_collator::orphaned_blocks::{
+	OrphanedBlock, OrphanedBlocks, OrphanedTransaction,
+};
+use cumulus_primitives_core::{
+	relay_chain::{BlockNumber as RelayBlockNumber, Hash as RelayHash},
+	RelayBlockIdentifier,
+};
+use jsonrpsee::{core::RpcResult, proc_macros::rpc, Extensions};
+use sc_rpc_api::check_if_safe;
+use serde::{Deserialize, Serialize};
+use sp_runtime::traits::Block as BlockT;
+
+/// Provides RPC methods for inspecting the blocks built by the collator.
+#[rpc(client, server)]
+pub trait CollatorApi<Hash> {
+	/// Returns the most recent blocks built by the collator that were orphaned by relay chain
+	/// reorganizations, with the transactions that were returned to the transaction pool or
+	/// included again.
+	#[method(name = "collator_orphanedBlocks", with_extensions)]
+	fn orphaned_blocks(&self) -> RpcResult<OrphanedBlocksReport<Hash>>;
+}
+
+/// Implements the [`CollatorApiServer`] on top of an [`OrphanedBlocks`] tracker.
+pub struct Collator<Block: BlockT> {
+	orphaned_blocks: OrphanedBlocks<Block>,
+}
+
+impl<Block: BlockT> Collator<Block> {
+	/// Creates a new instance of the collator RPC handler.
+	pub fn new(orphaned_blocks: OrphanedBlocks<Block>) -> Self {
+		Self { orphaned_blocks }
+	}
+}
+
+impl<Block: BlockT> CollatorApiServer<Block::Hash> for Collator<Block> {
+	fn orphaned_blocks(&self, ext: &Extensions) -> RpcResult<OrphanedBlocksReport<Block::Hash>> {
+		check_if_safe(ext)?;
+
+		Ok(OrphanedBlocksReport {
+			orphaned_total: self.orphaned_blocks.orphaned_total(),
+			requeued_total: self.orphaned_blocks.requeued_total(),
+			reincluded_total: self.orphaned_blocks.reincluded_total(),
+			blocks: self.orphaned_blocks.orphaned_blocks().into_iter().map(Into::into).collect(),
+		})
+	}
+}
+
+/// The blocks built by the collator that were orphaned by relay chain reorganizations.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedBlocksReport<Hash> {
+	/// The number of blocks orphaned since the node started.
+	pub orphaned_total: u64,
+	/// The number of transactions of orphaned blocks returned to the transaction pool since the
+	/// node started.
+	pub requeued_total: u64,
+	/// The number of transactions of orphaned blocks included again in the best chain since the
+	/// node started.
+	pub reincluded_total: u64,
+	/// The most recently orphaned blocks, oldest first.
+	pub blocks: Vec<OrphanedBlockReport<Hash>>,
+}
+
+/// A block orphaned by a relay chain reorganization.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrphanedBlockReport<Hash> {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The number of the block.
+	pub number: u64,
+	/// The hash of the relay parent, if the block identifies its relay parent by hash.
+	pub relay_parent: Option<RelayHash>,
+	/// The storage root of the relay parent, if the block identifies its relay parent by storage
+	/// root.
+	pub relay_parent_storage_root: Option<RelayHash>,
+	/// The number of the relay parent.
+	pub relay_parent_number: RelayBlockNumber,
+	/// The relay chain block finalized at the height of the relay parent.
+	pub finalized_relay_block: RelayHash,
+	/// When the block was built, in milliseconds since the UNIX epoch.
+	pub authored_at: u64,
+	/// When the block was found orphaned, in milliseconds since the UNIX epoch.
+	pub detected_at: u64,
+	/// The transactions of the block.
+	pub transactions: Vec<TransactionReport<Hash>>,
+}
+
+/// A transaction of an orphaned block.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionReport<Hash> {
+	/// The hash of the transaction.
+	pub hash: Hash,
+	/// Whether the transaction was ready in the transaction pool.
+	pub requeued: bool,
+	/// The block of the best chain the transaction was included in again, if any.
+	pub included_in: Option<Hash>,
+}
+
+impl<Hash> From<OrphanedBlock<Hash>> for OrphanedBlockReport<Hash> {
+	fn from(block: OrphanedBlock<Hash>) -> Self {
+		let (relay_parent, relay_parent_storage_root) = match block.relay_parent {
+			RelayBlockIdentifier::ByHash(hash) => (Some(hash), None),
+			RelayBlockIdentifier::ByStorageRoot { storage_root, .. } => (None, Some(storage_root)),
+		};
+
+		Self {
+			hash: block.hash,
+			number: block.number,
+			relay_parent,
+			relay_parent_storage_root,
+			relay_parent_number: block.relay_parent_number,
+			finalized_relay_block: block.finalized_relay_block,
+			authored_at: block.authored_at,
+			detected_at: block.detected_at,
+			transactions: block.transactions.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl<Hash> From<OrphanedTransaction<Hash>> for TransactionReport<Hash> {
+	fn from(transaction: OrphanedTransaction<Hash>) -> Self {
+		Self {
+			hash: transaction.hash,
+			requeued: transaction.requeued,
+			included_in: transaction.included_in,
+		}
+	}
+}
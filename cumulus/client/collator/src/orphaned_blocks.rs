@@ -0,0 +1,901 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Tracking of the authored blocks orphaned by relay chain reorganizations.
+//!
+//! A parachain block can only be included in the relay chain forks containing its relay parent.
+//! When the relay chain abandons the fork of the relay parent, the block is dropped and the
+//! collator builds a new block on top of the new fork, picking up the transactions of the dropped
+//! block returned to the transaction pool.
+//!
+//! [`OrphanedBlocks`] records the blocks built by the collator and, once the relay chain finalizes
+//! a block at the height of their relay parent, reports the ones whose relay parent was abandoned.
+//! Their transactions are looked up in the best parachain chain, since by then they may already
+//! have been included again, and in the transaction pool.
+
+use cumulus_primitives_core::{
+	relay_chain::{
+		BlockId, BlockNumber as RelayBlockNumber, Hash as RelayHash, Header as RelayHeader,
+	},
+	CumulusDigestItem, RelayBlockIdentifier,
+};
+use cumulus_relay_chain_interface::{RelayChainInterface, RelayChainResult};
+use futures::{stream, Future, Stream, StreamExt};
+use parking_lot::Mutex;
+use prometheus_endpoint::{register, Counter, PrometheusError, Registry, U64};
+use sc_client_api::BlockBackend;
+use sc_transaction_pool_api::TransactionPool;
+use sp_runtime::{
+	traits::{Block as BlockT, Hash as HashT, HashingFor, Header as HeaderT},
+	SaturatedConversion,
+};
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+/// The logging target.
+const LOG_TARGET: &str = "cumulus-collator";
+
+/// The maximum number of authored blocks waiting for the finality of their relay parent.
+const MAX_PENDING_BLOCKS: usize = 1024;
+
+/// The maximum number of orphaned blocks kept for reporting.
+const MAX_ORPHANED_BLOCKS: usize = 256;
+
+/// The number of relay chain notifications after which a relay parent identified by a hash
+/// unknown to the relay chain is given up on.
+const MAX_RESOLVE_ATTEMPTS: u32 = 32;
+
+/// The number of relay chain finality notifications after which a block is given up on when the
+/// relay chain block finalized at the height of its relay parent can't be fetched.
+const MAX_CHECK_ATTEMPTS: u32 = 8;
+
+/// The maximum number of blocks of the best chain searched for the transactions of an orphaned
+/// block, starting at its height.
+const MAX_SEARCHED_BLOCKS: u64 = 256;
+
+/// A block built by the collator and orphaned by a relay chain reorganization.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrphanedBlock<Hash> {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The number of the block.
+	pub number: u64,
+	/// The relay parent the block was built on, as found in its digest.
+	pub relay_parent: RelayBlockIdentifier,
+	/// The number of the relay parent.
+	pub relay_parent_number: RelayBlockNumber,
+	/// The relay chain block finalized at the height of the relay parent.
+	pub finalized_relay_block: RelayHash,
+	/// When the block was built, in milliseconds since the UNIX epoch.
+	pub authored_at: u64,
+	/// When the block was found orphaned, in milliseconds since the UNIX epoch.
+	pub detected_at: u64,
+	/// The transactions of the block.
+	pub transactions: Vec<OrphanedTransaction<Hash>>,
+}
+
+/// A transaction of an [`OrphanedBlock`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OrphanedTransaction<Hash> {
+	/// The hash of the transaction.
+	pub hash: Hash,
+	/// Whether the transaction was returned to the transaction pool, i.e. whether it was ready in
+	/// the transaction pool when the block was found orphaned.
+	///
+	/// Inherents are never re-queued.
+	pub requeued: bool,
+	/// The block of the best chain the transaction was included in again, if any.
+	///
+	/// Transactions included again are not in the transaction pool anymore, so they are not
+	/// counted as re-queued.
+	pub included_in: Option<Hash>,
+}
+
+/// A block built by the collator, waiting for the finality of its relay parent.
+#[derive(Clone, Debug)]
+struct AuthoredBlock<Hash> {
+	hash: Hash,
+	number: u64,
+	relay_parent: RelayBlockIdentifier,
+	/// Resolved from the relay chain by [`OrphanedBlocks::run`] for blocks identifying their relay
+	/// parent by hash.
+	relay_parent_number: Option<RelayBlockNumber>,
+	/// The number of failed attempts to resolve the relay parent.
+	resolve_attempts: u32,
+	/// The number of failed attempts to fetch the relay chain block finalized at the height of the
+	/// relay parent.
+	check_attempts: u32,
+	transactions: Vec<Hash>,
+	authored_at: u64,
+}
+
+struct Inner<Hash> {
+	pending: VecDeque<AuthoredBlock<Hash>>,
+	orphaned: VecDeque<OrphanedBlock<Hash>>,
+	orphaned_total: u64,
+	requeued_total: u64,
+	reincluded_total: u64,
+}
+
+#[derive(Clone)]
+struct Metrics {
+	orphaned_blocks: Counter<U64>,
+	requeued_transactions: Counter<U64>,
+	reincluded_transactions: Counter<U64>,
+	untracked_blocks: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			orphaned_blocks: register(
+				Counter::new(
+					"cumulus_collator_orphaned_blocks_total",
+					"Number of authored blocks orphaned by relay chain reorganizations",
+				)?,
+				registry,
+			)?,
+			requeued_transactions: register(
+				Counter::new(
+					"cumulus_collator_requeued_transactions_total",
+					"Number of transactions of orphaned blocks returned to the transaction pool",
+				)?,
+				registry,
+			)?,
+			reincluded_transactions: register(
+				Counter::new(
+					"cumulus_collator_reincluded_transactions_total",
+					"Number of transactions of orphaned blocks included again in the best chain",
+				)?,
+				registry,
+			)?,
+			untracked_blocks: register(
+				Counter::new(
+					"cumulus_collator_untracked_authored_blocks_total",
+					"Number of authored blocks given up on because the relay chain block needed to \
+					check them couldn't be fetched",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Tracks the blocks built by the collator to report the ones orphaned by relay chain
+/// reorganizations.
+///
+/// Blocks are recorded by the [`CollatorService`](crate::service::CollatorService) when building
+/// their collation, and checked against the relay chain finality by [`OrphanedBlocks::run`].
+pub struct OrphanedBlocks<Block: BlockT> {
+	inner: Arc<Mutex<Inner<Block::Hash>>>,
+	metrics: Option<Metrics>,
+}
+
+impl<Block: BlockT> Clone for OrphanedBlocks<Block> {
+	fn clone(&self) -> Self {
+		Self { inner: self.inner.clone(), metrics: self.metrics.clone() }
+	}
+}
+
+impl<Block: BlockT> OrphanedBlocks<Block> {
+	/// Create a new instance, registering its metrics in the given `registry`.
+	pub fn new(registry: Option<&Registry>) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			inner: Arc::new(Mutex::new(Inner {
+				pending: VecDeque::new(),
+				orphaned: VecDeque::new(),
+				orphaned_total: 0,
+				requeued_total: 0,
+				reincluded_total: 0,
+			})),
+			metrics: registry.map(Metrics::register).transpose()?,
+		})
+	}
+
+	/// Record a block built by the collator.
+	///
+	/// Blocks without a digest identifying their relay parent can't be tracked and are ignored.
+	pub fn note_authored(&self, block: &Block) {
+		let header = block.header();
+		let Some(relay_parent) = CumulusDigestItem::find_relay_block_identifier(header.digest())
+		else {
+			tracing::debug!(
+				target: LOG_TARGET,
+				block_hash = ?header.hash(),
+				"Not tracking authored block without relay parent digest.",
+			);
+			return
+		};
+
+		let relay_parent_number = match relay_parent {
+			RelayBlockIdentifier::ByHash(_) => None,
+			RelayBlockIdentifier::ByStorageRoot { block_number, .. } => Some(block_number),
+		};
+		let authored = AuthoredBlock {
+			hash: header.hash(),
+			number: (*header.number()).saturated_into(),
+			relay_parent,
+			relay_parent_number,
+			resolve_attempts: 0,
+			check_attempts: 0,
+			transactions: block.extrinsics().iter().map(HashingFor::<Block>::hash_of).collect(),
+			authored_at: now(),
+		};
+
+		let mut inner = self.inner.lock();
+		if inner.pending.len() >= MAX_PENDING_BLOCKS {
+			inner.pending.pop_front();
+		}
+		inner.pending.push_back(authored);
+	}
+
+	/// The most recently orphaned blocks, oldest first.
+	pub fn orphaned_blocks(&self) -> Vec<OrphanedBlock<Block::Hash>> {
+		self.inner.lock().orphaned.iter().cloned().collect()
+	}
+
+	/// The number of blocks orphaned since the node started.
+	pub fn orphaned_total(&self) -> u64 {
+		self.inner.lock().orphaned_total
+	}
+
+	/// The number of transactions of orphaned blocks returned to the transaction pool since the
+	/// node started.
+	pub fn requeued_total(&self) -> u64 {
+		self.inner.lock().requeued_total
+	}
+
+	/// The number of transactions of orphaned blocks included again in the best chain since the
+	/// node started.
+	pub fn reincluded_total(&self) -> u64 {
+		self.inner.lock().reincluded_total
+	}
+
+	/// Check the recorded blocks against the relay chain finality.
+	///
+	/// The transactions of the orphaned blocks are looked up in the best chain of `para_client`
+	/// and in the `pool`. Runs until the relay chain notification streams end.
+	pub async fn run<RCInterface, Client, Pool>(
+		self,
+		relay_client: RCInterface,
+		para_client: Arc<Client>,
+		pool: Arc<Pool>,
+	) where
+		RCInterface: RelayChainInterface,
+		Client: BlockBackend<Block>,
+		Pool: TransactionPool<Block = Block, Hash = Block::Hash>,
+	{
+		let (imports, finality) = match (
+			relay_client.import_notification_stream().await,
+			relay_client.finality_notification_stream().await,
+		) {
+			(Ok(imports), Ok(finality)) => (imports, finality),
+			(Err(error), _) | (_, Err(error)) => {
+				tracing::error!(
+					target: LOG_TARGET,
+					?error,
+					"Failed to subscribe to relay chain notifications, not tracking orphaned blocks.",
+				);
+				return
+			},
+		};
+
+		let notifications = stream::select(
+			imports.map(|header| (header, false)),
+			finality.map(|header| (header, true)),
+		);
+		self.track(notifications, &relay_client, &*para_client, &*pool).await
+	}
+
+	/// Check the recorded blocks on every relay chain notification, given as the header and
+	/// whether it is finalized.
+	async fn track(
+		&self,
+		mut notifications: impl Stream<Item = (RelayHeader, bool)> + Unpin,
+		relay_chain: &impl RelayHeaders,
+		best_chain: &impl BestChain<Block>,
+		pool: &impl ReadyPool<Block::Hash>,
+	) {
+		while let Some((header, finalized)) = notifications.next().await {
+			self.resolve_relay_parents(relay_chain).await;
+
+			if finalized {
+				self.check_finalized(relay_chain, best_chain, pool, header.number).await;
+			}
+		}
+	}
+
+	/// Resolve the numbers of the relay parents identified by hash.
+	///
+	/// Done on every relay chain block import, while the relay parents are still known even if
+	/// their fork ends up abandoned. Blocks whose relay parent can't be resolved after
+	/// [`MAX_RESOLVE_ATTEMPTS`] are not tracked anymore.
+	async fn resolve_relay_parents(&self, relay_chain: &impl RelayHeaders) {
+		let unresolved = self
+			.inner
+			.lock()
+			.pending
+			.iter()
+			.filter(|block| block.relay_parent_number.is_none())
+			.filter_map(|block| match block.relay_parent {
+				RelayBlockIdentifier::ByHash(hash) => Some(hash),
+				RelayBlockIdentifier::ByStorageRoot { .. } => None,
+			})
+			.collect::<Vec<_>>();
+
+		for relay_parent in unresolved {
+			let number = match relay_chain.relay_header(BlockId::Hash(relay_parent)).await {
+				Ok(Some(header)) => Some(header.number),
+				Ok(None) => None,
+				Err(error) => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						?relay_parent,
+						?error,
+						"Failed to fetch the header of the relay parent.",
+					);
+					None
+				},
+			};
+
+			self.inner
+				.lock()
+				.pending
+				.iter_mut()
+				.filter(|block| block.relay_parent == RelayBlockIdentifier::ByHash(relay_parent))
+				.for_each(|block| match number {
+					Some(number) => block.relay_parent_number = Some(number),
+					None => block.resolve_attempts += 1,
+				});
+		}
+
+		let mut untracked = 0;
+		self.inner.lock().pending.retain(|block| {
+			let keep = block.relay_parent_number.is_some() ||
+				block.resolve_attempts < MAX_RESOLVE_ATTEMPTS;
+			if !keep {
+				tracing::debug!(
+					target: LOG_TARGET,
+					block_hash = ?block.hash,
+					relay_parent = ?block.relay_parent,
+					"Not tracking authored block with unknown relay parent anymore.",
+				);
+				untracked += 1;
+			}
+			keep
+		});
+		if let Some(metrics) = &self.metrics {
+			metrics.untracked_blocks.inc_by(untracked);
+		}
+	}
+
+	/// Check the pending blocks whose relay parent is at or below the finalized relay chain block.
+	///
+	/// Blocks for which the relay chain block finalized at the height of their relay parent can't
+	/// be fetched are checked again on the next finality notifications.
+	async fn check_finalized(
+		&self,
+		relay_chain: &impl RelayHeaders,
+		best_chain: &impl BestChain<Block>,
+		pool: &impl ReadyPool<Block::Hash>,
+		finalized_number: RelayBlockNumber,
+	) {
+		for block in self.take_finalized(finalized_number) {
+			let Some(relay_parent_number) = block.relay_parent_number else { continue };
+
+			let canonical = match relay_chain
+				.relay_header(BlockId::Number(relay_parent_number))
+				.await
+			{
+				Ok(Some(header)) => header,
+				result => {
+					tracing::debug!(
+						target: LOG_TARGET,
+						relay_parent_number,
+						error = ?result.err(),
+						"Failed to fetch the finalized relay chain header.",
+					);
+					self.retry_check(block);
+					continue
+				},
+			};
+
+			if is_relay_parent(&canonical, &block.relay_parent) {
+				continue
+			}
+
+			let included = included_transactions(best_chain, &block);
+			self.note_orphaned(block, canonical.hash(), |hash| {
+				(included.get(hash).copied(), pool.is_ready(hash))
+			});
+		}
+	}
+
+	/// Put back a pending `block` to check it on the next finality notification, unless it was
+	/// already checked [`MAX_CHECK_ATTEMPTS`] times.
+	fn retry_check(&self, mut block: AuthoredBlock<Block::Hash>) {
+		block.check_attempts += 1;
+		if block.check_attempts >= MAX_CHECK_ATTEMPTS {
+			tracing::debug!(
+				target: LOG_TARGET,
+				block_hash = ?block.hash,
+				relay_parent = ?block.relay_parent,
+				"Not tracking authored block whose finalized relay chain block can't be fetched \
+				anymore.",
+			);
+			if let Some(metrics) = &self.metrics {
+				metrics.untracked_blocks.inc();
+			}
+			return
+		}
+
+		let mut inner = self.inner.lock();
+		if inner.pending.len() < MAX_PENDING_BLOCKS {
+			inner.pending.push_front(block);
+		}
+	}
+
+	/// Remove and return the pending blocks with a relay parent at or below `finalized_number`.
+	fn take_finalized(
+		&self,
+		finalized_number: RelayBlockNumber,
+	) -> Vec<AuthoredBlock<Block::Hash>> {
+		let mut inner = self.inner.lock();
+		let (finalized, pending): (Vec<_>, Vec<_>) = inner.pending.drain(..).partition(|block| {
+			block.relay_parent_number.is_some_and(|number| number <= finalized_number)
+		});
+		inner.pending = pending.into();
+		finalized
+	}
+
+	/// Report an orphaned `block`, given where each of its transactions was included again, if
+	/// anywhere, and whether it is ready in the transaction pool.
+	fn note_orphaned(
+		&self,
+		block: AuthoredBlock<Block::Hash>,
+		finalized_relay_block: RelayHash,
+		status: impl Fn(&Block::Hash) -> (Option<Block::Hash>, bool),
+	) {
+		let transactions = block
+			.transactions
+			.into_iter()
+			.map(|hash| {
+				let (included_in, ready) = status(&hash);
+				OrphanedTransaction { requeued: ready, included_in, hash }
+			})
+			.collect::<Vec<_>>();
+		let requeued = transactions.iter().filter(|tx| tx.requeued).count() as u64;
+		let reincluded = transactions.iter().filter(|tx| tx.included_in.is_some()).count() as u64;
+
+		tracing::info!(
+			target: LOG_TARGET,
+			block_hash = ?block.hash,
+			relay_parent = ?block.relay_parent,
+			?finalized_relay_block,
+			requeued,
+			reincluded,
+			"Authored block was orphaned by a relay chain reorganization.",
+		);
+
+		if let Some(metrics) = &self.metrics {
+			metrics.orphaned_blocks.inc();
+			metrics.requeued_transactions.inc_by(requeued);
+			metrics.reincluded_transactions.inc_by(reincluded);
+		}
+
+		let mut inner = self.inner.lock();
+		inner.orphaned_total += 1;
+		inner.requeued_total += requeued;
+		inner.reincluded_total += reincluded;
+		if inner.orphaned.len() >= MAX_ORPHANED_BLOCKS {
+			inner.orphaned.pop_front();
+		}
+		inner.orphaned.push_back(OrphanedBlock {
+			hash: block.hash,
+			number: block.number,
+			relay_parent: block.relay_parent,
+			relay_parent_number: block.relay_parent_number.unwrap_or_default(),
+			finalized_relay_block,
+			authored_at: block.authored_at,
+			detected_at: now(),
+			transactions,
+		});
+	}
+}
+
+/// The relay chain headers needed to check the authored blocks.
+trait RelayHeaders {
+	/// Returns the header of the relay chain block `block_id`.
+	fn relay_header(
+		&self,
+		block_id: BlockId,
+	) -> impl Future<Output = RelayChainResult<Option<RelayHeader>>> + Send;
+}
+
+impl<T: RelayChainInterface> RelayHeaders for T {
+	fn relay_header(
+		&self,
+		block_id: BlockId,
+	) -> impl Future<Output = RelayChainResult<Option<RelayHeader>>> + Send {
+		self.header(block_id)
+	}
+}
+
+/// The best parachain chain, searched for the transactions of the orphaned blocks.
+trait BestChain<Block: BlockT> {
+	/// Returns the hash of the best chain block at height `number`.
+	fn best_block_hash(&self, number: u64) -> Option<Block::Hash>;
+
+	/// Returns the extrinsics of the block `hash`.
+	fn extrinsics(&self, hash: Block::Hash) -> Option<Vec<Block::Extrinsic>>;
+}
+
+impl<Block: BlockT, T: BlockBackend<Block>> BestChain<Block> for T {
+	fn best_block_hash(&self, number: u64) -> Option<Block::Hash> {
+		self.block_hash(number.saturated_into()).ok().flatten()
+	}
+
+	fn extrinsics(&self, hash: Block::Hash) -> Option<Vec<Block::Extrinsic>> {
+		self.block_body(hash).ok().flatten()
+	}
+}
+
+/// The transaction pool the transactions of the orphaned blocks are returned to.
+trait ReadyPool<Hash> {
+	/// Returns whether the transaction `hash` is ready in the pool.
+	fn is_ready(&self, hash: &Hash) -> bool;
+}
+
+impl<T: TransactionPool> ReadyPool<T::Hash> for T {
+	fn is_ready(&self, hash: &T::Hash) -> bool {
+		self.ready_transaction(hash).is_some()
+	}
+}
+
+/// Returns the transactions of the orphaned `block` included in other blocks of the best chain,
+/// with the block including them.
+fn included_transactions<Block: BlockT>(
+	best_chain: &impl BestChain<Block>,
+	block: &AuthoredBlock<Block::Hash>,
+) -> HashMap<Block::Hash, Block::Hash> {
+	let mut included = HashMap::new();
+	for number in block.number..block.number.saturating_add(MAX_SEARCHED_BLOCKS) {
+		let Some(hash) = best_chain.best_block_hash(number) else { break };
+		// The orphaned block may still be in the best chain.
+		if hash == block.hash {
+			continue
+		}
+		let Some(extrinsics) = best_chain.extrinsics(hash) else { continue };
+		for transaction in extrinsics.iter().map(HashingFor::<Block>::hash_of) {
+			if block.transactions.contains(&transaction) {
+				included.entry(transaction).or_insert(hash);
+			}
+		}
+	}
+	included
+}
+
+/// Returns whether `header` is the relay block identified by `relay_parent`.
+fn is_relay_parent(header: &RelayHeader, relay_parent: &RelayBlockIdentifier) -> bool {
+	match relay_parent {
+		RelayBlockIdentifier::ByHash(hash) => header.hash() == *hash,
+		RelayBlockIdentifier::ByStorageRoot { storage_root, block_number } =>
+			header.state_root == *storage_root && header.number == *block_number,
+	}
+}
+
+/// Returns the current time in milliseconds since the UNIX epoch.
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cumulus_primitives_core::rpsr_digest;
+	use cumulus_test_runtime::{Block, Hash, Header, SystemCall, UncheckedExtrinsic as Extrinsic};
+	use futures::executor::block_on;
+	use sp_runtime::Digest;
+
+	/// A relay chain knowing the given headers.
+	struct TestRelayChain {
+		/// The headers known by hash, including the ones of abandoned forks.
+		known: HashMap<RelayHash, RelayHeader>,
+		/// The headers of the finalized chain, by number.
+		finalized: HashMap<RelayBlockNumber, RelayHeader>,
+	}
+
+	impl RelayHeaders for TestRelayChain {
+		async fn relay_header(&self, block_id: BlockId) -> RelayChainResult<Option<RelayHeader>> {
+			Ok(match block_id {
+				BlockId::Hash(hash) => self.known.get(&hash).cloned(),
+				BlockId::Number(number) => self.finalized.get(&number).cloned(),
+			})
+		}
+	}
+
+	/// The best chain of the parachain.
+	struct TestParaClient(Vec<Block>);
+
+	impl BestChain<Block> for TestParaClient {
+		fn best_block_hash(&self, number: u64) -> Option<Hash> {
+			self.0.iter().find(|b| b.header.number as u64 == number).map(|b| b.header.hash())
+		}
+
+		fn extrinsics(&self, hash: Hash) -> Option<Vec<Extrinsic>> {
+			self.0.iter().find(|b| b.header.hash() == hash).map(|b| b.extrinsics.clone())
+		}
+	}
+
+	/// A transaction pool with the given ready transactions.
+	struct TestPool(Vec<Extrinsic>);
+
+	impl ReadyPool<Hash> for TestPool {
+		fn is_ready(&self, hash: &Hash) -> bool {
+			self.0.iter().any(|xt| HashingFor::<Block>::hash_of(xt) == *hash)
+		}
+	}
+
+	fn transaction(remark: u8) -> Extrinsic {
+		Extrinsic::new_bare(SystemCall::remark { remark: vec![remark] }.into())
+	}
+
+	fn relay_header(number: RelayBlockNumber, state_root: u8) -> RelayHeader {
+		RelayHeader {
+			parent_hash: Default::default(),
+			number,
+			state_root: RelayHash::repeat_byte(state_root),
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		}
+	}
+
+	fn block(number: u32, relay_parent: Option<RelayBlockIdentifier>) -> Block {
+		let mut digest = Digest::default();
+		match relay_parent {
+			Some(RelayBlockIdentifier::ByHash(hash)) =>
+				digest.push(CumulusDigestItem::RelayParent(hash).to_digest_item()),
+			Some(RelayBlockIdentifier::ByStorageRoot { storage_root, block_number }) =>
+				digest.push(rpsr_digest::relay_parent_storage_root_item(storage_root, block_number)),
+			None => {},
+		}
+		let header =
+			Header::new(number, Default::default(), Default::default(), Default::default(), digest);
+		Block { header, extrinsics: Vec::new() }
+	}
+
+	#[test]
+	fn blocks_are_checked_once_their_relay_parent_is_finalized() {
+		let tracker = OrphanedBlocks::<Block>::new(None).unwrap();
+		tracker.note_authored(&block(
+			1,
+			Some(RelayBlockIdentifier::ByHash(RelayHash::repeat_byte(1))),
+		));
+		tracker.note_authored(&block(
+			2,
+			Some(RelayBlockIdentifier::ByStorageRoot {
+				storage_root: RelayHash::repeat_byte(2),
+				block_number: 20,
+			}),
+		));
+		tracker.note_authored(&block(3, None));
+
+		// Relay parents identified by hash are only checked once their number is resolved.
+		assert!(tracker.take_finalized(15).is_empty());
+		tracker.inner.lock().pending[0].relay_parent_number = Some(10);
+
+		assert!(tracker.take_finalized(9).is_empty());
+		assert_eq!(
+			tracker.take_finalized(15).into_iter().map(|b| b.number).collect::<Vec<_>>(),
+			vec![1]
+		);
+		assert_eq!(
+			tracker.take_finalized(20).into_iter().map(|b| b.number).collect::<Vec<_>>(),
+			vec![2]
+		);
+		assert!(tracker.inner.lock().pending.is_empty());
+	}
+
+	#[test]
+	fn relay_parents_are_matched_by_hash_or_storage_root() {
+		let header = RelayHeader {
+			parent_hash: Default::default(),
+			number: 10,
+			state_root: RelayHash::repeat_byte(1),
+			extrinsics_root: Default::default(),
+			digest: Default::default(),
+		};
+
+		assert!(is_relay_parent(&header, &RelayBlockIdentifier::ByHash(header.hash())));
+		assert!(!is_relay_parent(
+			&header,
+			&RelayBlockIdentifier::ByHash(RelayHash::repeat_byte(1))
+		));
+		assert!(is_relay_parent(
+			&header,
+			&RelayBlockIdentifier::ByStorageRoot {
+				storage_root: RelayHash::repeat_byte(1),
+				block_number: 10
+			},
+		));
+		assert!(!is_relay_parent(
+			&header,
+			&RelayBlockIdentifier::ByStorageRoot {
+				storage_root: RelayHash::repeat_byte(2),
+				block_number: 10
+			},
+		));
+	}
+
+	#[test]
+	fn orphaned_blocks_are_reported() {
+		let tracker = OrphanedBlocks::<Block>::new(Some(&Registry::new())).unwrap();
+		let orphaned = AuthoredBlock {
+			hash: Default::default(),
+			number: 5,
+			relay_parent: RelayBlockIdentifier::ByHash(RelayHash::repeat_byte(1)),
+			relay_parent_number: Some(10),
+			resolve_attempts: 0,
+			check_attempts: 0,
+			transactions: vec![[1; 32].into(), [2; 32].into(), [3; 32].into()],
+			authored_at: 1_000,
+		};
+		let included_in = Hash::repeat_byte(9);
+
+		// The first transaction was included again, the second one is ready in the pool and the
+		// third one was dropped.
+		tracker.note_orphaned(orphaned, RelayHash::repeat_byte(2), |hash| {
+			match hash.as_bytes()[0] {
+				1 => (Some(included_in), false),
+				2 => (None, true),
+				_ => (None, false),
+			}
+		});
+
+		assert_eq!(tracker.orphaned_total(), 1);
+		assert_eq!(tracker.requeued_total(), 1);
+		assert_eq!(tracker.reincluded_total(), 1);
+		let reported = tracker.orphaned_blocks();
+		assert_eq!(reported.len(), 1);
+		assert_eq!(reported[0].finalized_relay_block, RelayHash::repeat_byte(2));
+		assert_eq!(
+			reported[0]
+				.transactions
+				.iter()
+				.map(|tx| (tx.requeued, tx.included_in))
+				.collect::<Vec<_>>(),
+			vec![(false, Some(included_in)), (true, None), (false, None)],
+		);
+		let metrics = tracker.metrics.as_ref().unwrap();
+		assert_eq!(metrics.requeued_transactions.get(), 1);
+		assert_eq!(metrics.reincluded_transactions.get(), 1);
+	}
+
+	#[test]
+	fn track_reports_the_blocks_orphaned_at_finality() {
+		// The block at height 10 was replaced by `finalized_10` and its fork abandoned.
+		let abandoned_10 = relay_header(10, 1);
+		let finalized_10 = relay_header(10, 2);
+		let finalized_11 = relay_header(11, 3);
+
+		let tracker = OrphanedBlocks::<Block>::new(None).unwrap();
+		let mut orphaned = block(1, Some(RelayBlockIdentifier::ByHash(abandoned_10.hash())));
+		orphaned.extrinsics = vec![transaction(1), transaction(2), transaction(3)];
+		tracker.note_authored(&orphaned);
+		tracker.note_authored(&block(
+			2,
+			Some(RelayBlockIdentifier::ByStorageRoot {
+				storage_root: finalized_11.state_root,
+				block_number: 11,
+			}),
+		));
+		// Relay parents unknown to the relay chain are eventually given up on.
+		tracker
+			.note_authored(&block(3, Some(RelayBlockIdentifier::ByHash(RelayHash::repeat_byte(9)))));
+
+		let notifications = std::iter::repeat((abandoned_10.clone(), false))
+			.take(MAX_RESOLVE_ATTEMPTS as usize)
+			.chain([(finalized_11.clone(), true)]);
+		let relay_chain = TestRelayChain {
+			known: [(abandoned_10.hash(), abandoned_10.clone())].into(),
+			finalized: [(10, finalized_10.clone()), (11, finalized_11)].into(),
+		};
+		// The first transaction was included again, the second one is still in the pool and the
+		// third one was dropped.
+		let mut included_again = block(1, None);
+		included_again.extrinsics = vec![transaction(1)];
+		let para_client = TestParaClient(vec![included_again.clone()]);
+		let pool = TestPool(vec![transaction(2)]);
+
+		block_on(tracker.track(stream::iter(notifications), &relay_chain, &para_client, &pool));
+
+		let reported = tracker.orphaned_blocks();
+		assert_eq!(reported.len(), 1);
+		assert_eq!(reported[0].hash, orphaned.header.hash());
+		assert_eq!(reported[0].relay_parent_number, 10);
+		assert_eq!(reported[0].finalized_relay_block, finalized_10.hash());
+		assert_eq!(
+			reported[0]
+				.transactions
+				.iter()
+				.map(|tx| (tx.requeued, tx.included_in))
+				.collect::<Vec<_>>(),
+			vec![(false, Some(included_again.header.hash())), (true, None), (false, None)],
+		);
+		assert_eq!(tracker.requeued_total(), 1);
+		assert_eq!(tracker.reincluded_total(), 1);
+		assert!(tracker.inner.lock().pending.is_empty());
+	}
+
+	#[test]
+	fn blocks_are_checked_again_when_the_finalized_relay_block_is_missing() {
+		let abandoned_10 = relay_header(10, 1);
+		let finalized_10 = relay_header(10, 2);
+		let finalized_11 = relay_header(11, 3);
+
+		let tracker = OrphanedBlocks::<Block>::new(Some(&Registry::new())).unwrap();
+		tracker.note_authored(&block(
+			1,
+			Some(RelayBlockIdentifier::ByStorageRoot {
+				storage_root: abandoned_10.state_root,
+				block_number: 10,
+			}),
+		));
+		let para_client = TestParaClient(Vec::new());
+		let pool = TestPool(Vec::new());
+
+		// The finalized block at height 10 isn't known yet, so the block is checked again later.
+		let mut relay_chain = TestRelayChain { known: HashMap::new(), finalized: HashMap::new() };
+		block_on(tracker.track(
+			stream::iter([(finalized_10.clone(), true)]),
+			&relay_chain,
+			&para_client,
+			&pool,
+		));
+		assert!(tracker.orphaned_blocks().is_empty());
+		assert_eq!(tracker.inner.lock().pending.len(), 1);
+
+		relay_chain.finalized.insert(10, finalized_10.clone());
+		block_on(tracker.track(
+			stream::iter([(finalized_11.clone(), true)]),
+			&relay_chain,
+			&para_client,
+			&pool,
+		));
+		assert_eq!(tracker.orphaned_blocks().len(), 1);
+		assert_eq!(tracker.orphaned_blocks()[0].finalized_relay_block, finalized_10.hash());
+
+		// Blocks are given up on after `MAX_CHECK_ATTEMPTS`.
+		tracker.note_authored(&block(
+			2,
+			Some(RelayBlockIdentifier::ByStorageRoot {
+				storage_root: abandoned_10.state_root,
+				block_number: 12,
+			}),
+		));
+		let finalized_12 = relay_header(12, 4);
+		let notifications =
+			std::iter::repeat((finalized_12, true)).take(MAX_CHECK_ATTEMPTS as usize);
+		block_on(tracker.track(stream::iter(notifications), &relay_chain, &para_client, &pool));
+		assert!(tracker.inner.lock().pending.is_empty());
+		assert_eq!(tracker.orphaned_total(), 1);
+		assert_eq!(tracker.metrics.as_ref().unwrap().untracked_blocks.get(), 1);
+	}
+}
@@ -18,6 +18,7 @@
 //! The Cumulus [`CollatorService`] is a utility struct for performing common
 //! operations used in parachain consensus/authoring.
 
+use crate::orphaned_blocks::OrphanedBlocks;
 use cumulus_client_network::WaitToAnnounce;
 use cumulus_primitives_core::{CollationInfo, CollectCollationInfo, ParachainBlockData};
 
@@ -83,6 +84,7 @@ pub struct CollatorService<Block: BlockT, BS, RA> {
 	wait_to_announce: Arc<Mutex<WaitToAnnounce<Block>>>,
 	announce_block: Arc<dyn Fn(Block::Hash, Option<Vec<u8>>) + Send + Sync>,
 	runtime_api: Arc<RA>,
+	orphaned_blocks: Option<OrphanedBlocks<Block>>,
 }
 
 impl<Block: BlockT, BS, RA> Clone for CollatorService<Block, BS, RA> {
@@ -92,6 +94,7 @@ impl<Block: BlockT, BS, RA> Clone for CollatorService<Block, BS, RA> {
 			wait_to_announce: self.wait_to_announce.clone(),
 			announce_block: self.announce_block.clone(),
 			runtime_api: self.runtime_api.clone(),
+			orphaned_blocks: self.orphaned_blocks.clone(),
 		}
 	}
 }
@@ -113,7 +116,14 @@ where
 		let wait_to_announce =
 			Arc::new(Mutex::new(WaitToAnnounce::new(spawner, announce_block.clone())));
 
-		Self { block_status, wait_to_announce, announce_block, runtime_api }
+		Self { block_status, wait_to_announce, announce_block, runtime_api, orphaned_blocks: None }
+	}
+
+	/// Record the blocks of the built collations in `orphaned_blocks`, to report the ones orphaned
+	/// by relay chain reorganizations.
+	pub fn with_orphaned_blocks(mut self, orphaned_blocks: OrphanedBlocks<Block>) -> Self {
+		self.orphaned_blocks = Some(orphaned_blocks);
+		self
 	}
 
 	/// Checks the status of the given block hash in the Parachain.
@@ -261,6 +271,10 @@ where
 			.ok()
 			.flatten()?;
 
+		if let Some(orphaned_blocks) = &self.orphaned_blocks {
+			orphaned_blocks.note_authored(&block);
+		}
+
 		let block_data = ParachainBlockData::<Block>::new(vec![block], compact_proof);
 
 		let pov = polkadot_node_primitives::maybe_compress_pov(PoV {
@@ -58,6 +58,7 @@ use sp_keystore::KeystorePtr;
 use sp_runtime::{
 	generic::Digest,
 	traits::{Block as BlockT, HashingFor, Header as HeaderT, Member},
+	transaction_validity::TransactionPriority,
 };
 use sp_state_machine::StorageChanges;
 use sp_timestamp::Timestamp;
@@ -239,8 +240,21 @@ where
 
 	/// Build and import a parachain block using the given parameters.
 	pub async fn build_block_and_import(
+		&mut self,
+		params: BuildBlockAndImportParams<'_, Block, P>,
+	) -> Result<Option<BuiltBlock<Block>>, Box<dyn Error + Send + 'static>> {
+		self.build_block_and_import_with_min_priority(params, None).await
+	}
+
+	/// Build and import a parachain block using the given parameters, like
+	/// [`Self::build_block_and_import`].
+	///
+	/// Transactions with a priority lower than `min_transaction_priority` are not included in the
+	/// block and stay in the transaction pool.
+	pub async fn build_block_and_import_with_min_priority(
 		&mut self,
 		mut params: BuildBlockAndImportParams<'_, Block, P>,
+		min_transaction_priority: Option<TransactionPriority>,
 	) -> Result<Option<BuiltBlock<Block>>, Box<dyn Error + Send + 'static>> {
 		let mut digest = params.additional_pre_digest;
 		digest.push(params.slot_claim.pre_digest.clone());
@@ -284,6 +298,7 @@ where
 			block_size_limit: Some(params.max_pov_size),
 			extra_extensions: params.extra_extensions,
 			storage_proof_recorder: Some(storage_proof_recorder.clone()),
+			min_transaction_priority,
 		};
 
 		// Propose the block
@@ -359,6 +374,11 @@ where
 			self.collator_service.build_collation(parent_header, hash, candidate.into())
 		{
 			block_data.log_size_info();
+			cumulus_client_pov_analyzer::log_storage_composition(
+				&block_data,
+				*parent_header.state_root(),
+				None,
+			);
 
 			if let MaybeCompressedPoV::Compressed(ref pov) = collation.proof_of_validity {
 				tracing::info!(
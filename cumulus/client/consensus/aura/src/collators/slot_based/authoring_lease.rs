@@ -0,0 +1,295 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Coordination of nodes sharing the same authoring keys.
+//!
+//! Running a standby node with the keys of the collator lets it take over block production when the
+//! collator stops, but both nodes authoring would make them equivocate. An [`AuthoringLease`]
+//! ensures only one of them authors: the node holding the lease renews it whenever it claims a
+//! slot to author in, while standby nodes acquire it once it expires.
+//!
+//! The lease also records the last slot claimed by its holder, so that a node taking over never
+//! authors in a slot the previous holder may already have authored in.
+
+use crate::LOG_TARGET;
+use codec::{Decode, Encode};
+use cumulus_primitives_aura::Slot;
+use fs4::FileExt;
+use std::{
+	fs::{File, OpenOptions},
+	io::{Read, Seek, SeekFrom, Write},
+	path::PathBuf,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// A lease deciding which of the nodes sharing the same authoring keys authors blocks.
+///
+/// The methods of the lease may block, e.g. on file system accesses, so the block builder calls
+/// them on a blocking thread.
+pub trait AuthoringLease: Send + Sync {
+	/// Returns whether this node holds the lease or may acquire it.
+	///
+	/// Called at every block production attempt, without renewing the lease.
+	fn is_available(&self) -> bool;
+
+	/// Acquires or renews the lease and records that this node is about to author in `slot`,
+	/// returning whether it may.
+	///
+	/// Only called when this node is the author of `slot`, so the lease expires if it stops
+	/// authoring. The lease can only be acquired once expired, and slots can only be claimed
+	/// after the last one claimed by another holder.
+	fn claim_slot(&self, slot: Slot) -> bool;
+}
+
+/// The state of a [`FileLease`], as stored in its file.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Encode, Decode)]
+struct LeaseState {
+	/// The node holding the lease.
+	holder: Vec<u8>,
+	/// The time at which the lease expires, in milliseconds since the UNIX epoch.
+	expires_at: u64,
+	/// The last slot claimed by a holder of the lease.
+	last_slot: u64,
+	/// The node that claimed `last_slot`.
+	last_slot_holder: Vec<u8>,
+}
+
+/// An [`AuthoringLease`] stored in a file shared by the nodes, e.g. on a shared volume.
+///
+/// Accesses to the file are serialized by an exclusive lock on it. As the expiration of the lease
+/// relies on the clocks of the nodes, the lease duration must be much longer than the clock drift
+/// between them, and longer than the time between two slots authored by the holder.
+///
+/// The lock is taken with `flock`, which is not reliable on network file systems: depending on
+/// the NFS version, client and server, it is emulated with byte-range locks, only local to the
+/// client or silently ignored. The state is read back after being written, so that a node whose
+/// write was overwritten by another node doesn't author, but two nodes may still both author
+/// when their writes interleave. The file should be on a file system honoring `flock` across the
+/// nodes sharing it.
+pub struct FileLease {
+	path: PathBuf,
+	holder: Vec<u8>,
+	duration: Duration,
+}
+
+impl FileLease {
+	/// Creates a lease stored at `path`, held under the name `holder`.
+	///
+	/// `holder` must be unique among the nodes sharing the lease, e.g. their peer id.
+	pub fn new(path: PathBuf, holder: impl Into<Vec<u8>>, duration: Duration) -> Self {
+		Self { path, holder: holder.into(), duration }
+	}
+
+	fn is_available_at(&self, now: u64) -> std::io::Result<bool> {
+		let state = self.read()?;
+		Ok(state.holder == self.holder || state.expires_at <= now)
+	}
+
+	fn claim_slot_at(&self, slot: Slot, now: u64) -> std::io::Result<bool> {
+		self.update(|state| {
+			let claimable = *slot > state.last_slot ||
+				(*slot == state.last_slot && state.last_slot_holder == self.holder);
+			if (state.holder != self.holder && state.expires_at > now) || !claimable {
+				return false
+			}
+
+			if state.holder != self.holder {
+				tracing::info!(
+					target: LOG_TARGET,
+					path = %self.path.display(),
+					previous_holder = %String::from_utf8_lossy(&state.holder),
+					last_slot = state.last_slot,
+					"Acquired the authoring lease.",
+				);
+				state.holder = self.holder.clone();
+			}
+			state.expires_at = now.saturating_add(self.duration.as_millis() as u64);
+			state.last_slot = *slot;
+			state.last_slot_holder = self.holder.clone();
+			true
+		})
+	}
+
+	/// Reads the state of the lease.
+	fn read(&self) -> std::io::Result<LeaseState> {
+		let mut file = match File::open(&self.path) {
+			Ok(file) => file,
+			Err(error) if error.kind() == std::io::ErrorKind::NotFound =>
+				return Ok(LeaseState::default()),
+			Err(error) => return Err(error),
+		};
+		file.lock_shared()?;
+		let result = Self::read_locked(&mut file);
+		let _ = FileExt::unlock(&file);
+		result
+	}
+
+	/// Applies `f` to the state of the lease, writing it back if `f` returns `true`.
+	fn update(&self, f: impl FnOnce(&mut LeaseState) -> bool) -> std::io::Result<bool> {
+		let mut file = OpenOptions::new()
+			.read(true)
+			.write(true)
+			.create(true)
+			.truncate(false)
+			.open(&self.path)?;
+		file.lock_exclusive()?;
+		let result = Self::update_locked(&mut file, f);
+		let _ = FileExt::unlock(&file);
+		result
+	}
+
+	fn read_locked(file: &mut File) -> std::io::Result<LeaseState> {
+		let mut encoded = Vec::new();
+		file.seek(SeekFrom::Start(0))?;
+		file.read_to_end(&mut encoded)?;
+		// A missing or corrupted lease is free.
+		Ok(LeaseState::decode(&mut &encoded[..]).unwrap_or_default())
+	}
+
+	fn update_locked(
+		file: &mut File,
+		f: impl FnOnce(&mut LeaseState) -> bool,
+	) -> std::io::Result<bool> {
+		let mut state = Self::read_locked(file)?;
+		if !f(&mut state) {
+			return Ok(false)
+		}
+
+		file.seek(SeekFrom::Start(0))?;
+		file.set_len(0)?;
+		file.write_all(&state.encode())?;
+		file.sync_data()?;
+
+		// Without a working lock, another node may have written the file in the meantime.
+		let written = Self::read_locked(file)?;
+		if written != state {
+			tracing::warn!(
+				target: LOG_TARGET,
+				holder = %String::from_utf8_lossy(&written.holder),
+				"The authoring lease was written by another node concurrently, is the file \
+				 system honoring `flock`?",
+			);
+			return Ok(false)
+		}
+		Ok(true)
+	}
+}
+
+impl AuthoringLease for FileLease {
+	fn is_available(&self) -> bool {
+		self.is_available_at(now()).unwrap_or_else(|error| {
+			tracing::warn!(
+				target: LOG_TARGET,
+				%error,
+				path = %self.path.display(),
+				"Failed to read the authoring lease.",
+			);
+			false
+		})
+	}
+
+	fn claim_slot(&self, slot: Slot) -> bool {
+		self.claim_slot_at(slot, now()).unwrap_or_else(|error| {
+			tracing::warn!(
+				target: LOG_TARGET,
+				%error,
+				path = %self.path.display(),
+				"Failed to claim the slot in the authoring lease.",
+			);
+			false
+		})
+	}
+}
+
+/// Returns the current time in milliseconds since the UNIX epoch.
+fn now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const DURATION: Duration = Duration::from_secs(12);
+
+	fn leases(dir: &tempfile::TempDir) -> (FileLease, FileLease) {
+		let path = dir.path().join("lease");
+		(
+			FileLease::new(path.clone(), "primary", DURATION),
+			FileLease::new(path, "standby", DURATION),
+		)
+	}
+
+	#[test]
+	fn only_one_node_holds_the_lease() {
+		let dir = tempfile::tempdir().unwrap();
+		let (primary, standby) = leases(&dir);
+
+		// A missing lease is available.
+		assert!(primary.is_available_at(1_000).unwrap());
+		assert!(primary.claim_slot_at(1.into(), 1_000).unwrap());
+		assert!(!standby.is_available_at(2_000).unwrap());
+		assert!(!standby.claim_slot_at(2.into(), 2_000).unwrap());
+		assert!(primary.claim_slot_at(2.into(), 10_000).unwrap());
+
+		// The lease was renewed until 22s.
+		assert!(!standby.is_available_at(21_999).unwrap());
+		assert!(standby.is_available_at(22_000).unwrap());
+		assert!(standby.claim_slot_at(3.into(), 22_000).unwrap());
+		assert!(!primary.is_available_at(23_000).unwrap());
+		assert!(!primary.claim_slot_at(4.into(), 23_000).unwrap());
+	}
+
+	#[test]
+	fn lease_is_only_renewed_by_claiming_slots() {
+		let dir = tempfile::tempdir().unwrap();
+		let (primary, standby) = leases(&dir);
+
+		assert!(primary.claim_slot_at(1.into(), 1_000).unwrap());
+		// Checking the lease doesn't renew it.
+		assert!(primary.is_available_at(12_000).unwrap());
+		assert!(standby.is_available_at(13_000).unwrap());
+		assert!(standby.claim_slot_at(2.into(), 13_000).unwrap());
+	}
+
+	#[test]
+	fn standby_does_not_author_in_slots_claimed_by_the_primary() {
+		let dir = tempfile::tempdir().unwrap();
+		let (primary, standby) = leases(&dir);
+
+		assert!(primary.claim_slot_at(10.into(), 1_000).unwrap());
+		// Several blocks may be authored in the same slot.
+		assert!(primary.claim_slot_at(10.into(), 2_000).unwrap());
+		assert!(!standby.claim_slot_at(11.into(), 2_000).unwrap());
+
+		// The primary stops authoring.
+		assert!(!standby.claim_slot_at(10.into(), 14_000).unwrap());
+		assert!(standby.claim_slot_at(11.into(), 14_000).unwrap());
+		assert!(standby.claim_slot_at(11.into(), 15_000).unwrap());
+		assert!(!primary.claim_slot_at(12.into(), 15_000).unwrap());
+	}
+
+	#[test]
+	fn corrupted_lease_is_free() {
+		let dir = tempfile::tempdir().unwrap();
+		let (primary, _) = leases(&dir);
+		std::fs::write(dir.path().join("lease"), b"garbage").unwrap();
+
+		assert!(primary.is_available_at(1_000).unwrap());
+		assert!(primary.claim_slot_at(1.into(), 1_000).unwrap());
+	}
+}
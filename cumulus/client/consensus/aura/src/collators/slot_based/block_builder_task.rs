@@ -24,7 +24,8 @@ use crate::{
 		check_validation_code_or_log,
 		slot_based::{
 			relay_chain_data_cache::{RelayChainData, RelayChainDataCache},
-			slot_timer::{SlotInfo, SlotTimer},
+			slot_timer::{SlotInfo, SlotTimer, TimeSource},
+			AuthoringLease, CoreAvailability, CoreScarcity,
 		},
 		BackingGroupConnectionHelper, RelayParentData,
 	},
@@ -110,6 +111,12 @@ pub struct BuilderTaskParams<
 	/// The maximum percentage of the maximum PoV size that the collator can use.
 	/// It will be removed once https://github.com/paritytech/polkadot-sdk/issues/6020 is fixed.
 	pub max_pov_percentage: Option<u32>,
+	/// When set, blocks are only built while holding this lease.
+	pub authoring_lease: Option<Arc<dyn AuthoringLease>>,
+	/// When set, the block building is adapted to the availability of relay chain cores.
+	pub core_scarcity: Option<CoreScarcity>,
+	/// The source of the current time, the system time when `None`.
+	pub time_source: Option<TimeSource>,
 }
 
 /// Run block-builder.
@@ -160,13 +167,17 @@ where
 			para_backend,
 			slot_offset,
 			max_pov_percentage,
+			authoring_lease,
+			core_scarcity,
+			time_source,
 		} = params;
 
 		let mut slot_timer = SlotTimer::<_, _, P>::new_with_offset(
 			para_client.clone(),
 			slot_offset,
 			relay_chain_slot_duration,
-		);
+		)
+		.with_time_source(time_source);
 
 		let mut collator = {
 			let params = collator_util::Params {
@@ -200,6 +211,20 @@ where
 				return;
 			};
 
+			// The lease is only renewed when claiming a slot below, so that standby nodes take
+			// over once this node stops authoring.
+			let lease_available = match &authoring_lease {
+				Some(lease) => access_lease(lease, |lease| lease.is_available()).await,
+				None => true,
+			};
+			if !lease_available {
+				tracing::trace!(
+					target: crate::LOG_TARGET,
+					"Not building block, the authoring lease is held by another node."
+				);
+				continue
+			}
+
 			let Ok(relay_best_hash) = relay_client.best_block_hash().await else {
 				tracing::warn!(target: crate::LOG_TARGET, "Unable to fetch latest relay chain block hash.");
 				continue
@@ -286,8 +311,9 @@ where
 				},
 			};
 
-			let Ok(RelayChainData { max_pov_size, last_claimed_core_selector, .. }) =
-				relay_chain_data_cache.get_mut_relay_chain_data(relay_parent).await
+			let Ok(RelayChainData {
+				max_pov_size, last_claimed_core_selector, claim_queue, ..
+			}) = relay_chain_data_cache.get_mut_relay_chain_data(relay_parent).await
 			else {
 				continue;
 			};
@@ -340,6 +366,37 @@ where
 				},
 			};
 
+			let slot = para_slot.slot;
+			let slot_claimed = match &authoring_lease {
+				Some(lease) => access_lease(lease, move |lease| lease.claim_slot(slot)).await,
+				None => true,
+			};
+			if !slot_claimed {
+				tracing::debug!(
+					target: crate::LOG_TARGET,
+					slot = ?para_slot.slot,
+					"Not building block, the slot can't be claimed in the authoring lease."
+				);
+				continue
+			}
+
+			// Only checked once the slot is claimed, so that the metrics of the core scarcity only
+			// count the blocks built by this collator.
+			let scarcity_config = core_scarcity.as_ref().and_then(|core_scarcity| {
+				let availability =
+					CoreAvailability::from_claim_queue(claim_queue, para_id, relay_parent_offset);
+				let config = core_scarcity.check(&availability);
+				if config.is_some() {
+					tracing::debug!(
+						target: LOG_TARGET,
+						?relay_parent,
+						?availability,
+						"Relay chain cores are scarce, adapting block building."
+					);
+				}
+				config
+			});
+
 			tracing::debug!(
 				target: crate::LOG_TARGET,
 				unincluded_segment_len = parent.depth,
@@ -394,6 +451,9 @@ where
 			)
 			.await;
 
+			let max_pov_percentage = scarcity_config
+				.and_then(|config| config.max_pov_percentage)
+				.or(max_pov_percentage);
 			let allowed_pov_size = if let Some(max_pov_percentage) = max_pov_percentage {
 				validation_data.max_pov_size * max_pov_percentage / 100
 			} else {
@@ -425,19 +485,22 @@ where
 			};
 
 			let Ok(Some(candidate)) = collator
-				.build_block_and_import(BuildBlockAndImportParams {
-					parent_header: &parent_header,
-					slot_claim: &slot_claim,
-					additional_pre_digest: vec![
-						CumulusDigestItem::CoreInfo(core.core_info()).to_digest_item()
-					],
-					parachain_inherent_data,
-					extra_inherent_data: other_inherent_data,
-					proposal_duration: adjusted_authoring_duration,
-					max_pov_size: allowed_pov_size,
-					storage_proof_recorder: None,
-					extra_extensions: Default::default(),
-				})
+				.build_block_and_import_with_min_priority(
+					BuildBlockAndImportParams {
+						parent_header: &parent_header,
+						slot_claim: &slot_claim,
+						additional_pre_digest: vec![
+							CumulusDigestItem::CoreInfo(core.core_info()).to_digest_item()
+						],
+						parachain_inherent_data,
+						extra_inherent_data: other_inherent_data,
+						proposal_duration: adjusted_authoring_duration,
+						max_pov_size: allowed_pov_size,
+						storage_proof_recorder: None,
+						extra_extensions: Default::default(),
+					},
+					scarcity_config.and_then(|config| config.min_transaction_priority),
+				)
 				.await
 			else {
 				tracing::error!(target: crate::LOG_TARGET, "Unable to build block at slot.");
@@ -466,6 +529,20 @@ where
 	}
 }
 
+/// Calls `f` with the authoring lease on a blocking thread, as accessing the lease may block.
+///
+/// Returns `false` if the blocking task fails, so that no block is built.
+async fn access_lease(
+	lease: &Arc<dyn AuthoringLease>,
+	f: impl FnOnce(&dyn AuthoringLease) -> bool + Send + 'static,
+) -> bool {
+	let lease = lease.clone();
+	tokio::task::spawn_blocking(move || f(&*lease)).await.unwrap_or_else(|error| {
+		tracing::error!(target: LOG_TARGET, %error, "Failed to access the authoring lease.");
+		false
+	})
+}
+
 /// Translate the slot of the relay parent to the slot of the parachain.
 fn adjust_para_to_relay_parent_slot(
 	relay_header: &RelayHeader,
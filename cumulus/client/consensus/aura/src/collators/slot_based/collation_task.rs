@@ -55,6 +55,8 @@ pub struct Params<Block: BlockT, RClient, CS> {
 	pub block_import_handle: super::SlotBasedBlockImportHandle<Block>,
 	/// When set, the collator will export every produced `POV` to this folder.
 	pub export_pov: Option<PathBuf>,
+	/// When set, the composition of the storage proof of each block is observed in these metrics.
+	pub pov_metrics: Option<cumulus_client_pov_analyzer::Metrics>,
 }
 
 /// Asynchronously executes the collation task for a parachain.
@@ -73,6 +75,7 @@ pub async fn run_collation_task<Block, RClient, CS>(
 		mut collator_receiver,
 		mut block_import_handle,
 		export_pov,
+		pov_metrics,
 	}: Params<Block, RClient, CS>,
 ) where
 	Block: BlockT,
@@ -99,7 +102,15 @@ pub async fn run_collation_task<Block, RClient, CS>(
 					return;
 				};
 
-				handle_collation_message(message, &collator_service, &mut overseer_handle,relay_client.clone(),export_pov.clone()).await;
+				handle_collation_message(
+					message,
+					&collator_service,
+					&mut overseer_handle,
+					relay_client.clone(),
+					export_pov.clone(),
+					pov_metrics.as_ref(),
+				)
+				.await;
 			},
 			block_import_msg = block_import_handle.next().fuse() => {
 				// TODO: Implement me.
@@ -119,6 +130,7 @@ async fn handle_collation_message<Block: BlockT, RClient: RelayChainInterface +
 	overseer_handle: &mut OverseerHandle,
 	relay_client: RClient,
 	export_pov: Option<PathBuf>,
+	pov_metrics: Option<&cumulus_client_pov_analyzer::Metrics>,
 ) {
 	let CollatorMessage {
 		parent_header,
@@ -141,6 +153,11 @@ async fn handle_collation_message<Block: BlockT, RClient: RelayChainInterface +
 		};
 
 	block_data.log_size_info();
+	cumulus_client_pov_analyzer::log_storage_composition(
+		&block_data,
+		*parent_header.state_root(),
+		pov_metrics,
+	);
 
 	if let MaybeCompressedPoV::Compressed(ref pov) = collation.proof_of_validity {
 		if let Some(pov_path) = export_pov {
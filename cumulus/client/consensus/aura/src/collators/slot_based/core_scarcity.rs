@@ -0,0 +1,225 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Adapting the block building to the availability of relay chain cores.
+//!
+//! The claim queue fetched for every relay parent tells how many cores the para will get in the
+//! upcoming relay chain blocks. When the para only claims a core in a few of them, the blocks it
+//! builds now may be the last ones to get backed for a while. [`CoreScarcity`] detects these
+//! situations, so that the block builder can keep the blocks small and reserve them to the
+//! transactions with the highest priority, leaving the bulk ones in the pool for later blocks.
+
+use polkadot_node_subsystem_util::runtime::ClaimQueueSnapshot;
+use polkadot_primitives::Id as ParaId;
+use prometheus_endpoint::{register, Counter, Gauge, PrometheusError, Registry, U64};
+use sp_runtime::{transaction_validity::TransactionPriority, Percent};
+
+/// Availability of the cores claimed by the para in the claim queue.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CoreAvailability {
+	/// Number of relay chain blocks after the one the para builds on that are covered by the
+	/// claim queue.
+	pub upcoming_blocks: u32,
+	/// Number of these upcoming relay chain blocks for which the para claims at least one core.
+	pub upcoming_blocks_with_cores: u32,
+}
+
+impl CoreAvailability {
+	/// Compute the availability of the cores of `para_id` in the `claim_queue`, when building on
+	/// the claims at depth `relay_parent_offset`.
+	pub fn from_claim_queue(
+		claim_queue: &ClaimQueueSnapshot,
+		para_id: ParaId,
+		relay_parent_offset: u32,
+	) -> Self {
+		let depth = relay_parent_offset as usize;
+		let claim_queue_len =
+			claim_queue.iter_all_claims().map(|(_, claims)| claims.len()).max().unwrap_or(0);
+		let cores_at = |depth| claim_queue.iter_claims_at_depth_for_para(depth, para_id).count();
+
+		let upcoming_depths = (depth + 1)..claim_queue_len;
+		Self {
+			upcoming_blocks: upcoming_depths.len() as u32,
+			upcoming_blocks_with_cores: upcoming_depths.filter(|depth| cores_at(*depth) > 0).count()
+				as u32,
+		}
+	}
+
+	/// Share of the upcoming relay chain blocks for which the para claims a core.
+	///
+	/// Returns `None` if the claim queue doesn't cover any upcoming relay chain block.
+	pub fn upcoming_share(&self) -> Option<Percent> {
+		(self.upcoming_blocks > 0)
+			.then(|| Percent::from_rational(self.upcoming_blocks_with_cores, self.upcoming_blocks))
+	}
+}
+
+/// Configuration of the block building while cores are scarce.
+#[derive(Clone, Debug)]
+pub struct CoreScarcityConfig {
+	/// Cores are scarce when the para claims a core for less than this share of the upcoming
+	/// relay chain blocks.
+	pub threshold: Percent,
+	/// The maximum percentage of the maximum PoV size that the collator can use while cores are
+	/// scarce.
+	///
+	/// The percentage used otherwise applies when not set.
+	pub max_pov_percentage: Option<u32>,
+	/// Transactions with a lower priority are left in the transaction pool while cores are
+	/// scarce.
+	pub min_transaction_priority: Option<TransactionPriority>,
+}
+
+impl Default for CoreScarcityConfig {
+	fn default() -> Self {
+		Self {
+			threshold: Percent::from_percent(50),
+			max_pov_percentage: None,
+			min_transaction_priority: None,
+		}
+	}
+}
+
+#[derive(Clone)]
+struct Metrics {
+	upcoming_core_availability: Gauge<U64>,
+	scarce_core_attempts: Counter<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			upcoming_core_availability: register(
+				Gauge::new(
+					"cumulus_collator_upcoming_core_availability_percent",
+					"Share of the upcoming relay chain blocks for which the para claims a core",
+				)?,
+				registry,
+			)?,
+			scarce_core_attempts: register(
+				Counter::new(
+					"cumulus_collator_scarce_core_block_production_attempts_total",
+					"Number of block production attempts made while relay chain cores were scarce",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Adapts the block building of the slot-based collator to the availability of relay chain
+/// cores, following a [`CoreScarcityConfig`].
+#[derive(Clone)]
+pub struct CoreScarcity {
+	config: CoreScarcityConfig,
+	metrics: Option<Metrics>,
+}
+
+impl CoreScarcity {
+	/// Create a new instance, registering its metrics in the given `registry`.
+	pub fn new(
+		config: CoreScarcityConfig,
+		registry: Option<&Registry>,
+	) -> Result<Self, PrometheusError> {
+		Ok(Self { config, metrics: registry.map(Metrics::register).transpose()? })
+	}
+
+	/// Returns the configuration to apply to the next block if cores are scarce given their
+	/// `availability`.
+	pub fn check(&self, availability: &CoreAvailability) -> Option<&CoreScarcityConfig> {
+		let share = availability.upcoming_share();
+		let scarce = share.is_some_and(|share| share < self.config.threshold);
+
+		if let Some(metrics) = &self.metrics {
+			if let Some(share) = share {
+				metrics.upcoming_core_availability.set(share.deconstruct().into());
+			}
+			if scarce {
+				metrics.scarce_core_attempts.inc();
+			}
+		}
+
+		scarce.then_some(&self.config)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_primitives::CoreIndex;
+	use std::collections::BTreeMap;
+
+	const PARA: u32 = 1;
+	const OTHER_PARA: u32 = 2;
+
+	fn claim_queue(cores: &[&[u32]]) -> ClaimQueueSnapshot {
+		BTreeMap::from_iter(cores.iter().enumerate().map(|(index, claims)| {
+			(CoreIndex(index as u32), claims.iter().map(|para| ParaId::from(*para)).collect())
+		}))
+		.into()
+	}
+
+	#[test]
+	fn availability_counts_upcoming_blocks_with_cores() {
+		let claim_queue = claim_queue(&[
+			&[PARA, OTHER_PARA, OTHER_PARA, PARA],
+			&[PARA, OTHER_PARA, PARA, OTHER_PARA],
+		]);
+
+		assert_eq!(
+			CoreAvailability::from_claim_queue(&claim_queue, PARA.into(), 0),
+			CoreAvailability { upcoming_blocks: 3, upcoming_blocks_with_cores: 2 },
+		);
+		assert_eq!(
+			CoreAvailability::from_claim_queue(&claim_queue, PARA.into(), 1),
+			CoreAvailability { upcoming_blocks: 2, upcoming_blocks_with_cores: 2 },
+		);
+		assert_eq!(
+			CoreAvailability::from_claim_queue(&claim_queue, PARA.into(), 3).upcoming_share(),
+			None,
+		);
+	}
+
+	#[test]
+	fn cores_are_scarce_below_threshold() {
+		let registry = Registry::new();
+		let core_scarcity = CoreScarcity::new(
+			CoreScarcityConfig { min_transaction_priority: Some(100), ..Default::default() },
+			Some(&registry),
+		)
+		.unwrap();
+		let metrics = core_scarcity.metrics.as_ref().unwrap();
+
+		let available = claim_queue(&[&[PARA, PARA, OTHER_PARA]]);
+		let availability = CoreAvailability::from_claim_queue(&available, PARA.into(), 0);
+		assert!(core_scarcity.check(&availability).is_none());
+		assert_eq!(metrics.upcoming_core_availability.get(), 50);
+
+		let scarce = claim_queue(&[&[PARA, OTHER_PARA, OTHER_PARA]]);
+		let availability = CoreAvailability::from_claim_queue(&scarce, PARA.into(), 0);
+		let config = core_scarcity.check(&availability).unwrap();
+		assert_eq!(config.min_transaction_priority, Some(100));
+		assert_eq!(metrics.upcoming_core_availability.get(), 0);
+		assert_eq!(metrics.scarce_core_attempts.get(), 1);
+
+		// Without any visible upcoming block, cores are not considered scarce.
+		let short = claim_queue(&[&[PARA]]);
+		let availability = CoreAvailability::from_claim_queue(&short, PARA.into(), 0);
+		assert!(core_scarcity.check(&availability).is_none());
+		assert_eq!(metrics.scarce_core_attempts.get(), 1);
+	}
+}
@@ -65,11 +65,31 @@
 //!
 //! 1. Block compression
 //! 2. Submission to the collation-generation subsystem
+//!
+//! # Standby Collators
+//!
+//! Several nodes can share the same authoring keys when they coordinate through an
+//! [`AuthoringLease`]: only the node holding the lease builds blocks, while the others take over
+//! once it stops renewing the lease. See [`FileLease`] for a lease shared through a file.
+//!
+//! # Core Scarcity
+//!
+//! The claim queue fetched for every relay parent also tells how many cores the para gets in the
+//! upcoming relay chain blocks. With a [`CoreScarcity`], the block builder keeps its blocks
+//! smaller and only includes high priority transactions while cores are scarce.
+//!
+//! # Simulation
+//!
+//! The block production can be simulated without a running node, e.g. to tune the elastic scaling
+//! parameters of a parachain, by running the collator against mocked clients with a
+//! [`TimeSource`] following the paused clock of tokio.
 
 use self::{block_builder_task::run_block_builder, collation_task::run_collation_task};
+pub use authoring_lease::{AuthoringLease, FileLease};
 pub use block_import::{SlotBasedBlockImport, SlotBasedBlockImportHandle};
 use codec::Codec;
 use consensus_common::ParachainCandidate;
+pub use core_scarcity::{CoreAvailability, CoreScarcity, CoreScarcityConfig};
 use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterface;
 use cumulus_client_consensus_common::{self as consensus_common, ParachainBlockImportMarker};
 use cumulus_primitives_aura::AuraUnincludedSegmentApi;
@@ -83,6 +103,7 @@ use sc_client_api::{backend::AuxStore, BlockBackend, BlockOf, UsageProvider};
 use sc_consensus::BlockImport;
 use sc_network_types::PeerId;
 use sc_utils::mpsc::tracing_unbounded;
+pub use slot_timer::TimeSource;
 use sp_api::ProvideRuntimeApi;
 use sp_application_crypto::AppPublic;
 use sp_blockchain::HeaderBackend;
@@ -94,9 +115,11 @@ use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block as BlockT, Member};
 use std::{path::PathBuf, sync::Arc, time::Duration};
 
+mod authoring_lease;
 mod block_builder_task;
 mod block_import;
 mod collation_task;
+mod core_scarcity;
 mod relay_chain_data_cache;
 mod slot_timer;
 
@@ -149,6 +172,17 @@ pub struct Params<Block, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS,
 	/// The maximum percentage of the maximum PoV size that the collator can use.
 	/// It will be removed once <https://github.com/paritytech/polkadot-sdk/issues/6020> is fixed.
 	pub max_pov_percentage: Option<u32>,
+	/// When set, blocks are only built while holding this lease, shared with standby nodes using
+	/// the same authoring keys.
+	pub authoring_lease: Option<Arc<dyn AuthoringLease>>,
+	/// When set, the block building is adapted to the availability of relay chain cores.
+	pub core_scarcity: Option<CoreScarcity>,
+	/// When set, the composition of the storage proof of each block is observed in these metrics.
+	pub pov_metrics: Option<cumulus_client_pov_analyzer::Metrics>,
+	/// The source of the current time, the system time when `None`.
+	///
+	/// Only meant to simulate the block production, see [`TimeSource`].
+	pub time_source: Option<TimeSource>,
 }
 
 /// Run aura-based block building and collation task.
@@ -201,6 +235,10 @@ pub fn run<Block, P, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS, Spaw
 		export_pov,
 		relay_chain_slot_duration,
 		max_pov_percentage,
+		authoring_lease,
+		core_scarcity,
+		pov_metrics,
+		time_source,
 	} = params;
 
 	let (tx, rx) = tracing_unbounded("mpsc_builder_to_collator", 100);
@@ -213,6 +251,7 @@ pub fn run<Block, P, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS, Spaw
 		collator_receiver: rx,
 		block_import_handle,
 		export_pov,
+		pov_metrics,
 	};
 
 	let collation_task_fut = run_collation_task::<Block, _, _>(collator_task_params);
@@ -234,6 +273,9 @@ pub fn run<Block, P, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS, Spaw
 		relay_chain_slot_duration,
 		slot_offset,
 		max_pov_percentage,
+		authoring_lease,
+		core_scarcity,
+		time_source,
 	};
 
 	let block_builder_fut =
@@ -55,6 +55,13 @@ const BLOCK_PRODUCTION_THRESHOLD_MS: Duration = Duration::from_millis(100);
 /// should be reduced by to fit into the slot timing.
 const BLOCK_PRODUCTION_ADJUSTMENT_MS: Duration = Duration::from_millis(1000);
 
+/// Returns the current time as a duration since the Unix epoch.
+///
+/// The block production is timed with the system time, unless another source is given to
+/// [`Params`](super::Params) to simulate it. As the block production attempts are awaited with
+/// tokio, the source must follow its clock, e.g. while it is paused.
+pub type TimeSource = Arc<dyn Fn() -> Duration + Send + Sync>;
+
 #[derive(Debug)]
 pub(crate) struct SlotInfo {
 	pub timestamp: Timestamp,
@@ -62,12 +69,13 @@ pub(crate) struct SlotInfo {
 }
 
 /// Manages block-production timings based on chain parameters and assigned cores.
-#[derive(Debug)]
 pub(crate) struct SlotTimer<Block, Client, P> {
 	/// Parachain client that is used for runtime calls
 	client: Arc<Client>,
 	/// Offset the current time by this duration.
 	time_offset: Duration,
+	/// The source of the current time, the system time when `None`.
+	time_source: Option<TimeSource>,
 	/// Last reported core count.
 	last_reported_core_num: Option<u32>,
 	/// Slot duration of the relay chain. This is used to compute how man block-production
@@ -258,6 +266,7 @@ where
 		Self {
 			client,
 			time_offset,
+			time_source: None,
 			last_reported_core_num: None,
 			relay_slot_duration,
 			last_reported_slot: Default::default(),
@@ -265,6 +274,17 @@ where
 		}
 	}
 
+	/// Use `time_source` instead of the system time.
+	pub fn with_time_source(mut self, time_source: Option<TimeSource>) -> Self {
+		self.time_source = time_source;
+		self
+	}
+
+	/// Returns the current time since the Unix epoch.
+	fn now(&self) -> Duration {
+		self.time_source.as_ref().map_or_else(duration_now, |time_source| time_source())
+	}
+
 	/// Inform the slot timer about the last seen number of cores.
 	pub fn update_scheduling(&mut self, num_cores_next_block: u32) {
 		self.last_reported_core_num = Some(num_cores_next_block);
@@ -276,7 +296,7 @@ where
 			slot_duration,
 			self.relay_slot_duration,
 			self.last_reported_core_num,
-			duration_now(),
+			self.now(),
 			self.time_offset,
 		)
 	}
@@ -288,7 +308,7 @@ where
 	) -> Option<(Duration, Slot)> {
 		compute_time_until_next_slot_change(
 			slot_duration,
-			duration_now(),
+			self.now(),
 			self.time_offset,
 			self.last_reported_slot.unwrap_or_default(),
 		)
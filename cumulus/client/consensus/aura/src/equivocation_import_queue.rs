@@ -20,8 +20,12 @@
 /// Equivocation resistance in general is a hard problem, as different nodes in the network
 /// may see equivocations in a different order, and therefore may not agree on which blocks
 /// should be thrown out and which ones should be kept.
+///
+/// Collators authoring conflicting blocks can additionally be reported to the runtime, see
+/// [`Verifier::with_equivocation_reporter`].
 use codec::Codec;
 use cumulus_client_consensus_common::ParachainBlockImportMarker;
+use cumulus_primitives_aura::{AuraEquivocationApi, EquivocationProof};
 use cumulus_primitives_core::{CumulusDigestItem, RelayBlockIdentifier};
 use parking_lot::Mutex;
 use polkadot_primitives::Hash as RHash;
@@ -31,8 +35,9 @@ use sc_consensus::{
 };
 use sc_consensus_aura::{standalone as aura_internal, AuthoritiesTracker};
 use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_TRACE};
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use schnellru::{ByLength, LruMap};
-use sp_api::ProvideRuntimeApi;
+use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder as BlockBuilderApi;
 use sp_blockchain::{HeaderBackend, HeaderMetadata};
 use sp_consensus::{error::Error as ConsensusError, BlockOrigin};
@@ -73,11 +78,129 @@ impl<N: std::hash::Hash + PartialEq> NaiveEquivocationDefender<N> {
 	}
 }
 
+/// Reports the equivocations detected by the [`Verifier`].
+pub trait EquivocationReporter<Block: BlockT, AuthorityId>: Send + Sync {
+	/// Reports the equivocation, whose headers still carry their seals.
+	fn report_equivocation(
+		&self,
+		equivocation_proof: EquivocationProof<Block::Header, AuthorityId>,
+	) -> Result<(), String>;
+}
+
+/// Reports equivocations through the [`AuraEquivocationApi`] of the runtime at the best block.
+///
+/// The runtime submits the report to the transaction pool as an unsigned extrinsic.
+pub struct RuntimeEquivocationReporter<Block: BlockT, Client> {
+	client: Arc<Client>,
+	offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+}
+
+impl<Block: BlockT, Client> RuntimeEquivocationReporter<Block, Client> {
+	/// Creates a new reporter submitting the reports to the pool of `offchain_tx_pool_factory`.
+	pub fn new(
+		client: Arc<Client>,
+		offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+	) -> Self {
+		Self { client, offchain_tx_pool_factory }
+	}
+}
+
+impl<Block, Client, AuthorityId> EquivocationReporter<Block, AuthorityId>
+	for RuntimeEquivocationReporter<Block, Client>
+where
+	Block: BlockT,
+	AuthorityId: Codec + 'static,
+	Client: HeaderBackend<Block> + ProvideRuntimeApi<Block> + Send + Sync,
+	<Client as ProvideRuntimeApi<Block>>::Api: AuraEquivocationApi<Block, AuthorityId>,
+{
+	fn report_equivocation(
+		&self,
+		equivocation_proof: EquivocationProof<Block::Header, AuthorityId>,
+	) -> Result<(), String> {
+		let best_hash = self.client.info().best_hash;
+		let mut runtime_api = self.client.runtime_api();
+		if !runtime_api
+			.has_api::<dyn AuraEquivocationApi<Block, AuthorityId>>(best_hash)
+			.map_err(|e| e.to_string())?
+		{
+			return Err("The runtime doesn't support equivocation reports".into())
+		}
+
+		// Register the offchain tx pool to be able to use it from the runtime.
+		runtime_api
+			.register_extension(self.offchain_tx_pool_factory.offchain_transaction_pool(best_hash));
+
+		runtime_api
+			.submit_report_equivocation_unsigned_extrinsic(best_hash, equivocation_proof)
+			.map_err(|e| e.to_string())?
+			.ok_or_else(|| "The runtime failed to submit the equivocation report".into())
+	}
+}
+
+/// Detects blocks conflicting with the first one seen in their slot and reports them.
+struct EquivocationReporting<Block: BlockT, AuthorityId> {
+	/// The first sealed header seen for each `(Slot, Parent, RelayParent)`, or `None` once an
+	/// equivocation was reported for it.
+	first_headers: Mutex<LruMap<(u64, Block::Hash, RHash), Option<Block::Header>>>,
+	reporter: Box<dyn EquivocationReporter<Block, AuthorityId>>,
+}
+
+impl<Block: BlockT, AuthorityId: Debug> EquivocationReporting<Block, AuthorityId> {
+	fn new(reporter: Box<dyn EquivocationReporter<Block, AuthorityId>>) -> Self {
+		Self { first_headers: Mutex::new(LruMap::new(ByLength::new(LRU_WINDOW))), reporter }
+	}
+
+	/// Reports `offender` if `header` conflicts with the first block seen in `slot` on top of the
+	/// same parent and relay parent.
+	///
+	/// As authors may author several consecutive blocks per slot, blocks of the same slot only
+	/// conflict when they build on the same parent.
+	fn check_and_report(
+		&self,
+		slot: Slot,
+		relay_parent: RHash,
+		offender: AuthorityId,
+		header: Block::Header,
+	) {
+		let first_header = {
+			let mut first_headers = self.first_headers.lock();
+			let key = (*slot, *header.parent_hash(), relay_parent);
+			match first_headers.get(&key) {
+				None => {
+					first_headers.insert(key, Some(header.clone()));
+					return
+				},
+				Some(first_header) => match first_header {
+					Some(first) if first.hash() != header.hash() => first_header.take(),
+					_ => None,
+				},
+			}
+		};
+		let Some(first_header) = first_header else { return };
+
+		tracing::warn!(
+			target: crate::LOG_TARGET,
+			?offender,
+			?slot,
+			first = ?first_header.hash(),
+			second = ?header.hash(),
+			"Detected an equivocation, reporting it",
+		);
+
+		let equivocation_proof =
+			EquivocationProof { offender, slot, first_header, second_header: header };
+		if let Err(error) = self.reporter.report_equivocation(equivocation_proof) {
+			tracing::warn!(target: crate::LOG_TARGET, %error, "Failed to report equivocation");
+		}
+	}
+}
+
 /// A parachain block import verifier that checks for equivocation limits within each slot.
 pub struct Verifier<P: Pair, Client, Block: BlockT, CIDP> {
 	client: Arc<Client>,
 	create_inherent_data_providers: CIDP,
 	defender: Mutex<NaiveEquivocationDefender<NumberFor<Block>>>,
+	equivocation_reporting: Option<EquivocationReporting<Block, P::Public>>,
 	telemetry: Option<TelemetryHandle>,
 	// Unused for now. Will be plugged in with a later PR.
 	_authorities_tracker: AuthoritiesTracker<P, Block, Client>,
@@ -105,10 +228,23 @@ where
 			client: client.clone(),
 			create_inherent_data_providers: inherent_data_provider,
 			defender: Mutex::new(NaiveEquivocationDefender::default()),
+			equivocation_reporting: None,
 			telemetry,
 			_authorities_tracker: AuthoritiesTracker::new(client),
 		}
 	}
+
+	/// Reports the authors of conflicting blocks with `reporter`.
+	///
+	/// Blocks conflict when they are authored in the same slot on top of the same parent and
+	/// relay parent. Blocks imported during the initial sync are not checked.
+	pub fn with_equivocation_reporter(
+		mut self,
+		reporter: impl EquivocationReporter<Block, P::Public> + 'static,
+	) -> Self {
+		self.equivocation_reporting = Some(EquivocationReporting::new(Box::new(reporter)));
+		self
+	}
 }
 
 #[async_trait::async_trait]
@@ -172,16 +308,32 @@ where
 						"pre_header" => ?pre_header,
 					);
 
+					let relay_block_identifier = CumulusDigestItem::find_relay_block_identifier(
+						pre_header.digest(),
+					)
+					.map(|identifier| match identifier {
+						RelayBlockIdentifier::ByHash(h) |
+						RelayBlockIdentifier::ByStorageRoot { storage_root: h, .. } => h,
+					});
+
+					// Report conflicting blocks, unless catching up with the chain. Blocks without
+					// a relay parent are never reported, the runtime can't tell them to conflict.
+					let reporting = self.equivocation_reporting.as_ref().filter(|_| {
+						!matches!(block_params.origin, BlockOrigin::NetworkInitialSync)
+					});
+					if let (Some(reporting), Some(relay_parent), Some(offender)) = (
+						reporting,
+						relay_block_identifier,
+						aura_internal::slot_author::<P>(slot, &authorities),
+					) {
+						let mut header = pre_header.clone();
+						header.digest_mut().push(seal_digest.clone());
+						reporting.check_and_report(slot, relay_parent, offender.clone(), header);
+					}
+
 					// We need some kind of identifier for the relay parent, in the worst case we
 					// take the all `0` hash.
-					let relay_parent =
-						match CumulusDigestItem::find_relay_block_identifier(pre_header.digest()) {
-							None => Default::default(),
-							Some(RelayBlockIdentifier::ByHash(h)) |
-							Some(RelayBlockIdentifier::ByStorageRoot {
-								storage_root: h, ..
-							}) => h,
-						};
+					let relay_parent = relay_block_identifier.unwrap_or_default();
 
 					block_params.header = pre_header;
 					block_params.post_digests.push(seal_digest);
@@ -294,6 +446,7 @@ where
 		client: client.clone(),
 		create_inherent_data_providers,
 		defender: Mutex::new(NaiveEquivocationDefender::default()),
+		equivocation_reporting: None,
 		telemetry,
 		_authorities_tracker: AuthoritiesTracker::new(client.clone()),
 	};
@@ -329,6 +482,7 @@ mod test {
 				Ok(sp_timestamp::InherentDataProvider::from_system_time())
 			},
 			defender: Mutex::new(NaiveEquivocationDefender::default()),
+			equivocation_reporting: None,
 			telemetry: None,
 			_authorities_tracker: AuthoritiesTracker::new(client.clone()),
 		};
@@ -389,4 +543,72 @@ mod test {
 			assert!(verifier.verify(params).now_or_never().unwrap().is_ok());
 		});
 	}
+
+	type Reports =
+		Arc<Mutex<Vec<EquivocationProof<<Block as BlockT>::Header, sr25519::AuthorityId>>>>;
+
+	struct CollectingReporter(Reports);
+
+	impl EquivocationReporter<Block, sr25519::AuthorityId> for CollectingReporter {
+		fn report_equivocation(
+			&self,
+			equivocation_proof: EquivocationProof<<Block as BlockT>::Header, sr25519::AuthorityId>,
+		) -> Result<(), String> {
+			self.0.lock().push(equivocation_proof);
+			Ok(())
+		}
+	}
+
+	#[test]
+	fn report_equivocated_blocks_once() {
+		try_init_simple();
+
+		let client = Arc::new(TestClientBuilder::default().build());
+		let reports = Reports::default();
+
+		let verifier = Verifier::<sr25519::AuthorityPair, Client, Block, _>::new(
+			client.clone(),
+			|_, _| async move { Ok(sp_timestamp::InherentDataProvider::from_system_time()) },
+			None,
+		)
+		.with_equivocation_reporter(CollectingReporter(reports.clone()));
+
+		let genesis = client.info().best_hash;
+		let mut sproof = RelayStateSproofBuilder::default();
+		sproof.included_para_head = Some(HeadData(client.header(genesis).unwrap().encode()));
+		sproof.para_id = cumulus_test_client::runtime::PARACHAIN_ID.into();
+
+		let validation_data = PersistedValidationData {
+			relay_parent_number: 1,
+			parent_head: client.header(genesis).unwrap().encode().into(),
+			..Default::default()
+		};
+
+		let block_builder = client.init_block_builder(Some(validation_data), sproof);
+		let block = block_builder.block_builder.build().unwrap();
+
+		// Sealing the same block several times results in conflicting blocks of the same slot.
+		let blocks = (0..3).map(|_| seal_block(block.block.clone(), &client)).collect::<Vec<_>>();
+		let verify = |block: &Block, origin| {
+			let mut params = BlockImportParams::new(origin, block.header().clone());
+			params.body = Some(block.extrinsics().to_vec());
+			verifier.verify(params).now_or_never().unwrap().unwrap();
+		};
+
+		// Blocks of the initial sync are not checked.
+		verify(&blocks[2], BlockOrigin::NetworkInitialSync);
+		assert!(reports.lock().is_empty());
+
+		verify(&blocks[0], BlockOrigin::NetworkBroadcast);
+		verify(&blocks[0], BlockOrigin::NetworkBroadcast);
+		assert!(reports.lock().is_empty());
+
+		verify(&blocks[1], BlockOrigin::NetworkBroadcast);
+		verify(&blocks[2], BlockOrigin::NetworkBroadcast);
+
+		let reports = reports.lock();
+		assert_eq!(reports.len(), 1);
+		assert_eq!(reports[0].first_header, *blocks[0].header());
+		assert_eq!(reports[0].second_header, *blocks[1].header());
+	}
 }
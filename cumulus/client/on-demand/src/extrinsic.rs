@@ -0,0 +1,277 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Encoding of the signed extrinsics placing orders on the relay chain.
+//!
+//! The call and the transaction extensions are taken from the metadata of the relay chain
+//! runtime, see [`crate::metadata`]. Each extension adds explicit data to the extrinsic and
+//! implicit data to the signed payload. Only the extensions in [`Extension`] are supported, plus
+//! any extension adding no data at all.
+
+use codec::{Compact, Encode};
+use cumulus_primitives_core::relay_chain::{AccountId, Balance, Hash, Nonce};
+use sp_core::{blake2_256, sr25519};
+use sp_runtime::{generic::Era, MultiAddress, MultiSignature};
+
+/// The version of the extrinsic format, with the bit marking signed extrinsics.
+const SIGNED_EXTRINSIC_VERSION: u8 = 0b1000_0000 | 4;
+
+/// How an order is paid for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PaymentMethod {
+	/// Pay with the on-demand credits of the account.
+	Credits,
+	/// Pay with the free balance of the account, keeping it alive.
+	Balance,
+}
+
+/// A transaction extension of the relay chain runtime, by the data it adds.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Extension {
+	/// An extension adding no data, like `CheckNonZeroSender` or `CheckWeight`.
+	Empty,
+	/// `CheckSpecVersion`, signing the spec version.
+	SpecVersion,
+	/// `CheckTxVersion`, signing the transaction version.
+	TxVersion,
+	/// `CheckGenesis`, signing the genesis hash.
+	Genesis,
+	/// `CheckMortality`, adding the era and signing the hash of the block it starts at.
+	Mortality,
+	/// `CheckNonce`, adding the nonce.
+	Nonce,
+	/// `ChargeTransactionPayment`, adding the tip, which is always zero.
+	Tip,
+	/// `CheckMetadataHash`, adding its mode, which is always disabled.
+	MetadataHash,
+}
+
+impl Extension {
+	/// Returns the extension with the given identifier, if it adds data.
+	pub(crate) fn from_identifier(identifier: &str) -> Option<Self> {
+		Some(match identifier {
+			"CheckSpecVersion" => Self::SpecVersion,
+			"CheckTxVersion" => Self::TxVersion,
+			"CheckGenesis" => Self::Genesis,
+			"CheckMortality" => Self::Mortality,
+			"CheckNonce" => Self::Nonce,
+			"ChargeTransactionPayment" => Self::Tip,
+			"CheckMetadataHash" => Self::MetadataHash,
+			_ => return None,
+		})
+	}
+
+	/// Encodes the data the extension adds to the extrinsic.
+	fn encode_explicit(self, params: &SigningParams, dest: &mut Vec<u8>) {
+		match self {
+			Self::Mortality => params.era.encode_to(dest),
+			Self::Nonce => Compact(params.nonce).encode_to(dest),
+			Self::Tip => Compact(0 as Balance).encode_to(dest),
+			Self::MetadataHash => 0u8.encode_to(dest),
+			Self::Empty | Self::SpecVersion | Self::TxVersion | Self::Genesis => {},
+		}
+	}
+
+	/// Encodes the data the extension adds to the signed payload.
+	fn encode_implicit(self, params: &SigningParams, dest: &mut Vec<u8>) {
+		match self {
+			Self::SpecVersion => params.spec_version.encode_to(dest),
+			Self::TxVersion => params.transaction_version.encode_to(dest),
+			Self::Genesis => params.genesis_hash.encode_to(dest),
+			Self::Mortality => params.era_block_hash.encode_to(dest),
+			Self::MetadataHash => None::<[u8; 32]>.encode_to(dest),
+			Self::Empty | Self::Nonce | Self::Tip => {},
+		}
+	}
+}
+
+/// The data the extrinsic is signed for, besides the call.
+pub(crate) struct SigningParams {
+	/// The spec version of the relay chain runtime.
+	pub spec_version: u32,
+	/// The transaction version of the relay chain runtime.
+	pub transaction_version: u32,
+	/// The hash of the genesis block of the relay chain.
+	pub genesis_hash: Hash,
+	/// The mortality of the extrinsic.
+	pub era: Era,
+	/// The hash of the block the mortality of the extrinsic starts at.
+	pub era_block_hash: Hash,
+	/// The nonce of the signing account.
+	pub nonce: Nonce,
+}
+
+/// Returns the encoded extrinsic dispatching `call` with the transaction `extensions`, signed by
+/// `signer` with `sign`.
+///
+/// Returns `None` if `sign` fails to sign the payload.
+pub(crate) fn signed_extrinsic(
+	call: &[u8],
+	extensions: &[Extension],
+	params: &SigningParams,
+	signer: &sr25519::Public,
+	sign: impl FnOnce(&[u8]) -> Option<sr25519::Signature>,
+) -> Option<Vec<u8>> {
+	let mut explicit = Vec::new();
+	let mut implicit = Vec::new();
+	for extension in extensions {
+		extension.encode_explicit(params, &mut explicit);
+		extension.encode_implicit(params, &mut implicit);
+	}
+
+	let payload = [call, &explicit, &implicit].concat();
+	let signature = if payload.len() > 256 { sign(&blake2_256(&payload)) } else { sign(&payload) }?;
+
+	let mut extrinsic = vec![SIGNED_EXTRINSIC_VERSION];
+	MultiAddress::<AccountId, ()>::Id((*signer).into()).encode_to(&mut extrinsic);
+	MultiSignature::from(signature).encode_to(&mut extrinsic);
+	extrinsic.extend_from_slice(&explicit);
+	extrinsic.extend_from_slice(call);
+	Some(extrinsic.encode())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::metadata::{RelayMetadata, METADATA_VERSION};
+	use codec::Decode;
+	use cumulus_primitives_core::ParaId;
+	use sp_core::Pair;
+
+	fn sign_with(pair: &sr25519::Pair) -> impl FnOnce(&[u8]) -> Option<sr25519::Signature> + '_ {
+		|payload| Some(pair.sign(payload))
+	}
+
+	fn params() -> SigningParams {
+		SigningParams {
+			spec_version: 1_018_000,
+			transaction_version: 27,
+			genesis_hash: Hash::repeat_byte(1),
+			era: Era::mortal(64, 1_000),
+			era_block_hash: Hash::repeat_byte(2),
+			nonce: 7,
+		}
+	}
+
+	#[test]
+	fn signed_extrinsic_encoding_works() {
+		use Extension::*;
+
+		let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+		let call = vec![66, 1, 2, 3];
+		let extensions = [Empty, SpecVersion, TxVersion, Genesis, Mortality, Nonce, Tip, MetadataHash];
+		let params = params();
+		let encoded =
+			signed_extrinsic(&call, &extensions, &params, &pair.public(), sign_with(&pair))
+				.unwrap();
+
+		let extrinsic = Vec::<u8>::decode(&mut &encoded[..]).unwrap();
+		let input = &mut &extrinsic[..];
+		assert_eq!(u8::decode(input).unwrap(), SIGNED_EXTRINSIC_VERSION);
+		assert_eq!(
+			MultiAddress::<AccountId, ()>::decode(input).unwrap(),
+			MultiAddress::Id(pair.public().into())
+		);
+		let MultiSignature::Sr25519(signature) = MultiSignature::decode(input).unwrap() else {
+			panic!("extrinsics are signed with sr25519")
+		};
+		assert_eq!(Era::decode(input).unwrap(), params.era);
+		assert_eq!(<Compact<Nonce>>::decode(input).unwrap().0, params.nonce);
+		assert_eq!(<Compact<Balance>>::decode(input).unwrap().0, 0);
+		assert_eq!(u8::decode(input).unwrap(), 0);
+		assert_eq!(*input, &call[..]);
+
+		let mut payload = call.clone();
+		(params.era, Compact(params.nonce), Compact(0 as Balance), 0u8).encode_to(&mut payload);
+		(
+			params.spec_version,
+			params.transaction_version,
+			params.genesis_hash,
+			params.era_block_hash,
+			None::<[u8; 32]>,
+		)
+			.encode_to(&mut payload);
+		assert!(sr25519::Pair::verify(&signature, &payload, &pair.public()));
+	}
+
+	#[test]
+	fn signed_extrinsic_matches_westend_tx_extension() {
+		use frame_system::{
+			AuthorizeCall, CheckGenesis, CheckMortality, CheckNonZeroSender, CheckNonce,
+			CheckSpecVersion, CheckTxVersion, CheckWeight, WeightReclaim,
+		};
+		use sp_runtime::{generic::ExtrinsicFormat, traits::Checkable};
+		use westend_runtime::{Runtime, RuntimeCall, TxExtension, UncheckedExtrinsic, VERSION};
+
+		let metadata =
+			RelayMetadata::decode(&Runtime::metadata_at_version(METADATA_VERSION).encode())
+				.unwrap();
+		let pair = sr25519::Pair::from_string("//Alice", None).unwrap();
+		let current_block = 1_000;
+		let params = SigningParams {
+			spec_version: VERSION.spec_version,
+			transaction_version: VERSION.transaction_version,
+			..params()
+		};
+		let call = RuntimeCall::OnDemandAssignmentProvider(
+			polkadot_runtime_parachains::on_demand::Call::place_order_keep_alive {
+				max_amount: 1_000,
+				para_id: 2000.into(),
+			},
+		);
+		let encoded_call =
+			metadata.place_order_call(PaymentMethod::Balance, 1_000, ParaId::from(2000));
+		assert_eq!(encoded_call, call.encode());
+		let encoded = signed_extrinsic(
+			&encoded_call,
+			metadata.extensions(),
+			&params,
+			&pair.public(),
+			sign_with(&pair),
+		)
+		.unwrap();
+
+		let tx_extension: TxExtension = (
+			AuthorizeCall::new(),
+			CheckNonZeroSender::new(),
+			CheckSpecVersion::new(),
+			CheckTxVersion::new(),
+			CheckGenesis::new(),
+			CheckMortality::from(params.era),
+			CheckNonce::from(params.nonce),
+			CheckWeight::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::from(0),
+			frame_metadata_hash_extension::CheckMetadataHash::new(false),
+			WeightReclaim::new(),
+		);
+		sp_io::TestExternalities::default().execute_with(|| {
+			frame_system::BlockHash::<Runtime>::insert(0, params.genesis_hash);
+			let birth = params.era.birth(current_block) as u32;
+			frame_system::BlockHash::<Runtime>::insert(birth, params.era_block_hash);
+			frame_system::Pallet::<Runtime>::set_block_number(current_block as u32);
+
+			// Checking the extrinsic verifies its signature against the payload implied by the
+			// runtime.
+			let extrinsic = UncheckedExtrinsic::decode(&mut &encoded[..]).unwrap();
+			let checked = extrinsic
+				.check(&frame_system::ChainContext::<Runtime>::default())
+				.expect("the extrinsic is signed for the payload of the runtime");
+			assert_eq!(checked.function, call);
+			assert_eq!(checked.format, ExtrinsicFormat::Signed(pair.public().into(), tx_extension));
+		});
+	}
+}
@@ -0,0 +1,489 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Places on-demand coretime orders on the relay chain for a parachain.
+//!
+//! A parachain using on-demand coretime only gets its blocks backed on the relay chain if orders
+//! are placed for it. The task started by [`run`] places them whenever the parachain has
+//! transactions waiting to be included into a block, but no core assigned to it on the relay
+//! chain and no order queued. Orders are only placed while the spot price doesn't exceed the
+//! configured maximum and the configured budget isn't spent yet.
+//!
+//! The orders are placed by submitting signed extrinsics to the relay chain through
+//! [`RelayChainInterface::submit_extrinsic`], signed with the sr25519 key of type [`KEY_TYPE`] in
+//! the keystore. The call and the transaction extensions of the extrinsics are taken from the
+//! metadata of the relay chain runtime, which has to contain the `OnDemandAssignmentProvider`
+//! pallet.
+//!
+//! Orders count against the budget with the spot price reported by the `OnDemandOrderPlaced`
+//! event of the relay chain block including them. Orders which failed or expired don't count.
+
+use codec::Encode;
+use cumulus_primitives_core::{
+	relay_chain::{
+		vstaging::{OnDemandParaOrders, OnDemandQueueStatus},
+		AccountId, Balance, BlockId, BlockNumber, Hash as PHash, Header as PHeader, Nonce,
+	},
+	ParaId,
+};
+use cumulus_relay_chain_interface::{
+	call_runtime_api, RelayChainError, RelayChainInterface, RelayChainResult,
+};
+use futures::StreamExt;
+use metadata::RelayMetadata;
+use sc_transaction_pool_api::TransactionPool;
+use sp_core::{crypto::KeyTypeId, sr25519};
+use sp_keystore::KeystorePtr;
+use sp_runtime::generic::Era;
+use std::sync::Arc;
+
+mod extrinsic;
+mod metadata;
+
+pub use extrinsic::PaymentMethod;
+
+const LOG_TARGET: &str = "cumulus-on-demand";
+
+/// The key type of the key signing the orders.
+pub const KEY_TYPE: KeyTypeId = KeyTypeId(*b"ondm");
+
+/// The number of relay chain blocks an order extrinsic stays valid for.
+const MORTALITY_PERIOD: u64 = 64;
+
+/// Configuration of the orders placed.
+#[derive(Clone, Debug)]
+pub struct OnDemandConfig {
+	/// How orders are paid for.
+	pub payment: PaymentMethod,
+	/// The highest spot price to place orders at.
+	pub max_spot_price: Balance,
+	/// The total amount to spend on orders.
+	///
+	/// An order is only placed if the budget left covers the highest amount it may be charged,
+	/// so the budget is never exceeded.
+	pub budget: Balance,
+}
+
+/// Parameters for [`run`].
+pub struct Params<RClient, TP> {
+	/// The parachain to place orders for.
+	pub para_id: ParaId,
+	/// The interface to the relay chain.
+	pub relay_client: RClient,
+	/// The transaction pool of the parachain, to tell whether a block needs to be built.
+	pub transaction_pool: Arc<TP>,
+	/// The keystore holding the key of type [`KEY_TYPE`] signing the orders.
+	pub keystore: KeystorePtr,
+	/// Configuration of the orders placed.
+	pub config: OnDemandConfig,
+}
+
+/// The state of the parachain at a relay chain block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct ParaState {
+	/// Whether the claim queue assigns a core to the parachain.
+	scheduled: bool,
+	/// Whether an order of the parachain is queued.
+	queued: bool,
+	/// The current spot price of orders.
+	spot_price: Balance,
+}
+
+/// An order submitted to the relay chain, but not yet included.
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct PendingOrder {
+	/// The nonce of the extrinsic placing the order.
+	nonce: Nonce,
+	/// The relay chain block the order was placed at.
+	placed_at: BlockNumber,
+	/// The highest amount the order may be charged.
+	max_amount: Balance,
+}
+
+/// What became of the pending order at a relay chain block.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum PendingStatus {
+	/// No order is pending.
+	Idle,
+	/// The order is waiting to be included.
+	Waiting,
+	/// The order was included in a block after `placed_at`.
+	Included { placed_at: BlockNumber },
+	/// The order expired without being included.
+	Expired,
+}
+
+/// Decides when to place orders and accounts for them against the budget.
+#[derive(Debug)]
+struct Orders {
+	para_id: ParaId,
+	config: OnDemandConfig,
+	spent: Balance,
+	pending: Option<PendingOrder>,
+}
+
+impl Orders {
+	fn new(para_id: ParaId, config: OnDemandConfig) -> Self {
+		Self { para_id, config, spent: 0, pending: None }
+	}
+
+	/// Returns what became of the pending order at relay chain block `number`, given the nonce
+	/// of the account at that block.
+	fn pending_status(&self, number: BlockNumber, nonce: Nonce) -> PendingStatus {
+		match &self.pending {
+			None => PendingStatus::Idle,
+			Some(pending) if nonce > pending.nonce =>
+				PendingStatus::Included { placed_at: pending.placed_at },
+			Some(pending)
+				if number >= pending.placed_at.saturating_add(MORTALITY_PERIOD as BlockNumber) =>
+				PendingStatus::Expired,
+			Some(_) => PendingStatus::Waiting,
+		}
+	}
+
+	/// Accounts for the amount `charged` for the pending order, which is no longer pending.
+	fn settle(&mut self, charged: Balance) {
+		if let Some(pending) = self.pending.take() {
+			tracing::debug!(target: LOG_TARGET, charged, "On-demand order settled.");
+			self.spent = self.spent.saturating_add(charged.min(pending.max_amount));
+		}
+	}
+
+	/// Returns the highest amount to place an order for, or `None` if the parachain doesn't need
+	/// an order or the spot price exceeds the amount.
+	fn order_amount(&self, para: &ParaState) -> Option<Balance> {
+		if self.pending.is_some() || para.scheduled || para.queued {
+			return None
+		}
+		let max_amount =
+			self.config.max_spot_price.min(self.config.budget.saturating_sub(self.spent));
+		(para.spot_price <= max_amount).then_some(max_amount)
+	}
+
+	/// Records an order placed at relay chain block `number` with the given nonce.
+	fn placed(&mut self, number: BlockNumber, nonce: Nonce, max_amount: Balance) {
+		self.pending = Some(PendingOrder { nonce, placed_at: number, max_amount });
+	}
+}
+
+/// The state of the task placing orders.
+struct OrderPlacer<RClient> {
+	relay_client: RClient,
+	keystore: KeystorePtr,
+	signer: sr25519::Public,
+	account: AccountId,
+	orders: Orders,
+	/// The metadata of the relay chain runtime, with its spec version.
+	metadata: Option<(u32, Arc<RelayMetadata>)>,
+}
+
+/// Run the task placing on-demand orders for the parachain.
+pub async fn run<RClient, TP>(params: Params<RClient, TP>)
+where
+	RClient: RelayChainInterface,
+	TP: TransactionPool,
+{
+	let Params { para_id, relay_client, transaction_pool, keystore, config } = params;
+	let Some(signer) = keystore.sr25519_public_keys(KEY_TYPE).into_iter().next() else {
+		tracing::error!(
+			target: LOG_TARGET,
+			"No sr25519 key of type `ondm` in the keystore, not placing on-demand orders.",
+		);
+		return
+	};
+	let mut best_heads = match relay_client.new_best_notification_stream().await {
+		Ok(stream) => stream,
+		Err(error) => {
+			tracing::error!(target: LOG_TARGET, ?error, "Unable to follow the relay chain.");
+			return
+		},
+	};
+	let mut placer = OrderPlacer {
+		relay_client,
+		keystore,
+		signer,
+		account: signer.into(),
+		orders: Orders::new(para_id, config),
+		metadata: None,
+	};
+
+	while let Some(header) = best_heads.next().await {
+		let needs_block = transaction_pool.status().ready > 0;
+		if let Err(error) = placer.on_best_block(&header, needs_block).await {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?error,
+				relay_parent = ?header.hash(),
+				"Unable to place an on-demand order.",
+			);
+		}
+	}
+}
+
+impl<RClient: RelayChainInterface> OrderPlacer<RClient> {
+	/// Place an order at the new best relay chain block if the parachain needs one.
+	async fn on_best_block(&mut self, header: &PHeader, needs_block: bool) -> RelayChainResult<()> {
+		if !needs_block && self.orders.pending.is_none() {
+			return Ok(())
+		}
+		let hash = header.hash();
+		let nonce: Nonce = call_runtime_api(
+			&self.relay_client,
+			"AccountNonceApi_account_nonce",
+			hash,
+			&self.account,
+		)
+		.await?;
+		match self.orders.pending_status(header.number, nonce) {
+			PendingStatus::Idle => {},
+			PendingStatus::Waiting => return Ok(()),
+			PendingStatus::Included { placed_at } => {
+				let charged = self.charged(header, placed_at).await?;
+				self.orders.settle(charged);
+			},
+			PendingStatus::Expired => self.orders.settle(0),
+		}
+		if !needs_block {
+			return Ok(())
+		}
+
+		let para = self.para_state(hash).await?;
+		let Some(max_amount) = self.orders.order_amount(&para) else {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?para,
+				spent = self.orders.spent,
+				"Not placing an on-demand order.",
+			);
+			return Ok(())
+		};
+
+		let extrinsic = self.order_extrinsic(header, nonce, max_amount).await?;
+		let tx_hash = self.relay_client.submit_extrinsic(extrinsic).await?;
+		tracing::info!(
+			target: LOG_TARGET,
+			para_id = ?self.orders.para_id,
+			spot_price = para.spot_price,
+			?tx_hash,
+			"Placed an on-demand order.",
+		);
+		self.orders.placed(header.number, nonce, max_amount);
+		Ok(())
+	}
+
+	/// Returns the spot price the pending order was charged, searching the `OnDemandOrderPlaced`
+	/// event of the account in the blocks from `header` back to the one after `placed_at`.
+	///
+	/// Returns zero if no such event was emitted, because the order failed.
+	async fn charged(&mut self, header: &PHeader, placed_at: BlockNumber) -> RelayChainResult<Balance> {
+		let mut header = header.clone();
+		while header.number > placed_at {
+			let hash = header.hash();
+			let metadata = self.metadata(hash).await?;
+			let events = self
+				.relay_client
+				.get_storage_by_key(hash, metadata.events_key())
+				.await?
+				.unwrap_or_default();
+			let charged = metadata
+				.order_placed(&events, self.orders.para_id, &self.account)
+				.map_err(RelayChainError::GenericError)?;
+			if let Some(charged) = charged {
+				return Ok(charged)
+			}
+			header = self.header(BlockId::Hash(header.parent_hash)).await?;
+		}
+		tracing::debug!(target: LOG_TARGET, "On-demand order included, but not placed.");
+		Ok(0)
+	}
+
+	/// Returns the state of the parachain at relay chain block `hash`.
+	async fn para_state(&self, hash: PHash) -> RelayChainResult<ParaState> {
+		let para_id = self.orders.para_id;
+		let claim_queue = self.relay_client.claim_queue(hash).await?;
+		let orders: OnDemandParaOrders =
+			call_runtime_api(&self.relay_client, "OnDemandApi_para_orders", hash, para_id).await?;
+		let status: OnDemandQueueStatus<Balance> =
+			call_runtime_api(&self.relay_client, "OnDemandApi_queue_status", hash, ()).await?;
+		Ok(ParaState {
+			scheduled: claim_queue.values().any(|paras| paras.contains(&para_id)),
+			queued: !orders.positions.is_empty(),
+			spot_price: status.spot_price,
+		})
+	}
+
+	/// Returns the metadata of the relay chain runtime at block `hash`.
+	async fn metadata(&mut self, hash: PHash) -> RelayChainResult<Arc<RelayMetadata>> {
+		let spec_version = self.relay_client.version(hash).await?.spec_version;
+		match &self.metadata {
+			Some((version, metadata)) if *version == spec_version => return Ok(metadata.clone()),
+			_ => {},
+		}
+		let encoded = self
+			.relay_client
+			.call_runtime_api(
+				"Metadata_metadata_at_version",
+				hash,
+				&metadata::METADATA_VERSION.encode(),
+			)
+			.await?;
+		let metadata = Arc::new(RelayMetadata::decode(&encoded).map_err(|error| {
+			RelayChainError::GenericError(format!(
+				"Unable to place on-demand orders with the relay chain runtime: {error}"
+			))
+		})?);
+		self.metadata = Some((spec_version, metadata.clone()));
+		Ok(metadata)
+	}
+
+	/// Returns the signed extrinsic placing an order for at most `max_amount`.
+	async fn order_extrinsic(
+		&mut self,
+		header: &PHeader,
+		nonce: Nonce,
+		max_amount: Balance,
+	) -> RelayChainResult<Vec<u8>> {
+		let hash = header.hash();
+		let metadata = self.metadata(hash).await?;
+		let version = self.relay_client.version(hash).await?;
+		let genesis_hash = self.header(BlockId::Number(0)).await?.hash();
+		let era = Era::mortal(MORTALITY_PERIOD, header.number.into());
+		let era_block_hash = self
+			.header(BlockId::Number(era.birth(header.number.into()) as BlockNumber))
+			.await?
+			.hash();
+
+		let call = metadata.place_order_call(
+			self.orders.config.payment,
+			max_amount,
+			self.orders.para_id,
+		);
+		let params = extrinsic::SigningParams {
+			spec_version: version.spec_version,
+			transaction_version: version.transaction_version,
+			genesis_hash,
+			era,
+			era_block_hash,
+			nonce,
+		};
+		extrinsic::signed_extrinsic(
+			&call,
+			metadata.extensions(),
+			&params,
+			&self.signer,
+			|payload| self.keystore.sr25519_sign(KEY_TYPE, &self.signer, payload).ok().flatten(),
+		)
+		.ok_or_else(|| RelayChainError::GenericError("Unable to sign the on-demand order".into()))
+	}
+
+	async fn header(&self, id: BlockId) -> RelayChainResult<PHeader> {
+		self.relay_client.header(id).await?.ok_or_else(|| {
+			RelayChainError::GenericError(format!("Relay chain block {id:?} not found"))
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn orders(max_spot_price: Balance, budget: Balance) -> Orders {
+		Orders::new(
+			2000.into(),
+			OnDemandConfig { payment: PaymentMethod::Balance, max_spot_price, budget },
+		)
+	}
+
+	fn para(spot_price: Balance) -> ParaState {
+		ParaState { scheduled: false, queued: false, spot_price }
+	}
+
+	const NONCE: Nonce = 5;
+
+	#[test]
+	fn order_amount_respects_max_spot_price_and_budget() {
+		assert_eq!(orders(20, 100).order_amount(&para(10)), Some(20));
+		assert_eq!(orders(20, 15).order_amount(&para(10)), Some(15));
+		assert_eq!(orders(20, 100).order_amount(&para(20)), Some(20));
+		assert_eq!(orders(20, 100).order_amount(&para(21)), None);
+		assert_eq!(orders(20, 5).order_amount(&para(10)), None);
+	}
+
+	#[test]
+	fn no_order_is_placed_while_the_para_is_served() {
+		let orders = orders(20, 100);
+		assert_eq!(orders.order_amount(&ParaState { scheduled: true, ..para(10) }), None);
+		assert_eq!(orders.order_amount(&ParaState { queued: true, ..para(10) }), None);
+	}
+
+	#[test]
+	fn no_order_is_placed_while_one_is_pending() {
+		let mut orders = orders(20, 100);
+		assert_eq!(orders.pending_status(10, NONCE), PendingStatus::Idle);
+		orders.placed(10, NONCE, 20);
+		assert_eq!(orders.order_amount(&para(10)), None);
+
+		// The nonce didn't change, so the order is still pending.
+		assert_eq!(orders.pending_status(11, NONCE), PendingStatus::Waiting);
+		assert_eq!(orders.order_amount(&para(10)), None);
+	}
+
+	#[test]
+	fn only_charged_orders_count_against_the_budget() {
+		let mut orders = orders(20, 100);
+
+		// The order was placed at the spot price.
+		orders.placed(10, NONCE, 20);
+		assert_eq!(
+			orders.pending_status(11, NONCE + 1),
+			PendingStatus::Included { placed_at: 10 }
+		);
+		orders.settle(10);
+		assert_eq!(orders.spent, 10);
+		assert_eq!(orders.pending_status(12, NONCE + 1), PendingStatus::Idle);
+
+		// The order was included, but failed.
+		orders.placed(12, NONCE + 1, 20);
+		assert_eq!(
+			orders.pending_status(13, NONCE + 2),
+			PendingStatus::Included { placed_at: 12 }
+		);
+		orders.settle(0);
+		assert_eq!(orders.spent, 10);
+
+		// The order expired without being included.
+		orders.placed(14, NONCE + 2, 20);
+		let expires_at = 14 + MORTALITY_PERIOD as BlockNumber;
+		assert_eq!(orders.pending_status(expires_at - 1, NONCE + 2), PendingStatus::Waiting);
+		assert_eq!(orders.pending_status(expires_at, NONCE + 2), PendingStatus::Expired);
+		orders.settle(0);
+		assert_eq!(orders.spent, 10);
+		assert_eq!(orders.order_amount(&para(10)), Some(20));
+	}
+
+	#[test]
+	fn charges_are_capped_at_the_max_amount() {
+		let mut orders = orders(20, 30);
+		orders.placed(10, NONCE, 20);
+		orders.settle(100);
+		assert_eq!(orders.spent, 20);
+
+		// Only 10 are left of the budget.
+		assert_eq!(orders.order_amount(&para(10)), Some(10));
+		assert_eq!(orders.order_amount(&para(11)), None);
+	}
+}
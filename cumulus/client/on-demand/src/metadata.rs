@@ -0,0 +1,277 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! What the task needs to know about the relay chain runtime, taken from its metadata.
+
+use crate::extrinsic::{Extension, PaymentMethod};
+use codec::{Compact, Decode, Encode};
+use cumulus_primitives_core::{
+	relay_chain::{AccountId, Balance},
+	ParaId,
+};
+use scale_info::TypeDef;
+use sp_core::twox_128;
+use subxt_metadata::Metadata;
+
+/// The version of the metadata requested from the relay chain runtime.
+pub(crate) const METADATA_VERSION: u32 = 15;
+
+/// The version of the extrinsic format the orders are encoded in.
+const EXTRINSIC_VERSION: u8 = 4;
+
+/// The name of the on-demand pallet in the relay chain runtime.
+const PALLET_NAME: &str = "OnDemandAssignmentProvider";
+
+/// The name of the event emitted when an order is placed.
+const ORDER_PLACED: &str = "OnDemandOrderPlaced";
+
+/// The fields of [`ORDER_PLACED`], in the order they are decoded in.
+const ORDER_PLACED_FIELDS: [&str; 3] = ["para_id", "spot_price", "ordered_by"];
+
+/// The calls, transaction extensions and events of the relay chain runtime used to place orders.
+pub(crate) struct RelayMetadata {
+	metadata: Metadata,
+	pallet_index: u8,
+	place_order_keep_alive: u8,
+	place_order_with_credits: u8,
+	order_placed: u8,
+	extensions: Vec<Extension>,
+	events_key: Vec<u8>,
+	/// The types of the fields of `frame_system::EventRecord`, with whether the field is the event.
+	event_record: Vec<(u32, bool)>,
+}
+
+impl RelayMetadata {
+	/// Decodes the result of the `Metadata_metadata_at_version` runtime API.
+	pub(crate) fn decode(encoded: &[u8]) -> Result<Self, String> {
+		let metadata = Option::<Vec<u8>>::decode(&mut &encoded[..])
+			.map_err(|error| format!("failed to decode the metadata: {error}"))?
+			.ok_or_else(|| format!("metadata version {METADATA_VERSION} is not supported"))?;
+		let metadata = Metadata::decode(&mut &metadata[..])
+			.map_err(|error| format!("failed to decode the metadata: {error}"))?;
+		Self::new(metadata)
+	}
+
+	fn new(metadata: Metadata) -> Result<Self, String> {
+		if !metadata.extrinsic().supported_versions().contains(&EXTRINSIC_VERSION) {
+			return Err(format!("extrinsic version {EXTRINSIC_VERSION} is not supported"))
+		}
+		let extensions = metadata
+			.extrinsic()
+			.transaction_extensions_by_version(0)
+			.ok_or("the runtime has no transaction extensions for signed extrinsics")?
+			.map(|extension| {
+				Extension::from_identifier(extension.identifier())
+					.or_else(|| {
+						(is_empty(&metadata, extension.extra_ty()) &&
+							is_empty(&metadata, extension.additional_ty()))
+						.then_some(Extension::Empty)
+					})
+					.ok_or_else(|| {
+						format!("unsupported transaction extension `{}`", extension.identifier())
+					})
+			})
+			.collect::<Result<Vec<_>, _>>()?;
+
+		let pallet = metadata
+			.pallet_by_name(PALLET_NAME)
+			.ok_or_else(|| format!("the runtime has no `{PALLET_NAME}` pallet"))?;
+		let call_index = |name| {
+			pallet
+				.call_variant_by_name(name)
+				.map(|variant| variant.index)
+				.ok_or_else(|| format!("the `{PALLET_NAME}` pallet has no `{name}` call"))
+		};
+		let order_placed = pallet
+			.event_variants()
+			.into_iter()
+			.flatten()
+			.find(|variant| variant.name == ORDER_PLACED)
+			.ok_or_else(|| format!("the `{PALLET_NAME}` pallet has no `{ORDER_PLACED}` event"))?;
+		if !order_placed
+			.fields
+			.iter()
+			.map(|field| field.name.as_deref())
+			.eq(ORDER_PLACED_FIELDS.map(Some))
+		{
+			return Err(format!("unexpected fields of the `{ORDER_PLACED}` event"))
+		}
+
+		let system = metadata.pallet_by_name("System").ok_or("the runtime has no `System` pallet")?;
+		let storage = system.storage().ok_or("the `System` pallet has no storage")?;
+		let events = storage.entry_by_name("Events").ok_or("the runtime stores no events")?;
+		let events_key = [twox_128(storage.prefix().as_bytes()), twox_128(b"Events")].concat();
+		let event_record = match metadata
+			.types()
+			.resolve(events.entry_type().value_ty())
+			.map(|ty| &ty.type_def)
+		{
+			Some(TypeDef::Sequence(records)) => metadata.types().resolve(records.type_param.id),
+			_ => None,
+		};
+		let Some(TypeDef::Composite(event_record)) = event_record.map(|ty| &ty.type_def) else {
+			return Err("unexpected type of the events".into())
+		};
+		let event_record = event_record
+			.fields
+			.iter()
+			.map(|field| (field.ty.id, field.name.as_deref() == Some("event")))
+			.collect();
+
+		Ok(Self {
+			pallet_index: pallet.index(),
+			place_order_keep_alive: call_index("place_order_keep_alive")?,
+			place_order_with_credits: call_index("place_order_with_credits")?,
+			order_placed: order_placed.index,
+			extensions,
+			events_key,
+			event_record,
+			metadata,
+		})
+	}
+
+	/// Returns the encoded call placing an order for `para_id`.
+	pub(crate) fn place_order_call(
+		&self,
+		payment: PaymentMethod,
+		max_amount: Balance,
+		para_id: ParaId,
+	) -> Vec<u8> {
+		let call_index = match payment {
+			PaymentMethod::Credits => self.place_order_with_credits,
+			PaymentMethod::Balance => self.place_order_keep_alive,
+		};
+		(self.pallet_index, call_index, max_amount, para_id).encode()
+	}
+
+	/// The transaction extensions of signed extrinsics, in the order they are encoded in.
+	pub(crate) fn extensions(&self) -> &[Extension] {
+		&self.extensions
+	}
+
+	/// The storage key of the events of a block.
+	pub(crate) fn events_key(&self) -> &[u8] {
+		&self.events_key
+	}
+
+	/// Returns the spot price of the order placed by `account` for `para_id`, given the encoded
+	/// events of a block, or `None` if the block placed no such order.
+	pub(crate) fn order_placed(
+		&self,
+		events: &[u8],
+		para_id: ParaId,
+		account: &AccountId,
+	) -> Result<Option<Balance>, String> {
+		let input = &mut &events[..];
+		let Compact(len) = Compact::<u32>::decode(input)
+			.map_err(|error| format!("failed to decode the events: {error}"))?;
+		for _ in 0..len {
+			for (ty, is_event) in &self.event_record {
+				let event = *input;
+				scale_value::scale::decode_as_type(input, *ty, self.metadata.types())
+					.map_err(|error| format!("failed to decode an event: {error}"))?;
+				if !is_event || event.get(..2) != Some(&[self.pallet_index, self.order_placed][..]) {
+					continue
+				}
+				let (ordered_for, spot_price, ordered_by) =
+					<(ParaId, Balance, AccountId)>::decode(&mut &event[2..]).map_err(|error| {
+						format!("failed to decode the `{ORDER_PLACED}` event: {error}")
+					})?;
+				if ordered_for == para_id && ordered_by == *account {
+					return Ok(Some(spot_price))
+				}
+			}
+		}
+		Ok(None)
+	}
+}
+
+/// Returns whether values of type `ty` are encoded to nothing.
+fn is_empty(metadata: &Metadata, ty: u32) -> bool {
+	match metadata.types().resolve(ty).map(|ty| &ty.type_def) {
+		Some(TypeDef::Composite(composite)) =>
+			composite.fields.iter().all(|field| is_empty(metadata, field.ty.id)),
+		Some(TypeDef::Tuple(tuple)) => tuple.fields.iter().all(|field| is_empty(metadata, field.id)),
+		Some(TypeDef::Array(array)) => array.len == 0 || is_empty(metadata, array.type_param.id),
+		_ => false,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_runtime_parachains::on_demand;
+	use westend_runtime::{Runtime, RuntimeEvent};
+
+	fn westend() -> RelayMetadata {
+		RelayMetadata::decode(&Runtime::metadata_at_version(METADATA_VERSION).encode()).unwrap()
+	}
+
+	fn order_placed(para_id: u32, spot_price: Balance, ordered_by: AccountId) -> RuntimeEvent {
+		RuntimeEvent::OnDemandAssignmentProvider(on_demand::Event::OnDemandOrderPlaced {
+			para_id: para_id.into(),
+			spot_price,
+			ordered_by,
+		})
+	}
+
+	#[test]
+	fn westend_extensions_are_supported() {
+		use Extension::*;
+
+		assert_eq!(
+			westend().extensions(),
+			[
+				Empty,
+				Empty,
+				SpecVersion,
+				TxVersion,
+				Genesis,
+				Mortality,
+				Nonce,
+				Empty,
+				Tip,
+				MetadataHash,
+				Empty
+			]
+		);
+	}
+
+	#[test]
+	fn order_placed_is_found_in_events() {
+		let metadata = westend();
+		let account = AccountId::new([1; 32]);
+		let other = AccountId::new([2; 32]);
+
+		sp_io::TestExternalities::default().execute_with(|| {
+			// Events are not deposited in the genesis block.
+			frame_system::Pallet::<Runtime>::set_block_number(1);
+			let events = || sp_io::storage::get(metadata.events_key()).unwrap_or_default();
+			assert_eq!(metadata.order_placed(&events(), 2000.into(), &account), Ok(None));
+
+			frame_system::Pallet::<Runtime>::deposit_event(RuntimeEvent::OnDemandAssignmentProvider(
+				on_demand::Event::SpotPriceSet { spot_price: 5 },
+			));
+			frame_system::Pallet::<Runtime>::deposit_event(order_placed(2001, 10, account.clone()));
+			frame_system::Pallet::<Runtime>::deposit_event(order_placed(2000, 20, other));
+			assert_eq!(metadata.order_placed(&events(), 2000.into(), &account), Ok(None));
+
+			frame_system::Pallet::<Runtime>::deposit_event(order_placed(2000, 30, account.clone()));
+			assert_eq!(metadata.order_placed(&events(), 2000.into(), &account), Ok(Some(30)));
+		});
+	}
+}
@@ -0,0 +1,197 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Attribution of the storage proof to the extrinsics of a block, by replaying the block.
+
+use crate::{Error, Labels};
+use codec::{Compact, Decode, Encode};
+use cumulus_primitives_core::ParachainBlockData;
+use sp_core::traits::{CallContext, CodeExecutor, RuntimeCode};
+use sp_runtime::{
+	traits::{Block as BlockT, HashingFor, Header as _},
+	ApplyExtrinsicResult,
+};
+use sp_state_machine::{TestExternalities, TrieBackendBuilder};
+use sp_trie::{proof_size_extension::ProofSizeExt, recorder::Recorder, LayoutV1, PrefixedMemoryDB};
+
+/// The bits of the first byte of an extrinsic telling whether it is bare, signed or general.
+const EXTRINSIC_TYPE_MASK: u8 = 0b1100_0000;
+
+/// The storage proof recorded by each step of the execution of a block.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockComposition<Hash> {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The proof recorded by `Core_initialize_block`.
+	pub initialize: u64,
+	/// The proof recorded by each extrinsic.
+	pub extrinsics: Vec<ExtrinsicComposition>,
+	/// The proof recorded by `BlockBuilder_finalize_block`, including the storage root.
+	pub finalize: u64,
+}
+
+/// The storage proof recorded by an extrinsic.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExtrinsicComposition {
+	/// The index of the extrinsic in its block.
+	pub index: u32,
+	/// The pallet and call index of the extrinsic, if it could be decoded.
+	///
+	/// The call of signed and general extrinsics comes after transaction extensions whose
+	/// encoding depends on the runtime, so it is only decoded with the metadata of the runtime.
+	pub call: Option<(u8, u8)>,
+	/// The encoded size of the extrinsic.
+	pub encoded_size: u64,
+	/// The estimated size of the storage proof recorded when applying the extrinsic.
+	pub proof_size: u64,
+	/// Whether the call of the extrinsic was dispatched successfully.
+	pub success: bool,
+}
+
+/// Replays the blocks of `block_data` on top of the state proven by its storage proof, recording
+/// the proof accessed by each step.
+///
+/// The state with root `parent_state_root`, the one of the parent of the first block, is only
+/// backed by the nodes of the proof. The changes of each block are applied before replaying the
+/// next one, like `validate_block` does.
+///
+/// The executor needs to provide the host functions of the runtime, including the storage proof
+/// size host function used by the storage weight reclaim. The calls of the extrinsics are decoded
+/// with the metadata of `labels`.
+pub fn replay_blocks<B: BlockT, E: CodeExecutor>(
+	executor: &E,
+	runtime_code: &RuntimeCode,
+	block_data: &ParachainBlockData<B>,
+	parent_state_root: B::Hash,
+	labels: &Labels,
+) -> Result<Vec<BlockComposition<B::Hash>>, Error> {
+	let mut db = PrefixedMemoryDB::<HashingFor<B>>::default();
+	let root = sp_trie::decode_compact::<LayoutV1<HashingFor<B>>, _, _>(
+		&mut db,
+		block_data.proof().iter_compact_encoded_nodes(),
+		Some(&parent_state_root),
+	)
+	.map_err(|error| Error::Proof(format!("{error:?}")))?;
+
+	let recorder = Recorder::<HashingFor<B>>::default();
+	let mut ext = TestExternalities::<HashingFor<B>>::default();
+	ext.backend = TrieBackendBuilder::new(db, root).with_recorder(recorder.clone()).build();
+	ext.register_extension(ProofSizeExt::new(recorder.clone()));
+
+	let call = |ext: &mut TestExternalities<HashingFor<B>>, method: &'static str, data: &[u8]| {
+		call_recorded::<B, E>(executor, runtime_code, ext, &recorder, method, data)
+	};
+
+	let mut compositions = Vec::with_capacity(block_data.blocks().len());
+	for block in block_data.blocks() {
+		let (header, extrinsics) = block.clone().deconstruct();
+		let (_, initialize) = call(&mut ext, "Core_initialize_block", &header.encode())?;
+
+		let extrinsics = extrinsics
+			.iter()
+			.enumerate()
+			.map(|(index, extrinsic)| {
+				let index = index as u32;
+				let encoded = extrinsic.encode();
+				let (result, proof_size) =
+					call(&mut ext, "BlockBuilder_apply_extrinsic", &encoded)?;
+				let success = match ApplyExtrinsicResult::decode(&mut &result[..])? {
+					Ok(outcome) => outcome.is_ok(),
+					Err(error) =>
+						return Err(Error::InvalidExtrinsic { index, error: format!("{error:?}") }),
+				};
+
+				Ok(ExtrinsicComposition {
+					index,
+					call: labels.extrinsic_call(&encoded),
+					encoded_size: encoded.len() as u64,
+					proof_size,
+					success,
+				})
+			})
+			.collect::<Result<Vec<_>, Error>>()?;
+
+		let (_, mut finalize) = call(&mut ext, "BlockBuilder_finalize_block", &[])?;
+
+		// Apply the changes of the block, which records the nodes needed to compute the new
+		// storage root not already recorded by the runtime.
+		let before = recorder.estimate_encoded_size();
+		ext.commit_all()
+			.map_err(|error| Error::Execution { method: "commit_all", error })?;
+		let root = *ext.backend.root();
+		let storage = core::mem::take(&mut ext.backend).into_storage();
+		ext.backend =
+			TrieBackendBuilder::new(storage, root).with_recorder(recorder.clone()).build();
+		finalize += recorder.estimate_encoded_size().saturating_sub(before) as u64;
+
+		compositions.push(BlockComposition {
+			hash: header.hash(),
+			initialize,
+			extrinsics,
+			finalize,
+		});
+	}
+
+	Ok(compositions)
+}
+
+/// Calls `method` of the runtime, returning its result and the size of the proof it recorded.
+fn call_recorded<B: BlockT, E: CodeExecutor>(
+	executor: &E,
+	runtime_code: &RuntimeCode,
+	ext: &mut TestExternalities<HashingFor<B>>,
+	recorder: &Recorder<HashingFor<B>>,
+	method: &'static str,
+	data: &[u8],
+) -> Result<(Vec<u8>, u64), Error> {
+	let before = recorder.estimate_encoded_size();
+	let result = executor
+		.call(&mut ext.ext(), runtime_code, method, data, CallContext::Onchain)
+		.0
+		.map_err(|error| Error::Execution { method, error: error.to_string() })?;
+
+	Ok((result, recorder.estimate_encoded_size().saturating_sub(before) as u64))
+}
+
+/// Returns the pallet and call index of an encoded extrinsic, if it is bare.
+pub(crate) fn bare_call_index(mut encoded: &[u8]) -> Option<(u8, u8)> {
+	Compact::<u32>::decode(&mut encoded).ok()?;
+	match encoded {
+		[version, pallet, call, ..] if version & EXTRINSIC_TYPE_MASK == 0 => Some((*pallet, *call)),
+		_ => None,
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn bare_call_index_works() {
+		// A bare extrinsic of version 4 calling the call 3 of the pallet 1.
+		let bare = (vec![4u8, 1, 3, 42]).encode();
+		assert_eq!(bare_call_index(&bare), Some((1, 3)));
+
+		let signed = (vec![0b1000_0100u8, 1, 3, 42]).encode();
+		assert_eq!(bare_call_index(&signed), None);
+
+		let general = (vec![0b0100_0101u8, 0, 1, 3]).encode();
+		assert_eq!(bare_call_index(&general), None);
+
+		assert_eq!(bare_call_index(&[4u8, 1]), None);
+	}
+}
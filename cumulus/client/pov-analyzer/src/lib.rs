@@ -0,0 +1,166 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Analyzes what the `PoV` of parachain blocks is made of.
+//!
+//! The storage proof of a [`ParachainBlockData`] is attributed in two ways:
+//!
+//! - [`StorageComposition`] attributes each node of the proof to the storage item found at its key:
+//!   the pallet and storage item whose hashed prefixes start the key, a well-known key or a child
+//!   trie. [`Labels`] names them after the runtime metadata.
+//! - [`replay_blocks`] executes the blocks on top of the state backed by the proof, recording the
+//!   proof accessed by each of their extrinsics.
+//!
+//! Collators log the storage composition of the blocks they build under the [`LOG_TARGET`] at the
+//! debug level and, given [`Metrics`], observe the proof size of each pallet in a histogram.
+
+mod extrinsics;
+mod metadata;
+mod storage;
+
+pub use extrinsics::{replay_blocks, BlockComposition, ExtrinsicComposition};
+pub use metadata::Labels;
+pub use storage::{ProofItem, StorageComposition, Usage};
+
+use cumulus_primitives_core::ParachainBlockData;
+use prometheus_endpoint::{
+	exponential_buckets, register, HistogramOpts, HistogramVec, PrometheusError, Registry,
+};
+use sp_runtime::traits::{Block as BlockT, HashingFor};
+use std::collections::BTreeMap;
+
+/// The log target of the `PoV` composition logged by collators.
+pub const LOG_TARGET: &str = "pov-analyzer";
+
+/// The number of storage items logged by [`log_storage_composition`].
+const LOGGED_ITEMS: usize = 10;
+
+/// Errors that can occur while analyzing a `PoV`.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	#[error("Invalid storage proof: {0}")]
+	Proof(String),
+	#[error("Failed to call `{method}`: {error}")]
+	Execution { method: &'static str, error: String },
+	#[error("Extrinsic {index} is invalid: {error}")]
+	InvalidExtrinsic { index: u32, error: String },
+	#[error("Invalid metadata: {0}")]
+	Metadata(String),
+	#[error(transparent)]
+	Codec(#[from] codec::Error),
+}
+
+/// Prometheus metrics of the storage proofs of the blocks built by a collator.
+#[derive(Clone)]
+pub struct Metrics {
+	pallet_proof_size: HistogramVec,
+}
+
+impl Metrics {
+	/// Registers the metrics in `registry`.
+	pub fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			pallet_proof_size: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"cumulus_pov_storage_proof_bytes",
+						"Bytes of the storage proof of a block attributed to each pallet",
+					)
+					.buckets(exponential_buckets(256.0, 4.0, 9)?),
+					&["pallet"],
+				)?,
+				registry,
+			)?,
+		})
+	}
+
+	/// Observes the bytes `composition` attributes to each pallet.
+	///
+	/// Pallets are named after the hex of their hashed prefix, as collators don't decode the
+	/// runtime metadata. Nodes not attributed to a pallet are observed as `<shared>`,
+	/// `<well-known>` and `<child tries>`.
+	fn observe(&self, composition: &StorageComposition, labels: &Labels) {
+		let mut pallets = BTreeMap::<String, u64>::new();
+		for (item, usage) in &composition.items {
+			let pallet = match item {
+				ProofItem::Shared => "<shared>".into(),
+				ProofItem::WellKnown(_) => "<well-known>".into(),
+				ProofItem::ChildTrie(_) => "<child tries>".into(),
+				ProofItem::Pallet(pallet) | ProofItem::Storage { pallet, .. } =>
+					labels.pallet(pallet),
+			};
+			*pallets.entry(pallet).or_default() += usage.bytes;
+		}
+		for (pallet, bytes) in pallets {
+			self.pallet_proof_size.with_label_values(&[&pallet]).observe(bytes as f64);
+		}
+	}
+}
+
+/// Logs the largest storage items of the proof of `block_data`, if enabled, and observes its
+/// composition in `metrics`.
+///
+/// `parent_state_root` is the state root of the parent of the first block.
+pub fn log_storage_composition<B: BlockT>(
+	block_data: &ParachainBlockData<B>,
+	parent_state_root: B::Hash,
+	metrics: Option<&Metrics>,
+) {
+	let log = tracing::enabled!(target: LOG_TARGET, tracing::Level::DEBUG);
+	if !log && metrics.is_none() {
+		return
+	}
+
+	let composition = match StorageComposition::from_compact_proof::<HashingFor<B>>(
+		block_data.proof(),
+		parent_state_root,
+	) {
+		Ok(composition) => composition,
+		Err(error) => {
+			tracing::debug!(target: LOG_TARGET, %error, "Failed to analyze the storage proof");
+			return
+		},
+	};
+
+	let labels = Labels::default();
+	if let Some(metrics) = metrics {
+		metrics.observe(&composition, &labels);
+	}
+	if !log {
+		return
+	}
+
+	let block = block_data.blocks().first().map(|block| block.hash());
+	tracing::debug!(
+		target: LOG_TARGET,
+		?block,
+		compact_kb = composition.compact_size as f64 / 1024f64,
+		nodes = composition.total.nodes,
+		nodes_kb = composition.total.bytes as f64 / 1024f64,
+		"Storage proof composition",
+	);
+	for (item, usage) in composition.sorted_items().into_iter().take(LOGGED_ITEMS) {
+		tracing::debug!(
+			target: LOG_TARGET,
+			?block,
+			item = %labels.item(item),
+			nodes = usage.nodes,
+			kb = usage.bytes as f64 / 1024f64,
+			"Storage proof item",
+		);
+	}
+}
@@ -0,0 +1,187 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Names of storage items and calls, taken from the runtime metadata.
+
+use crate::{extrinsics::bare_call_index, Error, ProofItem};
+use codec::Decode;
+use frame_decode::extrinsics::decode_extrinsic_current;
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use scale_info::TypeDef;
+use sp_core::{hexdisplay::HexDisplay, twox_128};
+use std::{collections::HashMap, sync::Arc};
+
+/// Names of the storage items and calls of a runtime.
+///
+/// Without metadata, things are named after their hashed prefixes and indices, and only the calls
+/// of bare extrinsics are decoded.
+#[derive(Clone, Debug, Default)]
+pub struct Labels {
+	pallets: HashMap<[u8; 16], String>,
+	items: HashMap<([u8; 16], [u8; 16]), String>,
+	calls: HashMap<(u8, u8), String>,
+	metadata: Option<Arc<RuntimeMetadata>>,
+}
+
+/// Fills `$labels` from the pallets of metadata of any version from 14 on, which only differ in
+/// their types.
+macro_rules! collect_labels {
+	($labels:ident, $metadata:ident) => {
+		for pallet in &$metadata.pallets {
+			if let Some(storage) = &pallet.storage {
+				let prefix = twox_128(storage.prefix.as_bytes());
+				$labels.pallets.insert(prefix, pallet.name.clone());
+				for entry in &storage.entries {
+					let item = twox_128(entry.name.as_bytes());
+					$labels
+						.items
+						.insert((prefix, item), format!("{}::{}", pallet.name, entry.name));
+				}
+			}
+
+			let calls =
+				pallet.calls.as_ref().and_then(|calls| $metadata.types.resolve(calls.ty.id));
+			if let Some(TypeDef::Variant(calls)) = calls.map(|calls| &calls.type_def) {
+				for call in &calls.variants {
+					$labels.calls.insert(
+						(pallet.index, call.index),
+						format!("{}::{}", pallet.name, call.name),
+					);
+				}
+			}
+		}
+	};
+}
+
+impl Labels {
+	/// Takes the names from the SCALE-encoded [`RuntimeMetadataPrefixed`].
+	pub fn from_metadata(encoded: &[u8]) -> Result<Self, Error> {
+		let metadata = RuntimeMetadataPrefixed::decode(&mut &encoded[..])?.1;
+		let mut labels = Self::default();
+		match &metadata {
+			RuntimeMetadata::V14(metadata) => collect_labels!(labels, metadata),
+			RuntimeMetadata::V15(metadata) => collect_labels!(labels, metadata),
+			RuntimeMetadata::V16(metadata) => collect_labels!(labels, metadata),
+			other =>
+				return Err(Error::Metadata(format!(
+					"Unsupported metadata version {}",
+					other.version()
+				))),
+		}
+
+		labels.metadata = Some(Arc::new(metadata));
+		Ok(labels)
+	}
+
+	/// Returns the pallet and call index of an encoded extrinsic.
+	///
+	/// Returns `None` if the extrinsic can't be decoded, which without metadata is the case for
+	/// all but bare extrinsics.
+	pub fn extrinsic_call(&self, encoded: &[u8]) -> Option<(u8, u8)> {
+		let input = &mut &encoded[..];
+		let extrinsic = match self.metadata.as_deref() {
+			Some(RuntimeMetadata::V14(metadata)) => decode_extrinsic_current(input, metadata),
+			Some(RuntimeMetadata::V15(metadata)) => decode_extrinsic_current(input, metadata),
+			Some(RuntimeMetadata::V16(metadata)) => decode_extrinsic_current(input, metadata),
+			_ => return bare_call_index(encoded),
+		};
+		extrinsic.ok().map(|extrinsic| (extrinsic.pallet_index(), extrinsic.call_index()))
+	}
+
+	/// Returns the name of what proof nodes are attributed to.
+	pub fn item(&self, item: &ProofItem) -> String {
+		match item {
+			ProofItem::Shared => "<shared>".into(),
+			ProofItem::WellKnown(key) => String::from_utf8_lossy(key).into_owned(),
+			ProofItem::Pallet(pallet) => format!("{} <shared>", self.pallet(pallet)),
+			ProofItem::Storage { pallet, item } =>
+				self.items.get(&(*pallet, *item)).cloned().unwrap_or_else(|| {
+					format!("{}::0x{}", self.pallet(pallet), HexDisplay::from(item))
+				}),
+			ProofItem::ChildTrie(storage_key) =>
+				format!("child trie 0x{}", HexDisplay::from(storage_key)),
+		}
+	}
+
+	/// Returns the name of the call with the given pallet and call index.
+	pub fn call(&self, pallet: u8, call: u8) -> String {
+		self.calls
+			.get(&(pallet, call))
+			.cloned()
+			.unwrap_or_else(|| format!("{pallet}::{call}"))
+	}
+
+	/// Returns the name of the pallet with the given hashed prefix.
+	pub(crate) fn pallet(&self, pallet: &[u8; 16]) -> String {
+		self.pallets
+			.get(pallet)
+			.cloned()
+			.unwrap_or_else(|| format!("0x{}", HexDisplay::from(pallet)))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use cumulus_test_runtime::{
+		BalancesCall, Runtime, RuntimeCall, TxExtension, UncheckedExtrinsic,
+	};
+	use sp_core::sr25519;
+	use sp_runtime::{generic::Era, MultiAddress, MultiSignature};
+
+	fn call() -> RuntimeCall {
+		RuntimeCall::Balances(BalancesCall::transfer_keep_alive {
+			dest: MultiAddress::Id([2; 32].into()),
+			value: 100,
+		})
+	}
+
+	#[test]
+	fn calls_of_signed_extrinsics_are_decoded_with_metadata() {
+		let tx_ext: TxExtension = (
+			frame_system::AuthorizeCall::<Runtime>::new(),
+			frame_system::CheckNonZeroSender::<Runtime>::new(),
+			frame_system::CheckSpecVersion::<Runtime>::new(),
+			frame_system::CheckGenesis::<Runtime>::new(),
+			frame_system::CheckEra::<Runtime>::from(Era::mortal(64, 10)),
+			frame_system::CheckNonce::<Runtime>::from(3),
+			frame_system::CheckWeight::<Runtime>::new(),
+			pallet_transaction_payment::ChargeTransactionPayment::<Runtime>::from(0),
+		)
+			.into();
+		let signed = UncheckedExtrinsic::new_signed(
+			call(),
+			MultiAddress::Id([1; 32].into()),
+			MultiSignature::Sr25519(sr25519::Signature::from_raw([0; 64])),
+			tx_ext,
+		)
+		.encode();
+		let bare = UncheckedExtrinsic::new_bare(call()).encode();
+		let call_index = call().encode();
+		let call_index = Some((call_index[0], call_index[1]));
+
+		let labels = Labels::from_metadata(&Runtime::metadata().encode()).unwrap();
+		assert_eq!(labels.extrinsic_call(&signed), call_index);
+		assert_eq!(labels.extrinsic_call(&bare), call_index);
+		assert_eq!(labels.call(call_index.unwrap().0, call_index.unwrap().1), "Balances::transfer_keep_alive");
+
+		// Without metadata, only bare extrinsics are decoded.
+		assert_eq!(Labels::default().extrinsic_call(&signed), None);
+		assert_eq!(Labels::default().extrinsic_call(&bare), call_index);
+	}
+}
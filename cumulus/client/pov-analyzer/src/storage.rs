@@ -0,0 +1,319 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Attribution of the nodes of a storage proof to the storage items they prove.
+
+use crate::Error;
+use codec::Encode;
+use sp_core::{storage::well_known_keys::DEFAULT_CHILD_STORAGE_KEY_PREFIX, Hasher};
+use sp_trie::{CompactProof, HashDBT, MemoryDB, NodeCodec, EMPTY_PREFIX};
+use std::collections::{BTreeMap, HashSet};
+use trie_db::{
+	node::{Node, NodeHandle, Value},
+	NodeCodec as _,
+};
+
+/// The length in nibbles of the `twox_128` hash of a pallet prefix or of a storage item name.
+const PREFIX_NIBBLES: usize = 32;
+
+/// What the nodes of a storage proof are attributed to.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ProofItem {
+	/// Nodes close to the root, shared by the keys of several pallets.
+	Shared,
+	/// Nodes of a well-known key, like `:code`, or shared by the well-known keys with this prefix.
+	WellKnown(Vec<u8>),
+	/// Nodes shared by the storage items of the pallet with this `twox_128` hashed prefix.
+	Pallet([u8; 16]),
+	/// Nodes of a storage item, identified by the `twox_128` hashes of its pallet prefix and name.
+	Storage { pallet: [u8; 16], item: [u8; 16] },
+	/// Nodes of the child trie with this storage key, including the node storing its root.
+	ChildTrie(Vec<u8>),
+}
+
+/// The nodes attributed to something and their encoded size.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Usage {
+	/// The number of nodes.
+	pub nodes: u32,
+	/// The encoded size of the nodes.
+	pub bytes: u64,
+}
+
+impl Usage {
+	fn add(&mut self, bytes: usize) {
+		self.nodes += 1;
+		self.bytes += bytes as u64;
+	}
+}
+
+/// The composition of a storage proof.
+///
+/// Sizes are those of the nodes as found in a full storage proof. The compact proof included in
+/// the `PoV` leaves out the hashes of the child nodes it contains, so it is smaller than the sum of
+/// the sizes of its nodes.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StorageComposition {
+	/// The encoded size of the compact proof.
+	pub compact_size: u64,
+	/// All the nodes of the proof.
+	pub total: Usage,
+	/// The nodes of the proof, by what they are attributed to.
+	pub items: BTreeMap<ProofItem, Usage>,
+}
+
+impl StorageComposition {
+	/// Attributes the nodes of `proof` of the state with the given `root`.
+	pub fn from_compact_proof<H: Hasher>(
+		proof: &CompactProof,
+		root: H::Out,
+	) -> Result<Self, Error> {
+		let (db, root) = proof
+			.to_memory_db::<H>(Some(&root))
+			.map_err(|error| Error::Proof(format!("{error:?}")))?;
+
+		let mut walker = Walker::<H> {
+			db: &db,
+			visited: HashSet::new(),
+			composition: Self { compact_size: proof.encoded_size() as u64, ..Default::default() },
+		};
+		walker.walk(&root, Vec::new(), None)?;
+
+		Ok(walker.composition)
+	}
+
+	/// Returns what the nodes are attributed to, with the largest first.
+	pub fn sorted_items(&self) -> Vec<(&ProofItem, Usage)> {
+		let mut items = self.items.iter().map(|(item, usage)| (item, *usage)).collect::<Vec<_>>();
+		items.sort_by_key(|(_, usage)| core::cmp::Reverse(usage.bytes));
+		items
+	}
+}
+
+/// Walks a trie, attributing each node to the key it is found at.
+struct Walker<'a, H: Hasher> {
+	db: &'a MemoryDB<H>,
+	visited: HashSet<H::Out>,
+	composition: StorageComposition,
+}
+
+impl<H: Hasher> Walker<'_, H> {
+	/// Walks the node with the given hash, found at `path`.
+	///
+	/// Nodes which are not part of the proof, or which were already visited, are skipped.
+	fn walk(
+		&mut self,
+		hash: &H::Out,
+		path: Vec<u8>,
+		child_trie: Option<&[u8]>,
+	) -> Result<(), Error> {
+		if !self.visited.insert(*hash) {
+			return Ok(())
+		}
+
+		match self.db.get(hash, EMPTY_PREFIX) {
+			Some(data) => self.node(&data, path, child_trie, Some(data.len())),
+			None => Ok(()),
+		}
+	}
+
+	/// Walks the node encoded as `data`, found at `path`.
+	///
+	/// `size` is `None` for inline nodes, which are part of the encoding of their parent.
+	fn node(
+		&mut self,
+		data: &[u8],
+		mut path: Vec<u8>,
+		child_trie: Option<&[u8]>,
+		size: Option<usize>,
+	) -> Result<(), Error> {
+		let node = NodeCodec::<H>::decode(data).map_err(|error| Error::Proof(error.to_string()))?;
+		let (partial, children, value) = match node {
+			Node::Empty => (None, None, None),
+			Node::Leaf(partial, value) => (Some(partial), None, Some(value)),
+			Node::Branch(children, value) => (None, Some(children), value),
+			Node::NibbledBranch(partial, children, value) => (Some(partial), Some(children), value),
+			Node::Extension(..) =>
+				return Err(Error::Proof("Extension nodes are not supported".into())),
+		};
+
+		if let Some(partial) = partial {
+			path.extend((0..partial.len()).map(|i| partial.at(i)));
+		}
+
+		let item = item(&path, child_trie, value.is_some());
+		let usage = self.composition.items.entry(item).or_default();
+		if let Some(size) = size {
+			usage.add(size);
+			self.composition.total.add(size);
+		}
+
+		let value = match value {
+			Some(Value::Inline(value)) => Some(value.to_vec()),
+			Some(Value::Node(hash)) => decode_hash::<H>(hash).and_then(|hash| {
+				let value = self.db.get(&hash, EMPTY_PREFIX)?;
+				if self.visited.insert(hash) {
+					usage.add(value.len());
+					self.composition.total.add(value.len());
+				}
+				Some(value)
+			}),
+			None => None,
+		};
+
+		if let (None, Some(value)) = (child_trie, value) {
+			let key = nibbles_to_bytes(&path);
+			if let (Some(storage_key), Some(root)) =
+				(key.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX), decode_hash::<H>(&value))
+			{
+				self.walk(&root, Vec::new(), Some(storage_key))?;
+			}
+		}
+
+		for (nibble, child) in children.into_iter().flatten().enumerate() {
+			let mut path = path.clone();
+			path.push(nibble as u8);
+
+			match child {
+				Some(NodeHandle::Hash(hash)) =>
+					if let Some(hash) = decode_hash::<H>(hash) {
+						self.walk(&hash, path, child_trie)?;
+					},
+				Some(NodeHandle::Inline(data)) => self.node(data, path, child_trie, None)?,
+				None => {},
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Returns what a node found at `path` is attributed to.
+///
+/// `has_value` tells whether the node stores the value of the key at `path`.
+fn item(path: &[u8], child_trie: Option<&[u8]>, has_value: bool) -> ProofItem {
+	if let Some(storage_key) = child_trie {
+		return ProofItem::ChildTrie(storage_key.to_vec())
+	}
+
+	let key = nibbles_to_bytes(path);
+	if key.first() == Some(&b':') {
+		return match key.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+			Some(storage_key) if has_value => ProofItem::ChildTrie(storage_key.to_vec()),
+			_ => ProofItem::WellKnown(key),
+		}
+	}
+
+	let prefix = |range: core::ops::Range<usize>| {
+		key[range].try_into().expect("The range is 16 bytes long; qed")
+	};
+	if path.len() >= 2 * PREFIX_NIBBLES {
+		ProofItem::Storage { pallet: prefix(0..16), item: prefix(16..32) }
+	} else if path.len() >= PREFIX_NIBBLES {
+		ProofItem::Pallet(prefix(0..16))
+	} else {
+		ProofItem::Shared
+	}
+}
+
+/// Converts `nibbles` to bytes, dropping the last nibble if there is an odd number of them.
+fn nibbles_to_bytes(nibbles: &[u8]) -> Vec<u8> {
+	nibbles.chunks_exact(2).map(|pair| (pair[0] << 4) | pair[1]).collect()
+}
+
+fn decode_hash<H: Hasher>(data: &[u8]) -> Option<H::Out> {
+	let mut hash = H::Out::default();
+	if data.len() != hash.as_ref().len() {
+		return None
+	}
+
+	hash.as_mut().copy_from_slice(data);
+	Some(hash)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::{
+		storage::{ChildInfo, StateVersion},
+		twox_128, Blake2Hasher,
+	};
+	use sp_state_machine::{
+		new_in_mem, Backend, ChildStorageCollection, StorageCollection, TrieBackendBuilder,
+	};
+	use sp_trie::recorder::Recorder;
+
+	fn storage_key(pallet: &[u8], item: &[u8], map_key: &[u8]) -> Vec<u8> {
+		[&twox_128(pallet)[..], &twox_128(item), map_key].concat()
+	}
+
+	#[test]
+	fn nodes_are_attributed_to_storage_items() {
+		let child = ChildInfo::new_default(b"child");
+		let top: StorageCollection = (0u8..32)
+			.map(|i| (storage_key(b"System", b"Account", &[i; 32]), Some(vec![i; 100])))
+			.chain([
+				(storage_key(b"System", b"Number", &[]), Some(vec![1; 4])),
+				(storage_key(b"Timestamp", b"Now", &[]), Some(vec![2; 8])),
+				(b":code".to_vec(), Some(vec![3; 1000])),
+			])
+			.collect();
+		let children: ChildStorageCollection = vec![(
+			child.storage_key().to_vec(),
+			(0u8..4).map(|i| (vec![i], Some(vec![i; 64]))).collect(),
+		)];
+
+		let mut backend = new_in_mem::<Blake2Hasher>();
+		backend.insert(
+			[(None, top)]
+				.into_iter()
+				.chain(children.into_iter().map(|(_, c)| (Some(child.clone()), c))),
+			StateVersion::V1,
+		);
+		let root = *backend.root();
+
+		let recorder = Recorder::<Blake2Hasher>::default();
+		let proving = TrieBackendBuilder::wrap(&backend).with_recorder(recorder.clone()).build();
+		proving.storage(&storage_key(b"System", b"Account", &[7; 32])).unwrap().unwrap();
+		proving.storage(&storage_key(b"Timestamp", b"Now", &[])).unwrap().unwrap();
+		proving.storage(b":code").unwrap().unwrap();
+		proving.child_storage(&child, &[2]).unwrap().unwrap();
+		let proof = recorder.drain_storage_proof();
+		let full_size = proof.iter_nodes().map(|node| node.len() as u64).sum::<u64>();
+		let compact = proof.into_compact_proof::<Blake2Hasher>(root).unwrap();
+
+		let composition =
+			StorageComposition::from_compact_proof::<Blake2Hasher>(&compact, root).unwrap();
+
+		assert_eq!(composition.total.bytes, full_size);
+		assert_eq!(composition.compact_size, compact.encoded_size() as u64);
+		assert_eq!(composition.items.values().map(|u| u.bytes).sum::<u64>(), full_size);
+
+		let account =
+			ProofItem::Storage { pallet: twox_128(b"System"), item: twox_128(b"Account") };
+		let now = ProofItem::Storage { pallet: twox_128(b"Timestamp"), item: twox_128(b"Now") };
+		assert!(composition.items[&account].nodes >= 2);
+		assert_eq!(composition.items[&now].nodes, 1);
+		// The leaf and its value, which is too large to be inlined.
+		assert_eq!(composition.items[&ProofItem::WellKnown(b":code".to_vec())].nodes, 2);
+		assert!(composition.items[&ProofItem::WellKnown(b":code".to_vec())].bytes > 1000);
+		assert!(composition.items[&ProofItem::ChildTrie(b"child".to_vec())].nodes >= 2);
+		assert!(composition.items.contains_key(&ProofItem::Shared));
+
+		assert_eq!(composition.sorted_items()[0].0, &ProofItem::WellKnown(b":code".to_vec()));
+	}
+}
@@ -23,6 +23,7 @@ use std::{
 };
 
 use async_trait::async_trait;
+use codec::Decode;
 use cumulus_client_bootnodes::bootnode_request_response_config;
 use cumulus_primitives_core::{
 	relay_chain::{
@@ -51,6 +52,8 @@ use sc_network::{
 	service::traits::{NetworkBackend, NetworkService},
 };
 use sc_telemetry::TelemetryWorkerHandle;
+use sc_transaction_pool::TransactionPoolHandle;
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
 use sp_api::{CallApiAt, CallApiAtParams, CallContext, ProvideRuntimeApi};
 use sp_consensus::SyncOracle;
 use sp_core::Pair;
@@ -59,6 +62,9 @@ use sp_state_machine::{Backend as StateBackend, StorageValue};
 /// The timeout in seconds after that the waiting for a block should be aborted.
 const TIMEOUT_IN_SECONDS: u64 = 6;
 
+/// The transaction pool of the in-process relay chain node.
+pub type FullPool = TransactionPoolHandle<PBlock, FullClient>;
+
 /// Provides an implementation of the [`RelayChainInterface`] using a local in-process relay chain
 /// node.
 #[derive(Clone)]
@@ -67,6 +73,7 @@ pub struct RelayChainInProcessInterface {
 	backend: Arc<FullBackend>,
 	sync_oracle: Arc<dyn SyncOracle + Send + Sync>,
 	overseer_handle: Handle,
+	transaction_pool: Option<Arc<FullPool>>,
 }
 
 impl RelayChainInProcessInterface {
@@ -77,7 +84,14 @@ impl RelayChainInProcessInterface {
 		sync_oracle: Arc<dyn SyncOracle + Send + Sync>,
 		overseer_handle: Handle,
 	) -> Self {
-		Self { full_client, backend, sync_oracle, overseer_handle }
+		Self { full_client, backend, sync_oracle, overseer_handle, transaction_pool: None }
+	}
+
+	/// Submit extrinsics through [`RelayChainInterface::submit_extrinsic`] to the given
+	/// transaction pool.
+	pub fn with_transaction_pool(mut self, transaction_pool: Arc<FullPool>) -> Self {
+		self.transaction_pool = Some(transaction_pool);
+		self
 	}
 }
 
@@ -333,6 +347,19 @@ impl RelayChainInterface for RelayChainInProcessInterface {
 	async fn candidate_events(&self, hash: PHash) -> RelayChainResult<Vec<CandidateEvent>> {
 		Ok(self.full_client.runtime_api().candidate_events(hash)?)
 	}
+
+	async fn submit_extrinsic(&self, extrinsic: Vec<u8>) -> RelayChainResult<PHash> {
+		let Some(transaction_pool) = &self.transaction_pool else {
+			return Err(RelayChainError::GenericError(
+				"The relay chain interface has no transaction pool".into(),
+			))
+		};
+		let extrinsic = Decode::decode(&mut &extrinsic[..])?;
+		transaction_pool
+			.submit_one(self.full_client.info().best_hash, TransactionSource::Local, extrinsic)
+			.await
+			.map_err(|e| RelayChainError::Application(Box::new(e)))
+	}
 }
 
 pub enum BlockCheckStatus {
@@ -415,6 +442,7 @@ fn build_polkadot_full_node(
 		hwbench,
 		execute_workers_max_num: None,
 		prepare_workers_hard_max_num: None,
+		execute_workers_profiling: false,
 		prepare_workers_soft_max_num: None,
 		keep_finalized_for: None,
 		invulnerable_ah_collators: HashSet::new(),
@@ -466,7 +494,8 @@ pub fn build_inprocess_relay_chain(
 		full_node.overseer_handle.clone().ok_or(RelayChainError::GenericError(
 			"Overseer not running in full node.".to_string(),
 		))?,
-	));
+	)
+	.with_transaction_pool(full_node.transaction_pool));
 
 	task_manager.add_child(full_node.task_manager);
 
@@ -250,6 +250,16 @@ pub trait RelayChainInterface: Send + Sync {
 	async fn scheduling_lookahead(&self, relay_parent: PHash) -> RelayChainResult<u32>;
 
 	async fn candidate_events(&self, at: RelayHash) -> RelayChainResult<Vec<CandidateEvent>>;
+
+	/// Submit a SCALE-encoded extrinsic to the transaction pool of the relay chain node.
+	///
+	/// Returns the hash of the extrinsic.
+	///
+	/// Interfaces without access to a transaction pool keep the default implementation, which
+	/// returns an error.
+	async fn submit_extrinsic(&self, _extrinsic: Vec<u8>) -> RelayChainResult<PHash> {
+		Err(RelayChainError::GenericError("Submitting extrinsics is not supported".into()))
+	}
 }
 
 #[async_trait]
@@ -412,6 +422,10 @@ where
 	async fn candidate_events(&self, at: RelayHash) -> RelayChainResult<Vec<CandidateEvent>> {
 		(**self).candidate_events(at).await
 	}
+
+	async fn submit_extrinsic(&self, extrinsic: Vec<u8>) -> RelayChainResult<PHash> {
+		(**self).submit_extrinsic(extrinsic).await
+	}
 }
 
 /// Helper function to call an arbitrary runtime API using a `RelayChainInterface` client.
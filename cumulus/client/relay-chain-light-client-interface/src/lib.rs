@@ -0,0 +1,763 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! A [`RelayChainInterface`] verifying the relay chain data retrieved from an untrusted source.
+//!
+//! [`LightClientRelayChainInterface`] wraps another [`RelayChainInterface`], usually retrieving its
+//! data from RPC nodes, and verifies this data the way a light client does:
+//!
+//! - Starting from a trusted [`LightClientCheckpoint`], the finality of relay chain blocks is
+//!   verified with GRANDPA justifications provided by a [`FinalityProofSource`], following the
+//!   changes of the authority set.
+//! - Headers must hash to the requested block hash and descend from the verified finalized chain.
+//!   Blocks that are not finalized yet must be sealed by the BABE authority allowed to author in
+//!   their slot, as read from the verified state of their parent.
+//! - Storage reads are checked against the state root of verified headers.
+//! - The results of the runtime APIs read to build collations, e.g. the persisted validation data,
+//!   the claim queue or the inbound messages, are derived from storage reads checked against the
+//!   state root of verified headers instead of being requested from the wrapped interface. The
+//!   storage layouts are mirrored from the relay chain runtime pallets, so these calls fail if the
+//!   storage version of a pallet differs from the one the layouts were mirrored from.
+//!
+//! **The other runtime API calls are not verified.** [`RelayChainInterface::version`],
+//! [`RelayChainInterface::availability_cores`], [`RelayChainInterface::candidate_events`] and
+//! [`RelayChainInterface::call_runtime_api`] can't be verified without executing the relay chain
+//! runtime, so their results are forwarded as returned by the wrapped interface, only checking
+//! that the block they are requested at is verified. The runtime version only selects which
+//! runtime APIs are called, and the other calls are not used to build collations, but anything
+//! else relying on them trusts the wrapped interface.
+//!
+//! The first block of an epoch following skipped epochs can't be verified before its finality,
+//! as the index of its epoch is not known from the state of its parent.
+
+use async_trait::async_trait;
+use codec::Decode;
+use cumulus_primitives_core::relay_chain::{
+	BlakeTwo256, BlockId, CandidateEvent, CommittedCandidateReceiptV2 as CommittedCandidateReceipt,
+	HrmpChannelId,
+};
+use cumulus_relay_chain_interface::{
+	BlockNumber, CoreIndex, CoreState, InboundDownwardMessage, InboundHrmpMessage,
+	OccupiedCoreAssumption, OverseerHandle, PHash, PHeader, ParaId, PersistedValidationData,
+	RelayChainError, RelayChainInterface, RelayChainResult, SessionIndex, StorageValue,
+	ValidationCodeHash, ValidatorId,
+};
+use futures::{Stream, StreamExt};
+use parking_lot::Mutex;
+use sc_client_api::StorageProof;
+use sc_consensus_grandpa::FinalityProof;
+use serde::{Deserialize, Serialize};
+use sp_consensus_babe::digests::CompatibleDigestItem;
+use sp_consensus_grandpa::{AuthorityList, SetId};
+use sp_state_machine::read_proof_check;
+use sp_version::RuntimeVersion;
+use std::{
+	collections::{BTreeMap, HashMap, VecDeque},
+	path::Path,
+	pin::Pin,
+	sync::Arc,
+};
+
+mod storage;
+mod verifier;
+
+use storage::{
+	Assignment, CandidatePendingAvailability, HostConfiguration, CONFIGURATION, PARA_INCLUSION,
+	PARA_SCHEDULER,
+};
+pub use verifier::VerificationError;
+use verifier::{BabeEpoch, VerifiedChain};
+
+const LOG_TARGET: &str = "relay-chain-light-client";
+
+/// A trusted starting point for verifying the relay chain.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LightClientCheckpoint {
+	/// The hash of the relay chain genesis block.
+	pub genesis_hash: PHash,
+	/// A finalized relay chain header.
+	pub header: PHeader,
+	/// The id of the GRANDPA authority set finalizing the descendants of `header`.
+	pub set_id: SetId,
+	/// The GRANDPA authority set finalizing the descendants of `header`.
+	pub authorities: AuthorityList,
+}
+
+impl LightClientCheckpoint {
+	/// Reads a checkpoint from a JSON file.
+	pub fn from_json_file(path: &Path) -> RelayChainResult<Self> {
+		let file = std::fs::File::open(path).map_err(|error| {
+			RelayChainError::GenericError(format!(
+				"Unable to open checkpoint file {}: {error}",
+				path.display()
+			))
+		})?;
+		serde_json::from_reader(std::io::BufReader::new(file)).map_err(|error| {
+			RelayChainError::GenericError(format!(
+				"Invalid checkpoint file {}: {error}",
+				path.display()
+			))
+		})
+	}
+}
+
+/// A source of GRANDPA finality proofs for the relay chain.
+#[async_trait]
+pub trait FinalityProofSource: Send + Sync {
+	/// Returns the encoded [`FinalityProof`] of a block at or after `number`, as returned by the
+	/// `grandpa_proveFinality` RPC method.
+	async fn prove_finality(&self, number: BlockNumber) -> RelayChainResult<Option<Vec<u8>>>;
+}
+
+/// A [`RelayChainInterface`] verifying the data of the wrapped interface.
+///
+/// See the [crate documentation](crate) for what is verified.
+#[derive(Clone)]
+pub struct LightClientRelayChainInterface<I, S> {
+	inner: I,
+	finality_proofs: S,
+	genesis_hash: PHash,
+	chain: Arc<Mutex<VerifiedChain>>,
+}
+
+impl<I, S> LightClientRelayChainInterface<I, S>
+where
+	I: RelayChainInterface,
+	S: FinalityProofSource,
+{
+	/// Creates a new interface verifying the data of `inner` from `checkpoint`, using the finality
+	/// proofs of `finality_proofs`.
+	pub fn new(inner: I, finality_proofs: S, checkpoint: LightClientCheckpoint) -> Self {
+		Self {
+			inner,
+			finality_proofs,
+			genesis_hash: checkpoint.genesis_hash,
+			chain: Arc::new(Mutex::new(VerifiedChain::new(checkpoint))),
+		}
+	}
+
+	/// Verifies the finality of the blocks finalized by the wrapped interface, returning the last
+	/// block whose finality was verified.
+	///
+	/// The verified chain is not locked while waiting for the wrapped interface, so several calls
+	/// may verify the same blocks concurrently. Only the first of them imports the blocks.
+	async fn sync_finality(&self) -> RelayChainResult<PHeader> {
+		let target = self.inner.finalized_block_hash().await?;
+
+		loop {
+			let finalized = {
+				let mut chain = self.chain.lock();
+				if chain.header(&target).is_some_and(|header| chain.is_finalized(&header)) {
+					break
+				}
+				chain.finalized().clone()
+			};
+
+			let Some(encoded) = self.finality_proofs.prove_finality(finalized.number + 1).await?
+			else {
+				break
+			};
+			let proof = FinalityProof::<PHeader>::decode(&mut &encoded[..])?;
+			let headers = self.ancestry(proof.block, &finalized, proof.unknown_headers).await?;
+
+			let mut chain = self.chain.lock();
+			if chain.finalized().hash() != finalized.hash() {
+				// The finality of blocks was verified concurrently, start over from them.
+				continue
+			}
+			chain.import_finalized(headers, &proof.justification)?;
+			tracing::debug!(
+				target: LOG_TARGET,
+				finalized = ?chain.finalized().hash(),
+				number = chain.finalized().number,
+				"Verified finality of relay chain block.",
+			);
+		}
+
+		Ok(self.chain.lock().finalized().clone())
+	}
+
+	/// Returns the headers from `ancestor` (excluded) to `hash`, ordered by ascending number.
+	///
+	/// `known` headers are used instead of fetching them from the wrapped interface.
+	async fn ancestry(
+		&self,
+		hash: PHash,
+		ancestor: &PHeader,
+		known: Vec<PHeader>,
+	) -> RelayChainResult<Vec<PHeader>> {
+		let mut known: HashMap<_, _> =
+			known.into_iter().map(|header| (header.hash(), header)).collect();
+		let mut headers = Vec::new();
+		let mut next = hash;
+		while next != ancestor.hash() {
+			let header = match known.remove(&next) {
+				Some(header) => header,
+				None => self.fetch_header(next).await?,
+			};
+			if header.number <= ancestor.number {
+				return Err(VerificationError::UnknownAncestry(hash).into())
+			}
+			next = header.parent_hash;
+			headers.push(header);
+		}
+
+		headers.reverse();
+		Ok(headers)
+	}
+
+	/// Fetches the header of `hash` from the wrapped interface.
+	async fn fetch_header(&self, hash: PHash) -> RelayChainResult<PHeader> {
+		let header = self.inner.header(BlockId::Hash(hash)).await?.ok_or_else(|| {
+			RelayChainError::GenericError(format!("Unknown relay chain block {hash:?}"))
+		})?;
+		if header.hash() != hash {
+			return Err(VerificationError::UnexpectedHeader {
+				expected: hash,
+				actual: header.hash(),
+			}
+			.into())
+		}
+
+		Ok(header)
+	}
+
+	/// Verifies that `header` descends from the verified finalized chain, checking the BABE seals
+	/// of the blocks that are not finalized.
+	async fn verify_header(&self, header: PHeader) -> RelayChainResult<PHeader> {
+		let finalized = {
+			let mut chain = self.chain.lock();
+			if chain.header(&header.hash()).is_some() {
+				return Ok(header)
+			}
+			chain.finalized().clone()
+		};
+
+		let mut headers = vec![header];
+		let mut parent = loop {
+			let lowest = &headers[headers.len() - 1];
+			if lowest.number <= finalized.number {
+				return Err(VerificationError::UnknownAncestry(headers[0].hash()).into())
+			}
+			let parent_hash = lowest.parent_hash;
+			if let Some(parent) = self.chain.lock().header(&parent_hash) {
+				break parent
+			}
+			headers.push(self.fetch_header(parent_hash).await?);
+		};
+
+		for header in headers.into_iter().rev() {
+			let epoch = self.babe_epoch(&parent, &header).await?;
+			self.chain.lock().import_descendant(header.clone(), &epoch)?;
+			parent = header;
+		}
+		Ok(parent)
+	}
+
+	/// Reads the BABE epoch `header` is authored in from the state of its verified `parent`.
+	async fn babe_epoch(&self, parent: &PHeader, header: &PHeader) -> RelayChainResult<BabeEpoch> {
+		let [epoch_index, authorities, randomness, config, next_authorities, next_randomness, next_config] =
+			self.read_storage(
+				parent,
+				[
+					storage::EPOCH_INDEX.to_vec(),
+					storage::AUTHORITIES.to_vec(),
+					storage::randomness(),
+					storage::epoch_config(),
+					storage::NEXT_AUTHORITIES.to_vec(),
+					storage::next_randomness(),
+					storage::next_epoch_config(),
+				],
+			)
+			.await?;
+		let epoch_index = decode::<u64>(epoch_index)?.unwrap_or_default();
+		let config = decode(config)?;
+
+		// The first block of an epoch announces the next one.
+		let (epoch_index, authorities, randomness, config) = if header
+			.digest
+			.logs()
+			.iter()
+			.any(|log| log.as_next_epoch_descriptor().is_some())
+		{
+			(epoch_index + 1, next_authorities, next_randomness, decode(next_config)?.or(config))
+		} else {
+			(epoch_index, authorities, randomness, config)
+		};
+
+		Ok(BabeEpoch {
+			epoch_index,
+			authorities: decode(authorities)?.unwrap_or_default(),
+			randomness: decode(randomness)?.unwrap_or_default(),
+			config: config.ok_or_else(|| {
+				RelayChainError::GenericError("Missing BABE epoch configuration".into())
+			})?,
+		})
+	}
+
+	/// Returns the verified header of `hash`, or an error if the block is unknown.
+	async fn verified_header(&self, hash: PHash) -> RelayChainResult<PHeader> {
+		if let Some(header) = self.chain.lock().header(&hash) {
+			return Ok(header)
+		}
+
+		let header = self.fetch_header(hash).await?;
+		self.verify_header(header).await
+	}
+
+	/// Verifies a header notified by the wrapped interface, discarding it if it can't be verified.
+	async fn verify_notification(&self, header: PHeader) -> Option<PHeader> {
+		let hash = header.hash();
+		self.verify_header(header)
+			.await
+			.inspect_err(|error| {
+				tracing::debug!(
+					target: LOG_TARGET,
+					%error,
+					?hash,
+					"Discarding unverified relay chain block notification.",
+				)
+			})
+			.ok()
+	}
+
+	/// Reads the values of `keys` from the state of `header`, checking them against its state root.
+	async fn read_storage_values(
+		&self,
+		header: &PHeader,
+		keys: Vec<Vec<u8>>,
+	) -> RelayChainResult<Vec<Option<StorageValue>>> {
+		let proof = self.inner.prove_read(header.hash(), &keys).await?;
+		let mut values = read_proof_check::<BlakeTwo256, _>(header.state_root, proof, &keys)
+			.map_err(RelayChainError::StateMachineError)?;
+
+		Ok(keys.iter().map(|key| values.remove(key).flatten()).collect())
+	}
+
+	/// Reads the values of `keys` from the state of `header`, checking them against its state root.
+	async fn read_storage<const N: usize>(
+		&self,
+		header: &PHeader,
+		keys: [Vec<u8>; N],
+	) -> RelayChainResult<[Option<StorageValue>; N]> {
+		let values = self.read_storage_values(header, keys.to_vec()).await?;
+		values.try_into().map_err(|values: Vec<_>| {
+			RelayChainError::GenericError(format!(
+				"Read {} storage values instead of {N}",
+				values.len()
+			))
+		})
+	}
+
+	/// Reads the candidates of `para_id` pending availability from the state of `header`.
+	async fn pending_availability(
+		&self,
+		header: &PHeader,
+		para_id: ParaId,
+	) -> RelayChainResult<VecDeque<CandidatePendingAvailability>> {
+		let [pending, version] = self
+			.read_storage(
+				header,
+				[storage::pending_availability(para_id), PARA_INCLUSION.storage_version_key()],
+			)
+			.await?;
+		candidates_pending_availability(pending, version)
+	}
+
+	/// Reads the host configuration from the state of `header`.
+	async fn host_configuration(&self, header: &PHeader) -> RelayChainResult<HostConfiguration> {
+		let [config, version] = self
+			.read_storage(
+				header,
+				[storage::ACTIVE_CONFIG.to_vec(), CONFIGURATION.storage_version_key()],
+			)
+			.await?;
+		host_configuration(config, version)
+	}
+}
+
+/// Decodes a storage value read by the light client.
+fn decode<T: Decode>(value: Option<StorageValue>) -> RelayChainResult<Option<T>> {
+	Ok(value.map(|value| T::decode(&mut &value[..])).transpose()?)
+}
+
+/// Decodes the candidates pending availability read by the light client, given the storage
+/// version of the inclusion pallet read along with them.
+fn candidates_pending_availability(
+	value: Option<StorageValue>,
+	version: Option<StorageValue>,
+) -> RelayChainResult<VecDeque<CandidatePendingAvailability>> {
+	PARA_INCLUSION.check_storage_version(version)?;
+	Ok(decode(value)?.unwrap_or_default())
+}
+
+/// Decodes the host configuration read by the light client, given the storage version of the
+/// configuration pallet read along with it.
+fn host_configuration(
+	value: Option<StorageValue>,
+	version: Option<StorageValue>,
+) -> RelayChainResult<HostConfiguration> {
+	CONFIGURATION.check_storage_version(version)?;
+	decode(value)?.ok_or_else(|| {
+		RelayChainError::GenericError("Missing relay chain host configuration".into())
+	})
+}
+
+#[async_trait]
+impl<I, S> RelayChainInterface for LightClientRelayChainInterface<I, S>
+where
+	I: RelayChainInterface + Clone + 'static,
+	S: FinalityProofSource + Clone + 'static,
+{
+	async fn retrieve_dmq_contents(
+		&self,
+		para_id: ParaId,
+		relay_parent: PHash,
+	) -> RelayChainResult<Vec<InboundDownwardMessage>> {
+		let header = self.verified_header(relay_parent).await?;
+		let [messages] =
+			self.read_storage(&header, [storage::downward_message_queue(para_id)]).await?;
+		Ok(decode(messages)?.unwrap_or_default())
+	}
+
+	async fn retrieve_all_inbound_hrmp_channel_contents(
+		&self,
+		para_id: ParaId,
+		relay_parent: PHash,
+	) -> RelayChainResult<BTreeMap<ParaId, Vec<InboundHrmpMessage>>> {
+		let header = self.verified_header(relay_parent).await?;
+		let [senders] = self
+			.read_storage(&header, [storage::hrmp_ingress_channel_index(para_id)])
+			.await?;
+		let senders: Vec<ParaId> = decode(senders)?.unwrap_or_default();
+		let keys = senders
+			.iter()
+			.map(|sender| {
+				storage::hrmp_channel_contents(HrmpChannelId {
+					sender: *sender,
+					recipient: para_id,
+				})
+			})
+			.collect();
+		let contents = self.read_storage_values(&header, keys).await?;
+
+		senders
+			.into_iter()
+			.zip(contents)
+			.map(|(sender, messages)| Ok((sender, decode(messages)?.unwrap_or_default())))
+			.collect()
+	}
+
+	async fn header(&self, block_id: BlockId) -> RelayChainResult<Option<PHeader>> {
+		let hash = match block_id {
+			BlockId::Hash(hash) => hash,
+			BlockId::Number(0) => self.genesis_hash,
+			BlockId::Number(number) => {
+				let (finalized, finalized_hash) = {
+					let mut chain = self.chain.lock();
+					(chain.finalized().number, chain.finalized_hash(number))
+				};
+				match finalized_hash {
+					Some(hash) => hash,
+					None if number <= finalized =>
+						return Err(RelayChainError::GenericError(format!(
+							"Relay chain block #{number} is not part of the verified chain"
+						))),
+					None => match self.inner.header(BlockId::Number(number)).await? {
+						Some(header) => return self.verify_header(header).await.map(Some),
+						None => return Ok(None),
+					},
+				}
+			},
+		};
+
+		if let Some(header) = self.chain.lock().header(&hash) {
+			return Ok(Some(header))
+		}
+		if hash == self.genesis_hash {
+			return self.fetch_header(hash).await.map(Some)
+		}
+		match self.inner.header(BlockId::Hash(hash)).await? {
+			Some(header) if header.hash() != hash =>
+				Err(VerificationError::UnexpectedHeader { expected: hash, actual: header.hash() }
+					.into()),
+			Some(header) => self.verify_header(header).await.map(Some),
+			None => Ok(None),
+		}
+	}
+
+	async fn persisted_validation_data(
+		&self,
+		hash: PHash,
+		para_id: ParaId,
+		occupied_core_assumption: OccupiedCoreAssumption,
+	) -> RelayChainResult<Option<PersistedValidationData>> {
+		let header = self.verified_header(hash).await?;
+		let [head, pending, inclusion_version, config, config_version] = self
+			.read_storage(
+				&header,
+				[
+					storage::para_head(para_id),
+					storage::pending_availability(para_id),
+					PARA_INCLUSION.storage_version_key(),
+					storage::ACTIVE_CONFIG.to_vec(),
+					CONFIGURATION.storage_version_key(),
+				],
+			)
+			.await?;
+		let pending = candidates_pending_availability(pending, inclusion_version)?;
+
+		let parent_head = match occupied_core_assumption {
+			// The candidates pending availability are enacted in order.
+			OccupiedCoreAssumption::Included => match pending.back() {
+				Some(candidate) => Some(candidate.commitments.head_data.clone()),
+				None => decode(head)?,
+			},
+			OccupiedCoreAssumption::TimedOut => decode(head)?,
+			OccupiedCoreAssumption::Free if !pending.is_empty() => None,
+			OccupiedCoreAssumption::Free => decode(head)?,
+		};
+		let Some(parent_head) = parent_head else { return Ok(None) };
+
+		Ok(Some(PersistedValidationData {
+			parent_head,
+			relay_parent_number: header.number,
+			relay_parent_storage_root: header.state_root,
+			max_pov_size: host_configuration(config, config_version)?.max_pov_size,
+		}))
+	}
+
+	async fn validation_code_hash(
+		&self,
+		hash: PHash,
+		para_id: ParaId,
+		occupied_core_assumption: OccupiedCoreAssumption,
+	) -> RelayChainResult<Option<ValidationCodeHash>> {
+		let header = self.verified_header(hash).await?;
+		if occupied_core_assumption == OccupiedCoreAssumption::Free &&
+			!self.pending_availability(&header, para_id).await?.is_empty()
+		{
+			return Ok(None)
+		}
+
+		let [code_hash] = self.read_storage(&header, [storage::current_code_hash(para_id)]).await?;
+		decode(code_hash)
+	}
+
+	#[allow(deprecated)]
+	async fn candidate_pending_availability(
+		&self,
+		hash: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<Option<CommittedCandidateReceipt>> {
+		Ok(self.candidates_pending_availability(hash, para_id).await?.into_iter().next())
+	}
+
+	async fn session_index_for_child(&self, hash: PHash) -> RelayChainResult<SessionIndex> {
+		let header = self.verified_header(hash).await?;
+		let [session_index] =
+			self.read_storage(&header, [storage::current_session_index()]).await?;
+		Ok(decode(session_index)?.unwrap_or_default())
+	}
+
+	async fn validators(&self, block_id: PHash) -> RelayChainResult<Vec<ValidatorId>> {
+		let header = self.verified_header(block_id).await?;
+		let [validators] = self.read_storage(&header, [storage::active_validator_keys()]).await?;
+		Ok(decode(validators)?.unwrap_or_default())
+	}
+
+	async fn import_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		let this = self.clone();
+		let stream = self.inner.import_notification_stream().await?;
+
+		Ok(stream
+			.filter_map(move |header| {
+				let this = this.clone();
+				async move { this.verify_notification(header).await }
+			})
+			.boxed())
+	}
+
+	async fn finality_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		let this = self.clone();
+		let stream = self.inner.finality_notification_stream().await?;
+
+		Ok(stream
+			.filter_map(move |header| {
+				let this = this.clone();
+				async move {
+					if let Err(error) = this.sync_finality().await {
+						tracing::debug!(
+							target: LOG_TARGET,
+							%error,
+							"Unable to verify the finality of relay chain blocks.",
+						);
+					}
+					this.chain.lock().is_finalized(&header).then_some(header)
+				}
+			})
+			.boxed())
+	}
+
+	async fn best_block_hash(&self) -> RelayChainResult<PHash> {
+		let hash = self.inner.best_block_hash().await?;
+		self.verified_header(hash).await.map(|header| header.hash())
+	}
+
+	async fn finalized_block_hash(&self) -> RelayChainResult<PHash> {
+		self.sync_finality().await.map(|header| header.hash())
+	}
+
+	async fn call_runtime_api(
+		&self,
+		method_name: &'static str,
+		hash: PHash,
+		payload: &[u8],
+	) -> RelayChainResult<Vec<u8>> {
+		self.verified_header(hash).await?;
+		self.inner.call_runtime_api(method_name, hash, payload).await
+	}
+
+	async fn is_major_syncing(&self) -> RelayChainResult<bool> {
+		self.inner.is_major_syncing().await
+	}
+
+	fn overseer_handle(&self) -> RelayChainResult<OverseerHandle> {
+		self.inner.overseer_handle()
+	}
+
+	async fn get_storage_by_key(
+		&self,
+		relay_parent: PHash,
+		key: &[u8],
+	) -> RelayChainResult<Option<StorageValue>> {
+		let header = self.verified_header(relay_parent).await?;
+		let [value] = self.read_storage(&header, [key.to_vec()]).await?;
+		Ok(value)
+	}
+
+	async fn prove_read(
+		&self,
+		relay_parent: PHash,
+		relevant_keys: &Vec<Vec<u8>>,
+	) -> RelayChainResult<StorageProof> {
+		let header = self.verified_header(relay_parent).await?;
+		let proof = self.inner.prove_read(relay_parent, relevant_keys).await?;
+		read_proof_check::<BlakeTwo256, _>(header.state_root, proof.clone(), relevant_keys)
+			.map_err(RelayChainError::StateMachineError)?;
+
+		Ok(proof)
+	}
+
+	async fn wait_for_block(&self, hash: PHash) -> RelayChainResult<()> {
+		self.inner.wait_for_block(hash).await?;
+		self.verified_header(hash).await.map(drop)
+	}
+
+	async fn new_best_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		let this = self.clone();
+		let stream = self.inner.new_best_notification_stream().await?;
+
+		Ok(stream
+			.filter_map(move |header| {
+				let this = this.clone();
+				async move { this.verify_notification(header).await }
+			})
+			.boxed())
+	}
+
+	async fn candidates_pending_availability(
+		&self,
+		hash: PHash,
+		para_id: ParaId,
+	) -> RelayChainResult<Vec<CommittedCandidateReceipt>> {
+		let header = self.verified_header(hash).await?;
+		Ok(self
+			.pending_availability(&header, para_id)
+			.await?
+			.into_iter()
+			.map(|candidate| CommittedCandidateReceipt {
+				descriptor: candidate.descriptor,
+				commitments: candidate.commitments,
+			})
+			.collect())
+	}
+
+	async fn version(&self, relay_parent: PHash) -> RelayChainResult<RuntimeVersion> {
+		self.verified_header(relay_parent).await?;
+		self.inner.version(relay_parent).await
+	}
+
+	async fn availability_cores(
+		&self,
+		relay_parent: PHash,
+	) -> RelayChainResult<Vec<CoreState<PHash, BlockNumber>>> {
+		self.verified_header(relay_parent).await?;
+		self.inner.availability_cores(relay_parent).await
+	}
+
+	async fn claim_queue(
+		&self,
+		relay_parent: PHash,
+	) -> RelayChainResult<BTreeMap<CoreIndex, VecDeque<ParaId>>> {
+		let header = self.verified_header(relay_parent).await?;
+		let [claim_queue, scheduler_version, config, config_version] = self
+			.read_storage(
+				&header,
+				[
+					storage::claim_queue(),
+					PARA_SCHEDULER.storage_version_key(),
+					storage::ACTIVE_CONFIG.to_vec(),
+					CONFIGURATION.storage_version_key(),
+				],
+			)
+			.await?;
+		PARA_SCHEDULER.check_storage_version(scheduler_version)?;
+		let lookahead = host_configuration(config, config_version)?.scheduler_params.lookahead.max(1);
+		let claim_queue: BTreeMap<CoreIndex, VecDeque<Assignment>> =
+			decode(claim_queue)?.unwrap_or_default();
+
+		Ok(claim_queue
+			.into_iter()
+			.map(|(core_index, assignments)| {
+				let para_ids = assignments.iter().map(Assignment::para_id);
+				(core_index, para_ids.take(lookahead as usize).collect())
+			})
+			.collect())
+	}
+
+	async fn scheduling_lookahead(&self, relay_parent: PHash) -> RelayChainResult<u32> {
+		let header = self.verified_header(relay_parent).await?;
+		Ok(self.host_configuration(&header).await?.scheduler_params.lookahead)
+	}
+
+	async fn candidate_events(&self, at: PHash) -> RelayChainResult<Vec<CandidateEvent>> {
+		self.verified_header(at).await?;
+		self.inner.candidate_events(at).await
+	}
+
+	async fn submit_extrinsic(&self, extrinsic: Vec<u8>) -> RelayChainResult<PHash> {
+		self.inner.submit_extrinsic(extrinsic).await
+	}
+}
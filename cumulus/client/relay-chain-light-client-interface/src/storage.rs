@@ -0,0 +1,298 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Keys and layouts of the relay chain storage items read by the light client.
+//!
+//! The relay chain runtime APIs used to build collations are implemented by reading these storage
+//! items, which lets the light client derive their results from storage proofs instead of trusting
+//! the results returned by the untrusted source. The layouts mirror the ones of the relay chain
+//! runtime pallets at a given [`StorageVersion`](Pallet::storage_version). The storage version of
+//! these pallets is read along with their items, and the items are not decoded if it changed.
+
+use codec::{Decode, Encode};
+use cumulus_primitives_core::relay_chain::{
+	well_known_keys, ApprovalVotingParams, AsyncBackingParams, Balance, BlockNumber,
+	CandidateCommitments, CandidateDescriptorV2, CandidateHash, CoreIndex, ExecutorParams,
+	GroupIndex, HrmpChannelId, NodeFeatures, SchedulerParams, SessionIndex,
+};
+use cumulus_relay_chain_interface::{PHash, ParaId, RelayChainError, RelayChainResult, StorageValue};
+use sp_crypto_hashing::{twox_128, twox_64};
+
+pub(crate) use well_known_keys::{
+	para_head, ACTIVE_CONFIG, AUTHORITIES, EPOCH_INDEX, NEXT_AUTHORITIES,
+};
+
+/// Returns the key of the storage value `item` of `pallet`.
+fn value_key(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+/// A relay chain runtime pallet whose storage layouts are mirrored here.
+pub(crate) struct Pallet {
+	/// The name of the pallet in the relay chain runtime.
+	name: &'static str,
+	/// The storage version of the pallet the layouts are mirrored from.
+	storage_version: u16,
+}
+
+/// `polkadot_runtime_parachains::configuration`.
+pub(crate) const CONFIGURATION: Pallet = Pallet { name: "Configuration", storage_version: 12 };
+
+/// `polkadot_runtime_parachains::inclusion`.
+pub(crate) const PARA_INCLUSION: Pallet = Pallet { name: "ParaInclusion", storage_version: 1 };
+
+/// `polkadot_runtime_parachains::scheduler`.
+pub(crate) const PARA_SCHEDULER: Pallet = Pallet { name: "ParaScheduler", storage_version: 3 };
+
+impl Pallet {
+	/// Returns the key of the storage version of the pallet.
+	pub(crate) fn storage_version_key(&self) -> Vec<u8> {
+		value_key(self.name, ":__STORAGE_VERSION__:")
+	}
+
+	/// Checks the storage version read at [`Self::storage_version_key`], failing if the layouts
+	/// mirrored here may not match the ones of the pallet anymore.
+	pub(crate) fn check_storage_version(&self, value: Option<StorageValue>) -> RelayChainResult<()> {
+		let version = value.map(|value| u16::decode(&mut &value[..])).transpose()?.unwrap_or(0);
+		if version != self.storage_version {
+			return Err(RelayChainError::GenericError(format!(
+				"Unsupported storage version {version} of the `{}` relay chain pallet, expected {}",
+				self.name, self.storage_version,
+			)))
+		}
+		Ok(())
+	}
+}
+
+/// Returns the key of `key` in the `Twox64Concat` storage map `item` of `pallet`.
+fn map_key(pallet: &str, item: &str, key: impl Encode) -> Vec<u8> {
+	let key = key.encode();
+	[value_key(pallet, item), twox_64(&key).to_vec(), key].concat()
+}
+
+/// `Babe::Randomness`, the randomness of the current epoch.
+pub(crate) fn randomness() -> Vec<u8> {
+	value_key("Babe", "Randomness")
+}
+
+/// `Babe::NextRandomness`, the randomness of the next epoch.
+pub(crate) fn next_randomness() -> Vec<u8> {
+	value_key("Babe", "NextRandomness")
+}
+
+/// `Babe::EpochConfig`, the configuration of the current epoch.
+pub(crate) fn epoch_config() -> Vec<u8> {
+	value_key("Babe", "EpochConfig")
+}
+
+/// `Babe::NextEpochConfig`, the configuration of the next epoch if it changes.
+pub(crate) fn next_epoch_config() -> Vec<u8> {
+	value_key("Babe", "NextEpochConfig")
+}
+
+/// `ParasShared::CurrentSessionIndex`.
+pub(crate) fn current_session_index() -> Vec<u8> {
+	value_key("ParasShared", "CurrentSessionIndex")
+}
+
+/// `ParasShared::ActiveValidatorKeys`.
+pub(crate) fn active_validator_keys() -> Vec<u8> {
+	value_key("ParasShared", "ActiveValidatorKeys")
+}
+
+/// `Paras::CurrentCodeHash` of `para_id`.
+pub(crate) fn current_code_hash(para_id: ParaId) -> Vec<u8> {
+	map_key("Paras", "CurrentCodeHash", para_id)
+}
+
+/// `ParaInclusion::PendingAvailability` of `para_id`.
+pub(crate) fn pending_availability(para_id: ParaId) -> Vec<u8> {
+	map_key("ParaInclusion", "V1", para_id)
+}
+
+/// `ParaScheduler::ClaimQueue`.
+pub(crate) fn claim_queue() -> Vec<u8> {
+	value_key("ParaScheduler", "ClaimQueue")
+}
+
+/// `Dmp::DownwardMessageQueues` of `para_id`.
+pub(crate) fn downward_message_queue(para_id: ParaId) -> Vec<u8> {
+	map_key("Dmp", "DownwardMessageQueues", para_id)
+}
+
+/// `Hrmp::HrmpIngressChannelsIndex` of `para_id`, the senders of its inbound channels.
+pub(crate) fn hrmp_ingress_channel_index(para_id: ParaId) -> Vec<u8> {
+	well_known_keys::hrmp_ingress_channel_index(para_id)
+}
+
+/// `Hrmp::HrmpChannelContents` of `channel`.
+pub(crate) fn hrmp_channel_contents(channel: HrmpChannelId) -> Vec<u8> {
+	map_key("Hrmp", "HrmpChannelContents", channel)
+}
+
+/// The layout of `Configuration::ActiveConfig`.
+#[derive(Decode)]
+#[cfg_attr(test, derive(scale_info::TypeInfo))]
+#[allow(dead_code)]
+pub(crate) struct HostConfiguration {
+	pub max_code_size: u32,
+	pub max_head_data_size: u32,
+	pub max_upward_queue_count: u32,
+	pub max_upward_queue_size: u32,
+	pub max_upward_message_size: u32,
+	pub max_upward_message_num_per_candidate: u32,
+	pub hrmp_max_message_num_per_candidate: u32,
+	pub validation_upgrade_cooldown: BlockNumber,
+	pub validation_upgrade_delay: BlockNumber,
+	pub async_backing_params: AsyncBackingParams,
+	pub max_pov_size: u32,
+	pub max_downward_message_size: u32,
+	pub hrmp_max_parachain_outbound_channels: u32,
+	pub hrmp_sender_deposit: Balance,
+	pub hrmp_recipient_deposit: Balance,
+	pub hrmp_channel_max_capacity: u32,
+	pub hrmp_channel_max_total_size: u32,
+	pub hrmp_max_parachain_inbound_channels: u32,
+	pub hrmp_channel_max_message_size: u32,
+	pub executor_params: ExecutorParams,
+	pub code_retention_period: BlockNumber,
+	pub max_validators: Option<u32>,
+	pub dispute_period: SessionIndex,
+	pub dispute_post_conclusion_acceptance_period: BlockNumber,
+	pub no_show_slots: u32,
+	pub n_delay_tranches: u32,
+	pub zeroth_delay_tranche_width: u32,
+	pub needed_approvals: u32,
+	pub relay_vrf_modulo_samples: u32,
+	pub pvf_voting_ttl: SessionIndex,
+	pub minimum_validation_upgrade_delay: BlockNumber,
+	pub minimum_backing_votes: u32,
+	pub node_features: NodeFeatures,
+	pub approval_voting_params: ApprovalVotingParams,
+	pub scheduler_params: SchedulerParams<BlockNumber>,
+}
+
+/// The layout of the entries of `ParaInclusion::PendingAvailability`.
+#[derive(Decode)]
+#[cfg_attr(test, derive(scale_info::TypeInfo))]
+#[allow(dead_code)]
+pub(crate) struct CandidatePendingAvailability {
+	pub core: CoreIndex,
+	pub hash: CandidateHash,
+	pub descriptor: CandidateDescriptorV2<PHash>,
+	pub commitments: CandidateCommitments,
+	pub availability_votes: NodeFeatures,
+	pub backers: NodeFeatures,
+	pub relay_parent_number: BlockNumber,
+	pub backed_in_number: BlockNumber,
+	pub backing_group: GroupIndex,
+}
+
+/// The layout of the entries of `ParaScheduler::ClaimQueue`.
+#[derive(Decode)]
+#[cfg_attr(test, derive(scale_info::TypeInfo))]
+#[allow(dead_code)]
+pub(crate) enum Assignment {
+	Pool { para_id: ParaId, core_index: CoreIndex },
+	Bulk(ParaId),
+}
+
+impl Assignment {
+	pub(crate) fn para_id(&self) -> ParaId {
+		match self {
+			Self::Pool { para_id, .. } | Self::Bulk(para_id) => *para_id,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::DecodeAll;
+	use frame_support::traits::{GetStorageVersion, StorageVersion};
+	use polkadot_runtime_parachains::{configuration, inclusion, scheduler};
+	use scale_info::{meta_type, TypeDef, TypeInfo};
+	use westend_runtime::Runtime;
+
+	/// Returns the names of the fields, or of the variants and their fields, of `T`.
+	fn field_names<T: TypeInfo + 'static>() -> Vec<String> {
+		let fields = |fields: &[scale_info::Field]| {
+			fields.iter().map(|field| field.name.unwrap_or_default().to_string()).collect::<Vec<_>>()
+		};
+		match meta_type::<T>().type_info().type_def {
+			TypeDef::Composite(composite) => fields(&composite.fields),
+			TypeDef::Variant(variant) => variant
+				.variants
+				.iter()
+				.flat_map(|variant| {
+					[variant.name.to_string()].into_iter().chain(fields(&variant.fields))
+				})
+				.collect(),
+			_ => unreachable!("only used for structs and enums"),
+		}
+	}
+
+	#[test]
+	fn keys_match_well_known_keys() {
+		let para_id = ParaId::from(2000);
+		assert_eq!(&value_key("Babe", "EpochIndex"), EPOCH_INDEX);
+		assert_eq!(&value_key("Babe", "Authorities"), AUTHORITIES);
+		assert_eq!(&value_key("Babe", "NextAuthorities"), NEXT_AUTHORITIES);
+		assert_eq!(&value_key("Configuration", "ActiveConfig"), ACTIVE_CONFIG);
+		assert_eq!(map_key("Paras", "Heads", para_id), para_head(para_id));
+		assert_eq!(
+			map_key("Hrmp", "HrmpIngressChannelsIndex", para_id),
+			hrmp_ingress_channel_index(para_id)
+		);
+	}
+
+	#[test]
+	fn storage_versions_match_the_runtime() {
+		for (pallet, version) in [
+			(CONFIGURATION, configuration::Pallet::<Runtime>::in_code_storage_version()),
+			(PARA_INCLUSION, inclusion::Pallet::<Runtime>::in_code_storage_version()),
+			(PARA_SCHEDULER, scheduler::Pallet::<Runtime>::in_code_storage_version()),
+		] {
+			assert_eq!(StorageVersion::new(pallet.storage_version), version);
+		}
+	}
+
+	#[test]
+	fn layouts_match_the_runtime() {
+		assert_eq!(
+			field_names::<HostConfiguration>(),
+			field_names::<configuration::HostConfiguration<BlockNumber>>()
+		);
+		let config = configuration::HostConfiguration::<BlockNumber>::default();
+		let decoded = HostConfiguration::decode_all(&mut &config.encode()[..]).unwrap();
+		assert_eq!(decoded.scheduler_params.lookahead, config.scheduler_params.lookahead);
+		assert_eq!(decoded.max_pov_size, config.max_pov_size);
+
+		assert_eq!(
+			field_names::<CandidatePendingAvailability>(),
+			field_names::<inclusion::CandidatePendingAvailability<PHash, BlockNumber>>()
+		);
+
+		assert_eq!(field_names::<Assignment>(), field_names::<scheduler::common::Assignment>());
+		let assignment =
+			scheduler::common::Assignment::Pool { para_id: 2000.into(), core_index: CoreIndex(1) };
+		assert_eq!(
+			Assignment::decode_all(&mut &assignment.encode()[..]).unwrap().para_id(),
+			assignment.para_id()
+		);
+	}
+}
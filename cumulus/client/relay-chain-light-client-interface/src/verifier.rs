@@ -0,0 +1,514 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Verification of relay chain headers against GRANDPA finality and BABE seals.
+
+use crate::LightClientCheckpoint;
+use codec::DecodeAll;
+use cumulus_primitives_core::relay_chain::Block;
+use cumulus_relay_chain_interface::{BlockNumber, PHash, PHeader, RelayChainError};
+use sc_consensus_babe::{
+	authorship::{calculate_primary_threshold, secondary_slot_author},
+	AUTHORING_SCORE_LENGTH, AUTHORING_SCORE_VRF_CONTEXT,
+};
+use sc_consensus_grandpa::{find_forced_change, find_scheduled_change, GrandpaJustification};
+use schnellru::{ByLength, LruMap};
+use sp_consensus_babe::{
+	digests::{CompatibleDigestItem, PreDigest},
+	make_vrf_sign_data, AuthorityId, AuthorityPair, BabeAuthorityWeight, BabeEpochConfiguration,
+	Randomness,
+};
+use sp_consensus_grandpa::{AuthorityList, SetId};
+use sp_core::{
+	crypto::{VrfPublic, Wraps},
+	Pair,
+};
+
+/// The number of verified headers kept in memory.
+const MAX_VERIFIED_HEADERS: u32 = 4096;
+
+/// Errors raised when the data retrieved from the untrusted source can't be verified.
+#[derive(Debug, thiserror::Error)]
+pub enum VerificationError {
+	#[error("Header {actual:?} was returned when requesting header {expected:?}")]
+	UnexpectedHeader { expected: PHash, actual: PHash },
+	#[error("Block {0:?} does not descend from the verified finalized chain")]
+	UnknownAncestry(PHash),
+	#[error("Invalid GRANDPA justification for block {0:?}: {1}")]
+	InvalidJustification(PHash, String),
+	#[error("Block {0:?} is finalized past an authority set change that was not justified")]
+	SkippedAuthoritySetChange(PHash),
+	#[error("Block {0:?} forces an authority set change, a new checkpoint is required")]
+	ForcedAuthoritySetChange(PHash),
+	#[error("Invalid BABE seal for block {0:?}: {1}")]
+	InvalidSeal(PHash, &'static str),
+}
+
+impl From<VerificationError> for RelayChainError {
+	fn from(error: VerificationError) -> Self {
+		RelayChainError::Application(Box::new(error))
+	}
+}
+
+/// The BABE epoch a relay chain block is authored in, as read from the state of its parent.
+pub(crate) struct BabeEpoch {
+	pub(crate) epoch_index: u64,
+	pub(crate) authorities: Vec<(AuthorityId, BabeAuthorityWeight)>,
+	pub(crate) randomness: Randomness,
+	pub(crate) config: BabeEpochConfiguration,
+}
+
+/// The part of the relay chain verified so far.
+pub(crate) struct VerifiedChain {
+	/// The last block whose finality was verified.
+	finalized: PHeader,
+	/// The id of the authority set finalizing the descendants of `finalized`.
+	set_id: SetId,
+	/// The authority set finalizing the descendants of `finalized`.
+	authorities: AuthorityList,
+	/// Authority set changes scheduled by finalized blocks, with the number of the block enacting
+	/// them.
+	pending_changes: Vec<(BlockNumber, AuthorityList)>,
+	/// Verified headers, either finalized or descending from a finalized block.
+	headers: LruMap<PHash, PHeader>,
+	/// The hashes of the finalized blocks, by number.
+	finalized_hashes: LruMap<BlockNumber, PHash>,
+}
+
+impl VerifiedChain {
+	pub(crate) fn new(checkpoint: LightClientCheckpoint) -> Self {
+		let mut chain = Self {
+			finalized: checkpoint.header.clone(),
+			set_id: checkpoint.set_id,
+			authorities: checkpoint.authorities,
+			pending_changes: Vec::new(),
+			headers: LruMap::new(ByLength::new(MAX_VERIFIED_HEADERS)),
+			finalized_hashes: LruMap::new(ByLength::new(MAX_VERIFIED_HEADERS)),
+		};
+		chain.insert_finalized(checkpoint.header);
+		chain
+	}
+
+	/// The last block whose finality was verified.
+	pub(crate) fn finalized(&self) -> &PHeader {
+		&self.finalized
+	}
+
+	/// Returns the verified header of `hash`, if known.
+	pub(crate) fn header(&mut self, hash: &PHash) -> Option<PHeader> {
+		self.headers.get(hash).cloned()
+	}
+
+	/// Returns the hash of the finalized block at `number`, if known.
+	pub(crate) fn finalized_hash(&mut self, number: BlockNumber) -> Option<PHash> {
+		self.finalized_hashes.get(&number).copied()
+	}
+
+	/// Returns whether the finality of `header` was verified.
+	pub(crate) fn is_finalized(&mut self, header: &PHeader) -> bool {
+		self.finalized_hash(header.number) == Some(header.hash())
+	}
+
+	/// Imports a header whose parent is a verified header, checking its BABE seal against the
+	/// `epoch` it is authored in.
+	pub(crate) fn import_descendant(
+		&mut self,
+		header: PHeader,
+		epoch: &BabeEpoch,
+	) -> Result<(), VerificationError> {
+		if header.number <= self.finalized.number ||
+			self.headers.peek(&header.parent_hash).is_none()
+		{
+			return Err(VerificationError::UnknownAncestry(header.hash()))
+		}
+		check_babe_seal(&header, epoch)
+			.map_err(|error| VerificationError::InvalidSeal(header.hash(), error))?;
+
+		self.headers.insert(header.hash(), header);
+		Ok(())
+	}
+
+	/// Imports the headers finalized by `justification`.
+	///
+	/// The headers are ordered by ascending number, starting with the child of the last verified
+	/// finalized block and ending with the block targeted by the justification.
+	pub(crate) fn import_finalized(
+		&mut self,
+		headers: Vec<PHeader>,
+		justification: &[u8],
+	) -> Result<(), VerificationError> {
+		let (Some(first), Some(target)) = (headers.first(), headers.last()) else { return Ok(()) };
+		if first.parent_hash != self.finalized.hash() {
+			return Err(VerificationError::UnknownAncestry(first.hash()))
+		}
+		check_linked(&headers)?;
+
+		let target_hash = target.hash();
+		let mut pending_changes = self.pending_changes.clone();
+		for header in &headers {
+			if find_forced_change::<Block>(header).is_some() {
+				return Err(VerificationError::ForcedAuthoritySetChange(header.hash()))
+			}
+			if let Some(change) = find_scheduled_change::<Block>(header) {
+				pending_changes
+					.push((header.number.saturating_add(change.delay), change.next_authorities));
+			}
+		}
+		// The current authorities stop finalizing blocks once a change is enacted.
+		if pending_changes.iter().any(|(number, _)| *number < target.number) {
+			return Err(VerificationError::SkippedAuthoritySetChange(target_hash))
+		}
+
+		let invalid_justification =
+			|error: String| VerificationError::InvalidJustification(target_hash, error);
+		let justification = GrandpaJustification::<Block>::decode_all(&mut &*justification)
+			.map_err(|error| invalid_justification(error.to_string()))?;
+		if justification.target() != (target.number, target_hash) {
+			return Err(invalid_justification("unexpected target".into()))
+		}
+		justification
+			.verify(self.set_id, &self.authorities)
+			.map_err(|error| invalid_justification(error.to_string()))?;
+
+		if let Some(index) = pending_changes.iter().position(|(number, _)| *number == target.number)
+		{
+			let (_, authorities) = pending_changes.remove(index);
+			self.set_id += 1;
+			self.authorities = authorities;
+		}
+		self.pending_changes = pending_changes;
+
+		for header in headers {
+			self.insert_finalized(header);
+		}
+		Ok(())
+	}
+
+	fn insert_finalized(&mut self, header: PHeader) {
+		let hash = header.hash();
+		self.finalized_hashes.insert(header.number, hash);
+		self.headers.insert(hash, header.clone());
+		self.finalized = header;
+	}
+}
+
+/// Checks that `header` is sealed by the authority of `epoch` allowed to author in its slot.
+///
+/// This mirrors the checks of the BABE import queue, except that the slot is not checked against
+/// the current time.
+fn check_babe_seal(header: &PHeader, epoch: &BabeEpoch) -> Result<(), &'static str> {
+	let mut header = header.clone();
+	let seal = header.digest.pop().ok_or("missing seal")?;
+	let signature = seal.as_babe_seal().ok_or("missing seal")?;
+	let pre_digest = header
+		.digest
+		.logs()
+		.iter()
+		.find_map(|log| log.as_babe_pre_digest())
+		.ok_or("missing pre-digest")?;
+	// The seal signs the header without the seal.
+	let pre_hash = header.hash();
+
+	let authority_index = pre_digest.authority_index() as usize;
+	let author = &epoch.authorities.get(authority_index).ok_or("unknown authority")?.0;
+	if !AuthorityPair::verify(&signature, pre_hash, author) {
+		return Err("bad signature")
+	}
+
+	let allowed_slots = epoch.config.allowed_slots;
+	let vrf_signature = match &pre_digest {
+		PreDigest::Primary(primary) => &primary.vrf_signature,
+		PreDigest::SecondaryPlain(_) if allowed_slots.is_secondary_plain_slots_allowed() => {
+			check_secondary_author(&pre_digest, author, epoch)?;
+			return Ok(())
+		},
+		PreDigest::SecondaryVRF(secondary) if allowed_slots.is_secondary_vrf_slots_allowed() => {
+			check_secondary_author(&pre_digest, author, epoch)?;
+			&secondary.vrf_signature
+		},
+		_ => return Err("secondary slots are not allowed"),
+	};
+
+	let data = make_vrf_sign_data(&epoch.randomness, pre_digest.slot(), epoch.epoch_index);
+	if !author.as_inner_ref().vrf_verify(&data, vrf_signature) {
+		return Err("invalid VRF signature")
+	}
+	if let PreDigest::Primary(_) = pre_digest {
+		let threshold =
+			calculate_primary_threshold(epoch.config.c, &epoch.authorities, authority_index);
+		let score = author
+			.as_inner_ref()
+			.make_bytes::<AUTHORING_SCORE_LENGTH>(
+				AUTHORING_SCORE_VRF_CONTEXT,
+				data.as_ref(),
+				&vrf_signature.pre_output,
+			)
+			.map(u128::from_le_bytes)
+			.map_err(|_| "invalid VRF signature")?;
+		if score >= threshold {
+			return Err("VRF threshold exceeded")
+		}
+	}
+	Ok(())
+}
+
+/// Checks that `author` is the authority expected to author in the secondary slot of `pre_digest`.
+fn check_secondary_author(
+	pre_digest: &PreDigest,
+	author: &AuthorityId,
+	epoch: &BabeEpoch,
+) -> Result<(), &'static str> {
+	match secondary_slot_author(pre_digest.slot(), &epoch.authorities, epoch.randomness) {
+		Some(expected) if expected == author => Ok(()),
+		_ => Err("unexpected secondary slot author"),
+	}
+}
+
+/// Checks that each header is the parent of the next one.
+fn check_linked(headers: &[PHeader]) -> Result<(), VerificationError> {
+	for pair in headers.windows(2) {
+		if pair[1].parent_hash != pair[0].hash() {
+			return Err(VerificationError::UnknownAncestry(pair[1].hash()))
+		}
+	}
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use sp_consensus_babe::{digests::SecondaryPlainPreDigest, AllowedSlots};
+	use sp_consensus_grandpa::{ConsensusLog, ScheduledChange, GRANDPA_ENGINE_ID};
+	use sp_keyring::{Ed25519Keyring, Sr25519Keyring};
+	use sp_runtime::{traits::Header as HeaderT, Digest, DigestItem};
+
+	const GENESIS_SET: [Ed25519Keyring; 4] =
+		[Ed25519Keyring::Alice, Ed25519Keyring::Bob, Ed25519Keyring::Charlie, Ed25519Keyring::Dave];
+	const NEXT_SET: [Ed25519Keyring; 4] =
+		[Ed25519Keyring::Eve, Ed25519Keyring::Ferdie, Ed25519Keyring::One, Ed25519Keyring::Two];
+
+	fn authorities(keys: &[Ed25519Keyring]) -> AuthorityList {
+		keys.iter().map(|key| (key.public().into(), 1)).collect()
+	}
+
+	fn checkpoint() -> (LightClientCheckpoint, PHeader) {
+		let header = PHeader::new(
+			10,
+			Default::default(),
+			Default::default(),
+			[1; 32].into(),
+			Default::default(),
+		);
+		let checkpoint = LightClientCheckpoint {
+			genesis_hash: Default::default(),
+			header: header.clone(),
+			set_id: 0,
+			authorities: authorities(&GENESIS_SET),
+		};
+		(checkpoint, header)
+	}
+
+	fn child(parent: &PHeader, change: Option<&[Ed25519Keyring]>) -> PHeader {
+		let mut digest = Digest::default();
+		if let Some(keys) = change {
+			let change = ScheduledChange { next_authorities: authorities(keys), delay: 0 };
+			digest.push(DigestItem::Consensus(
+				GRANDPA_ENGINE_ID,
+				ConsensusLog::ScheduledChange(change).encode(),
+			));
+		}
+		PHeader::new(
+			parent.number + 1,
+			Default::default(),
+			Default::default(),
+			parent.hash(),
+			digest,
+		)
+	}
+
+	fn justification(target: &PHeader, set_id: SetId, keys: &[Ed25519Keyring]) -> Vec<u8> {
+		let round = 1;
+		let precommit = finality_grandpa::Precommit {
+			target_hash: target.hash(),
+			target_number: target.number,
+		};
+		let message = finality_grandpa::Message::Precommit(precommit.clone());
+		let payload = sp_consensus_grandpa::localized_payload(round, set_id, &message);
+		let precommits = keys
+			.iter()
+			.map(|key| finality_grandpa::SignedPrecommit {
+				precommit: precommit.clone(),
+				signature: key.sign(&payload).into(),
+				id: key.public().into(),
+			})
+			.collect();
+
+		sp_consensus_grandpa::GrandpaJustification::<PHeader> {
+			round,
+			commit: finality_grandpa::Commit {
+				target_hash: target.hash(),
+				target_number: target.number,
+				precommits,
+			},
+			votes_ancestries: Vec::new(),
+		}
+		.encode()
+	}
+
+	#[test]
+	fn finalizes_blocks_with_valid_justifications() {
+		let (checkpoint, header) = checkpoint();
+		let mut chain = VerifiedChain::new(checkpoint);
+		let first = child(&header, None);
+		let second = child(&first, None);
+
+		chain
+			.import_finalized(
+				vec![first.clone(), second.clone()],
+				&justification(&second, 0, &GENESIS_SET[..3]),
+			)
+			.unwrap();
+
+		assert_eq!(chain.finalized(), &second);
+		assert!(chain.is_finalized(&first));
+		assert_eq!(chain.header(&first.hash()), Some(first));
+	}
+
+	#[test]
+	fn rejects_invalid_justifications() {
+		let (checkpoint, header) = checkpoint();
+		let mut chain = VerifiedChain::new(checkpoint);
+		let first = child(&header, None);
+		let second = child(&first, None);
+
+		for justification in [
+			// Not enough votes.
+			justification(&second, 0, &GENESIS_SET[..2]),
+			// Votes of another authority set.
+			justification(&second, 1, &GENESIS_SET),
+			justification(&second, 0, &NEXT_SET),
+			// Votes for another block.
+			justification(&first, 0, &GENESIS_SET),
+		] {
+			assert!(matches!(
+				chain.import_finalized(vec![first.clone(), second.clone()], &justification),
+				Err(VerificationError::InvalidJustification(..))
+			));
+		}
+
+		// The headers must descend from the last finalized block.
+		assert!(matches!(
+			chain.import_finalized(vec![second.clone()], &justification(&second, 0, &GENESIS_SET)),
+			Err(VerificationError::UnknownAncestry(_))
+		));
+
+		assert_eq!(chain.finalized(), &header);
+		assert_eq!(chain.header(&first.hash()), None);
+	}
+
+	#[test]
+	fn follows_authority_set_changes() {
+		let (checkpoint, header) = checkpoint();
+		let mut chain = VerifiedChain::new(checkpoint);
+		let change = child(&header, Some(&NEXT_SET));
+		let next = child(&change, None);
+
+		// The block enacting the change must be justified by the current authorities.
+		assert!(matches!(
+			chain.import_finalized(
+				vec![change.clone(), next.clone()],
+				&justification(&next, 0, &GENESIS_SET)
+			),
+			Err(VerificationError::SkippedAuthoritySetChange(_))
+		));
+		chain
+			.import_finalized(vec![change.clone()], &justification(&change, 0, &GENESIS_SET))
+			.unwrap();
+
+		assert!(matches!(
+			chain.import_finalized(vec![next.clone()], &justification(&next, 0, &GENESIS_SET)),
+			Err(VerificationError::InvalidJustification(..))
+		));
+		chain
+			.import_finalized(vec![next.clone()], &justification(&next, 1, &NEXT_SET))
+			.unwrap();
+		assert_eq!(chain.finalized(), &next);
+	}
+
+	fn epoch() -> BabeEpoch {
+		BabeEpoch {
+			epoch_index: 0,
+			authorities: vec![(Sr25519Keyring::Alice.public().into(), 1)],
+			randomness: [0; 32],
+			config: BabeEpochConfiguration {
+				c: (1, 4),
+				allowed_slots: AllowedSlots::PrimaryAndSecondaryPlainSlots,
+			},
+		}
+	}
+
+	fn sealed(mut header: PHeader, key: Sr25519Keyring) -> PHeader {
+		let pre_digest = PreDigest::SecondaryPlain(SecondaryPlainPreDigest {
+			authority_index: 0,
+			slot: (header.number as u64).into(),
+		});
+		header.digest.push(DigestItem::babe_pre_digest(pre_digest));
+		let signature = key.pair().sign(header.hash().as_ref());
+		header.digest.push(DigestItem::babe_seal(signature.into()));
+		header
+	}
+
+	#[test]
+	fn imports_descendants_of_verified_blocks() {
+		let (checkpoint, header) = checkpoint();
+		let mut chain = VerifiedChain::new(checkpoint);
+		let first = sealed(child(&header, None), Sr25519Keyring::Alice);
+		let second = sealed(child(&first, None), Sr25519Keyring::Alice);
+
+		assert!(matches!(
+			chain.import_descendant(second.clone(), &epoch()),
+			Err(VerificationError::UnknownAncestry(_))
+		));
+
+		chain.import_descendant(first.clone(), &epoch()).unwrap();
+		chain.import_descendant(second.clone(), &epoch()).unwrap();
+		assert_eq!(chain.header(&second.hash()), Some(second.clone()));
+		assert!(!chain.is_finalized(&second));
+	}
+
+	#[test]
+	fn rejects_descendants_not_sealed_by_the_epoch_authorities() {
+		let (checkpoint, header) = checkpoint();
+		let mut chain = VerifiedChain::new(checkpoint);
+		let unsealed = child(&header, None);
+		let mut secondary_slots_disallowed = epoch();
+		secondary_slots_disallowed.config.allowed_slots = AllowedSlots::PrimarySlots;
+
+		for (header, epoch) in [
+			(unsealed.clone(), epoch()),
+			(sealed(unsealed.clone(), Sr25519Keyring::Bob), epoch()),
+			(sealed(unsealed.clone(), Sr25519Keyring::Alice), secondary_slots_disallowed),
+		] {
+			assert!(matches!(
+				chain.import_descendant(header.clone(), &epoch),
+				Err(VerificationError::InvalidSeal(..))
+			));
+			assert_eq!(chain.header(&header.hash()), None);
+		}
+	}
+}
@@ -18,17 +18,19 @@
 use std::{
 	collections::{BTreeMap, VecDeque},
 	pin::Pin,
+	sync::Arc,
 };
 
 use cumulus_primitives_core::{InboundDownwardMessage, ParaId, PersistedValidationData};
-use cumulus_relay_chain_interface::{RelayChainError, RelayChainResult};
+use cumulus_relay_chain_interface::{RelayChainError, RelayChainInterface, RelayChainResult};
+use cumulus_relay_chain_light_client_interface::FinalityProofSource;
 use cumulus_relay_chain_rpc_interface::RelayChainRpcClient;
 use futures::{Stream, StreamExt};
 use polkadot_core_primitives::{Block, BlockNumber, Hash, Header};
 use polkadot_overseer::{ChainApiBackend, RuntimeApiSubsystemClient};
 use polkadot_primitives::{
 	async_backing::{AsyncBackingParams, BackingState, Constraints},
-	slashing, ApprovalVotingParams, CoreIndex, NodeFeatures,
+	slashing, ApprovalVotingParams, BlockId, CoreIndex, NodeFeatures,
 };
 use sc_authority_discovery::{AuthorityDiscovery, Error as AuthorityDiscoveryError};
 use sc_client_api::AuxStore;
@@ -36,28 +38,71 @@ use sp_api::{ApiError, RuntimeApiInfo};
 use sp_blockchain::Info;
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
 
+/// Relay chain client used by the subsystems of the minimal node.
+///
+/// Relay chain data is fetched from the RPC node. When a verifier is set with
+/// [`Self::with_verifier`], blocks and the runtime API results it can derive are taken from the
+/// verifier instead, and every other runtime API call is only forwarded once its block has been
+/// verified.
 #[derive(Clone)]
 pub struct BlockChainRpcClient {
 	rpc_client: RelayChainRpcClient,
+	verifier: Option<Arc<dyn RelayChainInterface>>,
 }
 
 impl BlockChainRpcClient {
 	pub fn new(rpc_client: RelayChainRpcClient) -> Self {
-		Self { rpc_client }
+		Self { rpc_client, verifier: None }
+	}
+
+	/// Retrieves relay chain blocks and the collation data from `verifier`.
+	pub fn with_verifier(mut self, verifier: Arc<dyn RelayChainInterface>) -> Self {
+		self.verifier = Some(verifier);
+		self
+	}
+
+	/// Ensures that the block `at` is part of the verified relay chain, if a verifier is set.
+	async fn verify_block(&self, at: Hash) -> RelayChainResult<()> {
+		let Some(verifier) = &self.verifier else { return Ok(()) };
+		match verifier.header(BlockId::Hash(at)).await? {
+			Some(_) => Ok(()),
+			None => Err(RelayChainError::GenericError(format!(
+				"Unable to retrieve relay chain block {at:?}"
+			))),
+		}
 	}
 
 	pub async fn chain_get_header(
 		&self,
 		hash: Option<Hash>,
 	) -> Result<Option<Header>, RelayChainError> {
-		self.rpc_client.chain_get_header(hash).await
+		match (&self.verifier, hash) {
+			(Some(verifier), Some(hash)) => verifier.header(BlockId::Hash(hash)).await,
+			(Some(verifier), None) => {
+				let hash = verifier.best_block_hash().await?;
+				verifier.header(BlockId::Hash(hash)).await
+			},
+			(None, hash) => self.rpc_client.chain_get_header(hash).await,
+		}
 	}
 
 	pub async fn block_get_hash(
 		&self,
 		number: Option<BlockNumber>,
 	) -> Result<Option<Hash>, RelayChainError> {
-		self.rpc_client.chain_get_block_hash(number).await
+		match (&self.verifier, number) {
+			(Some(verifier), Some(number)) =>
+				Ok(verifier.header(BlockId::Number(number)).await?.map(|header| header.hash())),
+			(Some(verifier), None) => verifier.best_block_hash().await.map(Some),
+			(None, number) => self.rpc_client.chain_get_block_hash(number).await,
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl FinalityProofSource for BlockChainRpcClient {
+	async fn prove_finality(&self, number: BlockNumber) -> RelayChainResult<Option<Vec<u8>>> {
+		Ok(self.rpc_client.grandpa_prove_finality(number).await?.map(|proof| proof.0))
 	}
 }
 
@@ -67,23 +112,34 @@ impl ChainApiBackend for BlockChainRpcClient {
 		&self,
 		hash: <Block as BlockT>::Hash,
 	) -> sp_blockchain::Result<Option<<Block as BlockT>::Header>> {
-		Ok(self.rpc_client.chain_get_header(Some(hash)).await?)
+		Ok(self.chain_get_header(Some(hash)).await?)
 	}
 
 	async fn info(&self) -> sp_blockchain::Result<Info<Block>> {
+		let finalized_head = async {
+			match &self.verifier {
+				Some(verifier) => verifier.finalized_block_hash().await,
+				None => self.rpc_client.chain_get_finalized_head().await,
+			}
+		};
 		let (best_header_opt, genesis_hash, finalized_head) = futures::try_join!(
-			self.rpc_client.chain_get_header(None),
-			self.rpc_client.chain_get_head(Some(0)),
-			self.rpc_client.chain_get_finalized_head()
+			self.chain_get_header(None),
+			self.block_get_hash(Some(0)),
+			finalized_head
 		)?;
 		let best_header = best_header_opt.ok_or_else(|| {
 			RelayChainError::GenericError(
 				"Unable to retrieve best header from relay chain.".to_string(),
 			)
 		})?;
+		let genesis_hash = genesis_hash.ok_or_else(|| {
+			RelayChainError::GenericError(
+				"Unable to retrieve genesis hash from relay chain.".to_string(),
+			)
+		})?;
 
 		let finalized_header =
-			self.rpc_client.chain_get_header(Some(finalized_head)).await?.ok_or_else(|| {
+			self.chain_get_header(Some(finalized_head)).await?.ok_or_else(|| {
 				RelayChainError::GenericError(
 					"Unable to retrieve finalized header from relay chain.".to_string(),
 				)
@@ -104,18 +160,14 @@ impl ChainApiBackend for BlockChainRpcClient {
 		&self,
 		hash: <Block as BlockT>::Hash,
 	) -> sp_blockchain::Result<Option<<<Block as BlockT>::Header as HeaderT>::Number>> {
-		Ok(self
-			.rpc_client
-			.chain_get_header(Some(hash))
-			.await?
-			.map(|maybe_header| maybe_header.number))
+		Ok(self.chain_get_header(Some(hash)).await?.map(|maybe_header| maybe_header.number))
 	}
 
 	async fn hash(
 		&self,
 		number: NumberFor<Block>,
 	) -> sp_blockchain::Result<Option<<Block as BlockT>::Hash>> {
-		Ok(self.rpc_client.chain_get_block_hash(number.into()).await?)
+		Ok(self.block_get_hash(Some(number)).await?)
 	}
 }
 
@@ -125,6 +177,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		&self,
 		at: Hash,
 	) -> Result<Vec<polkadot_primitives::ValidatorId>, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.validators(at).await?)
+		}
 		Ok(self.rpc_client.parachain_host_validators(at).await?)
 	}
 
@@ -138,6 +193,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		),
 		sp_api::ApiError,
 	> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_validator_groups(at).await?)
 	}
 
@@ -148,6 +204,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		Vec<polkadot_primitives::CoreState<Hash, polkadot_core_primitives::BlockNumber>>,
 		sp_api::ApiError,
 	> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.availability_cores(at).await?)
+		}
 		Ok(self.rpc_client.parachain_host_availability_cores(at).await?)
 	}
 
@@ -157,6 +216,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		para_id: ParaId,
 		assumption: polkadot_primitives::OccupiedCoreAssumption,
 	) -> Result<Option<PersistedValidationData<Hash, BlockNumber>>, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.persisted_validation_data(at, para_id, assumption).await?)
+		}
 		Ok(self
 			.rpc_client
 			.parachain_host_persisted_validation_data(at, para_id, assumption)
@@ -175,6 +237,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		)>,
 		sp_api::ApiError,
 	> {
+		self.verify_block(at).await?;
 		Ok(self
 			.rpc_client
 			.parachain_host_assumed_validation_data(
@@ -191,6 +254,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		para_id: ParaId,
 		outputs: polkadot_primitives::CandidateCommitments,
 	) -> Result<bool, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self
 			.rpc_client
 			.parachain_host_check_validation_outputs(at, para_id, outputs)
@@ -201,6 +265,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		&self,
 		at: Hash,
 	) -> Result<polkadot_primitives::SessionIndex, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.session_index_for_child(at).await?)
+		}
 		Ok(self.rpc_client.parachain_host_session_index_for_child(at).await?)
 	}
 
@@ -210,7 +277,16 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		para_id: ParaId,
 		assumption: polkadot_primitives::OccupiedCoreAssumption,
 	) -> Result<Option<polkadot_primitives::ValidationCode>, sp_api::ApiError> {
-		Ok(self.rpc_client.parachain_host_validation_code(at, para_id, assumption).await?)
+		let Some(verifier) = &self.verifier else {
+			return Ok(self
+				.rpc_client
+				.parachain_host_validation_code(at, para_id, assumption)
+				.await?)
+		};
+		match verifier.validation_code_hash(at, para_id, assumption).await? {
+			Some(code_hash) => self.validation_code_by_hash(at, code_hash).await,
+			None => Ok(None),
+		}
 	}
 
 	async fn candidate_pending_availability(
@@ -218,6 +294,13 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		para_id: cumulus_primitives_core::ParaId,
 	) -> Result<Option<polkadot_primitives::CommittedCandidateReceiptV2<Hash>>, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier
+				.candidates_pending_availability(at, para_id)
+				.await?
+				.into_iter()
+				.next())
+		}
 		Ok(self
 			.rpc_client
 			.parachain_host_candidate_pending_availability(at, para_id)
@@ -228,6 +311,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		&self,
 		at: Hash,
 	) -> Result<Vec<polkadot_primitives::CandidateEvent<Hash>>, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.candidate_events(at).await?)
+		}
 		Ok(self.rpc_client.parachain_host_candidate_events(at).await?)
 	}
 
@@ -236,6 +322,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		recipient: ParaId,
 	) -> Result<Vec<InboundDownwardMessage<BlockNumber>>, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.retrieve_dmq_contents(recipient, at).await?)
+		}
 		Ok(self.rpc_client.parachain_host_dmq_contents(recipient, at).await?)
 	}
 
@@ -250,6 +339,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		>,
 		sp_api::ApiError,
 	> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.retrieve_all_inbound_hrmp_channel_contents(recipient, at).await?)
+		}
 		Ok(self
 			.rpc_client
 			.parachain_host_inbound_hrmp_channels_contents(recipient, at)
@@ -261,16 +353,26 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		validation_code_hash: polkadot_primitives::ValidationCodeHash,
 	) -> Result<Option<polkadot_primitives::ValidationCode>, sp_api::ApiError> {
-		Ok(self
+		self.verify_block(at).await?;
+		let code = self
 			.rpc_client
 			.parachain_host_validation_code_by_hash(at, validation_code_hash)
-			.await?)
+			.await?;
+		match code {
+			Some(code) if code.hash() != validation_code_hash =>
+				Err(RelayChainError::GenericError(format!(
+					"Relay chain returned validation code not matching the hash {validation_code_hash:?}"
+				))
+				.into()),
+			code => Ok(code),
+		}
 	}
 
 	async fn on_chain_votes(
 		&self,
 		at: Hash,
 	) -> Result<Option<polkadot_primitives::ScrapedOnChainVotes<Hash>>, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_on_chain_votes(at).await?)
 	}
 
@@ -279,6 +381,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		index: polkadot_primitives::SessionIndex,
 	) -> Result<Option<polkadot_primitives::SessionInfo>, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_session_info(at, index).await?)
 	}
 
@@ -287,6 +390,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		session_index: polkadot_primitives::SessionIndex,
 	) -> Result<Option<polkadot_primitives::ExecutorParams>, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self
 			.rpc_client
 			.parachain_host_session_executor_params(at, session_index)
@@ -299,6 +403,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		stmt: polkadot_primitives::PvfCheckStatement,
 		signature: polkadot_primitives::ValidatorSignature,
 	) -> Result<(), sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self
 			.rpc_client
 			.parachain_host_submit_pvf_check_statement(at, stmt, signature)
@@ -309,6 +414,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		&self,
 		at: Hash,
 	) -> Result<Vec<polkadot_primitives::ValidationCodeHash>, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_pvfs_require_precheck(at).await?)
 	}
 
@@ -318,6 +424,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		para_id: ParaId,
 		assumption: polkadot_primitives::OccupiedCoreAssumption,
 	) -> Result<Option<polkadot_primitives::ValidationCodeHash>, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.validation_code_hash(at, para_id, assumption).await?)
+		}
 		Ok(self
 			.rpc_client
 			.parachain_host_validation_code_hash(at, para_id, assumption)
@@ -325,6 +434,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 	}
 
 	async fn current_epoch(&self, at: Hash) -> Result<sp_consensus_babe::Epoch, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.babe_api_current_epoch(at).await?)
 	}
 
@@ -332,11 +442,13 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		&self,
 		at: Hash,
 	) -> std::result::Result<Vec<polkadot_primitives::AuthorityDiscoveryId>, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.authority_discovery_authorities(at).await?)
 	}
 
 	async fn api_version_parachain_host(&self, at: Hash) -> Result<Option<u32>, sp_api::ApiError> {
 		let api_id = <dyn polkadot_primitives::runtime_api::ParachainHost<Block>>::ID;
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.runtime_version(at).await.map(|v| v.api_version(&api_id))?)
 	}
 
@@ -351,6 +463,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		)>,
 		ApiError,
 	> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_disputes(at).await?)
 	}
 
@@ -365,6 +478,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		)>,
 		ApiError,
 	> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_unapplied_slashes(at).await?)
 	}
 
@@ -379,6 +493,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		)>,
 		ApiError,
 	> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_unapplied_slashes_v2(at).await?)
 	}
 
@@ -387,6 +502,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		validator_id: polkadot_primitives::ValidatorId,
 	) -> Result<Option<slashing::OpaqueKeyOwnershipProof>, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_key_ownership_proof(at, validator_id).await?)
 	}
 
@@ -396,6 +512,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		dispute_proof: slashing::DisputeProof,
 		key_ownership_proof: slashing::OpaqueKeyOwnershipProof,
 	) -> Result<Option<()>, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self
 			.rpc_client
 			.parachain_host_submit_report_dispute_lost(at, dispute_proof, key_ownership_proof)
@@ -407,6 +524,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		session_index: polkadot_primitives::SessionIndex,
 	) -> Result<u32, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_minimum_backing_votes(at, session_index).await?)
 	}
 
@@ -414,10 +532,12 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		&self,
 		at: Hash,
 	) -> Result<Vec<polkadot_primitives::ValidatorIndex>, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_disabled_validators(at).await?)
 	}
 
 	async fn async_backing_params(&self, at: Hash) -> Result<AsyncBackingParams, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_async_backing_params(at).await?)
 	}
 
@@ -426,6 +546,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		para_id: ParaId,
 	) -> Result<Option<BackingState>, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_para_backing_state(at, para_id).await?)
 	}
 
@@ -435,6 +556,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		session_index: polkadot_primitives::SessionIndex,
 	) -> Result<ApprovalVotingParams, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self
 			.rpc_client
 			.parachain_host_staging_approval_voting_params(at, session_index)
@@ -442,6 +564,7 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 	}
 
 	async fn node_features(&self, at: Hash) -> Result<NodeFeatures, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_node_features(at).await?)
 	}
 
@@ -449,6 +572,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		&self,
 		at: Hash,
 	) -> Result<BTreeMap<CoreIndex, VecDeque<ParaId>>, ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.claim_queue(at).await?)
+		}
 		Ok(self.rpc_client.parachain_host_claim_queue(at).await?)
 	}
 
@@ -457,6 +583,9 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		para_id: cumulus_primitives_core::ParaId,
 	) -> Result<Vec<polkadot_primitives::CommittedCandidateReceiptV2<Hash>>, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.candidates_pending_availability(at, para_id).await?)
+		}
 		Ok(self
 			.rpc_client
 			.parachain_host_candidates_pending_availability(at, para_id)
@@ -468,18 +597,24 @@ impl RuntimeApiSubsystemClient for BlockChainRpcClient {
 		at: Hash,
 		para_id: ParaId,
 	) -> Result<Option<Constraints>, ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_backing_constraints(at, para_id).await?)
 	}
 
 	async fn scheduling_lookahead(&self, at: Hash) -> Result<u32, sp_api::ApiError> {
+		if let Some(verifier) = &self.verifier {
+			return Ok(verifier.scheduling_lookahead(at).await?)
+		}
 		Ok(self.rpc_client.parachain_host_scheduling_lookahead(at).await?)
 	}
 
 	async fn validation_code_bomb_limit(&self, at: Hash) -> Result<u32, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_validation_code_bomb_limit(at).await?)
 	}
 
 	async fn para_ids(&self, at: Hash) -> Result<Vec<ParaId>, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		Ok(self.rpc_client.parachain_host_para_ids(at).await?)
 	}
 }
@@ -490,6 +625,7 @@ impl AuthorityDiscovery<Block> for BlockChainRpcClient {
 		&self,
 		at: Hash,
 	) -> std::result::Result<Vec<polkadot_primitives::AuthorityDiscoveryId>, sp_api::ApiError> {
+		self.verify_block(at).await?;
 		let result = self.rpc_client.authority_discovery_authorities(at).await?;
 		Ok(result)
 	}
@@ -507,12 +643,18 @@ impl BlockChainRpcClient {
 	pub async fn import_notification_stream(
 		&self,
 	) -> RelayChainResult<Pin<Box<dyn Stream<Item = Header> + Send>>> {
+		if let Some(verifier) = &self.verifier {
+			return verifier.import_notification_stream().await
+		}
 		Ok(self.rpc_client.get_imported_heads_stream()?.boxed())
 	}
 
 	pub async fn finality_notification_stream(
 		&self,
 	) -> RelayChainResult<Pin<Box<dyn Stream<Item = Header> + Send>>> {
+		if let Some(verifier) = &self.verifier {
+			return verifier.finality_notification_stream().await
+		}
 		Ok(self.rpc_client.get_finalized_heads_stream()?.boxed())
 	}
 }
@@ -48,11 +48,11 @@ fn build_overseer(
 
 pub(crate) fn spawn_overseer(
 	overseer_args: OverseerGenArgs<sc_service::SpawnTaskHandle, BlockChainRpcClient>,
+	connector: OverseerConnector,
 	task_manager: &TaskManager,
 	relay_chain_rpc_client: Arc<BlockChainRpcClient>,
-) -> Result<polkadot_overseer::Handle, RelayChainError> {
-	let (overseer, overseer_handle) = build_overseer(OverseerConnector::default(), overseer_args)
-		.map_err(|e| {
+) -> Result<(), RelayChainError> {
+	let (overseer, overseer_handle) = build_overseer(connector, overseer_args).map_err(|e| {
 		tracing::error!("Failed to initialize overseer: {}", e);
 		e
 	})?;
@@ -80,15 +80,13 @@ pub(crate) fn spawn_overseer(
 			}),
 		);
 	}
-	Ok(overseer_handle)
+	Ok(())
 }
 
 /// Minimal relay chain node representation
 pub struct NewMinimalNode {
 	/// Task manager running all tasks for the minimal node
 	pub task_manager: TaskManager,
-	/// Overseer handle to interact with subsystems
-	pub overseer_handle: Handle,
 	/// Network service
 	pub network_service: Arc<dyn NetworkService>,
 	/// Parachain bootnode request-response protocol receiver
@@ -19,6 +19,9 @@ use collator_overseer::NewMinimalNode;
 
 use cumulus_client_bootnodes::bootnode_request_response_config;
 use cumulus_relay_chain_interface::{RelayChainError, RelayChainInterface, RelayChainResult};
+use cumulus_relay_chain_light_client_interface::{
+	LightClientCheckpoint, LightClientRelayChainInterface,
+};
 use cumulus_relay_chain_rpc_interface::{RelayChainRpcClient, RelayChainRpcInterface, Url};
 use network::build_collator_network;
 use polkadot_network_bridge::{peer_sets_info, IsAuthority};
@@ -31,6 +34,7 @@ use polkadot_node_network_protocol::{
 
 use polkadot_core_primitives::{Block as RelayBlock, Hash as RelayHash};
 use polkadot_node_subsystem_util::metrics::prometheus::Registry;
+use polkadot_overseer::{Handle as OverseerHandle, OverseerConnector};
 use polkadot_primitives::CollatorPair;
 use polkadot_service::{overseer::OverseerGenArgs, IsParachainNode};
 
@@ -99,6 +103,7 @@ async fn build_interface(
 	polkadot_config: Configuration,
 	task_manager: &mut TaskManager,
 	client: RelayChainRpcClient,
+	light_client_checkpoint: Option<LightClientCheckpoint>,
 ) -> RelayChainResult<(
 	Arc<dyn RelayChainInterface + 'static>,
 	Option<CollatorPair>,
@@ -106,13 +111,31 @@ async fn build_interface(
 	async_channel::Receiver<GenericIncomingRequest>,
 )> {
 	let collator_pair = CollatorPair::generate().0;
-	let blockchain_rpc_client = Arc::new(BlockChainRpcClient::new(client.clone()));
+	// The relay chain interface is built before the node, so that the subsystems of the node can
+	// retrieve relay chain data from it.
+	let overseer_connector = OverseerConnector::default();
+	let overseer_handle = OverseerHandle::new(overseer_connector.handle());
+	let rpc_interface = RelayChainRpcInterface::new(client.clone(), overseer_handle);
+	let (relay_chain_interface, blockchain_rpc_client): (Arc<dyn RelayChainInterface>, _) =
+		match light_client_checkpoint {
+			Some(checkpoint) => {
+				let interface = Arc::new(LightClientRelayChainInterface::new(
+					rpc_interface,
+					BlockChainRpcClient::new(client.clone()),
+					checkpoint,
+				));
+				(interface.clone(), BlockChainRpcClient::new(client).with_verifier(interface))
+			},
+			None => (Arc::new(rpc_interface), BlockChainRpcClient::new(client)),
+		};
+	let blockchain_rpc_client = Arc::new(blockchain_rpc_client);
 	let collator_node = match polkadot_config.network.network_backend {
 		sc_network::config::NetworkBackendType::Libp2p =>
 			new_minimal_relay_chain::<RelayBlock, sc_network::NetworkWorker<RelayBlock, RelayHash>>(
 				polkadot_config,
 				collator_pair.clone(),
 				blockchain_rpc_client,
+				overseer_connector,
 			)
 			.await?,
 		sc_network::config::NetworkBackendType::Litep2p =>
@@ -120,12 +143,13 @@ async fn build_interface(
 				polkadot_config,
 				collator_pair.clone(),
 				blockchain_rpc_client,
+				overseer_connector,
 			)
 			.await?,
 	};
 	task_manager.add_child(collator_node.task_manager);
 	Ok((
-		Arc::new(RelayChainRpcInterface::new(client, collator_node.overseer_handle)),
+		relay_chain_interface,
 		Some(collator_pair),
 		collator_node.network_service,
 		collator_node.paranode_rx,
@@ -150,7 +174,33 @@ pub async fn build_minimal_relay_chain_node_with_rpc(
 	)
 	.await?;
 
-	build_interface(relay_chain_config, task_manager, client).await
+	build_interface(relay_chain_config, task_manager, client, None).await
+}
+
+/// Builds a minimal relay chain node retrieving relay chain data from untrusted RPC nodes.
+///
+/// The data used by the parachain node is verified from the given `checkpoint`, see
+/// [`LightClientRelayChainInterface`].
+pub async fn build_minimal_relay_chain_node_with_light_client(
+	relay_chain_config: Configuration,
+	parachain_prometheus_registry: Option<&Registry>,
+	task_manager: &mut TaskManager,
+	relay_chain_url: Vec<Url>,
+	checkpoint: LightClientCheckpoint,
+) -> RelayChainResult<(
+	Arc<dyn RelayChainInterface + 'static>,
+	Option<CollatorPair>,
+	Arc<dyn NetworkService>,
+	async_channel::Receiver<GenericIncomingRequest>,
+)> {
+	let client = cumulus_relay_chain_rpc_interface::create_client_and_start_worker(
+		relay_chain_url,
+		task_manager,
+		parachain_prometheus_registry,
+	)
+	.await?;
+
+	build_interface(relay_chain_config, task_manager, client, Some(checkpoint)).await
 }
 
 /// Builds a minimal relay chain node. Chain data is fetched
@@ -169,6 +219,7 @@ async fn new_minimal_relay_chain<Block: BlockT, Network: NetworkBackend<RelayBlo
 	config: Configuration,
 	collator_pair: CollatorPair,
 	relay_chain_rpc_client: Arc<BlockChainRpcClient>,
+	overseer_connector: OverseerConnector,
 ) -> Result<NewMinimalNode, RelayChainError> {
 	let role = config.role;
 	let mut net_config = sc_network::config::FullNetworkConfiguration::<_, _, Network>::new(
@@ -257,10 +308,14 @@ async fn new_minimal_relay_chain<Block: BlockT, Network: NetworkBackend<RelayBlo
 		notification_services,
 	};
 
-	let overseer_handle =
-		collator_overseer::spawn_overseer(overseer_args, &task_manager, relay_chain_rpc_client)?;
+	collator_overseer::spawn_overseer(
+		overseer_args,
+		overseer_connector,
+		&task_manager,
+		relay_chain_rpc_client,
+	)?;
 
-	Ok(NewMinimalNode { task_manager, overseer_handle, network_service: network, paranode_rx })
+	Ok(NewMinimalNode { task_manager, network_service: network, paranode_rx })
 }
 
 fn build_request_response_protocol_receivers<
@@ -289,4 +289,8 @@ impl RelayChainInterface for RelayChainRpcInterface {
 	) -> RelayChainResult<Vec<CandidateEvent>> {
 		self.rpc_client.parachain_host_candidate_events(relay_parent).await
 	}
+
+	async fn submit_extrinsic(&self, extrinsic: Vec<u8>) -> RelayChainResult<RelayHash> {
+		self.rpc_client.author_submit_extrinsic(extrinsic.into()).await
+	}
 }
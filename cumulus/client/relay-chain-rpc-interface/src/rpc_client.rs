@@ -266,6 +266,14 @@ impl RelayChainRpcClient {
 		self.request("system_health", rpc_params![]).await
 	}
 
+	/// Submit an extrinsic to the transaction pool.
+	pub async fn author_submit_extrinsic(
+		&self,
+		extrinsic: sp_core::Bytes,
+	) -> Result<RelayHash, RelayChainError> {
+		self.request("author_submitExtrinsic", rpc_params![extrinsic]).await
+	}
+
 	/// Get read proof for `storage_keys`
 	pub async fn state_get_read_proof(
 		&self,
@@ -360,6 +368,15 @@ impl RelayChainRpcClient {
 		self.request("chain_getBlockHash", params).await
 	}
 
+	/// Get the encoded GRANDPA finality proof of a block at or after `block_number`.
+	pub async fn grandpa_prove_finality(
+		&self,
+		block_number: BlockNumber,
+	) -> Result<Option<sp_core::Bytes>, RelayChainError> {
+		let params = rpc_params![block_number];
+		self.request("grandpa_proveFinality", params).await
+	}
+
 	/// Yields the persisted validation data for the given `ParaId` along with an assumption that
 	/// should be used if the para currently occupies a core.
 	///
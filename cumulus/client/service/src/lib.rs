@@ -26,7 +26,10 @@ use cumulus_primitives_core::{CollectCollationInfo, ParaId};
 pub use cumulus_primitives_proof_size_hostfunction::storage_proof_size;
 use cumulus_relay_chain_inprocess_interface::build_inprocess_relay_chain;
 use cumulus_relay_chain_interface::{RelayChainInterface, RelayChainResult};
-use cumulus_relay_chain_minimal_node::build_minimal_relay_chain_node_with_rpc;
+use cumulus_relay_chain_light_client_interface::LightClientCheckpoint;
+use cumulus_relay_chain_minimal_node::{
+	build_minimal_relay_chain_node_with_light_client, build_minimal_relay_chain_node_with_rpc,
+};
 use futures::{channel::mpsc, StreamExt};
 use polkadot_primitives::{CandidateEvent, CollatorPair, OccupiedCoreAssumption};
 use prometheus::{Histogram, HistogramOpts, Registry};
@@ -219,8 +222,8 @@ pub fn prepare_node_config(mut parachain_config: Configuration) -> Configuration
 }
 
 /// Build a relay chain interface.
-/// Will return a minimal relay chain node with RPC
-/// client or an inprocess node, based on the [`CollatorOptions`] passed in.
+/// Will return a minimal relay chain node with RPC client, optionally verifying the relay chain
+/// data like a light client, or an inprocess node, based on the [`CollatorOptions`] passed in.
 pub async fn build_relay_chain_interface(
 	relay_chain_config: Configuration,
 	parachain_config: &Configuration,
@@ -250,6 +253,15 @@ pub async fn build_relay_chain_interface(
 				rpc_target_urls,
 			)
 			.await,
+		cumulus_client_cli::RelayChainMode::LightClient { rpc_urls, checkpoint } =>
+			build_minimal_relay_chain_node_with_light_client(
+				relay_chain_config,
+				parachain_config.prometheus_registry(),
+				task_manager,
+				rpc_urls,
+				LightClientCheckpoint::from_json_file(&checkpoint)?,
+			)
+			.await,
 	}
 }
 
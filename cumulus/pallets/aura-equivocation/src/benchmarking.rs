@@ -0,0 +1,77 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Benchmarking setup for cumulus-pallet-aura-equivocation
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_system::RawOrigin;
+use sp_runtime::traits::{Hash, One};
+
+type AuthoritySignature<T> =
+	<<T as pallet_aura::Config>::AuthorityId as RuntimeAppPublic>::Signature;
+
+/// Builds a header of `slot` sealed by `authority`, which differs from the other headers built by
+/// this function by `state_root`.
+fn sealed_header<T: Config>(
+	authority: &T::AuthorityId,
+	slot: Slot,
+	state_root: u8,
+) -> HeaderFor<T> {
+	let mut header = HeaderFor::<T>::new(
+		One::one(),
+		Default::default(),
+		T::Hashing::hash(&[state_root]),
+		Default::default(),
+		Default::default(),
+	);
+	header
+		.digest_mut()
+		.push(<DigestItem as CompatibleDigestItem<AuthoritySignature<T>>>::aura_pre_digest(slot));
+	header
+		.digest_mut()
+		.push(CumulusDigestItem::RelayParent(Default::default()).to_digest_item());
+
+	let signature = authority.sign(&header.hash()).expect("the offender key is in the keystore");
+	header
+		.digest_mut()
+		.push(<DigestItem as CompatibleDigestItem<AuthoritySignature<T>>>::aura_seal(signature));
+	header
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn report_equivocation() {
+		let (offender, account) = T::BenchmarkHelper::setup_offender();
+		let slot = pallet_aura::CurrentSlot::<T>::get();
+		let equivocation_proof = EquivocationProof {
+			offender: offender.clone(),
+			slot,
+			first_header: sealed_header::<T>(&offender, slot, 1),
+			second_header: sealed_header::<T>(&offender, slot, 2),
+		};
+
+		#[extrinsic_call]
+		report_equivocation_unsigned(RawOrigin::None, Box::new(equivocation_proof));
+
+		assert_eq!(LastReportedSlot::<T>::get(&account), Some(slot));
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,355 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Equivocation reporting for Aura collators.
+//!
+//! A collator equivocates when it authors two different blocks in the same slot, on top of the
+//! same parent and the same relay parent. As parachain authors may author several consecutive
+//! blocks per slot, blocks of the same slot only conflict when they build on the same parent.
+//!
+//! Collators detecting an equivocation while importing blocks report it through the
+//! [`AuraEquivocationApi`](cumulus_primitives_aura::AuraEquivocationApi) runtime API, which should
+//! call [`Pallet::submit_unsigned_equivocation_report`]. The report is an unsigned extrinsic that
+//! is only accepted from the local node, so it is only included by the collator that detected the
+//! equivocation.
+//!
+//! The pallet checks the seals of both headers against the offender, resolves the offender to an
+//! account with [`Config::KeyOwner`] and hands it to [`Config::HandleEquivocation`]. Runtimes
+//! using `pallet-collator-selection` can implement the handler with its `slash_candidate`, which
+//! removes the offending candidate and moves its bond to the pot. Candidates which left recently
+//! are slashed as well, as long as their deposits are unbonding, so its `UnbondingSessions` should
+//! cover [`Config::ReportLongevity`].
+//!
+//! IMPORTANT: the `ValidateUnsigned` of this pallet must be part of the runtime definition for the
+//! reports to be accepted.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use alloc::boxed::Box;
+use core::marker::PhantomData;
+use cumulus_primitives_core::CumulusDigestItem;
+use frame_support::{dispatch::DispatchResult, pallet_prelude::*};
+use frame_system::pallet_prelude::*;
+use sp_consensus_aura::{digests::CompatibleDigestItem, Slot};
+use sp_runtime::{
+	traits::{Convert, Header as HeaderT},
+	transaction_validity::{
+		InvalidTransaction, TransactionPriority, TransactionSource, TransactionValidity,
+		TransactionValidityError, ValidTransaction,
+	},
+	DigestItem, RuntimeAppPublic,
+};
+
+pub use cumulus_primitives_aura::EquivocationProof;
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+pub mod weights;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "runtime::aura-equivocation";
+
+/// Handles the equivocations proven on chain.
+pub trait HandleEquivocation<AccountId> {
+	/// Punishes `offender` for authoring conflicting blocks in `slot`.
+	fn handle_equivocation(offender: &AccountId, slot: Slot) -> DispatchResult;
+}
+
+impl<AccountId> HandleEquivocation<AccountId> for () {
+	fn handle_equivocation(_: &AccountId, _: Slot) -> DispatchResult {
+		Ok(())
+	}
+}
+
+/// Sets up the offender of the benchmarked equivocation reports.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper<AuthorityId, AccountId> {
+	/// Returns a new authority, whose private key is in the keystore, and the account it resolves
+	/// to with [`Config::KeyOwner`].
+	///
+	/// The account should be punished by [`Config::HandleEquivocation`], e.g. by being a candidate
+	/// of the collator selection.
+	fn setup_offender() -> (AuthorityId, AccountId);
+}
+
+/// Resolves Aura authorities to the accounts that set them as their session keys.
+pub struct SessionKeyOwner<T>(PhantomData<T>);
+
+impl<T> Convert<T::AuthorityId, Option<T::AccountId>> for SessionKeyOwner<T>
+where
+	T: pallet_aura::Config
+		+ pallet_session::Config<ValidatorId = <T as frame_system::Config>::AccountId>,
+{
+	fn convert(authority: T::AuthorityId) -> Option<T::AccountId> {
+		pallet_session::Pallet::<T>::key_owner(
+			<T::AuthorityId as RuntimeAppPublic>::ID,
+			&authority.to_raw_vec(),
+		)
+	}
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_aura::Config {
+		/// Overarching event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Resolves the offending authority to its account.
+		///
+		/// [`SessionKeyOwner`] resolves the authority to the owner of the session keys.
+		type KeyOwner: Convert<Self::AuthorityId, Option<Self::AccountId>>;
+
+		/// Punishes the offenders of the equivocations proven on chain.
+		type HandleEquivocation: HandleEquivocation<Self::AccountId>;
+
+		/// The number of slots an equivocation can be reported for after it happened.
+		///
+		/// This is also the longevity of the unsigned reports in the transaction pool.
+		#[pallet::constant]
+		type ReportLongevity: Get<u64>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+
+		/// Sets up the offender of the benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper<Self::AuthorityId, Self::AccountId>;
+	}
+
+	/// The slot of the last equivocation reported for each offender.
+	#[pallet::storage]
+	pub type LastReportedSlot<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AccountId, Slot, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// An equivocation of `offender` in `slot` was reported.
+		EquivocationReported { offender: T::AccountId, slot: Slot },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The headers don't prove an equivocation of the offender in the slot.
+		InvalidEquivocationProof,
+		/// The slot of the equivocation is in the future or too old to be reported.
+		OutdatedReport,
+		/// The offender doesn't resolve to an account.
+		UnknownOffender,
+		/// An equivocation of the offender was already reported for this or a later slot.
+		DuplicateReport,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Report a collator authoring conflicting blocks in the same slot.
+		///
+		/// This extrinsic must be called unsigned and it is expected that only block authors will
+		/// call it (validated in `ValidateUnsigned`). The offender is punished by
+		/// [`Config::HandleEquivocation`].
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::report_equivocation())]
+		pub fn report_equivocation_unsigned(
+			origin: OriginFor<T>,
+			equivocation_proof: Box<EquivocationProof<HeaderFor<T>, T::AuthorityId>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_none(origin)?;
+
+			let slot = equivocation_proof.slot;
+			let offender = Self::check_evidence(&equivocation_proof)?;
+			LastReportedSlot::<T>::insert(&offender, slot);
+
+			// The report is recorded even if the offender can't be punished, e.g. because it is
+			// invulnerable, so that it isn't reported again.
+			if let Err(error) = T::HandleEquivocation::handle_equivocation(&offender, slot) {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to punish the equivocation of {:?} in slot {:?}: {:?}",
+					offender,
+					slot,
+					error,
+				);
+			}
+
+			Self::deposit_event(Event::EquivocationReported { offender, slot });
+			Ok(Pays::No.into())
+		}
+	}
+
+	#[pallet::validate_unsigned]
+	impl<T: Config> ValidateUnsigned for Pallet<T> {
+		type Call = Call<T>;
+
+		fn validate_unsigned(source: TransactionSource, call: &Self::Call) -> TransactionValidity {
+			let Call::report_equivocation_unsigned { equivocation_proof } = call else {
+				return InvalidTransaction::Call.into()
+			};
+
+			// Discard equivocation reports not coming from the local node.
+			match source {
+				TransactionSource::Local | TransactionSource::InBlock => { /* allowed */ },
+				_ => {
+					log::warn!(
+						target: LOG_TARGET,
+						"Rejecting unsigned equivocation report because it is not local/in-block.",
+					);
+
+					return InvalidTransaction::Call.into()
+				},
+			}
+
+			let offender = Self::check_evidence(equivocation_proof).map_err(invalid_transaction)?;
+
+			ValidTransaction::with_tag_prefix("AuraEquivocation")
+				// We assign the maximum priority for any equivocation report.
+				.priority(TransactionPriority::MAX)
+				// Only one equivocation report for the same offender at the same slot.
+				.and_provides((offender, equivocation_proof.slot))
+				.longevity(T::ReportLongevity::get())
+				// We don't propagate this. This can never be included on a remote node.
+				.propagate(false)
+				.build()
+		}
+
+		fn pre_dispatch(call: &Self::Call) -> Result<(), TransactionValidityError> {
+			let Call::report_equivocation_unsigned { equivocation_proof } = call else {
+				return Err(InvalidTransaction::Call.into())
+			};
+
+			Self::check_evidence(equivocation_proof)
+				.map(|_| ())
+				.map_err(invalid_transaction)
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Checks that `equivocation_proof` can be reported, returning the account of the offender.
+	fn check_evidence(
+		equivocation_proof: &EquivocationProof<HeaderFor<T>, T::AuthorityId>,
+	) -> Result<T::AccountId, Error<T>> {
+		let slot = equivocation_proof.slot;
+		let current_slot = pallet_aura::CurrentSlot::<T>::get();
+		if slot > current_slot || slot.saturating_add(T::ReportLongevity::get()) < current_slot {
+			return Err(Error::<T>::OutdatedReport)
+		}
+
+		if !check_equivocation_proof(equivocation_proof) {
+			return Err(Error::<T>::InvalidEquivocationProof)
+		}
+
+		let offender = T::KeyOwner::convert(equivocation_proof.offender.clone())
+			.ok_or(Error::<T>::UnknownOffender)?;
+		if LastReportedSlot::<T>::get(&offender).is_some_and(|last| last >= slot) {
+			return Err(Error::<T>::DuplicateReport)
+		}
+
+		Ok(offender)
+	}
+}
+
+impl<T: Config + frame_system::offchain::CreateBare<Call<T>>> Pallet<T> {
+	/// Submits an extrinsic to report an equivocation. This method will create an unsigned
+	/// extrinsic with a call to `report_equivocation_unsigned` and will push the transaction to
+	/// the pool. Only useful in an offchain context.
+	pub fn submit_unsigned_equivocation_report(
+		equivocation_proof: EquivocationProof<HeaderFor<T>, T::AuthorityId>,
+	) -> Option<()> {
+		use frame_system::offchain::SubmitTransaction;
+
+		let call =
+			Call::report_equivocation_unsigned { equivocation_proof: Box::new(equivocation_proof) };
+		let xt = T::create_bare(call.into());
+		match SubmitTransaction::<T, Call<T>>::submit_transaction(xt) {
+			Ok(()) => {
+				log::info!(target: LOG_TARGET, "Submitted equivocation report");
+				Some(())
+			},
+			Err(()) => {
+				log::error!(target: LOG_TARGET, "Error submitting equivocation report");
+				None
+			},
+		}
+	}
+}
+
+/// Maps the errors of an equivocation report to the validity of its transaction.
+fn invalid_transaction<T>(error: Error<T>) -> TransactionValidityError {
+	match error {
+		Error::<T>::OutdatedReport | Error::<T>::DuplicateReport => InvalidTransaction::Stale,
+		_ => InvalidTransaction::BadProof,
+	}
+	.into()
+}
+
+/// Verifies the equivocation proof by making sure that both headers are different, are sealed by
+/// the offender for the slot of the proof and build on the same parent and relay parent.
+///
+/// Headers which don't identify their relay parent never prove an equivocation.
+pub fn check_equivocation_proof<H, AuthorityId>(
+	equivocation_proof: &EquivocationProof<H, AuthorityId>,
+) -> bool
+where
+	H: HeaderT,
+	AuthorityId: RuntimeAppPublic,
+{
+	let check_header = |header: &H| {
+		let mut header = header.clone();
+		let seal = header.digest_mut().pop()?;
+		let signature =
+			<DigestItem as CompatibleDigestItem<AuthorityId::Signature>>::as_aura_seal(&seal)?;
+		let slot = header.digest().logs().iter().find_map(|log| {
+			<DigestItem as CompatibleDigestItem<AuthorityId::Signature>>::as_aura_pre_digest(log)
+		})?;
+		if slot != equivocation_proof.slot ||
+			!equivocation_proof.offender.verify(&header.hash().as_ref(), &signature)
+		{
+			return None
+		}
+
+		// Blocks without a relay parent can't be told to conflict.
+		CumulusDigestItem::find_relay_block_identifier(header.digest())
+	};
+
+	let EquivocationProof { first_header, second_header, .. } = equivocation_proof;
+	if first_header.hash() == second_header.hash() ||
+		first_header.parent_hash() != second_header.parent_hash()
+	{
+		return false
+	}
+
+	match (check_header(first_header), check_header(second_header)) {
+		(Some(first_relay_parent), Some(second_relay_parent)) =>
+			first_relay_parent == second_relay_parent,
+		_ => false,
+	}
+}
@@ -0,0 +1,263 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{
+	self as aura_equivocation, Config, EquivocationProof, HandleEquivocation, SessionKeyOwner,
+};
+use cumulus_primitives_core::{relay_chain, CumulusDigestItem};
+use frame_support::{
+	derive_impl,
+	dispatch::DispatchResult,
+	parameter_types,
+	traits::{ConstBool, ConstU32, ConstU64},
+	PalletId,
+};
+use frame_system::{pallet_prelude::HeaderFor, EnsureRoot};
+use sp_consensus_aura::{
+	digests::CompatibleDigestItem,
+	sr25519::{AuthorityId, AuthoritySignature},
+	Slot,
+};
+use sp_core::{Pair, H256};
+use sp_keyring::Sr25519Keyring;
+use sp_keystore::{testing::MemoryKeystore, KeystoreExt};
+use sp_runtime::{
+	traits::{Header as _, OpaqueKeys},
+	BuildStorage, DigestItem,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Timestamp: pallet_timestamp,
+		Balances: pallet_balances,
+		CollatorSelection: pallet_collator_selection,
+		Session: pallet_session,
+		Aura: pallet_aura,
+		AuraEquivocation: aura_equivocation,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+impl pallet_timestamp::Config for Test {
+	type Moment = u64;
+	type OnTimestampSet = Aura;
+	type MinimumPeriod = ConstU64<1>;
+	type WeightInfo = ();
+}
+
+impl pallet_aura::Config for Test {
+	type AuthorityId = AuthorityId;
+	type MaxAuthorities = ConstU32<100>;
+	type DisabledValidators = ();
+	type AllowMultipleBlocksPerSlot = ConstBool<true>;
+	type SlotDuration = pallet_aura::MinimumPeriodTimesTwo<Self>;
+}
+
+sp_runtime::impl_opaque_keys! {
+	pub struct MockSessionKeys {
+		pub aura: Aura,
+	}
+}
+
+parameter_types! {
+	pub const Period: u64 = 10;
+	pub const Offset: u64 = 0;
+	pub const PotId: PalletId = PalletId(*b"PotStake");
+}
+
+impl pallet_session::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type ValidatorId = u64;
+	type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
+	type ShouldEndSession = pallet_session::PeriodicSessions<Period, Offset>;
+	type NextSessionRotation = pallet_session::PeriodicSessions<Period, Offset>;
+	type SessionManager = CollatorSelection;
+	type SessionHandler = <MockSessionKeys as OpaqueKeys>::KeyTypeIdProviders;
+	type Keys = MockSessionKeys;
+	type DisablingStrategy = ();
+	type WeightInfo = ();
+	type Currency = Balances;
+	type KeyDeposit = ();
+}
+
+impl pallet_collator_selection::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type Currency = Balances;
+	type UpdateOrigin = EnsureRoot<u64>;
+	type PotId = PotId;
+	type MaxCandidates = ConstU32<20>;
+	type MinEligibleCollators = ConstU32<1>;
+	type MaxInvulnerables = ConstU32<20>;
+	type KickThreshold = Period;
+	type ValidatorId = u64;
+	type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
+	type ValidatorRegistration = Session;
+	type UnbondingSessions = ConstU32<3>;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const ReportLongevity: u64 = 10;
+}
+
+/// Slashes the collator selection candidates reported for equivocating.
+pub struct SlashCandidate;
+impl HandleEquivocation<u64> for SlashCandidate {
+	fn handle_equivocation(offender: &u64, _: Slot) -> DispatchResult {
+		CollatorSelection::slash_candidate(offender).map(|_| ())
+	}
+}
+
+impl Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type KeyOwner = SessionKeyOwner<Self>;
+	type HandleEquivocation = SlashCandidate;
+	type ReportLongevity = ReportLongevity;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = BenchmarkHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct BenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::BenchmarkHelper<AuthorityId, u64> for BenchmarkHelper {
+	fn setup_offender() -> (AuthorityId, u64) {
+		use codec::Encode;
+		use frame_support::traits::Currency;
+
+		let offender = 4;
+		let keys = MockSessionKeys::generate(&offender.encode(), None);
+		Balances::make_free_balance_be(&offender, 100);
+		Session::set_keys(RuntimeOrigin::signed(offender), keys.keys.clone(), keys.proof.encode())
+			.unwrap();
+		CollatorSelection::register_as_candidate(RuntimeOrigin::signed(offender)).unwrap();
+		(keys.keys.aura, offender)
+	}
+}
+
+/// The collators of the mock runtime, by account.
+pub const COLLATORS: [(u64, Sr25519Keyring); 3] =
+	[(1, Sr25519Keyring::Alice), (2, Sr25519Keyring::Bob), (3, Sr25519Keyring::Charlie)];
+
+/// The candidacy bond of the collator selection.
+pub const CANDIDACY_BOND: u64 = 10;
+
+/// Returns the test externalities with `1` as an invulnerable, and `2` and `3` having session
+/// keys but not being candidates yet.
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+
+	pallet_balances::GenesisConfig::<Test> {
+		balances: COLLATORS
+			.iter()
+			.map(|(account, _)| (*account, 100))
+			.chain([(CollatorSelection::account_id(), 1)])
+			.collect(),
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	pallet_collator_selection::GenesisConfig::<Test> {
+		desired_candidates: 2,
+		candidacy_bond: CANDIDACY_BOND,
+		invulnerables: vec![1],
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+	pallet_session::GenesisConfig::<Test> {
+		keys: COLLATORS
+			.iter()
+			.map(|(account, keyring)| {
+				(*account, *account, MockSessionKeys { aura: keyring.public().into() })
+			})
+			.collect(),
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.register_extension(KeystoreExt::new(MemoryKeystore::new()));
+	ext.execute_with(|| {
+		System::set_block_number(1);
+		pallet_aura::CurrentSlot::<Test>::put(Slot::from(100));
+	});
+	ext
+}
+
+/// Builds a header of `slot` on top of `parent` and `relay_parent`, if any, sealed by `author`.
+pub fn sealed_header(
+	author: Sr25519Keyring,
+	slot: u64,
+	parent: H256,
+	relay_parent: Option<relay_chain::Hash>,
+	state_root: H256,
+) -> HeaderFor<Test> {
+	let mut header =
+		HeaderFor::<Test>::new(2, Default::default(), state_root, parent, Default::default());
+	header.digest_mut().push(
+		<DigestItem as CompatibleDigestItem<AuthoritySignature>>::aura_pre_digest(Slot::from(slot)),
+	);
+	if let Some(relay_parent) = relay_parent {
+		header
+			.digest_mut()
+			.push(CumulusDigestItem::RelayParent(relay_parent).to_digest_item());
+	}
+
+	let signature: AuthoritySignature = author.pair().sign(header.hash().as_ref()).into();
+	header
+		.digest_mut()
+		.push(<DigestItem as CompatibleDigestItem<AuthoritySignature>>::aura_seal(signature));
+	header
+}
+
+/// Builds the proof of `offender` authoring two blocks in `slot` on top of the same parent.
+pub fn equivocation_proof(
+	offender: Sr25519Keyring,
+	slot: u64,
+) -> EquivocationProof<HeaderFor<Test>, AuthorityId> {
+	let parent = H256::repeat_byte(1);
+	let relay_parent = Some(H256::repeat_byte(2));
+	EquivocationProof {
+		offender: offender.public().into(),
+		slot: Slot::from(slot),
+		first_header: sealed_header(offender, slot, parent, relay_parent, H256::repeat_byte(3)),
+		second_header: sealed_header(offender, slot, parent, relay_parent, H256::repeat_byte(4)),
+	}
+}
+
+/// Returns the call reporting `equivocation_proof`.
+pub fn report_call(
+	equivocation_proof: EquivocationProof<HeaderFor<Test>, AuthorityId>,
+) -> crate::Call<Test> {
+	crate::Call::report_equivocation_unsigned { equivocation_proof: Box::new(equivocation_proof) }
+}
@@ -0,0 +1,210 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::{check_equivocation_proof, mock::*, Error, Event, LastReportedSlot};
+use frame_support::{assert_noop, assert_ok, dispatch::Pays};
+use sp_consensus_aura::Slot;
+use sp_core::H256;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::{
+	traits::{Header as _, ValidateUnsigned},
+	transaction_validity::{InvalidTransaction, TransactionSource},
+};
+
+fn report(
+	equivocation_proof: crate::EquivocationProof<
+		frame_system::pallet_prelude::HeaderFor<Test>,
+		sp_consensus_aura::sr25519::AuthorityId,
+	>,
+) -> frame_support::dispatch::DispatchResultWithPostInfo {
+	AuraEquivocation::report_equivocation_unsigned(
+		RuntimeOrigin::none(),
+		Box::new(equivocation_proof),
+	)
+}
+
+#[test]
+fn report_equivocation_slashes_candidate() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(2)));
+		let pot = CollatorSelection::account_id();
+		let pot_balance = Balances::free_balance(pot);
+
+		let post_info = report(equivocation_proof(Sr25519Keyring::Bob, 99)).unwrap();
+		assert_eq!(post_info.pays_fee, Pays::No);
+
+		assert!(pallet_collator_selection::CandidateList::<Test>::get().is_empty());
+		assert_eq!(Balances::reserved_balance(2), 0);
+		assert_eq!(Balances::free_balance(pot), pot_balance + CANDIDACY_BOND);
+		assert_eq!(LastReportedSlot::<Test>::get(2), Some(Slot::from(99)));
+		System::assert_last_event(
+			Event::<Test>::EquivocationReported { offender: 2, slot: Slot::from(99) }.into(),
+		);
+	});
+}
+
+#[test]
+fn report_equivocation_of_invulnerable_is_recorded() {
+	new_test_ext().execute_with(|| {
+		let pot_balance = Balances::free_balance(CollatorSelection::account_id());
+
+		assert_ok!(report(equivocation_proof(Sr25519Keyring::Alice, 100)));
+
+		// invulnerables have no bond to slash.
+		assert_eq!(Balances::free_balance(CollatorSelection::account_id()), pot_balance);
+		assert_eq!(Balances::free_balance(1), 100);
+		assert_eq!(LastReportedSlot::<Test>::get(1), Some(Slot::from(100)));
+		System::assert_last_event(
+			Event::<Test>::EquivocationReported { offender: 1, slot: Slot::from(100) }.into(),
+		);
+	});
+}
+
+#[test]
+fn duplicate_reports_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(2)));
+		assert_ok!(report(equivocation_proof(Sr25519Keyring::Bob, 98)));
+
+		assert_noop!(
+			report(equivocation_proof(Sr25519Keyring::Bob, 98)),
+			Error::<Test>::DuplicateReport
+		);
+		assert_noop!(
+			report(equivocation_proof(Sr25519Keyring::Bob, 95)),
+			Error::<Test>::DuplicateReport
+		);
+		assert_ok!(report(equivocation_proof(Sr25519Keyring::Bob, 99)));
+	});
+}
+
+#[test]
+fn outdated_reports_are_rejected() {
+	new_test_ext().execute_with(|| {
+		// the current slot is 100 and reports live for 10 slots.
+		assert_noop!(
+			report(equivocation_proof(Sr25519Keyring::Bob, 101)),
+			Error::<Test>::OutdatedReport
+		);
+		assert_noop!(
+			report(equivocation_proof(Sr25519Keyring::Bob, 89)),
+			Error::<Test>::OutdatedReport
+		);
+		assert_ok!(report(equivocation_proof(Sr25519Keyring::Bob, 90)));
+	});
+}
+
+#[test]
+fn unknown_offenders_are_rejected() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			report(equivocation_proof(Sr25519Keyring::Dave, 100)),
+			Error::<Test>::UnknownOffender
+		);
+	});
+}
+
+#[test]
+fn check_equivocation_proof_works() {
+	let parent = H256::repeat_byte(1);
+	let relay_parent = H256::repeat_byte(2);
+	let header = |author, slot, parent, relay_parent, state_root| {
+		sealed_header(author, slot, parent, Some(relay_parent), H256::repeat_byte(state_root))
+	};
+
+	let mut proof = equivocation_proof(Sr25519Keyring::Bob, 100);
+	assert!(check_equivocation_proof(&proof));
+
+	// the same block twice.
+	proof.second_header = proof.first_header.clone();
+	assert!(!check_equivocation_proof(&proof));
+
+	// consecutive blocks of the same slot.
+	proof.second_header = header(Sr25519Keyring::Bob, 100, H256::repeat_byte(5), relay_parent, 4);
+	assert!(!check_equivocation_proof(&proof));
+
+	// blocks built on different relay parents.
+	proof.second_header = header(Sr25519Keyring::Bob, 100, parent, H256::repeat_byte(5), 4);
+	assert!(!check_equivocation_proof(&proof));
+
+	// blocks of different slots.
+	proof.second_header = header(Sr25519Keyring::Bob, 101, parent, relay_parent, 4);
+	assert!(!check_equivocation_proof(&proof));
+
+	// a block sealed by someone else.
+	proof.second_header = header(Sr25519Keyring::Charlie, 100, parent, relay_parent, 4);
+	assert!(!check_equivocation_proof(&proof));
+
+	// the slot of the proof isn't the one of the blocks.
+	let mut proof = equivocation_proof(Sr25519Keyring::Bob, 100);
+	proof.slot = Slot::from(99);
+	assert!(!check_equivocation_proof(&proof));
+
+	// a block without seal.
+	let mut proof = equivocation_proof(Sr25519Keyring::Bob, 100);
+	proof.second_header.digest_mut().pop();
+	assert!(!check_equivocation_proof(&proof));
+
+	// blocks without relay parent.
+	let mut proof = equivocation_proof(Sr25519Keyring::Bob, 100);
+	proof.first_header =
+		sealed_header(Sr25519Keyring::Bob, 100, parent, None, H256::repeat_byte(3));
+	proof.second_header =
+		sealed_header(Sr25519Keyring::Bob, 100, parent, None, H256::repeat_byte(4));
+	assert!(!check_equivocation_proof(&proof));
+}
+
+#[test]
+fn invalid_proofs_are_rejected() {
+	new_test_ext().execute_with(|| {
+		let mut proof = equivocation_proof(Sr25519Keyring::Bob, 100);
+		proof.offender = Sr25519Keyring::Charlie.public().into();
+
+		assert_noop!(report(proof), Error::<Test>::InvalidEquivocationProof);
+	});
+}
+
+#[test]
+fn validate_unsigned_only_accepts_local_reports() {
+	new_test_ext().execute_with(|| {
+		let call = report_call(equivocation_proof(Sr25519Keyring::Bob, 100));
+
+		assert_eq!(
+			AuraEquivocation::validate_unsigned(TransactionSource::External, &call),
+			InvalidTransaction::Call.into(),
+		);
+
+		let valid = AuraEquivocation::validate_unsigned(TransactionSource::Local, &call).unwrap();
+		assert_eq!(valid.priority, u64::MAX);
+		assert_eq!(valid.longevity, ReportLongevity::get());
+		assert!(!valid.propagate);
+		assert_ok!(AuraEquivocation::pre_dispatch(&call));
+
+		assert_ok!(report(equivocation_proof(Sr25519Keyring::Bob, 100)));
+		assert_eq!(
+			AuraEquivocation::validate_unsigned(TransactionSource::Local, &call),
+			InvalidTransaction::Stale.into(),
+		);
+		assert_eq!(AuraEquivocation::pre_dispatch(&call), Err(InvalidTransaction::Stale.into()));
+
+		let mut invalid = equivocation_proof(Sr25519Keyring::Bob, 99);
+		invalid.second_header = invalid.first_header.clone();
+		assert_eq!(
+			AuraEquivocation::validate_unsigned(TransactionSource::Local, &report_call(invalid)),
+			InvalidTransaction::BadProof.into(),
+		);
+	});
+}
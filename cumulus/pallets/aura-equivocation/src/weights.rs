@@ -0,0 +1,65 @@
+// This file is part of Cumulus.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use core::marker::PhantomData;
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+
+// The weight info trait for `cumulus_pallet_aura_equivocation`.
+pub trait WeightInfo {
+	fn report_equivocation() -> Weight;
+}
+
+/// Weights for cumulus_pallet_aura_equivocation using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `Aura::CurrentSlot` (r:1 w:0)
+	/// Storage: `Session::KeyOwner` (r:1 w:0)
+	/// Storage: `AuraEquivocation::LastReportedSlot` (r:1 w:1)
+	/// Storage: `CollatorSelection::CandidateList` (r:1 w:1)
+	/// Storage: `CollatorSelection::LastAuthoredBlock` (r:0 w:1)
+	/// Storage: `CollatorSelection::PlannedSessions` (r:1 w:0)
+	/// Storage: `CollatorSelection::UnbondingDeposits` (r:3 w:3)
+	/// Storage: `System::Account` (r:2 w:2)
+	fn report_equivocation() -> Weight {
+		Weight::from_parts(134_218_000_u64, 0)
+			.saturating_add(T::DbWeight::get().reads(10_u64))
+			.saturating_add(T::DbWeight::get().writes(8_u64))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	/// Storage: `Aura::CurrentSlot` (r:1 w:0)
+	/// Storage: `Session::KeyOwner` (r:1 w:0)
+	/// Storage: `AuraEquivocation::LastReportedSlot` (r:1 w:1)
+	/// Storage: `CollatorSelection::CandidateList` (r:1 w:1)
+	/// Storage: `CollatorSelection::LastAuthoredBlock` (r:0 w:1)
+	/// Storage: `CollatorSelection::PlannedSessions` (r:1 w:0)
+	/// Storage: `CollatorSelection::UnbondingDeposits` (r:3 w:3)
+	/// Storage: `System::Account` (r:2 w:2)
+	fn report_equivocation() -> Weight {
+		Weight::from_parts(134_218_000_u64, 0)
+			.saturating_add(RocksDbWeight::get().reads(10_u64))
+			.saturating_add(RocksDbWeight::get().writes(8_u64))
+	}
+}
@@ -140,27 +140,41 @@ impl<
 	/// whose state we are querying against, this must always return `true` as long as the slot
 	/// is more recent than the included block itself.
 	pub fn can_build_upon(included_hash: T::Hash, new_slot: Slot) -> bool {
-		let velocity = V.max(1);
-		let (last_slot, authored_so_far) = match pallet::RelaySlotInfo::<T>::get() {
-			None => return true,
-			Some(x) => x,
-		};
-
-		let size_after_included =
-			parachain_system::Pallet::<T>::unincluded_segment_size_after(included_hash);
+		can_build_upon(V, C, pallet::RelaySlotInfo::<T>::get(), new_slot, || {
+			parachain_system::Pallet::<T>::unincluded_segment_size_after(included_hash)
+		})
+	}
+}
 
-		// can never author when the unincluded segment is full.
-		if size_after_included >= C {
-			return false
-		}
+/// The checks of [`FixedVelocityConsensusHook::can_build_upon`] with the given `velocity` and
+/// unincluded segment `capacity`, given the `RelaySlotInfo` of the parent block and the size of
+/// its unincluded segment after the included block.
+///
+/// Exposed so that the block production can be simulated without a runtime.
+pub fn can_build_upon(
+	velocity: u32,
+	capacity: u32,
+	relay_slot_info: Option<(Slot, u32)>,
+	new_slot: Slot,
+	size_after_included: impl FnOnce() -> u32,
+) -> bool {
+	let velocity = velocity.max(1);
+	let (last_slot, authored_so_far) = match relay_slot_info {
+		None => return true,
+		Some(x) => x,
+	};
+
+	// can never author when the unincluded segment is full.
+	if size_after_included() >= capacity {
+		return false
+	}
 
-		// Check that we have not authored more than `V + 1` parachain blocks in the current relay
-		// chain slot.
-		if last_slot == new_slot {
-			authored_so_far < velocity + 1
-		} else {
-			// disallow slot from moving backwards.
-			last_slot < new_slot
-		}
+	// Check that we have not authored more than `velocity + 1` parachain blocks in the current
+	// relay chain slot.
+	if last_slot == new_slot {
+		authored_so_far < velocity + 1
+	} else {
+		// disallow slot from moving backwards.
+		last_slot < new_slot
 	}
 }
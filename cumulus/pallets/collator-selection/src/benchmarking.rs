@@ -375,6 +375,7 @@ mod benchmarks {
 	fn new_session(
 		r: Linear<1, { T::MaxCandidates::get() }>,
 		c: Linear<1, { T::MaxCandidates::get() }>,
+		u: Linear<0, { T::MaxCandidates::get() }>,
 	) {
 		CandidacyBond::<T>::put(<T as pallet::Config>::Currency::minimum_balance());
 		DesiredCandidates::<T>::put(c);
@@ -383,6 +384,15 @@ mod benchmarks {
 		register_validators::<T>(c);
 		register_candidates::<T>(c);
 
+		// The deposits of former candidates unreserved by the planned session.
+		let deposit = <T as pallet::Config>::Currency::minimum_balance();
+		let released = PlannedSessions::<T>::get().saturating_add(1);
+		for i in 0..u {
+			let former_candidate = create_funded_user::<T>("former_candidate", i, 1000);
+			<T as pallet::Config>::Currency::reserve(&former_candidate, deposit).unwrap();
+			UnbondingDeposits::<T>::insert(released, former_candidate, deposit);
+		}
+
 		let new_block: BlockNumberFor<T> = T::KickThreshold::get();
 		let zero_block: BlockNumberFor<T> = 0u32.into();
 		let candidates: Vec<T::AccountId> = CandidateList::<T>::get()
@@ -421,6 +431,8 @@ mod benchmarks {
 			<CollatorSelection<T> as SessionManager<_>>::new_session(0);
 		}
 
+		assert_eq!(UnbondingDeposits::<T>::iter_prefix(released).count(), 0);
+
 		if c > r && non_removals >= min_candidates {
 			// candidates > removals and remaining candidates > min candidates
 			// => remaining candidates should be shorter than before removal, i.e. some were
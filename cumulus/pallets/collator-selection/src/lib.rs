@@ -65,6 +65,12 @@
 //! the desired number of collators is reached. Candidates can increase or decrease their deposits
 //! between sessions in order to ensure they receive a slot in the collator list.
 //!
+//! The deposits of candidates leaving, being kicked or being replaced can be kept reserved for
+//! [`Config::UnbondingSessions`] sessions (see [`UnbondingDeposits`]). Former candidates may still
+//! author blocks until the end of the next session, so this keeps their deposits at stake for
+//! misbehaving until they can't be reported anymore, see [`Pallet::slash_candidate`]. With zero
+//! unbonding sessions, the deposits are unreserved right away.
+//!
 //! ### Rewards
 //!
 //! The Collator Selection pallet maintains an on-chain account (the "Pot"). In each block, the
@@ -109,8 +115,8 @@ pub mod pallet {
 		dispatch::{DispatchClass, DispatchResultWithPostInfo},
 		pallet_prelude::*,
 		traits::{
-			Currency, EnsureOrigin, ExistenceRequirement::KeepAlive, ReservableCurrency,
-			ValidatorRegistration,
+			BalanceStatus, Currency, EnsureOrigin, ExistenceRequirement::KeepAlive,
+			ReservableCurrency, ValidatorRegistration,
 		},
 		BoundedVec, DefaultNoBound, PalletId,
 	};
@@ -185,6 +191,12 @@ pub mod pallet {
 		/// Validate a user is registered
 		type ValidatorRegistration: ValidatorRegistration<Self::ValidatorId>;
 
+		/// The number of sessions after which the deposits of former candidates are unreserved.
+		///
+		/// Former candidates can be slashed until then. Zero unreserves the deposits immediately.
+		#[pallet::constant]
+		type UnbondingSessions: Get<SessionIndex>;
+
 		/// The weight information of this pallet.
 		type WeightInfo: WeightInfo;
 	}
@@ -227,6 +239,22 @@ pub mod pallet {
 		ValueQuery,
 	>;
 
+	/// The deposits of former candidates, by the [`PlannedSessions`] they are unreserved at.
+	#[pallet::storage]
+	pub type UnbondingDeposits<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		SessionIndex,
+		Twox64Concat,
+		T::AccountId,
+		BalanceOf<T>,
+		ValueQuery,
+	>;
+
+	/// The number of sessions planned since the deposits of former candidates are unbonding.
+	#[pallet::storage]
+	pub type PlannedSessions<T> = StorageValue<_, SessionIndex, ValueQuery>;
+
 	/// Last block authored by collator.
 	#[pallet::storage]
 	pub type LastAuthoredBlock<T: Config> =
@@ -304,6 +332,8 @@ pub mod pallet {
 		/// An account was unable to be added to the Invulnerables because they did not have keys
 		/// registered. Other Invulnerables may have been set.
 		InvalidInvulnerableSkipped { account_id: T::AccountId },
+		/// A candidate was removed for misbehaving and their deposit moved to the pot.
+		CandidateSlashed { account_id: T::AccountId, amount: BalanceOf<T> },
 	}
 
 	#[pallet::error]
@@ -349,8 +379,8 @@ pub mod pallet {
 		fn integrity_test() {
 			assert!(T::MinEligibleCollators::get() > 0, "chain must require at least one collator");
 			assert!(
-				T::MaxInvulnerables::get().saturating_add(T::MaxCandidates::get()) >=
-					T::MinEligibleCollators::get(),
+				T::MaxInvulnerables::get().saturating_add(T::MaxCandidates::get())
+					>= T::MinEligibleCollators::get(),
 				"invulnerables and candidates must be able to satisfy collator demand"
 			);
 		}
@@ -385,8 +415,8 @@ pub mod pallet {
 			if new.is_empty() {
 				// Casting `u32` to `usize` should be safe on all machines running this.
 				ensure!(
-					CandidateList::<T>::decode_len().unwrap_or_default() >=
-						T::MinEligibleCollators::get() as usize,
+					CandidateList::<T>::decode_len().unwrap_or_default()
+						>= T::MinEligibleCollators::get() as usize,
 					Error::<T>::TooFewEligibleCollators
 				);
 			}
@@ -411,7 +441,7 @@ pub mod pallet {
 							Self::deposit_event(Event::InvalidInvulnerableSkipped {
 								account_id: account_id.clone(),
 							});
-							continue
+							continue;
 						}
 						// else condition passes; key is registered
 					},
@@ -420,7 +450,7 @@ pub mod pallet {
 						Self::deposit_event(Event::InvalidInvulnerableSkipped {
 							account_id: account_id.clone(),
 						});
-						continue
+						continue;
 					},
 				}
 
@@ -498,7 +528,7 @@ pub mod pallet {
 							.unwrap_or(initial_len);
 						let kicked_candidates = candidates.drain(..first_safe_candidate);
 						for candidate in kicked_candidates {
-							T::Currency::unreserve(&candidate.who, candidate.deposit);
+							Self::unbond(&candidate.who, candidate.deposit);
 							LastAuthoredBlock::<T>::remove(candidate.who);
 						}
 						first_safe_candidate
@@ -563,7 +593,8 @@ pub mod pallet {
 		}
 
 		/// Deregister `origin` as a collator candidate. Note that the collator can only leave on
-		/// session change. The `CandidacyBond` will be unreserved immediately.
+		/// session change. The deposit will be unreserved [`Config::UnbondingSessions`] sessions
+		/// later.
 		///
 		/// This call will fail if the total number of candidates would drop below
 		/// `MinEligibleCollators`.
@@ -693,13 +724,13 @@ pub mod pallet {
 					} else if new_deposit < old_deposit {
 						// Casting `u32` to `usize` should be safe on all machines running this.
 						ensure!(
-							idx.saturating_add(DesiredCandidates::<T>::get() as usize) <
-								candidate_count,
+							idx.saturating_add(DesiredCandidates::<T>::get() as usize)
+								< candidate_count,
 							Error::<T>::InvalidUnreserve
 						);
 						T::Currency::unreserve(&who, old_deposit - new_deposit);
 					} else {
-						return Err(Error::<T>::IdenticalDeposit.into())
+						return Err(Error::<T>::IdenticalDeposit.into());
 					}
 
 					// Update the deposit and insert the candidate in the correct spot in the list.
@@ -797,7 +828,7 @@ pub mod pallet {
 				},
 			)?;
 			T::Currency::reserve(&who, deposit)?;
-			T::Currency::unreserve(&target_info.who, target_info.deposit);
+			Self::unbond(&target_info.who, target_info.deposit);
 			LastAuthoredBlock::<T>::remove(target_info.who.clone());
 			LastAuthoredBlock::<T>::insert(
 				who.clone(),
@@ -825,7 +856,7 @@ pub mod pallet {
 				.unwrap_or(u32::MAX)
 		}
 
-		/// Removes a candidate if they exist and sends them back their deposit.
+		/// Removes a candidate if they exist and unbonds their deposit.
 		fn try_remove_candidate(
 			who: &T::AccountId,
 			remove_last_authored: bool,
@@ -836,7 +867,7 @@ pub mod pallet {
 					.position(|candidate_info| candidate_info.who == *who)
 					.ok_or(Error::<T>::NotCandidate)?;
 				let deposit = candidates[idx].deposit;
-				T::Currency::unreserve(who, deposit);
+				Self::unbond(who, deposit);
 				candidates.remove(idx);
 				if remove_last_authored {
 					LastAuthoredBlock::<T>::remove(who.clone())
@@ -847,6 +878,61 @@ pub mod pallet {
 			Ok(())
 		}
 
+		/// Keeps the deposit of a former candidate reserved until [`Config::UnbondingSessions`]
+		/// more sessions are planned, or unreserves it if there are none.
+		fn unbond(who: &T::AccountId, deposit: BalanceOf<T>) {
+			if deposit.is_zero() {
+				return;
+			}
+			let unbonding_sessions = T::UnbondingSessions::get();
+			if unbonding_sessions == 0 {
+				T::Currency::unreserve(who, deposit);
+				return;
+			}
+			let session = PlannedSessions::<T>::get().saturating_add(unbonding_sessions);
+			UnbondingDeposits::<T>::mutate(session, who, |unbonding| {
+				*unbonding = unbonding.saturating_add(deposit)
+			});
+		}
+
+		/// Removes a misbehaving candidate and moves their deposit, including the deposits they
+		/// are still unbonding, to the pot, to be distributed to the collators authoring the next
+		/// blocks. Returns the amount slashed.
+		///
+		/// Former candidates can be slashed until their deposits are unreserved, so that they can't
+		/// escape by leaving before being reported. Invulnerables have no deposit and can't be
+		/// slashed. The candidate keeps authoring until the end of the session, like any candidate
+		/// leaving.
+		pub fn slash_candidate(who: &T::AccountId) -> Result<BalanceOf<T>, DispatchError> {
+			let candidate_deposit = CandidateList::<T>::mutate(|candidates| {
+				let idx =
+					candidates.iter().position(|candidate_info| candidate_info.who == *who)?;
+				Some(candidates.remove(idx).deposit)
+			});
+			if candidate_deposit.is_some() {
+				LastAuthoredBlock::<T>::remove(who);
+			}
+			let planned = PlannedSessions::<T>::get();
+			let unbonding = (1..=T::UnbondingSessions::get())
+				.map(|n| UnbondingDeposits::<T>::take(planned.saturating_add(n), who))
+				.fold(BalanceOf::<T>::zero(), |total, deposit| total.saturating_add(deposit));
+			ensure!(candidate_deposit.is_some() || !unbonding.is_zero(), Error::<T>::NotCandidate);
+			let deposit = candidate_deposit.unwrap_or_default().saturating_add(unbonding);
+
+			let amount = match T::Currency::repatriate_reserved(
+				who,
+				&Self::account_id(),
+				deposit,
+				BalanceStatus::Free,
+			) {
+				Ok(unslashed) => deposit.saturating_sub(unslashed),
+				// The pot can't receive the deposit, e.g. because it doesn't exist, so it is burnt.
+				Err(_) => deposit.saturating_sub(T::Currency::slash_reserved(who, deposit).1),
+			};
+			Self::deposit_event(Event::CandidateSlashed { account_id: who.clone(), amount });
+			Ok(amount)
+		}
+
 		/// Assemble the current set of candidates and invulnerables into the next collator set.
 		///
 		/// This is done on the fly, as frequent as we are told to do so, as the session manager.
@@ -968,6 +1054,16 @@ pub mod pallet {
 				<frame_system::Pallet<T>>::block_number(),
 			);
 
+			let planned = PlannedSessions::<T>::mutate(|planned| {
+				*planned = planned.saturating_add(1);
+				*planned
+			});
+			let mut released = 0u32;
+			for (who, deposit) in UnbondingDeposits::<T>::drain_prefix(planned) {
+				T::Currency::unreserve(&who, deposit);
+				released.saturating_inc();
+			}
+
 			// The `expect` below is safe because the list is a `BoundedVec` with a max size of
 			// `T::MaxCandidates`, which is a `u32`. When `decode_len` returns `Some(len)`, `len`
 			// must be valid and at most `u32::MAX`, which must always be able to convert to `u32`.
@@ -984,7 +1080,7 @@ pub mod pallet {
 			let result = Self::assemble_collators();
 
 			frame_system::Pallet::<T>::register_extra_weight_unchecked(
-				T::WeightInfo::new_session(removed, candidates_len_before),
+				T::WeightInfo::new_session(removed, candidates_len_before, released),
 				DispatchClass::Mandatory,
 			);
 			Some(result)
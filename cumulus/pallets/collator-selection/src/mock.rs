@@ -150,6 +150,7 @@ ord_parameter_types! {
 
 parameter_types! {
 	pub const PotId: PalletId = PalletId(*b"PotStake");
+	pub static UnbondingSessions: u32 = 0;
 }
 
 pub struct IsRegistered;
@@ -171,6 +172,7 @@ impl Config for Test {
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = IdentityCollator;
 	type ValidatorRegistration = IsRegistered;
+	type UnbondingSessions = UnbondingSessions;
 	type WeightInfo = ();
 }
 
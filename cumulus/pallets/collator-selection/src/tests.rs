@@ -16,7 +16,7 @@
 use crate as collator_selection;
 use crate::{
 	mock::*, CandidacyBond, CandidateInfo, CandidateList, DesiredCandidates, Error, Invulnerables,
-	LastAuthoredBlock,
+	LastAuthoredBlock, PlannedSessions, UnbondingDeposits,
 };
 use codec::Encode;
 use frame_support::{
@@ -1176,6 +1176,105 @@ fn leave_intent() {
 	});
 }
 
+#[test]
+fn slash_candidate() {
+	new_test_ext().execute_with(|| {
+		initialize_to_block(1);
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 90);
+		let pot = CollatorSelection::account_id();
+		Balances::make_free_balance_be(&pot, 5);
+
+		// invulnerables and other accounts can't be slashed.
+		assert_noop!(CollatorSelection::slash_candidate(&1), Error::<Test>::NotCandidate);
+		assert_noop!(CollatorSelection::slash_candidate(&4), Error::<Test>::NotCandidate);
+
+		// the bond goes to the pot.
+		assert_eq!(CollatorSelection::slash_candidate(&3), Ok(10));
+		assert_eq!(Balances::free_balance(3), 90);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::free_balance(pot), 15);
+		assert!(CandidateList::<Test>::get().is_empty());
+		assert_eq!(LastAuthoredBlock::<Test>::get(3), 0);
+		System::assert_last_event(RuntimeEvent::CollatorSelection(
+			crate::Event::CandidateSlashed { account_id: 3, amount: 10 },
+		));
+
+		// and only once.
+		assert_noop!(CollatorSelection::slash_candidate(&3), Error::<Test>::NotCandidate);
+	});
+}
+
+#[test]
+fn deposits_unbond_for_unbonding_sessions() {
+	new_test_ext().execute_with(|| {
+		UnbondingSessions::set(3);
+		initialize_to_block(1);
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		assert_ok!(CollatorSelection::update_bond(RuntimeOrigin::signed(4), 20));
+
+		// the deposit of a leaving candidate is unbonding,
+		assert_ok!(CollatorSelection::leave_intent(RuntimeOrigin::signed(3)));
+		assert_eq!(Balances::free_balance(3), 90);
+		assert_eq!(Balances::reserved_balance(3), 10);
+		let planned = PlannedSessions::<Test>::get();
+		assert_eq!(UnbondingDeposits::<Test>::get(planned + 3, 3), 10);
+
+		// like the deposit of a replaced candidate.
+		initialize_to_block(10);
+		assert_ok!(CollatorSelection::take_candidate_slot(RuntimeOrigin::signed(5), 30, 4));
+		assert_eq!(Balances::free_balance(4), 80);
+		assert_eq!(Balances::reserved_balance(4), 20);
+		assert_eq!(UnbondingDeposits::<Test>::get(planned + 4, 4), 20);
+
+		// both are unreserved after `UnbondingSessions` sessions.
+		initialize_to_block(20);
+		assert_eq!(Balances::reserved_balance(3), 10);
+		initialize_to_block(30);
+		assert_eq!(Balances::free_balance(3), 100);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::reserved_balance(4), 20);
+		initialize_to_block(40);
+		assert_eq!(Balances::free_balance(4), 100);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		// only the deposit of the candidate kicked for not authoring is still unbonding.
+		assert_eq!(
+			UnbondingDeposits::<Test>::iter()
+				.map(|(_, who, deposit)| (who, deposit))
+				.collect::<Vec<_>>(),
+			vec![(5, 30)]
+		);
+	});
+}
+
+#[test]
+fn slash_former_candidate() {
+	new_test_ext().execute_with(|| {
+		UnbondingSessions::set(3);
+		initialize_to_block(1);
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(3)));
+		assert_ok!(CollatorSelection::register_as_candidate(RuntimeOrigin::signed(4)));
+		let pot = CollatorSelection::account_id();
+		Balances::make_free_balance_be(&pot, 5);
+
+		// leaving doesn't escape slashing until the deposit is unreserved.
+		assert_ok!(CollatorSelection::leave_intent(RuntimeOrigin::signed(3)));
+		initialize_to_block(20);
+		assert_eq!(CollatorSelection::slash_candidate(&3), Ok(10));
+		assert_eq!(Balances::free_balance(3), 90);
+		assert_eq!(Balances::reserved_balance(3), 0);
+		assert_eq!(Balances::free_balance(pot), 15);
+		assert_noop!(CollatorSelection::slash_candidate(&3), Error::<Test>::NotCandidate);
+
+		// but not afterwards.
+		assert_ok!(CollatorSelection::leave_intent(RuntimeOrigin::signed(4)));
+		initialize_to_block(50);
+		assert_eq!(Balances::reserved_balance(4), 0);
+		assert_noop!(CollatorSelection::slash_candidate(&4), Error::<Test>::NotCandidate);
+	});
+}
+
 #[test]
 fn authorship_event_handler() {
 	new_test_ext().execute_with(|| {
@@ -36,7 +36,7 @@ pub trait WeightInfo {
 	fn update_bond(_c: u32) -> Weight;
 	fn take_candidate_slot(_c: u32) -> Weight;
 	fn note_author() -> Weight;
-	fn new_session(_c: u32, _r: u32) -> Weight;
+	fn new_session(_c: u32, _r: u32, _u: u32) -> Weight;
 }
 
 /// Weights for pallet_collator_selection using the Substrate node and recommended hardware.
@@ -87,7 +87,7 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(3_u64))
 			.saturating_add(T::DbWeight::get().writes(4_u64))
 	}
-	fn new_session(r: u32, c: u32) -> Weight {
+	fn new_session(r: u32, c: u32, _u: u32) -> Weight {
 		Weight::from_parts(0_u64, 0)
 			// Standard Error: 1_010_000
 			.saturating_add(Weight::from_parts(109_961_000_u64, 0).saturating_mul(r as u64))
@@ -188,7 +188,7 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(3_u64))
 			.saturating_add(RocksDbWeight::get().writes(4_u64))
 	}
-	fn new_session(r: u32, c: u32) -> Weight {
+	fn new_session(r: u32, c: u32, _u: u32) -> Weight {
 		Weight::from_parts(0_u64, 0)
 			// Standard Error: 1_010_000
 			.saturating_add(Weight::from_parts(109_961_000_u64, 0).saturating_mul(r as u64))
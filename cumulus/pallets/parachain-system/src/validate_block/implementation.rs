@@ -27,7 +27,7 @@ use frame_support::{
 	traits::{ExecuteBlock, Get, IsSubType},
 	BoundedVec,
 };
-use polkadot_parachain_primitives::primitives::{HeadData, ValidationResult};
+use polkadot_parachain_primitives::primitives::{HeadData, ValidationResult, ValidationStats};
 use sp_core::storage::{well_known_keys, ChildInfo, StateVersion};
 use sp_externalities::{set_and_run_with_externalities, Externalities};
 use sp_io::{hashing::blake2_128, KillStorageResult};
@@ -69,7 +69,8 @@ environmental::environmental!(recorder: trait ProofSizeProvider);
 /// 5. The last step is to execute the entire block in the machinery we just have setup. Executing
 /// the blocks include running all transactions in the block against our in-memory database and
 /// ensuring that the final storage root matches the storage root in the header of the block. In the
-/// end we return back the [`ValidationResult`] with all the required information for the validator.
+/// end we return back the [`ValidationResult`] with all the required information for the validator,
+/// together with the [`ValidationStats`] of the execution.
 #[doc(hidden)]
 pub fn validate_block<B: BlockT, E: ExecuteBlock<B>, PSC: crate::Config>(
 	MemoryOptimizedValidationParams {
@@ -78,7 +79,7 @@ pub fn validate_block<B: BlockT, E: ExecuteBlock<B>, PSC: crate::Config>(
 		relay_parent_number,
 		relay_parent_storage_root,
 	}: MemoryOptimizedValidationParams,
-) -> ValidationResult
+) -> (ValidationResult, ValidationStats)
 where
 	B::Extrinsic: ExtrinsicCall,
 	<B::Extrinsic as ExtrinsicCall>::Call: IsSubType<crate::Call<PSC>>,
@@ -170,6 +171,7 @@ where
 	let mut hrmp_watermark = Default::default();
 	let mut head_data = None;
 	let mut new_validation_code = None;
+	let mut stats = ValidationStats::default();
 	let num_blocks = blocks.len();
 
 	// Create the db
@@ -230,6 +232,7 @@ where
 				E::execute_verified_block(block);
 			},
 		);
+		stats.storage_proof_reads += execute_recorder.key_reads();
 
 		if overlay.storage(well_known_keys::CODE).is_some() && num_blocks > 1 {
 			panic!("When applying a runtime upgrade, only one block per PoV is allowed. Received {num_blocks}.")
@@ -356,14 +359,16 @@ where
 			.expect("UMPSignals does not fit in UMPMessages");
 	}
 
-	ValidationResult {
+	let result = ValidationResult {
 		head_data: head_data.expect("HeadData not set"),
 		new_validation_code: new_validation_code.map(Into::into),
 		upward_messages,
 		processed_downward_messages,
 		horizontal_messages,
 		hrmp_watermark,
-	}
+	};
+
+	(result, stats)
 }
 
 /// Validates the given [`PersistedValidationData`] against the data from the relay chain.
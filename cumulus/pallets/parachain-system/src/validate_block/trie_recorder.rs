@@ -37,6 +37,7 @@ pub struct SizeOnlyRecorder<'a, H: Hasher> {
 	seen_nodes: RefMut<'a, HashSet<H::Out, RandomState>>,
 	encoded_size: RefMut<'a, usize>,
 	recorded_keys: RefMut<'a, HashMap<Rc<[u8]>, RecordedForKey, RandomState>>,
+	key_reads: RefMut<'a, u64>,
 }
 
 impl<'a, H: trie_db::Hasher> trie_db::TrieRecorder<H::Out> for SizeOnlyRecorder<'a, H> {
@@ -53,6 +54,7 @@ impl<'a, H: trie_db::Hasher> trie_db::TrieRecorder<H::Out> for SizeOnlyRecorder<
 					encoded_size_update += encoded_node.encoded_size();
 				},
 			TrieAccess::Value { hash, value, full_key } => {
+				*self.key_reads += 1;
 				if self.seen_nodes.insert(hash) {
 					encoded_size_update += value.encoded_size();
 				}
@@ -62,17 +64,20 @@ impl<'a, H: trie_db::Hasher> trie_db::TrieRecorder<H::Out> for SizeOnlyRecorder<
 					.or_insert_with(|| RecordedForKey::Value);
 			},
 			TrieAccess::Hash { full_key } => {
+				*self.key_reads += 1;
 				self.recorded_keys
 					.entry(full_key.into())
 					.or_insert_with(|| RecordedForKey::Hash);
 			},
 			TrieAccess::NonExisting { full_key } => {
+				*self.key_reads += 1;
 				self.recorded_keys
 					.entry(full_key.into())
 					.and_modify(|e| *e = RecordedForKey::Value)
 					.or_insert_with(|| RecordedForKey::Value);
 			},
 			TrieAccess::InlineValue { full_key } => {
+				*self.key_reads += 1;
 				self.recorded_keys
 					.entry(full_key.into())
 					.and_modify(|e| *e = RecordedForKey::Value)
@@ -93,6 +98,7 @@ pub struct SizeOnlyRecorderProvider<H: Hasher> {
 	seen_nodes: SeenNodes<H>,
 	encoded_size: Rc<RefCell<usize>>,
 	recorded_keys: Rc<RefCell<HashMap<Rc<[u8]>, RecordedForKey, RandomState>>>,
+	key_reads: Rc<RefCell<u64>>,
 }
 
 impl<H: Hasher> Default for SizeOnlyRecorderProvider<H> {
@@ -101,6 +107,7 @@ impl<H: Hasher> Default for SizeOnlyRecorderProvider<H> {
 			seen_nodes: Default::default(),
 			encoded_size: Default::default(),
 			recorded_keys: Default::default(),
+			key_reads: Default::default(),
 		}
 	}
 }
@@ -111,6 +118,12 @@ impl<H: Hasher> SizeOnlyRecorderProvider<H> {
 	pub(crate) fn with_seen_nodes(seen_nodes: SeenNodes<H>) -> Self {
 		Self { seen_nodes, ..Default::default() }
 	}
+
+	/// The number of storage keys looked up through this recorder so far.
+	#[cfg(not(feature = "std"))]
+	pub(crate) fn key_reads(&self) -> u64 {
+		*self.key_reads.borrow()
+	}
 }
 
 impl<H: trie_db::Hasher> sp_trie::TrieRecorderProvider<H> for SizeOnlyRecorderProvider<H> {
@@ -128,6 +141,7 @@ impl<H: trie_db::Hasher> sp_trie::TrieRecorderProvider<H> for SizeOnlyRecorderPr
 			encoded_size: self.encoded_size.borrow_mut(),
 			seen_nodes: self.seen_nodes.borrow_mut(),
 			recorded_keys: self.recorded_keys.borrow_mut(),
+			key_reads: self.key_reads.borrow_mut(),
 		}
 	}
 }
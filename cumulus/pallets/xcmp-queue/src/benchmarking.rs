@@ -273,6 +273,47 @@ mod benchmarks {
 		);
 	}
 
+	/// Schedule a channel when all but one of the schedules are used.
+	#[benchmark]
+	fn set_channel_schedule() {
+		let schedule = ChannelSchedule { priority: 1, reserved_weight: Weight::from_parts(1, 1) };
+		for sibling in 1..MAX_SCHEDULED_CHANNELS {
+			assert_ok!(Pallet::<T>::set_channel_schedule(
+				RawOrigin::Root.into(),
+				sibling.into(),
+				Some(schedule)
+			));
+		}
+		let sibling = ParaId::from(MAX_SCHEDULED_CHANNELS);
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, sibling, Some(schedule));
+
+		assert_eq!(InboundChannelSchedules::<T>::get().get(&sibling), Some(&schedule));
+	}
+
+	/// Look up the footprints of `c` scheduled channels without anything to service.
+	///
+	/// The weight used to service the queues is measured by the message queue itself.
+	#[benchmark]
+	fn service_scheduled_channels(c: Linear<0, MAX_SCHEDULED_CHANNELS>) {
+		let schedule = ChannelSchedule { priority: 1, reserved_weight: Weight::from_parts(1, 1) };
+		for sibling in 1..=c {
+			assert_ok!(Pallet::<T>::set_channel_schedule(
+				RawOrigin::Root.into(),
+				sibling.into(),
+				Some(schedule)
+			));
+		}
+
+		#[block]
+		{
+			Pallet::<T>::service_scheduled_channels();
+		}
+
+		assert_eq!(InboundChannelSchedules::<T>::get().len(), c as usize);
+	}
+
 	/// Split a singular XCM.
 	#[benchmark]
 	fn take_first_concatenated_xcm(
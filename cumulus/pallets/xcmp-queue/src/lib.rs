@@ -32,10 +32,16 @@
 //! It is defined in the channel configuration.
 //! - `THRESHOLD_FACTOR` just declares which percentage of the max size is the actual threshold.
 //! If it's 2, then the threshold is half of the max size, if it's 4, it's a quarter, and so on.
+//!
+//! Inbound messages are enqueued per sibling into `XcmpQueue`, whose queues are serviced in a
+//! round-robin fashion. A [`ChannelSchedule`] can be set for up to [`MAX_SCHEDULED_CHANNELS`]
+//! siblings, e.g. system parachains, to guarantee their queues some weight in every block. See
+//! [`Pallet::service_scheduled_channels`] for details.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod migration;
+pub mod runtime_api;
 
 #[cfg(test)]
 mod mock;
@@ -56,8 +62,8 @@ pub use weights_ext::WeightInfoExt;
 extern crate alloc;
 
 use alloc::{collections::BTreeSet, vec, vec::Vec};
-use bounded_collections::{BoundedBTreeSet, BoundedSlice, BoundedVec};
-use codec::{Compact, Decode, DecodeLimit, Encode, MaxEncodedLen};
+use bounded_collections::{BoundedBTreeMap, BoundedBTreeSet, BoundedSlice, BoundedVec};
+use codec::{Compact, Decode, DecodeLimit, DecodeWithMemTracking, Encode, MaxEncodedLen};
 use cumulus_primitives_core::{
 	relay_chain::BlockNumber as RelayBlockNumber, ChannelStatus, GetChannelInfo, MessageSendError,
 	ParaId, XcmpMessageFormat, XcmpMessageHandler, XcmpMessageSource,
@@ -71,12 +77,12 @@ use frame_support::{
 	},
 	weights::{Weight, WeightMeter},
 };
-use pallet_message_queue::OnQueueChanged;
+use pallet_message_queue::{OnQueueChanged, ServiceQueue};
 use polkadot_runtime_common::xcm_sender::PriceForMessageDelivery;
 use polkadot_runtime_parachains::{FeeTracker, GetMinFeeFactor};
 use scale_info::TypeInfo;
-use sp_core::MAX_POSSIBLE_ALLOCATION;
-use sp_runtime::{FixedU128, SaturatedConversion, WeakBoundedVec};
+use sp_core::{ConstU32, MAX_POSSIBLE_ALLOCATION};
+use sp_runtime::{FixedU128, Perbill, SaturatedConversion, WeakBoundedVec};
 use xcm::{latest::prelude::*, VersionedLocation, VersionedXcm, WrapVersion, MAX_XCM_DECODE_DEPTH};
 use xcm_builder::InspectMessageQueues;
 use xcm_executor::traits::ConvertOrigin;
@@ -95,6 +101,10 @@ const DEFAULT_POV_SIZE: u64 = 64 * 1024; // 64 KB
 pub const XCM_BATCH_SIZE: usize = 250;
 /// The maximum number of signals that we can have in an XCMP page.
 pub const MAX_SIGNALS_PER_PAGE: usize = 3;
+/// The maximum number of inbound channels that can have a [`ChannelSchedule`].
+pub const MAX_SCHEDULED_CHANNELS: u32 = 16;
+/// The maximum share of the block weight that can be reserved by [`ChannelSchedule`]s.
+pub const MAX_RESERVED_WEIGHT: Perbill = Perbill::from_percent(25);
 
 /// Constants related to delivery fee calculation
 pub mod delivery_fee_constants {
@@ -127,8 +137,11 @@ pub mod pallet {
 		///
 		/// This defines the maximal message length via [`crate::MaxXcmpMessageLenOf`]. The pallet
 		/// assumes that this hook will eventually process all the pushed messages.
+		///
+		/// The queues of the scheduled channels are serviced through it in `on_initialize`.
 		type XcmpQueue: EnqueueMessage<ParaId>
-			+ QueueFootprintQuery<ParaId, MaxMessageLen = MaxXcmpMessageLenOf<Self>>;
+			+ QueueFootprintQuery<ParaId, MaxMessageLen = MaxXcmpMessageLenOf<Self>>
+			+ ServiceQueue<ParaId>;
 
 		/// The maximum number of inbound XCMP channels that can be suspended simultaneously.
 		///
@@ -258,10 +271,58 @@ pub mod pallet {
 				data.validate::<T>()
 			})
 		}
+
+		/// Sets or removes the schedule of the inbound channel from `sibling`.
+		///
+		/// The weights reserved by all schedules must not exceed [`MAX_RESERVED_WEIGHT`] of the
+		/// maximum block weight.
+		///
+		/// - `origin`: Must pass `Root`.
+		/// - `sibling`: The sibling parachain sending on the channel.
+		/// - `schedule`: The new schedule of the channel, or `None` to remove it.
+		#[pallet::call_index(6)]
+		#[pallet::weight((T::WeightInfo::set_channel_schedule(), DispatchClass::Operational,))]
+		pub fn set_channel_schedule(
+			origin: OriginFor<T>,
+			sibling: ParaId,
+			schedule: Option<ChannelSchedule>,
+		) -> DispatchResult {
+			ensure_root(origin)?;
+
+			InboundChannelSchedules::<T>::try_mutate(|channels| {
+				match schedule {
+					Some(schedule) => {
+						ensure!(
+							!schedule.reserved_weight.is_zero(),
+							Error::<T>::BadChannelSchedule
+						);
+						channels
+							.try_insert(sibling, schedule)
+							.map_err(|_| Error::<T>::TooManyScheduledChannels)?;
+						let reserved = channels
+							.values()
+							.fold(Weight::zero(), |acc, s| acc.saturating_add(s.reserved_weight));
+						let max_reserved = MAX_RESERVED_WEIGHT * T::BlockWeights::get().max_block;
+						ensure!(reserved.all_lte(max_reserved), Error::<T>::BadChannelSchedule);
+					},
+					None => {
+						channels.remove(&sibling);
+					},
+				}
+				Ok::<_, Error<T>>(())
+			})?;
+
+			Self::deposit_event(Event::ChannelScheduleSet { sibling, schedule });
+			Ok(())
+		}
 	}
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_initialize(_block: BlockNumberFor<T>) -> Weight {
+			Self::service_scheduled_channels()
+		}
+
 		fn integrity_test() {
 			assert!(!T::MaxPageSize::get().is_zero(), "MaxPageSize too low");
 
@@ -295,6 +356,8 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		/// An HRMP message was sent to a sibling parachain.
 		XcmpMessageSent { message_hash: XcmHash },
+		/// The schedule of the inbound channel from a sibling parachain was set or removed.
+		ChannelScheduleSet { sibling: ParaId, schedule: Option<ChannelSchedule> },
 	}
 
 	#[pallet::error]
@@ -309,6 +372,10 @@ pub mod pallet {
 		TooManyActiveOutboundChannels,
 		/// The message is too big.
 		TooBig,
+		/// The channel schedule is invalid.
+		BadChannelSchedule,
+		/// There are too many scheduled inbound channels.
+		TooManyScheduledChannels,
 	}
 
 	/// The suspended inbound XCMP channels. All others are not suspended.
@@ -365,6 +432,16 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type DeliveryFeeFactor<T: Config> =
 		StorageMap<_, Twox64Concat, ParaId, FixedU128, ValueQuery, GetMinFeeFactor<Pallet<T>>>;
+
+	/// The inbound channels with a schedule.
+	///
+	/// This is a `StorageValue` since all of them are read in every block.
+	#[pallet::storage]
+	pub type InboundChannelSchedules<T: Config> = StorageValue<
+		_,
+		BoundedBTreeMap<ParaId, ChannelSchedule, ConstU32<MAX_SCHEDULED_CHANNELS>>,
+		ValueQuery,
+	>;
 }
 
 #[derive(Copy, Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo, MaxEncodedLen)]
@@ -452,6 +529,43 @@ impl QueueConfigData {
 	}
 }
 
+/// How the queue of an inbound channel is serviced relative to the others.
+#[derive(
+	Copy,
+	Clone,
+	Eq,
+	PartialEq,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Debug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct ChannelSchedule {
+	/// Channels with a higher priority are serviced first.
+	pub priority: u8,
+	/// The weight reserved in every block for processing the messages of the channel.
+	pub reserved_weight: Weight,
+}
+
+/// The backlog of an inbound channel.
+#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+pub struct InboundChannelBacklog {
+	/// The number of messages waiting to be processed.
+	pub messages: u64,
+	/// The total size of the messages waiting to be processed.
+	pub size: u64,
+	/// The number of pages in the queue of the channel.
+	pub pages: u32,
+	/// The number of pages with messages ready to be processed.
+	pub ready_pages: u32,
+	/// Whether the sibling was told to suspend sending on the channel.
+	pub suspended: bool,
+	/// The schedule of the channel, if any.
+	pub schedule: Option<ChannelSchedule>,
+}
+
 #[derive(PartialEq, Eq, Copy, Clone, Encode, Decode, TypeInfo)]
 pub enum ChannelSignal {
 	Suspend,
@@ -797,6 +911,58 @@ impl<T: Config> Pallet<T> {
 		}
 	}
 
+	/// Services the queues of the scheduled channels with their reserved weight.
+	///
+	/// The scheduled channels with messages ready to be processed are serviced in decreasing order
+	/// of priority, each of them with at most its reserved weight. Since the reserved weights are
+	/// bounded by [`MAX_RESERVED_WEIGHT`], every channel is serviced in every block in which it
+	/// has messages, and the remaining weight is left to the round-robin servicing of all queues.
+	pub fn service_scheduled_channels() -> Weight {
+		let channels = InboundChannelSchedules::<T>::get();
+		let mut weight = T::WeightInfo::service_scheduled_channels(channels.len() as u32);
+
+		let mut ready = channels
+			.into_iter()
+			.filter(|(sibling, _)| T::XcmpQueue::footprint(*sibling).ready_pages > 0)
+			.collect::<Vec<_>>();
+		// Ties are broken by the para id, since the sort is stable.
+		ready.sort_by_key(|(_, schedule)| core::cmp::Reverse(schedule.priority));
+
+		for (sibling, schedule) in ready {
+			weight
+				.saturating_accrue(T::XcmpQueue::service_queue(schedule.reserved_weight, &sibling));
+		}
+
+		weight
+	}
+
+	/// Returns the backlog of the inbound channel from `sibling`.
+	pub fn inbound_channel_backlog(sibling: ParaId) -> InboundChannelBacklog {
+		let footprint = T::XcmpQueue::footprint(sibling);
+		InboundChannelBacklog {
+			messages: footprint.storage.count,
+			size: footprint.storage.size,
+			pages: footprint.pages,
+			ready_pages: footprint.ready_pages,
+			suspended: InboundXcmpSuspended::<T>::get().contains(&sibling),
+			schedule: InboundChannelSchedules::<T>::get().get(&sibling).copied(),
+		}
+	}
+
+	/// Returns the backlogs of the scheduled and suspended inbound channels.
+	pub fn inbound_channel_backlogs() -> Vec<(ParaId, InboundChannelBacklog)> {
+		let siblings = InboundChannelSchedules::<T>::get()
+			.into_inner()
+			.into_keys()
+			.chain(InboundXcmpSuspended::<T>::get())
+			.collect::<BTreeSet<_>>();
+
+		siblings
+			.into_iter()
+			.map(|sibling| (sibling, Self::inbound_channel_backlog(sibling)))
+			.collect()
+	}
+
 	/// The worst-case weight of `on_idle`.
 	pub fn on_idle_weight() -> Weight {
 		<T as crate::Config>::WeightInfo::on_idle_good_msg()
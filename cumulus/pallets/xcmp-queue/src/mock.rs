@@ -140,6 +140,7 @@ impl<RuntimeOrigin: OriginTrait> ConvertOrigin<RuntimeOrigin>
 parameter_types! {
 	pub static EnqueuedMessages: Vec<(ParaId, Vec<u8>)> = Default::default();
 	pub static FirstPagePos: BTreeMap<ParaId, usize> = Default::default();
+	pub static ServicedQueues: Vec<(ParaId, Weight)> = Default::default();
 }
 
 /// An `EnqueueMessage` implementation that puts all messages in thread-local storage.
@@ -213,6 +214,25 @@ impl<T: OnQueueChanged<ParaId>> QueueFootprintQuery<ParaId> for EnqueueToLocalSt
 	}
 }
 
+/// Processing a message takes one unit of weight.
+impl<T> ServiceQueue<ParaId> for EnqueueToLocalStorage<T> {
+	fn service_queue(weight_limit: Weight, origin: &ParaId) -> Weight {
+		let mut serviced = ServicedQueues::get();
+		serviced.push((*origin, weight_limit));
+		ServicedQueues::set(serviced);
+
+		let mut processed = 0;
+		let mut msgs = EnqueuedMessages::get();
+		msgs.retain(|(sibling, _)| {
+			let process = sibling == origin && processed < weight_limit.ref_time();
+			processed += process as u64;
+			!process
+		});
+		EnqueuedMessages::set(msgs);
+		Weight::from_parts(processed, processed)
+	}
+}
+
 parameter_types! {
 	/// The asset ID for the asset that we use to pay for message delivery fees.
 	pub FeeAssetId: AssetId = AssetId(RelayChain::get());
@@ -0,0 +1,32 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API definition for the XCMP queue pallet.
+
+use crate::InboundChannelBacklog;
+use alloc::vec::Vec;
+use cumulus_primitives_core::ParaId;
+
+sp_api::decl_runtime_apis! {
+	/// API to inspect the inbound XCMP channels.
+	pub trait XcmpQueueApi {
+		/// Returns the backlog of the inbound channel from `sibling`.
+		fn inbound_channel_backlog(sibling: ParaId) -> InboundChannelBacklog;
+
+		/// Returns the backlogs of the scheduled and suspended inbound channels.
+		fn inbound_channel_backlogs() -> Vec<(ParaId, InboundChannelBacklog)>;
+	}
+}
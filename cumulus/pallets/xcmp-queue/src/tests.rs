@@ -14,7 +14,7 @@
 // limitations under the License.
 
 use super::{
-	mock::{mk_page, versioned_xcm, EnqueuedMessages, HRMP_PARA_ID},
+	mock::{mk_page, versioned_xcm, EnqueuedMessages, ServicedQueues, HRMP_PARA_ID},
 	*,
 };
 use std::collections::BTreeMap;
@@ -27,7 +27,9 @@ use frame_support::{
 	traits::{BatchFootprint, Hooks},
 	StorageNoopGuard,
 };
-use mock::{new_test_ext, FirstPagePos, ParachainSystem, RuntimeOrigin as Origin, Test, XcmpQueue};
+use mock::{
+	new_test_ext, FirstPagePos, ParachainSystem, RuntimeOrigin as Origin, System, Test, XcmpQueue,
+};
 use sp_runtime::traits::{BadOrigin, Zero};
 use std::iter::{once, repeat};
 use xcm::{MAX_INSTRUCTIONS_TO_DECODE, MAX_XCM_DECODE_DEPTH};
@@ -1230,3 +1232,195 @@ fn page_not_modified_when_fragment_does_not_fit() {
 		}
 	});
 }
+
+fn channel_schedule(priority: u8, reserved: u64) -> Option<ChannelSchedule> {
+	Some(ChannelSchedule { priority, reserved_weight: Weight::from_parts(reserved, reserved) })
+}
+
+fn max_reserved_weight() -> Weight {
+	let block_weights: frame_system::limits::BlockWeights =
+		<Test as frame_system::Config>::BlockWeights::get();
+	MAX_RESERVED_WEIGHT * block_weights.max_block
+}
+
+fn serviced_queues() -> Vec<(u32, u64)> {
+	ServicedQueues::take()
+		.into_iter()
+		.map(|(sibling, weight)| (sibling.into(), weight.ref_time()))
+		.collect()
+}
+
+fn enqueue_messages(sibling: u32, count: usize) {
+	let mut msgs = EnqueuedMessages::get();
+	msgs.extend(repeat((sibling.into(), vec![0u8])).take(count));
+	EnqueuedMessages::set(msgs);
+}
+
+#[test]
+fn set_channel_schedule_works() {
+	new_test_ext().execute_with(|| {
+		System::set_block_number(1);
+
+		assert_noop!(
+			XcmpQueue::set_channel_schedule(Origin::signed(1), 1000.into(), channel_schedule(1, 1)),
+			BadOrigin
+		);
+		assert_noop!(
+			XcmpQueue::set_channel_schedule(Origin::root(), 1000.into(), channel_schedule(1, 0)),
+			Error::<Test>::BadChannelSchedule
+		);
+		// The reserved weight is bounded by a share of the block weight.
+		let max_reserved = max_reserved_weight();
+		assert_noop!(
+			XcmpQueue::set_channel_schedule(
+				Origin::root(),
+				1000.into(),
+				Some(ChannelSchedule {
+					priority: 1,
+					reserved_weight: max_reserved.saturating_add(Weight::from_parts(1, 0)),
+				})
+			),
+			Error::<Test>::BadChannelSchedule
+		);
+
+		assert_ok!(XcmpQueue::set_channel_schedule(
+			Origin::root(),
+			1000.into(),
+			channel_schedule(1, 1)
+		));
+		System::assert_last_event(
+			Event::<Test>::ChannelScheduleSet {
+				sibling: 1000.into(),
+				schedule: channel_schedule(1, 1),
+			}
+			.into(),
+		);
+		assert_eq!(
+			XcmpQueue::inbound_channel_backlog(1000.into()).schedule,
+			channel_schedule(1, 1)
+		);
+
+		for sibling in 1..MAX_SCHEDULED_CHANNELS {
+			assert_ok!(XcmpQueue::set_channel_schedule(
+				Origin::root(),
+				(2000 + sibling).into(),
+				channel_schedule(1, 1)
+			));
+		}
+		assert_noop!(
+			XcmpQueue::set_channel_schedule(Origin::root(), 3000.into(), channel_schedule(1, 1)),
+			Error::<Test>::TooManyScheduledChannels
+		);
+
+		// Scheduled channels can still be updated and removed.
+		assert_ok!(XcmpQueue::set_channel_schedule(
+			Origin::root(),
+			1000.into(),
+			channel_schedule(2, 1)
+		));
+		assert_ok!(XcmpQueue::set_channel_schedule(Origin::root(), 1000.into(), None));
+		assert_eq!(XcmpQueue::inbound_channel_backlog(1000.into()).schedule, None);
+		assert_ok!(XcmpQueue::set_channel_schedule(
+			Origin::root(),
+			3000.into(),
+			channel_schedule(1, 1)
+		));
+	});
+}
+
+#[test]
+fn set_channel_schedule_bounds_the_total_reserved_weight() {
+	new_test_ext().execute_with(|| {
+		let max_reserved = max_reserved_weight();
+		let half = ChannelSchedule { priority: 1, reserved_weight: max_reserved / 2 };
+
+		assert_ok!(XcmpQueue::set_channel_schedule(Origin::root(), 1000.into(), Some(half)));
+		assert_ok!(XcmpQueue::set_channel_schedule(Origin::root(), 1001.into(), Some(half)));
+		assert_noop!(
+			XcmpQueue::set_channel_schedule(Origin::root(), 1002.into(), channel_schedule(1, 1)),
+			Error::<Test>::BadChannelSchedule
+		);
+
+		// Lowering a reservation makes room for others.
+		assert_ok!(XcmpQueue::set_channel_schedule(
+			Origin::root(),
+			1001.into(),
+			channel_schedule(1, 1)
+		));
+		assert_ok!(XcmpQueue::set_channel_schedule(
+			Origin::root(),
+			1002.into(),
+			channel_schedule(1, 1)
+		));
+	});
+}
+
+#[test]
+fn higher_priority_channels_are_serviced_first() {
+	new_test_ext().execute_with(|| {
+		for (sibling, priority) in [(1000, 1), (1001, 2), (1002, 3)] {
+			assert_ok!(XcmpQueue::set_channel_schedule(
+				Origin::root(),
+				sibling.into(),
+				channel_schedule(priority, 2)
+			));
+		}
+		enqueue_messages(1000, 1);
+		enqueue_messages(1001, 1);
+		enqueue_messages(2000, 1);
+
+		// 1002 has the highest priority but nothing to service, and unscheduled channels are left
+		// to the message queue.
+		XcmpQueue::on_initialize(1);
+		assert_eq!(serviced_queues(), vec![(1001, 2), (1000, 2)]);
+		assert_eq!(EnqueuedMessages::get(), vec![(2000.into(), vec![0])]);
+
+		// Nothing is serviced without backlog.
+		XcmpQueue::on_initialize(2);
+		assert!(serviced_queues().is_empty());
+	});
+}
+
+#[test]
+fn scheduled_channels_are_serviced_in_every_block() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmpQueue::set_channel_schedule(
+			Origin::root(),
+			1000.into(),
+			channel_schedule(2, 2)
+		));
+		assert_ok!(XcmpQueue::set_channel_schedule(
+			Origin::root(),
+			1001.into(),
+			channel_schedule(1, 1)
+		));
+		enqueue_messages(1000, 20);
+		enqueue_messages(1001, 13);
+
+		// The channel with the lower priority is not starved by the busier one.
+		for block in 1..=3 {
+			XcmpQueue::on_initialize(block);
+			assert_eq!(serviced_queues(), vec![(1000, 2), (1001, 1)]);
+		}
+
+		let backlog = XcmpQueue::inbound_channel_backlog(1001.into());
+		assert_eq!(
+			backlog,
+			InboundChannelBacklog {
+				messages: 10,
+				size: 10,
+				pages: 10,
+				ready_pages: 10,
+				suspended: false,
+				schedule: channel_schedule(1, 1),
+			}
+		);
+		assert_eq!(
+			XcmpQueue::inbound_channel_backlogs()
+				.into_iter()
+				.map(|(sibling, _)| sibling)
+				.collect::<Vec<_>>(),
+			vec![1000.into(), 1001.into()]
+		);
+	});
+}
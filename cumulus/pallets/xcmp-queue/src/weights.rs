@@ -55,6 +55,8 @@ pub trait WeightInfo {
 	fn enqueue_1000_small_xcmp_messages() -> Weight;
 	fn suspend_channel() -> Weight;
 	fn resume_channel() -> Weight;
+	fn set_channel_schedule() -> Weight;
+	fn service_scheduled_channels(c: u32, ) -> Weight;
 	fn take_first_concatenated_xcm(n: u32, ) -> Weight;
 	fn on_idle_good_msg() -> Weight;
 	fn on_idle_large_msg() -> Weight;
@@ -201,6 +203,34 @@ impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: `XcmpQueue::InboundChannelSchedules` (r:1 w:1)
+	/// Proof: `XcmpQueue::InboundChannelSchedules` (`max_values`: Some(1), `max_size`: Some(373), added: 868, mode: `MaxEncodedLen`)
+	fn set_channel_schedule() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `472`
+		//  Estimated: `1858`
+		// Minimum execution time: 6_000_000 picoseconds.
+		Weight::from_parts(6_000_000, 1858)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmpQueue::InboundChannelSchedules` (r:1 w:0)
+	/// Proof: `XcmpQueue::InboundChannelSchedules` (`max_values`: Some(1), `max_size`: Some(373), added: 868, mode: `MaxEncodedLen`)
+	/// Storage: `MessageQueue::BookStateFor` (r:16 w:0)
+	/// Proof: `MessageQueue::BookStateFor` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 16]`.
+	fn service_scheduled_channels(c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `142 + c * (97 ±0)`
+		//  Estimated: `1858 + c * (2527 ±0)`
+		// Minimum execution time: 2_000_000 picoseconds.
+		Weight::from_parts(2_374_120, 1858)
+			// Standard Error: 1_903
+			.saturating_add(Weight::from_parts(1_482_361, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 2527).saturating_mul(c.into()))
+	}
 	/// The range of component `n` is `[0, 92]`.
 	fn take_first_concatenated_xcm(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
@@ -399,6 +429,34 @@ impl WeightInfo for () {
 			.saturating_add(RocksDbWeight::get().reads(1_u64))
 			.saturating_add(RocksDbWeight::get().writes(1_u64))
 	}
+	/// Storage: `XcmpQueue::InboundChannelSchedules` (r:1 w:1)
+	/// Proof: `XcmpQueue::InboundChannelSchedules` (`max_values`: Some(1), `max_size`: Some(373), added: 868, mode: `MaxEncodedLen`)
+	fn set_channel_schedule() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `472`
+		//  Estimated: `1858`
+		// Minimum execution time: 6_000_000 picoseconds.
+		Weight::from_parts(6_000_000, 1858)
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmpQueue::InboundChannelSchedules` (r:1 w:0)
+	/// Proof: `XcmpQueue::InboundChannelSchedules` (`max_values`: Some(1), `max_size`: Some(373), added: 868, mode: `MaxEncodedLen`)
+	/// Storage: `MessageQueue::BookStateFor` (r:16 w:0)
+	/// Proof: `MessageQueue::BookStateFor` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 16]`.
+	fn service_scheduled_channels(c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `142 + c * (97 ±0)`
+		//  Estimated: `1858 + c * (2527 ±0)`
+		// Minimum execution time: 2_000_000 picoseconds.
+		Weight::from_parts(2_374_120, 1858)
+			// Standard Error: 1_903
+			.saturating_add(Weight::from_parts(1_482_361, 0).saturating_mul(c.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 2527).saturating_mul(c.into()))
+	}
 	/// The range of component `n` is `[0, 92]`.
 	fn take_first_concatenated_xcm(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
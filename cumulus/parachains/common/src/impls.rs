@@ -290,6 +290,7 @@ mod tests {
 		type ValidatorId = <Self as frame_system::Config>::AccountId;
 		type ValidatorIdOf = IdentityCollator;
 		type ValidatorRegistration = IsRegistered;
+		type UnbondingSessions = ConstU32<0>;
 		type KickThreshold = ();
 		type WeightInfo = ();
 	}
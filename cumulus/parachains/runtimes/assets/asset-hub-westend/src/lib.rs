@@ -1034,6 +1034,7 @@ impl pallet_collator_selection::Config for Runtime {
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
 	type ValidatorRegistration = Session;
+	type UnbondingSessions = ConstU32<0>;
 	type WeightInfo = weights::pallet_collator_selection::WeightInfo<Runtime>;
 }
 
@@ -1972,6 +1973,7 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		}
 	}
 
+	#[api_version(3)]
 	impl xcm_runtime_apis::dry_run::DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for Runtime {
 		fn dry_run_call(origin: OriginCaller, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			PolkadotXcm::dry_run_call::<Runtime, xcm_config::XcmRouter, OriginCaller, RuntimeCall>(origin, call, result_xcms_version)
@@ -1980,6 +1982,10 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			PolkadotXcm::dry_run_xcm::<xcm_config::XcmRouter>(origin_location, xcm)
 		}
+
+		fn dry_run_xcm_explained(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<xcm_runtime_apis::dry_run::ExplainedXcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
+			PolkadotXcm::dry_run_xcm_explained::<xcm_config::XcmRouter>(origin_location, xcm)
+		}
 	}
 
 	impl xcm_runtime_apis::conversions::LocationToAccountApi<Block, AccountId> for Runtime {
@@ -2018,6 +2024,45 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		}
 	}
 
+	impl xcm_runtime_apis::trapped_assets::TrappedAssetsApi<Block> for Runtime {
+		fn trapped_assets(origin: VersionedLocation) -> Result<
+			Vec<xcm_runtime_apis::trapped_assets::TrappedAssets>,
+			xcm_runtime_apis::trapped_assets::Error
+		> {
+			PolkadotXcm::trapped_assets(origin)
+		}
+		fn claim_trapped_assets(
+			origin: VersionedLocation,
+			hash: sp_core::H256,
+			beneficiary: VersionedLocation,
+			fee_asset: Option<VersionedAssetId>,
+		) -> Result<
+			xcm_runtime_apis::trapped_assets::AssetClaim,
+			xcm_runtime_apis::trapped_assets::Error
+		> {
+			type Trader = <XcmConfig as xcm_executor::Config>::Trader;
+			PolkadotXcm::claim_trapped_assets::<Trader>(origin, hash, beneficiary, fee_asset)
+		}
+	}
+
+	impl xcm_runtime_apis::versions::XcmVersionApi<Block> for Runtime {
+		fn destination_version(destination: VersionedLocation) -> Result<
+			xcm_runtime_apis::versions::DestinationVersion,
+			xcm_runtime_apis::versions::Error
+		> {
+			PolkadotXcm::destination_version(destination)
+		}
+		fn version_subscriptions(xcm_version: XcmVersion) -> Result<
+			Vec<xcm_runtime_apis::versions::VersionSubscription>,
+			xcm_runtime_apis::versions::Error
+		> {
+			PolkadotXcm::version_subscriptions(xcm_version)
+		}
+		fn safe_xcm_version() -> Option<XcmVersion> {
+			PolkadotXcm::safe_xcm_version()
+		}
+	}
+
 	impl pallet_transaction_payment_rpc_runtime_api::TransactionPaymentCallApi<Block, Balance, RuntimeCall>
 		for Runtime
 	{
@@ -2087,6 +2132,17 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		}
 	}
 
+	impl cumulus_pallet_xcmp_queue::runtime_api::XcmpQueueApi<Block> for Runtime {
+		fn inbound_channel_backlog(
+			sibling: ParaId,
+		) -> cumulus_pallet_xcmp_queue::InboundChannelBacklog {
+			XcmpQueue::inbound_channel_backlog(sibling)
+		}
+		fn inbound_channel_backlogs() -> Vec<(ParaId, cumulus_pallet_xcmp_queue::InboundChannelBacklog)> {
+			XcmpQueue::inbound_channel_backlogs()
+		}
+	}
+
 	impl pallet_asset_rewards::AssetRewards<Block, Balance> for Runtime {
 		fn pool_creation_cost() -> Balance {
 			StakePoolCreationDeposit::get()
@@ -262,7 +262,7 @@ impl<T: frame_system::Config> pallet_collator_selection::WeightInfo for WeightIn
 	/// Proof: `System::Account` (`max_values`: None, `max_size`: Some(128), added: 2603, mode: `MaxEncodedLen`)
 	/// The range of component `r` is `[1, 100]`.
 	/// The range of component `c` is `[1, 100]`.
-	fn new_session(r: u32, c: u32, ) -> Weight {
+	fn new_session(r: u32, c: u32, _u: u32, ) -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `2265 + c * (97 ±0) + r * (114 ±0)`
 		//  Estimated: `6287 + c * (2519 ±0) + r * (2603 ±0)`
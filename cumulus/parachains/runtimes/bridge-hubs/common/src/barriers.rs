@@ -34,6 +34,8 @@ where
 	FromOrigin: Contains<Location>,
 	ToGlobalConsensus: Contains<NetworkId>,
 {
+	const NAME: &'static str = "DenyExportMessageFrom";
+
 	fn deny_execution<RuntimeCall>(
 		origin: &Location,
 		message: &mut [Instruction<RuntimeCall>],
@@ -690,6 +690,13 @@ impl pallet_message_queue::Config for Runtime {
 	type IdleMaxServiceWeight = MessageQueueServiceWeight;
 }
 
+impl pallet_xcm_rate_limiter::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxPeriod = ConstU32<100>;
+	type WeightInfo = pallet_xcm_rate_limiter::weights::SubstrateWeight<Runtime>;
+}
+
 impl cumulus_pallet_aura_ext::Config for Runtime {}
 
 parameter_types! {
@@ -774,7 +781,81 @@ impl pallet_collator_selection::Config for Runtime {
 	type ValidatorId = <Self as frame_system::Config>::AccountId;
 	type ValidatorIdOf = pallet_collator_selection::IdentityCollator;
 	type ValidatorRegistration = Session;
-	type WeightInfo = ();
+	// Former candidates author until the end of the next session and their equivocations can be
+	// reported for `ReportLongevity` afterwards, so their deposits stay slashable until then.
+	type UnbondingSessions = ConstU32<3>;
+	type WeightInfo = weights::pallet_collator_selection::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	// Equivocations can be reported for an hour, which is well below the session length.
+	pub const ReportLongevity: u64 = HOURS as u64 * MILLISECS_PER_BLOCK / SLOT_DURATION;
+}
+
+/// Removes the collator selection candidates reported for equivocating and moves their bond to the
+/// pot.
+pub struct SlashCandidate;
+impl cumulus_pallet_aura_equivocation::HandleEquivocation<AccountId> for SlashCandidate {
+	fn handle_equivocation(
+		offender: &AccountId,
+		_: sp_consensus_aura::Slot,
+	) -> frame_support::dispatch::DispatchResult {
+		CollatorSelection::slash_candidate(offender).map(|_| ())
+	}
+}
+
+impl cumulus_pallet_aura_equivocation::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type KeyOwner = cumulus_pallet_aura_equivocation::SessionKeyOwner<Self>;
+	type HandleEquivocation = SlashCandidate;
+	type ReportLongevity = ReportLongevity;
+	type WeightInfo = weights::cumulus_pallet_aura_equivocation::WeightInfo<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = AuraEquivocationBenchmarkHelper;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct AuraEquivocationBenchmarkHelper;
+
+#[cfg(feature = "runtime-benchmarks")]
+impl cumulus_pallet_aura_equivocation::BenchmarkHelper<AuraId, AccountId>
+	for AuraEquivocationBenchmarkHelper
+{
+	fn setup_offender() -> (AuraId, AccountId) {
+		use codec::Encode;
+		use frame_support::traits::Currency;
+
+		let offender: AccountId = frame_benchmarking::account("offender", 0, 0);
+		let keys = SessionKeys::generate(&offender.encode(), None);
+		let bond = pallet_collator_selection::CandidacyBond::<Runtime>::get();
+		Balances::make_free_balance_be(&offender, bond * 2 + ExistentialDeposit::get());
+		Session::set_keys(
+			RuntimeOrigin::signed(offender.clone()),
+			keys.keys.clone(),
+			keys.proof.encode(),
+		)
+		.expect("the keys were just generated for the offender");
+		CollatorSelection::register_as_candidate(RuntimeOrigin::signed(offender.clone()))
+			.expect("the offender can pay the candidacy bond");
+		(keys.keys.aura, offender)
+	}
+}
+
+impl<C> frame_system::offchain::CreateTransactionBase<C> for Runtime
+where
+	RuntimeCall: From<C>,
+{
+	type Extrinsic = UncheckedExtrinsic;
+	type RuntimeCall = RuntimeCall;
+}
+
+impl<LocalCall> frame_system::offchain::CreateBare<LocalCall> for Runtime
+where
+	RuntimeCall: From<LocalCall>,
+{
+	fn create_bare(call: RuntimeCall) -> UncheckedExtrinsic {
+		generic::UncheckedExtrinsic::new_bare(call).into()
+	}
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -878,12 +959,14 @@ construct_runtime!(
 		Session: pallet_session = 22,
 		Aura: pallet_aura = 23,
 		AuraExt: cumulus_pallet_aura_ext = 24,
+		AuraEquivocation: cumulus_pallet_aura_equivocation = 25,
 
 		// XCM helpers.
 		XcmpQueue: cumulus_pallet_xcmp_queue = 30,
 		PolkadotXcm: pallet_xcm = 31,
 		CumulusXcm: cumulus_pallet_xcm = 32,
 		MessageQueue: pallet_message_queue = 34,
+		XcmRateLimiter: pallet_xcm_rate_limiter = 35,
 
 		// Handy utilities.
 		Utility: pallet_utility = 40,
@@ -911,8 +994,10 @@ mod benches {
 		[pallet_sudo, Sudo]
 		[pallet_timestamp, Timestamp]
 		[pallet_collator_selection, CollatorSelection]
+		[cumulus_pallet_aura_equivocation, AuraEquivocation]
 		[cumulus_pallet_parachain_system, ParachainSystem]
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
+		[pallet_xcm_rate_limiter, XcmRateLimiter]
 		[pallet_utility, Utility]
 	);
 }
@@ -1087,6 +1172,7 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		}
 	}
 
+	#[api_version(3)]
 	impl xcm_runtime_apis::dry_run::DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for Runtime {
 		fn dry_run_call(origin: OriginCaller, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			PolkadotXcm::dry_run_call::<Runtime, xcm_config::XcmRouter, OriginCaller, RuntimeCall>(origin, call, result_xcms_version)
@@ -1095,6 +1181,10 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<XcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			PolkadotXcm::dry_run_xcm::<xcm_config::XcmRouter>(origin_location, xcm)
 		}
+
+		fn dry_run_xcm_explained(origin_location: VersionedLocation, xcm: VersionedXcm<RuntimeCall>) -> Result<xcm_runtime_apis::dry_run::ExplainedXcmDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
+			PolkadotXcm::dry_run_xcm_explained::<xcm_config::XcmRouter>(origin_location, xcm)
+		}
 	}
 
 	impl xcm_runtime_apis::conversions::LocationToAccountApi<Block, AccountId> for Runtime {
@@ -1133,6 +1223,45 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 		}
 	}
 
+	impl xcm_runtime_apis::trapped_assets::TrappedAssetsApi<Block> for Runtime {
+		fn trapped_assets(origin: VersionedLocation) -> Result<
+			Vec<xcm_runtime_apis::trapped_assets::TrappedAssets>,
+			xcm_runtime_apis::trapped_assets::Error
+		> {
+			PolkadotXcm::trapped_assets(origin)
+		}
+		fn claim_trapped_assets(
+			origin: VersionedLocation,
+			hash: sp_core::H256,
+			beneficiary: VersionedLocation,
+			fee_asset: Option<VersionedAssetId>,
+		) -> Result<
+			xcm_runtime_apis::trapped_assets::AssetClaim,
+			xcm_runtime_apis::trapped_assets::Error
+		> {
+			type Trader = <XcmConfig as xcm_executor::Config>::Trader;
+			PolkadotXcm::claim_trapped_assets::<Trader>(origin, hash, beneficiary, fee_asset)
+		}
+	}
+
+	impl xcm_runtime_apis::versions::XcmVersionApi<Block> for Runtime {
+		fn destination_version(destination: VersionedLocation) -> Result<
+			xcm_runtime_apis::versions::DestinationVersion,
+			xcm_runtime_apis::versions::Error
+		> {
+			PolkadotXcm::destination_version(destination)
+		}
+		fn version_subscriptions(xcm_version: XcmVersion) -> Result<
+			Vec<xcm_runtime_apis::versions::VersionSubscription>,
+			xcm_runtime_apis::versions::Error
+		> {
+			PolkadotXcm::version_subscriptions(xcm_version)
+		}
+		fn safe_xcm_version() -> Option<XcmVersion> {
+			PolkadotXcm::safe_xcm_version()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
@@ -1231,6 +1360,17 @@ pallet_revive::impl_runtime_apis_plus_revive_traits!(
 			ConsensusHook::can_build_upon(included_hash, slot)
 		}
 	}
+
+	impl cumulus_primitives_aura::AuraEquivocationApi<Block, AuraId> for Runtime {
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: cumulus_primitives_aura::EquivocationProof<
+				<Block as BlockT>::Header,
+				AuraId,
+			>,
+		) -> Option<()> {
+			AuraEquivocation::submit_unsigned_equivocation_report(equivocation_proof)
+		}
+	}
 );
 
 cumulus_pallet_parachain_system::register_validate_block! {
@@ -29,7 +29,9 @@
 //! Expose the auto generated weight files.
 
 pub mod block_weights;
+pub mod cumulus_pallet_aura_equivocation;
 pub mod extrinsic_weights;
+pub mod pallet_collator_selection;
 pub mod paritydb_weights;
 pub mod rocksdb_weights;
 
@@ -0,0 +1,176 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Autogenerated weights for `pallet_collator_selection`
+//!
+//! THIS FILE WAS AUTO-GENERATED USING THE SUBSTRATE BENCHMARK CLI VERSION 32.0.0
+//! DATE: 2026-10-19, STEPS: `50`, REPEAT: `20`, LOW RANGE: `[]`, HIGH RANGE: `[]`
+//! WORST CASE MAP SIZE: `1000000`
+//! HOSTNAME: `vm`, CPU: `Intel(R) Xeon(R) Processor`
+//! WASM-EXECUTION: `Compiled`, CHAIN: `None`, DB CACHE: 1024
+
+// Executed Command:
+// ./target/release/frame-omni-bencher
+// v1
+// benchmark
+// pallet
+// --extrinsic=*
+// --runtime=target/debug/wbuild/penpal-runtime/penpal_runtime.wasm
+// --pallet=pallet_collator_selection
+// --header=./cumulus/file_header.txt
+// --output=./cumulus/parachains/runtimes/testing/penpal/src/weights
+// --wasm-execution=compiled
+// --steps=50
+// --repeat=20
+// --heap-pages=4096
+// --no-storage-info
+// --no-min-squares
+// --no-median-slopes
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_collator_selection`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_collator_selection::WeightInfo for WeightInfo<T> {
+	/// The range of component `b` is `[1, 20]`.
+	fn set_invulnerables(b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `164 + b * (79 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 23_785_000 picoseconds.
+		Weight::from_parts(30_006_830, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 53_327
+			.saturating_add(Weight::from_parts(6_666_386, 0).saturating_mul(b.into()))
+	}
+	/// The range of component `b` is `[1, 19]`.
+	/// The range of component `c` is `[1, 99]`.
+	fn add_invulnerable(_b: u32, c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `718 + b * (32 ±0) + c * (52 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 81_156_000 picoseconds.
+		Weight::from_parts(102_290_710, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 18_194
+			.saturating_add(Weight::from_parts(246_435, 0).saturating_mul(c.into()))
+	}
+	/// The range of component `b` is `[5, 20]`.
+	fn remove_invulnerable(b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `106 + b * (32 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 25_773_000 picoseconds.
+		Weight::from_parts(34_577_776, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 47_204
+			.saturating_add(Weight::from_parts(648_779, 0).saturating_mul(b.into()))
+	}
+	fn set_desired_candidates() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 8_068_000 picoseconds.
+		Weight::from_parts(8_365_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+	/// The range of component `c` is `[0, 100]`.
+	/// The range of component `k` is `[0, 100]`.
+	fn set_candidacy_bond(c: u32, k: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `188 + c * (48 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 15_255_000 picoseconds.
+		Weight::from_parts(15_679_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 89_856
+			.saturating_add(Weight::from_parts(3_177_240, 0).saturating_mul(c.into()))
+			// Standard Error: 89_856
+			.saturating_add(Weight::from_parts(2_989_578, 0).saturating_mul(k.into()))
+	}
+	/// The range of component `c` is `[4, 100]`.
+	fn update_bond(c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `300 + c * (49 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 56_205_000 picoseconds.
+		Weight::from_parts(67_020_023, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 23_298
+			.saturating_add(Weight::from_parts(522_585, 0).saturating_mul(c.into()))
+	}
+	/// The range of component `c` is `[1, 99]`.
+	fn register_as_candidate(c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `710 + c * (52 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 78_068_000 picoseconds.
+		Weight::from_parts(105_433_171, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 26_618
+			.saturating_add(Weight::from_parts(239_531, 0).saturating_mul(c.into()))
+	}
+	/// The range of component `c` is `[4, 100]`.
+	fn take_candidate_slot(c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `768 + c * (52 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 98_035_000 picoseconds.
+		Weight::from_parts(119_963_735, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 23_951
+			.saturating_add(Weight::from_parts(443_849, 0).saturating_mul(c.into()))
+	}
+	/// The range of component `c` is `[4, 100]`.
+	fn leave_intent(c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `208 + c * (48 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 46_783_000 picoseconds.
+		Weight::from_parts(62_683_350, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 23_138
+			.saturating_add(Weight::from_parts(345_624, 0).saturating_mul(c.into()))
+	}
+	fn note_author() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `155`
+		//  Estimated: `0`
+		// Minimum execution time: 79_521_000 picoseconds.
+		Weight::from_parts(81_833_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+	}
+	/// The range of component `r` is `[1, 100]`.
+	/// The range of component `c` is `[1, 100]`.
+	/// The range of component `u` is `[0, 100]`.
+	fn new_session(_r: u32, c: u32, u: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0 + c * (126 ±0) + u * (209 ±0)`
+		//  Estimated: `0`
+		// Minimum execution time: 986_440_000 picoseconds.
+		Weight::from_parts(1_057_472_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			// Standard Error: 704_504
+			.saturating_add(Weight::from_parts(7_423_858, 0).saturating_mul(c.into()))
+			// Standard Error: 701_052
+			.saturating_add(Weight::from_parts(37_554_265, 0).saturating_mul(u.into()))
+	}
+}
@@ -28,6 +28,8 @@ use crate::{
 };
 use chain_spec_builder::ChainSpecBuilder;
 use clap::{Command, CommandFactory, FromArgMatches, ValueEnum};
+use cumulus_client_consensus_aura::collators::slot_based::CoreScarcityConfig;
+use cumulus_client_on_demand::{OnDemandConfig, PaymentMethod};
 use sc_chain_spec::ChainSpec;
 use sc_cli::{
 	CliConfiguration, DefaultConfigurationValues, ImportParams, KeystoreParams, NetworkParams,
@@ -35,10 +37,12 @@ use sc_cli::{
 };
 use sc_service::{config::PrometheusConfig, BasePath};
 use sc_storage_monitor::StorageMonitorParams;
+use sp_runtime::Percent;
 use std::{
 	fmt::{Display, Formatter},
 	marker::PhantomData,
 	path::PathBuf,
+	time::Duration,
 };
 /// Trait that can be used to customize some of the customer-facing info related to the node binary
 /// that is being built using this library.
@@ -143,7 +147,8 @@ pub enum Subcommand {
 #[command(
 	propagate_version = true,
 	args_conflicts_with_subcommands = true,
-	subcommand_negates_reqs = true
+	subcommand_negates_reqs = true,
+	group(clap::ArgGroup::new("scarce_cores").multiple(true))
 )]
 pub struct Cli<Config: CliConfig> {
 	#[arg(skip)]
@@ -184,6 +189,22 @@ pub struct Cli<Config: CliConfig> {
 	#[arg(long, conflicts_with = "dev_block_time")]
 	pub instant_seal: bool,
 
+	/// Upgrade the runtime of the dev node each time this wasm file changes.
+	///
+	/// This is a dev option. The new runtime is applied within the next blocks, bypassing
+	/// governance: its upgrade is authorized by a mocked downward message dispatched with the
+	/// `Superuser` origin, which the runtime must allow from the relay chain (e.g. using
+	/// `ParentAsSuperuser`). If the runtime implements the `DryRunApi`, the node refuses to start
+	/// when a dry run of this message fails. When the node and the new runtime are built with the
+	/// `try-runtime` feature, the migrations are first checked against the best block. Upgrades
+	/// failing these checks are reported and not applied.
+	///
+	/// The option conflicts with `--instant-seal`: an upgrade needs a few blocks to be authorized
+	/// and enacted, while instant seal only produces blocks when transactions are received, so the
+	/// upgrade would stall until unrelated transactions are submitted.
+	#[arg(long, value_name = "PATH", conflicts_with = "instant_seal")]
+	pub dev_watch_runtime: Option<PathBuf>,
+
 	/// DEPRECATED: This feature has been stabilized, pLease use `--authoring slot-based` instead.
 	///
 	/// Use slot-based collator which can handle elastic scaling.
@@ -212,6 +233,77 @@ pub struct Cli<Config: CliConfig> {
 	#[arg(long)]
 	pub export_pov_to_path: Option<PathBuf>,
 
+	/// Only author blocks while holding the lease stored in this file.
+	///
+	/// Nodes sharing the same authoring keys and lease file coordinate so that only one of them
+	/// authors at a time, letting standby nodes take over when the authoring node stops. The file
+	/// must be accessible to all of these nodes, e.g. on a shared volume.
+	///
+	/// The file is locked with `flock`, which is unreliable on NFS: the volume must honor `flock`
+	/// across the nodes, otherwise several of them may author and equivocate. The holder and
+	/// expiry written in the file are read back after each update, which only catches some of
+	/// these conflicts.
+	///
+	/// Only supported by the slot-based collator.
+	#[arg(long)]
+	pub authoring_lease_file: Option<PathBuf>,
+
+	/// Duration of the authoring lease in milliseconds.
+	///
+	/// The authoring node renews the lease whenever it authors in a slot, and a standby node takes
+	/// over once the lease wasn't renewed for this duration. Must be longer than the time between
+	/// two slots of the authoring node and the clock drift between the nodes.
+	#[arg(long, default_value_t = 12000, requires = "authoring_lease_file")]
+	pub authoring_lease_duration: u64,
+
+	/// The maximum percentage of the maximum PoV size that the collator can use while relay
+	/// chain cores are scarce.
+	///
+	/// Cores are scarce when the claim queue assigns a core to the parachain for less than
+	/// `--scarce-cores-threshold` percent of the upcoming relay chain blocks.
+	///
+	/// Only supported by the slot-based collator.
+	#[arg(long, value_parser = clap::value_parser!(u32).range(0..=100), group = "scarce_cores")]
+	pub scarce_cores_max_pov_percentage: Option<u32>,
+
+	/// Only include transactions with at least this priority while relay chain cores are scarce.
+	///
+	/// The other transactions are kept in the transaction pool for later blocks.
+	///
+	/// Only supported by the slot-based collator.
+	#[arg(long, group = "scarce_cores")]
+	pub scarce_cores_min_transaction_priority: Option<u64>,
+
+	/// Percentage of the upcoming relay chain blocks with a core assigned to the parachain below
+	/// which cores are considered scarce.
+	///
+	/// Requires `--scarce-cores-max-pov-percentage` or `--scarce-cores-min-transaction-priority`.
+	///
+	/// Only supported by the slot-based collator.
+	#[arg(
+		long,
+		default_value_t = 50,
+		value_parser = clap::value_parser!(u8).range(0..=100),
+		requires = "scarce_cores"
+	)]
+	pub scarce_cores_threshold: u8,
+
+	/// Place on-demand coretime orders for the parachain at a spot price of at most this amount.
+	///
+	/// Orders are placed whenever transactions are waiting to be included while the parachain has
+	/// no core assigned and no order queued. They are signed with the sr25519 key of type `ondm`
+	/// in the keystore, which can be inserted with `key insert --key-type ondm --scheme sr25519`.
+	#[arg(long, requires = "on_demand_budget")]
+	pub on_demand_max_spot_price: Option<u128>,
+
+	/// The total amount the on-demand orders may be charged.
+	#[arg(long, requires = "on_demand_max_spot_price")]
+	pub on_demand_budget: Option<u128>,
+
+	/// How on-demand orders are paid for.
+	#[arg(long, default_value_t = OnDemandPayment::Credits, requires = "on_demand_max_spot_price")]
+	pub on_demand_payment: OnDemandPayment,
+
 	/// Relay chain arguments
 	#[arg(raw = true)]
 	pub relay_chain_args: Vec<String>,
@@ -264,6 +356,24 @@ impl Display for AuthoringPolicy {
 	}
 }
 
+/// How on-demand orders are paid for.
+#[derive(PartialEq, Debug, ValueEnum, Clone, Copy)]
+pub enum OnDemandPayment {
+	/// Pay with the on-demand credits of the account.
+	Credits,
+	/// Pay with the free balance of the account, keeping it alive.
+	Balance,
+}
+
+impl Display for OnDemandPayment {
+	fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+		match self {
+			OnDemandPayment::Credits => write!(f, "credits"),
+			OnDemandPayment::Balance => write!(f, "balance"),
+		}
+	}
+}
+
 impl<Config: CliConfig> Cli<Config> {
 	pub(crate) fn node_extra_args(&self) -> NodeExtraArgs {
 		NodeExtraArgs {
@@ -272,7 +382,26 @@ impl<Config: CliConfig> Cli<Config> {
 				.then(|| AuthoringPolicy::SlotBased)
 				.unwrap_or(self.authoring),
 			export_pov: self.export_pov_to_path.clone(),
+			authoring_lease_file: self.authoring_lease_file.clone(),
+			authoring_lease_duration: Duration::from_millis(self.authoring_lease_duration),
 			max_pov_percentage: self.run.experimental_max_pov_percentage,
+			core_scarcity: (self.scarce_cores_max_pov_percentage.is_some() ||
+				self.scarce_cores_min_transaction_priority.is_some())
+			.then(|| CoreScarcityConfig {
+				threshold: Percent::from_percent(self.scarce_cores_threshold),
+				max_pov_percentage: self.scarce_cores_max_pov_percentage,
+				min_transaction_priority: self.scarce_cores_min_transaction_priority,
+			}),
+			on_demand: self.on_demand_max_spot_price.zip(self.on_demand_budget).map(
+				|(max_spot_price, budget)| OnDemandConfig {
+					payment: match self.on_demand_payment {
+						OnDemandPayment::Credits => PaymentMethod::Credits,
+						OnDemandPayment::Balance => PaymentMethod::Balance,
+					},
+					max_spot_price,
+					budget,
+				},
+			),
 			enable_statement_store: self.enable_statement_store,
 			statement_network_workers: self.statement_network_workers,
 			storage_monitor: self.storage_monitor.clone(),
@@ -302,7 +302,13 @@ where
 					new_node_spec(&config, &cmd_config.runtime_resolver, &cli.node_extra_args())?;
 
 				if let Some(dev_mode) = cli.dev_mode() {
-					return node_spec.start_dev_node(config, dev_mode).map_err(Into::into);
+					return node_spec
+						.start_dev_node(config, dev_mode, cli.dev_watch_runtime.clone())
+						.map_err(Into::into);
+				}
+
+				if cli.dev_watch_runtime.is_some() {
+					return Err("`--dev-watch-runtime` is only supported by dev nodes".into());
 				}
 
 				// If Statemint (Statemine, Westmint, Rockmine) DB exists and we're using the
@@ -17,7 +17,7 @@
 //! Aura-related primitives for cumulus parachain collators.
 
 use codec::Codec;
-use cumulus_primitives_aura::AuraUnincludedSegmentApi;
+use cumulus_primitives_aura::{AuraEquivocationApi, AuraUnincludedSegmentApi};
 use sp_consensus_aura::AuraApi;
 use sp_runtime::{
 	app_crypto::{AppCrypto, AppPair, AppSignature, Pair},
@@ -53,6 +53,7 @@ pub trait AuraRuntimeApi<Block: BlockT, AuraId: AuraIdT>:
 	sp_api::ApiExt<Block>
 	+ AuraApi<Block, <AuraId::BoundedPair as Pair>::Public>
 	+ AuraUnincludedSegmentApi<Block>
+	+ AuraEquivocationApi<Block, <AuraId::BoundedPair as Pair>::Public>
 	+ Sized
 {
 	/// Check if the runtime has the Aura API.
@@ -66,5 +67,6 @@ impl<T, Block: BlockT, AuraId: AuraIdT> AuraRuntimeApi<Block, AuraId> for T wher
 	T: sp_api::ApiExt<Block>
 		+ AuraApi<Block, <AuraId::BoundedPair as Pair>::Public>
 		+ AuraUnincludedSegmentApi<Block>
+		+ AuraEquivocationApi<Block, <AuraId::BoundedPair as Pair>::Public>
 {
 }
@@ -23,12 +23,16 @@ pub mod chain_spec;
 pub mod command;
 pub mod rpc;
 pub mod runtime;
+pub(crate) mod runtime_watcher;
 pub mod spec;
 pub(crate) mod statement_store;
 pub mod types;
+pub(crate) mod xcmp_queue_metrics;
 
 use crate::cli::AuthoringPolicy;
 
+use cumulus_client_consensus_aura::collators::slot_based::CoreScarcityConfig;
+use cumulus_pallet_xcmp_queue::runtime_api::XcmpQueueApi;
 use cumulus_primitives_core::{CollectCollationInfo, GetParachainInfo, RelayParentOffsetApi};
 use sc_client_db::DbHash;
 use sc_offchain::OffchainWorkerApi;
@@ -43,7 +47,7 @@ use sp_session::SessionKeys;
 use sp_statement_store::runtime_api::ValidateStatement;
 use sp_transaction_pool::runtime_api::TaggedTransactionQueue;
 use sp_transaction_storage_proof::runtime_api::TransactionStorageApi;
-use std::{fmt::Debug, path::PathBuf, str::FromStr};
+use std::{fmt::Debug, path::PathBuf, str::FromStr, time::Duration};
 
 pub trait NodeBlock:
 	BlockT<Extrinsic = OpaqueExtrinsic, Header = Self::BoundedHeader, Hash = DbHash> + DeserializeOwned
@@ -77,6 +81,7 @@ pub trait NodeRuntimeApi<Block: BlockT>:
 	+ GetParachainInfo<Block>
 	+ TransactionStorageApi<Block>
 	+ RelayParentOffsetApi<Block>
+	+ XcmpQueueApi<Block>
 	+ Sized
 {
 }
@@ -93,6 +98,7 @@ impl<T, Block: BlockT> NodeRuntimeApi<Block> for T where
 		+ ValidateStatement<Block>
 		+ GetParachainInfo<Block>
 		+ TransactionStorageApi<Block>
+		+ XcmpQueueApi<Block>
 {
 }
 
@@ -123,10 +129,23 @@ pub struct NodeExtraArgs {
 	/// If set, each `PoV` build by the node will be exported to this folder.
 	pub export_pov: Option<PathBuf>,
 
+	/// If set, blocks are only authored while holding the lease stored in this file.
+	pub authoring_lease_file: Option<PathBuf>,
+
+	/// The duration of the authoring lease.
+	pub authoring_lease_duration: Duration,
+
 	/// The maximum percentage of the maximum PoV size that the collator can use.
 	/// It will be removed once <https://github.com/paritytech/polkadot-sdk/issues/6020> is fixed.
 	pub max_pov_percentage: Option<u32>,
 
+	/// If set, the slot-based collator adapts its blocks to the availability of relay chain
+	/// cores.
+	pub core_scarcity: Option<CoreScarcityConfig>,
+
+	/// If set, the collator places on-demand coretime orders for the parachain.
+	pub on_demand: Option<cumulus_client_on_demand::OnDemandConfig>,
+
 	/// If true then the statement store will be enabled.
 	pub enable_statement_store: bool,
 
@@ -22,6 +22,8 @@ use crate::common::{
 	types::{AccountId, Balance, Nonce, ParachainBackend, ParachainClient},
 	ConstructNodeRuntimeApi,
 };
+use cumulus_client_collator::orphaned_blocks::OrphanedBlocks;
+use cumulus_client_collator_rpc::{Collator, CollatorApiServer};
 use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 use sc_rpc::{
 	dev::{Dev, DevApiServer},
@@ -35,12 +37,13 @@ use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpsee::RpcModule<()>;
 
-pub(crate) trait BuildRpcExtensions<Client, Backend, Pool, StatementStore> {
+pub(crate) trait BuildRpcExtensions<Block: BlockT, Client, Backend, Pool, StatementStore> {
 	fn build_rpc_extensions(
 		client: Arc<Client>,
 		backend: Arc<Backend>,
 		pool: Arc<Pool>,
 		statement_store: Option<Arc<StatementStore>>,
+		orphaned_blocks: Option<OrphanedBlocks<Block>>,
 	) -> sc_service::error::Result<RpcExtension>;
 }
 
@@ -48,6 +51,7 @@ pub(crate) struct BuildParachainRpcExtensions<Block, RuntimeApi>(PhantomData<(Bl
 
 impl<Block: BlockT, RuntimeApi>
 	BuildRpcExtensions<
+		Block,
 		ParachainClient<Block, RuntimeApi>,
 		ParachainBackend<Block>,
 		sc_transaction_pool::TransactionPoolHandle<Block, ParachainClient<Block, RuntimeApi>>,
@@ -66,6 +70,7 @@ where
 			sc_transaction_pool::TransactionPoolHandle<Block, ParachainClient<Block, RuntimeApi>>,
 		>,
 		statement_store: Option<Arc<sc_statement_store::Store>>,
+		orphaned_blocks: Option<OrphanedBlocks<Block>>,
 	) -> sc_service::error::Result<RpcExtension> {
 		let build = || -> Result<RpcExtension, Box<dyn std::error::Error + Send + Sync>> {
 			let mut module = RpcExtension::new(());
@@ -76,6 +81,9 @@ where
 			if let Some(statement_store) = statement_store {
 				module.merge(StatementStore::new(statement_store).into_rpc())?;
 			}
+			if let Some(orphaned_blocks) = orphaned_blocks {
+				module.merge(Collator::new(orphaned_blocks).into_rpc())?;
+			}
 			module.merge(Dev::new(client).into_rpc())?;
 
 			Ok(module)
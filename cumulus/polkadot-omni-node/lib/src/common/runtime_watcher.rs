@@ -0,0 +1,700 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime hot-reload for dev nodes.
+//!
+//! The [`RuntimeWatcher`] watches a wasm file and upgrades the runtime of the dev node each time
+//! the file changes, bypassing governance: a mocked downward message authorizes the upgrade with
+//! the `Superuser` origin, after which the authorized code is applied by an unsigned extrinsic.
+//! The new validation code is then scheduled by the parachain system pallet and enacted as soon as
+//! the dev node signals the go-ahead.
+//!
+//! Before authorizing an upgrade, the migrations of the new runtime are tried against the best
+//! block using its `TryRuntime` API. Upgrades failing these checks are reported and not applied.
+//!
+//! # Runtime requirements
+//!
+//! The runtime must dispatch `Transact` instructions received from the relay chain with the
+//! `Superuser` origin, typically by including `ParentAsSuperuser` in the `OriginConverter` of its
+//! XCM configuration, and must let that origin authorize upgrades, which `frame-system` does with
+//! the default `Root` origin. When the runtime implements the `DryRunApi`, this is checked by
+//! [`RuntimeWatcher::check_runtime`] before watching the file. Otherwise the authorization is
+//! dropped by the runtime and the upgrade is reported as failed after a few blocks.
+
+use crate::common::{
+	runtime::DEFAULT_FRAME_SYSTEM_PALLET_NAME, types::ParachainHostFunctions, NodeBlock,
+};
+use codec::{Decode, Encode};
+use sc_client_api::HeaderBackend;
+use sc_executor::{RuntimeVersionOf, WasmExecutor};
+use sc_runtime_utilities::fetch_latest_metadata_from_code_blob;
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use sp_api::CallApiAt;
+use sp_core::traits::{RuntimeCode, WrappedRuntimeCode};
+use sp_runtime::{
+	generic::UncheckedExtrinsic,
+	traits::{Hash as HashT, HashingFor, UniqueSaturatedInto},
+	OpaqueExtrinsic,
+};
+use sp_state_machine::{Backend as StateBackend, BasicExternalities};
+use std::{
+	path::PathBuf,
+	sync::{Arc, Mutex},
+	time::{Duration, SystemTime},
+};
+use subxt_metadata::Metadata;
+use xcm::{
+	latest::{Instruction, Location, MaybeErrorCode, OriginKind, Outcome, WeightLimit, Xcm},
+	VersionedLocation, VersionedXcm,
+};
+
+/// Log target of the runtime watcher.
+const LOG_TARGET: &str = "dev-runtime-watcher";
+
+/// How often the wasm file and the progress of a pending upgrade are checked.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of blocks after which a step of the upgrade that did not happen is reported as failed.
+const MAX_BLOCKS_PER_STEP: u32 = 10;
+
+/// Raw downward messages to inject in the next block produced by the dev node.
+#[derive(Clone, Default)]
+pub(crate) struct DownwardMessages(Arc<Mutex<Vec<Vec<u8>>>>);
+
+impl DownwardMessages {
+	/// Queues `message` for the next block.
+	pub(crate) fn push(&self, message: Vec<u8>) {
+		self.0.lock().expect("The lock is never poisoned; qed").push(message);
+	}
+
+	/// Takes the messages queued for the next block.
+	pub(crate) fn take(&self) -> Vec<Vec<u8>> {
+		std::mem::take(&mut *self.0.lock().expect("The lock is never poisoned; qed"))
+	}
+}
+
+/// The indices of the `frame-system` calls used to upgrade the runtime.
+struct SystemCalls {
+	pallet_index: u8,
+	authorize_upgrade: u8,
+	apply_authorized_upgrade: u8,
+}
+
+impl SystemCalls {
+	/// Looks up the indices of the calls in `metadata`.
+	fn from_metadata(metadata: &Metadata) -> Result<Self, String> {
+		let pallet = metadata
+			.pallet_by_name(DEFAULT_FRAME_SYSTEM_PALLET_NAME)
+			.ok_or("the runtime has no `System` pallet")?;
+		let call_index = |name| {
+			pallet
+				.call_variant_by_name(name)
+				.map(|variant| variant.index)
+				.ok_or_else(|| format!("the `System` pallet has no `{name}` call"))
+		};
+		Ok(Self {
+			pallet_index: pallet.index(),
+			authorize_upgrade: call_index("authorize_upgrade")?,
+			apply_authorized_upgrade: call_index("apply_authorized_upgrade")?,
+		})
+	}
+}
+
+/// A runtime upgrade in progress.
+#[derive(Debug, PartialEq)]
+enum PendingUpgrade<Hash> {
+	/// Waiting for the downward message to authorize the upgrade.
+	Authorizing {
+		code: Vec<u8>,
+		code_hash: Hash,
+		spec_version: u32,
+		apply_call: (u8, u8),
+		since: u32,
+	},
+	/// Waiting for the new runtime to be enacted.
+	Enacting { spec_version: u32, since: u32 },
+}
+
+/// The state of the best block relevant to a runtime upgrade in progress.
+struct BestBlock<Hash> {
+	number: u32,
+	/// The hash of the code whose upgrade is authorized, if any.
+	authorized_upgrade: Option<Hash>,
+	/// The spec version of the runtime, if it could be read.
+	spec_version: Option<u32>,
+}
+
+/// The next step of a runtime upgrade in progress.
+#[derive(Debug, PartialEq)]
+enum Step<Hash> {
+	/// The upgrade is still waiting for the best block to change.
+	Wait(PendingUpgrade<Hash>),
+	/// The upgrade is authorized and must be applied by submitting `extrinsic`.
+	Apply { extrinsic: Vec<u8>, spec_version: u32 },
+	/// The new runtime is enacted.
+	Enacted { spec_version: u32 },
+	/// The upgrade wasn't authorized within [`MAX_BLOCKS_PER_STEP`] blocks.
+	NotAuthorized,
+	/// The new runtime wasn't enacted within [`MAX_BLOCKS_PER_STEP`] blocks.
+	NotEnacted { since: u32 },
+}
+
+impl<Hash: PartialEq> PendingUpgrade<Hash> {
+	/// Returns the next step of the upgrade, given the state of the best block.
+	fn step(self, best: &BestBlock<Hash>) -> Step<Hash> {
+		match self {
+			Self::Authorizing { code, code_hash, spec_version, apply_call, since } => {
+				if best.authorized_upgrade.as_ref() == Some(&code_hash) {
+					let (pallet_index, call_index) = apply_call;
+					let extrinsic = UncheckedExtrinsic::<(), _, (), ()>::new_bare_legacy((
+						pallet_index,
+						call_index,
+						code,
+					))
+					.encode();
+					return Step::Apply { extrinsic, spec_version }
+				}
+				if best.number > since.saturating_add(MAX_BLOCKS_PER_STEP) {
+					return Step::NotAuthorized
+				}
+				Step::Wait(Self::Authorizing { code, code_hash, spec_version, apply_call, since })
+			},
+			Self::Enacting { spec_version, since } => {
+				if best.spec_version == Some(spec_version) {
+					return Step::Enacted { spec_version }
+				}
+				if best.number > since.saturating_add(MAX_BLOCKS_PER_STEP) {
+					return Step::NotEnacted { since }
+				}
+				Step::Wait(Self::Enacting { spec_version, since })
+			},
+		}
+	}
+}
+
+/// Returns the downward message authorizing the upgrade to the code with hash `code_hash`.
+///
+/// The message dispatches `frame_system::authorize_upgrade` with the `Superuser` origin, and fails
+/// if the call fails.
+fn authorize_upgrade_message(calls: &SystemCalls, code_hash: impl Encode) -> Vec<u8> {
+	let authorize_upgrade = (calls.pallet_index, calls.authorize_upgrade, code_hash).encode();
+	VersionedXcm::<()>::from(Xcm(vec![
+		Instruction::UnpaidExecution { weight_limit: WeightLimit::Unlimited, check_origin: None },
+		Instruction::Transact {
+			origin_kind: OriginKind::Superuser,
+			fallback_max_weight: None,
+			call: authorize_upgrade.into(),
+		},
+		Instruction::ExpectTransactStatus(MaybeErrorCode::Success),
+	]))
+	.encode()
+}
+
+/// Returns the id of the `DryRunApi` of `xcm-runtime-apis`.
+fn dry_run_api_id() -> [u8; 8] {
+	sp_core::blake2_64(b"DryRunApi")
+}
+
+/// Watches a wasm file, upgrading the runtime of the dev node to it whenever it changes.
+pub(crate) struct RuntimeWatcher<Client, Pool> {
+	path: PathBuf,
+	client: Arc<Client>,
+	transaction_pool: Arc<Pool>,
+	downward_messages: DownwardMessages,
+	executor: WasmExecutor<ParachainHostFunctions>,
+}
+
+impl<Block, Client, Pool> RuntimeWatcher<Client, Pool>
+where
+	Block: NodeBlock,
+	Client: HeaderBackend<Block> + CallApiAt<Block> + Send + Sync + 'static,
+	Pool: TransactionPool<Block = Block> + 'static,
+{
+	/// Creates a watcher of the wasm file at `path`.
+	///
+	/// The upgrades are authorized by messages pushed to `downward_messages`, which must be
+	/// injected in the blocks produced by the dev node.
+	pub(crate) fn new(
+		path: PathBuf,
+		client: Arc<Client>,
+		transaction_pool: Arc<Pool>,
+		downward_messages: DownwardMessages,
+	) -> Self {
+		let executor = WasmExecutor::<ParachainHostFunctions>::builder()
+			.with_allow_missing_host_functions(true)
+			.build();
+		Self { path, client, transaction_pool, downward_messages, executor }
+	}
+
+	/// Runs the watcher.
+	///
+	/// The runtime is only upgraded on changes of the file after the watcher started. Changes are
+	/// picked up once the file wasn't modified for one poll interval, to not read partially written
+	/// files, and one upgrade at a time.
+	pub(crate) async fn run(self) {
+		log::info!(
+			target: LOG_TARGET,
+			"👀 Watching {} for runtime upgrades.",
+			self.path.display(),
+		);
+
+		let mut applied = self.modified();
+		let mut observed = applied;
+		let mut pending = None;
+		loop {
+			futures_timer::Delay::new(POLL_INTERVAL).await;
+
+			if let Some(upgrade) = pending.take() {
+				pending = self.advance(upgrade).await;
+				continue
+			}
+
+			let modified = self.modified();
+			if modified != observed {
+				observed = modified;
+				continue
+			}
+			if modified.is_none() || modified == applied {
+				continue
+			}
+			applied = modified;
+
+			pending = self
+				.prepare()
+				.map_err(
+					|error| log::error!(target: LOG_TARGET, "❌ Runtime upgrade rejected: {error}"),
+				)
+				.ok();
+		}
+	}
+
+	/// Checks that the on-chain runtime lets the relay chain authorize upgrades, by dry-running the
+	/// message authorizing them.
+	///
+	/// The check is skipped if the runtime doesn't implement the `DryRunApi`.
+	pub(crate) fn check_runtime(&self) -> Result<(), String> {
+		let at = self.client.info().best_hash;
+		let version = self
+			.client
+			.runtime_version_at(at)
+			.map_err(|error| format!("failed to read the on-chain runtime version: {error}"))?;
+		if !version.has_api_with(&dry_run_api_id(), |_| true) {
+			log::warn!(
+				target: LOG_TARGET,
+				"The runtime doesn't implement the `DryRunApi`, skipping the check that it lets \
+				the relay chain authorize upgrades.",
+			);
+			return Ok(())
+		}
+
+		let message = authorize_upgrade_message(&self.system_calls(at)?, Block::Hash::default());
+		let origin = VersionedLocation::from(Location::parent());
+		let result = self.call_runtime_api(
+			at,
+			"DryRunApi_dry_run_xcm",
+			&[origin.encode(), message].concat(),
+		)?;
+		// Only the outcome, at the start of the effects, is decoded.
+		let outcome = <Result<Outcome, u8>>::decode(&mut &result[..])
+			.map_err(|error| format!("failed to decode the dry run result: {error}"))?
+			.map_err(|error| format!("failed to dry-run the upgrade authorization: {error}"))?;
+
+		match outcome {
+			Outcome::Complete { .. } => Ok(()),
+			Outcome::Incomplete { error, .. } | Outcome::Error(error) => Err(format!(
+				"the runtime doesn't let the relay chain authorize upgrades ({:?} at instruction \
+				{}). It must dispatch `Transact` instructions from the relay chain with the \
+				`Superuser` origin, e.g. using `ParentAsSuperuser`, and let that origin call \
+				`frame_system::authorize_upgrade`.",
+				error.error, error.index,
+			)),
+		}
+	}
+
+	fn modified(&self) -> Option<SystemTime> {
+		std::fs::metadata(&self.path).and_then(|metadata| metadata.modified()).ok()
+	}
+
+	/// Checks the runtime in the wasm file and queues the message authorizing its upgrade.
+	fn prepare(&self) -> Result<PendingUpgrade<Block::Hash>, String> {
+		let code = std::fs::read(&self.path)
+			.map_err(|error| format!("failed to read {}: {error}", self.path.display()))?;
+		let info = self.client.info();
+		let code_hash = HashingFor::<Block>::hash(&code);
+		let runtime_code = RuntimeCode {
+			code_fetcher: &WrappedRuntimeCode(code.as_slice().into()),
+			heap_pages: None,
+			hash: code_hash.encode(),
+		};
+
+		let current = self
+			.client
+			.runtime_version_at(info.best_hash)
+			.map_err(|error| format!("failed to read the on-chain runtime version: {error}"))?;
+		let version = self
+			.executor
+			.runtime_version(&mut BasicExternalities::new_empty(), &runtime_code)
+			.map_err(|error| format!("failed to read the runtime version: {error}"))?;
+		if version.spec_name != current.spec_name {
+			return Err(format!(
+				"the spec name `{}` differs from the on-chain `{}`",
+				version.spec_name, current.spec_name,
+			))
+		}
+		if version.spec_version <= current.spec_version {
+			return Err(format!(
+				"the spec version {} must be greater than the on-chain {}",
+				version.spec_version, current.spec_version,
+			))
+		}
+
+		self.try_migrations(info.best_hash, &version, &runtime_code)?;
+
+		let calls = self.system_calls(info.best_hash)?;
+		self.downward_messages.push(authorize_upgrade_message(&calls, code_hash));
+
+		log::info!(
+			target: LOG_TARGET,
+			"⬆️  Authorizing the upgrade to {}-{} ({code_hash:?}).",
+			version.spec_name,
+			version.spec_version,
+		);
+		Ok(PendingUpgrade::Authorizing {
+			code,
+			code_hash,
+			spec_version: version.spec_version,
+			apply_call: (calls.pallet_index, calls.apply_authorized_upgrade),
+			since: info.best_number.unique_saturated_into(),
+		})
+	}
+
+	/// Tries the migrations of the new runtime against the state of block `at`.
+	#[cfg(feature = "try-runtime")]
+	fn try_migrations(
+		&self,
+		at: Block::Hash,
+		version: &sp_version::RuntimeVersion,
+		runtime_code: &RuntimeCode,
+	) -> Result<(), String> {
+		use frame_try_runtime::{TryRuntime, UpgradeCheckSelect};
+		use sp_api::RuntimeApiInfo;
+		use sp_weights::Weight;
+
+		if !version.has_api_with(&<dyn TryRuntime<Block>>::ID, |_| true) {
+			log::warn!(
+				target: LOG_TARGET,
+				"The new runtime wasn't built with the `try-runtime` feature, skipping the migration checks.",
+			);
+			return Ok(())
+		}
+
+		let state = self
+			.client
+			.state_at(at)
+			.map_err(|error| format!("failed to get the state of the best block: {error}"))?;
+		let result = sp_state_machine::StateMachine::new(
+			&state,
+			&mut Default::default(),
+			&self.executor,
+			"TryRuntime_on_runtime_upgrade",
+			&UpgradeCheckSelect::All.encode(),
+			&mut Default::default(),
+			runtime_code,
+			sp_core::traits::CallContext::Offchain,
+		)
+		.execute()
+		.map_err(|error| format!("the migration checks failed: {error}"))?;
+		let (weight, max_weight) = <(Weight, Weight)>::decode(&mut &result[..])
+			.map_err(|error| format!("failed to decode the migration weight: {error}"))?;
+
+		if weight.any_gt(max_weight) {
+			log::warn!(
+				target: LOG_TARGET,
+				"⚠️  The migrations consume {weight}, more than the maximum block weight {max_weight}.",
+			);
+		}
+		log::info!(target: LOG_TARGET, "✅ The migration checks passed, consuming {weight}.");
+		Ok(())
+	}
+
+	/// Tries the migrations of the new runtime against the state of block `at`.
+	#[cfg(not(feature = "try-runtime"))]
+	fn try_migrations(
+		&self,
+		_at: Block::Hash,
+		_version: &sp_version::RuntimeVersion,
+		_runtime_code: &RuntimeCode,
+	) -> Result<(), String> {
+		log::warn!(
+			target: LOG_TARGET,
+			"The node wasn't built with the `try-runtime` feature, skipping the migration checks.",
+		);
+		Ok(())
+	}
+
+	/// Calls the runtime API `method` of the on-chain runtime at block `at`.
+	fn call_runtime_api(
+		&self,
+		at: Block::Hash,
+		method: &str,
+		data: &[u8],
+	) -> Result<Vec<u8>, String> {
+		let state = self
+			.client
+			.state_at(at)
+			.map_err(|error| format!("failed to get the state of the best block: {error}"))?;
+		let code = self.on_chain_code(at)?;
+		let runtime_code = RuntimeCode {
+			code_fetcher: &WrappedRuntimeCode(code.as_slice().into()),
+			heap_pages: None,
+			hash: HashingFor::<Block>::hash(&code).encode(),
+		};
+		sp_state_machine::StateMachine::new(
+			&state,
+			&mut Default::default(),
+			&self.executor,
+			method,
+			data,
+			&mut Default::default(),
+			&runtime_code,
+			sp_core::traits::CallContext::Offchain,
+		)
+		.execute()
+		.map_err(|error| format!("failed to call `{method}`: {error}"))
+	}
+
+	/// Reads the code of the on-chain runtime at block `at`.
+	fn on_chain_code(&self, at: Block::Hash) -> Result<Vec<u8>, String> {
+		let state = self
+			.client
+			.state_at(at)
+			.map_err(|error| format!("failed to get the state of the best block: {error}"))?;
+		state
+			.storage(sp_storage::well_known_keys::CODE)
+			.map_err(|error| format!("failed to read the on-chain runtime: {error}"))?
+			.ok_or_else(|| "the on-chain runtime is missing".into())
+	}
+
+	/// Looks up the indices of the `frame-system` upgrade calls in the metadata of the runtime at
+	/// block `at`.
+	fn system_calls(&self, at: Block::Hash) -> Result<SystemCalls, String> {
+		let code = self.on_chain_code(at)?;
+		let opaque_metadata =
+			fetch_latest_metadata_from_code_blob(&self.executor, code.as_slice().into())
+				.map_err(|error| format!("failed to fetch the on-chain metadata: {error}"))?;
+		let metadata = Metadata::decode(&mut (*opaque_metadata).as_slice())
+			.map_err(|error| format!("failed to decode the on-chain metadata: {error}"))?;
+
+		SystemCalls::from_metadata(&metadata)
+	}
+
+	/// Moves `upgrade` forward, returning it if it is still in progress.
+	async fn advance(
+		&self,
+		upgrade: PendingUpgrade<Block::Hash>,
+	) -> Option<PendingUpgrade<Block::Hash>> {
+		let info = self.client.info();
+		let best = BestBlock {
+			number: info.best_number.unique_saturated_into(),
+			authorized_upgrade: self.authorized_upgrade(info.best_hash),
+			spec_version: self
+				.client
+				.runtime_version_at(info.best_hash)
+				.ok()
+				.map(|version| version.spec_version),
+		};
+
+		match upgrade.step(&best) {
+			Step::Wait(upgrade) => Some(upgrade),
+			Step::Apply { extrinsic, spec_version } => {
+				let extrinsic = OpaqueExtrinsic::try_from_encoded_extrinsic(&extrinsic)
+					.expect("A bare extrinsic is a valid opaque extrinsic; qed");
+				match self
+					.transaction_pool
+					.submit_one(info.best_hash, TransactionSource::Local, extrinsic)
+					.await
+				{
+					Ok(_) => Some(PendingUpgrade::Enacting { spec_version, since: best.number }),
+					Err(error) => {
+						log::error!(
+							target: LOG_TARGET,
+							"❌ Failed to submit the authorized runtime upgrade: {error}",
+						);
+						None
+					},
+				}
+			},
+			Step::Enacted { spec_version } => {
+				log::info!(
+					target: LOG_TARGET,
+					"✅ Runtime upgraded to spec version {spec_version} at block #{}.",
+					best.number,
+				);
+				None
+			},
+			Step::NotAuthorized => {
+				log::error!(
+					target: LOG_TARGET,
+					"❌ The runtime upgrade wasn't authorized after {MAX_BLOCKS_PER_STEP} blocks. \
+					The runtime must dispatch `Transact` instructions from the relay chain with \
+					the `Superuser` origin, e.g. using `ParentAsSuperuser`.",
+				);
+				None
+			},
+			Step::NotEnacted { since } => {
+				log::error!(
+					target: LOG_TARGET,
+					"❌ The runtime upgrade wasn't enacted after {MAX_BLOCKS_PER_STEP} blocks. \
+					Check the events of the blocks since #{since}.",
+				);
+				None
+			},
+		}
+	}
+
+	/// Returns the hash of the code whose upgrade is authorized at block `at`, if any.
+	fn authorized_upgrade(&self, at: Block::Hash) -> Option<Block::Hash> {
+		let key = [
+			sp_core::twox_128(DEFAULT_FRAME_SYSTEM_PALLET_NAME.as_bytes()),
+			sp_core::twox_128(b"AuthorizedUpgrade"),
+		]
+		.concat();
+		let authorization = self.client.state_at(at).ok()?.storage(&key).ok()??;
+
+		<(Block::Hash, bool)>::decode(&mut &authorization[..])
+			.ok()
+			.map(|(code_hash, _)| code_hash)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_core::H256;
+
+	const CODE_HASH: H256 = H256::repeat_byte(1);
+
+	fn authorizing(since: u32) -> PendingUpgrade<H256> {
+		PendingUpgrade::Authorizing {
+			code: vec![1, 2, 3],
+			code_hash: CODE_HASH,
+			spec_version: 2,
+			apply_call: (0, 10),
+			since,
+		}
+	}
+
+	fn best(
+		number: u32,
+		authorized_upgrade: Option<H256>,
+		spec_version: Option<u32>,
+	) -> BestBlock<H256> {
+		BestBlock { number, authorized_upgrade, spec_version }
+	}
+
+	#[test]
+	fn downward_messages_are_taken_once() {
+		let messages = DownwardMessages::default();
+		messages.clone().push(vec![1]);
+		messages.push(vec![2]);
+
+		assert_eq!(messages.take(), vec![vec![1], vec![2]]);
+		assert!(messages.take().is_empty());
+	}
+
+	#[test]
+	fn authorize_upgrade_message_transacts_as_superuser() {
+		let calls =
+			SystemCalls { pallet_index: 0, authorize_upgrade: 9, apply_authorized_upgrade: 10 };
+		let message = authorize_upgrade_message(&calls, CODE_HASH);
+
+		let xcm = match VersionedXcm::<()>::decode(&mut &message[..]).unwrap() {
+			VersionedXcm::V5(xcm) => xcm,
+			other => panic!("Unexpected XCM version: {other:?}"),
+		};
+		let [
+			Instruction::UnpaidExecution { .. },
+			Instruction::Transact { origin_kind, call, .. },
+			Instruction::ExpectTransactStatus(MaybeErrorCode::Success),
+		] = &xcm.0[..]
+		else {
+			panic!("Unexpected instructions: {xcm:?}")
+		};
+		assert_eq!(*origin_kind, OriginKind::Superuser);
+		assert_eq!(call.clone().into_encoded(), (0u8, 9u8, CODE_HASH).encode());
+	}
+
+	#[test]
+	fn authorized_upgrade_is_applied() {
+		let step = authorizing(5).step(&best(6, Some(CODE_HASH), Some(1)));
+
+		let Step::Apply { extrinsic, spec_version } = step else {
+			panic!("Unexpected step: {step:?}")
+		};
+		assert_eq!(spec_version, 2);
+		// A bare extrinsic of version 4 calling `apply_authorized_upgrade` with the code.
+		let (_, version, call) =
+			<(codec::Compact<u32>, u8, (u8, u8, Vec<u8>))>::decode(&mut &extrinsic[..]).unwrap();
+		assert_eq!(version, 4);
+		assert_eq!(call, (0, 10, vec![1, 2, 3]));
+	}
+
+	#[test]
+	fn upgrade_waits_for_its_authorization() {
+		assert_eq!(authorizing(5).step(&best(6, None, Some(1))), Step::Wait(authorizing(5)));
+		assert_eq!(
+			authorizing(5).step(&best(6, Some(H256::repeat_byte(2)), Some(1))),
+			Step::Wait(authorizing(5)),
+		);
+		assert_eq!(
+			authorizing(5).step(&best(5 + MAX_BLOCKS_PER_STEP, None, Some(1))),
+			Step::Wait(authorizing(5)),
+		);
+		assert_eq!(
+			authorizing(5).step(&best(6 + MAX_BLOCKS_PER_STEP, None, Some(1))),
+			Step::NotAuthorized,
+		);
+	}
+
+	#[test]
+	fn upgrade_waits_for_the_new_runtime() {
+		let enacting = || PendingUpgrade::<H256>::Enacting { spec_version: 2, since: 5 };
+
+		assert_eq!(enacting().step(&best(6, None, Some(1))), Step::Wait(enacting()));
+		assert_eq!(enacting().step(&best(6, None, None)), Step::Wait(enacting()));
+		assert_eq!(enacting().step(&best(7, None, Some(2))), Step::Enacted { spec_version: 2 });
+		assert_eq!(
+			enacting().step(&best(6 + MAX_BLOCKS_PER_STEP, None, Some(1))),
+			Step::NotEnacted { since: 5 },
+		);
+	}
+
+	#[test]
+	fn system_calls_are_found_in_the_runtime_metadata() {
+		let opaque_metadata = fetch_latest_metadata_from_code_blob(
+			&WasmExecutor::<ParachainHostFunctions>::builder()
+				.with_allow_missing_host_functions(true)
+				.build(),
+			sp_runtime::Cow::Borrowed(cumulus_test_runtime::WASM_BINARY.unwrap()),
+		)
+		.unwrap();
+		let metadata = Metadata::decode(&mut (*opaque_metadata).as_slice()).unwrap();
+
+		let calls = SystemCalls::from_metadata(&metadata).unwrap();
+		assert_eq!(calls.pallet_index, 0);
+		assert_ne!(calls.authorize_upgrade, calls.apply_authorized_upgrade);
+	}
+}
@@ -25,12 +25,14 @@ use crate::{
 			ParachainBackend, ParachainBlockImport, ParachainClient, ParachainHostFunctions,
 			ParachainService,
 		},
+		xcmp_queue_metrics::XcmpQueueMetrics,
 		ConstructNodeRuntimeApi, NodeBlock, NodeExtraArgs,
 	},
 };
 use codec::Encode;
 use cumulus_client_bootnodes::{start_bootnode_tasks, StartBootnodeTasksParams};
 use cumulus_client_cli::CollatorOptions;
+use cumulus_client_collator::orphaned_blocks::OrphanedBlocks;
 use cumulus_client_service::{
 	build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
 	BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, ParachainTracingExecuteBlock,
@@ -59,7 +61,7 @@ use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_keystore::KeystorePtr;
 use sp_runtime::traits::AccountIdConversion;
-use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use std::{future::Future, path::PathBuf, pin::Pin, sync::Arc, time::Duration};
 
 // Override default idle connection timeout of 10 seconds to give IPFS clients more
 // time to query data over Bitswap. This is needed when manually adding our node
@@ -80,6 +82,7 @@ pub(crate) trait BuildImportQueue<
 		config: &Configuration,
 		telemetry_handle: Option<TelemetryHandle>,
 		task_manager: &TaskManager,
+		transaction_pool: Arc<TransactionPoolHandle<Block, ParachainClient<Block, RuntimeApi>>>,
 	) -> sc_service::error::Result<DefaultImportQueue<Block>>;
 }
 
@@ -105,6 +108,7 @@ where
 		backend: Arc<ParachainBackend<Block>>,
 		node_extra_args: NodeExtraArgs,
 		block_import_extra_return_value: BIAuxiliaryData,
+		orphaned_blocks: Option<OrphanedBlocks<Block>>,
 	) -> Result<(), sc_service::Error>;
 }
 
@@ -280,6 +284,7 @@ pub(crate) trait BaseNodeSpec {
 			config,
 			telemetry.as_ref().map(|telemetry| telemetry.handle()),
 			&task_manager,
+			transaction_pool.clone(),
 		)?;
 
 		Ok(PartialComponents {
@@ -297,6 +302,7 @@ pub(crate) trait BaseNodeSpec {
 
 pub(crate) trait NodeSpec: BaseNodeSpec {
 	type BuildRpcExtensions: BuildRpcExtensions<
+		Self::Block,
 		ParachainClient<Self::Block, Self::RuntimeApi>,
 		ParachainBackend<Self::Block>,
 		TransactionPoolHandle<Self::Block, ParachainClient<Self::Block, Self::RuntimeApi>>,
@@ -315,6 +321,7 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 	fn start_dev_node(
 		_config: Configuration,
 		_mode: DevSealMode,
+		_watch_runtime: Option<PathBuf>,
 	) -> sc_service::error::Result<TaskManager> {
 		Err(sc_service::Error::Other("Dev not supported for this node type".into()))
 	}
@@ -450,11 +457,50 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 				);
 			}
 
+			if let Some(registry) = prometheus_registry.as_ref() {
+				task_manager.spawn_handle().spawn(
+					"xcmp-queue-metrics",
+					None,
+					XcmpQueueMetrics::register(registry)?.run(client.clone()),
+				);
+			}
+
+			// Track the authored blocks orphaned by relay chain reorganizations.
+			let orphaned_blocks = validator
+				.then(|| OrphanedBlocks::new(prometheus_registry.as_ref()))
+				.transpose()?;
+			if let Some(orphaned_blocks) = &orphaned_blocks {
+				task_manager.spawn_handle().spawn(
+					"orphaned-blocks-tracker",
+					None,
+					orphaned_blocks.clone().run(
+						relay_chain_interface.clone(),
+						client.clone(),
+						transaction_pool.clone(),
+					),
+				);
+			}
+
+			if let Some(config) = node_extra_args.on_demand.clone().filter(|_| validator) {
+				task_manager.spawn_handle().spawn(
+					"on-demand-orders",
+					None,
+					cumulus_client_on_demand::run(cumulus_client_on_demand::Params {
+						para_id,
+						relay_client: relay_chain_interface.clone(),
+						transaction_pool: transaction_pool.clone(),
+						keystore: params.keystore_container.keystore(),
+						config,
+					}),
+				);
+			}
+
 			let rpc_builder = {
 				let client = client.clone();
 				let transaction_pool = transaction_pool.clone();
 				let backend_for_rpc = backend.clone();
 				let statement_store = statement_store.clone();
+				let orphaned_blocks = orphaned_blocks.clone();
 
 				Box::new(move |_| {
 					Self::BuildRpcExtensions::build_rpc_extensions(
@@ -462,6 +508,7 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 						backend_for_rpc.clone(),
 						transaction_pool.clone(),
 						statement_store.clone(),
+						orphaned_blocks.clone(),
 					)
 				})
 			};
@@ -576,6 +623,7 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 					backend.clone(),
 					node_extra_args,
 					block_import_auxiliary_data,
+					orphaned_blocks,
 				)?;
 			}
 
@@ -594,10 +642,13 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 
 pub(crate) trait DynNodeSpec: NodeCommandRunner {
 	/// Start node with manual or instant seal consensus.
+	///
+	/// If `watch_runtime` is set, the runtime is upgraded each time this wasm file changes.
 	fn start_dev_node(
 		self: Box<Self>,
 		config: Configuration,
 		mode: DevSealMode,
+		watch_runtime: Option<PathBuf>,
 	) -> sc_service::error::Result<TaskManager>;
 
 	/// Start the node.
@@ -619,8 +670,9 @@ where
 		self: Box<Self>,
 		config: Configuration,
 		mode: DevSealMode,
+		watch_runtime: Option<PathBuf>,
 	) -> sc_service::error::Result<TaskManager> {
-		<Self as NodeSpec>::start_dev_node(config, mode)
+		<Self as NodeSpec>::start_dev_node(config, mode, watch_runtime)
 	}
 
 	fn start_node(
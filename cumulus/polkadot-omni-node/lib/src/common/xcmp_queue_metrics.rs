@@ -0,0 +1,125 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Metrics of the inbound XCMP channels.
+//!
+//! The backlogs of the scheduled and suspended inbound channels are read from the
+//! `XcmpQueueApi` at every new best block and exported per sibling. Runtimes without the API are
+//! skipped.
+
+use cumulus_pallet_xcmp_queue::{runtime_api::XcmpQueueApi, InboundChannelBacklog};
+use cumulus_primitives_core::ParaId;
+use futures::StreamExt;
+use prometheus_endpoint::{register, GaugeVec, Opts, PrometheusError, Registry, U64};
+use sc_client_api::BlockchainEvents;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_runtime::traits::Block as BlockT;
+use std::sync::Arc;
+
+const LOG_TARGET: &str = "xcmp-queue-metrics";
+
+/// The gauges of the inbound XCMP channels, labeled by sibling.
+#[derive(Clone)]
+pub(crate) struct XcmpQueueMetrics {
+	messages: GaugeVec<U64>,
+	size: GaugeVec<U64>,
+	pages: GaugeVec<U64>,
+	ready_pages: GaugeVec<U64>,
+	suspended: GaugeVec<U64>,
+}
+
+impl XcmpQueueMetrics {
+	/// Registers the metrics in `registry`.
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		let gauge = |name: &str, help: &str| {
+			register(GaugeVec::new(Opts::new(name, help), &["sibling"])?, registry)
+		};
+		Ok(Self {
+			messages: gauge(
+				"cumulus_xcmp_inbound_messages",
+				"Number of inbound XCMP messages waiting to be processed",
+			)?,
+			size: gauge(
+				"cumulus_xcmp_inbound_size",
+				"Total size of the inbound XCMP messages waiting to be processed",
+			)?,
+			pages: gauge(
+				"cumulus_xcmp_inbound_pages",
+				"Number of pages of the inbound XCMP queue",
+			)?,
+			ready_pages: gauge(
+				"cumulus_xcmp_inbound_ready_pages",
+				"Number of pages of the inbound XCMP queue with messages ready to be processed",
+			)?,
+			suspended: gauge(
+				"cumulus_xcmp_inbound_suspended",
+				"Whether the sibling was told to suspend sending on the inbound XCMP channel",
+			)?,
+		})
+	}
+
+	fn report(&self, backlogs: Vec<(ParaId, InboundChannelBacklog)>) {
+		let gauges = [&self.messages, &self.size, &self.pages, &self.ready_pages, &self.suspended];
+		// Channels neither scheduled nor suspended anymore are not reported.
+		gauges.iter().for_each(|gauge| gauge.reset());
+
+		for (sibling, backlog) in backlogs {
+			let sibling = u32::from(sibling).to_string();
+			let values = [
+				backlog.messages,
+				backlog.size,
+				backlog.pages.into(),
+				backlog.ready_pages.into(),
+				backlog.suspended.into(),
+			];
+			for (gauge, value) in gauges.iter().zip(values) {
+				gauge.with_label_values(&[&sibling]).set(value);
+			}
+		}
+	}
+
+	/// Updates the metrics at every new best block of `client`.
+	pub(crate) async fn run<Block, Client>(self, client: Arc<Client>)
+	where
+		Block: BlockT,
+		Client: BlockchainEvents<Block> + ProvideRuntimeApi<Block>,
+		Client::Api: XcmpQueueApi<Block>,
+	{
+		let mut imported = client.import_notification_stream();
+		while let Some(notification) = imported.next().await {
+			if !notification.is_new_best {
+				continue
+			}
+
+			let api = client.runtime_api();
+			let at = notification.hash;
+			match api.has_api::<dyn XcmpQueueApi<Block>>(at) {
+				Ok(true) => {},
+				Ok(false) => continue,
+				Err(err) => {
+					log::debug!(target: LOG_TARGET, "Failed to check the XcmpQueueApi at {at:?}: {err}");
+					continue
+				},
+			}
+			match api.inbound_channel_backlogs(at) {
+				Ok(backlogs) => self.report(backlogs),
+				Err(err) => {
+					log::debug!(target: LOG_TARGET, "Failed to read the XCMP backlogs at {at:?}: {err}")
+				},
+			}
+		}
+	}
+}
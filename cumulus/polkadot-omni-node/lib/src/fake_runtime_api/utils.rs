@@ -84,6 +84,14 @@ macro_rules! impl_node_runtime_apis {
 				}
 			}
 
+			impl cumulus_primitives_aura::AuraEquivocationApi<$block, $aura_id> for $runtime {
+				fn submit_report_equivocation_unsigned_extrinsic(
+					_: cumulus_primitives_aura::EquivocationProof<<$block as BlockT>::Header, $aura_id>,
+				) -> Option<()> {
+					unimplemented!()
+				}
+			}
+
 			impl sp_block_builder::BlockBuilder<$block> for $runtime {
 				fn apply_extrinsic(_: <$block as BlockT>::Extrinsic) -> ApplyExtrinsicResult {
 					unimplemented!()
@@ -254,6 +262,19 @@ macro_rules! impl_node_runtime_apis {
 					unimplemented!()
 				}
 			}
+
+			impl cumulus_pallet_xcmp_queue::runtime_api::XcmpQueueApi<$block> for $runtime {
+				fn inbound_channel_backlog(
+					_: ParaId,
+				) -> cumulus_pallet_xcmp_queue::InboundChannelBacklog {
+					unimplemented!()
+				}
+
+				fn inbound_channel_backlogs(
+				) -> Vec<(ParaId, cumulus_pallet_xcmp_queue::InboundChannelBacklog)> {
+					unimplemented!()
+				}
+			}
 		}
 	};
 }
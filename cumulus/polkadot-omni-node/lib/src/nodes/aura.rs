@@ -19,6 +19,7 @@ use crate::{
 	common::{
 		aura::{AuraIdT, AuraRuntimeApi},
 		rpc::{BuildParachainRpcExtensions, BuildRpcExtensions},
+		runtime_watcher::{DownwardMessages, RuntimeWatcher},
 		spec::{
 			BaseNodeSpec, BuildImportQueue, ClientBlockImport, DynNodeSpec, InitBlockImport,
 			NodeSpec, StartConsensus,
@@ -31,22 +32,23 @@ use crate::{
 	},
 };
 use codec::Encode;
-use cumulus_client_collator::service::{
-	CollatorService, ServiceInterface as CollatorServiceInterface,
+use cumulus_client_collator::{
+	orphaned_blocks::OrphanedBlocks,
+	service::{CollatorService, ServiceInterface as CollatorServiceInterface},
 };
 #[docify::export(slot_based_colator_import)]
 use cumulus_client_consensus_aura::collators::slot_based::{
-	self as slot_based, Params as SlotBasedParams,
+	self as slot_based, AuthoringLease, CoreScarcity, FileLease, Params as SlotBasedParams,
 };
 use cumulus_client_consensus_aura::{
 	collators::{
 		lookahead::{self as aura, Params as AuraParams},
 		slot_based::{SlotBasedBlockImport, SlotBasedBlockImportHandle},
 	},
-	equivocation_import_queue::Verifier as EquivocationVerifier,
+	equivocation_import_queue::{RuntimeEquivocationReporter, Verifier as EquivocationVerifier},
 };
 use cumulus_client_consensus_relay_chain::Verifier as RelayChainVerifier;
-use cumulus_client_parachain_inherent::MockValidationDataInherentDataProvider;
+use cumulus_client_parachain_inherent::{MockValidationDataInherentDataProvider, MockXcmConfig};
 use cumulus_client_service::CollatorSybilResistance;
 use cumulus_primitives_core::{
 	relay_chain::ValidationCode, CollectCollationInfo, GetParachainInfo, ParaId,
@@ -78,7 +80,7 @@ use sp_runtime::{
 	traits::{Block as BlockT, Header as HeaderT, UniqueSaturatedInto},
 };
 use sp_transaction_storage_proof::runtime_api::TransactionStorageApi;
-use std::{marker::PhantomData, ops::Sub, sync::Arc, time::Duration};
+use std::{marker::PhantomData, ops::Sub, path::PathBuf, sync::Arc, time::Duration};
 
 struct Verifier<Block, Client, AuraId> {
 	client: Arc<Client>,
@@ -128,6 +130,7 @@ where
 		config: &Configuration,
 		telemetry_handle: Option<TelemetryHandle>,
 		task_manager: &TaskManager,
+		transaction_pool: Arc<TransactionPoolHandle<Block, ParachainClient<Block, RuntimeApi>>>,
 	) -> sc_service::error::Result<DefaultImportQueue<Block>> {
 		let inherent_data_providers =
 			move |_, _| async move { Ok(sp_timestamp::InherentDataProvider::from_system_time()) };
@@ -142,7 +145,11 @@ where
 				client.clone(),
 				inherent_data_providers,
 				telemetry_handle,
-			);
+			)
+			.with_equivocation_reporter(RuntimeEquivocationReporter::new(
+				client.clone(),
+				OffchainTransactionPoolFactory::new(transaction_pool),
+			));
 
 		let verifier = Verifier {
 			client,
@@ -216,6 +223,7 @@ where
 	fn start_dev_node(
 		mut config: Configuration,
 		mode: DevSealMode,
+		watch_runtime: Option<PathBuf>,
 	) -> sc_service::error::Result<TaskManager> {
 		let PartialComponents {
 			client,
@@ -290,8 +298,28 @@ where
 
 		let para_id =
 			Self::parachain_id(&client, &config).ok_or("Failed to retrieve the parachain id")?;
-		let create_inherent_data_providers =
-			Self::create_dev_node_inherent_data_providers(client.clone(), para_id, slot_duration);
+		let downward_messages = watch_runtime
+			.map(|path| {
+				let downward_messages = DownwardMessages::default();
+				let watcher = RuntimeWatcher::new(
+					path,
+					client.clone(),
+					transaction_pool.clone(),
+					downward_messages.clone(),
+				);
+				watcher.check_runtime().map_err(|error| {
+					format!("The runtime can't be upgraded with `--dev-watch-runtime`: {error}")
+				})?;
+				task_manager.spawn_handle().spawn("dev-runtime-watcher", None, watcher.run());
+				Ok::<_, String>(downward_messages)
+			})
+			.transpose()?;
+		let create_inherent_data_providers = Self::create_dev_node_inherent_data_providers(
+			client.clone(),
+			para_id,
+			slot_duration,
+			downward_messages,
+		);
 
 		match mode {
 			DevSealMode::InstantSeal => {
@@ -363,6 +391,7 @@ where
 					backend_for_rpc.clone(),
 					transaction_pool.clone(),
 					None,
+					None,
 				)?;
 				Ok(module)
 			})
@@ -399,11 +428,13 @@ where
 	/// Creates the inherent data providers for manual and instant seal consensus.
 	///
 	/// This function sets up the timestamp and parachain validation data providers
-	/// required for dev seal block production in a parachain environment.
+	/// required for dev seal block production in a parachain environment. If the runtime is
+	/// watched, the messages queued in `downward_messages` are injected in the next block.
 	fn create_dev_node_inherent_data_providers(
 		client: Arc<ParachainClient<Block, RuntimeApi>>,
 		para_id: ParaId,
 		slot_duration: sp_consensus_aura::SlotDuration,
+		downward_messages: Option<DownwardMessages>,
 	) -> impl Fn(
 		Hash,
 		(),
@@ -456,6 +487,14 @@ where
 			let relay_offset = (target_relay_slot as u32)
 				.saturating_sub(relay_blocks_per_para_block * current_block_number);
 
+			// Downward messages are only mocked when the runtime is watched, the queue chain then
+			// continues from the messages of previous blocks.
+			let (xcm_config, raw_downward_messages) = match &downward_messages {
+				Some(messages) =>
+					(MockXcmConfig::new(&*client, block, Default::default()), messages.take()),
+				None => Default::default(),
+			};
+
 			let mocked_parachain = MockValidationDataInherentDataProvider::<()> {
 				current_para_block: current_block_number,
 				para_id,
@@ -468,6 +507,8 @@ where
 					log::info!("Detected pending validation code, sending go-ahead signal.");
 					UpgradeGoAhead::GoAhead
 				}),
+				xcm_config,
+				raw_downward_messages,
 				..Default::default()
 			};
 
@@ -602,6 +643,7 @@ where
 		backend: Arc<ParachainBackend<Block>>,
 		node_extra_args: NodeExtraArgs,
 		block_import_handle: SlotBasedBlockImportHandle<Block>,
+		orphaned_blocks: Option<OrphanedBlocks<Block>>,
 	) -> Result<(), Error> {
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
@@ -611,12 +653,30 @@ where
 			telemetry.clone(),
 		);
 
-		let collator_service = CollatorService::new(
+		let mut collator_service = CollatorService::new(
 			client.clone(),
 			Arc::new(task_manager.spawn_handle()),
 			announce_block,
 			client.clone(),
 		);
+		if let Some(orphaned_blocks) = orphaned_blocks {
+			collator_service = collator_service.with_orphaned_blocks(orphaned_blocks);
+		}
+
+		let authoring_lease = node_extra_args.authoring_lease_file.map(|path| {
+			Arc::new(FileLease::new(
+				path,
+				collator_peer_id.to_string(),
+				node_extra_args.authoring_lease_duration,
+			)) as Arc<dyn AuthoringLease>
+		});
+
+		let core_scarcity = node_extra_args
+			.core_scarcity
+			.map(|config| CoreScarcity::new(config, prometheus_registry))
+			.transpose()?;
+		let pov_metrics =
+			prometheus_registry.map(cumulus_client_pov_analyzer::Metrics::register).transpose()?;
 
 		let client_for_aura = client.clone();
 		let client_clone = client.clone();
@@ -662,6 +722,10 @@ where
 			spawner: task_manager.spawn_essential_handle(),
 			export_pov: node_extra_args.export_pov,
 			max_pov_percentage: node_extra_args.max_pov_percentage,
+			authoring_lease,
+			core_scarcity,
+			pov_metrics,
+			time_source: None,
 		};
 
 		// We have a separate function only to be able to use `docify::export` on this piece of
@@ -748,7 +812,21 @@ where
 		backend: Arc<ParachainBackend<Block>>,
 		node_extra_args: NodeExtraArgs,
 		_: (),
+		orphaned_blocks: Option<OrphanedBlocks<Block>>,
 	) -> Result<(), Error> {
+		if node_extra_args.authoring_lease_file.is_some() {
+			return Err(Error::Other(
+				"The authoring lease is only supported by the slot-based collator".into(),
+			))
+		}
+
+		if node_extra_args.core_scarcity.is_some() {
+			return Err(Error::Other(
+				"Adapting blocks to scarce cores is only supported by the slot-based collator"
+					.into(),
+			))
+		}
+
 		let proposer = sc_basic_authorship::ProposerFactory::new(
 			task_manager.spawn_handle(),
 			client.clone(),
@@ -756,12 +834,15 @@ where
 			prometheus_registry,
 			telemetry.clone(),
 		);
-		let collator_service = CollatorService::new(
+		let mut collator_service = CollatorService::new(
 			client.clone(),
 			Arc::new(task_manager.spawn_handle()),
 			announce_block,
 			client.clone(),
 		);
+		if let Some(orphaned_blocks) = orphaned_blocks {
+			collator_service = collator_service.with_orphaned_blocks(orphaned_blocks);
+		}
 
 		let client_clone = client.clone();
 		let params = aura::ParamsWithExport {
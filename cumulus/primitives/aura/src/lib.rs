@@ -17,12 +17,22 @@
 //! Core primitives for Aura in Cumulus.
 //!
 //! In particular, this exposes the [`AuraUnincludedSegmentApi`] which is used to regulate
-//! the behavior of Aura within a parachain context.
+//! the behavior of Aura within a parachain context, and the [`AuraEquivocationApi`] which is used
+//! to report collators authoring conflicting blocks.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+use codec::Codec;
+
 pub use sp_consensus_aura::Slot;
 
+/// A proof of an Aura authority authoring two conflicting blocks in the same slot.
+///
+/// For parachains, the blocks conflict when they are built on the same parent and the same relay
+/// parent, as authors may legitimately author several consecutive blocks per slot.
+pub type EquivocationProof<Header, AuthorityId> =
+	sp_consensus_slots::EquivocationProof<Header, AuthorityId>;
+
 sp_api::decl_runtime_apis! {
 	/// This runtime API is used to inform potential block authors whether they will
 	/// have the right to author at a slot, assuming they have claimed the slot.
@@ -51,4 +61,18 @@ sp_api::decl_runtime_apis! {
 		/// is more recent than the included block itself.
 		fn can_build_upon(included_hash: Block::Hash, slot: Slot) -> bool;
 	}
+
+	/// This runtime API is used to report Aura equivocations, i.e. a collator authoring
+	/// conflicting blocks in the same slot, to the runtime.
+	pub trait AuraEquivocationApi<AuthorityId: Codec> {
+		/// Submits an unsigned extrinsic to report an equivocation. The caller must provide the
+		/// equivocation proof. The extrinsic will be unsigned and should only be accepted for
+		/// local authorship (not to be broadcast to the network). This method returns `None` when
+		/// creation of the extrinsic fails, e.g. if equivocation reporting is disabled for the
+		/// given runtime (i.e. this method is hardcoded to return `None`). Only useful in an
+		/// offchain context.
+		fn submit_report_equivocation_unsigned_extrinsic(
+			equivocation_proof: EquivocationProof<Block::Header, AuthorityId>,
+		) -> Option<()>;
+	}
 }
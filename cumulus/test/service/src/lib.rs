@@ -279,6 +279,10 @@ async fn build_relay_chain_interface(
 			)
 			.await
 			.map(|r| r.0),
+		cumulus_client_cli::RelayChainMode::LightClient { .. } =>
+			return Err(RelayChainError::GenericError(
+				"The light client relay chain mode is not supported by the test service".into(),
+			)),
 	};
 
 	task_manager.add_child(relay_chain_node.task_manager);
@@ -475,6 +479,10 @@ where
 				export_pov: None,
 				max_pov_percentage: None,
 				collator_peer_id,
+				authoring_lease: None,
+				core_scarcity: None,
+				pov_metrics: None,
+				time_source: None,
 			};
 
 			slot_based::run::<Block, AuthorityPair, _, _, _, _, _, _, _, _, _>(params);
@@ -150,6 +150,13 @@ pub struct RunCmd {
 	///  **Dangerous!** Do not touch unless explicitly advised to.
 	#[arg(long)]
 	pub prepare_workers_hard_max_num: Option<usize>,
+	/// Collect a detailed profile of every PVF execution: per-phase timings, host function call
+	/// counts and peak wasm heap usage. The profiles are logged at the debug level and exported as
+	/// metrics.
+	///
+	/// This adds some overhead to every execution, so it is disabled by default.
+	#[arg(long)]
+	pub pvf_execute_profiling: bool,
 	/// TESTING ONLY: disable the version check between nodes and workers.
 	#[arg(long, hide = true)]
 	pub disable_worker_version_check: bool,
@@ -1191,6 +1191,35 @@ fn process_message(
 				},
 			}
 		},
+		AvailabilityStoreMessage::StoreFinalizedChunk {
+			candidate_hash,
+			n_validators,
+			validator_index,
+			chunk,
+			tx,
+		} => {
+			subsystem.metrics.on_chunks_received(1);
+			let _timer = subsystem.metrics.time_store_chunk();
+
+			match store_finalized_chunk(
+				subsystem,
+				candidate_hash,
+				n_validators as _,
+				validator_index,
+				chunk,
+			) {
+				Ok(true) => {
+					let _ = tx.send(Ok(()));
+				},
+				Ok(false) => {
+					let _ = tx.send(Err(()));
+				},
+				Err(e) => {
+					let _ = tx.send(Err(()));
+					return Err(e)
+				},
+			}
+		},
 		AvailabilityStoreMessage::StoreAvailableData {
 			candidate_hash,
 			n_validators,
@@ -1244,14 +1273,75 @@ fn store_chunk(
 	candidate_hash: CandidateHash,
 	validator_index: ValidatorIndex,
 	chunk: ErasureChunk,
+) -> Result<bool, Error> {
+	let meta = match load_meta(db, config, &candidate_hash)? {
+		Some(m) => m,
+		None => return Ok(false), // we weren't informed of this candidate by import events.
+	};
+
+	write_chunk_and_meta(
+		db,
+		config,
+		DBTransaction::new(),
+		meta,
+		candidate_hash,
+		validator_index,
+		chunk,
+	)
+}
+
+// Like `store_chunk`, but records the candidate as finalized if we don't know it.
+//
+// Ok(true) on success, Ok(false) on failure, and Err on internal error.
+fn store_finalized_chunk(
+	subsystem: &AvailabilityStoreSubsystem,
+	candidate_hash: CandidateHash,
+	n_validators: usize,
+	validator_index: ValidatorIndex,
+	chunk: ErasureChunk,
 ) -> Result<bool, Error> {
 	let mut tx = DBTransaction::new();
 
-	let mut meta = match load_meta(db, config, &candidate_hash)? {
+	let meta = match load_meta(&subsystem.db, &subsystem.config, &candidate_hash)? {
 		Some(m) => m,
-		None => return Ok(false), // we weren't informed of this candidate by import events.
+		None => {
+			let now = subsystem.clock.now()?;
+
+			// We don't know when the candidate was finalized, so we keep it as if it was just now.
+			let prune_at = now + subsystem.pruning_config.keep_finalized_for;
+			write_pruning_key(&mut tx, &subsystem.config, prune_at, &candidate_hash);
+
+			gum::debug!(target: LOG_TARGET, ?candidate_hash, "Recording finalized candidate");
+
+			CandidateMeta {
+				state: State::Finalized(now.into()),
+				data_available: false,
+				chunks_stored: bitvec::bitvec![u8, BitOrderLsb0; 0; n_validators],
+			}
+		},
 	};
 
+	write_chunk_and_meta(
+		&subsystem.db,
+		&subsystem.config,
+		tx,
+		meta,
+		candidate_hash,
+		validator_index,
+		chunk,
+	)
+}
+
+// Ok(true) on success, Ok(false) on failure, and Err on internal error.
+fn write_chunk_and_meta(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	mut tx: DBTransaction,
+	mut meta: CandidateMeta,
+	candidate_hash: CandidateHash,
+	validator_index: ValidatorIndex,
+	chunk: ErasureChunk,
+) -> Result<bool, Error> {
 	match meta.chunks_stored.get(validator_index.0 as usize).map(|b| *b) {
 		Some(true) => return Ok(true), // already stored.
 		Some(false) => {
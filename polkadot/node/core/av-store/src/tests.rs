@@ -371,6 +371,62 @@ fn store_chunk_does_nothing_if_no_entry_already() {
 	});
 }
 
+#[test]
+fn store_finalized_chunk_records_unknown_candidate() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		let candidate_hash = CandidateHash(Hash::repeat_byte(33));
+		let validator_index = ValidatorIndex(2);
+		let n_validators = 10;
+
+		let chunk = ErasureChunk {
+			chunk: vec![1, 2, 3],
+			index: ChunkIndex(5),
+			proof: Proof::try_from(vec![vec![3, 4, 5]]).unwrap(),
+		};
+
+		let (tx, rx) = oneshot::channel();
+		let chunk_msg = AvailabilityStoreMessage::StoreFinalizedChunk {
+			candidate_hash,
+			n_validators,
+			validator_index,
+			chunk: chunk.clone(),
+			tx,
+		};
+
+		overseer_send(&mut virtual_overseer, chunk_msg).await;
+		assert_eq!(rx.await.unwrap(), Ok(()));
+
+		assert_eq!(
+			query_chunk(&mut virtual_overseer, candidate_hash, validator_index)
+				.await
+				.unwrap(),
+			chunk,
+		);
+
+		// The candidate is kept as long as finalized ones, not only as long as unavailable ones.
+		test_state.clock.inc(test_state.pruning_config.keep_unavailable_for * 3 / 2);
+		test_state.wait_for_pruning().await;
+
+		assert_eq!(
+			query_chunk(&mut virtual_overseer, candidate_hash, validator_index)
+				.await
+				.unwrap(),
+			chunk,
+		);
+
+		test_state.clock.inc(test_state.pruning_config.keep_finalized_for);
+		test_state.wait_for_pruning().await;
+
+		assert!(query_chunk(&mut virtual_overseer, candidate_hash, validator_index)
+			.await
+			.is_none());
+		virtual_overseer
+	});
+}
+
 #[test]
 fn query_chunk_checks_meta() {
 	let store = test_store();
@@ -99,6 +99,7 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_util::{
 	self as util,
 	backing_implicit_view::View as ImplicitView,
+	candidate_tracker::{CandidateLifecycleEvent, CandidateTracker, DropReason, TracksCandidates},
 	request_claim_queue, request_disabled_validators, request_min_backing_votes,
 	request_node_features, request_session_executor_params, request_session_index_for_child,
 	request_validator_groups, request_validators,
@@ -181,12 +182,20 @@ impl ValidatedCandidateCommand {
 pub struct CandidateBackingSubsystem {
 	keystore: KeystorePtr,
 	metrics: Metrics,
+	/// Records the candidates we second and back.
+	candidate_tracker: CandidateTracker,
 }
 
 impl CandidateBackingSubsystem {
 	/// Create a new instance of the `CandidateBackingSubsystem`.
 	pub fn new(keystore: KeystorePtr, metrics: Metrics) -> Self {
-		Self { keystore, metrics }
+		Self { keystore, metrics, candidate_tracker: CandidateTracker::default() }
+	}
+}
+
+impl TracksCandidates for CandidateBackingSubsystem {
+	fn candidate_tracker_mut(&mut self) -> &mut CandidateTracker {
+		&mut self.candidate_tracker
 	}
 }
 
@@ -197,7 +206,7 @@ where
 {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
 		let future = async move {
-			run(ctx, self.keystore, self.metrics)
+			run(ctx, self.keystore, self.metrics, self.candidate_tracker)
 				.await
 				.map_err(|e| SubsystemError::with_origin("candidate-backing", e))
 		}
@@ -445,12 +454,15 @@ struct State {
 	background_validation_tx: mpsc::Sender<(Hash, ValidatedCandidateCommand)>,
 	/// The handle to the keystore used for signing.
 	keystore: KeystorePtr,
+	/// Records the lifecycle of the candidates we second and back.
+	candidate_tracker: CandidateTracker,
 }
 
 impl State {
 	fn new(
 		background_validation_tx: mpsc::Sender<(Hash, ValidatedCandidateCommand)>,
 		keystore: KeystorePtr,
+		candidate_tracker: CandidateTracker,
 	) -> Self {
 		State {
 			implicit_view: ImplicitView::default(),
@@ -459,6 +471,7 @@ impl State {
 			per_session_cache: PerSessionCache::default(),
 			background_validation_tx,
 			keystore,
+			candidate_tracker,
 		}
 	}
 }
@@ -468,9 +481,10 @@ async fn run<Context>(
 	mut ctx: Context,
 	keystore: KeystorePtr,
 	metrics: Metrics,
+	candidate_tracker: CandidateTracker,
 ) -> FatalResult<()> {
 	let (background_validation_tx, mut background_validation_rx) = mpsc::channel(16);
-	let mut state = State::new(background_validation_tx, keystore);
+	let mut state = State::new(background_validation_tx, keystore, candidate_tracker);
 
 	loop {
 		let res =
@@ -1418,12 +1432,20 @@ async fn handle_validated_candidate_command<Context>(
 							&mut state.per_candidate,
 							statement,
 							state.keystore.clone(),
+							&state.candidate_tracker,
 							metrics,
 						)
 						.await;
 
 						if let Err(Error::RejectedByProspectiveParachains) = res {
 							let candidate_hash = candidate.hash();
+							state.candidate_tracker.note(
+								candidate_hash,
+								candidate.descriptor.para_id(),
+								CandidateLifecycleEvent::Dropped(
+									DropReason::RejectedByProspectiveParachains,
+								),
+							);
 							gum::debug!(
 								target: LOG_TARGET,
 								relay_parent = ?candidate.descriptor().relay_parent(),
@@ -1456,6 +1478,11 @@ async fn handle_validated_candidate_command<Context>(
 							rp_state.issued_statements.insert(candidate_hash);
 
 							metrics.on_candidate_seconded();
+							state.candidate_tracker.note(
+								candidate_hash,
+								candidate.descriptor.para_id(),
+								CandidateLifecycleEvent::Seconded,
+							);
 							ctx.send_message(CollatorProtocolMessage::Seconded(
 								rp_state.parent,
 								StatementWithPVD::drop_pvd_from_signed(stmt),
@@ -1464,6 +1491,11 @@ async fn handle_validated_candidate_command<Context>(
 						}
 					},
 					Err(candidate) => {
+						state.candidate_tracker.note(
+							candidate_hash,
+							candidate.descriptor.para_id(),
+							CandidateLifecycleEvent::Dropped(DropReason::Invalid),
+						);
 						ctx.send_message(CollatorProtocolMessage::Invalid(
 							rp_state.parent,
 							candidate,
@@ -1485,6 +1517,7 @@ async fn handle_validated_candidate_command<Context>(
 								&mut state.per_candidate,
 								statement,
 								state.keystore.clone(),
+								&state.candidate_tracker,
 								metrics,
 							)
 							.await?;
@@ -1650,6 +1683,7 @@ async fn post_import_statement_actions<Context>(
 	ctx: &mut Context,
 	rp_state: &mut PerRelayParentState,
 	summary: Option<&TableSummary>,
+	candidate_tracker: &CandidateTracker,
 ) {
 	if let Some(attested) = summary.as_ref().and_then(|s| {
 		rp_state.table.attested_candidate(
@@ -1671,6 +1705,7 @@ async fn post_import_statement_actions<Context>(
 					%para_id,
 					"Candidate backed",
 				);
+				candidate_tracker.note(candidate_hash, para_id, CandidateLifecycleEvent::Backed);
 
 				// Inform the prospective parachains subsystem
 				// that the candidate is now backed.
@@ -1724,6 +1759,7 @@ async fn sign_import_and_distribute_statement<Context>(
 	per_candidate: &mut HashMap<CandidateHash, PerCandidateState>,
 	statement: StatementWithPVD,
 	keystore: KeystorePtr,
+	candidate_tracker: &CandidateTracker,
 	metrics: &Metrics,
 ) -> Result<Option<SignedFullStatementWithPVD>, Error> {
 	if let Some(signed_statement) = sign_statement(&*rp_state, statement, keystore, metrics) {
@@ -1734,7 +1770,7 @@ async fn sign_import_and_distribute_statement<Context>(
 		let smsg = StatementDistributionMessage::Share(rp_state.parent, signed_statement.clone());
 		ctx.send_unbounded_message(smsg);
 
-		post_import_statement_actions(ctx, rp_state, summary.as_ref()).await;
+		post_import_statement_actions(ctx, rp_state, summary.as_ref(), candidate_tracker).await;
 
 		Ok(Some(signed_statement))
 	} else {
@@ -1888,7 +1924,7 @@ async fn maybe_validate_and_import<Context>(
 	}
 
 	let summary = res?;
-	post_import_statement_actions(ctx, rp_state, summary.as_ref()).await;
+	post_import_statement_actions(ctx, rp_state, summary.as_ref(), &state.candidate_tracker).await;
 
 	if let Some(summary) = summary {
 		// import_statement already takes care of communicating with the
@@ -195,7 +195,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		polkadot_node_subsystem_test_helpers::make_subsystem_context(pool.clone());
 
 	let subsystem = async move {
-		if let Err(e) = super::run(context, keystore, Metrics(None)).await {
+		if let Err(e) = super::run(context, keystore, Metrics(None), Default::default()).await {
 			panic!("{:?}", e);
 		}
 	};
@@ -49,7 +49,7 @@ use polkadot_primitives::{
 		DEFAULT_LENIENT_PREPARATION_TIMEOUT, DEFAULT_PRECHECK_PREPARATION_TIMEOUT,
 	},
 	transpose_claim_queue, AuthorityDiscoveryId, CandidateCommitments,
-	CandidateDescriptorV2 as CandidateDescriptor, CandidateEvent,
+	CandidateDescriptorV2 as CandidateDescriptor, CandidateEvent, CandidateHash,
 	CandidateReceiptV2 as CandidateReceipt,
 	CommittedCandidateReceiptV2 as CommittedCandidateReceipt, ExecutorParams, Hash,
 	PersistedValidationData, PvfExecKind as RuntimePvfExecKind, PvfPrepKind, SessionIndex,
@@ -112,6 +112,8 @@ pub struct Config {
 	pub pvf_prepare_workers_soft_max_num: usize,
 	/// The absolute number of pvf workers that can be spawned in the pvf prepare pool.
 	pub pvf_prepare_workers_hard_max_num: usize,
+	/// Whether the pvf execute workers should collect a detailed profile of every execution.
+	pub pvf_execute_profiling: bool,
 }
 
 /// The candidate validation subsystem.
@@ -315,22 +317,22 @@ async fn run<Context>(
 		pvf_execute_workers_max_num,
 		pvf_prepare_workers_soft_max_num,
 		pvf_prepare_workers_hard_max_num,
+		pvf_execute_profiling,
 	}: Config,
 ) -> SubsystemResult<()> {
-	let (mut validation_host, task) = polkadot_node_core_pvf::start(
-		polkadot_node_core_pvf::Config::new(
-			artifacts_cache_path,
-			node_version,
-			secure_validator_mode,
-			prep_worker_path,
-			exec_worker_path,
-			pvf_execute_workers_max_num,
-			pvf_prepare_workers_soft_max_num,
-			pvf_prepare_workers_hard_max_num,
-		),
-		pvf_metrics,
-	)
-	.await?;
+	let pvf_config = polkadot_node_core_pvf::Config::new(
+		artifacts_cache_path,
+		node_version,
+		secure_validator_mode,
+		prep_worker_path,
+		exec_worker_path,
+		pvf_execute_workers_max_num,
+		pvf_prepare_workers_soft_max_num,
+		pvf_prepare_workers_hard_max_num,
+		pvf_execute_profiling,
+	);
+	let (mut validation_host, task) =
+		polkadot_node_core_pvf::start(pvf_config, pvf_metrics).await?;
 	ctx.spawn_blocking("pvf-validation-host", task.boxed())?;
 
 	let mut tasks = FuturesUnordered::new();
@@ -911,6 +913,7 @@ async fn validate_candidate_exhaustive(
 				.validate_candidate(
 					pvf,
 					exec_timeout,
+					candidate_hash,
 					persisted_validation_data.clone(),
 					pov,
 					exec_kind.into(),
@@ -923,6 +926,7 @@ async fn validate_candidate_exhaustive(
 				.validate_candidate_with_retry(
 					validation_code.0,
 					pvf_exec_timeout(&executor_params, exec_kind.into()),
+					candidate_hash,
 					persisted_validation_data.clone(),
 					pov,
 					executor_params,
@@ -1076,6 +1080,7 @@ trait ValidationBackend {
 		&mut self,
 		pvf: PvfPrepData,
 		exec_timeout: Duration,
+		candidate_hash: CandidateHash,
 		pvd: Arc<PersistedValidationData>,
 		pov: Arc<PoV>,
 		// The priority for the preparation job.
@@ -1096,6 +1101,7 @@ trait ValidationBackend {
 		&mut self,
 		code: Vec<u8>,
 		exec_timeout: Duration,
+		candidate_hash: CandidateHash,
 		pvd: Arc<PersistedValidationData>,
 		pov: Arc<PoV>,
 		executor_params: ExecutorParams,
@@ -1124,6 +1130,7 @@ trait ValidationBackend {
 			.validate_candidate(
 				pvf.clone(),
 				exec_timeout,
+				candidate_hash,
 				pvd.clone(),
 				pov.clone(),
 				prepare_priority,
@@ -1211,6 +1218,7 @@ trait ValidationBackend {
 					.validate_candidate(
 						pvf.clone(),
 						new_timeout,
+						candidate_hash,
 						pvd.clone(),
 						pov.clone(),
 						prepare_priority,
@@ -1241,6 +1249,7 @@ impl ValidationBackend for ValidationHost {
 		&mut self,
 		pvf: PvfPrepData,
 		exec_timeout: Duration,
+		candidate_hash: CandidateHash,
 		pvd: Arc<PersistedValidationData>,
 		pov: Arc<PoV>,
 		// The priority for the preparation job.
@@ -1250,7 +1259,16 @@ impl ValidationBackend for ValidationHost {
 	) -> Result<WasmValidationResult, ValidationError> {
 		let (tx, rx) = oneshot::channel();
 		if let Err(err) = self
-			.execute_pvf(pvf, exec_timeout, pvd, pov, prepare_priority, exec_kind, tx)
+			.execute_pvf(
+				pvf,
+				exec_timeout,
+				candidate_hash,
+				pvd,
+				pov,
+				prepare_priority,
+				exec_kind,
+				tx,
+			)
 			.await
 		{
 			return Err(InternalValidationError::HostCommunication(format!(
@@ -439,6 +439,7 @@ impl ValidationBackend for MockValidateCandidateBackend {
 		&mut self,
 		_pvf: PvfPrepData,
 		_timeout: Duration,
+		_candidate_hash: CandidateHash,
 		_pvd: Arc<PersistedValidationData>,
 		_pov: Arc<PoV>,
 		_prepare_priority: polkadot_node_core_pvf::Priority,
@@ -1411,6 +1412,7 @@ impl ValidationBackend for MockPreCheckBackend {
 		&mut self,
 		_pvf: PvfPrepData,
 		_timeout: Duration,
+		_candidate_hash: CandidateHash,
 		_pvd: Arc<PersistedValidationData>,
 		_pov: Arc<PoV>,
 		_prepare_priority: polkadot_node_core_pvf::Priority,
@@ -1570,6 +1572,7 @@ impl ValidationBackend for MockHeadsUp {
 		&mut self,
 		_pvf: PvfPrepData,
 		_timeout: Duration,
+		_candidate_hash: CandidateHash,
 		_pvd: Arc<PersistedValidationData>,
 		_pov: Arc<PoV>,
 		_prepare_priority: polkadot_node_core_pvf::Priority,
@@ -19,17 +19,24 @@
 use polkadot_node_primitives::BlockWeight;
 use polkadot_node_subsystem::{
 	errors::ChainApiError,
-	messages::{ChainApiMessage, ChainSelectionMessage},
+	messages::{
+		BlockApprovalStatus, BlockViability, ChainApiMessage, ChainSelectionMessage,
+		ChainViability, RevertReason,
+	},
 	overseer::{self, SubsystemSender},
 	FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
 };
-use polkadot_node_subsystem_util::database::Database;
+use polkadot_node_subsystem_util::{
+	candidate_tracker::{CandidateTracker, TracksCandidates},
+	database::Database,
+};
 use polkadot_primitives::{BlockNumber, ConsensusLog, Hash, Header};
 
 use codec::Error as CodecError;
 use futures::{channel::oneshot, future::Either, prelude::*};
 
 use std::{
+	collections::{HashMap, VecDeque},
 	sync::Arc,
 	time::{Duration, SystemTime, UNIX_EPOCH},
 };
@@ -73,6 +80,16 @@ impl Approval {
 	}
 }
 
+impl From<&Approval> for BlockApprovalStatus {
+	fn from(approval: &Approval) -> Self {
+		match approval {
+			Approval::Approved => BlockApprovalStatus::Approved,
+			Approval::Unapproved => BlockApprovalStatus::Unapproved,
+			Approval::Stagnant => BlockApprovalStatus::Stagnant,
+		}
+	}
+}
+
 #[derive(Debug, Clone)]
 struct ViabilityCriteria {
 	// Whether this block has been explicitly reverted by one of its descendants.
@@ -191,6 +208,28 @@ impl BlockEntry {
 	}
 }
 
+// The reasons for explicit reversions applied since the subsystem started.
+//
+// These are only kept in memory and are used for inspection purposes only.
+#[derive(Debug, Default)]
+struct RevertReasons {
+	inner: HashMap<Hash, (BlockNumber, RevertReason)>,
+}
+
+impl RevertReasons {
+	fn note(&mut self, hash: Hash, number: BlockNumber, reason: RevertReason) {
+		self.inner.entry(hash).or_insert((number, reason));
+	}
+
+	fn get(&self, hash: &Hash) -> Option<RevertReason> {
+		self.inner.get(hash).map(|(_, reason)| *reason)
+	}
+
+	fn prune_finalized(&mut self, finalized_number: BlockNumber) {
+		self.inner.retain(|_, (number, _)| *number > finalized_number);
+	}
+}
+
 #[derive(Debug, thiserror::Error)]
 #[allow(missing_docs)]
 pub enum Error {
@@ -329,13 +368,15 @@ pub struct Config {
 pub struct ChainSelectionSubsystem {
 	config: Config,
 	db: Arc<dyn Database>,
+	/// Records the approval and finality of blocks.
+	candidate_tracker: CandidateTracker,
 }
 
 impl ChainSelectionSubsystem {
 	/// Create a new instance of the subsystem with the given config
 	/// and key-value store.
 	pub fn new(config: Config, db: Arc<dyn Database>) -> Self {
-		ChainSelectionSubsystem { config, db }
+		ChainSelectionSubsystem { config, db, candidate_tracker: CandidateTracker::default() }
 	}
 
 	/// Revert to the block corresponding to the specified `hash`.
@@ -350,6 +391,12 @@ impl ChainSelectionSubsystem {
 	}
 }
 
+impl TracksCandidates for ChainSelectionSubsystem {
+	fn candidate_tracker_mut(&mut self) -> &mut CandidateTracker {
+		&mut self.candidate_tracker
+	}
+}
+
 #[overseer::subsystem(ChainSelection, error = SubsystemError, prefix = self::overseer)]
 impl<Context> ChainSelectionSubsystem {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
@@ -365,6 +412,7 @@ impl<Context> ChainSelectionSubsystem {
 				self.config.stagnant_check_interval,
 				self.config.stagnant_check_mode,
 				Box::new(SystemClock),
+				self.candidate_tracker,
 			)
 			.map(Ok)
 			.boxed(),
@@ -380,6 +428,7 @@ async fn run<Context, B>(
 	stagnant_check_interval: StagnantCheckInterval,
 	stagnant_check_mode: StagnantCheckMode,
 	clock: Box<dyn Clock + Send + Sync>,
+	candidate_tracker: CandidateTracker,
 ) where
 	B: Backend,
 {
@@ -391,6 +440,7 @@ async fn run<Context, B>(
 			&stagnant_check_interval,
 			&stagnant_check_mode,
 			&*clock,
+			&candidate_tracker,
 		)
 		.await;
 		match res {
@@ -419,11 +469,13 @@ async fn run_until_error<Context, B>(
 	stagnant_check_interval: &StagnantCheckInterval,
 	stagnant_check_mode: &StagnantCheckMode,
 	clock: &(dyn Clock + Sync),
+	candidate_tracker: &CandidateTracker,
 ) -> Result<(), Error>
 where
 	B: Backend,
 {
 	let mut stagnant_check_stream = stagnant_check_interval.timeout_stream();
+	let mut revert_reasons = RevertReasons::default();
 	loop {
 		futures::select! {
 			msg = ctx.recv().fuse() => {
@@ -439,17 +491,20 @@ where
 								&*backend,
 								clock.timestamp_now() + STAGNANT_TIMEOUT,
 								leaf.hash,
+								&mut revert_reasons,
 							).await?;
 
 							backend.write(write_ops)?;
 						}
 					}
 					FromOrchestra::Signal(OverseerSignal::BlockFinalized(h, n)) => {
-						handle_finalized_block(backend, h, n)?
+						handle_finalized_block(backend, h, n, candidate_tracker)?;
+						revert_reasons.prune_finalized(n);
 					}
 					FromOrchestra::Communication { msg } => match msg {
 						ChainSelectionMessage::Approved(hash) => {
-							handle_approved_block(backend, hash)?
+							handle_approved_block(backend, hash)?;
+							candidate_tracker.note_block_approved(hash);
 						}
 						ChainSelectionMessage::Leaves(tx) => {
 							let leaves = load_leaves(ctx.sender(), &*backend).await?;
@@ -469,9 +524,17 @@ where
 							let _ = tx.send(best_containing);
 						}
 						ChainSelectionMessage::RevertBlocks(blocks_to_revert) => {
-							let write_ops = handle_revert_blocks(backend, blocks_to_revert)?;
+							let write_ops = handle_revert_blocks(
+								backend,
+								blocks_to_revert,
+								&mut revert_reasons,
+							)?;
 							backend.write(write_ops)?;
 						}
+						ChainSelectionMessage::InspectViability(tx) => {
+							let viability = inspect_viability(&*backend, &revert_reasons)?;
+							let _ = tx.send(viability);
+						}
 					}
 				}
 			}
@@ -554,6 +617,7 @@ async fn handle_active_leaf(
 	backend: &impl Backend,
 	stagnant_at: Timestamp,
 	hash: Hash,
+	revert_reasons: &mut RevertReasons,
 ) -> Result<Vec<BackendWriteOp>, Error> {
 	let lower_bound = match backend.load_first_block_number()? {
 		Some(l) => {
@@ -603,7 +667,7 @@ async fn handle_active_leaf(
 		};
 
 		let reversion_logs = extract_reversion_logs(&header);
-		tree::import_block(
+		let reverted = tree::import_block(
 			&mut overlay,
 			hash,
 			header.number,
@@ -612,6 +676,14 @@ async fn handle_active_leaf(
 			weight,
 			stagnant_at,
 		)?;
+
+		for (reverted_number, reverted_hash) in reverted {
+			revert_reasons.note(
+				reverted_hash,
+				reverted_number,
+				RevertReason::RevertLog { reported_by: hash },
+			);
+		}
 	}
 
 	Ok(overlay.into_write_ops().collect())
@@ -665,7 +737,17 @@ fn handle_finalized_block(
 	backend: &mut impl Backend,
 	finalized_hash: Hash,
 	finalized_number: BlockNumber,
+	candidate_tracker: &CandidateTracker,
 ) -> Result<(), Error> {
+	// The entries of newly finalized blocks are pruned below, so note their finality first.
+	if candidate_tracker.is_enabled() {
+		let mut hash = finalized_hash;
+		while let Some(entry) = backend.load_block_entry(&hash)? {
+			candidate_tracker.note_block_finalized(hash);
+			hash = entry.parent_hash;
+		}
+	}
+
 	let ops = tree::finalize_block(&*backend, finalized_hash, finalized_number)?.into_write_ops();
 
 	backend.write(ops)
@@ -690,10 +772,13 @@ fn handle_approved_block(backend: &mut impl Backend, approved_block: Hash) -> Re
 fn handle_revert_blocks(
 	backend: &impl Backend,
 	blocks_to_revert: Vec<(BlockNumber, Hash)>,
+	revert_reasons: &mut RevertReasons,
 ) -> Result<Vec<BackendWriteOp>, Error> {
 	let mut overlay = OverlayedBackend::new(backend);
 	for (block_number, block_hash) in blocks_to_revert {
-		tree::apply_single_reversion(&mut overlay, block_hash, block_number)?;
+		if tree::apply_single_reversion(&mut overlay, block_hash, block_number)? {
+			revert_reasons.note(block_hash, block_number, RevertReason::DisputeConcludedAgainst);
+		}
 	}
 
 	Ok(overlay.into_write_ops().collect())
@@ -741,3 +826,49 @@ async fn load_leaves(
 		Ok(leaves)
 	}
 }
+
+// Gather the viability of all unfinalized blocks, starting from the blocks
+// directly above the finalized block and descending through their children.
+fn inspect_viability(
+	backend: &impl Backend,
+	revert_reasons: &RevertReasons,
+) -> Result<ChainViability, Error> {
+	let leaves = backend.load_leaves()?;
+	let mut viability =
+		ChainViability { leaves: leaves.into_hashes_descending().collect(), blocks: Vec::new() };
+
+	let mut to_visit = match backend.load_first_block_number()? {
+		None => return Ok(viability),
+		Some(first) => backend.load_blocks_by_number(first)?.into_iter().collect::<VecDeque<_>>(),
+	};
+
+	while let Some(hash) = to_visit.pop_front() {
+		let entry = match backend.load_block_entry(&hash)? {
+			None => continue,
+			Some(entry) => entry,
+		};
+
+		let revert_reason = entry
+			.viability
+			.explicitly_reverted
+			.then(|| revert_reasons.get(&hash).unwrap_or(RevertReason::Unknown));
+
+		viability.blocks.push(BlockViability {
+			hash,
+			number: entry.block_number,
+			parent_hash: entry.parent_hash,
+			weight: entry.weight,
+			is_leaf: viability.leaves.contains(&hash),
+			approval: (&entry.viability.approval).into(),
+			revert_reason,
+			earliest_unviable_ancestor: entry.viability.earliest_unviable_ancestor,
+			viable: entry.viability.is_viable(),
+		});
+
+		to_visit.extend(entry.children);
+	}
+
+	viability.blocks.sort_by_key(|b| b.number);
+
+	Ok(viability)
+}
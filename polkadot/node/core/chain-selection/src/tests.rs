@@ -247,6 +247,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		StagnantCheckInterval::new(TEST_STAGNANT_INTERVAL),
 		StagnantCheckMode::CheckAndPrune,
 		Box::new(clock.clone()),
+		Default::default(),
 	);
 
 	let test_fut = test(backend, clock, virtual_overseer);
@@ -2149,3 +2150,83 @@ fn revert_blocks_against_finalized_is_ignored() {
 		virtual_overseer
 	})
 }
+
+#[test]
+fn inspect_viability_reports_revert_reasons() {
+	test_harness(|backend, _, mut virtual_overseer| async move {
+		let finalized_number = 0;
+		let finalized_hash = Hash::repeat_byte(0);
+
+		// F <- A1 <- A2 <- A3
+		//   \
+		//    B1 <- B2
+		//
+		// A3 contains a revert log for block number 2, B2 is reverted by a dispute.
+		let (a3_hash, chain_a) =
+			construct_chain_on_base(vec![1, 2, 3], finalized_number, finalized_hash, |h| {
+				if h.number == 3 {
+					add_reversions(h, Some(2))
+				}
+			});
+		let a1_hash = chain_a[0].0.hash();
+		let a2_hash = chain_a[1].0.hash();
+
+		let (b2_hash, chain_b) =
+			construct_chain_on_base(vec![5, 6], finalized_number, finalized_hash, |h| {
+				salt_header(h, b"b");
+			});
+		let b1_hash = chain_b[0].0.hash();
+
+		import_chains_into_empty(
+			&mut virtual_overseer,
+			&backend,
+			finalized_number,
+			finalized_hash,
+			vec![chain_a, chain_b],
+		)
+		.await;
+
+		let (_, write_rx) = backend.await_next_write();
+		virtual_overseer
+			.send(FromOrchestra::Communication {
+				msg: ChainSelectionMessage::RevertBlocks(vec![(2, b2_hash)]),
+			})
+			.await;
+		write_rx.await.unwrap();
+
+		let (tx, rx) = oneshot::channel();
+		virtual_overseer
+			.send(FromOrchestra::Communication { msg: ChainSelectionMessage::InspectViability(tx) })
+			.await;
+		let viability = rx.await.unwrap();
+
+		assert_eq!(viability.leaves, vec![b1_hash, a1_hash]);
+		assert_eq!(
+			viability.blocks.iter().map(|b| b.number).collect::<Vec<_>>(),
+			vec![1, 1, 2, 2, 3],
+		);
+
+		let block = |hash| viability.blocks.iter().find(|b| b.hash == hash).unwrap();
+
+		assert!(block(a1_hash).viable);
+		assert!(block(a1_hash).is_leaf);
+		assert_eq!(block(a1_hash).approval, BlockApprovalStatus::Unapproved);
+		assert_eq!(block(a1_hash).revert_reason, None);
+
+		assert!(!block(a2_hash).viable);
+		assert_eq!(
+			block(a2_hash).revert_reason,
+			Some(RevertReason::RevertLog { reported_by: a3_hash }),
+		);
+
+		assert!(!block(a3_hash).viable);
+		assert_eq!(block(a3_hash).revert_reason, None);
+		assert_eq!(block(a3_hash).earliest_unviable_ancestor, Some(a2_hash));
+
+		assert!(!block(b2_hash).viable);
+		assert!(!block(b2_hash).is_leaf);
+		assert_eq!(block(b2_hash).revert_reason, Some(RevertReason::DisputeConcludedAgainst));
+
+		virtual_overseer
+	})
+}
@@ -237,6 +237,8 @@ fn propagate_viability_update(
 }
 
 /// Imports a new block and applies any reversions to ancestors or the block itself.
+///
+/// Returns the blocks which were reverted by the reversion logs of the imported block.
 pub(crate) fn import_block(
 	backend: &mut OverlayedBackend<impl Backend>,
 	block_hash: Hash,
@@ -245,12 +247,10 @@ pub(crate) fn import_block(
 	reversion_logs: Vec<BlockNumber>,
 	weight: BlockWeight,
 	stagnant_at: Timestamp,
-) -> Result<(), Error> {
+) -> Result<Vec<(BlockNumber, Hash)>, Error> {
 	let block_entry =
 		add_block(backend, block_hash, block_number, parent_hash, weight, stagnant_at)?;
-	apply_reversions(backend, block_entry, reversion_logs)?;
-
-	Ok(())
+	apply_reversions(backend, block_entry, reversion_logs)
 }
 
 // Load the given ancestor's block entry, in descending order from the `block_hash`.
@@ -358,7 +358,9 @@ fn apply_reversions(
 	backend: &mut OverlayedBackend<impl Backend>,
 	block_entry: BlockEntry,
 	reversions: Vec<BlockNumber>,
-) -> Result<(), Error> {
+) -> Result<Vec<(BlockNumber, Hash)>, Error> {
+	let mut reverted = Vec::new();
+
 	// Note: since revert numbers are  in ascending order, the expensive propagation
 	// of unviability is only heavy on the first log.
 	for revert_number in reversions {
@@ -371,7 +373,7 @@ fn apply_reversions(
 				"Block marked as reverted via scraped on-chain reversions"
 			);
 		}
-		revert_single_block_entry_if_present(
+		let maybe_reverted = revert_single_block_entry_if_present(
 			backend,
 			maybe_block_entry,
 			None,
@@ -379,19 +381,22 @@ fn apply_reversions(
 			Some(block_entry.block_hash),
 			Some(block_entry.block_number),
 		)?;
+		reverted.extend(maybe_reverted.map(|hash| (revert_number, hash)));
 	}
 
-	Ok(())
+	Ok(reverted)
 }
 
 /// Marks a single block as explicitly reverted, then propagates viability updates
 /// to all its children. This is triggered when the disputes subsystem signals that
 /// a dispute has concluded against a candidate.
+///
+/// Returns `false` if the block is not known, e.g. because it is already finalized.
 pub(crate) fn apply_single_reversion(
 	backend: &mut OverlayedBackend<impl Backend>,
 	revert_hash: Hash,
 	revert_number: BlockNumber,
-) -> Result<(), Error> {
+) -> Result<bool, Error> {
 	gum::trace!(
 		target: LOG_TARGET,
 		?revert_number,
@@ -399,7 +404,7 @@ pub(crate) fn apply_single_reversion(
 		"Block marked as reverted via ChainSelectionMessage::RevertBlocks"
 	);
 	let maybe_block_entry = backend.load_block_entry(&revert_hash)?;
-	revert_single_block_entry_if_present(
+	let reverted = revert_single_block_entry_if_present(
 		backend,
 		maybe_block_entry,
 		Some(revert_hash),
@@ -407,7 +412,7 @@ pub(crate) fn apply_single_reversion(
 		None,
 		None,
 	)?;
-	Ok(())
+	Ok(reverted.is_some())
 }
 
 fn revert_single_block_entry_if_present(
@@ -417,7 +422,7 @@ fn revert_single_block_entry_if_present(
 	revert_number: BlockNumber,
 	maybe_reporting_hash: Option<Hash>,
 	maybe_reporting_number: Option<BlockNumber>,
-) -> Result<(), Error> {
+) -> Result<Option<Hash>, Error> {
 	match maybe_block_entry {
 		None => {
 			gum::warn!(
@@ -430,6 +435,8 @@ fn revert_single_block_entry_if_present(
 				The protocol has indicated that a finalized block be reverted. \
 				Please inform an adult.",
 			);
+
+			Ok(None)
 		},
 		Some(mut block_entry) => {
 			gum::info!(
@@ -441,12 +448,14 @@ fn revert_single_block_entry_if_present(
 				"Unfinalized block reverted due to a bad parachain block.",
 			);
 
+			let block_hash = block_entry.block_hash;
 			block_entry.viability.explicitly_reverted = true;
 			// Marks children of reverted block as non-viable
 			propagate_viability_update(backend, block_entry)?;
+
+			Ok(Some(block_hash))
 		},
 	}
-	Ok(())
 }
 
 /// Finalize a block with the given number and hash.
@@ -135,7 +135,13 @@ impl Initialized {
 		offchain_disabled_validators: OffchainDisabledValidators,
 		controlled_validator_indices: ControlledValidatorIndices,
 	) -> Self {
-		let DisputeCoordinatorSubsystem { config: _, store: _, keystore, metrics } = subsystem;
+		let DisputeCoordinatorSubsystem {
+			config: _,
+			store: _,
+			keystore,
+			metrics,
+			candidate_tracker: _,
+		} = subsystem;
 
 		let (participation_sender, participation_receiver) = mpsc::channel(1);
 		let participation = Participation::new(participation_sender, metrics.clone());
@@ -42,6 +42,7 @@ use polkadot_node_subsystem::{
 	SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_util::{
+	candidate_tracker::{CandidateTracker, TracksCandidates},
 	database::Database,
 	runtime::{Config as RuntimeInfoConfig, RuntimeInfo},
 	ControlledValidatorIndices,
@@ -123,6 +124,14 @@ pub struct DisputeCoordinatorSubsystem {
 	store: Arc<dyn Database>,
 	keystore: Arc<LocalKeystore>,
 	metrics: Metrics,
+	/// Records the on-chain lifecycle of the candidates we scrape.
+	candidate_tracker: CandidateTracker,
+}
+
+impl TracksCandidates for DisputeCoordinatorSubsystem {
+	fn candidate_tracker_mut(&mut self) -> &mut CandidateTracker {
+		&mut self.candidate_tracker
+	}
 }
 
 /// Configuration for the dispute coordinator subsystem.
@@ -166,7 +175,7 @@ impl DisputeCoordinatorSubsystem {
 		keystore: Arc<LocalKeystore>,
 		metrics: Metrics,
 	) -> Self {
-		Self { store, config, keystore, metrics }
+		Self { store, config, keystore, metrics, candidate_tracker: CandidateTracker::default() }
 	}
 
 	/// Initialize and afterwards run `Initialized::run`.
@@ -362,7 +371,8 @@ impl DisputeCoordinatorSubsystem {
 		let mut controlled_indices =
 			ControlledValidatorIndices::new(self.keystore.clone(), DISPUTE_WINDOW.get());
 		let leaf_hash = initial_head.hash;
-		let (scraper, votes) = ChainScraper::new(ctx.sender(), initial_head).await?;
+		let (scraper, votes) =
+			ChainScraper::new(ctx.sender(), initial_head, self.candidate_tracker.clone()).await?;
 		for ((session, ref candidate_hash), _) in active_disputes {
 			let env = match CandidateEnvironment::new(
 				ctx,
@@ -24,8 +24,9 @@ use polkadot_node_subsystem::{
 	messages::ChainApiMessage, overseer, ActivatedLeaf, ActiveLeavesUpdate, ChainApiError,
 	RuntimeApiError, SubsystemSender,
 };
-use polkadot_node_subsystem_util::runtime::{
-	self, get_candidate_events, get_on_chain_votes, get_unapplied_slashes,
+use polkadot_node_subsystem_util::{
+	candidate_tracker::{CandidateLifecycleEvent, CandidateTracker, DropReason},
+	runtime::{self, get_candidate_events, get_on_chain_votes, get_unapplied_slashes},
 };
 use polkadot_primitives::{
 	slashing::PendingSlashes, BlockNumber, CandidateEvent, CandidateHash,
@@ -195,6 +196,9 @@ pub struct ChainScraper {
 	/// This is used to avoid redundant scraping of ancestry. We assume that ancestors of cached
 	/// blocks are already processed, i.e. we have saved corresponding included candidates.
 	last_observed_blocks: LruMap<Hash, ()>,
+
+	/// Records the on-chain lifecycle events of the scraped candidates.
+	candidate_tracker: CandidateTracker,
 }
 
 impl ChainScraper {
@@ -211,6 +215,7 @@ impl ChainScraper {
 	pub async fn new<Sender>(
 		sender: &mut Sender,
 		initial_head: ActivatedLeaf,
+		candidate_tracker: CandidateTracker,
 	) -> Result<(Self, Vec<ScrapedOnChainVotes>)>
 	where
 		Sender: overseer::DisputeCoordinatorSenderTrait,
@@ -219,6 +224,7 @@ impl ChainScraper {
 			backed_candidates: candidates::ScrapedCandidates::new(),
 			inclusions: Inclusions::new(),
 			last_observed_blocks: LruMap::new(ByLength::new(LRU_OBSERVED_BLOCKS_CAPACITY)),
+			candidate_tracker,
 		};
 		let update =
 			ActiveLeavesUpdate { activated: Some(initial_head), deactivated: Default::default() };
@@ -357,6 +363,11 @@ impl ChainScraper {
 						"Processing included event"
 					);
 					self.inclusions.insert(candidate_hash, block_number, block_hash);
+					self.candidate_tracker.note(
+						candidate_hash,
+						receipt.descriptor.para_id(),
+						CandidateLifecycleEvent::Included { block_hash },
+					);
 					included_receipts.push(receipt);
 				},
 				CandidateEvent::CandidateBacked(receipt, _, _, _) => {
@@ -368,9 +379,18 @@ impl ChainScraper {
 						"Processing backed event"
 					);
 					self.backed_candidates.insert(block_number, candidate_hash);
+					self.candidate_tracker.note(
+						candidate_hash,
+						receipt.descriptor.para_id(),
+						CandidateLifecycleEvent::BackedOnChain { block_hash },
+					);
 				},
-				_ => {
-					// skip the rest
+				CandidateEvent::CandidateTimedOut(receipt, _, _) => {
+					self.candidate_tracker.note(
+						receipt.hash(),
+						receipt.descriptor.para_id(),
+						CandidateLifecycleEvent::Dropped(DropReason::AvailabilityTimedOut),
+					);
 				},
 			}
 		}
@@ -86,10 +86,11 @@ impl TestState {
 			assert_unapplied_slashes_request(&mut ctx_handle, &chain).await;
 		};
 
-		let (scraper, _) = join(ChainScraper::new(ctx.sender(), leaf.clone()), overseer_fut)
-			.await
-			.0
-			.unwrap();
+		let (scraper, _) =
+			join(ChainScraper::new(ctx.sender(), leaf.clone(), Default::default()), overseer_fut)
+				.await
+				.0
+				.unwrap();
 		gum::trace!(target: LOG_TARGET, "After launching chain scraper");
 
 		let test_state = Self { chain, scraper, ctx };
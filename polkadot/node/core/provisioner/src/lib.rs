@@ -36,7 +36,10 @@ use polkadot_node_subsystem::{
 	overseer, ActivatedLeaf, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem,
 	SubsystemError,
 };
-use polkadot_node_subsystem_util::{request_availability_cores, TimeoutExt};
+use polkadot_node_subsystem_util::{
+	candidate_tracker::{CandidateLifecycleEvent, CandidateTracker, TracksCandidates},
+	request_availability_cores, TimeoutExt,
+};
 use polkadot_primitives::{
 	BackedCandidate, CandidateEvent, CandidateHash, CoreIndex, CoreState, Hash, Id as ParaId,
 	SignedAvailabilityBitfield, ValidatorIndex,
@@ -67,12 +70,20 @@ const LOG_TARGET: &str = "parachain::provisioner";
 /// The provisioner subsystem.
 pub struct ProvisionerSubsystem {
 	metrics: Metrics,
+	/// Records the candidates we provision into relay chain blocks.
+	candidate_tracker: CandidateTracker,
 }
 
 impl ProvisionerSubsystem {
 	/// Create a new instance of the `ProvisionerSubsystem`.
 	pub fn new(metrics: Metrics) -> Self {
-		Self { metrics }
+		Self { metrics, candidate_tracker: CandidateTracker::default() }
+	}
+}
+
+impl TracksCandidates for ProvisionerSubsystem {
+	fn candidate_tracker_mut(&mut self) -> &mut CandidateTracker {
+		&mut self.candidate_tracker
 	}
 }
 
@@ -104,7 +115,7 @@ type InherentReceivers =
 impl<Context> ProvisionerSubsystem {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
 		let future = async move {
-			run(ctx, self.metrics)
+			run(ctx, self.metrics, self.candidate_tracker)
 				.await
 				.map_err(|e| SubsystemError::with_origin("provisioner", e))
 		}
@@ -115,7 +126,11 @@ impl<Context> ProvisionerSubsystem {
 }
 
 #[overseer::contextbounds(Provisioner, prefix = self::overseer)]
-async fn run<Context>(mut ctx: Context, metrics: Metrics) -> FatalResult<()> {
+async fn run<Context>(
+	mut ctx: Context,
+	metrics: Metrics,
+	candidate_tracker: CandidateTracker,
+) -> FatalResult<()> {
 	let mut inherent_delays = InherentDelays::new();
 	let mut inherent_receivers = InherentReceivers::new();
 	let mut slot_delays = SlotDelays::new();
@@ -131,6 +146,7 @@ async fn run<Context>(mut ctx: Context, metrics: Metrics) -> FatalResult<()> {
 			&mut inherents,
 			&mut slot_delays,
 			&metrics,
+			&candidate_tracker,
 		)
 		.await;
 
@@ -152,6 +168,7 @@ async fn run_iteration<Context>(
 	inherents: &mut LruMap<Hash, ProvisionerInherentData>,
 	slot_delays: &mut SlotDelays,
 	metrics: &Metrics,
+	candidate_tracker: &CandidateTracker,
 ) -> Result<(), Error> {
 	loop {
 		futures::select! {
@@ -163,7 +180,7 @@ async fn run_iteration<Context>(
 					FromOrchestra::Signal(OverseerSignal::BlockFinalized(..)) => {},
 					FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
 					FromOrchestra::Communication { msg } => {
-						handle_communication(ctx, per_relay_parent, msg, metrics).await?;
+						handle_communication(ctx, per_relay_parent, msg, metrics, candidate_tracker).await?;
 					},
 				}
 			},
@@ -186,7 +203,17 @@ async fn run_iteration<Context>(
 
 				inherent_receivers.push(task);
 
-				send_inherent_data_bg(ctx, &state, vec![inherent_tx], metrics.clone()).await?;
+				// The debug inherent is never proposed, so only the availability of candidates is
+				// recorded, not their provisioning.
+				send_inherent_data_bg(
+					ctx,
+					&state,
+					vec![inherent_tx],
+					metrics.clone(),
+					candidate_tracker.clone(),
+					false,
+				)
+				.await?;
 			},
 			(hash, inherent_data) = inherent_receivers.select_next_some() => {
 				let Ok(inherent_data) = inherent_data else {
@@ -212,7 +239,15 @@ async fn run_iteration<Context>(
 
 					let return_senders = std::mem::take(&mut state.awaiting_inherent);
 					if !return_senders.is_empty() {
-						send_inherent_data_bg(ctx, &state, return_senders, metrics.clone()).await?;
+						send_inherent_data_bg(
+							ctx,
+							&state,
+							return_senders,
+							metrics.clone(),
+							candidate_tracker.clone(),
+							true,
+						)
+						.await?;
 					}
 				}
 			}
@@ -297,6 +332,7 @@ async fn handle_communication<Context>(
 	per_relay_parent: &mut HashMap<Hash, PerRelayParent>,
 	message: ProvisionerMessage,
 	metrics: &Metrics,
+	candidate_tracker: &CandidateTracker,
 ) -> Result<(), Error> {
 	match message {
 		ProvisionerMessage::RequestInherentData(relay_parent, return_sender) => {
@@ -305,8 +341,15 @@ async fn handle_communication<Context>(
 			if let Some(state) = per_relay_parent.get_mut(&relay_parent) {
 				if state.is_inherent_ready {
 					gum::trace!(target: LOG_TARGET, ?relay_parent, "Calling send_inherent_data.");
-					send_inherent_data_bg(ctx, &state, vec![return_sender], metrics.clone())
-						.await?;
+					send_inherent_data_bg(
+						ctx,
+						&state,
+						vec![return_sender],
+						metrics.clone(),
+						candidate_tracker.clone(),
+						true,
+					)
+					.await?;
 				} else {
 					gum::trace!(
 						target: LOG_TARGET,
@@ -337,6 +380,8 @@ async fn send_inherent_data_bg<Context>(
 	per_relay_parent: &PerRelayParent,
 	return_senders: Vec<oneshot::Sender<ProvisionerInherentData>>,
 	metrics: Metrics,
+	candidate_tracker: CandidateTracker,
+	note_provisioned: bool,
 ) -> Result<(), Error> {
 	let leaf = per_relay_parent.leaf.clone();
 	let signed_bitfields = per_relay_parent.signed_bitfields.clone();
@@ -351,13 +396,20 @@ async fn send_inherent_data_bg<Context>(
 			"Sending inherent data in background."
 		);
 
-		let send_result =
-			send_inherent_data(&leaf, &signed_bitfields, return_senders, &mut sender, &metrics) // Make sure call is not taking forever:
-				.timeout(SEND_INHERENT_DATA_TIMEOUT)
-				.map(|v| match v {
-					Some(r) => r,
-					None => Err(Error::SendInherentDataTimeout),
-				});
+		let send_result = send_inherent_data(
+			&leaf,
+			&signed_bitfields,
+			return_senders,
+			&mut sender,
+			&metrics,
+			&candidate_tracker,
+			note_provisioned,
+		) // Make sure call is not taking forever:
+		.timeout(SEND_INHERENT_DATA_TIMEOUT)
+		.map(|v| match v {
+			Some(r) => r,
+			None => Err(Error::SendInherentDataTimeout),
+		});
 
 		match send_result.await {
 			Err(err) => {
@@ -440,12 +492,17 @@ type CoreAvailability = BitVec<u8, bitvec::order::Lsb0>;
 /// When we're choosing bitfields to include, the rule should be simple:
 /// maximize availability. So basically, include all bitfields. And then
 /// choose a coherent set of candidates along with that.
+///
+/// The candidates made available by the selected bitfields are noted on the `candidate_tracker`,
+/// the selected backed candidates only if `note_provisioned` is set.
 async fn send_inherent_data(
 	leaf: &ActivatedLeaf,
 	bitfields: &[SignedAvailabilityBitfield],
 	return_senders: Vec<oneshot::Sender<ProvisionerInherentData>>,
 	from_job: &mut impl overseer::ProvisionerSenderTrait,
 	metrics: &Metrics,
+	candidate_tracker: &CandidateTracker,
+	note_provisioned: bool,
 ) -> Result<(), Error> {
 	gum::trace!(
 		target: LOG_TARGET,
@@ -479,6 +536,8 @@ async fn send_inherent_data(
 		"Selected bitfields"
 	);
 
+	note_available_candidates(&availability_cores, &bitfields, leaf.hash, candidate_tracker);
+
 	let candidates = select_candidates(&availability_cores, &bitfields, leaf, from_job).await?;
 
 	gum::trace!(
@@ -487,6 +546,16 @@ async fn send_inherent_data(
 		"Selected candidates"
 	);
 
+	if note_provisioned {
+		for candidate in &candidates {
+			candidate_tracker.note(
+				candidate.hash(),
+				candidate.descriptor().para_id(),
+				CandidateLifecycleEvent::Provisioned { relay_parent: leaf.hash },
+			);
+		}
+	}
+
 	gum::debug!(
 		target: LOG_TARGET,
 		availability_cores_len = availability_cores.len(),
@@ -586,6 +655,29 @@ fn select_availability_bitfields(
 	selected.into_values().collect()
 }
 
+/// Note the candidates pending availability on `cores` which the `bitfields` make available.
+fn note_available_candidates(
+	cores: &[CoreState],
+	bitfields: &[SignedAvailabilityBitfield],
+	relay_parent: Hash,
+	candidate_tracker: &CandidateTracker,
+) {
+	if !candidate_tracker.is_enabled() {
+		return
+	}
+
+	for (core_idx, core) in cores.iter().enumerate() {
+		let CoreState::Occupied(occupied_core) = core else { continue };
+		if bitfields_indicate_availability(core_idx, bitfields, &occupied_core.availability) {
+			candidate_tracker.note(
+				occupied_core.candidate_hash,
+				occupied_core.para_id(),
+				CandidateLifecycleEvent::Available { relay_parent },
+			);
+		}
+	}
+}
+
 /// Requests backable candidates from Prospective Parachains subsystem
 /// based on core states.
 async fn request_backable_candidates(
@@ -60,7 +60,7 @@ pub fn scheduled_core(id: u32) -> ScheduledCore {
 }
 
 mod select_availability_bitfields {
-	use super::{super::*, default_bitvec, occupied_core};
+	use super::{super::*, build_occupied_core, default_bitvec, occupied_core};
 	use polkadot_primitives::{ScheduledCore, SigningContext, ValidatorId, ValidatorIndex};
 	use sp_application_crypto::AppCrypto;
 	use sp_keystore::{testing::MemoryKeystore, Keystore, KeystorePtr};
@@ -212,6 +212,43 @@ mod select_availability_bitfields {
 		assert_eq!(selected_bitfields[2].payload().0, bitvec2);
 		assert_eq!(selected_bitfields[3].payload().0, bitvec3);
 	}
+
+	#[test]
+	fn candidates_made_available_get_noted() {
+		let keystore: KeystorePtr = Arc::new(MemoryKeystore::new());
+		let available = CandidateHash(Hash::repeat_byte(1));
+		let pending = CandidateHash(Hash::repeat_byte(2));
+
+		// 21 of 32 validators have their chunk of both candidates on chain.
+		let cores: Vec<_> = [available, pending]
+			.into_iter()
+			.enumerate()
+			.map(|(para_id, candidate_hash)| {
+				build_occupied_core(para_id as u32, |core| {
+					core.candidate_hash = candidate_hash;
+					core.availability[..21].fill(true);
+				})
+			})
+			.collect();
+
+		// Another bitfield makes the first candidate available.
+		let mut bitvec = default_bitvec(2);
+		bitvec.set(0, true);
+		let bitfields = vec![signed_bitfield(&keystore, bitvec, ValidatorIndex(21))];
+
+		let tracker = CandidateTracker::new();
+		let relay_parent = Hash::repeat_byte(3);
+		note_available_candidates(&cores, &bitfields, relay_parent, &tracker);
+
+		let timelines = tracker.recent_candidates(0.into(), 10);
+		assert_eq!(timelines.len(), 1);
+		assert_eq!(timelines[0].candidate_hash, available);
+		assert_eq!(
+			timelines[0].events.iter().map(|entry| entry.event.clone()).collect::<Vec<_>>(),
+			vec![CandidateLifecycleEvent::Available { relay_parent }],
+		);
+		assert!(tracker.recent_candidates(1.into(), 10).is_empty());
+	}
 }
 
 pub(crate) mod common {
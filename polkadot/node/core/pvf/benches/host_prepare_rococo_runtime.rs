@@ -51,6 +51,7 @@ impl TestHost {
 			2,
 			1,
 			2,
+			false,
 		);
 		f(&mut config);
 		let (host, task) = start(config, Metrics::default()).await.unwrap();
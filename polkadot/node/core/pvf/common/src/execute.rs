@@ -27,6 +27,8 @@ use std::time::Duration;
 pub struct Handshake {
 	/// The executor parameters.
 	pub executor_params: ExecutorParams,
+	/// Whether the worker should collect an [`ExecutionProfile`] for every job.
+	pub profiling: bool,
 }
 
 /// A request to execute a PVF
@@ -51,6 +53,74 @@ pub struct WorkerResponse {
 	pub duration: Duration,
 	/// The uncompressed PoV size.
 	pub pov_size: u32,
+	/// The execution profile of the job. Only collected if profiling is enabled in the handshake.
+	pub profile: Option<ExecutionProfile>,
+}
+
+/// A detailed breakdown of the resources consumed by a single PVF execution.
+///
+/// Collecting a profile adds a small overhead to every host function call, so it is only done when
+/// explicitly enabled in the [`Handshake`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct ExecutionProfile {
+	/// Wall clock time spent decompressing the PoV.
+	pub decompress: Duration,
+	/// Wall clock time spent instantiating the runtime from the artifact.
+	pub instantiate: Duration,
+	/// Wall clock time spent executing `validate_block`.
+	pub execute: Duration,
+	/// The number of host function calls made by the PVF, grouped by runtime interface.
+	pub host_calls: HostCallCounts,
+	/// Peak number of bytes allocated on the wasm heap. `None` if the executor didn't report it.
+	pub heap_peak: Option<u32>,
+	/// Size of the wasm address space used by the heap, including the fragmentation. `None` if
+	/// the executor didn't report it.
+	pub heap_address_space: Option<u32>,
+	/// The number of storage keys the PVF looked up in the storage proof of the PoV. `None` if the
+	/// PVF didn't report it.
+	pub storage_proof_reads: Option<u64>,
+}
+
+/// The number of host function calls made by a PVF, grouped by the runtime interface the host
+/// function belongs to.
+///
+/// The storage proof is read from within the PVF itself, without going through any host function,
+/// so the number of proof accesses is not part of these counts. PVFs report it along with their
+/// result instead, see [`ExecutionProfile::storage_proof_reads`].
+#[derive(Debug, Default, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct HostCallCounts {
+	/// Calls into `sp_io::misc`.
+	pub misc: u64,
+	/// Calls into `sp_io::crypto`.
+	pub crypto: u64,
+	/// Calls into `sp_io::hashing`.
+	pub hashing: u64,
+	/// Calls into `sp_io::allocator`.
+	pub allocator: u64,
+	/// Calls into `sp_io::logging`.
+	pub logging: u64,
+	/// Calls into `sp_io::trie`.
+	pub trie: u64,
+}
+
+impl HostCallCounts {
+	/// Returns the counts together with the name of the runtime interface they belong to.
+	pub fn iter(&self) -> impl Iterator<Item = (&'static str, u64)> {
+		[
+			("misc", self.misc),
+			("crypto", self.crypto),
+			("hashing", self.hashing),
+			("allocator", self.allocator),
+			("logging", self.logging),
+			("trie", self.trie),
+		]
+		.into_iter()
+	}
+
+	/// The total number of host function calls.
+	pub fn total(&self) -> u64 {
+		self.iter().map(|(_, count)| count).sum()
+	}
 }
 
 /// An error occurred in the worker process.
@@ -78,8 +148,9 @@ pub enum WorkerError {
 	InternalError(#[from] InternalValidationError),
 }
 
-/// The result of a job on the execution worker.
-pub type JobResult = Result<JobResponse, JobError>;
+/// The result of a job on the execution worker, along with the execution profile if profiling is
+/// enabled.
+pub type JobResult = Result<(JobResponse, Option<ExecutionProfile>), JobError>;
 
 /// The successful response from a job on the execution worker.
 #[derive(Debug, Encode, Decode)]
@@ -16,7 +16,10 @@
 
 //! Interface to the Substrate Executor
 
-use crate::error::ExecuteError;
+use crate::{
+	error::ExecuteError,
+	execute::{ExecutionProfile, HostCallCounts},
+};
 use polkadot_primitives::{
 	executor_params::{DEFAULT_LOGICAL_STACK_MAX, DEFAULT_NATIVE_STACK_MAX},
 	ExecutorParam, ExecutorParams,
@@ -28,8 +31,13 @@ use sc_executor_common::{
 };
 use sc_executor_wasmtime::{Config, DeterministicStackLimit, Semantics, WasmtimeRuntime};
 use sp_core::storage::{ChildInfo, TrackedStorageKey};
-use sp_externalities::MultiRemovalResults;
-use std::any::{Any, TypeId};
+use sp_externalities::{ExternalitiesExt as _, MultiRemovalResults};
+use sp_wasm_interface::{FunctionContext, HostFunctionRegistry};
+use std::{
+	any::{Any, TypeId},
+	marker::PhantomData,
+	time::Instant,
+};
 
 // Memory configuration
 //
@@ -110,19 +118,85 @@ pub unsafe fn execute_artifact(
 	compiled_artifact_blob: &[u8],
 	executor_params: &ExecutorParams,
 	params: &[u8],
+) -> Result<Vec<u8>, ExecuteError> {
+	execute_artifact_with::<HostFunctions>(compiled_artifact_blob, executor_params, params, None)
+}
+
+/// Same as [`execute_artifact`], but additionally records the time spent in the instantiation and
+/// execution phases, the number of host function calls and the usage of the wasm heap into the
+/// given `profile`.
+///
+/// # Safety
+///
+/// The same requirements as for [`execute_artifact`] apply.
+pub unsafe fn execute_artifact_with_profile(
+	compiled_artifact_blob: &[u8],
+	executor_params: &ExecutorParams,
+	params: &[u8],
+	profile: &mut ExecutionProfile,
+) -> Result<Vec<u8>, ExecuteError> {
+	execute_artifact_with::<ProfiledHostFunctions>(
+		compiled_artifact_blob,
+		executor_params,
+		params,
+		Some(profile),
+	)
+}
+
+unsafe fn execute_artifact_with<H: sp_wasm_interface::HostFunctions>(
+	compiled_artifact_blob: &[u8],
+	executor_params: &ExecutorParams,
+	params: &[u8],
+	profile: Option<&mut ExecutionProfile>,
 ) -> Result<Vec<u8>, ExecuteError> {
 	let mut extensions = sp_externalities::Extensions::new();
 
 	extensions.register(sp_core::traits::ReadRuntimeVersionExt::new(ReadRuntimeVersion));
+	if profile.is_some() {
+		// The counters live in the externalities of this execution, so that concurrent
+		// executions in the same process don't mix up their counts.
+		extensions.register(HostCallsExt(HostCallCounts::default()));
+	}
 
 	let mut ext = ValidationExternalities(extensions);
 
-	match sc_executor::with_externalities_safe(&mut ext, || {
-		let runtime = create_runtime_from_artifact_bytes(compiled_artifact_blob, executor_params)?;
-		runtime.new_instance()?.call("validate_block", params)
-	}) {
-		Ok(Ok(ok)) => Ok(ok),
-		Ok(Err(err)) | Err(err) => Err(err),
+	// The phase timings are returned from the closure since it must be unwind safe.
+	let result = sc_executor::with_externalities_safe(&mut ext, || {
+		let start = Instant::now();
+		let instance =
+			create_runtime_from_artifact_bytes_with::<H>(compiled_artifact_blob, executor_params)
+				.map_err(ExecuteError::from)
+				.and_then(|runtime| runtime.new_instance());
+		let instantiate = start.elapsed();
+
+		let start = Instant::now();
+		let (result, allocation_stats) = match instance {
+			Ok(mut instance) => instance.call_with_allocation_stats("validate_block", params),
+			Err(err) => (Err(err), None),
+		};
+		(result, allocation_stats, instantiate, start.elapsed())
+	});
+
+	if let Some(profile) = profile {
+		if let Some(host_calls) = ext
+			.0
+			.get_mut(HostCallsExt::type_id())
+			.and_then(|ext| ext.downcast_mut::<HostCallsExt>())
+		{
+			profile.host_calls = std::mem::take(&mut host_calls.0);
+		}
+		if let Ok((_, allocation_stats, instantiate, execute)) = &result {
+			profile.instantiate = *instantiate;
+			profile.execute = *execute;
+			profile.heap_peak = allocation_stats.as_ref().map(|stats| stats.bytes_allocated_peak);
+			profile.heap_address_space =
+				allocation_stats.as_ref().map(|stats| stats.address_space_used);
+		}
+	}
+
+	match result {
+		Ok((result, ..)) => result,
+		Err(err) => Err(err),
 	}
 }
 
@@ -138,14 +212,21 @@ pub unsafe fn execute_artifact(
 pub unsafe fn create_runtime_from_artifact_bytes(
 	compiled_artifact_blob: &[u8],
 	executor_params: &ExecutorParams,
+) -> Result<WasmtimeRuntime, WasmError> {
+	create_runtime_from_artifact_bytes_with::<HostFunctions>(
+		compiled_artifact_blob,
+		executor_params,
+	)
+}
+
+unsafe fn create_runtime_from_artifact_bytes_with<H: sp_wasm_interface::HostFunctions>(
+	compiled_artifact_blob: &[u8],
+	executor_params: &ExecutorParams,
 ) -> Result<WasmtimeRuntime, WasmError> {
 	let mut config = DEFAULT_CONFIG.clone();
 	config.semantics = params_to_wasmtime_semantics(executor_params).0;
 
-	sc_executor_wasmtime::create_runtime_from_artifact_bytes::<HostFunctions>(
-		compiled_artifact_blob,
-		config,
-	)
+	sc_executor_wasmtime::create_runtime_from_artifact_bytes::<H>(compiled_artifact_blob, config)
 }
 
 /// Takes the default config and overwrites any settings with existing executor parameters.
@@ -210,6 +291,94 @@ type HostFunctions = (
 	sp_io::trie::HostFunctions,
 );
 
+/// The same host functions as [`HostFunctions`], but every call is counted per runtime interface.
+type ProfiledHostFunctions = (
+	Counted<sp_io::misc::HostFunctions, MISC_CALLS>,
+	Counted<sp_io::crypto::HostFunctions, CRYPTO_CALLS>,
+	Counted<sp_io::hashing::HostFunctions, HASHING_CALLS>,
+	Counted<sp_io::allocator::HostFunctions, ALLOCATOR_CALLS>,
+	Counted<sp_io::logging::HostFunctions, LOGGING_CALLS>,
+	Counted<sp_io::trie::HostFunctions, TRIE_CALLS>,
+);
+
+const MISC_CALLS: usize = 0;
+const CRYPTO_CALLS: usize = 1;
+const HASHING_CALLS: usize = 2;
+const ALLOCATOR_CALLS: usize = 3;
+const LOGGING_CALLS: usize = 4;
+const TRIE_CALLS: usize = 5;
+
+sp_externalities::decl_extension! {
+	/// The host function calls made by the execution the externalities belong to.
+	///
+	/// Only registered when the execution is profiled.
+	struct HostCallsExt(HostCallCounts);
+}
+
+impl HostCallsExt {
+	fn increment(&mut self, idx: usize) {
+		let counter = match idx {
+			MISC_CALLS => &mut self.0.misc,
+			CRYPTO_CALLS => &mut self.0.crypto,
+			HASHING_CALLS => &mut self.0.hashing,
+			ALLOCATOR_CALLS => &mut self.0.allocator,
+			LOGGING_CALLS => &mut self.0.logging,
+			_ => &mut self.0.trie,
+		};
+		*counter += 1;
+	}
+}
+
+/// Wraps a set of host functions so that every call into them increments the counter at `IDX` of
+/// the [`HostCallsExt`] of the current execution.
+struct Counted<H, const IDX: usize>(PhantomData<H>);
+
+impl<H: sp_wasm_interface::HostFunctions, const IDX: usize> sp_wasm_interface::HostFunctions
+	for Counted<H, IDX>
+{
+	fn host_functions() -> Vec<&'static dyn sp_wasm_interface::Function> {
+		H::host_functions()
+	}
+
+	fn register_static<T>(registry: &mut T) -> Result<(), T::Error>
+	where
+		T: HostFunctionRegistry,
+	{
+		H::register_static(&mut CountingRegistry::<T, IDX>(registry))
+	}
+}
+
+/// A registry proxy that counts the calls of the host functions registered through it.
+struct CountingRegistry<'a, T, const IDX: usize>(&'a mut T);
+
+impl<'a, T: HostFunctionRegistry, const IDX: usize> HostFunctionRegistry
+	for CountingRegistry<'a, T, IDX>
+{
+	type State = T::State;
+	type Error = T::Error;
+	type FunctionContext = T::FunctionContext;
+
+	fn with_function_context<R>(
+		caller: sp_wasm_interface::wasmtime::Caller<Self::State>,
+		callback: impl FnOnce(&mut dyn FunctionContext) -> R,
+	) -> R {
+		sp_externalities::with_externalities(|mut ext| {
+			if let Some(host_calls) = ext.extension::<HostCallsExt>() {
+				host_calls.increment(IDX);
+			}
+		});
+		T::with_function_context(caller, callback)
+	}
+
+	fn register_static<Params, Results>(
+		&mut self,
+		fn_name: &str,
+		func: impl sp_wasm_interface::wasmtime::IntoFunc<Self::State, Params, Results> + 'static,
+	) -> Result<(), Self::Error> {
+		self.0.register_static(fn_name, func)
+	}
+}
+
 /// The validation externalities that will panic on any storage related access. (PVFs should not
 /// have a notion of a persistent storage/trie.)
 struct ValidationExternalities(sp_externalities::Extensions);
@@ -384,6 +553,63 @@ impl sp_core::traits::ReadRuntimeVersion for ReadRuntimeVersion {
 mod tests {
 	use super::*;
 
+	#[test]
+	fn execute_artifact_with_profile_counts_host_calls() {
+		// A module that calls `sp_io::logging::max_level` three times and returns an empty result.
+		let wat = r#"(module
+			(import "env" "ext_logging_max_level_version_1" (func $max_level (result i32)))
+			(memory (export "memory") 1)
+			(global (export "__heap_base") i32 (i32.const 1024))
+			(func (export "validate_block") (param i32 i32) (result i64)
+				(drop (call $max_level))
+				(drop (call $max_level))
+				(drop (call $max_level))
+				(i64.const 0)
+			)
+		)"#;
+		let wasm = wat::parse_str(wat).expect("wat parsing failed");
+		let blob = prevalidate(&wasm).expect("valid runtime blob");
+		let executor_params = ExecutorParams::default();
+		let artifact = prepare(blob, &executor_params).expect("prepare should succeed");
+
+		let mut profile = ExecutionProfile::default();
+		// SAFETY: the artifact was just produced by `prepare` and was not modified.
+		let result = unsafe {
+			execute_artifact_with_profile(&artifact, &executor_params, &[], &mut profile)
+		};
+
+		assert_eq!(result.expect("execution should succeed"), Vec::<u8>::new());
+		assert_eq!(profile.host_calls, HostCallCounts { logging: 3, ..Default::default() });
+		assert_eq!(profile.host_calls.total(), 3);
+		assert!(profile.heap_address_space.is_some());
+
+		// Concurrent executions in the same process keep their own counts.
+		let profiles = std::thread::scope(|scope| {
+			let handles = (0..4)
+				.map(|_| {
+					scope.spawn(|| {
+						let mut profile = ExecutionProfile::default();
+						// SAFETY: see above.
+						unsafe {
+							execute_artifact_with_profile(
+								&artifact,
+								&executor_params,
+								&[],
+								&mut profile,
+							)
+						}
+						.expect("execution should succeed");
+						profile
+					})
+				})
+				.collect::<Vec<_>>();
+			handles.into_iter().map(|handle| handle.join().unwrap()).collect::<Vec<_>>()
+		});
+		for profile in profiles {
+			assert_eq!(profile.host_calls, HostCallCounts { logging: 3, ..Default::default() });
+		}
+	}
+
 	#[test]
 	fn prep_hash_matches_artifact_effect_of_executor_params() {
 		use ExecutorParam::*;
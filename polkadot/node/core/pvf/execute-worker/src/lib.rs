@@ -20,7 +20,8 @@
 #![warn(missing_docs)]
 
 pub use polkadot_node_core_pvf_common::{
-	error::ExecuteError, executor_interface::execute_artifact,
+	error::ExecuteError,
+	executor_interface::{execute_artifact, execute_artifact_with_profile},
 };
 use polkadot_parachain_primitives::primitives::ValidationParams;
 
@@ -42,7 +43,8 @@ use polkadot_node_core_pvf_common::{
 	compute_checksum,
 	error::InternalValidationError,
 	execute::{
-		ExecuteRequest, Handshake, JobError, JobResponse, JobResult, WorkerError, WorkerResponse,
+		ExecuteRequest, ExecutionProfile, Handshake, JobError, JobResponse, JobResult, WorkerError,
+		WorkerResponse,
 	},
 	executor_interface::params_to_wasmtime_semantics,
 	framed_recv_blocking, framed_send_blocking,
@@ -55,7 +57,7 @@ use polkadot_node_core_pvf_common::{
 	worker_dir, ArtifactChecksum,
 };
 use polkadot_node_primitives::{BlockData, PoV, POV_BOMB_LIMIT};
-use polkadot_parachain_primitives::primitives::ValidationResult;
+use polkadot_parachain_primitives::primitives::{ValidationResult, ValidationStats};
 use polkadot_primitives::{ExecutorParams, PersistedValidationData};
 use std::{
 	io::{self, Read},
@@ -66,7 +68,7 @@ use std::{
 	path::PathBuf,
 	process,
 	sync::{mpsc::channel, Arc},
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 /// The number of threads for the child process:
@@ -142,8 +144,8 @@ pub fn worker_entrypoint(
 		|mut stream, worker_info, security_status| {
 			let artifact_path = worker_dir::execute_artifact(&worker_info.worker_dir_path);
 
-			let Handshake { executor_params } =
-				recv_execute_handshake(&mut stream).map_err(|e| {
+			let Handshake { executor_params, profiling } = recv_execute_handshake(&mut stream)
+				.map_err(|e| {
 					map_and_send_err!(
 						e,
 						InternalValidationError::HostCommunication,
@@ -190,6 +192,7 @@ pub fn worker_entrypoint(
 							job_response: JobResponse::CorruptedArtifact,
 							duration: Duration::ZERO,
 							pov_size: 0,
+							profile: None,
 						}),
 						worker_info,
 					)?;
@@ -219,6 +222,7 @@ pub fn worker_entrypoint(
 
 				let compiled_artifact_blob = Arc::new(compiled_artifact_blob);
 
+				let decompress_start = Instant::now();
 				let raw_block_data =
 					match sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT) {
 						Ok(data) => data,
@@ -229,6 +233,7 @@ pub fn worker_entrypoint(
 									job_response: JobResponse::PoVDecompressionFailure,
 									duration: Duration::ZERO,
 									pov_size: 0,
+									profile: None,
 								}),
 								worker_info,
 							)?;
@@ -236,6 +241,7 @@ pub fn worker_entrypoint(
 						},
 					};
 
+				let decompress_duration = decompress_start.elapsed();
 				let pov_size = raw_block_data.len() as u32;
 
 				let params = ValidationParams {
@@ -258,6 +264,7 @@ pub fn worker_entrypoint(
 								&params,
 								execution_timeout,
 								execute_thread_stack_size,
+								profiling,
 								worker_info,
 								security_status.can_unshare_user_namespace_and_change_root,
 								usage_before,
@@ -274,6 +281,7 @@ pub fn worker_entrypoint(
 								&params,
 								execution_timeout,
 								execute_thread_stack_size,
+								profiling,
 								worker_info,
 								usage_before,
 								pov_size,
@@ -289,6 +297,7 @@ pub fn worker_entrypoint(
 							&params,
 							execution_timeout,
 							execute_thread_stack_size,
+							profiling,
 							worker_info,
 							usage_before,
 							pov_size,
@@ -296,6 +305,13 @@ pub fn worker_entrypoint(
 					}
 				}
 
+				let result = result.map(|mut response| {
+					if let Some(profile) = response.profile.as_mut() {
+						profile.decompress = decompress_duration;
+					}
+					response
+				});
+
 				gum::trace!(
 					target: LOG_TARGET,
 					?worker_info,
@@ -312,20 +328,31 @@ fn validate_using_artifact(
 	compiled_artifact_blob: &[u8],
 	executor_params: &ExecutorParams,
 	params: &[u8],
+	mut profile: Option<&mut ExecutionProfile>,
 ) -> JobResponse {
-	let descriptor_bytes = match unsafe {
-		// SAFETY: this should be safe since the compiled artifact passed here comes from the
-		//         file created by the prepare workers. These files are obtained by calling
-		//         [`executor_interface::prepare`].
-		execute_artifact(compiled_artifact_blob, executor_params, params)
-	} {
+	// SAFETY: this should be safe since the compiled artifact passed here comes from the
+	//         file created by the prepare workers. These files are obtained by calling
+	//         [`executor_interface::prepare`].
+	let result = unsafe {
+		match profile.as_deref_mut() {
+			Some(profile) => execute_artifact_with_profile(
+				compiled_artifact_blob,
+				executor_params,
+				params,
+				profile,
+			),
+			None => execute_artifact(compiled_artifact_blob, executor_params, params),
+		}
+	};
+	let descriptor_bytes = match result {
 		Err(ExecuteError::RuntimeConstruction(wasmerr)) =>
 			return JobResponse::runtime_construction("execute", &wasmerr.to_string()),
 		Err(err) => return JobResponse::format_invalid("execute", &err.to_string()),
 		Ok(d) => d,
 	};
 
-	let result_descriptor = match ValidationResult::decode(&mut &descriptor_bytes[..]) {
+	let mut descriptor_bytes = &descriptor_bytes[..];
+	let result_descriptor = match ValidationResult::decode(&mut descriptor_bytes) {
 		Err(err) =>
 			return JobResponse::format_invalid(
 				"validation result decoding failed",
@@ -334,6 +361,13 @@ fn validate_using_artifact(
 		Ok(r) => r,
 	};
 
+	// The statistics are optional, PVFs that don't report them only return the result.
+	if let Some(profile) = profile {
+		profile.storage_proof_reads = ValidationStats::decode(&mut descriptor_bytes)
+			.ok()
+			.map(|stats| stats.storage_proof_reads);
+	}
+
 	JobResponse::Ok { result_descriptor }
 }
 
@@ -347,6 +381,7 @@ fn handle_clone(
 	params: &Arc<Vec<u8>>,
 	execution_timeout: Duration,
 	execute_stack_size: usize,
+	profiling: bool,
 	worker_info: &WorkerInfo,
 	have_unshare_newuser: bool,
 	usage_before: Usage,
@@ -370,6 +405,7 @@ fn handle_clone(
 					Arc::clone(params),
 					execution_timeout,
 					execute_stack_size,
+					profiling,
 				)
 			}),
 		)
@@ -397,6 +433,7 @@ fn handle_fork(
 	params: &Arc<Vec<u8>>,
 	execution_timeout: Duration,
 	execute_worker_stack_size: usize,
+	profiling: bool,
 	worker_info: &WorkerInfo,
 	usage_before: Usage,
 	pov_size: u32,
@@ -413,6 +450,7 @@ fn handle_fork(
 			Arc::clone(params),
 			execution_timeout,
 			execute_worker_stack_size,
+			profiling,
 		),
 		Ok(ForkResult::Parent { child }) => handle_parent_process(
 			pipe_read_fd,
@@ -442,6 +480,7 @@ fn handle_child_process(
 	params: Arc<Vec<u8>>,
 	execution_timeout: Duration,
 	execute_thread_stack_size: usize,
+	profiling: bool,
 ) -> ! {
 	// SAFETY: this is an open and owned file descriptor at this point.
 	let mut pipe_write = unsafe { PipeFd::from_raw_fd(pipe_write_fd) };
@@ -483,7 +522,16 @@ fn handle_child_process(
 
 	let execute_thread = thread::spawn_worker_thread_with_stack_size(
 		"execute thread",
-		move || validate_using_artifact(&compiled_artifact_blob, &executor_params, &params),
+		move || {
+			let mut profile = profiling.then(ExecutionProfile::default);
+			let response = validate_using_artifact(
+				&compiled_artifact_blob,
+				&executor_params,
+				&params,
+				profile.as_mut(),
+			);
+			(response, profile)
+		},
 		Arc::clone(&condvar),
 		WaitOutcome::Finished,
 		execute_thread_stack_size,
@@ -625,7 +673,7 @@ fn handle_parent_process(
 			let result = recv_child_response(&mut reader, "execute")?;
 
 			match result {
-				Ok(job_response) => {
+				Ok((job_response, profile)) => {
 					// The exit status should have been zero if no error occurred.
 					if exit_status != 0 {
 						return Ok(Err(WorkerError::JobError(JobError::UnexpectedExitStatus(
@@ -633,7 +681,7 @@ fn handle_parent_process(
 						))));
 					}
 
-					Ok(Ok(WorkerResponse { job_response, pov_size, duration: cpu_tv }))
+					Ok(Ok(WorkerResponse { job_response, pov_size, duration: cpu_tv, profile }))
 				},
 				Err(job_error) => {
 					gum::warn!(
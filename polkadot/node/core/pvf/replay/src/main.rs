@@ -0,0 +1,133 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replays the validation of a parachain candidate locally and prints its execution profile.
+//!
+//! The candidate is prepared and executed in-process, the same way the PVF workers do it, so the
+//! numbers match the ones reported by a validator running with `--pvf-execute-profiling`.
+
+use clap::Parser;
+use codec::{Decode, Encode};
+use polkadot_node_core_pvf_common::{
+	execute::ExecutionProfile,
+	executor_interface::{execute_artifact_with_profile, prepare, prevalidate},
+};
+use polkadot_node_primitives::{PoV, POV_BOMB_LIMIT};
+use polkadot_parachain_primitives::primitives::{
+	BlockData, ValidationParams, ValidationResult, ValidationStats,
+};
+use polkadot_primitives::{ExecutorParams, PersistedValidationData, MAX_CODE_SIZE};
+use std::{path::PathBuf, time::Instant};
+
+type Error = Box<dyn std::error::Error>;
+
+#[derive(Debug, Parser)]
+#[command(about = "Replay the validation of a parachain candidate and print its execution profile")]
+struct Cli {
+	/// Path to the validation code of the parachain, optionally compressed.
+	#[arg(long)]
+	code: PathBuf,
+
+	/// Path to the SCALE-encoded `PoV` of the candidate.
+	#[arg(long)]
+	pov: PathBuf,
+
+	/// Path to the SCALE-encoded `PersistedValidationData` of the candidate.
+	#[arg(long)]
+	pvd: PathBuf,
+
+	/// Path to the SCALE-encoded `ExecutorParams` of the session. The defaults are used if not
+	/// given.
+	#[arg(long)]
+	executor_params: Option<PathBuf>,
+
+	/// Maximum size of the decompressed validation code, as returned by the
+	/// `validation_code_bomb_limit` runtime API.
+	#[arg(long, default_value_t = MAX_CODE_SIZE * 4)]
+	code_bomb_limit: u32,
+}
+
+fn decode_file<T: Decode>(path: &PathBuf) -> Result<T, Error> {
+	let bytes = std::fs::read(path)?;
+	T::decode(&mut &bytes[..])
+		.map_err(|e| format!("failed to decode {}: {e}", path.display()).into())
+}
+
+fn main() -> Result<(), Error> {
+	let cli = Cli::parse();
+
+	let code = std::fs::read(&cli.code)?;
+	let pov: PoV = decode_file(&cli.pov)?;
+	let pvd: PersistedValidationData = decode_file(&cli.pvd)?;
+	let executor_params = match &cli.executor_params {
+		Some(path) => decode_file(path)?,
+		None => ExecutorParams::default(),
+	};
+
+	let code = sp_maybe_compressed_blob::decompress(&code, cli.code_bomb_limit as usize)?;
+	let prepare_start = Instant::now();
+	let artifact = prepare(prevalidate(&code)?, &executor_params)?;
+	println!("prepared validation code in {:?}", prepare_start.elapsed());
+
+	let mut profile = ExecutionProfile::default();
+	let decompress_start = Instant::now();
+	let block_data = sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT)?;
+	profile.decompress = decompress_start.elapsed();
+
+	let params = ValidationParams {
+		parent_head: pvd.parent_head,
+		block_data: BlockData(block_data.to_vec()),
+		relay_parent_number: pvd.relay_parent_number,
+		relay_parent_storage_root: pvd.relay_parent_storage_root,
+	};
+
+	// SAFETY: the artifact was just produced by `prepare` and was never written to the disk.
+	let result = unsafe {
+		execute_artifact_with_profile(&artifact, &executor_params, &params.encode(), &mut profile)
+	};
+
+	match result {
+		Ok(output) => {
+			let mut output = &output[..];
+			match ValidationResult::decode(&mut output) {
+				Ok(result) => println!("candidate is valid, new head data: {:?}", result.head_data),
+				Err(e) => println!("candidate returned an undecodable result: {e}"),
+			}
+			profile.storage_proof_reads =
+				ValidationStats::decode(&mut output).ok().map(|stats| stats.storage_proof_reads);
+		},
+		Err(e) => println!("candidate is invalid: {e}"),
+	}
+
+	println!("decompress:  {:?}", profile.decompress);
+	println!("instantiate: {:?}", profile.instantiate);
+	println!("execute:     {:?}", profile.execute);
+	println!("host calls:  {}", profile.host_calls.total());
+	for (interface, calls) in profile.host_calls.iter() {
+		println!("  {interface}: {calls}");
+	}
+	if let Some(heap_peak) = profile.heap_peak {
+		println!("wasm heap peak: {heap_peak} bytes");
+	}
+	if let Some(address_space) = profile.heap_address_space {
+		println!("wasm heap address space: {address_space} bytes");
+	}
+	if let Some(reads) = profile.storage_proof_reads {
+		println!("storage proof reads: {reads}");
+	}
+
+	Ok(())
+}
@@ -36,7 +36,9 @@ use polkadot_node_core_pvf_common::{
 };
 use polkadot_node_primitives::PoV;
 use polkadot_node_subsystem::{messages::PvfExecKind, ActiveLeavesUpdate};
-use polkadot_primitives::{ExecutorParams, ExecutorParamsHash, Hash, PersistedValidationData};
+use polkadot_primitives::{
+	CandidateHash, ExecutorParams, ExecutorParamsHash, Hash, PersistedValidationData,
+};
 use slotmap::HopSlotMap;
 use std::{
 	collections::{HashMap, VecDeque},
@@ -73,6 +75,7 @@ pub enum FromQueue {
 #[derive(Debug)]
 pub struct PendingExecutionRequest {
 	pub exec_timeout: Duration,
+	pub candidate_hash: CandidateHash,
 	pub pvd: Arc<PersistedValidationData>,
 	pub pov: Arc<PoV>,
 	pub executor_params: ExecutorParams,
@@ -84,6 +87,7 @@ struct ExecuteJob {
 	artifact: ArtifactPathId,
 	exec_timeout: Duration,
 	exec_kind: PvfExecKind,
+	candidate_hash: CandidateHash,
 	pvd: Arc<PersistedValidationData>,
 	pov: Arc<PoV>,
 	executor_params: ExecutorParams,
@@ -169,6 +173,7 @@ struct Queue {
 	spawn_timeout: Duration,
 	node_version: Option<String>,
 	security_status: SecurityStatus,
+	profiling: bool,
 
 	/// The queue of jobs that are waiting for a worker to pick up.
 	unscheduled: Unscheduled,
@@ -188,6 +193,7 @@ impl Queue {
 		spawn_timeout: Duration,
 		node_version: Option<String>,
 		security_status: SecurityStatus,
+		profiling: bool,
 		to_queue_rx: mpsc::Receiver<ToQueue>,
 		from_queue_tx: mpsc::UnboundedSender<FromQueue>,
 	) -> Self {
@@ -198,6 +204,7 @@ impl Queue {
 			spawn_timeout,
 			node_version,
 			security_status,
+			profiling,
 			to_queue_rx,
 			from_queue_tx,
 			unscheduled: Unscheduled::new(),
@@ -375,6 +382,7 @@ fn handle_to_queue(queue: &mut Queue, to_queue: ToQueue) {
 		ToQueue::Enqueue { artifact, pending_execution_request } => {
 			let PendingExecutionRequest {
 				exec_timeout,
+				candidate_hash,
 				pvd,
 				pov,
 				executor_params,
@@ -392,6 +400,7 @@ fn handle_to_queue(queue: &mut Queue, to_queue: ToQueue) {
 				artifact,
 				exec_timeout,
 				exec_kind,
+				candidate_hash,
 				pvd,
 				pov,
 				executor_params,
@@ -443,6 +452,14 @@ async fn handle_job_finish(
 	artifact_id: ArtifactId,
 	result_tx: ResultSender,
 ) {
+	if let Ok(WorkerInterfaceResponse {
+		worker_response: WorkerResponse { profile: Some(ref profile), .. },
+		..
+	}) = worker_result
+	{
+		queue.metrics.observe_execution_profile(profile);
+	}
+
 	let (idle_worker, result, duration, sync_channel, pov_size) = match worker_result {
 		Ok(WorkerInterfaceResponse {
 			worker_response:
@@ -450,6 +467,7 @@ async fn handle_job_finish(
 					job_response: JobResponse::Ok { result_descriptor },
 					duration,
 					pov_size,
+					..
 				},
 			idle_worker,
 		}) => {
@@ -624,6 +642,7 @@ fn spawn_extra_worker(queue: &mut Queue, job: ExecuteJob) {
 			queue.spawn_timeout,
 			queue.node_version.clone(),
 			queue.security_status.clone(),
+			queue.profiling,
 		)
 		.boxed(),
 	);
@@ -644,6 +663,7 @@ async fn spawn_worker_task(
 	spawn_timeout: Duration,
 	node_version: Option<String>,
 	security_status: SecurityStatus,
+	profiling: bool,
 ) -> QueueEvent {
 	use futures_timer::Delay;
 
@@ -652,6 +672,7 @@ async fn spawn_worker_task(
 			&program_path,
 			&cache_path,
 			job.executor_params.clone(),
+			profiling,
 			spawn_timeout,
 			node_version.as_deref(),
 			security_status.clone(),
@@ -707,6 +728,7 @@ fn assign(queue: &mut Queue, worker: Worker, job: ExecuteJob) {
 				idle,
 				job.artifact.clone(),
 				job.exec_timeout,
+				job.candidate_hash,
 				job.pvd,
 				job.pov,
 			)
@@ -725,6 +747,7 @@ pub fn start(
 	spawn_timeout: Duration,
 	node_version: Option<String>,
 	security_status: SecurityStatus,
+	profiling: bool,
 ) -> (mpsc::Sender<ToQueue>, mpsc::UnboundedReceiver<FromQueue>, impl Future<Output = ()>) {
 	let (to_queue_tx, to_queue_rx) = mpsc::channel(20);
 	let (from_queue_tx, from_queue_rx) = mpsc::unbounded();
@@ -737,6 +760,7 @@ pub fn start(
 		spawn_timeout,
 		node_version,
 		security_status,
+		profiling,
 		to_queue_rx,
 		from_queue_tx,
 	)
@@ -933,6 +957,7 @@ mod tests {
 			},
 			exec_timeout: Duration::from_secs(10),
 			exec_kind: PvfExecKind::Approval,
+			candidate_hash: CandidateHash::default(),
 			pvd,
 			pov,
 			executor_params: ExecutorParams::default(),
@@ -1084,6 +1109,7 @@ mod tests {
 			Duration::from_secs(1),
 			None,
 			SecurityStatus::default(),
+			false,
 			to_queue_rx,
 			from_queue_tx,
 		);
@@ -1101,6 +1127,7 @@ mod tests {
 			},
 			exec_timeout: Duration::from_secs(1),
 			exec_kind: PvfExecKind::Backing(relevant_relay_parent),
+			candidate_hash: CandidateHash::default(),
 			pvd: Arc::new(PersistedValidationData::default()),
 			pov: Arc::new(PoV { block_data: BlockData(Vec::new()) }),
 			executor_params: ExecutorParams::default(),
@@ -1118,6 +1145,7 @@ mod tests {
 				},
 				exec_timeout: Duration::from_secs(1),
 				exec_kind: PvfExecKind::Backing(old_relay_parent),
+				candidate_hash: CandidateHash::default(),
 				pvd: Arc::new(PersistedValidationData::default()),
 				pov: Arc::new(PoV { block_data: BlockData(Vec::new()) }),
 				executor_params: ExecutorParams::default(),
@@ -33,7 +33,7 @@ use polkadot_node_core_pvf_common::{
 	worker_dir, ArtifactChecksum, SecurityStatus,
 };
 use polkadot_node_primitives::PoV;
-use polkadot_primitives::{ExecutorParams, PersistedValidationData};
+use polkadot_primitives::{CandidateHash, ExecutorParams, PersistedValidationData};
 use std::{path::Path, sync::Arc, time::Duration};
 use tokio::{io, net::UnixStream};
 
@@ -44,6 +44,7 @@ pub async fn spawn(
 	program_path: &Path,
 	cache_path: &Path,
 	executor_params: ExecutorParams,
+	profiling: bool,
 	spawn_timeout: Duration,
 	node_version: Option<&str>,
 	security_status: SecurityStatus,
@@ -62,7 +63,7 @@ pub async fn spawn(
 		security_status,
 	)
 	.await?;
-	send_execute_handshake(&mut idle_worker.stream, Handshake { executor_params })
+	send_execute_handshake(&mut idle_worker.stream, Handshake { executor_params, profiling })
 		.await
 		.map_err(|error| {
 			let err = SpawnErr::Handshake { err: error.to_string() };
@@ -124,6 +125,7 @@ pub async fn start_work(
 	worker: IdleWorker,
 	artifact: ArtifactPathId,
 	execution_timeout: Duration,
+	candidate_hash: CandidateHash,
 	pvd: Arc<PersistedValidationData>,
 	pov: Arc<PoV>,
 ) -> Result<Response, Error> {
@@ -192,6 +194,17 @@ pub async fn start_work(
 			},
 		};
 
+		if let Ok(WorkerResponse { profile: Some(ref profile), .. }) = worker_result {
+			gum::debug!(
+				target: LOG_TARGET,
+				worker_pid = %pid,
+				validation_code_hash = ?artifact.id.code_hash,
+				?candidate_hash,
+				?profile,
+				"execute job profile",
+			);
+		}
+
 		match worker_result {
 			Ok(worker_response) => Ok(Response {
 				worker_response,
@@ -43,7 +43,7 @@ use polkadot_node_subsystem::{
 	messages::PvfExecKind, ActiveLeavesUpdate, SubsystemError, SubsystemResult,
 };
 use polkadot_parachain_primitives::primitives::ValidationResult;
-use polkadot_primitives::{Hash, PersistedValidationData};
+use polkadot_primitives::{CandidateHash, Hash, PersistedValidationData};
 use std::{
 	collections::HashMap,
 	path::PathBuf,
@@ -107,6 +107,9 @@ impl ValidationHost {
 	/// Execute PVF with the given code, execution timeout, parameters and priority.
 	/// The result of execution will be sent to the provided result sender.
 	///
+	/// The `candidate_hash` is only used to correlate the logs of the execution with other
+	/// subsystems.
+	///
 	/// This is async to accommodate the possibility of back-pressure. In the vast majority of
 	/// situations this function should return immediately.
 	///
@@ -115,6 +118,7 @@ impl ValidationHost {
 		&mut self,
 		pvf: PvfPrepData,
 		exec_timeout: Duration,
+		candidate_hash: CandidateHash,
 		pvd: Arc<PersistedValidationData>,
 		pov: Arc<PoV>,
 		priority: Priority,
@@ -125,6 +129,7 @@ impl ValidationHost {
 			.send(ToHost::ExecutePvf(ExecutePvfInputs {
 				pvf,
 				exec_timeout,
+				candidate_hash,
 				pvd,
 				pov,
 				priority,
@@ -201,6 +206,7 @@ enum ToHost {
 struct ExecutePvfInputs {
 	pvf: PvfPrepData,
 	exec_timeout: Duration,
+	candidate_hash: CandidateHash,
 	pvd: Arc<PersistedValidationData>,
 	pov: Arc<PoV>,
 	priority: Priority,
@@ -234,6 +240,8 @@ pub struct Config {
 	pub execute_worker_spawn_timeout: Duration,
 	/// The maximum number of execute workers that can run at the same time.
 	pub execute_workers_max_num: usize,
+	/// Whether the execute workers should collect a detailed profile of every execution.
+	pub execute_worker_profiling: bool,
 }
 
 impl Config {
@@ -247,6 +255,7 @@ impl Config {
 		execute_workers_max_num: usize,
 		prepare_workers_soft_max_num: usize,
 		prepare_workers_hard_max_num: usize,
+		execute_worker_profiling: bool,
 	) -> Self {
 		Self {
 			cache_path,
@@ -261,6 +270,7 @@ impl Config {
 			execute_worker_program_path,
 			execute_worker_spawn_timeout: Duration::from_secs(3),
 			execute_workers_max_num,
+			execute_worker_profiling,
 		}
 	}
 }
@@ -341,6 +351,7 @@ pub async fn start(
 		config.execute_worker_spawn_timeout,
 		config.node_version,
 		security_status,
+		config.execute_worker_profiling,
 	);
 
 	let (to_sweeper_tx, to_sweeper_rx) = mpsc::channel(100);
@@ -601,7 +612,16 @@ async fn handle_execute_pvf(
 	awaiting_prepare: &mut AwaitingPrepare,
 	inputs: ExecutePvfInputs,
 ) -> Result<(), Fatal> {
-	let ExecutePvfInputs { pvf, exec_timeout, pvd, pov, priority, exec_kind, result_tx } = inputs;
+	let ExecutePvfInputs {
+		pvf,
+		exec_timeout,
+		candidate_hash,
+		pvd,
+		pov,
+		priority,
+		exec_kind,
+		result_tx,
+	} = inputs;
 	let artifact_id = ArtifactId::from_pvf_prep_data(&pvf);
 	let executor_params = (*pvf.executor_params()).clone();
 
@@ -620,6 +640,7 @@ async fn handle_execute_pvf(
 							artifact: ArtifactPathId::new(artifact_id, path, *checksum),
 							pending_execution_request: PendingExecutionRequest {
 								exec_timeout,
+								candidate_hash,
 								pvd,
 								pov,
 								executor_params,
@@ -651,6 +672,7 @@ async fn handle_execute_pvf(
 						artifact_id,
 						PendingExecutionRequest {
 							exec_timeout,
+							candidate_hash,
 							pvd,
 							pov,
 							executor_params,
@@ -666,6 +688,7 @@ async fn handle_execute_pvf(
 					artifact_id,
 					PendingExecutionRequest {
 						exec_timeout,
+						candidate_hash,
 						pvd,
 						pov,
 						executor_params,
@@ -700,6 +723,7 @@ async fn handle_execute_pvf(
 						artifact_id,
 						PendingExecutionRequest {
 							exec_timeout,
+							candidate_hash,
 							pvd,
 							pov,
 							executor_params,
@@ -725,6 +749,7 @@ async fn handle_execute_pvf(
 			artifact_id,
 			PendingExecutionRequest {
 				exec_timeout,
+				candidate_hash,
 				pvd,
 				pov,
 				executor_params,
@@ -852,8 +877,15 @@ async fn handle_prepare_done(
 	// It's finally time to dispatch all the execution requests that were waiting for this artifact
 	// to be prepared.
 	let pending_requests = awaiting_prepare.take(&artifact_id);
-	for PendingExecutionRequest { exec_timeout, pvd, pov, executor_params, result_tx, exec_kind } in
-		pending_requests
+	for PendingExecutionRequest {
+		exec_timeout,
+		candidate_hash,
+		pvd,
+		pov,
+		executor_params,
+		result_tx,
+		exec_kind,
+	} in pending_requests
 	{
 		if result_tx.is_canceled() {
 			// Preparation could've taken quite a bit of time and the requester may be not
@@ -875,6 +907,7 @@ async fn handle_prepare_done(
 				artifact: ArtifactPathId::new(artifact_id.clone(), &path, checksum),
 				pending_execution_request: PendingExecutionRequest {
 					exec_timeout,
+					candidate_hash,
 					pvd,
 					pov,
 					executor_params,
@@ -1325,6 +1358,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov1.clone(),
 			Priority::Normal,
@@ -1338,6 +1372,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov1,
 			Priority::Critical,
@@ -1351,6 +1386,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(2),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd,
 			pov2,
 			Priority::Normal,
@@ -1501,6 +1537,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov.clone(),
 			Priority::Critical,
@@ -1550,6 +1587,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(2),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd,
 			pov,
 			Priority::Critical,
@@ -1661,6 +1699,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov.clone(),
 			Priority::Critical,
@@ -1693,6 +1732,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov.clone(),
 			Priority::Critical,
@@ -1717,6 +1757,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov.clone(),
 			Priority::Critical,
@@ -1776,6 +1817,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov.clone(),
 			Priority::Critical,
@@ -1808,6 +1850,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov.clone(),
 			Priority::Critical,
@@ -1832,6 +1875,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd.clone(),
 			pov.clone(),
 			Priority::Critical,
@@ -1907,6 +1951,7 @@ pub(crate) mod tests {
 		host.execute_pvf(
 			PvfPrepData::from_discriminator(1),
 			TEST_EXECUTION_TIMEOUT,
+			CandidateHash::default(),
 			pvd,
 			pov,
 			Priority::Normal,
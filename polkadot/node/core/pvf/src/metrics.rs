@@ -16,7 +16,7 @@
 
 //! Prometheus metrics related to the validation host.
 
-use polkadot_node_core_pvf_common::prepare::MemoryStats;
+use polkadot_node_core_pvf_common::{execute::ExecutionProfile, prepare::MemoryStats};
 use polkadot_node_metrics::metrics::{self, prometheus};
 use polkadot_node_subsystem::messages::PvfExecKind;
 
@@ -122,6 +122,34 @@ impl Metrics {
 		}
 	}
 
+	/// Observe the profile of a single PVF execution.
+	pub(crate) fn observe_execution_profile(&self, profile: &ExecutionProfile) {
+		if let Some(metrics) = &self.0 {
+			for (phase, duration) in [
+				("decompress", profile.decompress),
+				("instantiate", profile.instantiate),
+				("execute", profile.execute),
+			] {
+				metrics
+					.execution_phase_time
+					.with_label_values(&[phase])
+					.observe(duration.as_secs_f64());
+			}
+			for (interface, calls) in profile.host_calls.iter() {
+				metrics
+					.execution_host_calls
+					.with_label_values(&[interface])
+					.observe(calls as f64);
+			}
+			if let Some(heap_peak) = profile.heap_peak {
+				metrics.execution_heap_peak.observe(heap_peak as f64);
+			}
+			if let Some(reads) = profile.storage_proof_reads {
+				metrics.execution_storage_proof_reads.observe(reads as f64);
+			}
+		}
+	}
+
 	/// When preparation pipeline concluded working on an item.
 	pub(crate) fn on_execute_kind(&self, kind: PvfExecKind) {
 		if let Some(metrics) = &self.0 {
@@ -142,6 +170,11 @@ struct MetricsInner {
 	preparation_time: prometheus::Histogram,
 	execution_time: prometheus::Histogram,
 	execution_queued_time: prometheus::Histogram,
+	// Only observed if execution profiling is enabled
+	execution_phase_time: prometheus::HistogramVec,
+	execution_host_calls: prometheus::HistogramVec,
+	execution_heap_peak: prometheus::Histogram,
+	execution_storage_proof_reads: prometheus::Histogram,
 	#[cfg(target_os = "linux")]
 	preparation_max_rss: prometheus::Histogram,
 	// Max. allocated memory, tracked by Jemallocator, polling-based
@@ -298,6 +331,68 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			execution_phase_time: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_phase_time",
+						"Time spent in the individual phases of executing PVFs, only observed if profiling is enabled",
+					).buckets(vec![
+						0.001,
+						0.0025,
+						0.005,
+						0.01,
+						0.025,
+						0.05,
+						0.1,
+						0.25,
+						0.5,
+						1.0,
+						2.0,
+						4.0,
+						8.0,
+						12.0,
+					]),
+					&["phase"],
+				)?,
+				registry,
+			)?,
+			execution_host_calls: prometheus::register(
+				prometheus::HistogramVec::new(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_host_calls",
+						"The number of host function calls made by a PVF execution, only observed if profiling is enabled",
+					).buckets(
+						prometheus::exponential_buckets(1.0, 4.0, 12)
+							.expect("arguments are always valid; qed"),
+					),
+					&["interface"],
+				)?,
+				registry,
+			)?,
+			execution_heap_peak: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_heap_peak",
+						"Peak number of bytes allocated on the wasm heap by a PVF execution, only observed if profiling is enabled",
+					).buckets(
+						prometheus::exponential_buckets(65536.0, 2.0, 12)
+							.expect("arguments are always valid; qed"),
+					),
+				)?,
+				registry,
+			)?,
+			execution_storage_proof_reads: prometheus::register(
+				prometheus::Histogram::with_opts(
+					prometheus::HistogramOpts::new(
+						"polkadot_pvf_execution_storage_proof_reads",
+						"The number of storage keys looked up in the storage proof by a PVF execution, only observed if profiling is enabled and the PVF reports it",
+					).buckets(
+						prometheus::exponential_buckets(1.0, 4.0, 12)
+							.expect("arguments are always valid; qed"),
+					),
+				)?,
+				registry,
+			)?,
 			#[cfg(target_os = "linux")]
 			preparation_max_rss: prometheus::register(
 				prometheus::Histogram::with_opts(
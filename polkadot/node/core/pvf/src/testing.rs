@@ -23,7 +23,7 @@ pub use crate::{
 
 use crate::{artifacts::ArtifactId, get_worker_version};
 use is_executable::IsExecutable;
-use polkadot_node_core_pvf_common::pvf::PvfPrepData;
+use polkadot_node_core_pvf_common::{execute::ExecutionProfile, pvf::PvfPrepData};
 use polkadot_node_primitives::NODE_VERSION;
 use polkadot_primitives::ExecutorParams;
 use std::{
@@ -37,8 +37,19 @@ pub fn validate_candidate(
 	code: &[u8],
 	params: &[u8],
 ) -> Result<Vec<u8>, Box<dyn std::error::Error>> {
+	validate_candidate_with_profile(code, params).map(|(result, _)| result)
+}
+
+/// Same as [`validate_candidate`], but also returns the [`ExecutionProfile`] of the execution.
+///
+/// Useful for replaying a candidate locally to find out where its validation time goes. Since
+/// `params` are passed already decoded, the decompression phase is not measured.
+pub fn validate_candidate_with_profile(
+	code: &[u8],
+	params: &[u8],
+) -> Result<(Vec<u8>, ExecutionProfile), Box<dyn std::error::Error>> {
 	use polkadot_node_core_pvf_common::executor_interface::{prepare, prevalidate};
-	use polkadot_node_core_pvf_execute_worker::execute_artifact;
+	use polkadot_node_core_pvf_execute_worker::execute_artifact_with_profile;
 
 	let code = sp_maybe_compressed_blob::decompress(code, 10 * 1024 * 1024)
 		.expect("Decompressing code failed");
@@ -47,13 +58,19 @@ pub fn validate_candidate(
 	let executor_params = ExecutorParams::default();
 	let compiled_artifact_blob = prepare(blob, &executor_params)?;
 
+	let mut profile = ExecutionProfile::default();
 	let result = unsafe {
 		// SAFETY: This is trivially safe since the artifact is obtained by calling `prepare`
 		//         and is written into a temporary directory in an unmodified state.
-		execute_artifact(&compiled_artifact_blob, &executor_params, params)?
+		execute_artifact_with_profile(
+			&compiled_artifact_blob,
+			&executor_params,
+			params,
+			&mut profile,
+		)?
 	};
 
-	Ok(result)
+	Ok((result, profile))
 }
 
 /// Retrieves the worker paths and builds workers as needed.
@@ -18,6 +18,8 @@
 
 use super::TestHost;
 use codec::{Decode, Encode};
+use polkadot_node_core_pvf::Metrics;
+use polkadot_node_metrics::metrics::{prometheus::Registry, Metrics as _};
 use polkadot_node_primitives::PoV;
 use polkadot_parachain_primitives::primitives::{
 	BlockData as GenericBlockData, HeadData as GenericHeadData,
@@ -58,6 +60,52 @@ async fn execute_good_block_on_parent() {
 	assert_eq!(new_head.post_state, hash_state(512));
 }
 
+#[tokio::test]
+async fn execute_with_profiling_observes_profile() {
+	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+	let block_data = BlockData { state: 0, add: 512 };
+	let pvd = PersistedValidationData {
+		parent_head: GenericHeadData(parent_head.encode()),
+		relay_parent_number: 1u32,
+		relay_parent_storage_root: H256::default(),
+		max_pov_size: 4096 * 1024,
+	};
+	let pov = PoV { block_data: GenericBlockData(block_data.encode()) };
+
+	let registry = Registry::new();
+	let metrics = Metrics::try_register(&registry).unwrap();
+	let host = TestHost::new_with_config_and_metrics(
+		|config| config.execute_worker_profiling = true,
+		metrics,
+	)
+	.await;
+
+	host.validate_candidate(
+		test_parachain_adder::wasm_binary_unwrap(),
+		pvd,
+		pov,
+		Default::default(),
+		H256::default(),
+	)
+	.await
+	.unwrap();
+
+	let sample_count = |name: &str| -> u64 {
+		registry
+			.gather()
+			.into_iter()
+			.filter(|family| family.get_name() == name)
+			.flat_map(|family| family.get_metric().to_vec())
+			.map(|metric| metric.get_histogram().get_sample_count())
+			.sum()
+	};
+	// One sample per execution phase.
+	assert_eq!(sample_count("polkadot_pvf_execution_phase_time"), 3);
+	// One sample per runtime interface.
+	assert_eq!(sample_count("polkadot_pvf_execution_host_calls"), 6);
+	assert_eq!(sample_count("polkadot_pvf_execution_heap_peak"), 1);
+}
+
 #[tokio::test]
 async fn execute_good_chain_on_parent() {
 	let mut parent_hash = [0; 32];
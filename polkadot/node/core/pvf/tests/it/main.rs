@@ -29,7 +29,8 @@ use polkadot_node_primitives::{PoV, POV_BOMB_LIMIT};
 use polkadot_node_subsystem::messages::PvfExecKind;
 use polkadot_parachain_primitives::primitives::{BlockData, ValidationResult};
 use polkadot_primitives::{
-	ExecutorParam, ExecutorParams, Hash, PersistedValidationData, PvfExecKind as RuntimePvfExecKind,
+	CandidateHash, ExecutorParam, ExecutorParams, Hash, PersistedValidationData,
+	PvfExecKind as RuntimePvfExecKind,
 };
 use sp_core::H256;
 
@@ -58,6 +59,13 @@ impl TestHost {
 	}
 
 	async fn new_with_config<F>(f: F) -> Self
+	where
+		F: FnOnce(&mut Config),
+	{
+		Self::new_with_config_and_metrics(f, Metrics::default()).await
+	}
+
+	async fn new_with_config_and_metrics<F>(f: F, metrics: Metrics) -> Self
 	where
 		F: FnOnce(&mut Config),
 	{
@@ -73,9 +81,10 @@ impl TestHost {
 			2,
 			1,
 			2,
+			false,
 		);
 		f(&mut config);
-		let (host, task) = start(config, Metrics::default()).await.unwrap();
+		let (host, task) = start(config, metrics).await.unwrap();
 		let _ = tokio::task::spawn(task);
 		Self { cache_dir, host: Mutex::new(host) }
 	}
@@ -127,6 +136,7 @@ impl TestHost {
 					VALIDATION_CODE_BOMB_LIMIT,
 				),
 				TEST_EXECUTION_TIMEOUT,
+				CandidateHash::default(),
 				Arc::new(pvd),
 				Arc::new(pov),
 				polkadot_node_core_pvf::Priority::Normal,
@@ -58,6 +58,13 @@ pub enum Error {
 	#[error("Failed to get node features from the runtime")]
 	FailedNodeFeatures(#[source] RuntimeApiError),
 
+	#[error("Failed to get candidate events from the runtime")]
+	FailedCandidateEvents(#[source] RuntimeApiError),
+
+	// The runtime API subsystem will drop the sender on shutdown.
+	#[error("Response channel to obtain candidate events failed")]
+	CandidateEventsResponseChannel(#[source] oneshot::Canceled),
+
 	// av-store will drop the sender on any error that happens.
 	#[error("Response channel to obtain chunk failed")]
 	QueryChunkResponseChannel(#[source] oneshot::Canceled),
@@ -66,6 +73,18 @@ pub enum Error {
 	#[error("Response channel to obtain available data failed")]
 	QueryAvailableDataResponseChannel(#[source] oneshot::Canceled),
 
+	// availability-recovery will drop the sender on shutdown.
+	#[error("Response channel to recover available data failed")]
+	RecoverAvailableDataResponseChannel(#[source] oneshot::Canceled),
+
+	// av-store will drop the sender on any error that happens.
+	#[error("Response channel to store available data failed")]
+	StoreAvailableDataResponseChannel(#[source] oneshot::Canceled),
+
+	// av-store will drop the sender on any error that happens.
+	#[error("Response channel to store chunk failed")]
+	StoreChunkResponseChannel(#[source] oneshot::Canceled),
+
 	// We tried accessing a session that was not cached.
 	#[error("Session {missing_session} is not cached, cached sessions: {available_sessions:?}.")]
 	NoSuchCachedSession { available_sessions: Vec<SessionIndex>, missing_session: SessionIndex },
@@ -111,7 +130,12 @@ pub fn log_error(
 				JfyiError::NoSuchCachedSession { .. } |
 				JfyiError::QueryAvailableDataResponseChannel(_) |
 				JfyiError::QueryChunkResponseChannel(_) |
+				JfyiError::RecoverAvailableDataResponseChannel(_) |
+				JfyiError::StoreAvailableDataResponseChannel(_) |
+				JfyiError::StoreChunkResponseChannel(_) |
 				JfyiError::FailedNodeFeatures(_) |
+				JfyiError::FailedCandidateEvents(_) |
+				JfyiError::CandidateEventsResponseChannel(_) |
 				JfyiError::ErasureCoding(_) => gum::warn!(target: LOG_TARGET, error = %jfyi, ctx),
 				JfyiError::FetchPoV(_) |
 				JfyiError::SendResponse |
@@ -15,6 +15,7 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use futures::{future::Either, FutureExt, StreamExt, TryFutureExt};
+use futures_timer::Delay;
 
 use sp_keystore::KeystorePtr;
 
@@ -39,9 +40,13 @@ use requester::Requester;
 /// Handing requests for PoVs during backing.
 mod pov_requester;
 
-/// Responding to erasure chunk requests:
+/// Re-seeding chunks of included candidates which went missing.
+mod repairer;
+use repairer::{Repairer, REPAIR_INTERVAL};
+
+/// Responding to erasure chunk requests and storing pushed chunks:
 mod responder;
-use responder::{run_chunk_receivers, run_pov_receiver};
+use responder::{run_chunk_push_receiver, run_chunk_receivers, run_pov_receiver};
 
 mod metrics;
 /// Prometheus `Metrics` for availability distribution.
@@ -72,6 +77,8 @@ pub struct IncomingRequestReceivers {
 	pub chunk_req_v1_receiver: IncomingRequestReceiver<v1::ChunkFetchingRequest>,
 	/// Receiver for incoming v2 availability chunk requests.
 	pub chunk_req_v2_receiver: IncomingRequestReceiver<v2::ChunkFetchingRequest>,
+	/// Receiver for incoming chunks pushed to us by the repair of other validators.
+	pub chunk_push_receiver: IncomingRequestReceiver<v1::ChunkPushingRequest>,
 }
 
 #[overseer::subsystem(AvailabilityDistribution, error=SubsystemError, prefix=self::overseer)]
@@ -107,8 +114,11 @@ impl AvailabilityDistributionSubsystem {
 			pov_req_receiver,
 			chunk_req_v1_receiver,
 			chunk_req_v2_receiver,
+			chunk_push_receiver,
 		} = recvs;
 		let mut requester = Requester::new(req_protocol_names, metrics.clone()).fuse();
+		let mut repairer = Repairer::new(metrics.clone());
+		let mut repair_timer = Delay::new(REPAIR_INTERVAL).fuse();
 		let mut warn_freq = gum::Freq::new();
 
 		{
@@ -122,7 +132,7 @@ impl AvailabilityDistributionSubsystem {
 			ctx.spawn(
 				"chunk-receiver",
 				run_chunk_receivers(
-					sender,
+					sender.clone(),
 					chunk_req_v1_receiver,
 					chunk_req_v2_receiver,
 					metrics.clone(),
@@ -130,6 +140,18 @@ impl AvailabilityDistributionSubsystem {
 				.boxed(),
 			)
 			.map_err(FatalError::SpawnTask)?;
+
+			ctx.spawn(
+				"chunk-push-receiver",
+				run_chunk_push_receiver(
+					sender,
+					chunk_push_receiver,
+					repairer.finalized_candidates(),
+					metrics.clone(),
+				)
+				.boxed(),
+			)
+			.map_err(FatalError::SpawnTask)?;
 		}
 
 		loop {
@@ -137,7 +159,8 @@ impl AvailabilityDistributionSubsystem {
 				let mut subsystem_next = ctx.recv().fuse();
 				futures::select! {
 					subsystem_msg = subsystem_next => Either::Left(subsystem_msg),
-					from_task = requester.next() => Either::Right(from_task),
+					from_task = requester.next() => Either::Right(Some(from_task)),
+					_ = repair_timer => Either::Right(None),
 				}
 			};
 
@@ -145,11 +168,20 @@ impl AvailabilityDistributionSubsystem {
 			let message = match action {
 				Either::Left(subsystem_msg) =>
 					subsystem_msg.map_err(|e| FatalError::IncomingMessageChannel(e))?,
-				Either::Right(from_task) => {
+				Either::Right(Some(from_task)) => {
 					let from_task = from_task.ok_or(FatalError::RequesterExhausted)?;
 					ctx.send_message(from_task).await;
 					continue
 				},
+				Either::Right(None) => {
+					repair_timer = Delay::new(REPAIR_INTERVAL).fuse();
+					log_error(
+						repairer.repair_sampled_candidate(&mut ctx).await,
+						"Error in Repairer::repair_sampled_candidate",
+						&mut warn_freq,
+					)?;
+					continue
+				},
 			};
 			match message {
 				FromOrchestra::Signal(OverseerSignal::ActiveLeaves(update)) => {
@@ -162,7 +194,12 @@ impl AvailabilityDistributionSubsystem {
 						&mut warn_freq,
 					)?;
 				},
-				FromOrchestra::Signal(OverseerSignal::BlockFinalized(_hash, _finalized_number)) => {
+				FromOrchestra::Signal(OverseerSignal::BlockFinalized(hash, number)) => {
+					log_error(
+						repairer.note_finalized_block(&mut ctx, &mut runtime, hash, number).await,
+						"Error in Repairer::note_finalized_block",
+						&mut warn_freq,
+					)?;
 				},
 				FromOrchestra::Signal(OverseerSignal::Conclude) => return Ok(()),
 				FromOrchestra::Communication {
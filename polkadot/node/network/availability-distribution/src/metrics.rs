@@ -56,6 +56,18 @@ struct MetricsInner {
 	/// Number of times our first set of validators did not provide the needed chunk and we had to
 	/// query further validators.
 	retries: Counter<U64>,
+
+	/// Number of chunks of recently included candidates probed at their holders.
+	probed_chunks: CounterVec<U64>,
+
+	/// Number of attempts to repair our own missing chunk of an included candidate.
+	repairs: CounterVec<U64>,
+
+	/// Number of recovered chunks pushed to holders missing them.
+	pushed_chunks: CounterVec<U64>,
+
+	/// Number of chunks pushed to us by other validators.
+	received_pushed_chunks: CounterVec<U64>,
 }
 
 impl Metrics {
@@ -98,6 +110,34 @@ impl Metrics {
 			metrics.retries.inc()
 		}
 	}
+
+	/// Increment counter on probed chunks.
+	pub fn on_probed_chunk(&self, label: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.probed_chunks.with_label_values(&[label]).inc()
+		}
+	}
+
+	/// Increment counter on repairs of our own chunk.
+	pub fn on_repair(&self, label: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.repairs.with_label_values(&[label]).inc()
+		}
+	}
+
+	/// Increment counter on chunks pushed to their holders.
+	pub fn on_pushed_chunk(&self, label: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.pushed_chunks.with_label_values(&[label]).inc()
+		}
+	}
+
+	/// Increment counter on chunks pushed to us.
+	pub fn on_received_pushed_chunk(&self, label: &'static str) {
+		if let Some(metrics) = &self.0 {
+			metrics.received_pushed_chunks.with_label_values(&[label]).inc()
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -150,6 +190,46 @@ impl metrics::Metrics for Metrics {
 				)?,
 				registry,
 			)?,
+			probed_chunks: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_availability_repair_probed_chunks_total",
+						"Total number of chunks of included candidates probed at their holders.",
+					),
+					&["success"]
+				)?,
+				registry,
+			)?,
+			repairs: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_availability_repairs_total",
+						"Total number of attempts to restore our missing chunk of an included candidate.",
+					),
+					&["success"]
+				)?,
+				registry,
+			)?,
+			pushed_chunks: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_availability_repair_pushed_chunks_total",
+						"Total number of recovered chunks pushed to holders missing them.",
+					),
+					&["success"]
+				)?,
+				registry,
+			)?,
+			received_pushed_chunks: prometheus::register(
+				CounterVec::new(
+					Opts::new(
+						"polkadot_parachain_availability_received_pushed_chunks_total",
+						"Total number of chunks pushed to us by other validators.",
+					),
+					&["success"]
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
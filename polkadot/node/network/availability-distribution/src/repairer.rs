@@ -0,0 +1,1003 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Repairer takes care of re-seeding chunks of included candidates which went missing.
+//!
+//! Validators might lose their av-store data, e.g. due to database corruption. If enough of them
+//! do, candidates might become unrecoverable long before they are pruned. To counter this, we
+//! record the candidates included in finalized blocks, together with the session data needed for
+//! checking their chunks, at the time the blocks get finalized. Their state might be pruned later
+//! on, so we never query the runtime of old blocks.
+//!
+//! Periodically, we sample a recorded candidate, probe a sample of its chunk holders via the chunk
+//! fetching protocol and check whether we still have our own chunk. If our chunk or the chunk of
+//! any probed holder is missing, we recover the available data via availability recovery. We store
+//! it again, which re-creates our own chunk, and push the missing chunks of the probed holders to
+//! them via the chunk pushing protocol. The record is also used for checking the chunks pushed to
+//! us.
+
+use std::{
+	collections::{HashMap, VecDeque},
+	sync::Arc,
+	time::Duration,
+};
+
+use futures::{channel::oneshot, stream::FuturesUnordered, FutureExt, StreamExt};
+use parking_lot::Mutex;
+use rand::{seq::IteratorRandom, thread_rng};
+
+use polkadot_erasure_coding::{branch_hash, branches, obtain_chunks_v1, recovery_threshold};
+use polkadot_node_network_protocol::request_response::{
+	outgoing::{OutgoingResult, Requests},
+	v1, v2, OutgoingRequest, Recipient,
+};
+use polkadot_node_primitives::{AvailableData, ErasureChunk};
+use polkadot_node_subsystem::{
+	messages::{
+		AvailabilityRecoveryMessage, AvailabilityStoreMessage, ChainApiMessage, IfDisconnected,
+		NetworkBridgeTxMessage,
+	},
+	overseer,
+};
+use polkadot_node_subsystem_util::{
+	availability_chunks::availability_chunk_index, request_candidate_events, runtime::RuntimeInfo,
+};
+use polkadot_primitives::{
+	AuthorityDiscoveryId, BlakeTwo256, BlockNumber, CandidateEvent, CandidateHash,
+	CandidateReceiptV2 as CandidateReceipt, ChunkIndex, CoreIndex, GroupIndex, Hash, HashT,
+	NodeFeatures, SessionIndex, ValidatorIndex,
+};
+
+use crate::{
+	error::{Error, FatalError, Result},
+	metrics::{FAILED, NOT_FOUND, SUCCEEDED},
+	Metrics, LOG_TARGET,
+};
+
+/// How often we sample a recently finalized candidate for repair.
+pub const REPAIR_INTERVAL: Duration = Duration::from_secs(60);
+
+/// How many of the most recently finalized blocks we keep the included candidates of.
+///
+/// This covers about four hours of 6 second blocks, which is well below the time chunks of
+/// finalized candidates are kept in the av-store.
+const FINALIZED_BLOCKS: usize = 2400;
+
+/// How many chunk holders, other than ourselves, we probe for each sampled candidate.
+const PROBED_HOLDERS: usize = 10;
+
+/// A candidate included in a finalized block.
+#[derive(Clone, Debug)]
+pub struct IncludedCandidate {
+	/// The receipt of the candidate.
+	pub receipt: CandidateReceipt,
+	/// The session the candidate got backed in.
+	pub session_index: SessionIndex,
+	/// The core the candidate occupied.
+	pub core_index: CoreIndex,
+	/// The backing group of the candidate.
+	pub group_index: GroupIndex,
+}
+
+/// The data of a session needed for checking the chunks of its candidates.
+#[derive(Clone, Debug)]
+pub struct SessionData {
+	/// The authority discovery keys of the validators of the session.
+	pub discovery_keys: Vec<AuthorityDiscoveryId>,
+	/// Our index in the session, if we are a validator.
+	pub our_index: Option<ValidatorIndex>,
+	/// The node features enabled in the session.
+	pub node_features: NodeFeatures,
+}
+
+impl SessionData {
+	/// The number of validators in the session, which is also the number of chunks.
+	pub fn n_validators(&self) -> usize {
+		self.discovery_keys.len()
+	}
+}
+
+/// The candidates included in a single finalized block.
+struct FinalizedBlock {
+	/// All candidates included in the block.
+	candidates: Vec<CandidateHash>,
+	/// The candidate of the block to be sampled for repair, if any got included.
+	sample: Option<IncludedCandidate>,
+}
+
+/// Record of the candidates included in the most recently finalized blocks.
+///
+/// Shared between the `Repairer` and the chunk push receiver.
+#[derive(Default)]
+pub struct FinalizedCandidates {
+	/// The recorded blocks, the most recently finalized one last.
+	blocks: VecDeque<FinalizedBlock>,
+	/// The session and core of every recorded candidate.
+	candidates: HashMap<CandidateHash, (SessionIndex, CoreIndex)>,
+	/// The data of the sessions of the recorded candidates, with the number of candidates
+	/// referencing them.
+	sessions: HashMap<SessionIndex, (SessionData, usize)>,
+}
+
+impl FinalizedCandidates {
+	/// Whether the data of the given session is recorded already.
+	pub fn has_session(&self, session_index: SessionIndex) -> bool {
+		self.sessions.contains_key(&session_index)
+	}
+
+	/// Record the candidates included in the next finalized block.
+	///
+	/// The data of the sessions of all `included` candidates must be in `sessions` or recorded
+	/// already, candidates of other sessions are ignored.
+	pub fn note_block(
+		&mut self,
+		included: Vec<IncludedCandidate>,
+		sessions: HashMap<SessionIndex, SessionData>,
+	) {
+		if self.blocks.len() == FINALIZED_BLOCKS {
+			if let Some(pruned) = self.blocks.pop_front() {
+				self.prune_block(pruned);
+			}
+		}
+
+		for (session_index, data) in sessions {
+			self.sessions.entry(session_index).or_insert((data, 0));
+		}
+		let included: Vec<_> = included
+			.into_iter()
+			.filter(|candidate| self.sessions.contains_key(&candidate.session_index))
+			.collect();
+		let mut candidates = Vec::with_capacity(included.len());
+		for candidate in &included {
+			let candidate_hash = candidate.receipt.hash();
+			let previous = self
+				.candidates
+				.insert(candidate_hash, (candidate.session_index, candidate.core_index));
+			if previous.is_none() {
+				if let Some((_, refs)) = self.sessions.get_mut(&candidate.session_index) {
+					*refs += 1;
+				}
+				candidates.push(candidate_hash);
+			}
+		}
+		self.sessions.retain(|_, (_, refs)| *refs > 0);
+
+		let sample = included.into_iter().choose(&mut thread_rng());
+		self.blocks.push_back(FinalizedBlock { candidates, sample });
+	}
+
+	/// Forget the candidates of a block which is no longer recorded.
+	fn prune_block(&mut self, block: FinalizedBlock) {
+		for candidate_hash in block.candidates {
+			let Some((session_index, _)) = self.candidates.remove(&candidate_hash) else {
+				continue
+			};
+			if let Some((_, refs)) = self.sessions.get_mut(&session_index) {
+				*refs -= 1;
+				if *refs == 0 {
+					self.sessions.remove(&session_index);
+				}
+			}
+		}
+	}
+
+	/// The session data and core of a recorded candidate.
+	pub fn candidate(&self, candidate_hash: &CandidateHash) -> Option<(&SessionData, CoreIndex)> {
+		let (session_index, core_index) = self.candidates.get(candidate_hash)?;
+		let (data, _) = self.sessions.get(session_index)?;
+		Some((data, *core_index))
+	}
+
+	/// Sample a candidate of a random recorded block, together with the data of its session.
+	fn sample(&self) -> Option<(IncludedCandidate, SessionData)> {
+		let block = self.blocks.iter().choose(&mut thread_rng())?;
+		let candidate = block.sample.clone()?;
+		let (data, _) = self.sessions.get(&candidate.session_index)?;
+		Some((candidate, data.clone()))
+	}
+}
+
+/// Repairer keeps track of finalized blocks and periodically spawns repair jobs for candidates
+/// included in them.
+pub struct Repairer {
+	/// The candidates included in recently finalized blocks.
+	finalized: Arc<Mutex<FinalizedCandidates>>,
+
+	/// The number of the last finalized block we recorded.
+	last_finalized: Option<BlockNumber>,
+
+	/// Prometheus Metrics
+	metrics: Metrics,
+}
+
+#[overseer::contextbounds(AvailabilityDistribution, prefix = self::overseer)]
+impl Repairer {
+	/// Create a new `Repairer`.
+	///
+	/// You must feed it with finalized blocks via `note_finalized_block` and trigger repairs by
+	/// calling `repair_sampled_candidate` every [`REPAIR_INTERVAL`].
+	pub fn new(metrics: Metrics) -> Self {
+		Repairer { finalized: Default::default(), last_finalized: None, metrics }
+	}
+
+	/// The record of finalized candidates, to be shared with the chunk push receiver.
+	pub fn finalized_candidates(&self) -> Arc<Mutex<FinalizedCandidates>> {
+		self.finalized.clone()
+	}
+
+	/// Record the candidates included in the blocks finalized since the last call, up to and
+	/// including `hash`.
+	///
+	/// This must be called right on finalization, while the state of the blocks is still
+	/// available.
+	pub async fn note_finalized_block<Context>(
+		&mut self,
+		ctx: &mut Context,
+		runtime: &mut RuntimeInfo,
+		hash: Hash,
+		number: BlockNumber,
+	) -> Result<()> {
+		let new_blocks = match self.last_finalized {
+			Some(last) if number <= last => return Ok(()),
+			Some(last) => ((number - last) as usize).min(FINALIZED_BLOCKS),
+			None => 1,
+		};
+		self.last_finalized = Some(number);
+
+		let mut blocks = vec![hash];
+		if new_blocks > 1 {
+			let (tx, rx) = oneshot::channel();
+			ctx.send_message(ChainApiMessage::Ancestors {
+				hash,
+				k: new_blocks - 1,
+				response_channel: tx,
+			})
+			.await;
+			match rx.await {
+				Ok(Ok(ancestors)) => blocks.extend(ancestors),
+				response => gum::debug!(
+					target: LOG_TARGET,
+					?hash,
+					?response,
+					"Failed to get the ancestors of the finalized block",
+				),
+			}
+		}
+
+		// Record the oldest block first.
+		for block in blocks.into_iter().rev() {
+			let (included, sessions) =
+				included_candidates(ctx, runtime, &self.finalized, block).await?;
+			self.finalized.lock().note_block(included, sessions);
+		}
+		Ok(())
+	}
+
+	/// Sample a candidate included in one of the recorded finalized blocks and spawn a job
+	/// repairing it.
+	///
+	/// Does nothing if there are no recorded blocks, the sampled block did not include any
+	/// candidates or we are not a validator in the session of the sampled candidate.
+	pub async fn repair_sampled_candidate<Context>(&mut self, ctx: &mut Context) -> Result<()> {
+		let (candidate, session) = match self.finalized.lock().sample() {
+			Some(sampled) => sampled,
+			None => return Ok(()),
+		};
+		let our_index = match session.our_index {
+			Some(our_index) => our_index,
+			None => return Ok(()),
+		};
+
+		let n_validators = session.n_validators();
+		let holders = session
+			.discovery_keys
+			.into_iter()
+			.enumerate()
+			.map(|(index, authority_id)| (ValidatorIndex(index as u32), authority_id))
+			.filter(|(index, _)| *index != our_index)
+			.choose_multiple(&mut thread_rng(), PROBED_HOLDERS);
+
+		let job = RepairJob {
+			receipt: candidate.receipt,
+			session_index: candidate.session_index,
+			core_index: candidate.core_index,
+			group_index: candidate.group_index,
+			n_validators,
+			our_index,
+			node_features: session.node_features,
+			holders,
+		};
+
+		let sender = ctx.sender().clone();
+		ctx.spawn("availability-repair", repair_job(sender, job, self.metrics.clone()).boxed())
+			.map_err(|e| FatalError::SpawnTask(e))?;
+		Ok(())
+	}
+}
+
+/// Fetch the candidates included in `block` and the data of their sessions which is not recorded
+/// yet.
+#[overseer::contextbounds(AvailabilityDistribution, prefix = self::overseer)]
+async fn included_candidates<Context>(
+	ctx: &mut Context,
+	runtime: &mut RuntimeInfo,
+	finalized: &Mutex<FinalizedCandidates>,
+	block: Hash,
+) -> Result<(Vec<IncludedCandidate>, HashMap<SessionIndex, SessionData>)> {
+	let events = request_candidate_events(block, ctx.sender())
+		.await
+		.await
+		.map_err(Error::CandidateEventsResponseChannel)?
+		.map_err(Error::FailedCandidateEvents)?;
+
+	let mut included = Vec::new();
+	let mut sessions = HashMap::new();
+	for event in events {
+		let CandidateEvent::CandidateIncluded(receipt, _, core_index, group_index) = event else {
+			continue
+		};
+		let relay_parent = receipt.descriptor.relay_parent();
+		let session_index = runtime.get_session_index_for_child(ctx.sender(), relay_parent).await?;
+		if !sessions.contains_key(&session_index) && !finalized.lock().has_session(session_index) {
+			let info =
+				runtime.get_session_info_by_index(ctx.sender(), block, session_index).await?;
+			let n_validators = info.session_info.validators.len();
+			sessions.insert(
+				session_index,
+				SessionData {
+					discovery_keys: info
+						.session_info
+						.discovery_keys
+						.iter()
+						.take(n_validators)
+						.cloned()
+						.collect(),
+					our_index: info.validator_info.our_index,
+					node_features: info.node_features.clone(),
+				},
+			);
+		}
+		included.push(IncludedCandidate { receipt, session_index, core_index, group_index });
+	}
+	Ok((included, sessions))
+}
+
+/// Everything needed for checking and repairing a single included candidate.
+struct RepairJob {
+	receipt: CandidateReceipt,
+	session_index: SessionIndex,
+	core_index: CoreIndex,
+	group_index: GroupIndex,
+	n_validators: usize,
+	our_index: ValidatorIndex,
+	node_features: NodeFeatures,
+	/// The chunk holders to probe.
+	holders: Vec<(ValidatorIndex, AuthorityDiscoveryId)>,
+}
+
+/// Future to be spawned for checking and repairing a candidate.
+async fn repair_job<Sender>(mut sender: Sender, job: RepairJob, metrics: Metrics)
+where
+	Sender: overseer::AvailabilityDistributionSenderTrait,
+{
+	let candidate_hash = job.receipt.hash();
+	if let Err(err) = do_repair(&mut sender, job, &metrics).await {
+		gum::debug!(target: LOG_TARGET, ?err, ?candidate_hash, "repair_job");
+	}
+}
+
+/// Probe the chunk holders, restore our own chunk and push the missing chunks of the probed
+/// holders to them.
+async fn do_repair<Sender>(sender: &mut Sender, job: RepairJob, metrics: &Metrics) -> Result<()>
+where
+	Sender: overseer::AvailabilityDistributionSenderTrait,
+{
+	let candidate_hash = job.receipt.hash();
+
+	let probed = job.holders.len();
+	let ProbeOutcome { available, missing } = probe_holders(sender, &job, metrics).await;
+	let threshold = recovery_threshold(job.n_validators)?;
+	if available * job.n_validators < threshold * probed {
+		gum::warn!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			available,
+			probed,
+			"Included candidate appears to be under-replicated",
+		);
+	}
+
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityStoreMessage::QueryChunkAvailability(
+			candidate_hash,
+			job.our_index,
+			tx,
+		))
+		.await;
+	let have_our_chunk = rx.await.map_err(Error::QueryChunkResponseChannel)?;
+	if have_our_chunk && missing.is_empty() {
+		return Ok(())
+	}
+
+	gum::debug!(
+		target: LOG_TARGET,
+		?candidate_hash,
+		have_our_chunk,
+		missing = missing.len(),
+		"Chunks are missing, recovering the candidate",
+	);
+
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityRecoveryMessage::RecoverAvailableData(
+			job.receipt.clone(),
+			job.session_index,
+			Some(job.group_index),
+			Some(job.core_index),
+			tx,
+		))
+		.await;
+	let available_data = match rx.await.map_err(Error::RecoverAvailableDataResponseChannel)? {
+		Ok(available_data) => available_data,
+		Err(err) => {
+			metrics.on_repair(FAILED);
+			gum::warn!(target: LOG_TARGET, ?err, ?candidate_hash, "Failed to recover candidate");
+			return Ok(())
+		},
+	};
+
+	if !missing.is_empty() {
+		push_missing_chunks(sender, &job, &available_data, missing, metrics).await?;
+	}
+	if have_our_chunk {
+		return Ok(())
+	}
+
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityStoreMessage::StoreAvailableData {
+			candidate_hash,
+			n_validators: job.n_validators as u32,
+			available_data,
+			expected_erasure_root: job.receipt.descriptor.erasure_root(),
+			core_index: job.core_index,
+			node_features: job.node_features,
+			tx,
+		})
+		.await;
+	match rx.await.map_err(Error::StoreAvailableDataResponseChannel)? {
+		Ok(()) => {
+			metrics.on_repair(SUCCEEDED);
+			gum::info!(target: LOG_TARGET, ?candidate_hash, "Restored missing chunk");
+		},
+		Err(err) => {
+			metrics.on_repair(FAILED);
+			gum::warn!(target: LOG_TARGET, ?err, ?candidate_hash, "Failed to store recovered data");
+		},
+	}
+	Ok(())
+}
+
+/// The outcome of probing the chunk holders of a candidate.
+struct ProbeOutcome {
+	/// How many holders provided a valid chunk.
+	available: usize,
+	/// The holders which don't have their chunk.
+	missing: Vec<(ValidatorIndex, AuthorityDiscoveryId)>,
+}
+
+/// Request their chunk from all holders of the job.
+async fn probe_holders<Sender>(
+	sender: &mut Sender,
+	job: &RepairJob,
+	metrics: &Metrics,
+) -> ProbeOutcome
+where
+	Sender: overseer::AvailabilityDistributionSenderTrait,
+{
+	let candidate_hash = job.receipt.hash();
+	let mut requests = Vec::with_capacity(job.holders.len());
+	let mut pending = FuturesUnordered::new();
+	for (index, authority_id) in &job.holders {
+		let (req, response) = OutgoingRequest::new(
+			Recipient::Authority(authority_id.clone()),
+			v2::ChunkFetchingRequest { candidate_hash, index: *index },
+		);
+		requests.push(Requests::ChunkFetching(req));
+		let holder = (*index, authority_id.clone());
+		pending.push(response.map(move |response| (holder, response)));
+	}
+
+	sender
+		.send_message(NetworkBridgeTxMessage::SendRequests(requests, IfDisconnected::TryConnect))
+		.await;
+
+	let mut outcome = ProbeOutcome { available: 0, missing: Vec::new() };
+	while let Some(((index, authority_id), response)) = pending.next().await {
+		let label = match response {
+			Ok(v2::ChunkFetchingResponse::Chunk(chunk))
+				if is_valid_chunk(
+					&job.receipt,
+					&job.node_features,
+					job.n_validators,
+					job.core_index,
+					index,
+					&chunk,
+				) =>
+			{
+				outcome.available += 1;
+				SUCCEEDED
+			},
+			Ok(v2::ChunkFetchingResponse::NoSuchChunk) => {
+				outcome.missing.push((index, authority_id));
+				NOT_FOUND
+			},
+			response => {
+				log_probe_failure(job, index, response);
+				FAILED
+			},
+		};
+		metrics.on_probed_chunk(label);
+	}
+	outcome
+}
+
+/// Push their chunk, re-created from the recovered `available_data`, to the `missing` holders.
+async fn push_missing_chunks<Sender>(
+	sender: &mut Sender,
+	job: &RepairJob,
+	available_data: &AvailableData,
+	missing: Vec<(ValidatorIndex, AuthorityDiscoveryId)>,
+	metrics: &Metrics,
+) -> Result<()>
+where
+	Sender: overseer::AvailabilityDistributionSenderTrait,
+{
+	let candidate_hash = job.receipt.hash();
+	let chunks = obtain_chunks_v1(job.n_validators, available_data)?;
+	let branches = branches(chunks.as_ref());
+	if branches.root() != job.receipt.descriptor.erasure_root() {
+		gum::warn!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			"Recovered data does not match the erasure root, not pushing chunks",
+		);
+		return Ok(())
+	}
+	let chunks: Vec<_> = branches
+		.enumerate()
+		.map(|(index, (proof, chunk))| ErasureChunk {
+			chunk: chunk.to_vec(),
+			index: ChunkIndex(index as u32),
+			proof,
+		})
+		.collect();
+
+	let mut requests = Vec::with_capacity(missing.len());
+	let mut pending = FuturesUnordered::new();
+	for (index, authority_id) in missing {
+		let chunk_index = match availability_chunk_index(
+			&job.node_features,
+			job.n_validators,
+			job.core_index,
+			index,
+		) {
+			Ok(chunk_index) => chunk_index,
+			Err(_) => continue,
+		};
+		let Some(chunk) = chunks.get(chunk_index.0 as usize) else { continue };
+
+		let (req, response) = OutgoingRequest::new(
+			Recipient::Authority(authority_id),
+			v1::ChunkPushingRequest {
+				candidate_receipt: job.receipt.clone(),
+				core_index: job.core_index,
+				chunk: chunk.clone(),
+			},
+		);
+		requests.push(Requests::ChunkPushingV1(req));
+		pending.push(response.map(move |response| (index, response)));
+	}
+
+	sender
+		.send_message(NetworkBridgeTxMessage::SendRequests(requests, IfDisconnected::TryConnect))
+		.await;
+
+	while let Some((index, response)) = pending.next().await {
+		let label = match response {
+			Ok(v1::ChunkPushingResponse::Stored) => SUCCEEDED,
+			Ok(v1::ChunkPushingResponse::Rejected) => NOT_FOUND,
+			Err(err) => {
+				gum::trace!(
+					target: LOG_TARGET,
+					?candidate_hash,
+					validator_index = ?index,
+					?err,
+					"Pushing chunk failed",
+				);
+				FAILED
+			},
+		};
+		metrics.on_pushed_chunk(label);
+	}
+	Ok(())
+}
+
+fn log_probe_failure(
+	job: &RepairJob,
+	index: ValidatorIndex,
+	response: OutgoingResult<v2::ChunkFetchingResponse>,
+) {
+	gum::trace!(
+		target: LOG_TARGET,
+		candidate_hash = ?job.receipt.hash(),
+		validator_index = ?index,
+		?response,
+		"Chunk holder did not provide a valid chunk",
+	);
+}
+
+/// Whether `chunk` is the one assigned to the validator at `index` and matches the erasure root
+/// of the candidate.
+pub(crate) fn is_valid_chunk(
+	receipt: &CandidateReceipt,
+	node_features: &NodeFeatures,
+	n_validators: usize,
+	core_index: CoreIndex,
+	index: ValidatorIndex,
+	chunk: &ErasureChunk,
+) -> bool {
+	let expected_chunk_index =
+		match availability_chunk_index(node_features, n_validators, core_index, index) {
+			Ok(chunk_index) => chunk_index,
+			Err(_) => return false,
+		};
+	if chunk.index != expected_chunk_index {
+		return false
+	}
+
+	branch_hash(&receipt.descriptor.erasure_root(), chunk.proof(), chunk.index.0 as usize)
+		.map_or(false, |anticipated_hash| anticipated_hash == BlakeTwo256::hash(&chunk.chunk))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::sync::Arc;
+
+	use assert_matches::assert_matches;
+	use codec::Encode;
+	use futures::{executor, future};
+
+	use polkadot_node_primitives::{BlockData, PoV};
+	use polkadot_node_subsystem::messages::AllMessages;
+	use polkadot_node_subsystem_test_helpers as test_helpers;
+	use polkadot_primitives::{
+		CandidateHash, HeadData, MutateDescriptorV2, PersistedValidationData,
+	};
+	use polkadot_primitives_test_helpers::dummy_candidate_receipt_v2;
+	use sc_network::ProtocolName;
+	use sp_keyring::Sr25519Keyring;
+
+	use super::*;
+
+	const N_VALIDATORS: usize = 4;
+
+	fn make_available_data() -> AvailableData {
+		AvailableData {
+			validation_data: PersistedValidationData {
+				parent_head: HeadData(vec![7, 8, 9]),
+				relay_parent_number: Default::default(),
+				max_pov_size: 1024,
+				relay_parent_storage_root: Default::default(),
+			},
+			pov: Arc::new(PoV { block_data: BlockData(vec![1, 2, 3, 4, 5, 6]) }),
+		}
+	}
+
+	fn make_job(erasure_root: Hash) -> RepairJob {
+		let mut receipt = dummy_candidate_receipt_v2(Hash::repeat_byte(1));
+		receipt.descriptor.set_erasure_root(erasure_root);
+		RepairJob {
+			receipt,
+			session_index: 1,
+			core_index: CoreIndex(0),
+			group_index: GroupIndex(0),
+			n_validators: N_VALIDATORS,
+			our_index: ValidatorIndex(0),
+			node_features: NodeFeatures::EMPTY,
+			holders: [Sr25519Keyring::Alice, Sr25519Keyring::Bob, Sr25519Keyring::Charlie]
+				.into_iter()
+				.enumerate()
+				.map(|(i, k)| (ValidatorIndex(i as u32 + 1), k.public().into()))
+				.collect(),
+		}
+	}
+
+	fn make_erasure_chunks() -> (Hash, Vec<ErasureChunk>) {
+		let chunks = obtain_chunks_v1(N_VALIDATORS, &make_available_data()).unwrap();
+		let branches = branches(chunks.as_ref());
+		let erasure_root = branches.root();
+		let erasure_chunks = branches
+			.enumerate()
+			.map(|(index, (proof, chunk))| ErasureChunk {
+				chunk: chunk.to_vec(),
+				index: ChunkIndex(index as _),
+				proof,
+			})
+			.collect();
+		(erasure_root, erasure_chunks)
+	}
+
+	/// Answer the probes of the holders, only the ones in `present` have their chunk.
+	async fn answer_probes(
+		receiver: &mut futures::channel::mpsc::UnboundedReceiver<AllMessages>,
+		erasure_chunks: &[ErasureChunk],
+		present: &[ValidatorIndex],
+	) {
+		assert_matches!(
+			receiver.next().await,
+			Some(AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendRequests(
+				requests,
+				IfDisconnected::TryConnect,
+			))) => {
+				assert_eq!(requests.len(), 3);
+				for request in requests {
+					let request = assert_matches!(request, Requests::ChunkFetching(r) => r);
+					let index = request.payload.index;
+					let response = if present.contains(&index) {
+						v2::ChunkFetchingResponse::Chunk(erasure_chunks[index.0 as usize].clone())
+					} else {
+						v2::ChunkFetchingResponse::NoSuchChunk
+					};
+					request
+						.pending_response
+						.send(Ok((response.encode(), ProtocolName::from(""))))
+						.unwrap();
+				}
+			}
+		);
+	}
+
+	async fn answer_our_chunk_query(
+		receiver: &mut futures::channel::mpsc::UnboundedReceiver<AllMessages>,
+		candidate_hash: CandidateHash,
+		have_our_chunk: bool,
+	) {
+		assert_matches!(
+			receiver.next().await,
+			Some(AllMessages::AvailabilityStore(
+				AvailabilityStoreMessage::QueryChunkAvailability(hash, ValidatorIndex(0), tx)
+			)) => {
+				assert_eq!(hash, candidate_hash);
+				tx.send(have_our_chunk).unwrap();
+			}
+		);
+	}
+
+	async fn answer_recovery(
+		receiver: &mut futures::channel::mpsc::UnboundedReceiver<AllMessages>,
+		candidate_hash: CandidateHash,
+	) {
+		assert_matches!(
+			receiver.next().await,
+			Some(AllMessages::AvailabilityRecovery(
+				AvailabilityRecoveryMessage::RecoverAvailableData(receipt, 1, _, _, tx)
+			)) => {
+				assert_eq!(receipt.hash(), candidate_hash);
+				tx.send(Ok(make_available_data())).unwrap();
+			}
+		);
+	}
+
+	/// Check the pushed chunks go to the `expected` holders and are valid for them.
+	async fn answer_pushes(
+		receiver: &mut futures::channel::mpsc::UnboundedReceiver<AllMessages>,
+		job_receipt: CandidateReceipt,
+		expected: &[ValidatorIndex],
+	) {
+		assert_matches!(
+			receiver.next().await,
+			Some(AllMessages::NetworkBridgeTx(NetworkBridgeTxMessage::SendRequests(
+				requests,
+				IfDisconnected::TryConnect,
+			))) => {
+				let mut pushed_to = Vec::new();
+				for request in requests {
+					let request = assert_matches!(request, Requests::ChunkPushingV1(r) => r);
+					let authority_id = assert_matches!(request.peer, Recipient::Authority(id) => id);
+					let index = Sr25519Keyring::iter()
+						.position(|k| AuthorityDiscoveryId::from(k.public()) == authority_id)
+						.map(|position| ValidatorIndex(position as u32 + 1))
+						.unwrap();
+					assert_eq!(request.payload.candidate_receipt, job_receipt);
+					assert!(is_valid_chunk(
+						&job_receipt,
+						&NodeFeatures::EMPTY,
+						N_VALIDATORS,
+						CoreIndex(0),
+						index,
+						&request.payload.chunk,
+					));
+					pushed_to.push(index);
+					request
+						.pending_response
+						.send(Ok((
+							v1::ChunkPushingResponse::Stored.encode(),
+							ProtocolName::from(""),
+						)))
+						.unwrap();
+				}
+				pushed_to.sort();
+				assert_eq!(pushed_to, expected);
+			}
+		);
+	}
+
+	#[test]
+	fn missing_chunks_get_recovered_stored_and_pushed() {
+		let (erasure_root, erasure_chunks) = make_erasure_chunks();
+		let job = make_job(erasure_root);
+		let receipt = job.receipt.clone();
+		let candidate_hash = receipt.hash();
+
+		let (mut sender, mut receiver) = test_helpers::sender_receiver();
+		let test = async move {
+			// Only the first probed holder still has its chunk.
+			answer_probes(&mut receiver, &erasure_chunks, &[ValidatorIndex(1)]).await;
+			answer_our_chunk_query(&mut receiver, candidate_hash, false).await;
+			answer_recovery(&mut receiver, candidate_hash).await;
+			answer_pushes(&mut receiver, receipt, &[ValidatorIndex(2), ValidatorIndex(3)]).await;
+
+			assert_matches!(
+				receiver.next().await,
+				Some(AllMessages::AvailabilityStore(
+					AvailabilityStoreMessage::StoreAvailableData {
+						candidate_hash: hash,
+						expected_erasure_root,
+						tx,
+						..
+					}
+				)) => {
+					assert_eq!(hash, candidate_hash);
+					assert_eq!(expected_erasure_root, erasure_root);
+					tx.send(Ok(())).unwrap();
+				}
+			);
+			assert!(receiver.next().await.is_none());
+		};
+
+		let metrics = Metrics::new_dummy();
+		executor::block_on(future::join(
+			async move { do_repair(&mut sender, job, &metrics).await.unwrap() },
+			test,
+		));
+	}
+
+	#[test]
+	fn missing_holder_chunks_get_pushed() {
+		let (erasure_root, erasure_chunks) = make_erasure_chunks();
+		let job = make_job(erasure_root);
+		let receipt = job.receipt.clone();
+		let candidate_hash = receipt.hash();
+
+		let (mut sender, mut receiver) = test_helpers::sender_receiver();
+		let test = async move {
+			answer_probes(&mut receiver, &erasure_chunks, &[ValidatorIndex(1), ValidatorIndex(2)])
+				.await;
+			answer_our_chunk_query(&mut receiver, candidate_hash, true).await;
+			answer_recovery(&mut receiver, candidate_hash).await;
+			answer_pushes(&mut receiver, receipt, &[ValidatorIndex(3)]).await;
+
+			// Our own chunk is present, nothing gets stored.
+			assert!(receiver.next().await.is_none());
+		};
+
+		let metrics = Metrics::new_dummy();
+		executor::block_on(future::join(
+			async move { do_repair(&mut sender, job, &metrics).await.unwrap() },
+			test,
+		));
+	}
+
+	#[test]
+	fn nothing_gets_recovered_without_missing_chunks() {
+		let (erasure_root, erasure_chunks) = make_erasure_chunks();
+		let job = make_job(erasure_root);
+		let candidate_hash = job.receipt.hash();
+
+		let (mut sender, mut receiver) = test_helpers::sender_receiver();
+		let test = async move {
+			answer_probes(
+				&mut receiver,
+				&erasure_chunks,
+				&[ValidatorIndex(1), ValidatorIndex(2), ValidatorIndex(3)],
+			)
+			.await;
+			answer_our_chunk_query(&mut receiver, candidate_hash, true).await;
+
+			assert!(receiver.next().await.is_none());
+		};
+
+		let metrics = Metrics::new_dummy();
+		executor::block_on(future::join(
+			async move { do_repair(&mut sender, job, &metrics).await.unwrap() },
+			test,
+		));
+	}
+
+	#[test]
+	fn chunk_of_other_validator_is_invalid() {
+		let (erasure_root, erasure_chunks) = make_erasure_chunks();
+		let job = make_job(erasure_root);
+
+		let is_valid = |index: u32, chunk: &ErasureChunk| {
+			is_valid_chunk(
+				&job.receipt,
+				&job.node_features,
+				N_VALIDATORS,
+				job.core_index,
+				ValidatorIndex(index),
+				chunk,
+			)
+		};
+		assert!(is_valid(1, &erasure_chunks[1]));
+		assert!(!is_valid(2, &erasure_chunks[1]));
+
+		let mut tampered = erasure_chunks[1].clone();
+		tampered.chunk[0] ^= 1;
+		assert!(!is_valid(1, &tampered));
+	}
+
+	#[test]
+	fn finalized_candidates_get_recorded_and_pruned() {
+		let candidate = |session_index| IncludedCandidate {
+			receipt: dummy_candidate_receipt_v2(Hash::repeat_byte(session_index as u8)),
+			session_index,
+			core_index: CoreIndex(2),
+			group_index: GroupIndex(0),
+		};
+		let session = SessionData {
+			discovery_keys: vec![Sr25519Keyring::Alice.public().into()],
+			our_index: Some(ValidatorIndex(0)),
+			node_features: NodeFeatures::EMPTY,
+		};
+		let recorded = candidate(1);
+		let recorded_hash = recorded.receipt.hash();
+		let unrecorded_hash = candidate(2).receipt.hash();
+
+		let mut finalized = FinalizedCandidates::default();
+		// The data of session 2 is missing, so its candidate is ignored.
+		finalized.note_block(vec![recorded, candidate(2)], [(1, session)].into_iter().collect());
+		assert_matches!(finalized.candidate(&recorded_hash), Some((_, CoreIndex(2))));
+		assert!(finalized.candidate(&unrecorded_hash).is_none());
+		assert_matches!(
+			finalized.sample(),
+			Some((sampled, _)) => assert_eq!(sampled.receipt.hash(), recorded_hash)
+		);
+
+		for _ in 1..FINALIZED_BLOCKS {
+			finalized.note_block(Vec::new(), HashMap::new());
+		}
+		assert!(finalized.candidate(&recorded_hash).is_some());
+		assert!(finalized.has_session(1));
+
+		finalized.note_block(Vec::new(), HashMap::new());
+		assert!(finalized.candidate(&recorded_hash).is_none());
+		assert!(!finalized.has_session(1));
+		assert!(finalized.sample().is_none());
+	}
+}
@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
-//! Answer requests for availability chunks.
+//! Answer requests for availability chunks and store chunks pushed to us.
 
 use std::sync::Arc;
 
@@ -22,21 +22,27 @@ use futures::{channel::oneshot, select, FutureExt};
 
 use codec::{Decode, Encode};
 use fatality::Nested;
+use parking_lot::Mutex;
 use polkadot_node_network_protocol::{
-	request_response::{v1, v2, IncomingRequest, IncomingRequestReceiver, IsRequest},
+	request_response::{
+		incoming::OutgoingResponse, v1, v2, IncomingRequest, IncomingRequestReceiver, IsRequest,
+	},
 	UnifiedReputationChange as Rep,
 };
 use polkadot_node_primitives::{AvailableData, ErasureChunk};
-use polkadot_node_subsystem::{messages::AvailabilityStoreMessage, SubsystemSender};
+use polkadot_node_subsystem::{messages::AvailabilityStoreMessage, overseer, SubsystemSender};
 use polkadot_primitives::{CandidateHash, ValidatorIndex};
 
 use crate::{
 	error::{JfyiError, Result},
 	metrics::{Metrics, FAILED, NOT_FOUND, SUCCEEDED},
+	repairer::{is_valid_chunk, FinalizedCandidates},
 	LOG_TARGET,
 };
 
 const COST_INVALID_REQUEST: Rep = Rep::CostMajor("Received message could not be decoded.");
+const COST_INVALID_PUSHED_CHUNK: Rep =
+	Rep::CostMajor("Pushed chunk is not ours or does not match the erasure root.");
 
 /// Receiver task to be forked as a separate task to handle PoV requests.
 pub async fn run_pov_receiver<Sender>(
@@ -131,6 +137,47 @@ pub async fn run_chunk_receivers<Sender>(
 	}
 }
 
+/// Receiver task to be forked as a separate task to handle chunks pushed to us.
+pub async fn run_chunk_push_receiver<Sender>(
+	mut sender: Sender,
+	mut receiver: IncomingRequestReceiver<v1::ChunkPushingRequest>,
+	finalized: Arc<Mutex<FinalizedCandidates>>,
+	metrics: Metrics,
+) where
+	Sender: overseer::AvailabilityDistributionSenderTrait,
+{
+	loop {
+		match receiver.recv(|| vec![COST_INVALID_REQUEST]).await.into_nested() {
+			Ok(Ok(msg)) => {
+				let res = answer_chunk_push_request(&mut sender, &finalized, msg).await;
+				match res {
+					Ok(stored) =>
+						metrics.on_received_pushed_chunk(if stored { SUCCEEDED } else { NOT_FOUND }),
+					Err(err) => {
+						gum::debug!(target: LOG_TARGET, ?err, "Storing pushed chunk failed");
+						metrics.on_received_pushed_chunk(FAILED);
+					},
+				}
+			},
+			Err(fatal) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					error = ?fatal,
+					"Shutting down chunk push receiver."
+				);
+				return
+			},
+			Ok(Err(jfyi)) => {
+				gum::debug!(
+					target: LOG_TARGET,
+					error = ?jfyi,
+					"Error decoding incoming chunk push request."
+				);
+			},
+		}
+	}
+}
+
 /// Variant of `answer_pov_request` that does Prometheus metric and logging on errors.
 ///
 /// Any errors of `answer_pov_request` will simply be logged.
@@ -249,6 +296,82 @@ where
 	Ok(result)
 }
 
+/// Check a chunk pushed to us and store it in the av store.
+///
+/// Only chunks of candidates included in recently finalized blocks are accepted. The chunk must be
+/// the one assigned to us and match the erasure root of the candidate, otherwise the pushing peer
+/// gets punished. The chunk is stored even if the av store doesn't know the candidate, e.g. because
+/// we lost our database.
+///
+/// Returns: `Ok(true)` if the chunk is stored.
+pub async fn answer_chunk_push_request<Sender>(
+	sender: &mut Sender,
+	finalized: &Mutex<FinalizedCandidates>,
+	req: IncomingRequest<v1::ChunkPushingRequest>,
+) -> Result<bool>
+where
+	Sender: overseer::AvailabilityDistributionSenderTrait,
+{
+	let v1::ChunkPushingRequest { candidate_receipt, core_index, chunk } = &req.payload;
+	let candidate_hash = candidate_receipt.hash();
+
+	// We might just not have recorded the finalization of the candidate yet, so unknown candidates
+	// are rejected without punishing the peer.
+	let recorded = finalized.lock().candidate(&candidate_hash).map(|(session, core)| {
+		(session.our_index, session.n_validators(), session.node_features.clone(), core)
+	});
+	let (our_index, n_validators, node_features, included_core) = match recorded {
+		Some((Some(our_index), n_validators, node_features, included_core)) =>
+			(our_index, n_validators, node_features, included_core),
+		_ => {
+			req.send_response(v1::ChunkPushingResponse::Rejected)
+				.map_err(|_| JfyiError::SendResponse)?;
+			return Ok(false)
+		},
+	};
+
+	if *core_index != included_core ||
+		!is_valid_chunk(
+			candidate_receipt,
+			&node_features,
+			n_validators,
+			*core_index,
+			our_index,
+			chunk,
+		) {
+		gum::debug!(
+			target: LOG_TARGET,
+			?candidate_hash,
+			peer = ?req.peer,
+			"Received invalid pushed chunk",
+		);
+		req.send_outgoing_response(OutgoingResponse {
+			result: Ok(v1::ChunkPushingResponse::Rejected),
+			reputation_changes: vec![COST_INVALID_PUSHED_CHUNK],
+			sent_feedback: None,
+		})
+		.map_err(|_| JfyiError::SendResponse)?;
+		return Ok(false)
+	}
+
+	let (tx, rx) = oneshot::channel();
+	sender
+		.send_message(AvailabilityStoreMessage::StoreFinalizedChunk {
+			candidate_hash,
+			n_validators: n_validators as u32,
+			validator_index: our_index,
+			chunk: chunk.clone(),
+			tx,
+		})
+		.await;
+	let stored = rx.await.map_err(JfyiError::StoreChunkResponseChannel)?.is_ok();
+
+	let response =
+		if stored { v1::ChunkPushingResponse::Stored } else { v1::ChunkPushingResponse::Rejected };
+	req.send_response(response).map_err(|_| JfyiError::SendResponse)?;
+	Ok(stored)
+}
+
 /// Query chunk from the availability store.
 async fn query_chunk<Sender>(
 	sender: &mut Sender,
@@ -16,16 +16,25 @@
 
 use std::collections::HashSet;
 
-use futures::{executor, future, Future};
+use codec::Decode;
+use futures::{channel::oneshot, executor, future, Future, StreamExt};
+use parking_lot::Mutex;
 use rstest::rstest;
 
 use polkadot_node_network_protocol::request_response::{
-	IncomingRequest, Protocol, ReqProtocolNames,
+	v1, IncomingRequest, Protocol, ReqProtocolNames,
 };
-use polkadot_primitives::{node_features, Block, CoreState, Hash, NodeFeatures};
+use polkadot_node_primitives::{BlockData, ErasureChunk, PoV};
+use polkadot_node_subsystem::messages::{AllMessages, AvailabilityStoreMessage};
+use polkadot_primitives::{
+	node_features, Block, ChunkIndex, CoreIndex, CoreState, GroupIndex, Hash, MutateDescriptorV2,
+	NodeFeatures, ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::dummy_candidate_receipt_v2;
 use sp_keystore::KeystorePtr;
 
 use super::*;
+use crate::repairer::{FinalizedCandidates, IncludedCandidate, SessionData};
 
 mod state;
 /// State for test harnesses.
@@ -33,6 +42,7 @@ use state::{TestHarness, TestState};
 
 /// Mock data useful for testing.
 pub(crate) mod mock;
+use mock::{get_valid_chunk_data, make_session_info};
 
 fn test_harness<T: Future<Output = ()>>(
 	keystore: KeystorePtr,
@@ -57,9 +67,18 @@ fn test_harness<T: Future<Output = ()>>(
 		Block,
 		sc_network::NetworkWorker<Block, Hash>,
 	>(&req_protocol_names);
+	let (chunk_push_receiver, _chunk_push_cfg) = IncomingRequest::get_config_receiver::<
+		Block,
+		sc_network::NetworkWorker<Block, Hash>,
+	>(&req_protocol_names);
 	let subsystem = AvailabilityDistributionSubsystem::new(
 		keystore,
-		IncomingRequestReceivers { pov_req_receiver, chunk_req_v1_receiver, chunk_req_v2_receiver },
+		IncomingRequestReceivers {
+			pov_req_receiver,
+			chunk_req_v1_receiver,
+			chunk_req_v2_receiver,
+			chunk_push_receiver,
+		},
 		req_protocol_names,
 		Default::default(),
 	);
@@ -199,3 +218,140 @@ fn check_fetch_retry(#[case] node_features: NodeFeatures, #[case] chunk_resp_pro
 			.unwrap();
 	}
 }
+
+/// Push `chunk` of a candidate with the given erasure root to us and answer the av store request
+/// this triggers. The candidate is recorded as finalized on core 0 if `finalized` is set.
+///
+/// Returns the response, the number of reputation changes sent back to the pushing peer and
+/// whether the chunk got stored.
+fn push_chunk(
+	erasure_root: Hash,
+	core_index: CoreIndex,
+	chunk: ErasureChunk,
+	finalized: bool,
+) -> (Option<v1::ChunkPushingResponse>, usize, bool) {
+	let mut receipt = dummy_candidate_receipt_v2(Hash::repeat_byte(1));
+	receipt.descriptor.set_erasure_root(erasure_root);
+	receipt.descriptor.set_core_index(CoreIndex(0));
+	let candidate_hash = receipt.hash();
+
+	let finalized_candidates = Mutex::new(FinalizedCandidates::default());
+	if finalized {
+		let session_info = make_session_info();
+		let session = SessionData {
+			discovery_keys: session_info.discovery_keys,
+			// Ferdie is the first validator.
+			our_index: Some(ValidatorIndex(0)),
+			node_features: NodeFeatures::EMPTY,
+		};
+		finalized_candidates.lock().note_block(
+			vec![IncludedCandidate {
+				receipt: receipt.clone(),
+				session_index: 1,
+				core_index: CoreIndex(0),
+				group_index: GroupIndex(0),
+			}],
+			[(1, session)].into_iter().collect(),
+		);
+	}
+
+	let (tx, rx) = oneshot::channel();
+	let req = IncomingRequest::new(
+		sc_network::PeerId::random().into(),
+		v1::ChunkPushingRequest { candidate_receipt: receipt, core_index, chunk },
+		tx,
+	);
+
+	let (mut sender, mut receiver) = polkadot_node_subsystem_test_helpers::sender_receiver();
+	let overseer = async move {
+		let mut stored = false;
+		while let Some(msg) = receiver.next().await {
+			match msg {
+				AllMessages::AvailabilityStore(AvailabilityStoreMessage::StoreFinalizedChunk {
+					candidate_hash: hash,
+					n_validators,
+					validator_index,
+					tx,
+					..
+				}) => {
+					assert_eq!(hash, candidate_hash);
+					assert_eq!(n_validators, 7);
+					assert_eq!(validator_index, ValidatorIndex(0));
+					stored = true;
+					tx.send(Ok(())).unwrap();
+				},
+				msg => panic!("Unexpected message: {:?}", msg),
+			}
+		}
+		stored
+	};
+
+	let (_, stored) = executor::block_on(future::join(
+		async move {
+			let _ =
+				responder::answer_chunk_push_request(&mut sender, &finalized_candidates, req).await;
+		},
+		overseer,
+	));
+	let response = executor::block_on(rx).unwrap();
+	let decoded = response
+		.result
+		.ok()
+		.map(|bytes| v1::ChunkPushingResponse::decode(&mut &bytes[..]).unwrap());
+	(decoded, response.reputation_changes.len(), stored)
+}
+
+#[test]
+fn pushed_chunk_gets_stored() {
+	let pov = PoV { block_data: BlockData(vec![45, 46, 47]) };
+	let (erasure_root, chunk) = get_valid_chunk_data(pov, 7, ChunkIndex(0));
+
+	assert_eq!(
+		push_chunk(erasure_root, CoreIndex(0), chunk, true),
+		(Some(v1::ChunkPushingResponse::Stored), 0, true),
+	);
+}
+
+#[test]
+fn pushed_chunk_of_other_validator_gets_rejected() {
+	let pov = PoV { block_data: BlockData(vec![45, 46, 47]) };
+	let (erasure_root, chunk) = get_valid_chunk_data(pov, 7, ChunkIndex(1));
+
+	assert_eq!(
+		push_chunk(erasure_root, CoreIndex(0), chunk, true),
+		(Some(v1::ChunkPushingResponse::Rejected), 1, false),
+	);
+}
+
+#[test]
+fn pushed_chunk_not_matching_erasure_root_gets_rejected() {
+	let pov = PoV { block_data: BlockData(vec![45, 46, 47]) };
+	let (_, chunk) = get_valid_chunk_data(pov, 7, ChunkIndex(0));
+
+	assert_eq!(
+		push_chunk(Hash::repeat_byte(42), CoreIndex(0), chunk, true),
+		(Some(v1::ChunkPushingResponse::Rejected), 1, false),
+	);
+}
+
+#[test]
+fn pushed_chunk_for_wrong_core_gets_rejected() {
+	let pov = PoV { block_data: BlockData(vec![45, 46, 47]) };
+	let (erasure_root, chunk) = get_valid_chunk_data(pov, 7, ChunkIndex(0));
+
+	assert_eq!(
+		push_chunk(erasure_root, CoreIndex(1), chunk, true),
+		(Some(v1::ChunkPushingResponse::Rejected), 1, false),
+	);
+}
+
+#[test]
+fn pushed_chunk_of_unknown_candidate_gets_rejected_without_punishment() {
+	let pov = PoV { block_data: BlockData(vec![45, 46, 47]) };
+	let (erasure_root, chunk) = get_valid_chunk_data(pov, 7, ChunkIndex(0));
+
+	assert_eq!(
+		push_chunk(erasure_root, CoreIndex(0), chunk, false),
+		(Some(v1::ChunkPushingResponse::Rejected), 0, false),
+	);
+}
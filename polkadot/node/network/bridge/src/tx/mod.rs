@@ -295,6 +295,7 @@ where
 					Requests::PoVFetchingV1(_) => metrics.on_message("pov_fetching_v1"),
 					Requests::DisputeSendingV1(_) => metrics.on_message("dispute_sending_v1"),
 					Requests::AttestedCandidateV2(_) => metrics.on_message("attested_candidate_v2"),
+					Requests::ChunkPushingV1(_) => metrics.on_message("chunk_pushing_v1"),
 				}
 
 				network_service
@@ -31,7 +31,10 @@ use futures::{
 	FutureExt, TryFutureExt,
 };
 
-use polkadot_node_subsystem_util::reputation::ReputationAggregator;
+use polkadot_node_subsystem_util::{
+	candidate_tracker::{CandidateTracker, TracksCandidates},
+	reputation::ReputationAggregator,
+};
 use sp_keystore::KeystorePtr;
 
 use polkadot_node_network_protocol::{
@@ -109,6 +112,8 @@ pub enum ProtocolSide {
 /// The collator protocol subsystem.
 pub struct CollatorProtocolSubsystem {
 	protocol_side: ProtocolSide,
+	/// Records advertised and fetched collations, only used by the validator side.
+	candidate_tracker: CandidateTracker,
 }
 
 #[overseer::contextbounds(CollatorProtocol, prefix = self::overseer)]
@@ -118,7 +123,13 @@ impl CollatorProtocolSubsystem {
 	/// If `id` is `None` this is a validator side of the protocol.
 	/// Caller must provide a registry for prometheus metrics.
 	pub fn new(protocol_side: ProtocolSide) -> Self {
-		Self { protocol_side }
+		Self { protocol_side, candidate_tracker: CandidateTracker::default() }
+	}
+}
+
+impl TracksCandidates for CollatorProtocolSubsystem {
+	fn candidate_tracker_mut(&mut self) -> &mut CandidateTracker {
+		&mut self.candidate_tracker
 	}
 }
 
@@ -146,6 +157,7 @@ impl<Context> CollatorProtocolSubsystem {
 					metrics,
 					invulnerables,
 					collator_protocol_hold_off,
+					self.candidate_tracker,
 				)
 				.map_err(|e| SubsystemError::with_origin("collator-protocol", e))
 				.boxed()
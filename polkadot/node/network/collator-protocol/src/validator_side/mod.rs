@@ -48,6 +48,7 @@ use polkadot_node_subsystem::{
 };
 use polkadot_node_subsystem_util::{
 	backing_implicit_view::View as ImplicitView,
+	candidate_tracker::{CandidateLifecycleEvent, CandidateTracker, DropReason},
 	reputation::{ReputationAggregator, REPUTATION_CHANGE_INTERVAL},
 	request_claim_queue, request_node_features, request_session_index_for_child,
 };
@@ -508,6 +509,9 @@ struct State {
 
 	/// For how long to hold off AssetHub collations from non-invulnerable collators
 	hold_off_duration: Duration,
+
+	/// Records advertised and fetched candidates.
+	candidate_tracker: CandidateTracker,
 }
 
 impl State {
@@ -1355,6 +1359,14 @@ where
 		)
 		.map_err(AdvertisementError::Invalid)?;
 
+	if let Some(candidate_hash) = candidate_hash {
+		state.candidate_tracker.note(
+			candidate_hash,
+			para_id,
+			CandidateLifecycleEvent::Advertised { peer_id, relay_parent },
+		);
+	}
+
 	if hold_off_asset_hub_collation_if_needed(
 		state,
 		peer_id,
@@ -1879,6 +1891,7 @@ pub(crate) async fn run<Context>(
 	metrics: Metrics,
 	ah_invulnerables: HashSet<PeerId>,
 	hold_off_duration: Option<Duration>,
+	candidate_tracker: CandidateTracker,
 ) -> std::result::Result<(), SubsystemError> {
 	run_inner(
 		ctx,
@@ -1889,6 +1902,7 @@ pub(crate) async fn run<Context>(
 		REPUTATION_CHANGE_INTERVAL,
 		ah_invulnerables,
 		hold_off_duration.unwrap_or(HOLD_OFF_DURATION_DEFAULT_VALUE),
+		candidate_tracker,
 	)
 	.await
 }
@@ -1903,12 +1917,19 @@ async fn run_inner<Context>(
 	reputation_interval: Duration,
 	ah_invulnerables: HashSet<PeerId>,
 	hold_off_duration: Duration,
+	candidate_tracker: CandidateTracker,
 ) -> std::result::Result<(), SubsystemError> {
 	let new_reputation_delay = || futures_timer::Delay::new(reputation_interval).fuse();
 	let mut reputation_delay = new_reputation_delay();
 
-	let mut state =
-		State { metrics, reputation, ah_invulnerables, hold_off_duration, ..Default::default() };
+	let mut state = State {
+		metrics,
+		reputation,
+		ah_invulnerables,
+		hold_off_duration,
+		candidate_tracker,
+		..Default::default()
+	};
 
 	let next_inactivity_stream = tick_stream(ACTIVITY_POLL);
 	futures::pin_mut!(next_inactivity_stream);
@@ -2343,6 +2364,9 @@ async fn handle_collation_fetch_response(
 
 	let _timer = state.metrics.time_handle_collation_request_result();
 
+	let peer_id = pending_collation.peer_id;
+	let para_id = pending_collation.para_id;
+	let advertised_candidate = pending_collation.prospective_candidate.map(|c| c.candidate_hash);
 	let mut metrics_result = Err(());
 
 	let result = match response {
@@ -2463,6 +2487,23 @@ async fn handle_collation_fetch_response(
 		},
 	};
 	state.metrics.on_request(metrics_result);
+
+	match &result {
+		Ok(fetch) => state.candidate_tracker.note(
+			fetch.candidate_receipt.hash(),
+			para_id,
+			CandidateLifecycleEvent::Fetched { peer_id },
+		),
+		Err(_) =>
+			if let Some(candidate_hash) = advertised_candidate {
+				state.candidate_tracker.note(
+					candidate_hash,
+					para_id,
+					CandidateLifecycleEvent::Dropped(DropReason::FetchFailed),
+				)
+			},
+	}
+
 	result
 }
 
@@ -235,6 +235,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		REPUTATION_CHANGE_TEST_INTERVAL,
 		ah_invulnerable_collators,
 		HOLD_OFF_DURATION_DEFAULT_VALUE,
+		Default::default(),
 	);
 
 	let test_fut = test(TestHarness { virtual_overseer, keystore });
@@ -41,13 +41,16 @@ use sp_application_crypto::{AppCrypto, ByteArray};
 use sp_keystore::{Keystore, KeystorePtr};
 
 use polkadot_node_network_protocol::{
-	authority_discovery::AuthorityDiscovery, peer_set::PeerSet, GossipSupportNetworkMessage,
-	PeerId, ValidationProtocols,
+	authority_discovery::AuthorityDiscovery,
+	grid_topology::{SessionGridTopology, TopologyPeerInfo},
+	peer_set::PeerSet,
+	GossipSupportNetworkMessage, PeerId, ValidationProtocols,
 };
 use polkadot_node_subsystem::{
 	messages::{
-		ChainApiMessage, GossipSupportMessage, NetworkBridgeEvent, NetworkBridgeRxMessage,
-		NetworkBridgeTxMessage, RuntimeApiMessage, RuntimeApiRequest,
+		ChainApiMessage, GossipSupportMessage, GossipTopologyReport, GridNeighbor,
+		NetworkBridgeEvent, NetworkBridgeRxMessage, NetworkBridgeTxMessage, RuntimeApiMessage,
+		RuntimeApiRequest, ValidatorConnectivity,
 	},
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
 };
@@ -126,6 +129,9 @@ pub struct GossipSupport<AD> {
 	/// waiting for actual connection.
 	resolved_authorities: HashMap<AuthorityDiscoveryId, HashSet<Multiaddr>>,
 
+	/// Authorities we last requested connections to.
+	requested_authorities: Vec<AuthorityDiscoveryId>,
+
 	/// Actually connected authorities.
 	connected_authorities: HashMap<AuthorityDiscoveryId, PeerId>,
 	/// By `PeerId`.
@@ -138,10 +144,21 @@ pub struct GossipSupport<AD> {
 	/// The oldest session we need to build a topology for because
 	/// the finalized blocks are from a session we haven't built a topology for.
 	finalized_needed_session: Option<u32>,
+	/// The gossip topology of the current session, if we are an authority in it.
+	current_topology: Option<CurrentTopology>,
 	/// Subsystem metrics.
 	metrics: Metrics,
 }
 
+/// The gossip topology we built for the current session.
+struct CurrentTopology {
+	session_index: SessionIndex,
+	our_index: ValidatorIndex,
+	/// The discovery keys of the session, by validator index.
+	authorities: Vec<AuthorityDiscoveryId>,
+	topology: SessionGridTopology,
+}
+
 #[overseer::contextbounds(GossipSupport, prefix = self::overseer)]
 impl<AD> GossipSupport<AD>
 where
@@ -160,11 +177,13 @@ where
 			last_connection_request: None,
 			failure_start: None,
 			resolved_authorities: HashMap::new(),
+			requested_authorities: Vec::new(),
 			connected_authorities: HashMap::new(),
 			connected_peers: HashMap::new(),
 			min_known_session: u32::MAX,
 			authority_discovery,
 			finalized_needed_session: None,
+			current_topology: None,
 			is_authority_now: false,
 			metrics,
 		}
@@ -199,6 +218,11 @@ where
 				FromOrchestra::Communication {
 					msg: GossipSupportMessage::NetworkBridgeUpdate(ev),
 				} => self.handle_connect_disconnect(ev),
+				FromOrchestra::Communication {
+					msg: GossipSupportMessage::GetTopologyReport(tx),
+				} => {
+					let _ = tx.send(self.topology_report());
+				},
 				FromOrchestra::Signal(OverseerSignal::ActiveLeaves(ActiveLeavesUpdate {
 					activated,
 					..
@@ -209,6 +233,7 @@ where
 					if let Err(e) = self.handle_active_leaves(ctx.sender(), leaves).await {
 						gum::debug!(target: LOG_TARGET, error = ?e);
 					}
+					self.metrics.on_topology_report(&self.topology_report());
 				},
 				FromOrchestra::Signal(OverseerSignal::BlockFinalized(_hash, _number)) =>
 					if let Some(session_index) = self.last_session_index {
@@ -285,6 +310,7 @@ where
 						"New session detected",
 					);
 					self.last_session_index = Some(session_index);
+					self.current_topology = None;
 					self.is_authority_now =
 						ensure_i_am_an_authority(&self.keystore, &session_info.discovery_keys)
 							.is_ok();
@@ -336,7 +362,7 @@ where
 
 					// Gossip topology is only relevant for authorities in the current session.
 					let our_index = self.get_key_index_and_update_metrics(&session_info)?;
-					update_gossip_topology(
+					let topology = update_gossip_topology(
 						sender,
 						our_index,
 						session_info.discovery_keys.clone(),
@@ -344,6 +370,12 @@ where
 						session_index,
 					)
 					.await?;
+					self.current_topology = Some(CurrentTopology {
+						session_index,
+						our_index: ValidatorIndex(our_index as _),
+						authorities: session_info.discovery_keys.clone(),
+						topology,
+					});
 				}
 				// authority_discovery is just a cache so let's try every time we try to re-connect
 				// if new authorities are present.
@@ -506,6 +538,7 @@ where
 	) where
 		Sender: overseer::GossipSupportSenderTrait,
 	{
+		self.requested_authorities = authorities.clone();
 		let (_, resolved, _) = self.resolve_authorities(authorities).await;
 
 		let mut changed = Vec::new();
@@ -554,6 +587,7 @@ where
 		Sender: overseer::GossipSupportSenderTrait,
 	{
 		let num = authorities.len();
+		self.requested_authorities = authorities.clone();
 
 		let (validator_addrs, resolved, failures) = self.resolve_authorities(authorities).await;
 
@@ -701,6 +735,55 @@ where
 		}
 	}
 
+	/// Report our neighbors in the current grid topology and the connectivity of the
+	/// authorities we requested connections to.
+	fn topology_report(&self) -> GossipTopologyReport {
+		let mut report = GossipTopologyReport::default();
+
+		if let Some(current) = &self.current_topology {
+			let neighbors = current.topology.compute_grid_neighbors_for(current.our_index);
+			let to_report = |indices: HashSet<ValidatorIndex>| {
+				let mut indices = indices.into_iter().collect::<Vec<_>>();
+				indices.sort();
+				indices
+					.into_iter()
+					.filter_map(|validator_index| {
+						let authority_id =
+							current.authorities.get(validator_index.0 as usize)?.clone();
+						let connected = self.connected_authorities.contains_key(&authority_id);
+						Some(GridNeighbor { validator_index, authority_id, connected })
+					})
+					.collect()
+			};
+
+			report.session_index = Some(current.session_index);
+			report.our_index = Some(current.our_index);
+			if let Some(neighbors) = neighbors {
+				report.row_neighbors = to_report(neighbors.validator_indices_x);
+				report.column_neighbors = to_report(neighbors.validator_indices_y);
+			}
+		}
+
+		report.unresolved_authorities = self
+			.requested_authorities
+			.iter()
+			.filter(|a| !self.resolved_authorities.contains_key(a))
+			.cloned()
+			.collect();
+
+		report.connectivity = ValidatorConnectivity {
+			requested: self.requested_authorities.len(),
+			resolved: self.requested_authorities.len() - report.unresolved_authorities.len(),
+			connected: self
+				.requested_authorities
+				.iter()
+				.filter(|a| self.connected_authorities.contains_key(a))
+				.count(),
+		};
+
+		report
+	}
+
 	/// Check connectivity and report on it in logs.
 	fn check_connectivity(&mut self) {
 		let absolute_connected = self.connected_authorities.len();
@@ -791,7 +874,7 @@ async fn update_gossip_topology(
 	authorities: Vec<AuthorityDiscoveryId>,
 	relay_parent: Hash,
 	session_index: SessionIndex,
-) -> Result<(), util::Error> {
+) -> Result<SessionGridTopology, util::Error> {
 	// retrieve BABE randomness
 	let random_seed = {
 		let (tx, rx) = oneshot::channel();
@@ -831,6 +914,18 @@ async fn update_gossip_topology(
 		(shuffled_indices, canonical_shuffling)
 	};
 
+	let topology = SessionGridTopology::new(
+		shuffled_indices.clone(),
+		canonical_shuffling
+			.iter()
+			.map(|(discovery_id, validator_index)| TopologyPeerInfo {
+				peer_ids: Vec::new(),
+				validator_index: *validator_index,
+				discovery_id: discovery_id.clone(),
+			})
+			.collect(),
+	);
+
 	sender
 		.send_message(NetworkBridgeRxMessage::NewGossipTopology {
 			session: session_index,
@@ -840,7 +935,7 @@ async fn update_gossip_topology(
 		})
 		.await;
 
-	Ok(())
+	Ok(topology)
 }
 
 // Durstenfeld algorithm for the Fisher-Yates shuffle
@@ -14,11 +14,12 @@
 // You should have received a copy of the GNU General Public License
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
+use polkadot_node_subsystem::messages::GossipTopologyReport;
 use polkadot_node_subsystem_util::{
 	metrics,
 	metrics::{
 		prometheus,
-		prometheus::{Gauge, PrometheusError, Registry, U64},
+		prometheus::{Gauge, GaugeVec, Opts, PrometheusError, Registry, U64},
 	},
 };
 
@@ -32,6 +33,12 @@ struct MetricsInner {
 	is_authority: Gauge<U64>,
 	/// Tracks authority status for parachain approval checking.
 	is_parachain_validator: Gauge<U64>,
+	/// Number of grid topology neighbors by dimension and connection state.
+	grid_neighbors: GaugeVec<U64>,
+	/// Number of requested authorities without addresses in authority discovery.
+	unresolved_authorities: Gauge<U64>,
+	/// Number of requested, resolved and connected authorities on the validation peer set.
+	validator_connectivity: GaugeVec<U64>,
 }
 
 impl Metrics {
@@ -68,6 +75,36 @@ impl Metrics {
 			metrics.is_parachain_validator.set(0);
 		}
 	}
+
+	/// Update the topology and connectivity metrics from a fresh report.
+	pub fn on_topology_report(&self, report: &GossipTopologyReport) {
+		if let Some(metrics) = &self.0 {
+			for (dimension, neighbors) in
+				[("row", &report.row_neighbors), ("column", &report.column_neighbors)]
+			{
+				let connected = neighbors.iter().filter(|n| n.connected).count();
+				metrics
+					.grid_neighbors
+					.with_label_values(&[dimension, "connected"])
+					.set(connected as u64);
+				metrics
+					.grid_neighbors
+					.with_label_values(&[dimension, "disconnected"])
+					.set((neighbors.len() - connected) as u64);
+			}
+
+			metrics.unresolved_authorities.set(report.unresolved_authorities.len() as u64);
+
+			let connectivity = &report.connectivity;
+			for (state, count) in [
+				("requested", connectivity.requested),
+				("resolved", connectivity.resolved),
+				("connected", connectivity.connected),
+			] {
+				metrics.validator_connectivity.with_label_values(&[state]).set(count as u64);
+			}
+		}
+	}
 }
 
 impl metrics::Metrics for Metrics {
@@ -85,6 +122,33 @@ impl metrics::Metrics for Metrics {
 				Updates at session boundary.")?,
 				registry,
 			)?,
+			grid_neighbors: prometheus::register(
+				GaugeVec::new(
+					Opts::new(
+						"polkadot_parachain_gossip_grid_neighbors",
+						"Number of our grid topology neighbors in the current session.",
+					),
+					&["dimension", "state"],
+				)?,
+				registry,
+			)?,
+			unresolved_authorities: prometheus::register(
+				Gauge::new(
+					"polkadot_parachain_gossip_unresolved_authorities",
+					"Number of authorities we requested connections to, which could not be resolved by authority discovery.",
+				)?,
+				registry,
+			)?,
+			validator_connectivity: prometheus::register(
+				GaugeVec::new(
+					Opts::new(
+						"polkadot_parachain_gossip_validator_connectivity",
+						"Number of requested, resolved and connected authorities on the validation peer set.",
+					),
+					&["state"],
+				)?,
+				registry,
+			)?,
 		};
 		Ok(Metrics(Some(metrics)))
 	}
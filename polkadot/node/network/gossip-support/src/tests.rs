@@ -1377,6 +1377,108 @@ fn issues_a_connection_request_when_last_request_was_mostly_unresolved() {
 	assert!(state.last_failure.is_none());
 }
 
+#[test]
+fn reports_grid_neighbors_and_unresolved_authorities() {
+	let hash = Hash::repeat_byte(0xAA);
+	let mock_authority_discovery =
+		MockAuthorityDiscovery::new(PAST_PRESENT_FUTURE_AUTHORITIES.clone());
+	let state = make_subsystem_with_authority_discovery(mock_authority_discovery.clone());
+	let bob: AuthorityDiscoveryId = Sr25519Keyring::Bob.public().into();
+	let charlie: AuthorityDiscoveryId = Sr25519Keyring::Charlie.public().into();
+	state.authority_discovery.addrs.lock().remove(&bob);
+
+	test_harness(state, |mut virtual_overseer| async move {
+		let overseer = &mut virtual_overseer;
+		overseer_signal_active_leaves(overseer, hash).await;
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionIndexForChild(tx),
+			)) => {
+				tx.send(Ok(1)).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::SessionInfo(_, tx),
+			)) => {
+				tx.send(Ok(Some(make_session_info()))).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+				_,
+				RuntimeApiRequest::Authorities(tx),
+			)) => {
+				tx.send(Ok(AUTHORITIES.clone())).unwrap();
+			}
+		);
+
+		assert_matches!(
+			overseer_recv(overseer).await,
+			AllMessages::NetworkBridgeTx(
+				NetworkBridgeTxMessage::ConnectToResolvedValidators { .. }
+			)
+		);
+		provide_info_for_finalized(overseer, 1).await;
+
+		test_neighbors(overseer, 1).await;
+
+		let charlie_peer = *mock_authority_discovery
+			.authorities()
+			.iter()
+			.find(|(_, ids)| ids.contains(&charlie))
+			.unwrap()
+			.0;
+		let msg = GossipSupportMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerConnected(
+			charlie_peer,
+			ObservedRole::Authority,
+			ValidationVersion::V3.into(),
+			Some(HashSet::from([charlie.clone()])),
+		));
+		overseer.send(FromOrchestra::Communication { msg }).await;
+
+		let (tx, rx) = oneshot::channel();
+		overseer
+			.send(FromOrchestra::Communication { msg: GossipSupportMessage::GetTopologyReport(tx) })
+			.await;
+		let report = rx.timeout(TIMEOUT).await.expect("report timeout").unwrap();
+
+		assert_eq!(report.session_index, Some(1));
+		assert_eq!(report.our_index, Some(ValidatorIndex(6)));
+		assert_eq!(
+			report.row_neighbors,
+			vec![GridNeighbor {
+				validator_index: ROW_NEIGHBORS[0],
+				authority_id: charlie.clone(),
+				connected: true,
+			}],
+		);
+		assert_eq!(
+			report.column_neighbors.iter().map(|n| n.validator_index).collect::<Vec<_>>(),
+			COLUMN_NEIGHBORS.clone(),
+		);
+		assert!(report.column_neighbors.iter().all(|n| !n.connected));
+		assert_eq!(report.unresolved_authorities, vec![bob]);
+		assert_eq!(
+			report.connectivity,
+			ValidatorConnectivity {
+				requested: AUTHORITIES_WITHOUT_US.len(),
+				resolved: AUTHORITIES_WITHOUT_US.len() - 1,
+				connected: 1,
+			},
+		);
+
+		virtual_overseer
+	});
+}
+
 // Test that topology is updated for all sessions we still have unfinalized blocks for.
 #[test]
 fn updates_topology_for_all_finalized_blocks() {
@@ -100,6 +100,10 @@ pub enum Protocol {
 	/// Protocol for chunk fetching version 2, used by availability distribution and availability
 	/// recovery.
 	ChunkFetchingV2,
+
+	/// Protocol for pushing missing chunks of included candidates to their holders, used by
+	/// availability distribution.
+	ChunkPushingV1,
 }
 
 /// Minimum bandwidth we expect for validators - 500Mbit/s is the recommendation, so approximately
@@ -248,6 +252,16 @@ impl Protocol {
 				ATTESTED_CANDIDATE_TIMEOUT,
 				tx,
 			),
+			Protocol::ChunkPushingV1 => N::request_response_config(
+				name,
+				legacy_names,
+				// The request carries the chunk.
+				POV_RESPONSE_SIZE,
+				// Responses are just confirmation.
+				100,
+				CHUNK_REQUEST_TIMEOUT,
+				tx,
+			),
 		}
 	}
 
@@ -290,6 +304,8 @@ impl Protocol {
 				);
 				size as usize
 			},
+			// Chunks are only pushed by the few validators repairing a candidate, which is rare.
+			Protocol::ChunkPushingV1 => 10,
 		}
 	}
 
@@ -308,6 +324,7 @@ impl Protocol {
 			Protocol::AttestedCandidateV2 => None,
 			Protocol::CollationFetchingV2 => None,
 			Protocol::ChunkFetchingV2 => None,
+			Protocol::ChunkPushingV1 => None,
 		}
 	}
 }
@@ -364,6 +381,7 @@ impl ReqProtocolNames {
 			Protocol::PoVFetchingV1 => "/req_pov/1",
 			Protocol::AvailableDataFetchingV1 => "/req_available_data/1",
 			Protocol::DisputeSendingV1 => "/send_dispute/1",
+			Protocol::ChunkPushingV1 => "/push_chunk/1",
 
 			// V2:
 			Protocol::CollationFetchingV2 => "/req_collation/2",
@@ -45,6 +45,8 @@ pub enum Requests {
 	/// Fetch a collation from a collator which previously announced it.
 	/// Compared to V1 it requires specifying which candidate is requested by its hash.
 	CollationFetchingV2(OutgoingRequest<v2::CollationFetchingRequest>),
+	/// Push a missing availability chunk to its holder.
+	ChunkPushingV1(OutgoingRequest<v1::ChunkPushingRequest>),
 }
 
 impl Requests {
@@ -64,6 +66,7 @@ impl Requests {
 			Self::AvailableDataFetchingV1(r) => r.encode_request(),
 			Self::DisputeSendingV1(r) => r.encode_request(),
 			Self::AttestedCandidateV2(r) => r.encode_request(),
+			Self::ChunkPushingV1(r) => r.encode_request(),
 		}
 	}
 }
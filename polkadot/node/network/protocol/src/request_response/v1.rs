@@ -22,7 +22,7 @@ use polkadot_node_primitives::{
 	AvailableData, DisputeMessage, ErasureChunk, PoV, Proof, UncheckedDisputeMessage,
 };
 use polkadot_primitives::{
-	CandidateHash, CandidateReceiptV2 as CandidateReceipt, Hash, HeadData, Id as ParaId,
+	CandidateHash, CandidateReceiptV2 as CandidateReceipt, CoreIndex, Hash, HeadData, Id as ParaId,
 	ValidatorIndex,
 };
 
@@ -212,3 +212,32 @@ impl IsRequest for DisputeRequest {
 	type Response = DisputeResponse;
 	const PROTOCOL: Protocol = Protocol::DisputeSendingV1;
 }
+
+/// Push an availability chunk to the validator it is assigned to.
+///
+/// Used for re-seeding the chunks of included candidates which went missing.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct ChunkPushingRequest {
+	/// The candidate the chunk belongs to.
+	pub candidate_receipt: CandidateReceipt,
+	/// The core the candidate occupied, needed for checking the chunk index.
+	pub core_index: CoreIndex,
+	/// The chunk assigned to the recipient.
+	pub chunk: ErasureChunk,
+}
+
+/// Possible responses to a `ChunkPushingRequest`.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum ChunkPushingResponse {
+	/// The recipient holds the chunk now.
+	#[codec(index = 0)]
+	Stored,
+	/// The recipient did not accept the chunk, e.g. because it doesn't know the candidate.
+	#[codec(index = 1)]
+	Rejected,
+}
+
+impl IsRequest for ChunkPushingRequest {
+	type Response = ChunkPushingResponse;
+	const PROTOCOL: Protocol = Protocol::ChunkPushingV1;
+}
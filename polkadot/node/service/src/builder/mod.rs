@@ -46,6 +46,7 @@ use polkadot_node_network_protocol::{
 	request_response::{IncomingRequest, ReqProtocolNames},
 };
 use polkadot_node_subsystem_types::DefaultSubsystemClient;
+use polkadot_node_subsystem_util::candidate_tracker::CandidateTracker;
 use polkadot_overseer::{Handle, OverseerConnector};
 use polkadot_primitives::Block;
 use sc_client_api::Backend;
@@ -87,6 +88,8 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub prepare_workers_soft_max_num: Option<usize>,
 	/// An optional absolute number of pvf workers that can be spawned in the pvf prepare pool.
 	pub prepare_workers_hard_max_num: Option<usize>,
+	/// Whether the pvf execute workers should collect a detailed profile of every execution.
+	pub execute_workers_profiling: bool,
 	/// How long finalized data should be kept in the availability store (in hours)
 	pub keep_finalized_for: Option<u32>,
 	pub overseer_gen: OverseerGenerator,
@@ -109,6 +112,7 @@ pub struct NewFull {
 	pub sync_service: Arc<sc_network_sync::SyncingService<Block>>,
 	pub rpc_handlers: RpcHandlers,
 	pub backend: Arc<FullBackend>,
+	pub transaction_pool: Arc<sc_transaction_pool::TransactionPoolHandle<Block, FullClient>>,
 }
 
 pub struct PolkadotServiceBuilder<OverseerGenerator, Network>
@@ -121,6 +125,7 @@ where
 	overseer_connector: OverseerConnector,
 	partial_components: PolkadotPartialComponents<SelectRelayChain<FullBackend>>,
 	net_config: FullNetworkConfiguration<Block, <Block as BlockT>::Hash, Network>,
+	candidate_tracker: Option<CandidateTracker>,
 }
 
 impl<OverseerGenerator, Network> PolkadotServiceBuilder<OverseerGenerator, Network>
@@ -155,8 +160,17 @@ where
 			SelectRelayChain::new_longest_chain(basics.backend.clone())
 		};
 
-		let partial_components =
-			new_partial::<SelectRelayChain<_>>(&mut config, basics, select_chain)?;
+		// Only validators run the chain selection subsystem.
+		let rpc_overseer_handle = config.role.is_authority().then(|| overseer_handle.clone());
+		// The subsystems recording candidate lifecycles only run on validators as well.
+		let candidate_tracker = config.role.is_authority().then(CandidateTracker::new);
+		let partial_components = new_partial::<SelectRelayChain<_>>(
+			&mut config,
+			basics,
+			select_chain,
+			rpc_overseer_handle,
+			candidate_tracker.clone(),
+		)?;
 
 		let net_config = sc_network::config::FullNetworkConfiguration::<_, _, Network>::new(
 			&config.network,
@@ -169,6 +183,7 @@ where
 			overseer_connector,
 			partial_components,
 			net_config,
+			candidate_tracker,
 		})
 	}
 
@@ -206,6 +221,7 @@ where
 					execute_workers_max_num,
 					prepare_workers_soft_max_num,
 					prepare_workers_hard_max_num,
+					execute_workers_profiling,
 					keep_finalized_for,
 					invulnerable_ah_collators,
 					collator_protocol_hold_off,
@@ -224,6 +240,7 @@ where
 						(rpc_extensions_builder, import_setup, rpc_setup, slot_duration, mut telemetry),
 				},
 			mut net_config,
+			candidate_tracker,
 		} = self;
 
 		let role = config.role;
@@ -358,6 +375,9 @@ where
 		let (chunk_req_v2_receiver, cfg) =
 			IncomingRequest::get_config_receiver::<_, Network>(&req_protocol_names);
 		net_config.add_request_response_protocol(cfg);
+		let (chunk_push_receiver, cfg) =
+			IncomingRequest::get_config_receiver::<_, Network>(&req_protocol_names);
+		net_config.add_request_response_protocol(cfg);
 
 		let grandpa_hard_forks = if config.chain_spec.is_kusama() {
 			grandpa_support::kusama_hard_forks()
@@ -399,6 +419,7 @@ where
 					pvf_execute_workers_max_num: execute_workers_max_num.unwrap_or(4),
 					pvf_prepare_workers_soft_max_num: prepare_workers_soft_max_num.unwrap_or(1),
 					pvf_prepare_workers_hard_max_num: prepare_workers_hard_max_num.unwrap_or(2),
+					pvf_execute_profiling: execute_workers_profiling,
 				})
 			} else {
 				None
@@ -444,6 +465,7 @@ where
 				pov_req_receiver,
 				chunk_req_v1_receiver,
 				chunk_req_v2_receiver,
+				chunk_push_receiver,
 				candidate_req_v2_receiver,
 				approval_voting_config,
 				dispute_req_receiver,
@@ -452,6 +474,7 @@ where
 				fetch_chunks_threshold,
 				invulnerable_ah_collators,
 				collator_protocol_hold_off,
+				candidate_tracker: candidate_tracker.unwrap_or_default(),
 			})
 		};
 
@@ -852,6 +875,7 @@ where
 			sync_service,
 			rpc_handlers,
 			backend,
+			transaction_pool,
 		})
 	}
 }
@@ -31,6 +31,7 @@ use polkadot_node_network_protocol::{
 		v1 as request_v1, v2 as request_v2, IncomingRequestReceiver, ReqProtocolNames,
 	},
 };
+use polkadot_node_subsystem_util::candidate_tracker::{CandidateTracker, TracksCandidates};
 #[cfg(any(feature = "malus", test))]
 pub use polkadot_overseer::{dummy::dummy_overseer_builder, HeadSupportsParachains};
 use polkadot_overseer::{
@@ -129,6 +130,8 @@ pub struct ExtendedOverseerGenArgs {
 	pub chunk_req_v1_receiver: IncomingRequestReceiver<request_v1::ChunkFetchingRequest>,
 	/// Erasure chunk request v2 receiver.
 	pub chunk_req_v2_receiver: IncomingRequestReceiver<request_v2::ChunkFetchingRequest>,
+	/// Receiver for incoming pushed erasure chunks.
+	pub chunk_push_receiver: IncomingRequestReceiver<request_v1::ChunkPushingRequest>,
 	/// Receiver for incoming candidate requests.
 	pub candidate_req_v2_receiver: IncomingRequestReceiver<request_v2::AttestedCandidateRequest>,
 	/// Configuration for the approval voting subsystem.
@@ -147,6 +150,8 @@ pub struct ExtendedOverseerGenArgs {
 	pub invulnerable_ah_collators: HashSet<polkadot_node_network_protocol::PeerId>,
 	/// Override for `HOLD_OFF_DURATION` constant .
 	pub collator_protocol_hold_off: Option<Duration>,
+	/// Shared tracker recording the lifecycle of recent candidates.
+	pub candidate_tracker: CandidateTracker,
 }
 
 /// Obtain a prepared validator `Overseer`, that is initialized with all default values.
@@ -175,6 +180,7 @@ pub fn validator_overseer_builder<Spawner, RuntimeClient>(
 		pov_req_receiver,
 		chunk_req_v1_receiver,
 		chunk_req_v2_receiver,
+		chunk_push_receiver,
 		candidate_req_v2_receiver,
 		approval_voting_config,
 		dispute_req_receiver,
@@ -183,6 +189,7 @@ pub fn validator_overseer_builder<Spawner, RuntimeClient>(
 		fetch_chunks_threshold,
 		invulnerable_ah_collators,
 		collator_protocol_hold_off,
+		candidate_tracker,
 	}: ExtendedOverseerGenArgs,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -259,6 +266,7 @@ where
 				pov_req_receiver,
 				chunk_req_v1_receiver,
 				chunk_req_v2_receiver,
+				chunk_push_receiver,
 			},
 			req_protocol_names.clone(),
 			Metrics::register(registry)?,
@@ -280,10 +288,10 @@ where
 			keystore.clone(),
 			Metrics::register(registry)?,
 		))
-		.candidate_backing(CandidateBackingSubsystem::new(
-			keystore.clone(),
-			Metrics::register(registry)?,
-		))
+		.candidate_backing(
+			CandidateBackingSubsystem::new(keystore.clone(), Metrics::register(registry)?)
+				.with_candidate_tracker(candidate_tracker.clone()),
+		)
 		.candidate_validation(CandidateValidationSubsystem::with_config(
 			candidate_validation_config,
 			keystore.clone(),
@@ -307,9 +315,12 @@ where
 					collator_protocol_hold_off,
 				},
 			};
-			CollatorProtocolSubsystem::new(side)
+			CollatorProtocolSubsystem::new(side).with_candidate_tracker(candidate_tracker.clone())
 		})
-		.provisioner(ProvisionerSubsystem::new(Metrics::register(registry)?))
+		.provisioner(
+			ProvisionerSubsystem::new(Metrics::register(registry)?)
+				.with_candidate_tracker(candidate_tracker.clone()),
+		)
 		.runtime_api(RuntimeApiSubsystem::new(
 			runtime_client.clone(),
 			Metrics::register(registry)?,
@@ -336,19 +347,25 @@ where
 			authority_discovery_service.clone(),
 			Metrics::register(registry)?,
 		))
-		.dispute_coordinator(DisputeCoordinatorSubsystem::new(
-			parachains_db.clone(),
-			dispute_coordinator_config,
-			keystore.clone(),
-			Metrics::register(registry)?,
-		))
+		.dispute_coordinator(
+			DisputeCoordinatorSubsystem::new(
+				parachains_db.clone(),
+				dispute_coordinator_config,
+				keystore.clone(),
+				Metrics::register(registry)?,
+			)
+			.with_candidate_tracker(candidate_tracker.clone()),
+		)
 		.dispute_distribution(DisputeDistributionSubsystem::new(
 			keystore.clone(),
 			dispute_req_receiver,
 			authority_discovery_service.clone(),
 			Metrics::register(registry)?,
 		))
-		.chain_selection(ChainSelectionSubsystem::new(chain_selection_config, parachains_db))
+		.chain_selection(
+			ChainSelectionSubsystem::new(chain_selection_config, parachains_db)
+				.with_candidate_tracker(candidate_tracker),
+		)
 		.prospective_parachains(ProspectiveParachainsSubsystem::new(Metrics::register(registry)?))
 		.activation_external_listeners(Default::default())
 		.active_leaves(Default::default())
@@ -192,6 +192,15 @@ pub fn prepare_test(
 			sc_network::NetworkWorker<Block, Hash>,
 		>(&state.req_protocol_names);
 
+	let (chunk_push_receiver, chunk_push_cfg) =
+		IncomingRequest::<v1::ChunkPushingRequest>::get_config_receiver::<
+			Block,
+			sc_network::NetworkWorker<Block, Hash>,
+		>(&state.req_protocol_names);
+
+	// Chunks are not pushed in the benchmark, keep the inbound queue alive anyway.
+	std::mem::forget(chunk_push_cfg);
+
 	let (network, network_interface, network_receiver) = new_network(
 		&state.config,
 		&dependencies,
@@ -265,6 +274,7 @@ pub fn prepare_test(
 					pov_req_receiver,
 					chunk_req_v1_receiver,
 					chunk_req_v2_receiver,
+					chunk_push_receiver,
 				},
 				state.req_protocol_names.clone(),
 				Metrics::try_register(&dependencies.registry).unwrap(),
@@ -572,6 +572,26 @@ pub enum AvailabilityStoreMessage {
 		tx: oneshot::Sender<Result<(), ()>>,
 	},
 
+	/// Store an `ErasureChunk` of a candidate included in a finalized block in the AV store.
+	///
+	/// Unlike `StoreChunk`, the candidate is recorded as finalized if the AV store doesn't know it,
+	/// e.g. because its database was lost. Only to be used for candidates known to be included in
+	/// a finalized block.
+	///
+	/// Return `Ok(())` if the store operation succeeded, `Err(())` if it failed.
+	StoreFinalizedChunk {
+		/// A hash of the candidate this chunk belongs to.
+		candidate_hash: CandidateHash,
+		/// The number of validators in the session of the candidate.
+		n_validators: u32,
+		/// Validator index. May not be equal to the chunk index.
+		validator_index: ValidatorIndex,
+		/// The chunk itself.
+		chunk: ErasureChunk,
+		/// Sending side of the channel to send result to.
+		tx: oneshot::Sender<Result<(), ()>>,
+	},
+
 	/// Computes and checks the erasure root of `AvailableData` before storing all of its chunks in
 	/// the AV store.
 	///
@@ -658,6 +678,69 @@ pub enum ChainSelectionMessage {
 	/// The passed blocks must be marked as reverted, and their children must be marked
 	/// as non-viable.
 	RevertBlocks(Vec<(BlockNumber, Hash)>),
+	/// Request a snapshot of the viability of all unfinalized blocks known to the subsystem.
+	///
+	/// The reasons for reversions are only kept in memory and are lost on restart.
+	InspectViability(oneshot::Sender<ChainViability>),
+}
+
+/// The approval status of a block, as tracked by chain selection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BlockApprovalStatus {
+	/// The block is approved.
+	Approved,
+	/// The block is not yet approved.
+	Unapproved,
+	/// The block has been unapproved for too long and is considered stagnant.
+	Stagnant,
+}
+
+/// The reason a block was explicitly reverted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevertReason {
+	/// A dispute concluded against a candidate included in the block.
+	DisputeConcludedAgainst,
+	/// A revert digest log in the header of a descendant demanded the reversion.
+	RevertLog {
+		/// The block whose header carried the revert log.
+		reported_by: Hash,
+	},
+	/// The block was reverted, but the reason is no longer known, e.g. because it was
+	/// reverted before the node restarted.
+	Unknown,
+}
+
+/// The viability of a single unfinalized block.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockViability {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The number of the block.
+	pub number: BlockNumber,
+	/// The hash of the parent block.
+	pub parent_hash: Hash,
+	/// The weight of the block, as used for fork-choice.
+	pub weight: BlockWeight,
+	/// Whether the block is currently a viable leaf.
+	pub is_leaf: bool,
+	/// The approval status of the block.
+	pub approval: BlockApprovalStatus,
+	/// If the block itself was explicitly reverted, the reason why.
+	pub revert_reason: Option<RevertReason>,
+	/// The earliest unviable ancestor of the block, if any.
+	pub earliest_unviable_ancestor: Option<Hash>,
+	/// Whether the block is viable, i.e. it is not reverted, not stagnant and has no
+	/// unviable ancestor.
+	pub viable: bool,
+}
+
+/// A snapshot of the viability of all unfinalized blocks known to chain selection.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainViability {
+	/// The viable leaves in descending order by score.
+	pub leaves: Vec<Hash>,
+	/// All unfinalized blocks, ordered by ascending block number.
+	pub blocks: Vec<BlockViability>,
 }
 
 /// A sender for the result of a runtime API request.
@@ -1230,6 +1313,52 @@ pub enum GossipSupportMessage {
 	/// Dummy constructor, so we can receive networking events.
 	#[from]
 	NetworkBridgeUpdate(NetworkBridgeEvent<net_protocol::GossipSupportNetworkMessage>),
+	/// Get a report of our current grid topology neighbors and the state of the
+	/// connections requested on the validation peer set.
+	GetTopologyReport(oneshot::Sender<GossipTopologyReport>),
+}
+
+/// A neighbor of ours in the gossip grid topology.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GridNeighbor {
+	/// The index of the neighbor in the session.
+	pub validator_index: ValidatorIndex,
+	/// The authority discovery key of the neighbor.
+	pub authority_id: AuthorityDiscoveryId,
+	/// Whether we are currently connected to the neighbor.
+	pub connected: bool,
+}
+
+/// Connectivity of the validation peer set, in number of authorities.
+///
+/// Gossip support only requests connections on the validation peer set, connections on the
+/// collation peer set are managed by the collator protocol.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ValidatorConnectivity {
+	/// Authorities we requested a connection to.
+	pub requested: usize,
+	/// Requested authorities that were resolved by authority discovery.
+	pub resolved: usize,
+	/// Requested authorities we are connected to.
+	pub connected: usize,
+}
+
+/// Gossip topology and connectivity report of the gossip support subsystem.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GossipTopologyReport {
+	/// The session of the current topology, `None` if we are not an authority in the
+	/// current session.
+	pub session_index: Option<SessionIndex>,
+	/// Our index in the current topology.
+	pub our_index: Option<ValidatorIndex>,
+	/// Our neighbors in the same row of the grid.
+	pub row_neighbors: Vec<GridNeighbor>,
+	/// Our neighbors in the same column of the grid.
+	pub column_neighbors: Vec<GridNeighbor>,
+	/// Requested authorities for which authority discovery has no addresses.
+	pub unresolved_authorities: Vec<AuthorityDiscoveryId>,
+	/// Connectivity of the authorities we requested connections to.
+	pub connectivity: ValidatorConnectivity,
 }
 
 /// Request introduction of a seconded candidate into the prospective parachains subsystem.
@@ -0,0 +1,366 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tracking of the lifecycle of recent candidates across subsystems.
+//!
+//! Subsystems involved in getting a candidate from a collator into a finalized relay chain block
+//! note the steps they observe on a shared [`CandidateTracker`]. The resulting timelines can be
+//! queried per para, which allows answering why a candidate did not make it on chain without
+//! digging through the logs of several subsystems.
+//!
+//! Only validators record timelines. The subsystems observing the lifecycle of candidates are not
+//! run by non-authority full nodes, which could only learn about the on-chain stages from the
+//! candidate events anyway.
+
+use std::{
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use parking_lot::Mutex;
+use polkadot_node_network_protocol::PeerId;
+use polkadot_primitives::{CandidateHash, Hash, Id as ParaId};
+use schnellru::{ByLength, LruMap};
+
+/// The maximum number of candidates we keep timelines for.
+pub const MAX_TRACKED_CANDIDATES: u32 = 4096;
+
+/// The maximum number of events recorded for a single candidate.
+const MAX_EVENTS_PER_CANDIDATE: usize = 32;
+
+/// A step in the lifecycle of a candidate, as observed by this node.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CandidateLifecycleEvent {
+	/// A collator advertised the candidate to us.
+	Advertised {
+		/// The collator that advertised the candidate.
+		peer_id: PeerId,
+		/// The relay parent of the candidate.
+		relay_parent: Hash,
+	},
+	/// We fetched the collation from a collator.
+	Fetched {
+		/// The collator we fetched the collation from.
+		peer_id: PeerId,
+	},
+	/// We validated and seconded the candidate.
+	Seconded,
+	/// The candidate gathered enough backing votes.
+	Backed,
+	/// Our provisioner selected the candidate for a relay chain block built on `relay_parent`.
+	Provisioned {
+		/// The relay chain block the block including the candidate is built on.
+		relay_parent: Hash,
+	},
+	/// The candidate was backed on chain, in a relay chain block, and is pending availability.
+	BackedOnChain {
+		/// The relay chain block the candidate was backed in.
+		block_hash: Hash,
+	},
+	/// Our provisioner saw availability bitfields of a supermajority of validators for the
+	/// candidate when building on `relay_parent`.
+	///
+	/// The candidate gets included by the first relay chain block carrying enough of these
+	/// bitfields.
+	Available {
+		/// The relay chain block the bitfields were signed for.
+		relay_parent: Hash,
+	},
+	/// The candidate became available on chain and was included, i.e. enacted.
+	Included {
+		/// The relay chain block in which the candidate was included.
+		block_hash: Hash,
+	},
+	/// The relay chain block in which the candidate was enacted has been approved.
+	Approved {
+		/// The approved relay chain block.
+		block_hash: Hash,
+	},
+	/// The relay chain block in which the candidate was enacted has been finalized.
+	Finalized {
+		/// The finalized relay chain block.
+		block_hash: Hash,
+	},
+	/// The candidate was dropped.
+	Dropped(DropReason),
+}
+
+/// Why a candidate was dropped.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+	/// Fetching the advertised collation failed.
+	FetchFailed,
+	/// The candidate failed validation.
+	Invalid,
+	/// Prospective parachains did not accept the candidate for seconding.
+	RejectedByProspectiveParachains,
+	/// The candidate did not become available in time after being included.
+	AvailabilityTimedOut,
+}
+
+/// A recorded lifecycle event.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TimelineEntry {
+	/// When the event was noted, in milliseconds since the UNIX epoch.
+	pub timestamp: u64,
+	/// The event.
+	pub event: CandidateLifecycleEvent,
+}
+
+/// The recorded lifecycle of a single candidate.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CandidateTimeline {
+	/// The hash of the candidate.
+	pub candidate_hash: CandidateHash,
+	/// The para the candidate belongs to.
+	pub para_id: ParaId,
+	/// The recorded events, in the order they were noted.
+	pub events: Vec<TimelineEntry>,
+}
+
+struct Inner {
+	timelines: LruMap<CandidateHash, CandidateTimeline>,
+	/// Candidates enacted by relay chain blocks, used to resolve approval and finality.
+	enacted: LruMap<Hash, Vec<CandidateHash>>,
+}
+
+impl Inner {
+	fn note(
+		&mut self,
+		candidate_hash: CandidateHash,
+		para_id: ParaId,
+		event: CandidateLifecycleEvent,
+	) {
+		if let CandidateLifecycleEvent::Included { block_hash } = &event {
+			match self.enacted.get(block_hash) {
+				Some(candidates) =>
+					if !candidates.contains(&candidate_hash) {
+						candidates.push(candidate_hash)
+					},
+				None => {
+					self.enacted.insert(*block_hash, vec![candidate_hash]);
+				},
+			}
+		}
+
+		let Some(timeline) = self.timelines.get_or_insert(candidate_hash, || CandidateTimeline {
+			candidate_hash,
+			para_id,
+			events: Vec::new(),
+		}) else {
+			return
+		};
+
+		if timeline.events.len() >= MAX_EVENTS_PER_CANDIDATE ||
+			timeline.events.iter().any(|entry| entry.event == event)
+		{
+			return
+		}
+
+		timeline.events.push(TimelineEntry { timestamp: now_millis(), event });
+	}
+
+	fn note_block(&mut self, block_hash: &Hash, event: CandidateLifecycleEvent) {
+		let Some(candidates) = self.enacted.peek(block_hash).cloned() else { return };
+
+		for candidate_hash in candidates {
+			if let Some(timeline) = self.timelines.peek(&candidate_hash) {
+				let para_id = timeline.para_id;
+				self.note(candidate_hash, para_id, event.clone());
+			}
+		}
+	}
+}
+
+/// A shared handle for recording and querying candidate lifecycles.
+///
+/// The default handle is disabled and does not record anything.
+#[derive(Clone, Default)]
+pub struct CandidateTracker(Option<Arc<Mutex<Inner>>>);
+
+impl CandidateTracker {
+	/// Create a new, enabled, candidate tracker.
+	pub fn new() -> Self {
+		Self(Some(Arc::new(Mutex::new(Inner {
+			timelines: LruMap::new(ByLength::new(MAX_TRACKED_CANDIDATES)),
+			enacted: LruMap::new(ByLength::new(MAX_TRACKED_CANDIDATES)),
+		}))))
+	}
+
+	/// Whether the tracker records anything.
+	///
+	/// Useful for skipping work only needed for noting events on a disabled tracker.
+	pub fn is_enabled(&self) -> bool {
+		self.0.is_some()
+	}
+
+	/// Record a lifecycle event of a candidate.
+	///
+	/// Noting the same event for a candidate more than once has no effect.
+	pub fn note(
+		&self,
+		candidate_hash: CandidateHash,
+		para_id: ParaId,
+		event: CandidateLifecycleEvent,
+	) {
+		if let Some(inner) = &self.0 {
+			inner.lock().note(candidate_hash, para_id, event);
+		}
+	}
+
+	/// Record the approval of a relay chain block for all candidates it enacted.
+	pub fn note_block_approved(&self, block_hash: Hash) {
+		if let Some(inner) = &self.0 {
+			inner
+				.lock()
+				.note_block(&block_hash, CandidateLifecycleEvent::Approved { block_hash });
+		}
+	}
+
+	/// Record the finalization of a relay chain block for all candidates it enacted.
+	pub fn note_block_finalized(&self, block_hash: Hash) {
+		if let Some(inner) = &self.0 {
+			let mut inner = inner.lock();
+			inner.note_block(&block_hash, CandidateLifecycleEvent::Finalized { block_hash });
+			inner.enacted.remove(&block_hash);
+		}
+	}
+
+	/// Returns the timelines of the most recently active candidates of a para, most recent
+	/// first.
+	pub fn recent_candidates(&self, para_id: ParaId, limit: usize) -> Vec<CandidateTimeline> {
+		let Some(inner) = &self.0 else { return Vec::new() };
+
+		inner
+			.lock()
+			.timelines
+			.iter()
+			.map(|(_, timeline)| timeline)
+			.filter(|timeline| timeline.para_id == para_id)
+			.take(limit)
+			.cloned()
+			.collect()
+	}
+}
+
+/// Subsystems recording the lifecycle events they observe on a [`CandidateTracker`].
+pub trait TracksCandidates: Sized {
+	/// The tracker the subsystem records on.
+	fn candidate_tracker_mut(&mut self) -> &mut CandidateTracker;
+
+	/// Record the lifecycle events observed by the subsystem on the given tracker.
+	///
+	/// Subsystems start with a disabled tracker.
+	fn with_candidate_tracker(mut self, candidate_tracker: CandidateTracker) -> Self {
+		*self.candidate_tracker_mut() = candidate_tracker;
+		self
+	}
+}
+
+fn now_millis() -> u64 {
+	SystemTime::now()
+		.duration_since(UNIX_EPOCH)
+		.map(|d| d.as_millis() as u64)
+		.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn events(timeline: &CandidateTimeline) -> Vec<CandidateLifecycleEvent> {
+		timeline.events.iter().map(|entry| entry.event.clone()).collect()
+	}
+
+	#[test]
+	fn disabled_tracker_records_nothing() {
+		let tracker = CandidateTracker::default();
+		assert!(!tracker.is_enabled());
+		tracker.note(
+			CandidateHash(Hash::repeat_byte(1)),
+			1.into(),
+			CandidateLifecycleEvent::Seconded,
+		);
+
+		assert!(tracker.recent_candidates(1.into(), 10).is_empty());
+	}
+
+	#[test]
+	fn recent_candidates_are_filtered_by_para_and_most_recent_first() {
+		let tracker = CandidateTracker::new();
+		let a = CandidateHash(Hash::repeat_byte(1));
+		let b = CandidateHash(Hash::repeat_byte(2));
+		let c = CandidateHash(Hash::repeat_byte(3));
+
+		tracker.note(a, 1.into(), CandidateLifecycleEvent::Seconded);
+		tracker.note(b, 2.into(), CandidateLifecycleEvent::Seconded);
+		tracker.note(c, 1.into(), CandidateLifecycleEvent::Seconded);
+		// Noting an event again does not duplicate it.
+		tracker.note(a, 1.into(), CandidateLifecycleEvent::Seconded);
+		tracker.note(a, 1.into(), CandidateLifecycleEvent::Backed);
+
+		let recent = tracker.recent_candidates(1.into(), 10);
+		assert_eq!(recent.iter().map(|t| t.candidate_hash).collect::<Vec<_>>(), vec![a, c]);
+		assert_eq!(
+			events(&recent[0]),
+			vec![CandidateLifecycleEvent::Seconded, CandidateLifecycleEvent::Backed]
+		);
+		assert_eq!(tracker.recent_candidates(1.into(), 1).len(), 1);
+	}
+
+	#[test]
+	fn block_approval_and_finality_apply_to_enacted_candidates() {
+		let tracker = CandidateTracker::new();
+		let candidate = CandidateHash(Hash::repeat_byte(1));
+		let other = CandidateHash(Hash::repeat_byte(2));
+		let block_hash = Hash::repeat_byte(0xAA);
+
+		tracker.note(
+			candidate,
+			1.into(),
+			CandidateLifecycleEvent::Available { relay_parent: Hash::repeat_byte(0xA9) },
+		);
+		tracker.note(candidate, 1.into(), CandidateLifecycleEvent::Included { block_hash });
+		tracker.note(other, 1.into(), CandidateLifecycleEvent::Backed);
+		tracker.note_block_approved(block_hash);
+		tracker.note_block_finalized(block_hash);
+		// Unrelated blocks are ignored.
+		tracker.note_block_finalized(Hash::repeat_byte(0xBB));
+		// Being backed on chain does not enact a candidate.
+		tracker.note(other, 1.into(), CandidateLifecycleEvent::BackedOnChain { block_hash });
+		tracker.note_block_finalized(block_hash);
+
+		let recent = tracker.recent_candidates(1.into(), 10);
+		let timeline = recent.iter().find(|t| t.candidate_hash == candidate).unwrap();
+		assert_eq!(
+			events(timeline),
+			vec![
+				CandidateLifecycleEvent::Available { relay_parent: Hash::repeat_byte(0xA9) },
+				CandidateLifecycleEvent::Included { block_hash },
+				CandidateLifecycleEvent::Approved { block_hash },
+				CandidateLifecycleEvent::Finalized { block_hash },
+			]
+		);
+		let timeline = recent.iter().find(|t| t.candidate_hash == other).unwrap();
+		assert_eq!(
+			events(timeline),
+			vec![
+				CandidateLifecycleEvent::Backed,
+				CandidateLifecycleEvent::BackedOnChain { block_hash },
+			]
+		);
+	}
+}
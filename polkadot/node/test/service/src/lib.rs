@@ -98,6 +98,7 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 		hwbench: None,
 		execute_workers_max_num: None,
 		prepare_workers_hard_max_num: None,
+		execute_workers_profiling: false,
 		prepare_workers_soft_max_num: None,
 		keep_finalized_for: None,
 		invulnerable_ah_collators: HashSet::new(),
@@ -458,6 +458,18 @@ pub struct ValidationResult {
 	pub hrmp_watermark: RelayChainBlockNumber,
 }
 
+/// Statistics about the validation of a parachain block, reported by the PVF right after the
+/// encoded [`ValidationResult`].
+///
+/// The [`ValidationResult`] is decoded without requiring the whole output to be consumed, so
+/// validators that don't read the statistics and PVFs that don't report them are unaffected.
+#[derive(PartialEq, Eq, Clone, Default, Encode)]
+#[cfg_attr(feature = "std", derive(Debug, Decode))]
+pub struct ValidationStats {
+	/// The number of storage keys looked up in the storage proof of the PoV.
+	pub storage_proof_reads: u64,
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -467,4 +479,23 @@ mod tests {
 		let id = Id::new(42);
 		assert_eq!(format!("{:?}", id), "42");
 	}
+
+	#[test]
+	fn validation_stats_follow_the_result() {
+		let result = ValidationResult {
+			head_data: HeadData(vec![1, 2, 3]),
+			new_validation_code: None,
+			upward_messages: Default::default(),
+			horizontal_messages: Default::default(),
+			processed_downward_messages: 4,
+			hrmp_watermark: 5,
+		};
+		let stats = ValidationStats { storage_proof_reads: 6 };
+		let encoded = (&result, &stats).encode();
+
+		let mut input = &encoded[..];
+		assert_eq!(ValidationResult::decode(&mut input).unwrap(), result);
+		assert_eq!(ValidationStats::decode(&mut input).unwrap(), stats);
+		assert!(input.is_empty());
+	}
 }
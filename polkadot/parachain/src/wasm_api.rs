@@ -35,3 +35,14 @@ pub unsafe fn load_params(params: *const u8, len: usize) -> crate::primitives::V
 pub fn write_result(result: &crate::primitives::ValidationResult) -> u64 {
 	sp_core::to_substrate_wasm_fn_return_value(&result)
 }
+
+/// Same as [`write_result`], but also reports the given [`ValidationStats`] after the result.
+///
+/// [`ValidationStats`]: crate::primitives::ValidationStats
+#[cfg(not(feature = "std"))]
+pub fn write_result_with_stats(
+	result: &crate::primitives::ValidationResult,
+	stats: &crate::primitives::ValidationStats,
+) -> u64 {
+	sp_core::to_substrate_wasm_fn_return_value(&(result, stats))
+}
@@ -97,6 +97,7 @@ fn main() -> Result<()> {
 						hwbench: None,
 						execute_workers_max_num: None,
 						prepare_workers_hard_max_num: None,
+						execute_workers_profiling: false,
 						prepare_workers_soft_max_num: None,
 						keep_finalized_for: None,
 						invulnerable_ah_collators: HashSet::new(),
@@ -115,11 +115,12 @@
 
 use crate::{
 	async_backing::{BackingState, Constraints},
-	slashing, ApprovalVotingParams, AsyncBackingParams, BlockNumber, CandidateCommitments,
-	CandidateEvent, CandidateHash, CommittedCandidateReceiptV2 as CommittedCandidateReceipt,
-	CoreIndex, CoreState, DisputeState, ExecutorParams, GroupRotationInfo, Hash, NodeFeatures,
-	OccupiedCoreAssumption, PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes,
-	SessionIndex, SessionInfo, ValidatorId, ValidatorIndex, ValidatorSignature,
+	slashing, vstaging, ApprovalVotingParams, AsyncBackingParams, BlockNumber,
+	CandidateCommitments, CandidateEvent, CandidateHash,
+	CommittedCandidateReceiptV2 as CommittedCandidateReceipt, CoreIndex, CoreState, DisputeState,
+	ExecutorParams, GroupRotationInfo, Hash, NodeFeatures, OccupiedCoreAssumption,
+	PersistedValidationData, PvfCheckStatement, ScrapedOnChainVotes, SessionIndex, SessionInfo,
+	ValidatorId, ValidatorIndex, ValidatorSignature,
 };
 
 use alloc::{
@@ -321,5 +322,26 @@ sp_api::decl_runtime_apis! {
 		/// Returns a list of validators that lost a past session dispute and need to be slashed.
 		#[api_version(15)]
 		fn unapplied_slashes_v2() -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)>;
+
+		/***** Added in v16 *****/
+		/// Returns the state of the queue of upward messages sent by the given parachain.
+		#[api_version(16)]
+		fn ump_queue_state(para_id: ppp::Id) -> vstaging::UmpQueueState;
+	}
+
+	/// The API for querying the state of on-demand coretime orders.
+	pub trait OnDemandApi {
+		/// Returns the state of the queue of on-demand orders.
+		fn queue_status() -> vstaging::OnDemandQueueStatus<pcp::Balance>;
+
+		/// Returns the spot prices of orders placed in each of the next `blocks` blocks, assuming
+		/// no order is placed or served until then.
+		fn spot_price_forecast(blocks: u32) -> Vec<pcp::Balance>;
+
+		/// Returns the queued orders of the given parachain.
+		fn para_orders(para_id: ppp::Id) -> vstaging::OnDemandParaOrders;
+
+		/// Returns the on-demand credits of the given account.
+		fn credits(who: pcp::AccountId) -> pcp::Balance;
 	}
 }
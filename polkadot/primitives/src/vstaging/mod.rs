@@ -15,3 +15,62 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 //! Staging Primitives.
+
+use crate::CoreIndex;
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use scale_info::TypeInfo;
+use sp_arithmetic::{FixedU128, Perbill};
+
+/// The state of the queue of upward messages sent by a parachain, as processed on the relay chain.
+#[derive(Clone, Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Default)]
+pub struct UmpQueueState {
+	/// The number of messages in the queue, including overweight ones.
+	pub message_count: u64,
+	/// The total size of the messages in the queue in bytes.
+	pub size: u64,
+	/// The number of pages the queue is made of.
+	pub pages: u32,
+	/// The number of pages with messages which are ready to be serviced.
+	pub ready_pages: u32,
+	/// The overweight messages of the queue as `(page, index)` pairs.
+	///
+	/// They are not processed automatically and have to be executed explicitly.
+	pub overweight: Vec<(u32, u32)>,
+	/// The position of the queue among the queues with messages ready to be serviced, or `None`
+	/// if it has none.
+	pub position: Option<u32>,
+	/// The number of messages in the queues which are serviced before this one.
+	pub messages_ahead: u64,
+	/// A rough estimate of the number of blocks until all messages of the queue are processed.
+	///
+	/// Assumes every message uses the same weight, which only holds roughly in practice. `None`
+	/// if the queue has no messages ready to be serviced or no message fits into a block.
+	pub estimated_blocks: Option<u32>,
+}
+
+/// The state of the queue of on-demand coretime orders.
+#[derive(Clone, Encode, Decode, TypeInfo, Debug, PartialEq, Eq)]
+pub struct OnDemandQueueStatus<Balance> {
+	/// The price an order placed in the current block is charged.
+	pub spot_price: Balance,
+	/// The multiplier applied to the base fee to get the spot price.
+	pub traffic: FixedU128,
+	/// The number of orders in the queue, including the ones bound to a core.
+	pub size: u32,
+	/// The maximum number of orders in the queue.
+	pub capacity: u32,
+	/// The utilization of the queue above which the spot price rises and below which it falls.
+	pub target_utilization: Perbill,
+}
+
+/// The on-demand coretime orders of a parachain.
+#[derive(Clone, Encode, Decode, TypeInfo, Debug, PartialEq, Eq, Default)]
+pub struct OnDemandParaOrders {
+	/// The number of orders placed before each queued order of the parachain which are still
+	/// queued, in ascending order.
+	pub positions: Vec<u32>,
+	/// The core the orders of the parachain are bound to, because the scheduler has assignments
+	/// of the parachain for it in its lookahead.
+	pub affinity_core: Option<CoreIndex>,
+}
@@ -0,0 +1,297 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the lifecycle of recent candidates as observed by this node.
+
+use crate::subsystem::Error;
+use jsonrpsee::{core::async_trait, proc_macros::rpc, Extensions};
+use polkadot_node_subsystem_util::candidate_tracker::{
+	CandidateLifecycleEvent, CandidateTimeline, CandidateTracker, DropReason, TimelineEntry,
+};
+use polkadot_primitives::Hash;
+use sc_rpc_api::check_if_safe;
+use serde::{Deserialize, Serialize};
+
+/// The number of timelines returned if no limit is given.
+const DEFAULT_LIMIT: u32 = 16;
+
+/// The maximum number of timelines returned by a single call.
+const MAX_LIMIT: u32 = 256;
+
+/// Provides RPC methods for inspecting the lifecycle of candidates.
+#[rpc(client, server)]
+pub trait CandidatesApi {
+	/// Returns the timelines of the most recently active candidates of the given para, most
+	/// recent first.
+	#[method(name = "candidates_recentTimelines", with_extensions)]
+	async fn recent_timelines(
+		&self,
+		para_id: u32,
+		limit: Option<u32>,
+	) -> Result<Vec<CandidateTimelineReport>, Error>;
+}
+
+/// Implements the [`CandidatesApiServer`] on top of a [`CandidateTracker`].
+pub struct Candidates {
+	tracker: CandidateTracker,
+}
+
+impl Candidates {
+	/// Creates a new instance of the candidates RPC handler.
+	pub fn new(tracker: CandidateTracker) -> Self {
+		Self { tracker }
+	}
+}
+
+#[async_trait]
+impl CandidatesApiServer for Candidates {
+	async fn recent_timelines(
+		&self,
+		ext: &Extensions,
+		para_id: u32,
+		limit: Option<u32>,
+	) -> Result<Vec<CandidateTimelineReport>, Error> {
+		check_if_safe(ext)?;
+
+		let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+		Ok(self
+			.tracker
+			.recent_candidates(para_id.into(), limit)
+			.into_iter()
+			.map(Into::into)
+			.collect())
+	}
+}
+
+/// The recorded lifecycle of a single candidate.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateTimelineReport {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The para the candidate belongs to.
+	pub para_id: u32,
+	/// The recorded events, in the order they were observed.
+	pub events: Vec<TimelineEventReport>,
+}
+
+/// A single recorded lifecycle event.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEventReport {
+	/// When the event was observed, in milliseconds since the UNIX epoch.
+	pub timestamp: u64,
+	/// The event.
+	#[serde(flatten)]
+	pub event: LifecycleEvent,
+}
+
+/// A step in the lifecycle of a candidate.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", tag = "event")]
+pub enum LifecycleEvent {
+	/// A collator advertised the candidate.
+	#[serde(rename_all = "camelCase")]
+	Advertised {
+		/// The collator that advertised the candidate.
+		peer_id: String,
+		/// The relay parent of the candidate.
+		relay_parent: Hash,
+	},
+	/// The collation was fetched from a collator.
+	#[serde(rename_all = "camelCase")]
+	Fetched {
+		/// The collator the collation was fetched from.
+		peer_id: String,
+	},
+	/// The candidate was validated and seconded.
+	Seconded,
+	/// The candidate gathered enough backing votes.
+	Backed,
+	/// The candidate was selected for a relay chain block.
+	#[serde(rename_all = "camelCase")]
+	Provisioned {
+		/// The relay chain block the block including the candidate is built on.
+		relay_parent: Hash,
+	},
+	/// The candidate was backed in a relay chain block, pending availability.
+	#[serde(rename_all = "camelCase")]
+	BackedOnChain {
+		/// The relay chain block the candidate was backed in.
+		block_hash: Hash,
+	},
+	/// Availability bitfields of a supermajority of validators for the candidate were seen.
+	#[serde(rename_all = "camelCase")]
+	Available {
+		/// The relay chain block the bitfields were signed for.
+		relay_parent: Hash,
+	},
+	/// The candidate became available on chain and was included.
+	#[serde(rename_all = "camelCase")]
+	Included {
+		/// The relay chain block in which the candidate was included.
+		block_hash: Hash,
+	},
+	/// The relay chain block enacting the candidate was approved.
+	#[serde(rename_all = "camelCase")]
+	Approved {
+		/// The approved relay chain block.
+		block_hash: Hash,
+	},
+	/// The relay chain block enacting the candidate was finalized.
+	#[serde(rename_all = "camelCase")]
+	Finalized {
+		/// The finalized relay chain block.
+		block_hash: Hash,
+	},
+	/// The candidate was dropped.
+	Dropped {
+		/// Why the candidate was dropped.
+		reason: DroppedReason,
+	},
+}
+
+/// Why a candidate was dropped.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DroppedReason {
+	/// Fetching the advertised collation failed.
+	FetchFailed,
+	/// The candidate failed validation.
+	Invalid,
+	/// Prospective parachains did not accept the candidate for seconding.
+	RejectedByProspectiveParachains,
+	/// The candidate did not become available in time.
+	AvailabilityTimedOut,
+}
+
+impl From<CandidateTimeline> for CandidateTimelineReport {
+	fn from(timeline: CandidateTimeline) -> Self {
+		Self {
+			candidate_hash: timeline.candidate_hash.0,
+			para_id: timeline.para_id.into(),
+			events: timeline.events.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl From<TimelineEntry> for TimelineEventReport {
+	fn from(entry: TimelineEntry) -> Self {
+		Self { timestamp: entry.timestamp, event: entry.event.into() }
+	}
+}
+
+impl From<CandidateLifecycleEvent> for LifecycleEvent {
+	fn from(event: CandidateLifecycleEvent) -> Self {
+		match event {
+			CandidateLifecycleEvent::Advertised { peer_id, relay_parent } =>
+				Self::Advertised { peer_id: peer_id.to_string(), relay_parent },
+			CandidateLifecycleEvent::Fetched { peer_id } =>
+				Self::Fetched { peer_id: peer_id.to_string() },
+			CandidateLifecycleEvent::Seconded => Self::Seconded,
+			CandidateLifecycleEvent::Backed => Self::Backed,
+			CandidateLifecycleEvent::Provisioned { relay_parent } =>
+				Self::Provisioned { relay_parent },
+			CandidateLifecycleEvent::BackedOnChain { block_hash } =>
+				Self::BackedOnChain { block_hash },
+			CandidateLifecycleEvent::Available { relay_parent } => Self::Available { relay_parent },
+			CandidateLifecycleEvent::Included { block_hash } => Self::Included { block_hash },
+			CandidateLifecycleEvent::Approved { block_hash } => Self::Approved { block_hash },
+			CandidateLifecycleEvent::Finalized { block_hash } => Self::Finalized { block_hash },
+			CandidateLifecycleEvent::Dropped(reason) => Self::Dropped {
+				reason: match reason {
+					DropReason::FetchFailed => DroppedReason::FetchFailed,
+					DropReason::Invalid => DroppedReason::Invalid,
+					DropReason::RejectedByProspectiveParachains =>
+						DroppedReason::RejectedByProspectiveParachains,
+					DropReason::AvailabilityTimedOut => DroppedReason::AvailabilityTimedOut,
+				},
+			},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use jsonrpsee::MethodsError as RpcError;
+	use polkadot_primitives::{CandidateHash, Id as ParaId};
+	use sc_rpc_api::DenyUnsafe;
+	use serde_json::json;
+
+	#[tokio::test]
+	async fn recent_timelines_reports_events() {
+		let tracker = CandidateTracker::new();
+		let para_id = ParaId::from(100);
+		let candidate_hash = CandidateHash(Hash::repeat_byte(1));
+		let block_hash = Hash::repeat_byte(2);
+
+		tracker.note(candidate_hash, para_id, CandidateLifecycleEvent::Seconded);
+		tracker.note(
+			candidate_hash,
+			para_id,
+			CandidateLifecycleEvent::BackedOnChain { block_hash },
+		);
+		tracker.note(
+			candidate_hash,
+			para_id,
+			CandidateLifecycleEvent::Available { relay_parent: block_hash },
+		);
+		tracker.note(
+			CandidateHash(Hash::repeat_byte(3)),
+			para_id,
+			CandidateLifecycleEvent::Dropped(DropReason::Invalid),
+		);
+		tracker.note(
+			CandidateHash(Hash::repeat_byte(4)),
+			ParaId::from(200),
+			CandidateLifecycleEvent::Seconded,
+		);
+
+		let mut api = Candidates::new(tracker).into_rpc();
+		api.extensions_mut().insert(DenyUnsafe::No);
+
+		let timelines: Vec<serde_json::Value> =
+			api.call("candidates_recentTimelines", (100, 1)).await.unwrap();
+		assert_eq!(timelines.len(), 1);
+		assert_eq!(timelines[0]["candidateHash"], json!(Hash::repeat_byte(3)));
+		assert_eq!(timelines[0]["events"][0]["event"], "dropped");
+		assert_eq!(timelines[0]["events"][0]["reason"], "invalid");
+
+		let timelines: Vec<serde_json::Value> =
+			api.call("candidates_recentTimelines", [100]).await.unwrap();
+		assert_eq!(timelines.len(), 2);
+		assert_eq!(timelines[1]["candidateHash"], json!(candidate_hash.0));
+		assert_eq!(timelines[1]["paraId"], 100);
+		let events = timelines[1]["events"].as_array().unwrap();
+		assert_eq!(events[0]["event"], "seconded");
+		assert_eq!(events[1]["event"], "backedOnChain");
+		assert_eq!(events[1]["blockHash"], json!(block_hash));
+		assert!(events[1]["timestamp"].is_u64());
+		assert_eq!(events[2]["event"], "available");
+		assert_eq!(events[2]["relayParent"], json!(block_hash));
+	}
+
+	#[tokio::test]
+	async fn recent_timelines_is_unsafe() {
+		let mut api = Candidates::new(CandidateTracker::new()).into_rpc();
+		api.extensions_mut().insert(DenyUnsafe::Yes);
+
+		let result =
+			api.call::<_, Vec<serde_json::Value>>("candidates_recentTimelines", [100]).await;
+		assert!(matches!(result, Err(RpcError::JsonRpc(_))));
+	}
+}
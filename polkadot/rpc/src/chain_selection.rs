@@ -0,0 +1,286 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the viability of unfinalized blocks as seen by chain selection.
+
+use crate::subsystem::{request, Error};
+use jsonrpsee::{core::async_trait, proc_macros::rpc, Extensions};
+use polkadot_node_subsystem_types::messages::{
+	BlockApprovalStatus, BlockViability, ChainSelectionMessage, ChainViability, RevertReason,
+};
+use polkadot_overseer::Handle;
+use polkadot_primitives::{BlockNumber, Hash};
+use sc_rpc_api::check_if_safe;
+use serde::{Deserialize, Serialize};
+
+/// Provides RPC methods for inspecting chain selection.
+#[rpc(client, server)]
+pub trait ChainSelectionApi {
+	/// Returns the viable leaves and the viability of every unfinalized block, including the
+	/// reason for any explicit reversion.
+	///
+	/// The reasons are only kept in memory, so blocks reverted before the node restarted are
+	/// reported with an unknown reason.
+	#[method(name = "chainSelection_viability", with_extensions)]
+	async fn viability(&self) -> Result<ChainViabilityReport, Error>;
+}
+
+/// Implements the [`ChainSelectionApiServer`] on top of an overseer [`Handle`].
+pub struct ChainSelection {
+	overseer: Handle,
+}
+
+impl ChainSelection {
+	/// Creates a new instance of the chain selection RPC handler.
+	pub fn new(overseer: Handle) -> Self {
+		Self { overseer }
+	}
+}
+
+#[async_trait]
+impl ChainSelectionApiServer for ChainSelection {
+	async fn viability(&self, ext: &Extensions) -> Result<ChainViabilityReport, Error> {
+		check_if_safe(ext)?;
+
+		request(
+			&self.overseer,
+			"Chain selection",
+			"ChainSelectionRpc",
+			ChainSelectionMessage::InspectViability,
+		)
+		.await
+		.map(Into::into)
+	}
+}
+
+/// The viability of all unfinalized blocks known to chain selection.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChainViabilityReport {
+	/// The viable leaves in descending order by score.
+	pub leaves: Vec<Hash>,
+	/// All unfinalized blocks, ordered by ascending block number.
+	pub blocks: Vec<BlockViabilityReport>,
+}
+
+/// The viability of a single unfinalized block.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockViabilityReport {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The number of the block.
+	pub number: BlockNumber,
+	/// The hash of the parent block.
+	pub parent_hash: Hash,
+	/// The fork-choice weight of the block.
+	pub weight: u32,
+	/// Whether the block is a viable leaf.
+	pub is_leaf: bool,
+	/// The approval status of the block.
+	pub approval: ApprovalStatus,
+	/// Why the block was explicitly reverted, if it was.
+	pub reverted: Option<Reversion>,
+	/// The earliest unviable ancestor of the block, if any.
+	pub earliest_unviable_ancestor: Option<Hash>,
+	/// Whether the block can be built upon.
+	pub viable: bool,
+}
+
+/// The approval status of a block.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ApprovalStatus {
+	/// The block is approved.
+	Approved,
+	/// The block is awaiting approval.
+	Unapproved,
+	/// The block was not approved in time.
+	Stagnant,
+}
+
+/// The reason a block was explicitly reverted.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase", tag = "reason")]
+pub enum Reversion {
+	/// A dispute concluded against a candidate included in the block.
+	DisputeConcludedAgainst,
+	/// A revert log in the header of a descendant demanded the reversion.
+	#[serde(rename_all = "camelCase")]
+	RevertLog {
+		/// The block whose header carried the revert log.
+		reported_by: Hash,
+	},
+	/// The reason is not known, e.g. because the block was reverted before a restart.
+	Unknown,
+}
+
+impl From<ChainViability> for ChainViabilityReport {
+	fn from(viability: ChainViability) -> Self {
+		Self {
+			leaves: viability.leaves,
+			blocks: viability.blocks.into_iter().map(Into::into).collect(),
+		}
+	}
+}
+
+impl From<BlockViability> for BlockViabilityReport {
+	fn from(block: BlockViability) -> Self {
+		Self {
+			hash: block.hash,
+			number: block.number,
+			parent_hash: block.parent_hash,
+			weight: block.weight,
+			is_leaf: block.is_leaf,
+			approval: match block.approval {
+				BlockApprovalStatus::Approved => ApprovalStatus::Approved,
+				BlockApprovalStatus::Unapproved => ApprovalStatus::Unapproved,
+				BlockApprovalStatus::Stagnant => ApprovalStatus::Stagnant,
+			},
+			reverted: block.revert_reason.map(|reason| match reason {
+				RevertReason::DisputeConcludedAgainst => Reversion::DisputeConcludedAgainst,
+				RevertReason::RevertLog { reported_by } => Reversion::RevertLog { reported_by },
+				RevertReason::Unknown => Reversion::Unknown,
+			}),
+			earliest_unviable_ancestor: block.earliest_unviable_ancestor,
+			viable: block.viable,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::subsystem::tests::answering_overseer;
+	use jsonrpsee::{core::EmptyServerParams as EmptyParams, MethodsError as RpcError};
+	use polkadot_overseer::AllMessages;
+	use sc_rpc_api::DenyUnsafe;
+	use serde_json::json;
+
+	fn block(number: BlockNumber) -> BlockViability {
+		BlockViability {
+			hash: Hash::repeat_byte(number as u8),
+			number,
+			parent_hash: Hash::repeat_byte(number as u8 - 1),
+			weight: number,
+			is_leaf: false,
+			approval: BlockApprovalStatus::Approved,
+			revert_reason: None,
+			earliest_unviable_ancestor: None,
+			viable: true,
+		}
+	}
+
+	#[tokio::test]
+	async fn viability_reports_blocks() {
+		let overseer = answering_overseer(|msg| {
+			if let AllMessages::ChainSelection(ChainSelectionMessage::InspectViability(tx)) = msg {
+				let _ = tx.send(ChainViability {
+					leaves: vec![Hash::repeat_byte(1)],
+					blocks: vec![
+						BlockViability { is_leaf: true, ..block(1) },
+						BlockViability {
+							approval: BlockApprovalStatus::Stagnant,
+							revert_reason: Some(RevertReason::RevertLog {
+								reported_by: Hash::repeat_byte(3),
+							}),
+							viable: false,
+							..block(2)
+						},
+						BlockViability {
+							approval: BlockApprovalStatus::Unapproved,
+							revert_reason: Some(RevertReason::Unknown),
+							earliest_unviable_ancestor: Some(Hash::repeat_byte(2)),
+							viable: false,
+							..block(3)
+						},
+					],
+				});
+			}
+		});
+		let mut api = ChainSelection::new(overseer).into_rpc();
+		api.extensions_mut().insert(DenyUnsafe::No);
+
+		let report: serde_json::Value =
+			api.call("chainSelection_viability", EmptyParams::new()).await.unwrap();
+
+		let hash = |byte: u8| Hash::repeat_byte(byte);
+		assert_eq!(report["leaves"], json!([hash(1)]));
+		assert_eq!(
+			report["blocks"],
+			json!([
+				{
+					"hash": hash(1),
+					"number": 1,
+					"parentHash": hash(0),
+					"weight": 1,
+					"isLeaf": true,
+					"approval": "approved",
+					"reverted": null,
+					"earliestUnviableAncestor": null,
+					"viable": true,
+				},
+				{
+					"hash": hash(2),
+					"number": 2,
+					"parentHash": hash(1),
+					"weight": 2,
+					"isLeaf": false,
+					"approval": "stagnant",
+					"reverted": { "reason": "revertLog", "reportedBy": hash(3) },
+					"earliestUnviableAncestor": null,
+					"viable": false,
+				},
+				{
+					"hash": hash(3),
+					"number": 3,
+					"parentHash": hash(2),
+					"weight": 3,
+					"isLeaf": false,
+					"approval": "unapproved",
+					"reverted": { "reason": "unknown" },
+					"earliestUnviableAncestor": hash(2),
+					"viable": false,
+				},
+			]),
+		);
+	}
+
+	#[tokio::test]
+	async fn viability_fails_without_subsystem() {
+		// Dropping the request drops the response sender.
+		let overseer = answering_overseer(|_| {});
+		let mut api = ChainSelection::new(overseer).into_rpc();
+		api.extensions_mut().insert(DenyUnsafe::No);
+
+		let result = api
+			.call::<_, serde_json::Value>("chainSelection_viability", EmptyParams::new())
+			.await;
+		assert!(matches!(result, Err(RpcError::JsonRpc(err)) if err.code() == 9101));
+	}
+
+	#[tokio::test]
+	async fn viability_is_unsafe() {
+		let overseer = answering_overseer(|_| unreachable!("unsafe calls are denied"));
+		let mut api = ChainSelection::new(overseer).into_rpc();
+		api.extensions_mut().insert(DenyUnsafe::Yes);
+
+		let result = api
+			.call::<_, serde_json::Value>("chainSelection_viability", EmptyParams::new())
+			.await;
+		assert!(matches!(result, Err(RpcError::JsonRpc(_))));
+	}
+}
@@ -0,0 +1,223 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the gossip grid topology and validator connectivity.
+
+use crate::subsystem::{request, Error};
+use jsonrpsee::{core::async_trait, proc_macros::rpc, Extensions};
+use polkadot_node_subsystem_types::messages::{
+	GossipSupportMessage, GossipTopologyReport, GridNeighbor, ValidatorConnectivity,
+};
+use polkadot_overseer::Handle;
+use polkadot_primitives::{AuthorityDiscoveryId, SessionIndex, ValidatorIndex};
+use sc_rpc_api::check_if_safe;
+use serde::{Deserialize, Serialize};
+
+/// Provides RPC methods for inspecting the gossip topology.
+#[rpc(client, server)]
+pub trait GossipSupportApi {
+	/// Returns our row and column neighbors in the current grid topology, the authorities
+	/// unresolved in authority discovery and the connectivity of the validation peer set.
+	#[method(name = "gossipSupport_topology", with_extensions)]
+	async fn topology(&self) -> Result<TopologyReport, Error>;
+}
+
+/// Implements the [`GossipSupportApiServer`] on top of an overseer [`Handle`].
+pub struct GossipSupport {
+	overseer: Handle,
+}
+
+impl GossipSupport {
+	/// Creates a new instance of the gossip support RPC handler.
+	pub fn new(overseer: Handle) -> Self {
+		Self { overseer }
+	}
+}
+
+#[async_trait]
+impl GossipSupportApiServer for GossipSupport {
+	async fn topology(&self, ext: &Extensions) -> Result<TopologyReport, Error> {
+		check_if_safe(ext)?;
+
+		request(
+			&self.overseer,
+			"Gossip support",
+			"GossipSupportRpc",
+			GossipSupportMessage::GetTopologyReport,
+		)
+		.await
+		.map(Into::into)
+	}
+}
+
+/// Our view of the gossip grid topology and of the validator connectivity.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TopologyReport {
+	/// The session of the current topology, `None` if we are not an authority in it.
+	pub session_index: Option<SessionIndex>,
+	/// Our validator index in the current session.
+	pub our_index: Option<u32>,
+	/// Our neighbors in the same row of the grid.
+	pub row_neighbors: Vec<Neighbor>,
+	/// Our neighbors in the same column of the grid.
+	pub column_neighbors: Vec<Neighbor>,
+	/// Authorities we want to be connected to, but have no addresses for.
+	pub unresolved_authorities: Vec<AuthorityDiscoveryId>,
+	/// Connectivity of the authorities we requested connections to on the validation peer set.
+	pub connectivity: ConnectivityReport,
+}
+
+/// A neighbor in the gossip grid topology.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Neighbor {
+	/// The validator index of the neighbor.
+	pub validator_index: u32,
+	/// The authority discovery key of the neighbor.
+	pub authority_id: AuthorityDiscoveryId,
+	/// Whether we are connected to the neighbor.
+	pub connected: bool,
+}
+
+/// Connectivity of the validation peer set, in number of authorities.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityReport {
+	/// Authorities we requested a connection to.
+	pub requested: usize,
+	/// Requested authorities resolved by authority discovery.
+	pub resolved: usize,
+	/// Requested authorities we are connected to.
+	pub connected: usize,
+	/// Percentage of the requested authorities we are connected to.
+	pub connected_percent: f64,
+}
+
+impl From<GossipTopologyReport> for TopologyReport {
+	fn from(report: GossipTopologyReport) -> Self {
+		Self {
+			session_index: report.session_index,
+			our_index: report.our_index.map(|ValidatorIndex(index)| index),
+			row_neighbors: report.row_neighbors.into_iter().map(Into::into).collect(),
+			column_neighbors: report.column_neighbors.into_iter().map(Into::into).collect(),
+			unresolved_authorities: report.unresolved_authorities,
+			connectivity: report.connectivity.into(),
+		}
+	}
+}
+
+impl From<GridNeighbor> for Neighbor {
+	fn from(neighbor: GridNeighbor) -> Self {
+		Self {
+			validator_index: neighbor.validator_index.0,
+			authority_id: neighbor.authority_id,
+			connected: neighbor.connected,
+		}
+	}
+}
+
+impl From<ValidatorConnectivity> for ConnectivityReport {
+	fn from(connectivity: ValidatorConnectivity) -> Self {
+		let connected_percent = if connectivity.requested == 0 {
+			100.0
+		} else {
+			100.0 * connectivity.connected as f64 / connectivity.requested as f64
+		};
+
+		Self {
+			requested: connectivity.requested,
+			resolved: connectivity.resolved,
+			connected: connectivity.connected,
+			connected_percent,
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::subsystem::tests::answering_overseer;
+	use jsonrpsee::{core::EmptyServerParams as EmptyParams, MethodsError as RpcError};
+	use polkadot_overseer::AllMessages;
+	use sc_rpc_api::DenyUnsafe;
+	use serde_json::json;
+	use sp_keyring::Sr25519Keyring;
+
+	#[tokio::test]
+	async fn topology_reports_neighbors_and_connectivity() {
+		let alice: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+		let bob: AuthorityDiscoveryId = Sr25519Keyring::Bob.public().into();
+		let charlie: AuthorityDiscoveryId = Sr25519Keyring::Charlie.public().into();
+
+		let (row, column, unresolved) = (alice.clone(), bob.clone(), charlie.clone());
+		let overseer = answering_overseer(move |msg| {
+			if let AllMessages::GossipSupport(GossipSupportMessage::GetTopologyReport(tx)) = msg {
+				let _ = tx.send(GossipTopologyReport {
+					session_index: Some(2),
+					our_index: Some(ValidatorIndex(0)),
+					row_neighbors: vec![GridNeighbor {
+						validator_index: ValidatorIndex(1),
+						authority_id: row.clone(),
+						connected: true,
+					}],
+					column_neighbors: vec![GridNeighbor {
+						validator_index: ValidatorIndex(2),
+						authority_id: column.clone(),
+						connected: false,
+					}],
+					unresolved_authorities: vec![unresolved.clone()],
+					connectivity: ValidatorConnectivity { requested: 4, resolved: 3, connected: 1 },
+				});
+			}
+		});
+		let mut api = GossipSupport::new(overseer).into_rpc();
+		api.extensions_mut().insert(DenyUnsafe::No);
+
+		let report: serde_json::Value =
+			api.call("gossipSupport_topology", EmptyParams::new()).await.unwrap();
+
+		assert_eq!(
+			report,
+			json!({
+				"sessionIndex": 2,
+				"ourIndex": 0,
+				"rowNeighbors": [{ "validatorIndex": 1, "authorityId": alice, "connected": true }],
+				"columnNeighbors": [{ "validatorIndex": 2, "authorityId": bob, "connected": false }],
+				"unresolvedAuthorities": [charlie],
+				"connectivity": {
+					"requested": 4,
+					"resolved": 3,
+					"connected": 1,
+					"connectedPercent": 25.0,
+				},
+			}),
+		);
+	}
+
+	#[tokio::test]
+	async fn topology_fails_without_subsystem() {
+		// Dropping the request drops the response sender.
+		let overseer = answering_overseer(|_| {});
+		let mut api = GossipSupport::new(overseer).into_rpc();
+		api.extensions_mut().insert(DenyUnsafe::No);
+
+		let result = api
+			.call::<_, serde_json::Value>("gossipSupport_topology", EmptyParams::new())
+			.await;
+		assert!(matches!(result, Err(RpcError::JsonRpc(err)) if err.code() == 9101));
+	}
+}
@@ -21,6 +21,7 @@
 use std::sync::Arc;
 
 use jsonrpsee::RpcModule;
+use polkadot_node_subsystem_util::candidate_tracker::CandidateTracker;
 use polkadot_primitives::{AccountId, Balance, Block, BlockNumber, Hash, Nonce};
 use sc_client_api::AuxStore;
 use sc_consensus_beefy::communication::notification::{
@@ -38,6 +39,11 @@ use sp_consensus_babe::BabeApi;
 use sp_consensus_beefy::AuthorityIdBound;
 use sp_keystore::KeystorePtr;
 
+pub mod candidates;
+pub mod chain_selection;
+pub mod gossip_support;
+pub mod subsystem;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = RpcModule<()>;
 
@@ -91,17 +97,26 @@ pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	pub beefy: BeefyDeps<AuthorityId>,
 	/// Backend used by the node.
 	pub backend: Arc<B>,
+	/// A handle to the overseer, if the node runs the parachain subsystems.
+	pub overseer_handle: Option<polkadot_overseer::Handle>,
+	/// The tracker of recent candidate lifecycles, if the node runs the parachain subsystems.
+	pub candidate_tracker: Option<CandidateTracker>,
 }
 
 /// Instantiate all RPC extensions.
 pub fn create_full<C, P, SC, B, AuthorityId>(
-	FullDeps { client, pool, select_chain, chain_spec, babe, grandpa, beefy, backend }: FullDeps<
-		C,
-		P,
-		SC,
-		B,
-		AuthorityId,
-	>,
+	FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		babe,
+		grandpa,
+		beefy,
+		backend,
+		overseer_handle,
+		candidate_tracker,
+	}: FullDeps<C, P, SC, B, AuthorityId>,
 ) -> Result<RpcExtension, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>
@@ -123,6 +138,9 @@ where
 	AuthorityId: AuthorityIdBound,
 	<AuthorityId as RuntimeAppPublic>::Signature: Send + Sync,
 {
+	use candidates::{Candidates, CandidatesApiServer};
+	use chain_selection::{ChainSelection, ChainSelectionApiServer};
+	use gossip_support::{GossipSupport, GossipSupportApiServer};
 	use mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
@@ -180,5 +198,14 @@ where
 		.into_rpc(),
 	)?;
 
+	if let Some(overseer_handle) = overseer_handle {
+		io.merge(ChainSelection::new(overseer_handle.clone()).into_rpc())?;
+		io.merge(GossipSupport::new(overseer_handle).into_rpc())?;
+	}
+
+	if let Some(candidate_tracker) = candidate_tracker {
+		io.merge(Candidates::new(candidate_tracker).into_rpc())?;
+	}
+
 	Ok(io)
 }
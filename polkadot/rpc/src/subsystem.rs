@@ -0,0 +1,89 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Shared by the RPCs inspecting the state of the parachain subsystems.
+
+use futures::channel::oneshot;
+use jsonrpsee::types::{ErrorObject, ErrorObjectOwned};
+use polkadot_overseer::{AllMessages, Handle};
+use sc_rpc_api::UnsafeRpcError;
+
+/// Base error code of the RPCs inspecting the state of the parachain subsystems.
+const SUBSYSTEM_RPC_ERROR: i32 = 9100;
+
+/// Top-level error type for the RPC handlers inspecting the parachain subsystems.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The named subsystem did not answer the request.
+	#[error("{0} subsystem is unavailable")]
+	SubsystemUnavailable(&'static str),
+	/// Call to an unsafe RPC was denied.
+	#[error(transparent)]
+	UnsafeRpcCalled(#[from] UnsafeRpcError),
+}
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::SubsystemUnavailable(_) =>
+				ErrorObject::owned(SUBSYSTEM_RPC_ERROR + 1, error.to_string(), None::<()>),
+			Error::UnsafeRpcCalled(e) => e.into(),
+		}
+	}
+}
+
+/// Sends the request built by `make_request` to the named subsystem and waits for its answer.
+pub(crate) async fn request<T, M>(
+	overseer: &Handle,
+	subsystem: &'static str,
+	origin: &'static str,
+	make_request: impl FnOnce(oneshot::Sender<T>) -> M,
+) -> Result<T, Error>
+where
+	M: Into<AllMessages>,
+{
+	let (tx, rx) = oneshot::channel();
+	overseer.clone().send_msg(make_request(tx), origin).await;
+
+	rx.await.map_err(|_| Error::SubsystemUnavailable(subsystem))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+	use super::*;
+	use futures::StreamExt;
+	use polkadot_node_subsystem_util::metered;
+	use polkadot_overseer::Event;
+
+	/// Returns an overseer handle whose messages are answered by `answer`.
+	///
+	/// Messages `answer` doesn't handle are dropped, together with their response sender.
+	pub(crate) fn answering_overseer(
+		mut answer: impl FnMut(AllMessages) + Send + 'static,
+	) -> Handle {
+		let (tx, mut rx) = metered::channel(16);
+
+		tokio::spawn(async move {
+			while let Some(event) = rx.next().await {
+				if let Event::MsgToSubsystem { msg, .. } = event {
+					answer(msg);
+				}
+			}
+		});
+
+		Handle::new(tx)
+	}
+}
@@ -43,7 +43,7 @@ extern crate alloc;
 mod tests;
 
 use crate::{configuration, paras, scheduler::common::Assignment};
-use alloc::collections::BinaryHeap;
+use alloc::{collections::BinaryHeap, vec::Vec};
 use core::mem::take;
 use frame_support::{
 	pallet_prelude::*,
@@ -56,7 +56,10 @@ use frame_support::{
 	PalletId,
 };
 use frame_system::{pallet_prelude::*, Pallet as System};
-use polkadot_primitives::{CoreIndex, Id as ParaId};
+use polkadot_primitives::{
+	vstaging::{OnDemandParaOrders, OnDemandQueueStatus},
+	CoreIndex, Id as ParaId,
+};
 use sp_runtime::{
 	traits::{AccountIdConversion, One, SaturatedConversion},
 	FixedPointNumber, FixedPointOperand, FixedU128, Perbill, Saturating,
@@ -68,6 +71,9 @@ use types::{
 
 const LOG_TARGET: &str = "runtime::parachains::on-demand";
 
+/// The maximum number of blocks the spot price is forecast for.
+pub const MAX_SPOT_PRICE_FORECAST: u32 = 1024;
+
 pub use pallet::*;
 
 pub trait WeightInfo {
@@ -435,6 +441,55 @@ where
 		Pallet::<T>::deposit_event(Event::<T>::AccountCredited { who, amount });
 	}
 
+	/// Returns the state of the order queue.
+	///
+	/// The spot price is the one an order placed in the current block is charged.
+	pub fn queue_status() -> OnDemandQueueStatus<BalanceOf<T>> {
+		let config = configuration::ActiveConfig::<T>::get();
+		let queue_status = QueueStatus::<T>::get();
+		OnDemandQueueStatus {
+			spot_price: Self::spot_price(&config, Self::next_traffic(&config, &queue_status)),
+			traffic: queue_status.traffic,
+			size: queue_status.size(),
+			capacity: config.scheduler_params.on_demand_queue_max_size,
+			target_utilization: config.scheduler_params.on_demand_target_queue_utilization,
+		}
+	}
+
+	/// Returns the spot prices of orders placed in each of the next `blocks` blocks, assuming no
+	/// order is placed or served until then.
+	///
+	/// At most `MAX_SPOT_PRICE_FORECAST` prices are returned.
+	pub fn spot_price_forecast(blocks: u32) -> Vec<BalanceOf<T>> {
+		let config = configuration::ActiveConfig::<T>::get();
+		let mut queue_status = QueueStatus::<T>::get();
+		(0..blocks.min(MAX_SPOT_PRICE_FORECAST))
+			.map(|_| {
+				// The traffic is updated on block initialization and again when placing an order.
+				queue_status.traffic = Self::next_traffic(&config, &queue_status);
+				Self::spot_price(&config, Self::next_traffic(&config, &queue_status))
+			})
+			.collect()
+	}
+
+	/// Returns the queued orders of the given para.
+	pub fn para_orders(para_id: ParaId) -> OnDemandParaOrders {
+		let mut orders = FreeEntries::<T>::get().into_vec();
+		for (_, entries) in AffinityEntries::<T>::iter() {
+			orders.extend(entries.into_vec());
+		}
+		orders.sort_by_key(|order| order.idx);
+		OnDemandParaOrders {
+			positions: orders
+				.iter()
+				.enumerate()
+				.filter(|(_, order)| order.para_id == para_id)
+				.map(|(position, _)| position.saturated_into())
+				.collect(),
+			affinity_core: ParaIdAffinity::<T>::get(para_id).map(|affinity| affinity.core_index),
+		}
+	}
+
 	/// Helper function for `place_order_*` calls. Used to differentiate between placing orders
 	/// with a keep alive check or to allow the account to be reaped. The amount charged is
 	/// stored to the pallet account to be later paid out as revenue.
@@ -572,6 +627,31 @@ where
 		};
 	}
 
+	/// Returns the traffic after updating it for the given queue status, without storing it.
+	fn next_traffic(
+		config: &configuration::HostConfiguration<BlockNumberFor<T>>,
+		queue_status: &QueueStatusType,
+	) -> FixedU128 {
+		Self::calculate_spot_traffic(
+			queue_status.traffic,
+			config.scheduler_params.on_demand_queue_max_size,
+			queue_status.size(),
+			config.scheduler_params.on_demand_target_queue_utilization,
+			config.scheduler_params.on_demand_fee_variability,
+		)
+		.unwrap_or(queue_status.traffic)
+	}
+
+	/// Returns the spot price for the given traffic.
+	fn spot_price(
+		config: &configuration::HostConfiguration<BlockNumberFor<T>>,
+		traffic: FixedU128,
+	) -> BalanceOf<T> {
+		traffic.saturating_mul_int(
+			config.scheduler_params.on_demand_base_fee.saturated_into::<BalanceOf<T>>(),
+		)
+	}
+
 	/// The spot price multiplier. This is based on the transaction fee calculations defined in:
 	/// https://research.web3.foundation/Polkadot/overview/token-economics#setting-transaction-fees
 	///
@@ -266,6 +266,39 @@ fn spot_traffic_decreases_between_idle_blocks() {
 	})
 }
 
+#[test]
+fn spot_price_forecast_matches_spot_prices() {
+	let para_id = ParaId::from(111);
+
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		schedule_blank_para(para_id, ParaKind::Parathread);
+		run_to_block(100, |n| if n == 100 { Some(Default::default()) } else { None });
+
+		OnDemand::set_queue_status(QueueStatusType {
+			traffic: FixedU128::from_u32(10),
+			..Default::default()
+		});
+
+		let status = OnDemand::queue_status();
+		assert_eq!(status.traffic, FixedU128::from_u32(10));
+		assert_eq!(status.size, 0);
+		let forecast = OnDemand::spot_price_forecast(3);
+		assert_eq!(forecast.len(), 3);
+		// Idle blocks decrease the spot price.
+		assert!(forecast[0] < status.spot_price);
+
+		for (block, spot_price) in (101..).zip(forecast) {
+			run_to_block(block, |_| None);
+			assert_eq!(OnDemand::queue_status().spot_price, spot_price);
+		}
+
+		assert_eq!(
+			OnDemand::spot_price_forecast(u32::MAX).len(),
+			on_demand::MAX_SPOT_PRICE_FORECAST as usize
+		);
+	});
+}
+
 #[test]
 #[allow(deprecated)]
 fn place_order_works() {
@@ -439,6 +472,44 @@ fn pop_assignment_for_core_works() {
 	});
 }
 
+#[test]
+fn para_orders_works() {
+	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
+		let para_a = ParaId::from(111);
+		let para_b = ParaId::from(110);
+		schedule_blank_para(para_a, ParaKind::Parathread);
+		schedule_blank_para(para_b, ParaKind::Parathread);
+
+		run_to_block(11, |n| if n == 11 { Some(Default::default()) } else { None });
+
+		assert_eq!(OnDemand::para_orders(para_a), Default::default());
+
+		place_order(para_a);
+		place_order(para_b);
+		place_order(para_a);
+		assert_eq!(OnDemand::queue_status().size, 3);
+		assert_eq!(
+			OnDemand::para_orders(para_a),
+			OnDemandParaOrders { positions: vec![0, 2], affinity_core: None }
+		);
+		assert_eq!(
+			OnDemand::para_orders(para_b),
+			OnDemandParaOrders { positions: vec![1], affinity_core: None }
+		);
+
+		// The remaining order of para a is bound to the core its first order is served on.
+		assert_eq!(OnDemand::pop_assignment_for_core(CoreIndex(0)).unwrap().para_id(), para_a);
+		assert_eq!(
+			OnDemand::para_orders(para_a),
+			OnDemandParaOrders { positions: vec![1], affinity_core: Some(CoreIndex(0)) }
+		);
+		assert_eq!(
+			OnDemand::para_orders(para_b),
+			OnDemandParaOrders { positions: vec![0], affinity_core: None }
+		);
+	});
+}
+
 #[test]
 fn push_back_assignment_works() {
 	new_test_ext(GenesisConfigBuilder::default().build()).execute_with(|| {
@@ -16,10 +16,21 @@
 
 //! Put implementations of functions from staging APIs here.
 
-use crate::{disputes, initializer, paras};
+use crate::{
+	disputes,
+	inclusion::{AggregateMessageOrigin, UmpQueueId},
+	initializer, paras,
+};
 use alloc::vec::Vec;
+use frame_support::{
+	traits::{Get, QueueFootprintQuery, QueueInspect},
+	weights::Weight,
+};
+use sp_runtime::SaturatedConversion;
 
-use polkadot_primitives::{slashing, CandidateHash, Id as ParaId, SessionIndex};
+use polkadot_primitives::{
+	slashing, vstaging::UmpQueueState, CandidateHash, Id as ParaId, SessionIndex,
+};
 
 /// Implementation of `para_ids` runtime API
 pub fn para_ids<T: initializer::Config>() -> Vec<ParaId> {
@@ -31,3 +42,53 @@ pub fn unapplied_slashes_v2<T: disputes::slashing::Config>(
 ) -> Vec<(SessionIndex, CandidateHash, slashing::PendingSlashes)> {
 	disputes::slashing::Pallet::<T>::unapplied_slashes()
 }
+
+/// Implementation of `ump_queue_state` runtime API.
+///
+/// `Queue` is the message queue the UMP messages are enqueued in, usually the
+/// `inclusion::Config::MessageQueue` of the runtime. It is a separate parameter so that
+/// `inclusion::Config` does not need to require [`QueueInspect`], whose methods iterate storage
+/// without a bound and must only be used from runtime APIs.
+///
+/// `ServiceWeight` is the weight the message queue services queues with in each block and
+/// `MessageWeight` the weight a single message is assumed to use, to estimate the number of blocks
+/// until the messages of the queue are processed.
+pub fn ump_queue_state<Queue, ServiceWeight, MessageWeight>(para_id: ParaId) -> UmpQueueState
+where
+	Queue: QueueFootprintQuery<AggregateMessageOrigin> + QueueInspect<AggregateMessageOrigin>,
+	ServiceWeight: Get<Weight>,
+	MessageWeight: Get<Weight>,
+{
+	let origin = AggregateMessageOrigin::Ump(UmpQueueId::Para(para_id));
+	let footprint = Queue::footprint(origin.clone());
+	let ready = Queue::ready_queues();
+	let position = ready.iter().position(|o| *o == origin);
+	let messages_ahead = ready
+		.iter()
+		.take(position.unwrap_or(ready.len()))
+		.map(|o| Queue::footprint(o.clone()).storage.count)
+		.fold(0u64, u64::saturating_add);
+	let message_count = footprint.storage.count;
+
+	let per_block = ServiceWeight::get().checked_div_per_component(&MessageWeight::get());
+	let estimated_blocks = match (position, per_block) {
+		(Some(_), Some(per_block)) if per_block > 0 => Some(
+			messages_ahead
+				.saturating_add(message_count)
+				.div_ceil(per_block)
+				.saturated_into(),
+		),
+		_ => None,
+	};
+
+	UmpQueueState {
+		message_count,
+		size: footprint.storage.size,
+		pages: footprint.pages,
+		ready_pages: footprint.ready_pages,
+		overweight: Queue::overweight_messages(origin),
+		position: position.map(|p| p.saturated_into()),
+		messages_ahead,
+		estimated_blocks,
+	}
+}
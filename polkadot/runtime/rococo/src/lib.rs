@@ -1077,6 +1077,10 @@ parameter_types! {
 	pub MessageQueueServiceWeight: Weight = Perbill::from_percent(20) * BlockWeights::get().max_block;
 	pub const MessageQueueHeapSize: u32 = 32 * 1024;
 	pub const MessageQueueMaxStale: u32 = 96;
+	/// A rough estimate of the weight of processing a single upward message.
+	///
+	/// Only used to estimate the number of blocks until queued upward messages are processed.
+	pub UmpMessageWeightEstimate: Weight = Weight::from_parts(250_000_000, 16 * 1024);
 }
 
 /// Message processor to handle any messages that were enqueued into the `MessageQueue` pallet.
@@ -2011,7 +2015,7 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
-	#[api_version(15)]
+	#[api_version(16)]
 	impl polkadot_primitives::runtime_api::ParachainHost<Block> for Runtime {
 		fn validators() -> Vec<ValidatorId> {
 			parachains_runtime_api_impl::validators::<Runtime>()
@@ -2129,6 +2133,14 @@ sp_api::impl_runtime_apis! {
 			parachains_runtime_api_impl::unapplied_slashes_v2::<Runtime>()
 		}
 
+		fn ump_queue_state(para_id: ParaId) -> polkadot_primitives::vstaging::UmpQueueState {
+			parachains_staging_runtime_api_impl::ump_queue_state::<
+				MessageQueue,
+				MessageQueueServiceWeight,
+				UmpMessageWeightEstimate,
+			>(para_id)
+		}
+
 		fn key_ownership_proof(
 			validator_id: ValidatorId,
 		) -> Option<slashing::OpaqueKeyOwnershipProof> {
@@ -2200,6 +2212,24 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl polkadot_primitives::runtime_api::OnDemandApi<Block> for Runtime {
+		fn queue_status() -> polkadot_primitives::vstaging::OnDemandQueueStatus<Balance> {
+			OnDemandAssignmentProvider::queue_status()
+		}
+
+		fn spot_price_forecast(blocks: u32) -> Vec<Balance> {
+			OnDemandAssignmentProvider::spot_price_forecast(blocks)
+		}
+
+		fn para_orders(para_id: ParaId) -> polkadot_primitives::vstaging::OnDemandParaOrders {
+			OnDemandAssignmentProvider::para_orders(para_id)
+		}
+
+		fn credits(who: AccountId) -> Balance {
+			parachains_on_demand::Credits::<Runtime>::get(who)
+		}
+	}
+
 	#[api_version(6)]
 	impl sp_consensus_beefy::BeefyApi<Block, BeefyId> for Runtime {
 		fn beefy_genesis() -> Option<BlockNumber> {
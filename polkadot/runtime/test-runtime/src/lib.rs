@@ -962,7 +962,7 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
-	#[api_version(15)]
+	#[api_version(16)]
 	impl polkadot_primitives::runtime_api::ParachainHost<Block> for Runtime {
 		fn validators() -> Vec<ValidatorId> {
 			runtime_impl::validators::<Runtime>()
@@ -1077,6 +1077,11 @@ sp_api::impl_runtime_apis! {
 			runtime_impl::unapplied_slashes_v2::<Runtime>()
 		}
 
+		fn ump_queue_state(para_id: ParaId) -> polkadot_primitives::vstaging::UmpQueueState {
+			// There is no message queue to estimate processing times for.
+			staging_runtime_impl::ump_queue_state::<(), (), ()>(para_id)
+		}
+
 		fn key_ownership_proof(
 			validator_id: ValidatorId,
 		) -> Option<slashing::OpaqueKeyOwnershipProof> {
@@ -0,0 +1,81 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Converts between SCALE-encoded versioned XCM data structures and their text format.
+
+use codec::{DecodeLimit, Encode};
+use scale_info::TypeInfo;
+use staging_xcm::{
+	text, VersionedAsset, VersionedAssets, VersionedLocation, VersionedXcm, MAX_XCM_DECODE_DEPTH,
+};
+use std::io::Read;
+
+const USAGE: &str = "\
+Usage: xcm-text <print|parse> [xcm|location|asset|assets] [INPUT]
+
+  print  Prints the hex-encoded versioned value INPUT as text.
+  parse  Parses the text INPUT, printing the hex-encoded versioned value.
+
+The kind of value defaults to `xcm`, INPUT is read from stdin if not given.";
+
+fn main() {
+	if let Err(error) = run(std::env::args().skip(1).collect()) {
+		eprintln!("{error}");
+		std::process::exit(1);
+	}
+}
+
+fn run(args: Vec<String>) -> Result<(), String> {
+	let (command, kind, input) = match &args[..] {
+		[command] => (command, "xcm", None),
+		[command, kind] => (command, kind.as_str(), None),
+		[command, kind, input] => (command, kind.as_str(), Some(input.clone())),
+		_ => return Err(USAGE.into()),
+	};
+	let input = match input {
+		Some(input) => input,
+		None => {
+			let mut input = String::new();
+			std::io::stdin().read_to_string(&mut input).map_err(|error| error.to_string())?;
+			input
+		},
+	};
+	let output = match (command.as_str(), kind) {
+		("print", "xcm") => print::<VersionedXcm<()>>(&input),
+		("print", "location") => print::<VersionedLocation>(&input),
+		("print", "asset") => print::<VersionedAsset>(&input),
+		("print", "assets") => print::<VersionedAssets>(&input),
+		("parse", "xcm") => parse::<VersionedXcm<()>>(&input),
+		("parse", "location") => parse::<VersionedLocation>(&input),
+		("parse", "asset") => parse::<VersionedAsset>(&input),
+		("parse", "assets") => parse::<VersionedAssets>(&input),
+		_ => return Err(USAGE.into()),
+	}?;
+	println!("{output}");
+	Ok(())
+}
+
+fn print<T: DecodeLimit + Encode + TypeInfo + 'static>(input: &str) -> Result<String, String> {
+	let bytes = array_bytes::hex2bytes(input.trim()).map_err(|_| "invalid hex".to_string())?;
+	let value = T::decode_all_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut &bytes[..])
+		.map_err(|error| error.to_string())?;
+	text::to_string_pretty(&value).map_err(|error| error.to_string())
+}
+
+fn parse<T: DecodeLimit + Encode + TypeInfo + 'static>(input: &str) -> Result<String, String> {
+	let value = text::from_str::<T>(input).map_err(|error| error.to_string())?;
+	Ok(array_bytes::bytes2hex("0x", value.encode()))
+}
@@ -0,0 +1,84 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_support::traits::Get;
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn set_rate_limit() -> Result<(), BenchmarkError> {
+		let origin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let location = Location::new(1, [Parachain(1000)]);
+		let limit = RateLimit {
+			period: T::MaxPeriod::get(),
+			max_messages: 100,
+			max_weight: Weight::from_parts(1_000_000_000, 1_000_000),
+		};
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, Box::new(location.clone().into()), Some(limit));
+
+		assert_eq!(RateLimits::<T>::get(VersionedLocation::from(location)), Some(limit));
+		Ok(())
+	}
+
+	/// Admitting a message from an origin with `n - 1` interior junctions below the location of
+	/// its rate limit, whose window is filled with usage of earlier blocks.
+	#[benchmark]
+	fn try_admit(n: Linear<1, 8>) -> Result<(), BenchmarkError> {
+		let location = Location::new(1, [Parachain(1000)]);
+		let mut origin = location.clone();
+		for i in 1..n {
+			origin
+				.push_interior(GeneralIndex(i.into()))
+				.map_err(|_| BenchmarkError::Stop("Origin has too many junctions"))?;
+		}
+		let period = T::MaxPeriod::get();
+		let limit = RateLimit {
+			period,
+			max_messages: u32::MAX,
+			max_weight: Weight::from_parts(u64::MAX, u64::MAX),
+		};
+		let key = VersionedLocation::from(location);
+		RateLimits::<T>::insert(&key, limit);
+
+		let now: BlockNumberFor<T> = period.into();
+		frame_system::Pallet::<T>::set_block_number(now);
+		let usage: UsageOf<T> = (1..period)
+			.map(|block| UsageBucket { block: block.into(), messages: 1, weight: Weight::zero() })
+			.collect::<alloc::vec::Vec<_>>()
+			.try_into()
+			.map_err(|_| BenchmarkError::Stop("Usage exceeds MaxPeriod"))?;
+		Usage::<T>::insert(&key, usage);
+
+		let admitted;
+		#[block]
+		{
+			admitted = <Pallet<T> as CheckRateLimit>::try_admit(&origin, Weight::zero());
+		}
+
+		assert!(admitted);
+		assert_eq!(Usage::<T>::get(&key).len() as u32, period);
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,285 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pallet for throttling incoming XCM programs per origin.
+//!
+//! Well-formed, paid messages pass the usual barriers no matter how many of them an origin sends.
+//! This pallet keeps a budget of messages and weight per origin over a sliding window of blocks and
+//! implements [`CheckRateLimit`], so that it can be plugged into the
+//! [`DenyRateLimited`](xcm_builder::DenyRateLimited) barrier:
+//!
+//! ```ignore
+//! pub type Barrier = TrailingSetTopicAsId<(
+//!     TakeWeightCredit,
+//!     WithComputedOrigin<
+//!         DenyThenTry<
+//!             (DenyReserveTransferToRelayChain, DenyRateLimited<XcmRateLimiter>),
+//!             AllowTopLevelPaidExecutionFrom<Everything>,
+//!         >,
+//!         UniversalLocation,
+//!         ConstU32<8>,
+//!     >,
+//! )>;
+//! ```
+//!
+//! Limits are configured by [`Config::AdminOrigin`] for a location and apply to all messages whose
+//! origin is that location or one of its interior locations, which share a single budget. If
+//! several configured locations match an origin, the most specific one applies. Origins without a
+//! matching limit are not throttled.
+//!
+//! A message is admitted as long as the budget of its origin is not used up yet, and is then
+//! accounted for with the weight estimated by the barrier. The message that uses up the budget
+//! thus may exceed it, and triggers [`Event::Throttled`]. Further messages are denied until the
+//! window moves on, without touching the storage. Like any message failing the barrier, they are
+//! consumed by the message queue without being retried, so that a throttled origin does not hold
+//! up the other messages of the queue which delivered them.
+//!
+//! The executor doesn't account for the storage accesses of barriers, so the pallet registers
+//! their weight with `frame_system` itself, as [`WeightInfo::try_admit`] of the number of looked up
+//! locations.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "runtime-benchmarks")]
+mod benchmarking;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+use alloc::boxed::Box;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::{defensive, pallet_prelude::*, BoundedVec};
+use frame_system::pallet_prelude::*;
+use scale_info::TypeInfo;
+use sp_runtime::traits::Saturating;
+use xcm::prelude::*;
+use xcm_executor::traits::CheckRateLimit;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+const LOG_TARGET: &str = "xcm::rate-limiter";
+
+/// A budget of messages and weight for a window of blocks.
+#[derive(
+	Clone,
+	Copy,
+	Debug,
+	PartialEq,
+	Eq,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	MaxEncodedLen,
+	TypeInfo,
+)]
+pub struct RateLimit {
+	/// The length of the sliding window, in blocks.
+	pub period: u32,
+	/// The number of messages admitted within the window.
+	pub max_messages: u32,
+	/// The total estimated weight of the messages admitted within the window.
+	pub max_weight: Weight,
+}
+
+/// The messages admitted in a single block.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, MaxEncodedLen, TypeInfo)]
+pub struct UsageBucket<BlockNumber> {
+	/// The block the messages were admitted in.
+	pub block: BlockNumber,
+	/// The number of admitted messages.
+	pub messages: u32,
+	/// The total estimated weight of the admitted messages.
+	pub weight: Weight,
+}
+
+/// The usage of a budget, in blocks of ascending number.
+pub type UsageOf<T> = BoundedVec<UsageBucket<BlockNumberFor<T>>, <T as Config>::MaxPeriod>;
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		#[allow(deprecated)]
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin allowed to configure rate limits.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The maximum length of the sliding window of a rate limit, in blocks.
+		#[pallet::constant]
+		type MaxPeriod: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The configured rate limits.
+	#[pallet::storage]
+	pub type RateLimits<T: Config> =
+		StorageMap<_, Blake2_128Concat, VersionedLocation, RateLimit, OptionQuery>;
+
+	/// The usage of the budget of each configured rate limit within its current window.
+	#[pallet::storage]
+	pub type Usage<T: Config> =
+		StorageMap<_, Blake2_128Concat, VersionedLocation, UsageOf<T>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The rate limit of a location was set or removed.
+		RateLimitSet { location: Location, limit: Option<RateLimit> },
+		/// The budget of a location is used up, further messages are denied until the window
+		/// moves on.
+		Throttled { location: Location, messages: u32, weight: Weight },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The location could not be converted to the latest XCM version.
+		BadVersion,
+		/// The period of the rate limit is zero or exceeds [`Config::MaxPeriod`].
+		InvalidPeriod,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the rate limit of `location`, or remove it if `limit` is `None`.
+		///
+		/// The limit applies to messages from `location` and from all of its interior locations.
+		/// Any usage of a previous budget of `location` is discarded.
+		///
+		/// Can only be called by [`Config::AdminOrigin`].
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_rate_limit())]
+		pub fn set_rate_limit(
+			origin: OriginFor<T>,
+			location: Box<VersionedLocation>,
+			limit: Option<RateLimit>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let location: Location = (*location).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			if let Some(limit) = &limit {
+				ensure!(
+					limit.period > 0 && limit.period <= T::MaxPeriod::get(),
+					Error::<T>::InvalidPeriod
+				);
+			}
+
+			let key = VersionedLocation::from(location.clone());
+			RateLimits::<T>::set(&key, limit);
+			Usage::<T>::remove(&key);
+
+			Self::deposit_event(Event::RateLimitSet { location, limit });
+			Ok(())
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// Returns the most specific configured location `origin` falls under, with its rate limit.
+	pub fn rate_limit_of(origin: &Location) -> Option<(Location, RateLimit)> {
+		Self::find_rate_limit(origin).0
+	}
+
+	/// Like [`Self::rate_limit_of`], also returning the number of locations looked up.
+	fn find_rate_limit(origin: &Location) -> (Option<(Location, RateLimit)>, u32) {
+		let mut location = origin.clone();
+		let mut lookups = 0;
+		loop {
+			lookups += 1;
+			if let Some(limit) = RateLimits::<T>::get(VersionedLocation::from(location.clone())) {
+				return (Some((location, limit)), lookups)
+			}
+			if location.take_last().is_none() {
+				return (None, lookups)
+			}
+		}
+	}
+
+	/// Returns the number of messages and the weight admitted within the window of `limit`
+	/// ending at `now`, pruning usage which fell out of the window.
+	fn prune_usage(
+		usage: &mut UsageOf<T>,
+		now: BlockNumberFor<T>,
+		limit: &RateLimit,
+	) -> (u32, Weight) {
+		let period: BlockNumberFor<T> = limit.period.into();
+		usage.retain(|bucket| bucket.block.saturating_add(period) > now);
+		usage.iter().fold((0, Weight::zero()), |(messages, weight), bucket| {
+			(messages.saturating_add(bucket.messages), weight.saturating_add(bucket.weight))
+		})
+	}
+}
+
+impl<T: Config> CheckRateLimit for Pallet<T> {
+	fn try_admit(origin: &Location, max_weight: Weight) -> bool {
+		let (rate_limit, lookups) = Self::find_rate_limit(origin);
+		frame_system::Pallet::<T>::register_extra_weight_unchecked(
+			T::WeightInfo::try_admit(lookups),
+			DispatchClass::Mandatory,
+		);
+		let Some((location, limit)) = rate_limit else { return true };
+		let now = frame_system::Pallet::<T>::block_number();
+
+		let key = VersionedLocation::from(location.clone());
+		let mut usage = Usage::<T>::get(&key);
+		let (messages, weight) = Self::prune_usage(&mut usage, now, &limit);
+		if messages >= limit.max_messages || weight.any_gte(limit.max_weight) {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?origin,
+				?location,
+				?messages,
+				?weight,
+				"Denying message, budget is used up",
+			);
+			return false
+		}
+
+		match usage.last_mut() {
+			Some(bucket) if bucket.block == now => {
+				bucket.messages.saturating_inc();
+				bucket.weight.saturating_accrue(max_weight);
+			},
+			_ => {
+				// Only buckets of the `period - 1` blocks before `now` were retained.
+				let bucket = UsageBucket { block: now, messages: 1, weight: max_weight };
+				if usage.try_push(bucket).is_err() {
+					defensive!("Usage is bounded by the period of the rate limit; qed");
+				}
+			},
+		}
+		Usage::<T>::insert(&key, usage);
+
+		let (messages, weight) = (messages.saturating_add(1), weight.saturating_add(max_weight));
+		if messages >= limit.max_messages || weight.any_gte(limit.max_weight) {
+			Self::deposit_event(Event::Throttled { location, messages, weight });
+		}
+
+		true
+	}
+}
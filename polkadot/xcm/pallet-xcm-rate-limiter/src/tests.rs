@@ -0,0 +1,288 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::mock::*;
+use frame_support::{
+	assert_noop, assert_ok,
+	traits::{Everything, ProcessMessageError},
+};
+use sp_runtime::DispatchError;
+use xcm_builder::{
+	AllowUnpaidExecutionFrom, DenyRateLimited, DenyReserveTransferToRelayChain, DenyThenTry,
+	WithComputedOrigin,
+};
+use xcm_executor::traits::{Properties, ShouldExecute};
+
+type Barrier = WithComputedOrigin<
+	DenyThenTry<
+		(DenyReserveTransferToRelayChain, DenyRateLimited<XcmRateLimiter>),
+		AllowUnpaidExecutionFrom<Everything>,
+	>,
+	UniversalLocation,
+	ConstU32<2>,
+>;
+
+fn sibling(para_id: u32) -> Location {
+	Location::new(1, [Parachain(para_id)])
+}
+
+fn limit(max_messages: u32, max_weight: u64) -> RateLimit {
+	RateLimit { period: 3, max_messages, max_weight: Weight::from_parts(max_weight, max_weight) }
+}
+
+fn set_limit(location: Location, limit: RateLimit) {
+	assert_ok!(XcmRateLimiter::set_rate_limit(
+		RuntimeOrigin::root(),
+		Box::new(location.into()),
+		Some(limit)
+	));
+}
+
+fn admit(origin: &Location, weight: u64) -> bool {
+	XcmRateLimiter::try_admit(origin, Weight::from_parts(weight, weight))
+}
+
+fn throttled_events() -> Vec<Event<Test>> {
+	System::events()
+		.into_iter()
+		.filter_map(|record| match record.event {
+			RuntimeEvent::XcmRateLimiter(event @ Event::Throttled { .. }) => Some(event),
+			_ => None,
+		})
+		.collect()
+}
+
+#[test]
+fn set_rate_limit_works() {
+	new_test_ext().execute_with(|| {
+		let location = sibling(1000);
+		assert_noop!(
+			XcmRateLimiter::set_rate_limit(
+				RuntimeOrigin::signed(1),
+				Box::new(location.clone().into()),
+				Some(limit(1, 10))
+			),
+			DispatchError::BadOrigin
+		);
+		for period in [0, 11] {
+			assert_noop!(
+				XcmRateLimiter::set_rate_limit(
+					RuntimeOrigin::root(),
+					Box::new(location.clone().into()),
+					Some(RateLimit { period, ..limit(1, 10) })
+				),
+				Error::<Test>::InvalidPeriod
+			);
+		}
+
+		set_limit(location.clone(), limit(1, 10));
+		System::assert_last_event(
+			Event::RateLimitSet { location: location.clone(), limit: Some(limit(1, 10)) }.into(),
+		);
+		assert!(admit(&location, 1));
+		assert!(!admit(&location, 1));
+
+		// Setting the limit again discards the usage of the previous budget.
+		set_limit(location.clone(), limit(1, 10));
+		assert!(admit(&location, 1));
+
+		assert_ok!(XcmRateLimiter::set_rate_limit(
+			RuntimeOrigin::root(),
+			Box::new(location.clone().into()),
+			None
+		));
+		assert_eq!(RateLimits::<Test>::get(VersionedLocation::from(location.clone())), None);
+		assert!(Usage::<Test>::get(VersionedLocation::from(location.clone())).is_empty());
+		assert!(admit(&location, 1));
+	});
+}
+
+#[test]
+fn message_budget_is_enforced_over_sliding_window() {
+	new_test_ext().execute_with(|| {
+		let origin = sibling(1000);
+		set_limit(origin.clone(), limit(3, 1_000));
+
+		assert!(admit(&origin, 1));
+		System::set_block_number(2);
+		assert!(admit(&origin, 1));
+		assert!(throttled_events().is_empty());
+		assert!(admit(&origin, 1));
+		assert_eq!(
+			throttled_events(),
+			vec![Event::Throttled {
+				location: origin.clone(),
+				messages: 3,
+				weight: Weight::from_parts(3, 3)
+			}]
+		);
+		assert!(!admit(&origin, 1));
+
+		// The message of block 1 is still within the window.
+		System::set_block_number(3);
+		assert!(!admit(&origin, 1));
+
+		// Block 1 fell out of the window, block 2 did not.
+		System::set_block_number(4);
+		assert!(admit(&origin, 1));
+		assert!(!admit(&origin, 1));
+
+		// Other origins are not affected.
+		assert!(admit(&sibling(1001), 1));
+	});
+}
+
+#[test]
+fn weight_budget_is_enforced() {
+	new_test_ext().execute_with(|| {
+		let origin = sibling(1000);
+		set_limit(origin.clone(), limit(100, 10));
+
+		assert!(admit(&origin, 6));
+		// The message using up the budget is admitted, even if it exceeds the budget.
+		assert!(admit(&origin, 6));
+		assert_eq!(
+			throttled_events(),
+			vec![Event::Throttled {
+				location: origin.clone(),
+				messages: 2,
+				weight: Weight::from_parts(12, 12)
+			}]
+		);
+		assert!(!admit(&origin, 1));
+	});
+}
+
+#[test]
+fn most_specific_location_applies() {
+	new_test_ext().execute_with(|| {
+		let parachain = sibling(1000);
+		let account =
+			Location::new(1, [Parachain(1000), AccountId32 { network: None, id: [1; 32] }]);
+		let other_account =
+			Location::new(1, [Parachain(1000), AccountId32 { network: None, id: [2; 32] }]);
+		set_limit(parachain.clone(), limit(2, 1_000));
+		set_limit(account.clone(), limit(1, 1_000));
+
+		assert_eq!(
+			XcmRateLimiter::rate_limit_of(&account),
+			Some((account.clone(), limit(1, 1_000)))
+		);
+		assert_eq!(
+			XcmRateLimiter::rate_limit_of(&other_account),
+			Some((parachain.clone(), limit(2, 1_000)))
+		);
+		assert_eq!(XcmRateLimiter::rate_limit_of(&Location::parent()), None);
+
+		assert!(admit(&account, 1));
+		assert!(!admit(&account, 1));
+
+		// Interior locations without a limit of their own share the budget of the parachain.
+		assert!(admit(&other_account, 1));
+		assert!(admit(&parachain, 1));
+		assert!(!admit(&other_account, 1));
+	});
+}
+
+#[test]
+fn barrier_composes_with_computed_origin_and_deny() {
+	new_test_ext().execute_with(|| {
+		let origin = sibling(1000);
+		set_limit(origin.clone(), limit(1, 1_000));
+		let should_execute = |mut message: Xcm<()>| {
+			Barrier::should_execute(
+				&Parent.into(),
+				message.inner_mut(),
+				Weight::from_parts(10, 10),
+				&mut Properties { weight_credit: Weight::zero(), message_id: None },
+			)
+		};
+
+		// Denied messages are not accounted for.
+		assert_eq!(
+			should_execute(Xcm(vec![
+				DescendOrigin(Parachain(1000).into()),
+				InitiateReserveWithdraw {
+					assets: Wild(All),
+					reserve: Parent.into(),
+					xcm: Xcm::new()
+				},
+			])),
+			Err(ProcessMessageError::Unsupported)
+		);
+
+		let message = || {
+			Xcm(vec![
+				DescendOrigin(Parachain(1000).into()),
+				TransferAsset { assets: (Parent, 100).into(), beneficiary: Here.into() },
+			])
+		};
+		assert_eq!(should_execute(message()), Ok(()));
+		assert_eq!(should_execute(message()), Err(ProcessMessageError::Unsupported));
+
+		// The budget is kept for the computed origin.
+		assert_eq!(
+			Usage::<Test>::get(VersionedLocation::from(origin))
+				.iter()
+				.map(|bucket| bucket.messages)
+				.sum::<u32>(),
+			1
+		);
+	});
+}
+
+#[test]
+fn denied_messages_leave_storage_untouched() {
+	new_test_ext().execute_with(|| {
+		let location = sibling(1000);
+		set_limit(location.clone(), limit(1, 1_000));
+		let origin =
+			Location::new(1, [Parachain(1000), AccountId32 { network: None, id: [1; 32] }]);
+
+		assert!(admit(&origin, 10));
+		System::reset_events();
+		System::set_block_number(3);
+		let usage = Usage::<Test>::get(VersionedLocation::from(location.clone()));
+		assert!(!admit(&origin, 10));
+		assert_eq!(Usage::<Test>::get(VersionedLocation::from(location)), usage);
+		assert!(System::events().is_empty());
+	});
+}
+
+#[test]
+fn barrier_storage_accesses_are_metered() {
+	new_test_ext().execute_with(|| {
+		set_limit(sibling(1000), limit(10, 1_000));
+		let origin =
+			Location::new(1, [Parachain(1000), AccountId32 { network: None, id: [1; 32] }]);
+
+		let before = System::block_weight().total();
+		assert!(admit(&origin, 10));
+		assert_eq!(
+			System::block_weight().total(),
+			before.saturating_add(<() as WeightInfo>::try_admit(2))
+		);
+
+		// Origins without a limit are metered for their lookups as well.
+		let before = System::block_weight().total();
+		assert!(admit(&sibling(2000), 10));
+		assert_eq!(
+			System::block_weight().total(),
+			before.saturating_add(<() as WeightInfo>::try_admit(2))
+		);
+	});
+}
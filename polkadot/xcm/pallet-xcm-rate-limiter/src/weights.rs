@@ -0,0 +1,82 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_xcm_rate_limiter`.
+//!
+//! [`SubstrateWeight`] derives the storage accesses and proof sizes from the benchmarks of this
+//! pallet. Runtimes should provide weights generated by running the benchmarks on their reference
+//! hardware.
+
+use crate::{Config, RateLimit, UsageOf};
+use codec::MaxEncodedLen;
+use core::marker::PhantomData;
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use xcm::VersionedLocation;
+
+/// Weight functions needed for `pallet_xcm_rate_limiter`.
+pub trait WeightInfo {
+	fn set_rate_limit() -> Weight;
+	/// Admitting a message after looking up `n` locations.
+	fn try_admit(n: u32) -> Weight;
+}
+
+/// Proof size of reading an entry of a map keyed by `VersionedLocation` with `Blake2_128Concat`.
+fn map_entry_proof_size(value_size: usize) -> u64 {
+	(16 + VersionedLocation::max_encoded_len() + value_size) as u64
+}
+
+/// Weights for `pallet_xcm_rate_limiter` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `XcmRateLimiter::RateLimits` (r:0 w:1)
+	/// Storage: `XcmRateLimiter::Usage` (r:0 w:1)
+	fn set_rate_limit() -> Weight {
+		Weight::from_parts(10_000_000, 0).saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+	/// Storage: `XcmRateLimiter::RateLimits` (r:n w:0)
+	/// Storage: `XcmRateLimiter::Usage` (r:1 w:1)
+	/// The range of component `n` is `[1, 8]`.
+	fn try_admit(n: u32) -> Weight {
+		let proof_size = map_entry_proof_size(RateLimit::max_encoded_len())
+			.saturating_mul(n.into())
+			.saturating_add(map_entry_proof_size(UsageOf::<T>::max_encoded_len()));
+		Weight::from_parts(8_000_000, proof_size)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64.saturating_add(n.into())))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `XcmRateLimiter::RateLimits` (r:0 w:1)
+	/// Storage: `XcmRateLimiter::Usage` (r:0 w:1)
+	fn set_rate_limit() -> Weight {
+		Weight::from_parts(10_000_000, 0).saturating_add(RocksDbWeight::get().writes(2_u64))
+	}
+	/// Storage: `XcmRateLimiter::RateLimits` (r:n w:0)
+	/// Storage: `XcmRateLimiter::Usage` (r:1 w:1)
+	/// The range of component `n` is `[1, 8]`.
+	fn try_admit(n: u32) -> Weight {
+		Weight::from_parts(8_000_000, 0)
+			.saturating_add(Weight::from_parts(3_000_000, 0).saturating_mul(n.into()))
+			.saturating_add(RocksDbWeight::get().reads(1_u64.saturating_add(n.into())))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
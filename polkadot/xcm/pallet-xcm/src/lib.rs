@@ -74,9 +74,15 @@ use xcm_executor::{
 };
 use xcm_runtime_apis::{
 	authorized_aliases::{Error as AuthorizedAliasersApiError, OriginAliaser},
-	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
+	dry_run::{
+		CallDryRunEffects, Error as XcmDryRunApiError, ExplainedXcmDryRunEffects, XcmDryRunEffects,
+	},
 	fees::Error as XcmPaymentApiError,
+	trapped_assets::{AssetClaim, Error as TrappedAssetsApiError, TrappedAssets},
 	trusted_query::Error as TrustedQueryApiError,
+	versions::{
+		DestinationVersion, Error as XcmVersionApiError, VersionSource, VersionSubscription,
+	},
 };
 
 mod errors;
@@ -768,6 +774,17 @@ pub mod pallet {
 		Ready { response: VersionedResponse, at: BlockNumber },
 	}
 
+	/// The details of an asset trap.
+	#[derive(Clone, Eq, PartialEq, Encode, Decode, Debug, TypeInfo)]
+	pub struct AssetTrapDetails<BlockNumber> {
+		/// The trapped assets, in the version they were trapped with.
+		pub assets: VersionedAssets,
+		/// The block the assets were last trapped in.
+		pub block: BlockNumber,
+		/// The topic of the message which last trapped the assets, if it had one.
+		pub topic: Option<XcmHash>,
+	}
+
 	#[derive(Copy, Clone)]
 	pub(crate) struct LatestVersionedLocation<'a>(pub(crate) &'a Location);
 	impl<'a> EncodeLike<VersionedLocation> for LatestVersionedLocation<'a> {}
@@ -810,6 +827,22 @@ pub mod pallet {
 	#[pallet::storage]
 	pub(super) type AssetTraps<T: Config> = StorageMap<_, Identity, H256, u32, ValueQuery>;
 
+	/// The details of the existing asset traps, indexed by the origin they were trapped for.
+	///
+	/// First key is the blake2 256 hash of the origin (see [`Pallet::asset_trap_origin_key`]),
+	/// which doesn't depend on the XCM version the origin was given in. Second key is the hash of
+	/// the trap in [`AssetTraps`]. Only covers traps which happened after the index was introduced.
+	#[pallet::storage]
+	pub(super) type AssetTrapsByOrigin<T: Config> = StorageDoubleMap<
+		_,
+		Identity,
+		H256,
+		Identity,
+		H256,
+		AssetTrapDetails<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
 	/// Default version to encode XCM when latest version of destination is unknown. If `None`,
 	/// then the destinations whose XCM version is unknown are considered unreachable.
 	#[pallet::storage]
@@ -1990,6 +2023,210 @@ impl<T: Config> Pallet<T> {
 		AssetTraps::<T>::get(trap_id)
 	}
 
+	/// Returns the key of `origin` in [`AssetTrapsByOrigin`].
+	pub fn asset_trap_origin_key(origin: &Location) -> H256 {
+		BlakeTwo256::hash_of(origin)
+	}
+
+	/// Returns the assets trapped for `origin`.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::trapped_assets::TrappedAssetsApi` runtime API.
+	pub fn trapped_assets(
+		origin: VersionedLocation,
+	) -> Result<Vec<TrappedAssets>, TrappedAssetsApiError> {
+		let origin: Location = origin.try_into().map_err(|()| {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::trapped_assets",
+				"Failed to convert versioned location",
+			);
+			TrappedAssetsApiError::VersionedConversionFailed
+		})?;
+		Ok(AssetTrapsByOrigin::<T>::iter_prefix(Self::asset_trap_origin_key(&origin))
+			.map(|(hash, details)| TrappedAssets {
+				hash,
+				assets: details.assets,
+				count: AssetTraps::<T>::get(hash),
+				block: details.block.saturated_into(),
+				topic: details.topic,
+			})
+			.collect())
+	}
+
+	/// Builds the program claiming the trap `hash` of `origin` and depositing the assets to
+	/// `beneficiary`, paying for execution with the trapped amount of `fee_asset` if given.
+	///
+	/// The fee is priced with `Trader`, which should be the `Trader` of the XCM executor config.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::trapped_assets::TrappedAssetsApi` runtime API.
+	pub fn claim_trapped_assets<Trader: xcm_executor::traits::WeightTrader>(
+		origin: VersionedLocation,
+		hash: H256,
+		beneficiary: VersionedLocation,
+		fee_asset: Option<VersionedAssetId>,
+	) -> Result<AssetClaim, TrappedAssetsApiError> {
+		let origin: Location = origin.try_into().map_err(|()| {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::claim_trapped_assets",
+				"Failed to convert origin versioned location",
+			);
+			TrappedAssetsApiError::VersionedConversionFailed
+		})?;
+		let beneficiary: Location = beneficiary.try_into().map_err(|()| {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::claim_trapped_assets",
+				"Failed to convert beneficiary versioned location",
+			);
+			TrappedAssetsApiError::VersionedConversionFailed
+		})?;
+		let details = AssetTrapsByOrigin::<T>::get(Self::asset_trap_origin_key(&origin), hash)
+			.ok_or(TrappedAssetsApiError::TrapNotFound)?;
+
+		// The ticket tells `claim_assets` which version the assets were trapped with.
+		let ticket: Location = GeneralIndex(details.assets.identify_version() as u128).into();
+		let assets: Assets = details.assets.try_into().map_err(|()| {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::claim_trapped_assets",
+				"Failed to convert trapped versioned assets",
+			);
+			TrappedAssetsApiError::VersionedConversionFailed
+		})?;
+		let number_of_assets = assets.len() as u32;
+
+		let mut instructions = vec![ClaimAsset { assets: assets.clone(), ticket }];
+		let fee_asset = fee_asset
+			.map(|fee_asset| {
+				let fee_asset: AssetId = fee_asset.try_into().map_err(|()| {
+					tracing::debug!(
+						target: "xcm::pallet_xcm::claim_trapped_assets",
+						"Failed to convert fee versioned asset id",
+					);
+					TrappedAssetsApiError::VersionedConversionFailed
+				})?;
+				let fees = assets
+					.inner()
+					.iter()
+					.find(|asset| asset.id == fee_asset)
+					.cloned()
+					.ok_or(TrappedAssetsApiError::FeeAssetNotTrapped)?;
+				instructions.push(BuyExecution { fees, weight_limit: Unlimited });
+				Ok(fee_asset)
+			})
+			.transpose()?;
+		instructions
+			.push(DepositAsset { assets: AllCounted(number_of_assets).into(), beneficiary });
+
+		let mut program = Xcm::<<T as Config>::RuntimeCall>(instructions);
+		let weight = T::Weigher::weight(&mut program, Weight::MAX).map_err(|error| {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::claim_trapped_assets",
+				?error,
+				"Failed to calculate weight",
+			);
+			TrappedAssetsApiError::WeightNotComputable
+		})?;
+
+		let fee = fee_asset
+			.map(|fee_asset| {
+				Self::query_weight_to_asset_fee::<Trader>(weight, fee_asset.into()).map_err(
+					|error| {
+						tracing::debug!(
+							target: "xcm::pallet_xcm::claim_trapped_assets",
+							?error,
+							"Failed to price the weight in the fee asset",
+						);
+						TrappedAssetsApiError::FeeNotComputable
+					},
+				)
+			})
+			.transpose()?;
+
+		Ok(AssetClaim { program: VersionedXcm::from(program.into::<()>()), weight, fee })
+	}
+
+	/// Returns the state of version negotiation with `destination`.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::versions::XcmVersionApi` runtime API.
+	pub fn destination_version(
+		destination: VersionedLocation,
+	) -> Result<DestinationVersion, XcmVersionApiError> {
+		let requested_version = destination.identify_version();
+		let destination: Location = destination.try_into().map_err(|()| {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::destination_version",
+				"Failed to convert versioned location",
+			);
+			XcmVersionApiError::VersionedConversionFailed
+		})?;
+		let key = LatestVersionedLocation(&destination);
+
+		// Mirrors `WrapVersion::wrap_version`.
+		let advertised = SupportedVersion::<T>::get(XCM_VERSION, key);
+		let (version, source) = match (advertised, SafeXcmVersion::<T>::get()) {
+			(Some(version), _) => (Some(version.min(XCM_VERSION)), VersionSource::Negotiated),
+			(None, Some(version)) => (Some(version.min(XCM_VERSION)), VersionSource::SafeFallback),
+			(None, None) => (None, VersionSource::Unknown),
+		};
+		let subscription = VersionNotifiers::<T>::get(XCM_VERSION, key)
+			.map(|query_id| {
+				Self::version_subscription(
+					VersionedLocation::from(destination.clone()),
+					query_id,
+					requested_version,
+				)
+			})
+			.transpose()?;
+		let versioned_destination = VersionedLocation::from(destination.clone());
+		let discovery_queued = VersionDiscoveryQueue::<T>::get()
+			.iter()
+			.any(|(location, _)| location == &versioned_destination);
+		let notified = VersionNotifyTargets::<T>::get(XCM_VERSION, key).map(|(_, _, v)| v);
+
+		Ok(DestinationVersion {
+			version,
+			source,
+			advertised,
+			subscription,
+			discovery_queued,
+			notified,
+		})
+	}
+
+	/// Returns the subscriptions to the version changes of other locations, with their locations
+	/// in `xcm_version`.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::versions::XcmVersionApi` runtime API.
+	pub fn version_subscriptions(
+		xcm_version: XcmVersion,
+	) -> Result<Vec<VersionSubscription>, XcmVersionApiError> {
+		VersionNotifiers::<T>::iter_prefix(XCM_VERSION)
+			.map(|(location, query_id)| Self::version_subscription(location, query_id, xcm_version))
+			.collect()
+	}
+
+	/// The version used for destinations whose version is unknown, if any.
+	pub fn safe_xcm_version() -> Option<XcmVersion> {
+		SafeXcmVersion::<T>::get()
+	}
+
+	fn version_subscription(
+		location: VersionedLocation,
+		query_id: QueryId,
+		xcm_version: XcmVersion,
+	) -> Result<VersionSubscription, XcmVersionApiError> {
+		let location = location.into_version(xcm_version).map_err(|()| {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::version_subscription",
+				?xcm_version, "Failed to convert subscribed location",
+			);
+			XcmVersionApiError::VersionedConversionFailed
+		})?;
+		let is_active = matches!(
+			Queries::<T>::get(query_id),
+			Some(QueryStatus::VersionNotifier { is_active: true, .. })
+		);
+		Ok(VersionSubscription { location, query_id, is_active })
+	}
+
 	/// Find `TransferType`s for `assets` and fee identified through `fee_asset_item`, when
 	/// transferring to `dest`.
 	///
@@ -3148,6 +3385,25 @@ impl<T: Config> Pallet<T> {
 		Ok(XcmDryRunEffects { forwarded_xcms, emitted_events: events, execution_result: result })
 	}
 
+	/// Dry-runs `xcm` with the given `origin_location`, capturing a trace of the barriers and
+	/// filters it was checked against and of the executed instructions.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::dry_run::DryRunApi` runtime API.
+	pub fn dry_run_xcm_explained<Router>(
+		origin_location: VersionedLocation,
+		xcm: VersionedXcm<<T as Config>::RuntimeCall>,
+	) -> Result<
+		ExplainedXcmDryRunEffects<<T as frame_system::Config>::RuntimeEvent>,
+		XcmDryRunApiError,
+	>
+	where
+		Router: InspectMessageQueues,
+	{
+		let (effects, explanation) =
+			xcm_executor::trace::capture(|| Self::dry_run_xcm::<Router>(origin_location, xcm));
+		Ok(ExplainedXcmDryRunEffects { effects: effects?, explanation })
+	}
+
 	fn convert_xcms(
 		xcm_version: XcmVersion,
 		xcms: Vec<VersionedXcm<()>>,
@@ -3910,20 +4166,30 @@ impl<T: Config> VersionChangeNotifier for Pallet<T> {
 }
 
 impl<T: Config> DropAssets for Pallet<T> {
-	fn drop_assets(origin: &Location, assets: AssetsInHolding, _context: &XcmContext) -> Weight {
+	fn drop_assets(origin: &Location, assets: AssetsInHolding, context: &XcmContext) -> Weight {
 		if assets.is_empty() {
 			return Weight::zero()
 		}
 		let versioned = VersionedAssets::from(Assets::from(assets));
 		let hash = BlakeTwo256::hash_of(&(&origin, &versioned));
 		AssetTraps::<T>::mutate(hash, |n| *n += 1);
+		AssetTrapsByOrigin::<T>::insert(
+			Self::asset_trap_origin_key(origin),
+			hash,
+			AssetTrapDetails {
+				assets: versioned.clone(),
+				block: frame_system::Pallet::<T>::block_number(),
+				topic: context.topic,
+			},
+		);
 		Self::deposit_event(Event::AssetsTrapped {
 			hash,
 			origin: origin.clone(),
 			assets: versioned,
 		});
 		// TODO #3735: Put the real weight in there.
-		Weight::zero()
+		// Accounts for the storage accesses of `AssetTraps` and `AssetTrapsByOrigin` only.
+		T::DbWeight::get().reads_writes(1, 2)
 	}
 }
 
@@ -3947,7 +4213,10 @@ impl<T: Config> ClaimAssets for Pallet<T> {
 		let hash = BlakeTwo256::hash_of(&(origin.clone(), versioned.clone()));
 		match AssetTraps::<T>::get(hash) {
 			0 => return false,
-			1 => AssetTraps::<T>::remove(hash),
+			1 => {
+				AssetTraps::<T>::remove(hash);
+				AssetTrapsByOrigin::<T>::remove(Self::asset_trap_origin_key(origin), hash);
+			},
 			n => AssetTraps::<T>::insert(hash, n - 1),
 		}
 		Self::deposit_event(Event::AssetsClaimed {
@@ -24,10 +24,10 @@ use crate::{
 	mock::*,
 	pallet::{LockedFungibles, RemoteLockedFungibles, SupportedVersion},
 	xcm_helpers::find_xcm_sent_message_id,
-	AssetTraps, AuthorizedAliasers, Config, CurrentMigration, Error, ExecuteControllerWeightInfo,
-	LatestVersionedLocation, MaxAuthorizedAliases, Pallet, Queries, QueryStatus, RecordedXcm,
-	RemoteLockedFungibleRecord, ShouldRecordXcm, VersionDiscoveryQueue, VersionMigrationStage,
-	VersionNotifiers, VersionNotifyTargets, WeightInfo,
+	AssetTraps, AssetTrapsByOrigin, AuthorizedAliasers, Config, CurrentMigration, Error,
+	ExecuteControllerWeightInfo, LatestVersionedLocation, MaxAuthorizedAliases, Pallet, Queries,
+	QueryStatus, RecordedXcm, RemoteLockedFungibleRecord, ShouldRecordXcm, VersionDiscoveryQueue,
+	VersionMigrationStage, VersionNotifiers, VersionNotifyTargets, WeightInfo,
 };
 use bounded_collections::BoundedVec;
 use frame_support::{
@@ -46,6 +46,10 @@ use xcm_executor::{
 	traits::{Properties, QueryHandler, QueryResponseStatus, ShouldExecute},
 	XcmExecutor,
 };
+use xcm_runtime_apis::{
+	trapped_assets::Error as TrappedAssetsApiError,
+	versions::{DestinationVersion, VersionSource, VersionSubscription},
+};
 use xcm_simulator::fake_message_hash;
 
 const ALICE: AccountId = AccountId::new([0u8; 32]);
@@ -718,6 +722,113 @@ fn claim_assets_works() {
 	});
 }
 
+/// Test that trapped assets are listed per origin and can be claimed with the program built by
+/// `claim_trapped_assets`.
+#[test]
+fn trapped_assets_can_be_listed_and_claimed() {
+	let balances = vec![(ALICE, INITIAL_BALANCE), (BOB, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let topic = [7u8; 32];
+		let trapping_program = Xcm::<RuntimeCall>::builder_unsafe()
+			.set_topic(topic)
+			.withdraw_asset((Here, SEND_AMOUNT))
+			.build();
+		assert_ok!(XcmPallet::execute(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedXcm::from(trapping_program)),
+			BaseXcmWeight::get() * 2,
+		));
+
+		let source: Location = Junction::AccountId32 { network: None, id: ALICE.into() }.into();
+		let versioned_assets = VersionedAssets::from(Assets::from((Here, SEND_AMOUNT)));
+		let hash = BlakeTwo256::hash_of(&(source.clone(), versioned_assets.clone()));
+
+		let trapped = XcmPallet::trapped_assets(source.clone().into()).unwrap();
+		assert_eq!(trapped.len(), 1);
+		assert_eq!(trapped[0].hash, hash);
+		assert_eq!(trapped[0].assets, versioned_assets);
+		assert_eq!(trapped[0].count, 1);
+		assert_eq!(trapped[0].block, System::block_number().saturated_into::<u64>());
+		assert_eq!(trapped[0].topic, Some(topic));
+		// Other origins have nothing trapped.
+		let other: Location = Junction::AccountId32 { network: None, id: BOB.into() }.into();
+		assert_eq!(XcmPallet::trapped_assets(other.clone().into()), Ok(vec![]));
+
+		// Unknown traps and fee assets which were not trapped are rejected.
+		let beneficiary: Location = Junction::AccountId32 { network: None, id: BOB.into() }.into();
+		assert_eq!(
+			XcmPallet::claim_trapped_assets::<Trader>(
+				other.into(),
+				hash,
+				beneficiary.clone().into(),
+				None
+			),
+			Err(TrappedAssetsApiError::TrapNotFound)
+		);
+		assert_eq!(
+			XcmPallet::claim_trapped_assets::<Trader>(
+				source.clone().into(),
+				hash,
+				beneficiary.clone().into(),
+				Some(AssetId(Parent.into()).into()),
+			),
+			Err(TrappedAssetsApiError::FeeAssetNotTrapped)
+		);
+
+		// Paying fees with a trapped asset buys execution with all of it.
+		let claim = XcmPallet::claim_trapped_assets::<Trader>(
+			source.clone().into(),
+			hash,
+			beneficiary.clone().into(),
+			Some(AssetId(Here.into()).into()),
+		)
+		.unwrap();
+		let program: Xcm<()> = claim.program.try_into().unwrap();
+		assert_eq!(
+			program.0,
+			vec![
+				ClaimAsset {
+					assets: (Here, SEND_AMOUNT).into(),
+					ticket: GeneralIndex(XCM_VERSION as u128).into()
+				},
+				BuyExecution { fees: (Here, SEND_AMOUNT).into(), weight_limit: Unlimited },
+				DepositAsset { assets: AllCounted(1).into(), beneficiary: beneficiary.clone() },
+			]
+		);
+		assert_eq!(claim.weight, BaseXcmWeight::get() * 3);
+		assert_eq!(
+			claim.fee,
+			Some(
+				XcmPallet::query_weight_to_asset_fee::<Trader>(
+					claim.weight,
+					AssetId(Here.into()).into()
+				)
+				.unwrap()
+			)
+		);
+
+		// Executing the claim program recovers the assets and clears the trap.
+		let claim = XcmPallet::claim_trapped_assets::<Trader>(
+			source.clone().into(),
+			hash,
+			beneficiary.into(),
+			None,
+		)
+		.unwrap();
+		assert_eq!(claim.fee, None);
+		let program: Xcm<()> = claim.program.try_into().unwrap();
+		assert_ok!(XcmPallet::execute(
+			RuntimeOrigin::signed(ALICE),
+			Box::new(VersionedXcm::from(program.into::<RuntimeCall>())),
+			claim.weight,
+		));
+		assert_eq!(Balances::total_balance(&BOB), INITIAL_BALANCE + SEND_AMOUNT);
+		assert_eq!(AssetTraps::<Test>::iter().collect::<Vec<_>>(), vec![]);
+		assert_eq!(AssetTrapsByOrigin::<Test>::iter().count(), 0);
+		assert_eq!(XcmPallet::trapped_assets(source.into()), Ok(vec![]));
+	});
+}
+
 /// Test failure to complete execution reverts intermediate side-effects.
 ///
 /// XCM program will withdraw and deposit some assets, then fail execution of a further withdraw.
@@ -1118,6 +1229,89 @@ fn subscriber_side_subscription_works() {
 }
 
 /// We should auto-subscribe when we don't know the remote's version.
+#[test]
+fn destination_version_reports_negotiation_state() {
+	new_test_ext_with_balances_and_xcm_version(vec![], Some(XCM_VERSION), vec![]).execute_with(
+		|| {
+			let remote: Location = Parachain(1000).into();
+			let unknown = DestinationVersion {
+				version: Some(XCM_VERSION),
+				source: VersionSource::SafeFallback,
+				advertised: None,
+				subscription: None,
+				discovery_queued: false,
+				notified: None,
+			};
+			assert_eq!(XcmPallet::destination_version(remote.clone().into()), Ok(unknown.clone()));
+			assert_eq!(XcmPallet::safe_xcm_version(), Some(XCM_VERSION));
+
+			// Sending to a destination of unknown version queues it for discovery.
+			assert_ok!(XcmPallet::wrap_version(&remote, Xcm::<()>(vec![])));
+			assert_eq!(
+				XcmPallet::destination_version(remote.clone().into()),
+				Ok(DestinationVersion { discovery_queued: true, ..unknown.clone() })
+			);
+
+			// Negotiation is pending until the destination responds to the subscription.
+			assert_ok!(XcmPallet::force_subscribe_version_notify(
+				RuntimeOrigin::root(),
+				Box::new(remote.clone().into()),
+			));
+			let pending = VersionSubscription {
+				location: remote.clone().into(),
+				query_id: 0,
+				is_active: false,
+			};
+			assert_eq!(
+				XcmPallet::destination_version(remote.clone().into()).unwrap().subscription,
+				Some(pending.clone())
+			);
+			assert_eq!(XcmPallet::version_subscriptions(XCM_VERSION), Ok(vec![pending]));
+
+			let message = Xcm(vec![QueryResponse {
+				query_id: 0,
+				max_weight: Weight::zero(),
+				response: Response::Version(3),
+				querier: None,
+			}]);
+			let mut hash = fake_message_hash(&message);
+			let weight = BaseXcmWeight::get();
+			let r = XcmExecutor::<XcmConfig>::prepare_and_execute(
+				remote.clone(),
+				message,
+				&mut hash,
+				weight,
+				Weight::zero(),
+			);
+			assert_eq!(r, Outcome::Complete { used: weight });
+
+			// Locations are returned in the version of the destination.
+			let v4_remote = VersionedLocation::V4(xcm::v4::Junction::Parachain(1000).into());
+			let active =
+				VersionSubscription { location: v4_remote.clone(), query_id: 0, is_active: true };
+			assert_eq!(
+				XcmPallet::destination_version(v4_remote),
+				Ok(DestinationVersion {
+					version: Some(3),
+					source: VersionSource::Negotiated,
+					advertised: Some(3),
+					subscription: Some(active.clone()),
+					discovery_queued: true,
+					notified: None,
+				})
+			);
+			assert_eq!(XcmPallet::version_subscriptions(4), Ok(vec![active]));
+
+			// Without a safe version, destinations of unknown version are unreachable.
+			assert_ok!(XcmPallet::force_default_xcm_version(RuntimeOrigin::root(), None));
+			assert_eq!(
+				XcmPallet::destination_version(Location::new(1, [Parachain(2000)]).into()),
+				Ok(DestinationVersion { version: None, source: VersionSource::Unknown, ..unknown })
+			);
+		},
+	);
+}
+
 #[test]
 fn auto_subscription_works() {
 	new_test_ext_with_balances_and_xcm_version(vec![], None, vec![]).execute_with(|| {
@@ -0,0 +1,203 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A human-readable text format for XCM data structures.
+//!
+//! The format is meant for reviewing and writing programs, e.g. in governance proposals, and
+//! round-trips: parsing the text of a value gives back the same value. It works for any type
+//! implementing [`TypeInfo`], notably the versioned types like
+//! [`VersionedXcm`](crate::VersionedXcm), [`VersionedLocation`](crate::VersionedLocation) and
+//! [`VersionedAsset`](crate::VersionedAsset), and all types of the supported XCM versions:
+//!
+//! ```text
+//! V5([
+//!     WithdrawAsset([10000000000 @ ../Here]),
+//!     BuyExecution { fees: 10000000000 @ ../Here, weight_limit: Unlimited },
+//!     DepositAsset {
+//!         assets: Wild(AllCounted(1)),
+//!         beneficiary: AccountId32 { network: None, id: 0x0101...01 },
+//!     },
+//! ])
+//! ```
+//!
+//! Values are written like Rust expressions of their types, without type names:
+//! - enums as `Variant`, `Variant(a, b)` or `Variant { a: x, b: y }`,
+//! - structs as `(a, b)` or `{ a: x, b: y }`, structs with a single field as that field,
+//! - sequences as `[a, b]`, byte sequences and arrays as hex, e.g. `0x0102`,
+//! - integers in decimal, optionally with `_` separators.
+//!
+//! Locations are written as paths: one `..` per parent, followed by `Here` or the junctions, e.g.
+//! `Here`, `../Here` or `../Parachain(1000)/PalletInstance(50)`. Assets are written as the amount
+//! or `#` followed by the instance, then `@` and the asset id, e.g. `10 @ ../Here` or
+//! `#Index(1) @ Parachain(1000)/GeneralIndex(2)`. Amounts are always integers in the smallest unit
+//! of the asset: the format knows nothing about the symbols and decimals of assets, so symbolic
+//! amounts like `10 DOT @ ../Here` are not supported.
+//!
+//! Values of recursive types, like the programs of `SetAppendix`, can be nested at most
+//! [`MAX_XCM_DECODE_DEPTH`](crate::MAX_XCM_DECODE_DEPTH) times, for printing as well as for
+//! parsing.
+
+mod parse;
+mod print;
+#[cfg(test)]
+mod tests;
+
+use alloc::{string::String, vec::Vec};
+use codec::{DecodeLimit, Encode};
+use core::fmt;
+use scale_info::{
+	form::PortableForm, PortableRegistry, Registry, Type, TypeDef, TypeDefPrimitive, TypeInfo,
+	Variant,
+};
+
+/// An error of printing or parsing a value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Error {
+	/// The text is malformed at byte `offset`.
+	Syntax {
+		/// The byte offset of the malformed input.
+		offset: usize,
+		/// What is wrong with the input.
+		message: String,
+	},
+	/// The type contains types which can't be represented, e.g. bit sequences.
+	Unsupported(String),
+	/// The value doesn't match its type information, or the parsed value fails to decode, e.g.
+	/// because it has too many instructions.
+	Codec(String),
+}
+
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Syntax { offset, message } => write!(f, "syntax error at {offset}: {message}"),
+			Self::Unsupported(what) => write!(f, "unsupported type: {what}"),
+			Self::Codec(error) => write!(f, "codec error: {error}"),
+		}
+	}
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for Error {}
+
+/// Prints `value` on a single line.
+pub fn to_string<T: Encode + TypeInfo + 'static>(value: &T) -> Result<String, Error> {
+	let (registry, ty) = registry::<T>();
+	print::print(&registry, ty, &value.encode(), false)
+}
+
+/// Prints `value`, putting each instruction of a program on its own line.
+pub fn to_string_pretty<T: Encode + TypeInfo + 'static>(value: &T) -> Result<String, Error> {
+	let (registry, ty) = registry::<T>();
+	print::print(&registry, ty, &value.encode(), true)
+}
+
+/// Parses a value of type `T` from `text`.
+pub fn from_str<T: DecodeLimit + TypeInfo + 'static>(text: &str) -> Result<T, Error> {
+	let (registry, ty) = registry::<T>();
+	let encoded = parse::parse(&registry, ty, text)?;
+	T::decode_all_with_depth_limit(crate::MAX_XCM_DECODE_DEPTH, &mut &encoded[..])
+		.map_err(|error| Error::Codec(alloc::format!("{error}")))
+}
+
+/// The types of the values being printed or parsed, from the outermost one.
+#[derive(Default)]
+struct Nesting(Vec<u32>);
+
+impl Nesting {
+	/// Enters a value of type `ty`, returning `false` if values of `ty` are nested too deeply.
+	fn enter(&mut self, ty: u32) -> bool {
+		let depth = self.0.iter().filter(|entered| **entered == ty).count();
+		if depth > crate::MAX_XCM_DECODE_DEPTH as usize {
+			return false
+		}
+		self.0.push(ty);
+		true
+	}
+
+	/// Leaves the value entered last.
+	fn leave(&mut self) {
+		self.0.pop();
+	}
+}
+
+fn registry<T: TypeInfo + 'static>() -> (PortableRegistry, u32) {
+	let mut registry = Registry::new();
+	let ty = registry.register_type(&scale_info::meta_type::<T>()).id;
+	(registry.into(), ty)
+}
+
+/// The types written in a special syntax.
+enum Special<'a> {
+	/// A location, with the type of its junctions.
+	Location { interior: u32 },
+	/// An asset, with the types of its id and fungibility.
+	Asset { id: u32, fun: u32 },
+	/// A `v3::AssetId`, whose `Concrete` variant is written as the location.
+	ConcreteAssetId(&'a Variant<PortableForm>),
+}
+
+fn special(ty: &Type<PortableForm>) -> Option<Special<'_>> {
+	let field_types = |names: [&str; 2]| match &ty.type_def {
+		TypeDef::Composite(composite) if composite.fields.len() == 2 => {
+			let [first, second] = [0, 1].map(|i| &composite.fields[i]);
+			(first.name.as_deref() == Some(names[0]) && second.name.as_deref() == Some(names[1]))
+				.then(|| (first.ty.id, second.ty.id))
+		},
+		_ => None,
+	};
+	match ty.path.ident().as_deref() {
+		Some("Location" | "MultiLocation") =>
+			field_types(["parents", "interior"]).map(|(_, interior)| Special::Location { interior }),
+		Some("Asset" | "MultiAsset") =>
+			field_types(["id", "fun"]).map(|(id, fun)| Special::Asset { id, fun }),
+		Some("AssetId") => match &ty.type_def {
+			TypeDef::Variant(def) => def
+				.variants
+				.iter()
+				.find(|variant| variant.name == "Concrete" && variant.fields.len() == 1)
+				.map(Special::ConcreteAssetId),
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// The types of the junctions of a variant of `Junctions`, flattening arrays of junctions.
+fn junction_types(registry: &PortableRegistry, variant: &Variant<PortableForm>) -> Vec<u32> {
+	let mut types = Vec::new();
+	for field in &variant.fields {
+		match registry.resolve(field.ty.id).map(|ty| &ty.type_def) {
+			Some(TypeDef::Array(array)) =>
+				types.extend((0..array.len).map(|_| array.type_param.id)),
+			_ => types.push(field.ty.id),
+		}
+	}
+	types
+}
+
+fn is_byte(registry: &PortableRegistry, ty: u32) -> bool {
+	matches!(
+		registry.resolve(ty).map(|ty| &ty.type_def),
+		Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+	)
+}
+
+fn resolve(registry: &PortableRegistry, ty: u32) -> Result<&Type<PortableForm>, Error> {
+	registry
+		.resolve(ty)
+		.ok_or_else(|| Error::Codec(alloc::format!("unknown type {ty}")))
+}
@@ -0,0 +1,500 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Parsing of values into their encoding, driven by their type information.
+
+use super::{is_byte, junction_types, resolve, special, Error, Nesting, Special};
+use alloc::{format, string::String, vec, vec::Vec};
+use codec::{Compact, Encode};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+
+/// Parses a value of type `ty` from `text`, returning its encoding.
+pub(super) fn parse(registry: &PortableRegistry, ty: u32, text: &str) -> Result<Vec<u8>, Error> {
+	let mut parser =
+		Parser { registry, lexer: Lexer { text, offset: 0 }, nesting: Nesting::default() };
+	let mut out = Vec::new();
+	parser.value(ty, &mut out)?;
+	match parser.lexer.next()? {
+		(_, Token::End) => Ok(out),
+		(offset, token) => Err(syntax(offset, format!("expected end of input, found {token}"))),
+	}
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Token<'a> {
+	Ident(&'a str),
+	/// A decimal integer, possibly negative and with `_` separators.
+	Number(&'a str),
+	/// A hex string, without the `0x` prefix.
+	Hex(&'a str),
+	/// The `..` of a location.
+	Parent,
+	Punct(char),
+	End,
+}
+
+impl core::fmt::Display for Token<'_> {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		match self {
+			Self::Ident(ident) => write!(f, "`{ident}`"),
+			Self::Number(number) => write!(f, "`{number}`"),
+			Self::Hex(hex) => write!(f, "`0x{hex}`"),
+			Self::Parent => write!(f, "`..`"),
+			Self::Punct(punct) => write!(f, "`{punct}`"),
+			Self::End => write!(f, "end of input"),
+		}
+	}
+}
+
+#[derive(Clone)]
+struct Lexer<'a> {
+	text: &'a str,
+	offset: usize,
+}
+
+impl<'a> Lexer<'a> {
+	/// Returns the next token and its offset, without consuming it.
+	fn peek(&self) -> Result<(usize, Token<'a>), Error> {
+		let rest = &self.text[self.offset..];
+		let start = self.offset + (rest.len() - rest.trim_start().len());
+		let rest = &self.text[start..];
+		let word_len = |skip: usize| {
+			rest[skip..]
+				.find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+				.map_or(rest.len(), |len| len + skip)
+		};
+		let token = match rest.chars().next() {
+			None => Token::End,
+			Some(_) if rest.starts_with("..") => Token::Parent,
+			Some(_) if rest.starts_with("0x") => Token::Hex(&rest[2..word_len(2)]),
+			Some(c) if c.is_ascii_digit() => Token::Number(&rest[..word_len(0)]),
+			Some('-') if rest[1..].starts_with(|c: char| c.is_ascii_digit()) =>
+				Token::Number(&rest[..word_len(1)]),
+			Some(c) if c.is_ascii_alphabetic() || c == '_' => Token::Ident(&rest[..word_len(0)]),
+			Some(c @ ('(' | ')' | '[' | ']' | '{' | '}' | ',' | ':' | '/' | '@' | '#')) =>
+				Token::Punct(c),
+			Some(c) => return Err(syntax(start, format!("unexpected character `{c}`"))),
+		};
+		Ok((start, token))
+	}
+
+	/// Returns and consumes the next token and its offset.
+	fn next(&mut self) -> Result<(usize, Token<'a>), Error> {
+		let (start, token) = self.peek()?;
+		self.offset = start +
+			match token {
+				Token::Ident(text) | Token::Number(text) => text.len(),
+				Token::Hex(text) => text.len() + 2,
+				Token::Parent => 2,
+				Token::Punct(_) => 1,
+				Token::End => 0,
+			};
+		Ok((start, token))
+	}
+
+	/// Consumes the next token if it is `punct`.
+	fn eat(&mut self, punct: char) -> Result<bool, Error> {
+		if self.peek()?.1 == Token::Punct(punct) {
+			self.next()?;
+			return Ok(true)
+		}
+		Ok(false)
+	}
+
+	fn expect(&mut self, punct: char) -> Result<(), Error> {
+		match self.next()? {
+			(_, Token::Punct(found)) if found == punct => Ok(()),
+			(offset, token) => Err(syntax(offset, format!("expected `{punct}`, found {token}"))),
+		}
+	}
+
+	fn ident(&mut self) -> Result<(usize, &'a str), Error> {
+		match self.next()? {
+			(offset, Token::Ident(ident)) => Ok((offset, ident)),
+			(offset, token) => Err(syntax(offset, format!("expected identifier, found {token}"))),
+		}
+	}
+}
+
+struct Parser<'a> {
+	registry: &'a PortableRegistry,
+	lexer: Lexer<'a>,
+	nesting: Nesting,
+}
+
+impl<'a> Parser<'a> {
+	fn value(&mut self, ty: u32, out: &mut Vec<u8>) -> Result<(), Error> {
+		if !self.nesting.enter(ty) {
+			let offset = self.lexer.peek()?.0;
+			let limit = crate::MAX_XCM_DECODE_DEPTH;
+			return Err(syntax(offset, format!("values are nested more than {limit} times")))
+		}
+		let result = self.value_of_type(ty, out);
+		self.nesting.leave();
+		result
+	}
+
+	fn value_of_type(&mut self, ty: u32, out: &mut Vec<u8>) -> Result<(), Error> {
+		let registry = self.registry;
+		let def = resolve(registry, ty)?;
+		match special(def) {
+			Some(Special::Location { interior }) => return self.location(interior, out),
+			Some(Special::Asset { id, fun }) => return self.asset(id, fun, out),
+			Some(Special::ConcreteAssetId(concrete)) => {
+				let TypeDef::Variant(asset_id) = &def.type_def else { unreachable!() };
+				let is_variant = |name: &str| asset_id.variants.iter().any(|v| v.name == name);
+				if !matches!(self.lexer.peek()?.1, Token::Ident(name) if is_variant(name)) {
+					out.push(concrete.index);
+					return self.value(concrete.fields[0].ty.id, out)
+				}
+			},
+			None => {},
+		}
+
+		match &def.type_def {
+			TypeDef::Composite(composite) => self.fields(&composite.fields, false, out),
+			TypeDef::Variant(variant) => {
+				let (offset, name) = self.lexer.ident()?;
+				let Some(variant) = variant.variants.iter().find(|variant| variant.name == name)
+				else {
+					let names: Vec<_> =
+						variant.variants.iter().map(|v| -> &str { v.name.as_ref() }).collect();
+					let message = format!("unknown variant `{name}`, expected one of {names:?}");
+					return Err(syntax(offset, message))
+				};
+				out.push(variant.index);
+				self.fields(&variant.fields, true, out)
+			},
+			TypeDef::Sequence(sequence) => {
+				let ty = sequence.type_param.id;
+				let (len, encoded) = self.sequence(ty)?;
+				Compact(len).encode_to(out);
+				out.extend(encoded);
+				Ok(())
+			},
+			TypeDef::Array(array) => {
+				let offset = self.lexer.peek()?.0;
+				let (len, encoded) = self.sequence(array.type_param.id)?;
+				if len != array.len {
+					let message = format!("expected {} elements, found {len}", array.len);
+					return Err(syntax(offset, message))
+				}
+				out.extend(encoded);
+				Ok(())
+			},
+			TypeDef::Tuple(tuple) => {
+				self.lexer.expect('(')?;
+				for (i, field) in tuple.fields.iter().enumerate() {
+					if i > 0 {
+						self.lexer.expect(',')?;
+					}
+					self.value(field.id, out)?;
+				}
+				self.lexer.eat(',')?;
+				self.lexer.expect(')')
+			},
+			TypeDef::Primitive(primitive) => self.primitive(primitive, out),
+			TypeDef::Compact(compact) => {
+				let inner = resolve(registry, compact.type_param.id)?;
+				let TypeDef::Primitive(primitive) = &inner.type_def else {
+					return Err(Error::Unsupported(format!("compact {:?}", inner.path)))
+				};
+				let (offset, value) = self.number()?;
+				let max = match primitive {
+					TypeDefPrimitive::U8 => u8::MAX as u128,
+					TypeDefPrimitive::U16 => u16::MAX as u128,
+					TypeDefPrimitive::U32 => u32::MAX as u128,
+					TypeDefPrimitive::U64 => u64::MAX as u128,
+					TypeDefPrimitive::U128 => u128::MAX,
+					other => return Err(Error::Unsupported(format!("compact {other:?}"))),
+				};
+				let value = u128::try_from(value)
+					.ok()
+					.filter(|value| *value <= max)
+					.ok_or_else(|| syntax(offset, format!("{value} is out of range")))?;
+				Compact(value).encode_to(out);
+				Ok(())
+			},
+			TypeDef::BitSequence(_) => Err(Error::Unsupported("bit sequence".into())),
+		}
+	}
+
+	/// Parses `fields`, of a variant if `variant`, or of a struct otherwise.
+	fn fields(
+		&mut self,
+		fields: &[Field<PortableForm>],
+		variant: bool,
+		out: &mut Vec<u8>,
+	) -> Result<(), Error> {
+		match fields {
+			[] if variant => Ok(()),
+			[] => {
+				self.lexer.expect('(')?;
+				self.lexer.expect(')')
+			},
+			[field] if !variant => self.value(field.ty.id, out),
+			fields if fields.iter().all(|field| field.name.is_some()) => {
+				let start = self.lexer.peek()?.0;
+				// Named fields may be given in any order.
+				let mut values: Vec<Option<Vec<u8>>> = vec![None; fields.len()];
+				self.lexer.expect('{')?;
+				while !self.lexer.eat('}')? {
+					let (offset, name) = self.lexer.ident()?;
+					let Some(index) = fields.iter().position(|f| f.name.as_deref() == Some(name))
+					else {
+						return Err(syntax(offset, format!("unknown field `{name}`")))
+					};
+					if values[index].is_some() {
+						return Err(syntax(offset, format!("duplicate field `{name}`")))
+					}
+					self.lexer.expect(':')?;
+					let mut value = Vec::new();
+					self.value(fields[index].ty.id, &mut value)?;
+					values[index] = Some(value);
+					if !self.lexer.eat(',')? {
+						self.lexer.expect('}')?;
+						break
+					}
+				}
+				for (field, value) in fields.iter().zip(values) {
+					let Some(value) = value else {
+						let name = field.name.as_deref().unwrap_or_default();
+						return Err(syntax(start, format!("missing field `{name}`")))
+					};
+					out.extend(value);
+				}
+				Ok(())
+			},
+			fields => {
+				self.lexer.expect('(')?;
+				for (i, field) in fields.iter().enumerate() {
+					if i > 0 {
+						self.lexer.expect(',')?;
+					}
+					self.value(field.ty.id, out)?;
+				}
+				self.lexer.eat(',')?;
+				self.lexer.expect(')')
+			},
+		}
+	}
+
+	/// Parses a sequence of elements of type `ty`, returning its length and encoded elements.
+	fn sequence(&mut self, ty: u32) -> Result<(u32, Vec<u8>), Error> {
+		if is_byte(self.registry, ty) {
+			return match self.lexer.next()? {
+				(offset, Token::Hex(hex)) => {
+					let bytes = array_bytes::hex2bytes(hex)
+						.map_err(|_| syntax(offset, format!("invalid hex `0x{hex}`")))?;
+					Ok((bytes.len() as u32, bytes))
+				},
+				(offset, token) => Err(syntax(offset, format!("expected hex, found {token}"))),
+			}
+		}
+		let mut encoded = Vec::new();
+		let mut len = 0u32;
+		self.lexer.expect('[')?;
+		while !self.lexer.eat(']')? {
+			self.value(ty, &mut encoded)?;
+			len += 1;
+			if !self.lexer.eat(',')? {
+				self.lexer.expect(']')?;
+				break
+			}
+		}
+		Ok((len, encoded))
+	}
+
+	/// Parses a location written as `../Parachain(1000)/PalletInstance(50)`.
+	fn location(&mut self, interior: u32, out: &mut Vec<u8>) -> Result<(), Error> {
+		let start = self.lexer.peek()?.0;
+		let mut parents = 0u8;
+		while self.lexer.peek()?.1 == Token::Parent {
+			self.lexer.next()?;
+			parents =
+				parents.checked_add(1).ok_or_else(|| syntax(start, "too many parents".into()))?;
+			if !self.lexer.eat('/')? {
+				// A bare `..` has no junctions.
+				return self.junctions(interior, parents, 0, Vec::new(), start, out)
+			}
+		}
+		if self.lexer.peek()?.1 == Token::Ident("Here") {
+			self.lexer.next()?;
+			return self.junctions(interior, parents, 0, Vec::new(), start, out)
+		}
+
+		let TypeDef::Variant(variants) = &resolve(self.registry, interior)?.type_def else {
+			return Err(Error::Codec("junctions are not an enum".into()))
+		};
+		let junction = variants
+			.variants
+			.iter()
+			.find_map(|variant| junction_types(self.registry, variant).first().copied())
+			.ok_or_else(|| Error::Codec("junctions have no junction".into()))?;
+		let mut encoded = Vec::new();
+		let mut count = 0;
+		loop {
+			self.value(junction, &mut encoded)?;
+			count += 1;
+			if !self.lexer.eat('/')? {
+				break
+			}
+		}
+		self.junctions(interior, parents, count, encoded, start, out)
+	}
+
+	/// Encodes a location of `parents` and `count` encoded junctions.
+	fn junctions(
+		&self,
+		interior: u32,
+		parents: u8,
+		count: usize,
+		encoded: Vec<u8>,
+		offset: usize,
+		out: &mut Vec<u8>,
+	) -> Result<(), Error> {
+		let TypeDef::Variant(variants) = &resolve(self.registry, interior)?.type_def else {
+			return Err(Error::Codec("junctions are not an enum".into()))
+		};
+		let variant = variants
+			.variants
+			.iter()
+			.find(|variant| junction_types(self.registry, variant).len() == count)
+			.ok_or_else(|| syntax(offset, format!("too many junctions: {count}")))?;
+		out.push(parents);
+		out.push(variant.index);
+		out.extend(encoded);
+		Ok(())
+	}
+
+	/// Parses an asset written as `10 @ ../Here` or `#Index(1) @ ../Here`.
+	fn asset(&mut self, id: u32, fun: u32, out: &mut Vec<u8>) -> Result<(), Error> {
+		let TypeDef::Variant(fungibility) = &resolve(self.registry, fun)?.type_def else {
+			return Err(Error::Codec("fungibility is not an enum".into()))
+		};
+		let name = if self.lexer.eat('#')? { "NonFungible" } else { "Fungible" };
+		let variant = fungibility
+			.variants
+			.iter()
+			.find(|variant| variant.name == name && variant.fields.len() == 1)
+			.ok_or_else(|| Error::Codec(format!("fungibility has no {name} variant")))?;
+		let mut fun = vec![variant.index];
+		self.value(variant.fields[0].ty.id, &mut fun)?;
+		// Point out symbolic amounts like `10 DOT @ ../Here`.
+		let mut ahead = self.lexer.clone();
+		if let ((offset, Token::Ident(symbol)), (_, Token::Punct('@'))) =
+			(ahead.next()?, ahead.peek()?)
+		{
+			let message = format!(
+				"expected `@`, found `{symbol}`: amounts are integers in the smallest unit of the \
+				 asset, symbols are not supported"
+			);
+			return Err(syntax(offset, message))
+		}
+		self.lexer.expect('@')?;
+		self.value(id, out)?;
+		out.extend(fun);
+		Ok(())
+	}
+
+	fn primitive(&mut self, primitive: &TypeDefPrimitive, out: &mut Vec<u8>) -> Result<(), Error> {
+		if *primitive == TypeDefPrimitive::Bool {
+			return match self.lexer.ident()? {
+				(_, "true") => Ok(true.encode_to(out)),
+				(_, "false") => Ok(false.encode_to(out)),
+				(offset, other) =>
+					Err(syntax(offset, format!("expected `true` or `false`, found `{other}`"))),
+			}
+		}
+		let (offset, value) = self.number()?;
+		let out_of_range = || syntax(offset, format!("{value} is out of range"));
+		macro_rules! encode {
+			($ty:ty) => {
+				<$ty>::try_from(value).map_err(|_| out_of_range())?.encode_to(out)
+			};
+		}
+		match primitive {
+			TypeDefPrimitive::U8 => encode!(u8),
+			TypeDefPrimitive::U16 => encode!(u16),
+			TypeDefPrimitive::U32 => encode!(u32),
+			TypeDefPrimitive::U64 => encode!(u64),
+			TypeDefPrimitive::U128 => encode!(u128),
+			TypeDefPrimitive::I8 => encode!(i8),
+			TypeDefPrimitive::I16 => encode!(i16),
+			TypeDefPrimitive::I32 => encode!(i32),
+			TypeDefPrimitive::I64 => encode!(i64),
+			TypeDefPrimitive::I128 => encode!(i128),
+			other => return Err(Error::Unsupported(format!("{other:?}"))),
+		}
+		Ok(())
+	}
+
+	/// Parses an integer, which may not fit into an `i128` if it is a large `u128`.
+	fn number(&mut self) -> Result<(usize, Number), Error> {
+		match self.lexer.next()? {
+			(offset, Token::Number(text)) => {
+				let digits: String = text.chars().filter(|c| *c != '_').collect();
+				let number = match digits.strip_prefix('-') {
+					Some(digits) => digits
+						.parse::<u128>()
+						.ok()
+						.and_then(|value| 0i128.checked_sub_unsigned(value).map(Number::Negative)),
+					None => digits.parse::<u128>().ok().map(Number::Positive),
+				};
+				number
+					.map(|number| (offset, number))
+					.ok_or_else(|| syntax(offset, format!("invalid number `{text}`")))
+			},
+			(offset, token) => Err(syntax(offset, format!("expected number, found {token}"))),
+		}
+	}
+}
+
+/// A parsed integer.
+#[derive(Clone, Copy)]
+enum Number {
+	Positive(u128),
+	Negative(i128),
+}
+
+impl core::fmt::Display for Number {
+	fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+		match self {
+			Self::Positive(value) => value.fmt(f),
+			Self::Negative(value) => value.fmt(f),
+		}
+	}
+}
+
+macro_rules! impl_try_from_number {
+	($($ty:ty),*) => {$(
+		impl TryFrom<Number> for $ty {
+			type Error = ();
+			fn try_from(number: Number) -> Result<Self, ()> {
+				match number {
+					Number::Positive(value) => value.try_into().map_err(|_| ()),
+					Number::Negative(value) => value.try_into().map_err(|_| ()),
+				}
+			}
+		}
+	)*};
+}
+
+impl_try_from_number!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+fn syntax(offset: usize, message: String) -> Error {
+	Error::Syntax { offset, message }
+}
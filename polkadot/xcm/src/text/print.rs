@@ -0,0 +1,282 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Printing of encoded values, driven by their type information.
+
+use super::{is_byte, junction_types, resolve, special, Error, Nesting, Special};
+use alloc::{format, string::String};
+use codec::{Compact, Decode};
+use core::fmt::Write;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+
+/// Prints the value of type `ty` encoded in `encoded`.
+pub(super) fn print(
+	registry: &PortableRegistry,
+	ty: u32,
+	encoded: &[u8],
+	pretty: bool,
+) -> Result<String, Error> {
+	let mut printer = Printer {
+		registry,
+		input: encoded,
+		out: String::new(),
+		pretty,
+		indent: 0,
+		nesting: Nesting::default(),
+	};
+	printer.value(ty)?;
+	if !printer.input.is_empty() {
+		return Err(Error::Codec("value not fully consumed".into()))
+	}
+	Ok(printer.out)
+}
+
+struct Printer<'a> {
+	registry: &'a PortableRegistry,
+	input: &'a [u8],
+	out: String,
+	pretty: bool,
+	indent: usize,
+	nesting: Nesting,
+}
+
+impl<'a> Printer<'a> {
+	fn value(&mut self, ty: u32) -> Result<(), Error> {
+		if !self.nesting.enter(ty) {
+			let limit = crate::MAX_XCM_DECODE_DEPTH;
+			return Err(Error::Codec(format!("values are nested more than {limit} times")))
+		}
+		let result = self.value_of_type(ty);
+		self.nesting.leave();
+		result
+	}
+
+	fn value_of_type(&mut self, ty: u32) -> Result<(), Error> {
+		let registry = self.registry;
+		let def = resolve(registry, ty)?;
+		match special(def) {
+			Some(Special::Location { interior }) => return self.location(interior),
+			Some(Special::Asset { id, fun }) => return self.asset(id, fun),
+			Some(Special::ConcreteAssetId(concrete))
+				if self.input.first() == Some(&concrete.index) =>
+			{
+				self.input = &self.input[1..];
+				return self.value(concrete.fields[0].ty.id)
+			},
+			_ => {},
+		}
+
+		match &def.type_def {
+			TypeDef::Composite(composite) => self.fields(&composite.fields, false),
+			TypeDef::Variant(variant) => {
+				let index = self.decode::<u8>()?;
+				let variant = variant
+					.variants
+					.iter()
+					.find(|variant| variant.index == index)
+					.ok_or_else(|| Error::Codec(format!("unknown variant index {index}")))?;
+				self.out.push_str(&variant.name);
+				self.fields(&variant.fields, true)
+			},
+			TypeDef::Sequence(sequence) => {
+				let Compact(len) = self.decode::<Compact<u32>>()?;
+				self.sequence(len, sequence.type_param.id)
+			},
+			TypeDef::Array(array) => self.sequence(array.len, array.type_param.id),
+			TypeDef::Tuple(tuple) => {
+				self.out.push('(');
+				for (i, field) in tuple.fields.iter().enumerate() {
+					if i > 0 {
+						self.out.push_str(", ");
+					}
+					self.value(field.id)?;
+				}
+				self.out.push(')');
+				Ok(())
+			},
+			TypeDef::Primitive(primitive) => self.primitive(primitive),
+			TypeDef::Compact(_) => {
+				let Compact(value) = self.decode::<Compact<u128>>()?;
+				write!(self.out, "{value}").expect("writing to a string can't fail; qed");
+				Ok(())
+			},
+			TypeDef::BitSequence(_) => Err(Error::Unsupported("bit sequence".into())),
+		}
+	}
+
+	/// Prints `fields`, of a variant if `variant`, or of a struct otherwise.
+	fn fields(&mut self, fields: &[Field<PortableForm>], variant: bool) -> Result<(), Error> {
+		match fields {
+			[] if variant => Ok(()),
+			[] => {
+				self.out.push_str("()");
+				Ok(())
+			},
+			[field] if !variant => self.value(field.ty.id),
+			fields if fields.iter().all(|field| field.name.is_some()) => {
+				self.out.push_str(if variant { " { " } else { "{ " });
+				for (i, field) in fields.iter().enumerate() {
+					if i > 0 {
+						self.out.push_str(", ");
+					}
+					self.out.push_str(field.name.as_deref().unwrap_or_default());
+					self.out.push_str(": ");
+					self.value(field.ty.id)?;
+				}
+				self.out.push_str(" }");
+				Ok(())
+			},
+			fields => {
+				self.out.push('(');
+				for (i, field) in fields.iter().enumerate() {
+					if i > 0 {
+						self.out.push_str(", ");
+					}
+					self.value(field.ty.id)?;
+				}
+				self.out.push(')');
+				Ok(())
+			},
+		}
+	}
+
+	fn sequence(&mut self, len: u32, ty: u32) -> Result<(), Error> {
+		if is_byte(self.registry, ty) {
+			let bytes = self.take(len as usize)?;
+			self.out.push_str(&array_bytes::bytes2hex("0x", bytes));
+			return Ok(())
+		}
+		let instructions =
+			resolve(self.registry, ty)?.path.ident().as_deref() == Some("Instruction");
+		if self.pretty && instructions && len > 0 {
+			self.out.push_str("[\n");
+			self.indent += 1;
+			for _ in 0..len {
+				self.write_indent();
+				self.value(ty)?;
+				self.out.push_str(",\n");
+			}
+			self.indent -= 1;
+			self.write_indent();
+			self.out.push(']');
+			return Ok(())
+		}
+		self.out.push('[');
+		for i in 0..len {
+			if i > 0 {
+				self.out.push_str(", ");
+			}
+			self.value(ty)?;
+		}
+		self.out.push(']');
+		Ok(())
+	}
+
+	/// Prints a location as `../Parachain(1000)/PalletInstance(50)`.
+	fn location(&mut self, interior: u32) -> Result<(), Error> {
+		let parents = self.decode::<u8>()?;
+		for _ in 0..parents {
+			self.out.push_str("../");
+		}
+		let TypeDef::Variant(junctions) = &resolve(self.registry, interior)?.type_def else {
+			return Err(Error::Codec("junctions are not an enum".into()))
+		};
+		let index = self.decode::<u8>()?;
+		let variant = junctions
+			.variants
+			.iter()
+			.find(|variant| variant.index == index)
+			.ok_or_else(|| Error::Codec(format!("unknown junctions index {index}")))?;
+		let junctions = junction_types(self.registry, variant);
+		if junctions.is_empty() {
+			self.out.push_str("Here");
+		}
+		for (i, junction) in junctions.into_iter().enumerate() {
+			if i > 0 {
+				self.out.push('/');
+			}
+			self.value(junction)?;
+		}
+		Ok(())
+	}
+
+	/// Prints an asset as `10 @ ../Here` or `#Index(1) @ ../Here`.
+	fn asset(&mut self, id: u32, fun: u32) -> Result<(), Error> {
+		// The id is encoded first, but printed last.
+		let id_start = self.out.len();
+		self.value(id)?;
+		let id_text = self.out.split_off(id_start);
+
+		let TypeDef::Variant(fungibility) = &resolve(self.registry, fun)?.type_def else {
+			return Err(Error::Codec("fungibility is not an enum".into()))
+		};
+		let index = self.decode::<u8>()?;
+		let variant = fungibility
+			.variants
+			.iter()
+			.find(|variant| variant.index == index)
+			.ok_or_else(|| Error::Codec(format!("unknown fungibility index {index}")))?;
+		match (variant.name.as_ref(), &variant.fields[..]) {
+			("Fungible", [amount]) => self.value(amount.ty.id)?,
+			("NonFungible", [instance]) => {
+				self.out.push('#');
+				self.value(instance.ty.id)?;
+			},
+			_ => return Err(Error::Codec(format!("unknown fungibility {}", variant.name))),
+		}
+		self.out.push_str(" @ ");
+		self.out.push_str(&id_text);
+		Ok(())
+	}
+
+	fn primitive(&mut self, primitive: &TypeDefPrimitive) -> Result<(), Error> {
+		let text = match primitive {
+			TypeDefPrimitive::Bool => format!("{}", self.decode::<bool>()?),
+			TypeDefPrimitive::U8 => format!("{}", self.decode::<u8>()?),
+			TypeDefPrimitive::U16 => format!("{}", self.decode::<u16>()?),
+			TypeDefPrimitive::U32 => format!("{}", self.decode::<u32>()?),
+			TypeDefPrimitive::U64 => format!("{}", self.decode::<u64>()?),
+			TypeDefPrimitive::U128 => format!("{}", self.decode::<u128>()?),
+			TypeDefPrimitive::I8 => format!("{}", self.decode::<i8>()?),
+			TypeDefPrimitive::I16 => format!("{}", self.decode::<i16>()?),
+			TypeDefPrimitive::I32 => format!("{}", self.decode::<i32>()?),
+			TypeDefPrimitive::I64 => format!("{}", self.decode::<i64>()?),
+			TypeDefPrimitive::I128 => format!("{}", self.decode::<i128>()?),
+			other => return Err(Error::Unsupported(format!("{other:?}"))),
+		};
+		self.out.push_str(&text);
+		Ok(())
+	}
+
+	fn write_indent(&mut self) {
+		for _ in 0..self.indent {
+			self.out.push_str("    ");
+		}
+	}
+
+	fn decode<T: Decode>(&mut self) -> Result<T, Error> {
+		T::decode(&mut self.input).map_err(|error| Error::Codec(format!("{error}")))
+	}
+
+	fn take(&mut self, len: usize) -> Result<&'a [u8], Error> {
+		if self.input.len() < len {
+			return Err(Error::Codec("unexpected end of input".into()))
+		}
+		let (bytes, rest) = self.input.split_at(len);
+		self.input = rest;
+		Ok(bytes)
+	}
+}
@@ -0,0 +1,312 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate::{
+	v3, v4, v5, VersionedAsset, VersionedAssets, VersionedLocation, VersionedXcm,
+	MAX_XCM_DECODE_DEPTH,
+};
+use alloc::{vec, vec::Vec};
+use core::fmt::Debug;
+
+fn assert_round_trips<T>(value: T)
+where
+	T: Encode + DecodeLimit + TypeInfo + PartialEq + Debug + 'static,
+{
+	for text in [to_string(&value).unwrap(), to_string_pretty(&value).unwrap()] {
+		assert_eq!(from_str::<T>(&text).unwrap_or_else(|e| panic!("{e}: {text}")), value, "{text}");
+	}
+}
+
+/// A program using most instructions of XCM v3, and all kinds of values. Abstract asset ids are
+/// only used if `abstract_assets`, as they can't be converted to later versions.
+fn v3_program(abstract_assets: bool) -> v3::Xcm<()> {
+	use v3::prelude::*;
+	let here: MultiLocation = Here.into();
+	let account = AccountId32 { network: Some(ByGenesis([7; 32])), id: [1; 32] };
+	let response_info = QueryResponseInfo {
+		destination: Parent.into(),
+		query_id: u64::MAX,
+		max_weight: Weight::MAX,
+	};
+	let mut assets: Vec<MultiAsset> = vec![
+		(Parent, 10_000_000_000u128).into(),
+		(Concrete((Parent, Parachain(1000), PalletInstance(50), GeneralIndex(1984)).into()), 1)
+			.into(),
+		(here, Array32([3; 32])).into(),
+	];
+	if abstract_assets {
+		assets.push((Abstract([2; 32]), Index(u128::MAX)).into());
+	}
+	let assets: MultiAssets = assets.into();
+	let pallet =
+		PalletInfo::new(10, b"Balances".to_vec(), b"pallet_balances".to_vec(), 4, 1, 0).unwrap();
+	Xcm(vec![
+		WithdrawAsset(assets.clone()),
+		ReserveAssetDeposited(vec![].into()),
+		QueryResponse {
+			query_id: 1,
+			response: Response::PalletsInfo(vec![pallet].try_into().unwrap()),
+			max_weight: Weight::from_parts(1, 2),
+			querier: Some(here),
+		},
+		QueryResponse {
+			query_id: 2,
+			response: Response::DispatchResult(MaybeErrorCode::Error(
+				vec![1, 2].try_into().unwrap(),
+			)),
+			max_weight: Weight::zero(),
+			querier: None,
+		},
+		TransferReserveAsset {
+			assets: assets.clone(),
+			dest: MultiLocation::new(
+				2,
+				X8(
+					GlobalConsensus(Kusama),
+					Parachain(1000),
+					account,
+					AccountKey20 { network: None, key: [4; 20] },
+					GeneralKey { length: 2, data: [5; 32] },
+					Plurality {
+						id: BodyId::Treasury,
+						part: BodyPart::Fraction { nom: 1, denom: 3 },
+					},
+					OnlyChild,
+					AccountIndex64 { network: Some(Ethereum { chain_id: 1 }), index: 3 },
+				),
+			),
+			xcm: Xcm(vec![ClearOrigin, Trap(7)]),
+		},
+		Transact {
+			origin_kind: OriginKind::SovereignAccount,
+			require_weight_at_most: Weight::from_parts(1_000_000, 64 * 1024),
+			call: vec![0, 1, 2, 3].into(),
+		},
+		DescendOrigin(X1(account)),
+		ReportError(response_info.clone()),
+		DepositAsset { assets: Wild(AllCounted(1)), beneficiary: X1(account).into() },
+		ExchangeAsset {
+			give: Wild(AllOfCounted { id: Concrete(Parent.into()), fun: WildFungible, count: 1 }),
+			want: assets.clone(),
+			maximal: false,
+		},
+		ReportHolding { response_info: response_info.clone(), assets: Definite(assets.clone()) },
+		BuyExecution { fees: (Parent, 1u128).into(), weight_limit: Unlimited },
+		SetErrorHandler(Xcm(vec![RefundSurplus])),
+		SetAppendix(Xcm(vec![ClearError, SetAppendix(Xcm(vec![]))])),
+		ClaimAsset { assets, ticket: GeneralIndex(3).into() },
+		SubscribeVersion { query_id: 3, max_response_weight: Weight::zero() },
+		ExpectOrigin(Some(Parent.into())),
+		ExpectError(Some((1, XcmError::Barrier))),
+		ExpectTransactStatus(MaybeErrorCode::Success),
+		QueryPallet { module_name: b"pallet_xcm".to_vec(), response_info },
+		ExpectPallet {
+			index: 1,
+			name: b"System".to_vec(),
+			module_name: vec![],
+			crate_major: 1,
+			min_crate_minor: 0,
+		},
+		UniversalOrigin(GlobalConsensus(Polkadot)),
+		ExportMessage { network: Kusama, destination: X1(Parachain(1)), xcm: Xcm(vec![]) },
+		LockAsset { asset: (Parent, 5u128).into(), unlocker: Parachain(1).into() },
+		SetFeesMode { jit_withdraw: true },
+		SetTopic([6; 32]),
+		UnpaidExecution { weight_limit: Limited(Weight::from_parts(10, 10)), check_origin: None },
+	])
+}
+
+fn v5_program() -> v5::Xcm<()> {
+	use v5::prelude::*;
+	let mut program: v5::Xcm<()> =
+		v4::Xcm::try_from(v3_program(false)).unwrap().try_into().unwrap();
+	program.0.extend([
+		PayFees { asset: (Parent, 10u128).into() },
+		InitiateTransfer {
+			destination: (Parent, Parachain(1000)).into(),
+			remote_fees: Some(v5::AssetTransferFilter::Teleport(Definite((Parent, 1u128).into()))),
+			preserve_origin: true,
+			assets: vec![v5::AssetTransferFilter::ReserveWithdraw(Wild(All))].try_into().unwrap(),
+			remote_xcm: Xcm(vec![ClearOrigin]),
+		},
+		ExecuteWithOrigin { descendant_origin: None, xcm: Xcm(vec![ClearOrigin]) },
+		SetHints { hints: vec![AssetClaimer { location: Parent.into() }].try_into().unwrap() },
+		Transact { origin_kind: OriginKind::Xcm, fallback_max_weight: None, call: vec![].into() },
+	]);
+	program
+}
+
+#[test]
+fn locations_and_assets_are_printed_as_paths() {
+	use v5::prelude::*;
+	assert_eq!(to_string(&Location::here()).unwrap(), "Here");
+	assert_eq!(to_string(&Location::parent()).unwrap(), "../Here");
+	assert_eq!(
+		to_string(&Location::new(2, [GlobalConsensus(Kusama), Parachain(1000)])).unwrap(),
+		"../../GlobalConsensus(Kusama)/Parachain(1000)"
+	);
+	assert_eq!(to_string(&Asset::from((Parent, 10u128))).unwrap(), "10 @ ../Here");
+	assert_eq!(
+		to_string(&Asset::from(((Parachain(1000), GeneralIndex(2)), Index(1)))).unwrap(),
+		"#Index(1) @ Parachain(1000)/GeneralIndex(2)"
+	);
+	assert_eq!(
+		to_string(&v3::MultiAsset::from((v3::AssetId::Abstract([0; 32]), 1u128))).unwrap(),
+		"1 @ Abstract(0x0000000000000000000000000000000000000000000000000000000000000000)"
+	);
+	assert_eq!(
+		to_string(&VersionedAssets::from(Assets::from(vec![(Here, 1u128).into()]))).unwrap(),
+		"V5([1 @ Here])"
+	);
+}
+
+#[test]
+fn programs_are_pretty_printed() {
+	use v5::prelude::*;
+	let program = VersionedXcm::from(Xcm::<()>(vec![
+		WithdrawAsset((Parent, 10u128).into()),
+		SetAppendix(Xcm(vec![ClearOrigin])),
+		DepositAsset {
+			assets: Wild(AllCounted(1)),
+			beneficiary: AccountKey20 { network: None, key: [0xaa; 20] }.into(),
+		},
+	]));
+	assert_eq!(
+		to_string_pretty(&program).unwrap(),
+		"V5([
+    WithdrawAsset([10 @ ../Here]),
+    SetAppendix([
+        ClearOrigin,
+    ]),
+    DepositAsset { assets: Wild(AllCounted(1)), beneficiary: AccountKey20 { network: None, key: 0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa } },
+])"
+	);
+	assert_eq!(
+		to_string(&program).unwrap(),
+		"V5([WithdrawAsset([10 @ ../Here]), SetAppendix([ClearOrigin]), DepositAsset { assets: \
+		 Wild(AllCounted(1)), beneficiary: AccountKey20 { network: None, key: \
+		 0xaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa } }])"
+	);
+}
+
+#[test]
+fn v3_round_trips() {
+	assert_round_trips(v3_program(true));
+	assert_round_trips(VersionedXcm::V3(v3_program(true)));
+	assert_round_trips(VersionedLocation::V3(v3::MultiLocation::new(1, v3::Junctions::Here)));
+}
+
+#[test]
+fn v4_round_trips() {
+	let program = v4::Xcm::<()>::try_from(v3_program(false)).unwrap();
+	assert_round_trips(program.clone());
+	assert_round_trips(VersionedXcm::V4(program));
+	assert_round_trips(VersionedAsset::V4((v4::Location::parent(), 1u128).into()));
+}
+
+#[test]
+fn v5_round_trips() {
+	assert_round_trips(v5_program());
+	assert_round_trips(VersionedXcm::V5(v5_program()));
+	assert_round_trips(VersionedLocation::V5(v5::Location::new(255, [v5::Junction::OnlyChild])));
+}
+
+#[test]
+fn text_can_be_written_by_hand() {
+	use v5::prelude::*;
+	let program = from_str::<VersionedXcm<()>>(
+		"V5([
+			WithdrawAsset([10_000_000_000 @ ../Here]),
+			BuyExecution { weight_limit: Unlimited, fees: 1 @ .. },
+			DepositAsset { assets: Wild(AllCounted(1)), beneficiary: ../Parachain(1000) },
+		])",
+	)
+	.unwrap();
+	assert_eq!(
+		program,
+		VersionedXcm::V5(Xcm(vec![
+			WithdrawAsset((Parent, 10_000_000_000u128).into()),
+			BuyExecution { fees: (Parent, 1u128).into(), weight_limit: Unlimited },
+			DepositAsset {
+				assets: Wild(AllCounted(1)),
+				beneficiary: (Parent, Parachain(1000)).into()
+			},
+		]))
+	);
+	// `v3::AssetId::Concrete` may be written explicitly.
+	assert_eq!(
+		from_str::<v3::MultiAsset>("1 @ Concrete(Here)").unwrap(),
+		(v3::MultiLocation::here(), 1u128).into()
+	);
+}
+
+#[test]
+fn errors_point_at_the_malformed_input() {
+	use v5::prelude::*;
+	fn syntax<T>(offset: usize, message: &str) -> Result<T, Error> {
+		Err(Error::Syntax { offset, message: message.into() })
+	}
+
+	assert_eq!(
+		from_str::<Location>("../Parachain(1000)/Foo"),
+		syntax(19, "unknown variant `Foo`, expected one of [\"Parachain\", \"AccountId32\", \"AccountIndex64\", \"AccountKey20\", \"PalletInstance\", \"GeneralIndex\", \"GeneralKey\", \"OnlyChild\", \"Plurality\", \"GlobalConsensus\"]")
+	);
+	assert_eq!(
+		from_str::<Location>("../Parachain(4294967296)"),
+		syntax(13, "4294967296 is out of range")
+	);
+	assert_eq!(from_str::<Location>("Here Here"), syntax(5, "expected end of input, found `Here`"));
+	assert_eq!(
+		from_str::<Instruction<()>>("BuyExecution { fees: 1 @ Here }"),
+		syntax(13, "missing field `weight_limit`")
+	);
+	assert_eq!(
+		from_str::<Junction>("AccountKey20 { network: None, key: 0x00 }"),
+		syntax(35, "expected 20 elements, found 1")
+	);
+	assert_eq!(from_str::<Asset>("1 Here"), syntax(2, "expected `@`, found `Here`"));
+	assert_eq!(
+		from_str::<Asset>("10 DOT @ ../Here"),
+		syntax(
+			3,
+			"expected `@`, found `DOT`: amounts are integers in the smallest unit of the asset, \
+			 symbols are not supported"
+		)
+	);
+}
+
+#[test]
+fn nesting_is_limited() {
+	use v5::prelude::*;
+	let nested =
+		|depth| (0..depth).fold(ClearOrigin::<()>, |inner, _| SetAppendix(Xcm(vec![inner])));
+
+	// Values nested as deeply as the decoding limit allows are supported.
+	assert_round_trips(nested(MAX_XCM_DECODE_DEPTH));
+
+	// Deeper values are rejected without recursing through all of them.
+	assert!(matches!(to_string(&nested(MAX_XCM_DECODE_DEPTH + 1)), Err(Error::Codec(_))));
+	let text =
+		alloc::format!("{}ClearOrigin{}", "SetAppendix([".repeat(100_000), "])".repeat(100_000));
+	assert_eq!(
+		from_str::<Instruction<()>>(&text),
+		Err(Error::Syntax {
+			offset: 13 * (MAX_XCM_DECODE_DEPTH as usize + 1),
+			message: alloc::format!("values are nested more than {MAX_XCM_DECODE_DEPTH} times"),
+		})
+	);
+}
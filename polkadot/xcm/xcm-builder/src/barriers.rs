@@ -24,7 +24,9 @@ use frame_support::{
 };
 use polkadot_parachain_primitives::primitives::IsSystem;
 use xcm::prelude::*;
-use xcm_executor::traits::{CheckSuspension, DenyExecution, OnResponse, Properties, ShouldExecute};
+use xcm_executor::traits::{
+	CheckRateLimit, CheckSuspension, DenyExecution, OnResponse, Properties, ShouldExecute,
+};
 
 /// Execution barrier that just takes `max_weight` from `properties.weight_credit`.
 ///
@@ -33,6 +35,8 @@ use xcm_executor::traits::{CheckSuspension, DenyExecution, OnResponse, Propertie
 /// out of the local chain to another one.
 pub struct TakeWeightCredit;
 impl ShouldExecute for TakeWeightCredit {
+	const NAME: &'static str = "TakeWeightCredit";
+
 	fn should_execute<RuntimeCall>(
 		origin: &Location,
 		instructions: &mut [Instruction<RuntimeCall>],
@@ -65,6 +69,8 @@ const MAX_ASSETS_FOR_BUY_EXECUTION: usize = 2;
 /// pay for execution.
 pub struct AllowTopLevelPaidExecutionFrom<T>(PhantomData<T>);
 impl<T: Contains<Location>> ShouldExecute for AllowTopLevelPaidExecutionFrom<T> {
+	const NAME: &'static str = "AllowTopLevelPaidExecutionFrom";
+
 	fn should_execute<RuntimeCall>(
 		origin: &Location,
 		instructions: &mut [Instruction<RuntimeCall>],
@@ -173,6 +179,8 @@ pub struct WithComputedOrigin<InnerBarrier, LocalUniversal, MaxPrefixes>(
 impl<InnerBarrier: ShouldExecute, LocalUniversal: Get<InteriorLocation>, MaxPrefixes: Get<u32>>
 	ShouldExecute for WithComputedOrigin<InnerBarrier, LocalUniversal, MaxPrefixes>
 {
+	const NAME: &'static str = "WithComputedOrigin";
+
 	fn should_execute<Call>(
 		origin: &Location,
 		instructions: &mut [Instruction<Call>],
@@ -233,6 +241,8 @@ impl<InnerBarrier: ShouldExecute, LocalUniversal: Get<InteriorLocation>, MaxPref
 /// Requires some inner barrier to pass on the rest of the message.
 pub struct TrailingSetTopicAsId<InnerBarrier>(PhantomData<InnerBarrier>);
 impl<InnerBarrier: ShouldExecute> ShouldExecute for TrailingSetTopicAsId<InnerBarrier> {
+	const NAME: &'static str = "TrailingSetTopicAsId";
+
 	fn should_execute<Call>(
 		origin: &Location,
 		instructions: &mut [Instruction<Call>],
@@ -265,6 +275,8 @@ where
 	Inner: ShouldExecute,
 	SuspensionChecker: CheckSuspension,
 {
+	const NAME: &'static str = "RespectSuspension";
+
 	fn should_execute<Call>(
 		origin: &Location,
 		instructions: &mut [Instruction<Call>],
@@ -285,6 +297,8 @@ where
 /// can be sent.
 pub struct AllowUnpaidExecutionFrom<T>(PhantomData<T>);
 impl<T: Contains<Location>> ShouldExecute for AllowUnpaidExecutionFrom<T> {
+	const NAME: &'static str = "AllowUnpaidExecutionFrom";
+
 	fn should_execute<RuntimeCall>(
 		origin: &Location,
 		instructions: &mut [Instruction<RuntimeCall>],
@@ -321,6 +335,8 @@ pub struct AllowExplicitUnpaidExecutionFrom<T, Aliasers = Nothing>(PhantomData<(
 impl<T: Contains<Location>, Aliasers: ContainsPair<Location, Location>> ShouldExecute
 	for AllowExplicitUnpaidExecutionFrom<T, Aliasers>
 {
+	const NAME: &'static str = "AllowExplicitUnpaidExecutionFrom";
+
 	fn should_execute<Call>(
 		origin: &Location,
 		instructions: &mut [Instruction<Call>],
@@ -437,6 +453,8 @@ impl<Count: Get<u8>> Contains<Location> for IsParentsOnly<Count> {
 /// Allows only messages if the generic `ResponseHandler` expects them via `expecting_response`.
 pub struct AllowKnownQueryResponses<ResponseHandler>(PhantomData<ResponseHandler>);
 impl<ResponseHandler: OnResponse> ShouldExecute for AllowKnownQueryResponses<ResponseHandler> {
+	const NAME: &'static str = "AllowKnownQueryResponses";
+
 	fn should_execute<RuntimeCall>(
 		origin: &Location,
 		instructions: &mut [Instruction<RuntimeCall>],
@@ -465,6 +483,8 @@ impl<ResponseHandler: OnResponse> ShouldExecute for AllowKnownQueryResponses<Res
 /// `UnsubscribeVersion` instruction.
 pub struct AllowSubscriptionsFrom<T>(PhantomData<T>);
 impl<T: Contains<Location>> ShouldExecute for AllowSubscriptionsFrom<T> {
+	const NAME: &'static str = "AllowSubscriptionsFrom";
+
 	fn should_execute<RuntimeCall>(
 		origin: &Location,
 		instructions: &mut [Instruction<RuntimeCall>],
@@ -496,6 +516,8 @@ impl<T: Contains<Location>> ShouldExecute for AllowSubscriptionsFrom<T> {
 /// documentation.
 pub struct AllowHrmpNotificationsFromRelayChain;
 impl ShouldExecute for AllowHrmpNotificationsFromRelayChain {
+	const NAME: &'static str = "AllowHrmpNotificationsFromRelayChain";
+
 	fn should_execute<RuntimeCall>(
 		origin: &Location,
 		instructions: &mut [Instruction<RuntimeCall>],
@@ -535,6 +557,8 @@ where
 	Deny: DenyExecution,
 	Allow: ShouldExecute,
 {
+	const NAME: &'static str = "DenyThenTry";
+
 	fn should_execute<RuntimeCall>(
 		origin: &Location,
 		message: &mut [Instruction<RuntimeCall>],
@@ -549,6 +573,8 @@ where
 // See issue <https://github.com/paritytech/polkadot/issues/5233>
 pub struct DenyReserveTransferToRelayChain;
 impl DenyExecution for DenyReserveTransferToRelayChain {
+	const NAME: &'static str = "DenyReserveTransferToRelayChain";
+
 	fn deny_execution<RuntimeCall>(
 		origin: &Location,
 		message: &mut [Instruction<RuntimeCall>],
@@ -588,6 +614,40 @@ impl DenyExecution for DenyReserveTransferToRelayChain {
 
 environmental::environmental!(recursion_count: u8);
 
+/// Denies executing messages of origins which used up their budget enforced by `RateLimiter`.
+///
+/// Use it as the `Deny` part of [`DenyThenTry`], after any other barriers denying messages, and
+/// wrap it in [`WithComputedOrigin`] to apply the budget to the computed origin rather than to the
+/// origin which delivered the message. A message passing it is accounted for against the budget
+/// of its origin, even if the `Allow` part of [`DenyThenTry`] rejects it later on.
+///
+/// Denied messages fail the barrier like any other rejected message: the message queue consumes
+/// them without retrying them, so that a throttled origin does not hold up the other messages of
+/// the queue which delivered them.
+pub struct DenyRateLimited<RateLimiter>(PhantomData<RateLimiter>);
+impl<RateLimiter: CheckRateLimit> DenyExecution for DenyRateLimited<RateLimiter> {
+	const NAME: &'static str = "DenyRateLimited";
+
+	fn deny_execution<RuntimeCall>(
+		origin: &Location,
+		_instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		_properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		if RateLimiter::try_admit(origin, max_weight) {
+			Ok(())
+		} else {
+			tracing::debug!(
+				target: "xcm::barriers",
+				?origin,
+				?max_weight,
+				"DenyRateLimited: origin exceeded its budget",
+			);
+			Err(ProcessMessageError::Unsupported)
+		}
+	}
+}
+
 /// Denies execution if the XCM contains instructions not meant to run on this chain,
 /// first checking at the top-level and then **recursively**.
 ///
@@ -642,6 +702,8 @@ impl<Inner: DenyExecution> DenyRecursively<Inner> {
 }
 
 impl<Inner: DenyExecution> DenyExecution for DenyRecursively<Inner> {
+	const NAME: &'static str = "DenyRecursively";
+
 	/// Denies execution of restricted local nested XCM instructions.
 	///
 	/// This checks for `SetAppendix`, `SetErrorHandler`, and `ExecuteWithOrigin` instruction
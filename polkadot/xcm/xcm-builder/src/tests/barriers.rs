@@ -741,6 +741,70 @@ fn suspension_should_work() {
 	assert_eq!(r, Ok(()));
 }
 
+#[test]
+fn rate_limit_should_work() {
+	TestRateLimiter::set_budget(2);
+	AllowUnpaidFrom::set(vec![(Parent, Parachain(1000)).into()]);
+	type Barrier = WithComputedOrigin<
+		DenyThenTry<
+			(DenyReserveTransferToRelayChain, DenyRateLimited<TestRateLimiter>),
+			AllowUnpaidExecutionFrom<IsInVec<AllowUnpaidFrom>>,
+		>,
+		ExecutorUniversalLocation,
+		ConstU32<2>,
+	>;
+	let message = || {
+		Xcm::<()>(vec![
+			DescendOrigin(Parachain(1000).into()),
+			TransferAsset { assets: (Parent, 100).into(), beneficiary: Here.into() },
+		])
+	};
+
+	// Messages denied by the preceding barriers are not accounted for.
+	let mut denied = Xcm::<()>(vec![
+		DescendOrigin(Parachain(1000).into()),
+		InitiateReserveWithdraw { assets: Wild(All), reserve: Parent.into(), xcm: Xcm::new() },
+	]);
+	let r = Barrier::should_execute(
+		&Parent.into(),
+		denied.inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Err(ProcessMessageError::Unsupported));
+	assert!(TestRateLimiter::admitted().is_empty());
+
+	// The budget applies to the computed origin.
+	let r = Barrier::should_execute(
+		&Parent.into(),
+		message().inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Ok(()));
+	assert_eq!(TestRateLimiter::admitted(), vec![Location::new(1, [Parachain(1000)])]);
+
+	// Messages rejected by the allowing barriers are accounted for as well.
+	let r = Barrier::should_execute(
+		&Here.into(),
+		message().inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Err(ProcessMessageError::Unsupported));
+	assert_eq!(TestRateLimiter::admitted().len(), 2);
+
+	// Once the budget is used up, messages are denied.
+	let r = Barrier::should_execute(
+		&Parent.into(),
+		message().inner_mut(),
+		Weight::from_parts(10, 10),
+		&mut props(Weight::zero()),
+	);
+	assert_eq!(r, Err(ProcessMessageError::Unsupported));
+	assert_eq!(TestRateLimiter::admitted().len(), 2);
+}
+
 #[test]
 fn allow_subscriptions_from_should_work() {
 	// allow only parent
@@ -43,8 +43,8 @@ pub use frame_support::{
 pub use xcm::latest::{prelude::*, QueryId, Weight};
 pub use xcm_executor::{
 	traits::{
-		AssetExchange, AssetLock, CheckSuspension, ConvertOrigin, DenyExecution, Enact, ExportXcm,
-		FeeManager, FeeReason, LockError, OnResponse, Properties, QueryHandler,
+		AssetExchange, AssetLock, CheckRateLimit, CheckSuspension, ConvertOrigin, DenyExecution,
+		Enact, ExportXcm, FeeManager, FeeReason, LockError, OnResponse, Properties, QueryHandler,
 		QueryResponseStatus, TransactAsset,
 	},
 	AssetsInHolding, Config,
@@ -137,6 +137,8 @@ thread_local! {
 	)>> = RefCell::new(None);
 	pub static SEND_PRICE: RefCell<Assets> = RefCell::new(Assets::new());
 	pub static SUSPENDED: Cell<bool> = Cell::new(false);
+	pub static RATE_LIMIT_BUDGET: Cell<u32> = Cell::new(u32::MAX);
+	pub static RATE_LIMITED: RefCell<Vec<Location>> = RefCell::new(Vec::new());
 }
 pub fn sent_xcm() -> Vec<(Location, opaque::Xcm, XcmHash)> {
 	SENT_XCM.with(|q| (*q.borrow()).clone())
@@ -507,6 +509,29 @@ impl TestSuspender {
 	}
 }
 
+/// Admits up to `RATE_LIMIT_BUDGET` messages, recording the origins of admitted messages.
+pub struct TestRateLimiter;
+impl CheckRateLimit for TestRateLimiter {
+	fn try_admit(origin: &Location, _max_weight: Weight) -> bool {
+		let admitted = RATE_LIMITED.with(|r| r.borrow().len() as u32);
+		if admitted >= RATE_LIMIT_BUDGET.with(|b| b.get()) {
+			return false
+		}
+		RATE_LIMITED.with(|r| r.borrow_mut().push(origin.clone()));
+		true
+	}
+}
+
+impl TestRateLimiter {
+	pub fn set_budget(budget: u32) {
+		RATE_LIMIT_BUDGET.with(|b| b.set(budget));
+	}
+
+	pub fn admitted() -> Vec<Location> {
+		RATE_LIMITED.with(|r| r.borrow().clone())
+	}
+}
+
 pub type TestBarrier = (
 	TakeWeightCredit,
 	AllowKnownQueryResponses<TestResponseHandler>,
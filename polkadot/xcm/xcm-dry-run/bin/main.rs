@@ -0,0 +1,126 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dry-runs an extrinsic or an XCM program on a set of running nodes, following the programs sent
+//! to other chains, and prints the report of the whole route.
+
+use clap::Parser;
+use codec::Decode;
+use serde::Deserialize;
+use std::{path::PathBuf, process::ExitCode};
+use xcm::{latest::InteriorLocation, VersionedLocation, VersionedXcm};
+use xcm_dry_run::{Chain, Network, RpcCaller, DEFAULT_MAX_HOPS};
+
+/// Dry-runs an extrinsic or an XCM program across multiple chains.
+#[derive(Debug, Parser)]
+#[command(rename_all = "kebab-case", version, about)]
+struct Cli {
+	/// JSON file listing the chains of the network, e.g.
+	/// `[{ "name": "asset-hub", "url": "ws://127.0.0.1:9944",
+	/// "location": { "X2": [{ "GlobalConsensus": "Polkadot" }, { "Parachain": 1000 }] } }]`.
+	#[arg(long)]
+	network: PathBuf,
+
+	/// The name of the chain the route starts on.
+	#[arg(long)]
+	chain: String,
+
+	/// The hex-encoded `VersionedLocation` sending the program.
+	#[arg(long, requires = "xcm", conflicts_with_all = ["origin_caller", "call"])]
+	origin: Option<String>,
+
+	/// The hex-encoded `VersionedXcm` to dry-run.
+	#[arg(long, requires = "origin")]
+	xcm: Option<String>,
+
+	/// The hex-encoded `OriginCaller` dispatching the call.
+	#[arg(long, requires = "call")]
+	origin_caller: Option<String>,
+
+	/// The hex-encoded call to dry-run.
+	#[arg(long, requires = "origin_caller")]
+	call: Option<String>,
+
+	/// The maximum number of hops of the route.
+	#[arg(long, default_value_t = DEFAULT_MAX_HOPS)]
+	max_hops: usize,
+}
+
+#[derive(Deserialize)]
+struct ChainConfig {
+	name: String,
+	url: String,
+	location: InteriorLocation,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	match run(Cli::parse()).await {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::FAILURE,
+		Err(error) => {
+			eprintln!("error: {error}");
+			ExitCode::from(2)
+		},
+	}
+}
+
+/// Dry-runs the route, returning whether it succeeded.
+async fn run(cli: Cli) -> Result<bool, String> {
+	let config = std::fs::read(&cli.network)
+		.map_err(|error| format!("failed to read {}: {error}", cli.network.display()))?;
+	let config: Vec<ChainConfig> = serde_json::from_slice(&config)
+		.map_err(|error| format!("invalid network {}: {error}", cli.network.display()))?;
+	let mut chains = Vec::with_capacity(config.len());
+	for chain in config {
+		let api = RpcCaller::connect(&chain.url)
+			.await
+			.map_err(|error| format!("failed to connect to {}: {error}", chain.url))?;
+		chains.push(Chain::new(chain.name, chain.location, api));
+	}
+	let mut network = Network::new(chains).with_max_hops(cli.max_hops);
+
+	let report = match (cli.origin, cli.xcm, cli.origin_caller, cli.call) {
+		(Some(origin), Some(xcm), None, None) => {
+			let origin = decode_hex::<VersionedLocation>(&origin)?
+				.try_into()
+				.map_err(|()| "origin is not convertible to the latest XCM version")?;
+			let xcm = decode_hex::<VersionedXcm<()>>(&xcm)?
+				.try_into()
+				.map_err(|()| "program is not convertible to the latest XCM version")?;
+			network.dry_run_xcm(&cli.chain, origin, xcm).await
+		},
+		(None, None, Some(origin), Some(call)) =>
+			network.dry_run_call(&cli.chain, hex(&origin)?, hex(&call)?).await,
+		_ =>
+			return Err(
+				"either --origin and --xcm, or --origin-caller and --call are required".into()
+			),
+	}
+	.map_err(|error| error.to_string())?;
+
+	print!("{report}");
+	Ok(report.is_success())
+}
+
+fn hex(input: &str) -> Result<Vec<u8>, String> {
+	sp_core::bytes::from_hex(input).map_err(|error| format!("invalid hex {input}: {error}"))
+}
+
+fn decode_hex<T: Decode>(input: &str) -> Result<T, String> {
+	T::decode(&mut &hex(input)?[..]).map_err(|error| format!("failed to decode {input}: {error}"))
+}
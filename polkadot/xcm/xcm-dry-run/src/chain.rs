@@ -0,0 +1,121 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Access to the runtime APIs of the chains of a route.
+
+use crate::Error;
+use async_trait::async_trait;
+use jsonrpsee::{
+	core::client::ClientT,
+	rpc_params,
+	ws_client::{WsClient, WsClientBuilder},
+};
+use sp_core::Bytes;
+use xcm::latest::InteriorLocation;
+
+/// Calls into the runtime of a chain.
+#[async_trait]
+pub trait RuntimeApiCaller: Send + Sync {
+	/// Calls the runtime API function `method`, e.g. `DryRunApi_dry_run_xcm`, with the
+	/// SCALE-encoded `params` at the best block, returning the SCALE-encoded result.
+	async fn call(&self, method: &str, params: Vec<u8>) -> Result<Vec<u8>, Error>;
+}
+
+/// Calls into the runtime of a node through its `state_call` RPC.
+pub struct RpcCaller {
+	client: WsClient,
+}
+
+impl RpcCaller {
+	/// Connects to the node at `url`.
+	pub async fn connect(url: &str) -> Result<Self, Error> {
+		Ok(Self { client: WsClientBuilder::default().build(url).await? })
+	}
+}
+
+#[async_trait]
+impl RuntimeApiCaller for RpcCaller {
+	async fn call(&self, method: &str, params: Vec<u8>) -> Result<Vec<u8>, Error> {
+		let result: Bytes =
+			self.client.request("state_call", rpc_params![method, Bytes(params)]).await?;
+		Ok(result.0)
+	}
+}
+
+/// Calls into a runtime running in the same process, e.g. a chain of an `xcm-emulator` network.
+///
+/// The closure is given the name and the encoded parameters of the runtime API function, and is
+/// expected to dispatch them to the runtime, e.g. through the `api::dispatch` function generated by
+/// `impl_runtime_apis!`:
+///
+/// ```ignore
+/// let caller = InProcessCaller::new(|method, params| {
+/// 	AssetHubWestend::execute_with(|| {
+/// 		// Discard the changes made by dry-running.
+/// 		frame_support::storage::with_transaction(|| {
+/// 			TransactionOutcome::Rollback(Ok(asset_hub_westend_runtime::api::dispatch(
+/// 				method, params,
+/// 			)))
+/// 		})
+/// 		.unwrap_or_default()
+/// 	})
+/// });
+/// ```
+pub struct InProcessCaller<F> {
+	dispatch: F,
+}
+
+impl<F> InProcessCaller<F>
+where
+	F: Fn(&str, &[u8]) -> Option<Vec<u8>> + Send + Sync,
+{
+	/// Creates a caller dispatching runtime API calls with `dispatch`.
+	pub fn new(dispatch: F) -> Self {
+		Self { dispatch }
+	}
+}
+
+#[async_trait]
+impl<F> RuntimeApiCaller for InProcessCaller<F>
+where
+	F: Fn(&str, &[u8]) -> Option<Vec<u8>> + Send + Sync,
+{
+	async fn call(&self, method: &str, params: Vec<u8>) -> Result<Vec<u8>, Error> {
+		(self.dispatch)(method, &params).ok_or_else(|| Error::UnknownMethod(method.into()))
+	}
+}
+
+/// A chain a route may go through.
+pub struct Chain {
+	/// The name of the chain, used in reports.
+	pub name: String,
+	/// The universal location of the chain, e.g. `[GlobalConsensus(Westend), Parachain(1000)]`.
+	pub location: InteriorLocation,
+	/// Access to the runtime of the chain.
+	pub api: Box<dyn RuntimeApiCaller>,
+}
+
+impl Chain {
+	/// Creates a new chain.
+	pub fn new(
+		name: impl Into<String>,
+		location: impl Into<InteriorLocation>,
+		api: impl RuntimeApiCaller + 'static,
+	) -> Self {
+		Self { name: name.into(), location: location.into(), api: Box::new(api) }
+	}
+}
@@ -0,0 +1,138 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding of the results of the dry-run runtime APIs.
+//!
+//! The events emitted while dry-running are of the runtime specific event type, which is decoded
+//! dynamically using the metadata of the runtime.
+
+use crate::{Error, Event};
+use codec::{Compact, Decode};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use frame_support::dispatch::DispatchResultWithPostInfo;
+use scale_info::PortableRegistry;
+use scale_value::{Composite, Value, ValueDef};
+use xcm::{latest::Outcome, VersionedLocation, VersionedXcm};
+
+/// The version of the metadata the event type is looked up in.
+const METADATA_VERSION: u32 = 15;
+
+/// The types of a runtime needed to decode dry-run effects.
+pub(crate) struct RuntimeTypes {
+	registry: PortableRegistry,
+	event_ty: u32,
+}
+
+impl RuntimeTypes {
+	/// Decodes the types from the result of `Metadata_metadata_at_version`.
+	pub(crate) fn from_metadata_at_version(encoded: &[u8]) -> Result<Self, Error> {
+		let metadata = Option::<Vec<u8>>::decode(&mut &encoded[..])?.ok_or_else(|| {
+			Error::Metadata(format!("metadata version {METADATA_VERSION} is not supported"))
+		})?;
+		match RuntimeMetadataPrefixed::decode(&mut &metadata[..])?.1 {
+			RuntimeMetadata::V15(metadata) => Ok(Self {
+				event_ty: metadata.outer_enums.event_enum_ty.id,
+				registry: metadata.types,
+			}),
+			_ => Err(Error::Metadata(format!("expected metadata version {METADATA_VERSION}"))),
+		}
+	}
+
+	/// The encoded parameters of `Metadata_metadata_at_version`.
+	pub(crate) fn metadata_at_version_params() -> Vec<u8> {
+		codec::Encode::encode(&METADATA_VERSION)
+	}
+
+	fn decode_events(&self, input: &mut &[u8]) -> Result<Vec<Event>, Error> {
+		let Compact(len) = Compact::<u32>::decode(input)?;
+		(0..len)
+			.map(|_| {
+				scale_value::scale::decode_as_type(input, self.event_ty, &self.registry)
+					.map(Event::from_value)
+					.map_err(|error| Error::Metadata(format!("failed to decode event: {error}")))
+			})
+			.collect()
+	}
+}
+
+/// The result of executing a call or program, as reported by the dry-run runtime APIs.
+pub(crate) enum ExecutionResult {
+	Call(DispatchResultWithPostInfo),
+	Xcm(Outcome),
+}
+
+/// The decoded `CallDryRunEffects` or `XcmDryRunEffects`.
+pub(crate) struct Effects {
+	pub result: ExecutionResult,
+	pub events: Vec<Event>,
+	pub local_xcm: Option<VersionedXcm<()>>,
+	pub forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
+}
+
+/// Decodes the result of `DryRunApi_dry_run_call`.
+pub(crate) fn call_effects(
+	types: &RuntimeTypes,
+	mut input: &[u8],
+) -> Result<Result<Effects, xcm_runtime_apis::dry_run::Error>, Error> {
+	if u8::decode(&mut input)? != 0 {
+		return Ok(Err(Decode::decode(&mut input)?))
+	}
+	let result = ExecutionResult::Call(Decode::decode(&mut input)?);
+	let events = types.decode_events(&mut input)?;
+	let local_xcm = Decode::decode(&mut input)?;
+	let forwarded_xcms = Decode::decode(&mut input)?;
+	Ok(Ok(Effects { result, events, local_xcm, forwarded_xcms }))
+}
+
+/// Decodes the result of `DryRunApi_dry_run_xcm`.
+pub(crate) fn xcm_effects(
+	types: &RuntimeTypes,
+	mut input: &[u8],
+) -> Result<Result<Effects, xcm_runtime_apis::dry_run::Error>, Error> {
+	if u8::decode(&mut input)? != 0 {
+		return Ok(Err(Decode::decode(&mut input)?))
+	}
+	let result = ExecutionResult::Xcm(Decode::decode(&mut input)?);
+	let events = types.decode_events(&mut input)?;
+	let forwarded_xcms = Decode::decode(&mut input)?;
+	Ok(Ok(Effects { result, events, local_xcm: None, forwarded_xcms }))
+}
+
+impl Event {
+	/// Splits a value of the outer event enum of a runtime into the pallet and the event.
+	fn from_value(value: Value<u32>) -> Self {
+		let ValueDef::Variant(outer) = value.value else {
+			return Self {
+				pallet: String::new(),
+				name: String::new(),
+				fields: Composite::Unnamed(vec![value]),
+			}
+		};
+		match outer.values {
+			Composite::Unnamed(mut values) if values.len() == 1 => match values.remove(0) {
+				Value { value: ValueDef::Variant(inner), .. } =>
+					Self { pallet: outer.name, name: inner.name, fields: inner.values },
+				value => Self {
+					pallet: outer.name,
+					name: String::new(),
+					fields: Composite::Unnamed(vec![value]),
+				},
+			},
+			fields => Self { pallet: outer.name, name: String::new(), fields },
+		}
+	}
+}
@@ -0,0 +1,353 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dry-running XCM routes across multiple chains.
+//!
+//! Dry-running a cross-chain transfer means dry-running the extrinsic on the origin chain with
+//! `DryRunApi::dry_run_call`, then each of the forwarded programs on its destination with
+//! `DryRunApi::dry_run_xcm`, and so on until no more programs are forwarded. A [`Network`] does
+//! this for a set of [`Chain`]s, following the forwarded programs hop by hop, and aggregates the
+//! fees, balance changes and errors of the whole route into a [`RouteReport`].
+//!
+//! Chains are accessed through [`RuntimeApiCaller`]s, either over RPC with [`RpcCaller`], or in the
+//! same process with [`InProcessCaller`], e.g. for the chains of an `xcm-emulator` network.
+//!
+//! Fees are estimated with the `XcmPaymentApi`: the execution fee of a program is its weight priced
+//! in the asset it buys execution with, delivery fees are priced in the same asset, or in the first
+//! acceptable payment asset of the chain if the program does not pay for its execution.
+//!
+//! Programs exported over bridges are not followed, as they are not forwarded by the XCM router of
+//! the bridge hub.
+
+mod chain;
+mod decode;
+mod report;
+
+pub use chain::{Chain, InProcessCaller, RpcCaller, RuntimeApiCaller};
+pub use report::{BalanceChange, Event, Fee, FeeTotal, Hop, HopOutcome, RouteError, RouteReport};
+
+use codec::{Decode, Encode};
+use decode::{Effects, ExecutionResult, RuntimeTypes};
+use std::collections::VecDeque;
+use xcm::{
+	latest::{AssetId, InteriorLocation, Location, Xcm, VERSION as XCM_VERSION},
+	VersionedAssetId, VersionedAssets, VersionedLocation, VersionedXcm,
+};
+
+/// The default maximum number of hops of a route.
+pub const DEFAULT_MAX_HOPS: usize = 16;
+
+/// Errors preventing a route from being dry-run.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Calling a node failed.
+	#[error(transparent)]
+	Rpc(#[from] jsonrpsee::core::ClientError),
+	/// A result of a runtime API could not be decoded.
+	#[error("failed to decode runtime API result: {0}")]
+	Codec(#[from] codec::Error),
+	/// The runtime does not provide a runtime API function.
+	#[error("runtime API function {0} is not available")]
+	UnknownMethod(String),
+	/// The metadata of a runtime could not be used.
+	#[error("unusable metadata: {0}")]
+	Metadata(String),
+	/// No chain of the given name is part of the network.
+	#[error("unknown chain {0}")]
+	UnknownChain(String),
+	/// The dry-run runtime API returned an error.
+	#[error("dry-running on {chain} failed: {error:?}")]
+	DryRun {
+		/// The name of the chain.
+		chain: String,
+		/// The error.
+		error: xcm_runtime_apis::dry_run::Error,
+	},
+	/// Converting a versioned XCM data structure to the latest version failed.
+	#[error("failed to convert {0} to the latest XCM version")]
+	VersionedConversion(&'static str),
+}
+
+/// A set of chains routes are dry-run on.
+pub struct Network {
+	chains: Vec<(Chain, Option<RuntimeTypes>)>,
+	max_hops: usize,
+}
+
+impl Network {
+	/// Creates a network of `chains`.
+	pub fn new(chains: impl IntoIterator<Item = Chain>) -> Self {
+		Self {
+			chains: chains.into_iter().map(|chain| (chain, None)).collect(),
+			max_hops: DEFAULT_MAX_HOPS,
+		}
+	}
+
+	/// Limit routes to `max_hops` hops.
+	pub fn with_max_hops(mut self, max_hops: usize) -> Self {
+		self.max_hops = max_hops;
+		self
+	}
+
+	/// Dry-runs the encoded `call` dispatched by the encoded origin caller `origin` on `chain`, and
+	/// the programs it sends to other chains.
+	pub async fn dry_run_call(
+		&mut self,
+		chain: &str,
+		origin: Vec<u8>,
+		call: Vec<u8>,
+	) -> Result<RouteReport, Error> {
+		let index = self.chain_index(chain)?;
+		let params = [origin, call, XCM_VERSION.encode()].concat();
+		let encoded = self.call(index, "DryRunApi_dry_run_call", params).await?;
+		let effects = decode::call_effects(self.types(index).await?, &encoded)?
+			.map_err(|error| Error::DryRun { chain: chain.into(), error })?;
+
+		let mut report = RouteReport::default();
+		let message = effects.local_xcm.clone().map(latest_xcm).transpose()?;
+		self.record_hop(&mut report, index, None, None, message, effects).await?;
+		self.follow(report).await
+	}
+
+	/// Dry-runs `xcm` sent by `origin` on `chain`, and the programs it sends to other chains.
+	pub async fn dry_run_xcm(
+		&mut self,
+		chain: &str,
+		origin: Location,
+		xcm: Xcm<()>,
+	) -> Result<RouteReport, Error> {
+		let index = self.chain_index(chain)?;
+		let mut report = RouteReport::default();
+		self.dry_run_hop(&mut report, index, None, origin, xcm).await?;
+		self.follow(report).await
+	}
+
+	/// Dry-runs the programs forwarded by the hops of `report`, until no more are forwarded.
+	async fn follow(&mut self, mut report: RouteReport) -> Result<RouteReport, Error> {
+		let mut queue = VecDeque::from([0]);
+		while let Some(hop) = queue.pop_front() {
+			let sender = self.chain_index(&report.hops[hop].chain)?;
+			let sender_location = self.chains[sender].0.location.clone();
+			for (destination, message, _) in report.hops[hop].forwarded.clone() {
+				let Some(index) =
+					universal_destination(&sender_location, &destination).and_then(|location| {
+						self.chains.iter().position(|(chain, _)| chain.location == location)
+					})
+				else {
+					report.errors.push(RouteError::UnknownDestination { hop, destination });
+					continue
+				};
+				if report.hops.len() >= self.max_hops {
+					report.errors.push(RouteError::TooManyHops { max_hops: self.max_hops });
+					report.aggregate();
+					return Ok(report)
+				}
+				let origin = sender_location.clone().relative_to(&self.chains[index].0.location);
+				self.dry_run_hop(&mut report, index, Some(hop), origin, message).await?;
+				queue.push_back(report.hops.len() - 1);
+			}
+		}
+		report.aggregate();
+		Ok(report)
+	}
+
+	async fn dry_run_hop(
+		&mut self,
+		report: &mut RouteReport,
+		index: usize,
+		parent: Option<usize>,
+		origin: Location,
+		xcm: Xcm<()>,
+	) -> Result<(), Error> {
+		let params =
+			(VersionedLocation::from(origin.clone()), VersionedXcm::from(xcm.clone())).encode();
+		let encoded = self.call(index, "DryRunApi_dry_run_xcm", params).await?;
+		let effects = decode::xcm_effects(self.types(index).await?, &encoded)?
+			.map_err(|error| Error::DryRun { chain: self.chains[index].0.name.clone(), error })?;
+		self.record_hop(report, index, parent, Some(origin), Some(xcm), effects).await
+	}
+
+	async fn record_hop(
+		&mut self,
+		report: &mut RouteReport,
+		index: usize,
+		parent: Option<usize>,
+		origin: Option<Location>,
+		message: Option<Xcm<()>>,
+		effects: Effects,
+	) -> Result<(), Error> {
+		let hop = report.hops.len();
+		let chain = self.chains[index].0.name.clone();
+		let outcome = match effects.result {
+			ExecutionResult::Call(Ok(_)) => HopOutcome::Dispatched,
+			ExecutionResult::Call(Err(error)) => HopOutcome::DispatchFailed(error.error),
+			ExecutionResult::Xcm(outcome) => HopOutcome::Executed(outcome),
+		};
+		if !outcome.is_success() {
+			report.errors.push(RouteError::HopFailed {
+				hop,
+				chain: chain.clone(),
+				outcome: outcome.clone(),
+			});
+		}
+
+		let mut forwarded = Vec::new();
+		for (destination, messages) in effects.forwarded_xcms {
+			let destination: Location =
+				destination.try_into().map_err(|()| Error::VersionedConversion("destination"))?;
+			for message in messages {
+				forwarded.push((destination.clone(), latest_xcm(message)?));
+			}
+		}
+
+		let fees = self.query_fees(index, message.as_ref(), &forwarded).await;
+		let (weight, execution_fee, delivery_fees) = fees.unwrap_or_else(|error| {
+			report.errors.push(RouteError::FeeQueryFailed { hop, error: error.to_string() });
+			(None, None, vec![None; forwarded.len()])
+		});
+
+		report.hops.push(Hop {
+			chain,
+			parent,
+			origin,
+			message,
+			outcome,
+			events: effects.events,
+			weight,
+			execution_fee,
+			forwarded: forwarded
+				.into_iter()
+				.zip(delivery_fees)
+				.map(|((destination, message), fee)| (destination, message, fee))
+				.collect(),
+		});
+		Ok(())
+	}
+
+	/// Queries the weight and execution fee of `message`, and the delivery fees of `forwarded`.
+	async fn query_fees(
+		&self,
+		index: usize,
+		message: Option<&Xcm<()>>,
+		forwarded: &[(Location, Xcm<()>)],
+	) -> Result<(Option<xcm::latest::Weight>, Option<Fee>, Vec<Option<Fee>>), Error> {
+		let fee_asset = message.and_then(report::fee_asset);
+		let mut weight = None;
+		let mut execution_fee = None;
+		if let Some(message) = message {
+			let params = VersionedXcm::from(message.clone()).encode();
+			let encoded = self.call(index, "XcmPaymentApi_query_xcm_weight", params).await?;
+			weight = payment_result(&encoded)?;
+			if let (Some(weight), Some(asset)) = (weight, &fee_asset) {
+				let params = (weight, VersionedAssetId::from(asset.clone())).encode();
+				let encoded =
+					self.call(index, "XcmPaymentApi_query_weight_to_asset_fee", params).await?;
+				execution_fee = payment_result::<u128>(&encoded)?
+					.map(|amount| Fee { asset: asset.clone(), amount });
+			}
+		}
+
+		let mut delivery_fees = Vec::new();
+		if !forwarded.is_empty() {
+			let delivery_asset = match fee_asset {
+				Some(asset) => Some(asset),
+				None => self.default_fee_asset(index).await?,
+			};
+			for (destination, message) in forwarded {
+				let Some(asset) = &delivery_asset else {
+					delivery_fees.push(None);
+					continue
+				};
+				let params = (
+					VersionedLocation::from(destination.clone()),
+					VersionedXcm::from(message.clone()),
+					VersionedAssetId::from(asset.clone()),
+				)
+					.encode();
+				let encoded = self.call(index, "XcmPaymentApi_query_delivery_fees", params).await?;
+				let fees = payment_result::<VersionedAssets>(&encoded)?
+					.map(|fees| fees.try_into().map_err(|()| Error::VersionedConversion("fees")))
+					.transpose()?;
+				delivery_fees.push(fees.as_ref().and_then(report::first_fee));
+			}
+		}
+
+		Ok((weight, execution_fee, delivery_fees))
+	}
+
+	/// The first acceptable payment asset of the chain.
+	async fn default_fee_asset(&self, index: usize) -> Result<Option<AssetId>, Error> {
+		let params = XCM_VERSION.encode();
+		let encoded = self
+			.call(index, "XcmPaymentApi_query_acceptable_payment_assets", params)
+			.await?;
+		payment_result::<Vec<VersionedAssetId>>(&encoded)?
+			.and_then(|assets| assets.into_iter().next())
+			.map(|asset| asset.try_into().map_err(|()| Error::VersionedConversion("fee asset")))
+			.transpose()
+	}
+
+	async fn types(&mut self, index: usize) -> Result<&RuntimeTypes, Error> {
+		if self.chains[index].1.is_none() {
+			let params = RuntimeTypes::metadata_at_version_params();
+			let encoded = self.call(index, "Metadata_metadata_at_version", params).await?;
+			self.chains[index].1 = Some(RuntimeTypes::from_metadata_at_version(&encoded)?);
+		}
+		Ok(self.chains[index].1.as_ref().expect("set above; qed"))
+	}
+
+	async fn call(&self, index: usize, method: &str, params: Vec<u8>) -> Result<Vec<u8>, Error> {
+		self.chains[index].0.api.call(method, params).await
+	}
+
+	fn chain_index(&self, name: &str) -> Result<usize, Error> {
+		self.chains
+			.iter()
+			.position(|(chain, _)| chain.name == name)
+			.ok_or_else(|| Error::UnknownChain(name.into()))
+	}
+}
+
+/// Decodes the result of an `XcmPaymentApi` function, `None` if the runtime returned an error.
+fn payment_result<T: Decode>(encoded: &[u8]) -> Result<Option<T>, Error> {
+	Ok(Result::<T, xcm_runtime_apis::fees::Error>::decode(&mut &encoded[..])?.ok())
+}
+
+fn latest_xcm(xcm: VersionedXcm<()>) -> Result<Xcm<()>, Error> {
+	xcm.try_into().map_err(|()| Error::VersionedConversion("program"))
+}
+
+/// Returns the universal location of `destination`, as seen by the chain at `sender`.
+fn universal_destination(
+	sender: &InteriorLocation,
+	destination: &Location,
+) -> Option<InteriorLocation> {
+	if destination.parent_count() as usize > sender.len() {
+		return None
+	}
+	let mut location = sender.clone();
+	for _ in 0..destination.parent_count() {
+		location.take_last();
+	}
+	for junction in destination.interior() {
+		location.push(*junction).ok()?;
+	}
+	Some(location)
+}
+
+#[cfg(test)]
+mod tests;
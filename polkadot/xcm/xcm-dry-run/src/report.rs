@@ -0,0 +1,389 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The report of dry-running a route.
+
+use core::fmt;
+use frame_support::sp_runtime::DispatchError;
+use scale_value::{Composite, Primitive, Value, ValueDef};
+use std::collections::BTreeMap;
+use xcm::latest::{AssetId, Assets, Location, Outcome, Weight, Xcm};
+
+/// An event emitted while dry-running, decoded using the metadata of the runtime.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Event {
+	/// The name of the pallet which emitted the event.
+	pub pallet: String,
+	/// The name of the event.
+	pub name: String,
+	/// The fields of the event.
+	pub fields: Composite<u32>,
+}
+
+impl fmt::Display for Event {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}::{} {}", self.pallet, self.name, self.fields)
+	}
+}
+
+/// The outcome of a hop.
+#[derive(Clone, Debug, PartialEq)]
+pub enum HopOutcome {
+	/// The call was dispatched successfully.
+	Dispatched,
+	/// Dispatching the call failed.
+	DispatchFailed(DispatchError),
+	/// The program was executed.
+	Executed(Outcome),
+}
+
+impl HopOutcome {
+	/// Returns `true` if the call or program completed successfully.
+	pub fn is_success(&self) -> bool {
+		matches!(self, Self::Dispatched | Self::Executed(Outcome::Complete { .. }))
+	}
+}
+
+/// A fee paid on a hop.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fee {
+	/// The asset the fee is paid in, as seen by the chain of the hop.
+	pub asset: AssetId,
+	/// The amount paid.
+	pub amount: u128,
+}
+
+/// The execution of a call or program on a chain of the route.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Hop {
+	/// The name of the chain.
+	pub chain: String,
+	/// The hop which sent the executed program, `None` for the first hop.
+	pub parent: Option<usize>,
+	/// The origin of the executed program, as seen by the chain. `None` for a dispatched call.
+	pub origin: Option<Location>,
+	/// The executed program, or the program a dispatched call executed locally, if any.
+	pub message: Option<Xcm<()>>,
+	/// The outcome of the hop.
+	pub outcome: HopOutcome,
+	/// The events emitted by the hop.
+	pub events: Vec<Event>,
+	/// The weight of executing the program.
+	pub weight: Option<Weight>,
+	/// The fee for executing the program, in the asset the program pays fees with.
+	pub execution_fee: Option<Fee>,
+	/// The programs sent to other chains, with the fees for delivering them.
+	pub forwarded: Vec<(Location, Xcm<()>, Option<Fee>)>,
+}
+
+/// A net change of a balance on a chain of the route.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BalanceChange {
+	/// The name of the chain.
+	pub chain: String,
+	/// The pallet managing the balance.
+	pub pallet: String,
+	/// The asset, for pallets managing multiple assets.
+	pub asset: Option<String>,
+	/// The account holding the balance.
+	pub account: String,
+	/// The net change.
+	pub delta: i128,
+}
+
+/// The total fees paid on a chain of the route in one asset.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FeeTotal {
+	/// The name of the chain.
+	pub chain: String,
+	/// The asset, as seen by the chain.
+	pub asset: AssetId,
+	/// The total execution fees.
+	pub execution: u128,
+	/// The total delivery fees.
+	pub delivery: u128,
+}
+
+/// Something that went wrong along a route.
+#[derive(Clone, Debug, PartialEq, thiserror::Error)]
+pub enum RouteError {
+	/// A hop did not complete successfully.
+	#[error("hop {hop} on {chain} failed: {outcome:?}")]
+	HopFailed {
+		/// The index of the hop.
+		hop: usize,
+		/// The name of the chain.
+		chain: String,
+		/// The outcome of the hop.
+		outcome: HopOutcome,
+	},
+	/// A hop sent a program to a chain which is not part of the route.
+	#[error("hop {hop} sent a program to unknown destination {destination:?}")]
+	UnknownDestination {
+		/// The index of the hop.
+		hop: usize,
+		/// The destination, as seen by the chain of the hop.
+		destination: Location,
+	},
+	/// Querying the fees of a hop failed.
+	#[error("querying the fees of hop {hop} failed: {error}")]
+	FeeQueryFailed {
+		/// The index of the hop.
+		hop: usize,
+		/// The error.
+		error: String,
+	},
+	/// The route has more hops than allowed, the remaining programs were not dry-run.
+	#[error("the route exceeds {max_hops} hops")]
+	TooManyHops {
+		/// The maximum number of hops.
+		max_hops: usize,
+	},
+	/// An event of a hop reported an amount too large to be accounted for in balance changes.
+	#[error("hop {hop} reported amount {amount} in {pallet}::{event}, which is out of range")]
+	AmountOutOfRange {
+		/// The index of the hop.
+		hop: usize,
+		/// The pallet of the event.
+		pallet: String,
+		/// The name of the event.
+		event: String,
+		/// The amount.
+		amount: u128,
+	},
+}
+
+/// The report of dry-running a route.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RouteReport {
+	/// The hops, in the order they were dry-run.
+	pub hops: Vec<Hop>,
+	/// The total fees per chain and asset.
+	pub fees: Vec<FeeTotal>,
+	/// The net balance changes, as reported by the events of `pallet-balances` and `pallet-assets`
+	/// like pallets.
+	pub balance_changes: Vec<BalanceChange>,
+	/// What went wrong along the route.
+	pub errors: Vec<RouteError>,
+}
+
+impl RouteReport {
+	/// Returns `true` if all hops completed successfully.
+	pub fn is_success(&self) -> bool {
+		self.errors.is_empty()
+	}
+
+	/// Aggregates the fees and balance changes of all hops.
+	pub(crate) fn aggregate(&mut self) {
+		let mut fees = BTreeMap::<(String, AssetId), (u128, u128)>::new();
+		let mut changes = BTreeMap::<(String, String, Option<String>, String), i128>::new();
+		let mut errors = Vec::new();
+		for (index, hop) in self.hops.iter().enumerate() {
+			if let Some(fee) = &hop.execution_fee {
+				let total = fees.entry((hop.chain.clone(), fee.asset.clone())).or_default();
+				total.0 = total.0.saturating_add(fee.amount);
+			}
+			for fee in hop.forwarded.iter().filter_map(|(_, _, fee)| fee.as_ref()) {
+				let total = fees.entry((hop.chain.clone(), fee.asset.clone())).or_default();
+				total.1 = total.1.saturating_add(fee.amount);
+			}
+			for event in &hop.events {
+				let event_changes = balance_changes(event).unwrap_or_else(|amount| {
+					errors.push(RouteError::AmountOutOfRange {
+						hop: index,
+						pallet: event.pallet.clone(),
+						event: event.name.clone(),
+						amount,
+					});
+					Vec::new()
+				});
+				for (account, asset, delta) in event_changes {
+					let key = (hop.chain.clone(), event.pallet.clone(), asset, account);
+					let total = changes.entry(key).or_default();
+					*total = total.saturating_add(delta);
+				}
+			}
+		}
+		self.errors.extend(errors);
+
+		self.fees = fees
+			.into_iter()
+			.map(|((chain, asset), (execution, delivery))| FeeTotal {
+				chain,
+				asset,
+				execution,
+				delivery,
+			})
+			.collect();
+		self.balance_changes = changes
+			.into_iter()
+			.filter(|(_, delta)| *delta != 0)
+			.map(|((chain, pallet, asset, account), delta)| BalanceChange {
+				chain,
+				pallet,
+				asset,
+				account,
+				delta,
+			})
+			.collect();
+	}
+}
+
+impl fmt::Display for RouteReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		for (index, hop) in self.hops.iter().enumerate() {
+			match hop.parent {
+				Some(parent) => writeln!(f, "#{index} {} (from #{parent})", hop.chain)?,
+				None => writeln!(f, "#{index} {}", hop.chain)?,
+			}
+			if let Some(origin) = &hop.origin {
+				writeln!(f, "  origin: {origin:?}")?;
+			}
+			writeln!(f, "  outcome: {:?}", hop.outcome)?;
+			if let Some(weight) = hop.weight {
+				writeln!(f, "  weight: {weight:?}")?;
+			}
+			if let Some(fee) = &hop.execution_fee {
+				writeln!(f, "  execution fee: {} of {:?}", fee.amount, fee.asset)?;
+			}
+			for (destination, _, fee) in &hop.forwarded {
+				match fee {
+					Some(fee) => writeln!(
+						f,
+						"  sent to {destination:?}, delivery fee: {} of {:?}",
+						fee.amount, fee.asset
+					)?,
+					None => writeln!(f, "  sent to {destination:?}")?,
+				}
+			}
+			for event in &hop.events {
+				writeln!(f, "  event: {event}")?;
+			}
+		}
+		if !self.fees.is_empty() {
+			writeln!(f, "fees:")?;
+			for fee in &self.fees {
+				writeln!(
+					f,
+					"  {}: {:?}: execution {}, delivery {}",
+					fee.chain, fee.asset, fee.execution, fee.delivery
+				)?;
+			}
+		}
+		if !self.balance_changes.is_empty() {
+			writeln!(f, "balance changes:")?;
+			for change in &self.balance_changes {
+				match &change.asset {
+					Some(asset) => writeln!(
+						f,
+						"  {} {}[{asset}] {}: {:+}",
+						change.chain, change.pallet, change.account, change.delta
+					)?,
+					None => writeln!(
+						f,
+						"  {} {} {}: {:+}",
+						change.chain, change.pallet, change.account, change.delta
+					)?,
+				}
+			}
+		}
+		for error in &self.errors {
+			writeln!(f, "error: {error}")?;
+		}
+		Ok(())
+	}
+}
+
+/// Returns the balance changes `(account, asset, delta)` reported by `event`, or the amount of the
+/// event if it doesn't fit a delta.
+///
+/// Recognizes the events of `pallet-balances` and `pallet-assets`, as well as of pallets following
+/// their conventions.
+fn balance_changes(event: &Event) -> Result<Vec<(String, Option<String>, i128)>, u128> {
+	let field = |name: &str| match &event.fields {
+		Composite::Named(fields) =>
+			fields.iter().find_map(|(field, value)| (field == name).then_some(value)),
+		Composite::Unnamed(_) => None,
+	};
+	let amount = |name: &str| {
+		field(name)
+			.and_then(Value::as_u128)
+			.map(|amount| i128::try_from(amount).map_err(|_| amount))
+	};
+	let account = |name: &str| field(name).map(render_account);
+	let asset = field("asset_id").map(ToString::to_string);
+
+	let change = |who: &str, value: &str, negate: bool| {
+		let account = account(who)?;
+		Some(amount(value)?.map(|a| vec![(account, asset.clone(), if negate { -a } else { a })]))
+	};
+	match event.name.as_str() {
+		"Minted" | "Deposit" | "Deposited" => change("who", "amount", false),
+		"Burned" if asset.is_some() => change("owner", "balance", true),
+		"Burned" | "Withdraw" | "Withdrawn" => change("who", "amount", true),
+		"Issued" => change("owner", "amount", false),
+		"Transfer" | "Transferred" =>
+			(|| {
+				let (from, to) = (account("from")?, account("to")?);
+				Some(amount("amount")?.map(|amount| {
+					vec![(from, asset.clone(), -amount), (to, asset.clone(), amount)]
+				}))
+			})(),
+		_ => None,
+	}
+	.unwrap_or(Ok(Vec::new()))
+}
+
+/// Renders 32 byte account ids as hex, and other accounts as values.
+fn render_account(value: &Value<u32>) -> String {
+	fn bytes(value: &Value<u32>) -> Option<Vec<u8>> {
+		let ValueDef::Composite(composite) = &value.value else { return None };
+		let values: Vec<_> = composite.values().collect();
+		match values.as_slice() {
+			[inner] if matches!(inner.value, ValueDef::Composite(_)) => bytes(inner),
+			values => values
+				.iter()
+				.map(|value| match value.value {
+					ValueDef::Primitive(Primitive::U128(byte)) => u8::try_from(byte).ok(),
+					_ => None,
+				})
+				.collect(),
+		}
+	}
+	match bytes(value) {
+		Some(bytes) if bytes.len() == 32 =>
+			format!("0x{}", sp_core::hexdisplay::HexDisplay::from(&bytes)),
+		_ => value.to_string(),
+	}
+}
+
+/// The fee asset of `message`, if it pays for its execution.
+pub(crate) fn fee_asset(message: &Xcm<()>) -> Option<AssetId> {
+	message.0.iter().find_map(|instruction| match instruction {
+		xcm::latest::Instruction::BuyExecution { fees, .. } => Some(fees.id.clone()),
+		xcm::latest::Instruction::PayFees { asset } => Some(asset.id.clone()),
+		_ => None,
+	})
+}
+
+/// Convenience for the first asset of `assets`.
+pub(crate) fn first_fee(assets: &Assets) -> Option<Fee> {
+	assets.inner().first().and_then(|asset| match asset.fun {
+		xcm::latest::Fungibility::Fungible(amount) => Some(Fee { asset: asset.id.clone(), amount }),
+		_ => None,
+	})
+}
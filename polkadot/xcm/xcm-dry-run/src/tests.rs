@@ -0,0 +1,306 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::*;
+use frame_metadata::{
+	v15::{CustomMetadata, ExtrinsicMetadata, OuterEnums, RuntimeMetadataV15},
+	RuntimeMetadataPrefixed,
+};
+use scale_info::{meta_type, TypeInfo};
+use sp_core::crypto::AccountId32 as AccountId;
+use xcm::latest::prelude::*;
+
+#[derive(Encode, TypeInfo)]
+enum RuntimeEvent {
+	Balances(BalancesEvent),
+}
+
+#[derive(Encode, TypeInfo)]
+enum BalancesEvent {
+	Minted { who: AccountId, amount: u128 },
+	Burned { who: AccountId, amount: u128 },
+}
+
+const ALICE: [u8; 32] = [1; 32];
+
+fn metadata() -> Vec<u8> {
+	let metadata = RuntimeMetadataV15::new(
+		vec![],
+		ExtrinsicMetadata {
+			version: 4,
+			address_ty: meta_type::<()>(),
+			call_ty: meta_type::<()>(),
+			signature_ty: meta_type::<()>(),
+			extra_ty: meta_type::<()>(),
+			signed_extensions: vec![],
+		},
+		meta_type::<()>(),
+		vec![],
+		OuterEnums {
+			call_enum_ty: meta_type::<()>(),
+			event_enum_ty: meta_type::<RuntimeEvent>(),
+			error_enum_ty: meta_type::<()>(),
+		},
+		CustomMetadata { map: Default::default() },
+	);
+	Some(RuntimeMetadataPrefixed::from(metadata).encode()).encode()
+}
+
+/// Dry-runs programs by handing them to `execute`, which returns the events and forwarded
+/// programs. Execution costs 10 per instruction, and delivery 5, in any asset.
+fn fake_chain(
+	execute: impl Fn(Location, Xcm<()>) -> (Outcome, Vec<RuntimeEvent>, Vec<(Location, Xcm<()>)>)
+		+ Send
+		+ Sync,
+) -> impl RuntimeApiCaller {
+	InProcessCaller::new(move |method, mut params| {
+		let fees_ok = |value: &dyn Fn() -> Vec<u8>| [vec![0], value()].concat();
+		Some(match method {
+			"Metadata_metadata_at_version" => metadata(),
+			"DryRunApi_dry_run_xcm" => {
+				let (origin, xcm) =
+					<(VersionedLocation, VersionedXcm<()>)>::decode(&mut params).unwrap();
+				let (outcome, events, forwarded) =
+					execute(origin.try_into().unwrap(), xcm.try_into().unwrap());
+				let forwarded: Vec<_> = forwarded
+					.into_iter()
+					.map(|(dest, xcm)| {
+						(VersionedLocation::from(dest), vec![VersionedXcm::from(xcm)])
+					})
+					.collect();
+				[vec![0], outcome.encode(), events.encode(), forwarded.encode()].concat()
+			},
+			"XcmPaymentApi_query_xcm_weight" => {
+				let xcm: Xcm<()> =
+					VersionedXcm::<()>::decode(&mut params).unwrap().try_into().unwrap();
+				fees_ok(&|| Weight::from_parts(10 * xcm.len() as u64, 0).encode())
+			},
+			"XcmPaymentApi_query_weight_to_asset_fee" => {
+				let weight = Weight::decode(&mut params).unwrap();
+				fees_ok(&|| (weight.ref_time() as u128).encode())
+			},
+			"XcmPaymentApi_query_acceptable_payment_assets" =>
+				fees_ok(&|| vec![VersionedAssetId::from(AssetId(Here.into()))].encode()),
+			"XcmPaymentApi_query_delivery_fees" => {
+				let (_, _, asset) =
+					<(VersionedLocation, VersionedXcm<()>, VersionedAssetId)>::decode(&mut params)
+						.unwrap();
+				let asset: AssetId = asset.try_into().unwrap();
+				fees_ok(&|| VersionedAssets::from(Assets::from((asset.clone(), 5u128))).encode())
+			},
+			_ => return None,
+		})
+	})
+}
+
+fn complete(xcm: &Xcm<()>) -> Outcome {
+	Outcome::Complete { used: Weight::from_parts(10 * xcm.len() as u64, 0) }
+}
+
+#[test]
+fn universal_destinations_are_resolved() {
+	let asset_hub: InteriorLocation = [GlobalConsensus(Polkadot), Parachain(1000)].into();
+
+	assert_eq!(
+		universal_destination(&asset_hub, &(Parent, Parachain(2000)).into()),
+		Some([GlobalConsensus(Polkadot), Parachain(2000)].into())
+	);
+	assert_eq!(
+		universal_destination(&asset_hub, &Parent.into()),
+		Some([GlobalConsensus(Polkadot)].into())
+	);
+	assert_eq!(
+		universal_destination(
+			&asset_hub,
+			&Location::new(2, [GlobalConsensus(Kusama), Parachain(1000)])
+		),
+		Some([GlobalConsensus(Kusama), Parachain(1000)].into())
+	);
+	assert_eq!(universal_destination(&asset_hub, &Location::new(3, [])), None);
+}
+
+#[test]
+fn route_is_followed_and_aggregated() {
+	let transfer = Xcm::<()>::builder_unsafe()
+		.withdraw_asset((Here, 1_000u128))
+		.initiate_teleport(All, (Parent, Parachain(2000)), Xcm(vec![]))
+		.build();
+
+	let asset_hub = fake_chain(|_, xcm| {
+		let events = vec![RuntimeEvent::Balances(BalancesEvent::Burned {
+			who: ALICE.into(),
+			amount: 1_000,
+		})];
+		let forwarded = vec![
+			(
+				Location::new(1, [Parachain(2000)]),
+				Xcm::builder_unsafe()
+					.receive_teleported_asset((Parent, 1_000u128))
+					.buy_execution((Parent, 100u128), Unlimited)
+					.deposit_asset(All, ALICE)
+					.build(),
+			),
+			(Location::new(1, [Parachain(3000)]), Xcm::builder_unsafe().clear_origin().build()),
+		];
+		(complete(&xcm), events, forwarded)
+	});
+	let para = fake_chain(|origin, xcm| {
+		assert_eq!(origin, Location::new(1, [Parachain(1000)]));
+		let events =
+			vec![RuntimeEvent::Balances(BalancesEvent::Minted { who: ALICE.into(), amount: 970 })];
+		(complete(&xcm), events, vec![])
+	});
+
+	let mut network = Network::new([
+		Chain::new("asset-hub", [GlobalConsensus(Polkadot), Parachain(1000)], asset_hub),
+		Chain::new("para", [GlobalConsensus(Polkadot), Parachain(2000)], para),
+	]);
+	let report = futures::executor::block_on(network.dry_run_xcm(
+		"asset-hub",
+		AccountId32 { network: None, id: ALICE }.into(),
+		transfer,
+	))
+	.unwrap();
+
+	assert_eq!(report.hops.len(), 2);
+	assert_eq!(report.hops[0].chain, "asset-hub");
+	assert_eq!(report.hops[0].weight, Some(Weight::from_parts(20, 0)));
+	// The transfer doesn't pay for execution, delivery is paid in the native asset.
+	assert_eq!(report.hops[0].execution_fee, None);
+	assert_eq!(
+		report.hops[0]
+			.forwarded
+			.iter()
+			.map(|(_, _, fee)| fee.clone())
+			.collect::<Vec<_>>(),
+		vec![Some(Fee { asset: AssetId(Here.into()), amount: 5 }); 2]
+	);
+	assert_eq!(report.hops[1].chain, "para");
+	assert_eq!(report.hops[1].parent, Some(0));
+	assert_eq!(report.hops[1].origin, Some(Location::new(1, [Parachain(1000)])));
+	assert_eq!(
+		report.hops[1].execution_fee,
+		Some(Fee { asset: AssetId(Parent.into()), amount: 30 })
+	);
+	assert_eq!(report.hops[1].events[0].pallet, "Balances");
+	assert_eq!(report.hops[1].events[0].name, "Minted");
+
+	assert_eq!(
+		report.fees,
+		vec![
+			FeeTotal {
+				chain: "asset-hub".into(),
+				asset: AssetId(Here.into()),
+				execution: 0,
+				delivery: 10,
+			},
+			FeeTotal {
+				chain: "para".into(),
+				asset: AssetId(Parent.into()),
+				execution: 30,
+				delivery: 0
+			},
+		]
+	);
+	let alice = format!("0x{}", sp_core::hexdisplay::HexDisplay::from(&ALICE));
+	assert_eq!(
+		report.balance_changes,
+		vec![
+			BalanceChange {
+				chain: "asset-hub".into(),
+				pallet: "Balances".into(),
+				asset: None,
+				account: alice.clone(),
+				delta: -1_000,
+			},
+			BalanceChange {
+				chain: "para".into(),
+				pallet: "Balances".into(),
+				asset: None,
+				account: alice,
+				delta: 970,
+			},
+		]
+	);
+	assert_eq!(
+		report.errors,
+		vec![RouteError::UnknownDestination {
+			hop: 0,
+			destination: Location::new(1, [Parachain(3000)]),
+		}]
+	);
+	assert!(!report.is_success());
+}
+
+#[test]
+fn failed_hops_are_reported_and_routes_are_bounded() {
+	// A chain sending every program back to itself.
+	let looping = fake_chain(|_, xcm| {
+		let outcome = Outcome::Incomplete {
+			used: Weight::zero(),
+			error: InstructionError { index: 0, error: XcmError::Barrier },
+		};
+		(outcome, vec![], vec![(Here.into(), xcm)])
+	});
+	let mut network =
+		Network::new([Chain::new("looping", [Parachain(1)], looping)]).with_max_hops(3);
+	let report = futures::executor::block_on(network.dry_run_xcm(
+		"looping",
+		Here.into(),
+		Xcm::builder_unsafe().clear_origin().build(),
+	))
+	.unwrap();
+
+	assert_eq!(report.hops.len(), 3);
+	assert!(matches!(report.errors[0], RouteError::HopFailed { hop: 0, .. }));
+	assert_eq!(report.errors.last(), Some(&RouteError::TooManyHops { max_hops: 3 }));
+	assert!(matches!(
+		futures::executor::block_on(network.dry_run_xcm("unknown", Here.into(), Xcm::new())),
+		Err(Error::UnknownChain(_))
+	));
+}
+
+#[test]
+fn amounts_out_of_range_are_reported() {
+	let chain = fake_chain(|_, xcm| {
+		let events = vec![
+			RuntimeEvent::Balances(BalancesEvent::Minted { who: ALICE.into(), amount: u128::MAX }),
+			RuntimeEvent::Balances(BalancesEvent::Burned { who: ALICE.into(), amount: 10 }),
+		];
+		(complete(&xcm), events, vec![])
+	});
+	let mut network = Network::new([Chain::new("chain", [Parachain(1)], chain)]);
+	let report = futures::executor::block_on(network.dry_run_xcm(
+		"chain",
+		Here.into(),
+		Xcm::builder_unsafe().clear_origin().build(),
+	))
+	.unwrap();
+
+	assert_eq!(
+		report.errors,
+		vec![RouteError::AmountOutOfRange {
+			hop: 0,
+			pallet: "Balances".into(),
+			event: "Minted".into(),
+			amount: u128::MAX,
+		}]
+	);
+	// Other events are still accounted for.
+	assert_eq!(report.balance_changes.len(), 1);
+	assert_eq!(report.balance_changes[0].delta, -10);
+}
@@ -42,6 +42,8 @@ use traits::{
 
 pub use traits::RecordXcm;
 
+pub mod trace;
+
 mod assets;
 pub use assets::AssetsInHolding;
 mod config;
@@ -268,12 +270,15 @@ impl<Config: config::Config> ExecuteXcm<Config::RuntimeCall> for XcmExecutor<Con
 			Config::XcmRecorder::record(message.clone().into());
 		}
 
-		if let Err(e) = Config::Barrier::should_execute(
+		let barrier = trace::start_barrier(Config::Barrier::NAME);
+		let barrier_result = Config::Barrier::should_execute(
 			&origin,
 			message.inner_mut(),
 			xcm_weight,
 			&mut properties,
-		) {
+		);
+		trace::finish_barrier(barrier, &barrier_result);
+		if let Err(e) = barrier_result {
 			tracing::trace!(
 				target: "xcm::execute",
 				?origin,
@@ -752,6 +757,20 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		Ok(reanchored_assets)
 	}
 
+	/// Returns whether `location` is trusted as the reserve of `asset`.
+	fn is_reserve(asset: &Asset, location: &Location) -> bool {
+		let trusted = Config::IsReserve::contains(asset, location);
+		trace::note_filter(trace::Filter::IsReserve, asset, location, trusted);
+		trusted
+	}
+
+	/// Returns whether `location` is trusted to teleport `asset`.
+	fn is_teleporter(asset: &Asset, location: &Location) -> bool {
+		let trusted = Config::IsTeleporter::contains(asset, location);
+		trace::note_filter(trace::Filter::IsTeleporter, asset, location, trusted);
+		trusted
+	}
+
 	fn do_reserve_withdraw_assets(
 		assets: AssetsInHolding,
 		failed_bin: &mut AssetsInHolding,
@@ -761,10 +780,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		// Must ensure that we recognise the assets as being managed by the destination.
 		#[cfg(not(any(test, feature = "runtime-benchmarks")))]
 		for asset in assets.assets_iter() {
-			ensure!(
-				Config::IsReserve::contains(&asset, &reserve),
-				XcmError::UntrustedReserveLocation
-			);
+			ensure!(Self::is_reserve(&asset, &reserve), XcmError::UntrustedReserveLocation);
 		}
 		// Note that here we are able to place any assets which could not be
 		// reanchored back into Holding.
@@ -783,10 +799,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		for asset in assets.assets_iter() {
 			// Must ensure that we have teleport trust with destination for these assets.
 			#[cfg(not(any(test, feature = "runtime-benchmarks")))]
-			ensure!(
-				Config::IsTeleporter::contains(&asset, &dest),
-				XcmError::UntrustedTeleportLocation
-			);
+			ensure!(Self::is_teleporter(&asset, &dest), XcmError::UntrustedTeleportLocation);
 			// We should check that the asset can actually be teleported out (for
 			// this to be in error, there would need to be an accounting violation
 			// by ourselves, so it's unlikely, but we don't want to allow that kind
@@ -850,6 +863,8 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		for (i, mut instr) in xcm.0.into_iter().enumerate() {
 			match &mut result {
 				r @ Ok(()) => {
+					// Only needed to explain the execution when dry-running.
+					let traced_instr = trace::is_capturing().then(|| instr.clone());
 					// Initialize the recursion count only the first time we hit this code in our
 					// potential recursive execution.
 					let inst_res = recursion_count::using_once(&mut 1, || {
@@ -873,6 +888,15 @@ impl<Config: config::Config> XcmExecutor<Config> {
 
 						self.process_instruction(instr)
 					});
+					if let Some(mut instr) = traced_instr {
+						trace::note_instruction(|| trace::InstructionStep {
+							index: i as u32,
+							weight: Config::Weigher::instr_weight(&mut instr).ok(),
+							instruction: instr.into(),
+							holding: self.holding.clone().into(),
+							error: inst_res.err(),
+						});
+					}
 					if let Err(error) = inst_res {
 						tracing::debug!(
 							target: "xcm::process",
@@ -943,7 +967,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				for asset in assets.inner() {
 					// Must ensure that we recognise the asset as being managed by the origin.
 					ensure!(
-						Config::IsReserve::contains(asset, origin),
+						Self::is_reserve(asset, origin),
 						XcmError::UntrustedReserveLocation
 					);
 				}
@@ -1015,7 +1039,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 						// We only trust the origin to send us assets that they identify as their
 						// sovereign assets.
 						ensure!(
-							Config::IsTeleporter::contains(asset, origin),
+							Self::is_teleporter(asset, origin),
 							XcmError::UntrustedTeleportLocation
 						);
 						// We should check that the asset can actually be teleported in (for this to
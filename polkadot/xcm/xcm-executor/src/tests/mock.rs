@@ -164,6 +164,8 @@ impl TransactAsset for TestAssetTransactor {
 /// Test barrier that just lets everything through.
 pub struct TestBarrier;
 impl ShouldExecute for TestBarrier {
+	const NAME: &'static str = "TestBarrier";
+
 	fn should_execute<Call>(
 		_origin: &Location,
 		_instructions: &mut [Instruction<Call>],
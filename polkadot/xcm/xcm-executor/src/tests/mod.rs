@@ -24,3 +24,5 @@ mod initiate_transfer;
 mod mock;
 mod pay_fees;
 mod set_asset_claimer;
+#[cfg(feature = "dry-run-trace")]
+mod trace;
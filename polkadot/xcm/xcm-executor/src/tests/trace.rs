@@ -0,0 +1,93 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unit tests related to tracing the execution of XCM programs.
+
+use codec::Encode;
+use frame_support::traits::ProcessMessageError;
+use xcm::prelude::*;
+
+use super::mock::*;
+use crate::{
+	trace::{self, Filter, FilterCheck},
+	XcmExecutor,
+};
+
+const SENDER: [u8; 32] = [0; 32];
+
+#[test]
+fn nothing_is_traced_unless_capturing() {
+	add_asset(SENDER, (Here, 100u128));
+	let xcm = Xcm::<TestCall>::builder_unsafe().withdraw_asset((Here, 100u128)).build();
+
+	assert!(!trace::is_capturing());
+	let (mut vm, _) = instantiate_executor(SENDER, xcm.clone());
+	assert!(vm.bench_process(xcm).is_ok());
+
+	let ((), trace) = trace::capture(|| assert!(trace::is_capturing()));
+	assert_eq!(trace, Default::default());
+	assert!(!trace::is_capturing());
+}
+
+#[test]
+fn barriers_filters_and_instructions_are_traced() {
+	add_asset(SENDER, (Here, 100u128));
+	let xcm = Xcm::<TestCall>::builder_unsafe()
+		.withdraw_asset((Here, 100u128))
+		.reserve_asset_deposited((Parent, 10u128))
+		.deposit_asset(All, SENDER)
+		.build();
+	let mut hash = xcm.using_encoded(sp_io::hashing::blake2_256);
+
+	let (outcome, trace) = trace::capture(|| {
+		XcmExecutor::<XcmConfig>::prepare_and_execute(
+			SENDER,
+			xcm,
+			&mut hash,
+			Weight::MAX,
+			Weight::zero(),
+		)
+	});
+	assert!(outcome.ensure_complete().is_err());
+
+	// The configured barrier let the message through.
+	assert_eq!(trace.barriers.len(), 1);
+	assert_eq!(trace.barriers[0].depth, 0);
+	assert_eq!(trace.barriers[0].result, Ok::<(), ProcessMessageError>(()));
+	assert_eq!(trace.barriers[0].barrier, b"TestBarrier".to_vec());
+
+	// The sender is not trusted as a reserve.
+	assert_eq!(
+		trace.filters,
+		vec![FilterCheck {
+			filter: Filter::IsReserve,
+			asset: (Parent, 10u128).into(),
+			location: SENDER.into(),
+			passed: false,
+		}]
+	);
+
+	// Execution stopped at the second instruction, with the withdrawn assets in holding.
+	assert_eq!(trace.instructions.len(), 2);
+	assert_eq!(trace.instructions[0].index, 0);
+	assert_eq!(trace.instructions[0].instruction, WithdrawAsset((Here, 100u128).into()));
+	assert_eq!(trace.instructions[0].weight, Some(Weight::from_parts(2, 2)));
+	assert_eq!(trace.instructions[0].holding, (Here, 100u128).into());
+	assert_eq!(trace.instructions[0].error, None);
+	assert_eq!(trace.instructions[1].index, 1);
+	assert_eq!(trace.instructions[1].holding, (Here, 100u128).into());
+	assert_eq!(trace.instructions[1].error, Some(XcmError::UntrustedReserveLocation));
+}
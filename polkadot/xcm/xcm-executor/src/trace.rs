@@ -0,0 +1,174 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Structured tracing of the execution of XCM programs.
+//!
+//! Meant to explain why a message was rejected or failed when dry-running it: the executor and the
+//! barrier tuples report what they do while a trace is being [captured](capture), and do nothing
+//! otherwise.
+//!
+//! Tracing is only compiled in with the `dry-run-trace` feature. Without it, [`capture`] returns
+//! empty traces and the hooks of the executor compile down to nothing, so runtimes which don't
+//! explain dry-runs don't pay for checking whether a trace is being captured.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use frame_support::traits::ProcessMessageError;
+use scale_info::TypeInfo;
+use xcm::latest::{Asset, Assets, Error as XcmError, Instruction, Location, Weight};
+
+/// A barrier which was checked.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct BarrierCheck {
+	/// The [name](crate::traits::ShouldExecute::NAME) of the barrier, UTF-8 encoded.
+	pub barrier: Vec<u8>,
+	/// How deeply the barrier is nested into the configured barrier, which is at depth zero.
+	pub depth: u32,
+	/// The result of the check.
+	pub result: Result<(), ProcessMessageError>,
+}
+
+/// A filter of the executor configuration.
+#[derive(Clone, Copy, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub enum Filter {
+	/// [`Config::IsReserve`](crate::Config::IsReserve).
+	IsReserve,
+	/// [`Config::IsTeleporter`](crate::Config::IsTeleporter).
+	IsTeleporter,
+}
+
+/// A check of an asset against a filter of the executor configuration.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct FilterCheck {
+	/// The filter the asset was checked against.
+	pub filter: Filter,
+	/// The checked asset.
+	pub asset: Asset,
+	/// The location the asset was checked for, e.g. the reserve of a reserve asset transfer.
+	pub location: Location,
+	/// Whether the filter passed.
+	pub passed: bool,
+}
+
+/// An executed instruction.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct InstructionStep {
+	/// The index of the instruction in the program it belongs to.
+	pub index: u32,
+	/// The instruction.
+	pub instruction: Instruction<()>,
+	/// The weight of the instruction, as estimated by the weigher.
+	pub weight: Option<Weight>,
+	/// The contents of the holding register after executing the instruction.
+	pub holding: Assets,
+	/// The error the instruction failed with, if any.
+	pub error: Option<XcmError>,
+}
+
+/// The trace of the execution of XCM programs.
+#[derive(Clone, Debug, Default, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct ExecutionTrace {
+	/// The checked barriers, in the order the checks started.
+	pub barriers: Vec<BarrierCheck>,
+	/// The checks of assets against the filters of the executor configuration, in order.
+	pub filters: Vec<FilterCheck>,
+	/// The executed instructions, in order, including those of appendices and error handlers.
+	pub instructions: Vec<InstructionStep>,
+}
+
+#[derive(Default)]
+struct Tracer {
+	trace: ExecutionTrace,
+	/// The number of barrier checks which started but did not finish yet.
+	open_barriers: u32,
+}
+
+environmental::environmental!(tracer: Tracer);
+
+/// Whether tracing is compiled in.
+const ENABLED: bool = cfg!(feature = "dry-run-trace");
+
+/// Run `f`, capturing the trace of all XCM programs it executes.
+pub fn capture<R>(f: impl FnOnce() -> R) -> (R, ExecutionTrace) {
+	if !ENABLED {
+		return (f(), ExecutionTrace::default())
+	}
+	let mut state = Tracer::default();
+	let result = tracer::using(&mut state, f);
+	(result, state.trace)
+}
+
+/// Returns `true` if a trace is being captured.
+#[inline(always)]
+pub fn is_capturing() -> bool {
+	ENABLED && tracer::with(|_| ()).is_some()
+}
+
+/// Note the start of checking the barrier named `name`, returning a handle to note its result
+/// with.
+#[inline(always)]
+pub(crate) fn start_barrier(name: &'static str) -> Option<usize> {
+	if !ENABLED {
+		return None
+	}
+	tracer::with(|state| {
+		let index = state.trace.barriers.len();
+		state.trace.barriers.push(BarrierCheck {
+			barrier: name.as_bytes().to_vec(),
+			depth: state.open_barriers,
+			result: Ok(()),
+		});
+		state.open_barriers = state.open_barriers.saturating_add(1);
+		index
+	})
+}
+
+/// Note the result of a barrier check started with [`start_barrier`].
+#[inline(always)]
+pub(crate) fn finish_barrier(handle: Option<usize>, result: &Result<(), ProcessMessageError>) {
+	let Some(index) = handle else { return };
+	tracer::with(|state| {
+		state.open_barriers = state.open_barriers.saturating_sub(1);
+		if let Some(check) = state.trace.barriers.get_mut(index) {
+			check.result = *result;
+		}
+	});
+}
+
+/// Note the check of `asset` for `location` against `filter`.
+#[inline(always)]
+pub(crate) fn note_filter(filter: Filter, asset: &Asset, location: &Location, passed: bool) {
+	if !ENABLED {
+		return
+	}
+	tracer::with(|state| {
+		state.trace.filters.push(FilterCheck {
+			filter,
+			asset: asset.clone(),
+			location: location.clone(),
+			passed,
+		})
+	});
+}
+
+/// Note the execution of an instruction.
+#[inline(always)]
+pub(crate) fn note_instruction(step: impl FnOnce() -> InstructionStep) {
+	if !ENABLED {
+		return
+	}
+	tracer::with(|state| state.trace.instructions.push(step()));
+}
@@ -43,7 +43,9 @@ pub use on_response::{OnResponse, QueryHandler, QueryResponseStatus, VersionChan
 mod process_transaction;
 pub use process_transaction::ProcessTransaction;
 mod should_execute;
-pub use should_execute::{CheckSuspension, DenyExecution, Properties, ShouldExecute};
+pub use should_execute::{
+	CheckRateLimit, CheckSuspension, DenyExecution, Properties, ShouldExecute,
+};
 mod transact_asset;
 pub use transact_asset::TransactAsset;
 mod hrmp;
@@ -35,6 +35,9 @@ pub struct Properties {
 /// Can be amalgamated into a tuple to have multiple trials. If any of the tuple elements returns
 /// `Ok(())`, the execution stops. Else, `Err(_)` is returned if all elements reject the message.
 pub trait ShouldExecute {
+	/// The name of the barrier in [execution traces](crate::trace).
+	const NAME: &'static str = "unnamed";
+
 	/// Returns `Ok(())` if the given `message` may be executed.
 	///
 	/// - `origin`: The origin (sender) of the message.
@@ -52,6 +55,8 @@ pub trait ShouldExecute {
 
 #[impl_trait_for_tuples::impl_for_tuples(30)]
 impl ShouldExecute for Tuple {
+	const NAME: &'static str = "tuple";
+
 	fn should_execute<RuntimeCall>(
 		origin: &Location,
 		instructions: &mut [Instruction<RuntimeCall>],
@@ -59,8 +64,11 @@ impl ShouldExecute for Tuple {
 		properties: &mut Properties,
 	) -> Result<(), ProcessMessageError> {
 		for_tuples!( #(
-			let barrier = core::any::type_name::<Tuple>();
- 			match Tuple::should_execute(origin, instructions, max_weight, properties) {
+			let barrier = Tuple::NAME;
+			let handle = crate::trace::start_barrier(barrier);
+			let result = Tuple::should_execute(origin, instructions, max_weight, properties);
+			crate::trace::finish_barrier(handle, &result);
+ 			match result {
 				Ok(()) => {
 					tracing::trace!(
 						target: "xcm::should_execute",
@@ -128,12 +136,31 @@ impl CheckSuspension for Tuple {
 	}
 }
 
+/// Trait to account for the execution of messages against a per-origin budget.
+///
+/// Implementations are stateful: a message that is admitted is accounted for against the budget of
+/// its origin, while a message that is not admitted leaves the budget untouched.
+pub trait CheckRateLimit {
+	/// Returns `true` if a message from `origin` estimated to use up to `max_weight` may be
+	/// executed, accounting for it in that case.
+	fn try_admit(origin: &Location, max_weight: Weight) -> bool;
+}
+
+impl CheckRateLimit for () {
+	fn try_admit(_origin: &Location, _max_weight: Weight) -> bool {
+		true
+	}
+}
+
 /// Trait to determine whether the execution engine should not execute a given XCM.
 ///
 /// Can be amalgamated into a tuple to have multiple traits. If any of the tuple elements returns
 /// `Err(ProcessMessageError)`, the execution stops. Else, `Ok(())` is returned if all elements
 /// accept the message.
 pub trait DenyExecution {
+	/// The name of the barrier in [execution traces](crate::trace).
+	const NAME: &'static str = "unnamed";
+
 	/// Returns `Ok(())` if there is no reason to deny execution,
 	/// while `Err(ProcessMessageError)` indicates there is a reason to deny execution.
 	///
@@ -152,6 +179,8 @@ pub trait DenyExecution {
 
 #[impl_trait_for_tuples::impl_for_tuples(10)]
 impl DenyExecution for Tuple {
+	const NAME: &'static str = "tuple";
+
 	fn deny_execution<RuntimeCall>(
 		origin: &Location,
 		instructions: &mut [Instruction<RuntimeCall>],
@@ -159,8 +188,11 @@ impl DenyExecution for Tuple {
 		properties: &mut Properties,
 	) -> Result<(), ProcessMessageError> {
 		for_tuples!( #(
-            let barrier = core::any::type_name::<Tuple>();
-            match Tuple::deny_execution(origin, instructions, max_weight, properties) {
+            let barrier = Tuple::NAME;
+            let handle = crate::trace::start_barrier(barrier);
+            let result = Tuple::deny_execution(origin, instructions, max_weight, properties);
+            crate::trace::finish_barrier(handle, &result);
+            match result {
                 Err(error) => {
                     tracing::error!(
                         target: "xcm::deny_execution",
@@ -22,6 +22,7 @@ use alloc::vec::Vec;
 use codec::{Decode, Encode};
 use frame_support::pallet_prelude::{DispatchResultWithPostInfo, TypeInfo};
 use xcm::prelude::*;
+use xcm_executor::trace::ExecutionTrace;
 
 /// Effects of dry-running an extrinsic.
 #[derive(Encode, Decode, Debug, TypeInfo)]
@@ -47,6 +48,16 @@ pub struct XcmDryRunEffects<Event> {
 	pub forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
 }
 
+/// Effects of dry-running an XCM program, with an explanation of how it was executed.
+#[derive(Encode, Decode, Debug, TypeInfo)]
+pub struct ExplainedXcmDryRunEffects<Event> {
+	/// The effects of the XCM program execution.
+	pub effects: XcmDryRunEffects<Event>,
+	/// Which barriers and filters the program was checked against, with their results, and the
+	/// state of the executor after each executed instruction.
+	pub explanation: ExecutionTrace,
+}
+
 sp_api::decl_runtime_apis! {
 	/// API for dry-running extrinsics and XCM programs to get the programs that need to be passed to the fees API.
 	///
@@ -73,6 +84,10 @@ sp_api::decl_runtime_apis! {
 
 		/// Dry run XCM program
 		fn dry_run_xcm(origin_location: VersionedLocation, xcm: VersionedXcm<Call>) -> Result<XcmDryRunEffects<Event>, Error>;
+
+		/// Dry run XCM program, explaining why it was rejected or how it was executed.
+		#[api_version(3)]
+		fn dry_run_xcm_explained(origin_location: VersionedLocation, xcm: VersionedXcm<Call>) -> Result<ExplainedXcmDryRunEffects<Event>, Error>;
 	}
 }
 
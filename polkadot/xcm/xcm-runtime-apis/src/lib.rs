@@ -30,6 +30,10 @@ pub mod dry_run;
 /// Fee estimation API.
 /// Given an XCM program, it will return the fees needed to execute it properly or send it.
 pub mod fees;
+/// Runtime APIs for discovering and claiming trapped assets.
+pub mod trapped_assets;
 /// Exposes runtime API for querying whether a Location is trusted as a reserve or teleporter for a
 /// given Asset.
 pub mod trusted_query;
+/// Runtime APIs for querying the state of XCM version negotiation.
+pub mod versions;
@@ -0,0 +1,97 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains runtime APIs for discovering and claiming assets trapped by failed XCM executions.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use frame_support::pallet_prelude::TypeInfo;
+use sp_core::H256;
+use sp_weights::Weight;
+use xcm::{VersionedAssetId, VersionedAssets, VersionedLocation, VersionedXcm};
+
+/// Assets trapped for an origin.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct TrappedAssets {
+	/// The hash identifying the trap, as emitted in the `AssetsTrapped` event.
+	pub hash: H256,
+	/// The trapped assets, in the version they were trapped with.
+	pub assets: VersionedAssets,
+	/// How many times the same assets were trapped for the same origin.
+	pub count: u32,
+	/// The block the assets were last trapped in.
+	pub block: u64,
+	/// The topic of the message which last trapped the assets, if it had one.
+	pub topic: Option<[u8; 32]>,
+}
+
+/// A program claiming trapped assets, executed with the origin the assets were trapped for.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct AssetClaim {
+	/// The program claiming the trapped assets and depositing them to the beneficiary.
+	pub program: VersionedXcm<()>,
+	/// The weight of executing the program.
+	pub weight: Weight,
+	/// The amount of the fee asset paid for executing the program, if a fee asset was given.
+	///
+	/// The program buys execution with the whole trapped amount of the fee asset, the unused part
+	/// is deposited to the beneficiary along with the other assets.
+	pub fee: Option<u128>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// API for discovering and claiming assets trapped by failed XCM executions.
+	///
+	/// Only traps which happened after the runtime started indexing them are reported.
+	pub trait TrappedAssetsApi {
+		/// Returns the assets trapped for `origin`.
+		fn trapped_assets(origin: VersionedLocation) -> Result<Vec<TrappedAssets>, Error>;
+
+		/// Builds the program claiming the trap `hash` of `origin` and depositing the assets to
+		/// `beneficiary`.
+		///
+		/// If `fee_asset` is given, the program pays for its execution with the trapped amount of
+		/// that asset, which is required when sending it to this chain from another one. The claim
+		/// then reports the amount of it paid for execution.
+		fn claim_trapped_assets(
+			origin: VersionedLocation,
+			hash: H256,
+			beneficiary: VersionedLocation,
+			fee_asset: Option<VersionedAssetId>,
+		) -> Result<AssetClaim, Error>;
+	}
+}
+
+/// `TrappedAssetsApi` Runtime APIs errors.
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum Error {
+	/// Converting a versioned data structure from one version to another failed.
+	#[codec(index = 0)]
+	VersionedConversionFailed,
+	/// No assets are trapped for the given origin and hash.
+	#[codec(index = 1)]
+	TrapNotFound,
+	/// The fee asset is not among the trapped assets.
+	#[codec(index = 2)]
+	FeeAssetNotTrapped,
+	/// The weight of the claim program could not be computed.
+	#[codec(index = 3)]
+	WeightNotComputable,
+	/// The weight of the claim program could not be priced in the fee asset.
+	#[codec(index = 4)]
+	FeeNotComputable,
+}
@@ -0,0 +1,89 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains runtime APIs for querying the state of XCM version negotiation.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use frame_support::pallet_prelude::TypeInfo;
+use xcm::{Version as XcmVersion, VersionedLocation};
+
+/// How the XCM version of messages sent to a destination is determined.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub enum VersionSource {
+	/// The version the destination advertised is used.
+	Negotiated,
+	/// The version of the destination is unknown, the safe XCM version is used.
+	SafeFallback,
+	/// The version of the destination is unknown and there is no safe XCM version, messages to
+	/// the destination can't be sent.
+	Unknown,
+}
+
+/// A subscription to the version changes of another location.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct VersionSubscription {
+	/// The location notifying of its version changes.
+	pub location: VersionedLocation,
+	/// The query the notifications respond to.
+	pub query_id: u64,
+	/// Whether the location responded to the subscription. Until then, negotiation is pending.
+	pub is_active: bool,
+}
+
+/// The state of version negotiation with a destination.
+#[derive(Clone, Debug, Encode, Decode, Eq, PartialEq, TypeInfo)]
+pub struct DestinationVersion {
+	/// The version messages to the destination are encoded with, `None` if they can't be sent.
+	pub version: Option<XcmVersion>,
+	/// How `version` is determined.
+	pub source: VersionSource,
+	/// The latest version the destination advertised, which may be newer than the versions
+	/// supported by this chain.
+	pub advertised: Option<XcmVersion>,
+	/// The subscription to the version changes of the destination, if any.
+	pub subscription: Option<VersionSubscription>,
+	/// Whether the destination is queued for subscribing to its version changes, because a
+	/// message was sent to it while its version was unknown.
+	pub discovery_queued: bool,
+	/// The version of this chain the destination was last notified of, if it subscribed to the
+	/// version changes of this chain.
+	pub notified: Option<XcmVersion>,
+}
+
+sp_api::decl_runtime_apis! {
+	/// API for querying the state of XCM version negotiation, e.g. to find out which version to
+	/// encode messages to a destination with.
+	pub trait XcmVersionApi {
+		/// Returns the state of version negotiation with `destination`.
+		fn destination_version(destination: VersionedLocation) -> Result<DestinationVersion, Error>;
+
+		/// Returns the subscriptions to the version changes of other locations, with their
+		/// locations in `xcm_version`.
+		fn version_subscriptions(xcm_version: XcmVersion) -> Result<Vec<VersionSubscription>, Error>;
+
+		/// Returns the version used for destinations whose version is unknown, if any.
+		fn safe_xcm_version() -> Option<XcmVersion>;
+	}
+}
+
+/// `XcmVersionApi` Runtime APIs errors.
+#[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum Error {
+	/// Converting a versioned data structure from one version to another failed.
+	#[codec(index = 0)]
+	VersionedConversionFailed,
+}
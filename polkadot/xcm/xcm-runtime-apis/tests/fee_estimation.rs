@@ -16,10 +16,11 @@
 
 //! Tests for using both the XCM fee payment API and the dry-run API.
 
-use frame_support::sp_runtime::testing::H256;
+use frame_support::{sp_runtime::testing::H256, traits::ProcessMessageError};
 use frame_system::RawOrigin;
 use sp_api::ProvideRuntimeApi;
 use xcm::prelude::*;
+use xcm_executor::trace::{Filter, FilterCheck};
 use xcm_runtime_apis::{
 	dry_run::{CallDryRunEffects, DryRunApi},
 	fees::XcmPaymentApi,
@@ -569,3 +570,72 @@ fn fee_estimation_for_usdt_reserve_transfer_in_usdt() {
 		assert_eq!(delivery_fees_usdt, expected_usdt_fees);
 	});
 }
+
+#[test]
+fn dry_run_xcm_explains_barriers_filters_and_instructions() {
+	sp_tracing::init_for_tests();
+	let client = TestClient;
+	let runtime_api = client.runtime_api();
+	new_test_ext_with_balances(vec![(1, 1_000)]).execute_with(|| {
+		// A message from the relay chain is rejected by all barriers.
+		let xcm = Xcm::<RuntimeCall>::builder_unsafe().clear_origin().build();
+		let explained = runtime_api
+			.dry_run_xcm_explained(H256::zero(), Parent.into(), VersionedXcm::from(xcm))
+			.unwrap()
+			.unwrap();
+		assert_eq!(
+			explained.effects.execution_result,
+			Outcome::Incomplete {
+				used: Weight::from_parts(100, 10),
+				error: InstructionError { index: 0, error: XcmError::Barrier },
+			}
+		);
+		let barriers: Vec<_> = explained
+			.explanation
+			.barriers
+			.iter()
+			.map(|check| (core::str::from_utf8(&check.barrier).unwrap(), check.depth, check.result))
+			.collect();
+		assert_eq!(
+			barriers,
+			vec![
+				("tuple", 0, Err(ProcessMessageError::Unsupported)),
+				(
+					"TakeWeightCredit",
+					1,
+					Err(ProcessMessageError::Overweight(Weight::from_parts(100, 10)))
+				),
+				("AllowTopLevelPaidExecutionFrom", 1, Err(ProcessMessageError::Unsupported)),
+			]
+		);
+		assert!(explained.explanation.instructions.is_empty());
+
+		// A local account is no reserve of the relay token.
+		let who: Location = AccountIndex64 { index: 1, network: None }.into();
+		let xcm = Xcm::<RuntimeCall>::builder()
+			.reserve_asset_deposited((Parent, 100u128))
+			.buy_execution((Parent, 100u128), Unlimited)
+			.deposit_asset(AllCounted(1), who.clone())
+			.build();
+		let explained = runtime_api
+			.dry_run_xcm_explained(H256::zero(), who.clone().into(), VersionedXcm::from(xcm))
+			.unwrap()
+			.unwrap();
+		assert_eq!(explained.explanation.barriers[0].result, Ok(()));
+		assert_eq!(
+			explained.explanation.filters,
+			vec![FilterCheck {
+				filter: Filter::IsReserve,
+				asset: (Parent, 100u128).into(),
+				location: who,
+				passed: false,
+			}]
+		);
+		let instructions = &explained.explanation.instructions;
+		assert_eq!(instructions.len(), 1);
+		assert_eq!(instructions[0].instruction, ReserveAssetDeposited((Parent, 100u128).into()));
+		assert_eq!(instructions[0].weight, Some(Weight::from_parts(100, 10)));
+		assert_eq!(instructions[0].holding, Assets::new());
+		assert_eq!(instructions[0].error, Some(XcmError::UntrustedReserveLocation));
+	});
+}
@@ -0,0 +1,228 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Inputs seeding the corpus, shaped like the messages transferring assets on live networks.
+//!
+//! Each input is made of the messages of one scenario, encoded the way [`XcmMessage`] decodes
+//! them: the source and destination as little-endian `u32`s, followed by the SCALE-encoded
+//! message.
+//!
+//! [`XcmMessage`]: crate::XcmMessage
+
+use crate::{INITIAL_BALANCE, MESSAGES_PER_INPUT};
+use codec::Encode;
+use frame_support::BoundedVec;
+use xcm::latest::{prelude::*, AssetTransferFilter};
+
+/// The routes of messages, as the source and destination the fuzzer maps to them.
+const RELAY_TO_PARA_A: (u32, u32) = (0, 0);
+const PARA_A_TO_RELAY: (u32, u32) = (1, 0);
+const PARA_B_TO_RELAY: (u32, u32) = (2, 0);
+
+const AMOUNT: u128 = INITIAL_BALANCE / 10;
+const FEES: u128 = AMOUNT / 10;
+
+/// Returns the named inputs to seed the corpus with.
+pub fn inputs() -> Vec<(&'static str, Vec<u8>)> {
+	let relay_token: AssetId = Here.into();
+	let para_token: AssetId = Parent.into();
+	vec![
+		(
+			"reserve_transfer_from_relay",
+			input(vec![(
+				RELAY_TO_PARA_A,
+				Xcm(vec![
+					ReserveAssetDeposited((para_token.clone(), AMOUNT).into()),
+					ClearOrigin,
+					buy_execution(para_token.clone()),
+					deposit(),
+				]),
+			)]),
+		),
+		(
+			"reserve_withdraw_to_relay",
+			input(vec![(
+				PARA_A_TO_RELAY,
+				Xcm(vec![
+					WithdrawAsset((relay_token.clone(), AMOUNT).into()),
+					ClearOrigin,
+					buy_execution(relay_token.clone()),
+					deposit(),
+				]),
+			)]),
+		),
+		(
+			"reserve_transfer_to_sibling",
+			input(vec![(
+				PARA_A_TO_RELAY,
+				Xcm(vec![
+					WithdrawAsset((relay_token.clone(), AMOUNT).into()),
+					buy_execution(relay_token.clone()),
+					DepositReserveAsset {
+						assets: Wild(AllCounted(1)),
+						dest: Parachain(2).into(),
+						xcm: Xcm(vec![buy_execution(para_token.clone()), deposit()]),
+					},
+				]),
+			)]),
+		),
+		(
+			"teleport_mismatch",
+			input(vec![
+				(
+					PARA_A_TO_RELAY,
+					Xcm(vec![
+						ReceiveTeleportedAsset((relay_token.clone(), AMOUNT).into()),
+						ClearOrigin,
+						buy_execution(relay_token.clone()),
+						deposit(),
+					]),
+				),
+				(
+					PARA_A_TO_RELAY,
+					Xcm(vec![
+						WithdrawAsset((relay_token.clone(), AMOUNT).into()),
+						InitiateTeleport {
+							assets: Wild(AllCounted(1)),
+							dest: Parachain(1).into(),
+							xcm: Xcm(vec![deposit()]),
+						},
+					]),
+				),
+				(
+					PARA_A_TO_RELAY,
+					Xcm(vec![
+						ReceiveTeleportedAsset((relay_token.clone(), AMOUNT).into()),
+						ClearOrigin,
+						buy_execution(relay_token.clone()),
+						deposit(),
+					]),
+				),
+				(
+					PARA_B_TO_RELAY,
+					Xcm(vec![
+						ReceiveTeleportedAsset((relay_token.clone(), AMOUNT).into()),
+						ClearOrigin,
+						deposit(),
+					]),
+				),
+			]),
+		),
+		(
+			"trap_and_claim",
+			input(vec![
+				(PARA_A_TO_RELAY, Xcm(vec![WithdrawAsset((relay_token.clone(), AMOUNT).into())])),
+				(
+					PARA_A_TO_RELAY,
+					Xcm(vec![
+						ClaimAsset {
+							assets: (relay_token.clone(), AMOUNT).into(),
+							ticket: Here.into(),
+						},
+						deposit(),
+					]),
+				),
+				(
+					PARA_A_TO_RELAY,
+					Xcm(vec![
+						ClaimAsset {
+							assets: (relay_token.clone(), AMOUNT).into(),
+							ticket: Here.into(),
+						},
+						deposit(),
+					]),
+				),
+			]),
+		),
+		(
+			"initiate_transfer",
+			input(vec![(
+				PARA_A_TO_RELAY,
+				Xcm(vec![
+					WithdrawAsset((relay_token.clone(), AMOUNT).into()),
+					PayFees { asset: (relay_token.clone(), FEES).into() },
+					InitiateTransfer {
+						destination: Parachain(2).into(),
+						remote_fees: Some(AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1)))),
+						preserve_origin: false,
+						assets: BoundedVec::truncate_from(vec![
+							AssetTransferFilter::ReserveDeposit(Wild(AllCounted(1))),
+						]),
+						remote_xcm: Xcm(vec![deposit()]),
+					},
+				]),
+			)]),
+		),
+		(
+			"appendix_and_error_handler",
+			input(vec![(
+				PARA_A_TO_RELAY,
+				Xcm(vec![
+					SetAppendix(Xcm(vec![RefundSurplus, deposit()])),
+					SetErrorHandler(Xcm(vec![BurnAsset((relay_token.clone(), FEES).into())])),
+					WithdrawAsset((relay_token.clone(), AMOUNT).into()),
+					buy_execution(relay_token.clone()),
+					Trap(1),
+				]),
+			)]),
+		),
+		(
+			"report_holding",
+			input(vec![(
+				PARA_A_TO_RELAY,
+				Xcm(vec![
+					WithdrawAsset((relay_token, AMOUNT).into()),
+					ReportHolding {
+						response_info: QueryResponseInfo {
+							destination: Parachain(1).into(),
+							query_id: 1,
+							max_weight: Weight::zero(),
+						},
+						assets: Wild(All),
+					},
+					deposit(),
+				]),
+			)]),
+		),
+	]
+}
+
+/// Encodes the messages of a scenario as one input, padded with empty messages.
+fn input(messages: Vec<((u32, u32), Xcm<()>)>) -> Vec<u8> {
+	assert!(messages.len() <= MESSAGES_PER_INPUT, "too many messages for one input");
+	let padding = MESSAGES_PER_INPUT - messages.len();
+	messages
+		.into_iter()
+		.chain(core::iter::repeat_with(|| (RELAY_TO_PARA_A, Xcm::new())).take(padding))
+		.flat_map(|((source, destination), message)| {
+			let mut encoded = source.to_le_bytes().to_vec();
+			encoded.extend(destination.to_le_bytes());
+			encoded.extend(message.encode());
+			encoded
+		})
+		.collect()
+}
+
+fn buy_execution(id: AssetId) -> Instruction<()> {
+	BuyExecution { fees: (id, FEES).into(), weight_limit: Unlimited }
+}
+
+fn deposit() -> Instruction<()> {
+	DepositAsset {
+		assets: Wild(AllCounted(1)),
+		beneficiary: AccountId32 { network: None, id: [1; 32] }.into(),
+	}
+}
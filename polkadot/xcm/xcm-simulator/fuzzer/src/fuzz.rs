@@ -22,7 +22,14 @@ mod parachain;
 #[allow(unexpected_cfgs)]
 mod relay_chain;
 
+#[cfg(not(fuzzing))]
+mod corpus;
+mod invariants;
+#[cfg(test)]
+mod tests;
+
 use codec::DecodeLimit;
+use invariants::Invariants;
 use polkadot_core_primitives::AccountId;
 use polkadot_parachain_primitives::primitives::Id as ParaId;
 use sp_runtime::{traits::AccountIdConversion, BuildStorage};
@@ -36,6 +43,8 @@ use xcm::{latest::prelude::*, MAX_XCM_DECODE_DEPTH};
 use arbitrary::{Arbitrary, Error, Unstructured};
 
 pub const INITIAL_BALANCE: u128 = 1_000_000_000;
+/// The number of messages sent for each input.
+pub const MESSAGES_PER_INPUT: usize = 5;
 
 decl_test_parachain! {
 	pub struct ParaA {
@@ -101,13 +110,14 @@ impl<'a> Arbitrary<'a> for XcmMessage {
 	fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self, Error> {
 		let source: u32 = u.arbitrary()?;
 		let destination: u32 = u.arbitrary()?;
-		let mut encoded_message: &[u8] = u.arbitrary()?;
-		if let Ok(message) =
-			DecodeLimit::decode_all_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut encoded_message)
-		{
-			return Ok(XcmMessage { source, destination, message });
-		}
-		Err(Error::IncorrectFormat)
+		// The message is decoded from the remaining data, so that inputs seeding the corpus can be
+		// made by appending encoded messages.
+		let mut encoded_message = u.peek_bytes(u.len()).unwrap_or_default();
+		let message =
+			DecodeLimit::decode_with_depth_limit(MAX_XCM_DECODE_DEPTH, &mut encoded_message)
+				.map_err(|_| Error::IncorrectFormat)?;
+		u.bytes(u.len() - encoded_message.len())?;
+		Ok(XcmMessage { source, destination, message })
 	}
 }
 
@@ -165,14 +175,18 @@ fn recursively_matches_blocklisted_messages(message: &Instruction<()>) -> bool {
 		InitiateTeleport { xcm, .. } |
 		TransferReserveAsset { xcm, .. } |
 		SetErrorHandler(xcm) |
-		SetAppendix(xcm) => xcm.iter().any(recursively_matches_blocklisted_messages),
+		SetAppendix(xcm) |
+		ExecuteWithOrigin { xcm, .. } |
+		InitiateTransfer { remote_xcm: xcm, .. } =>
+			xcm.iter().any(recursively_matches_blocklisted_messages),
 		// The blocklisted message is the Transact instruction.
 		m => matches!(m, Transact { .. }),
 	}
 }
 
-fn run_input(xcm_messages: [XcmMessage; 5]) {
+fn run_input(xcm_messages: [XcmMessage; MESSAGES_PER_INPUT]) {
 	MockNet::reset();
+	let invariants = Invariants::new();
 
 	#[cfg(not(fuzzing))]
 	println!();
@@ -240,6 +254,8 @@ fn run_input(xcm_messages: [XcmMessage; 5]) {
 			relay_chain::AllPalletsWithSystem::try_state(Default::default(), All).unwrap();
 			relay_chain::AllPalletsWithSystem::integrity_test();
 		});
+		// We check that no assets were created and that trapped assets can be claimed
+		invariants.check();
 	}
 }
 
@@ -247,7 +263,7 @@ fn main() {
 	#[cfg(fuzzing)]
 	{
 		loop {
-			honggfuzz::fuzz!(|xcm_messages: [XcmMessage; 5]| {
+			honggfuzz::fuzz!(|xcm_messages: [XcmMessage; MESSAGES_PER_INPUT]| {
 				run_input(xcm_messages);
 			})
 		}
@@ -256,6 +272,24 @@ fn main() {
 	{
 		use std::{env, fs, fs::File, io::Read};
 		let args: Vec<_> = env::args().collect();
+		let usage = || -> ! {
+			eprintln!(
+				"Usage: xcm-fuzzer <input file or directory>... | --write-corpus <directory>"
+			);
+			std::process::exit(1)
+		};
+		if args.len() < 2 {
+			usage()
+		}
+		if args[1] == "--write-corpus" {
+			let Some(directory) = args.get(2) else { usage() };
+			for (name, input) in corpus::inputs() {
+				let path = std::path::Path::new(directory).join(name);
+				println!("Writing {:?}", path);
+				fs::write(path, input).unwrap();
+			}
+			return
+		}
 		let md = fs::metadata(&args[1]).unwrap();
 		let all_files = match md.is_dir() {
 			true => fs::read_dir(&args[1])
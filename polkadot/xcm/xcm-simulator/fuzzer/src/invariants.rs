@@ -0,0 +1,153 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Invariants checked across the network after each message.
+//!
+//! The fuzzer sends messages with the authority of the sending chain, so the invariants only bound
+//! what the chains may do, instead of requiring exact conservation:
+//!
+//! - The relay chain neither accepts reserve assets nor mints teleported assets beyond what was
+//!   teleported out, so its issuance, together with the value trapped on it, never increases.
+//! - A parachain only accepts the relay token as a reserve asset sent by the relay chain, so its
+//!   issuance, together with the value trapped on it, never increases by more than the relay chain
+//!   deposited into it.
+//! - Assets left in the holding register are trapped, and the traps match their index by origin, so
+//!   trapped assets can be claimed as many times as they were trapped.
+//!
+//! Checking the invariants after each message catches assets duplicated on their way between
+//! chains, e.g. assets deposited on a parachain without being sent by the relay chain.
+
+use crate::{parachain, relay_chain, ParaA, ParaB, ParaC, Relay};
+use codec::Decode;
+use frame_support::{storage::migration::storage_iter, traits::PalletInfoAccess};
+use frame_system::pallet_prelude::BlockNumberFor;
+use pallet_xcm::AssetTrapDetails;
+use sp_core::H256;
+use std::collections::{BTreeMap, BTreeSet};
+use xcm::{latest::prelude::*, VersionedAssets};
+use xcm_simulator::TestExt;
+
+/// The state of the network the invariants are checked against.
+pub struct Invariants {
+	relay_issuance: u128,
+	para_issuance: [u128; 3],
+}
+
+impl Invariants {
+	/// Takes the state of the network to check the invariants against.
+	pub fn new() -> Self {
+		Self {
+			relay_issuance: Relay::execute_with(relay_chain::Balances::total_issuance),
+			para_issuance: [
+				ParaA::execute_with(parachain::Balances::total_issuance),
+				ParaB::execute_with(parachain::Balances::total_issuance),
+				ParaC::execute_with(parachain::Balances::total_issuance),
+			],
+		}
+	}
+
+	/// Panics if any invariant is violated.
+	pub fn check(&self) {
+		Relay::execute_with(|| {
+			let trapped = trapped_value::<relay_chain::Runtime>(&Here.into());
+			let issuance = relay_chain::Balances::total_issuance();
+			assert!(
+				issuance.saturating_add(trapped) <= self.relay_issuance,
+				"relay chain created assets: issuance {issuance} with {trapped} trapped, \
+				 initially {}",
+				self.relay_issuance,
+			);
+		});
+
+		let paras = [ParaA::execute_with, ParaB::execute_with, ParaC::execute_with];
+		for (i, execute_with) in paras.iter().enumerate() {
+			execute_with(move || {
+				let trapped = trapped_value::<parachain::Runtime>(&Parent.into());
+				let deposited = parachain::MsgQueue::received_dmp()
+					.iter()
+					.map(|message| reserve_deposited(message, &Parent.into()))
+					.fold(0, u128::saturating_add);
+				let issuance = parachain::Balances::total_issuance();
+				assert!(
+					issuance.saturating_add(trapped) <=
+						self.para_issuance[i].saturating_add(deposited),
+					"parachain {} created assets: issuance {issuance} with {trapped} trapped, \
+					 initially {} with {deposited} deposited by the relay chain",
+					i + 1,
+					self.para_issuance[i],
+				);
+			});
+		}
+	}
+}
+
+/// Returns the value of `id` trapped on the chain of the pallet of `T`.
+///
+/// Panics if the traps don't match their index by origin.
+fn trapped_value<T: pallet_xcm::Config>(id: &AssetId) -> u128 {
+	let pallet = <pallet_xcm::Pallet<T> as PalletInfoAccess>::name().as_bytes();
+	let traps: BTreeMap<H256, u32> = storage_iter::<u32>(pallet, b"AssetTraps")
+		.map(|(key, count)| {
+			(H256::decode(&mut &key[..]).expect("traps are keyed by their hash; qed"), count)
+		})
+		.collect();
+	let mut indexed = BTreeSet::new();
+	let mut value = 0u128;
+	let index = storage_iter::<AssetTrapDetails<BlockNumberFor<T>>>(pallet, b"AssetTrapsByOrigin");
+	for (key, details) in index {
+		// The index is keyed by the hash of the origin, followed by the hash of the trap.
+		let (_origin, hash) = <(H256, H256)>::decode(&mut &key[..])
+			.expect("the index is keyed by origin and trap hashes; qed");
+		let count = traps.get(&hash).copied().unwrap_or_default();
+		assert!(count > 0, "trap {hash:?} indexed but not claimable");
+		let amount = fungible_value(&latest(details.assets), id);
+		value = value.saturating_add(amount.saturating_mul(count.into()));
+		indexed.insert(hash);
+	}
+	for hash in traps.keys() {
+		assert!(indexed.contains(hash), "trap {hash:?} not indexed");
+	}
+	value
+}
+
+/// Returns the total fungible amount of `id` in `assets`.
+fn fungible_value(assets: &Assets, id: &AssetId) -> u128 {
+	assets
+		.inner()
+		.iter()
+		.filter_map(|asset| match asset {
+			Asset { id: asset_id, fun: Fungible(amount) } if asset_id == id => Some(*amount),
+			_ => None,
+		})
+		.fold(0, u128::saturating_add)
+}
+
+fn latest(assets: VersionedAssets) -> Assets {
+	Assets::try_from(assets).expect("trapped assets are converted from the latest version; qed")
+}
+
+/// Returns the fungible amount of `id` which `message` deposits as a reserve asset when executed.
+fn reserve_deposited<Call>(message: &Xcm<Call>, id: &AssetId) -> u128 {
+	message
+		.iter()
+		.map(|instruction| match instruction {
+			ReserveAssetDeposited(assets) => fungible_value(assets, id),
+			SetErrorHandler(xcm) | SetAppendix(xcm) | ExecuteWithOrigin { xcm, .. } =>
+				reserve_deposited(xcm, id),
+			_ => 0,
+		})
+		.fold(0, u128::saturating_add)
+}
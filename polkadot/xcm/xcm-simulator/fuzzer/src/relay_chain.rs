@@ -40,7 +40,7 @@ use xcm::latest::prelude::*;
 use xcm_builder::{
 	AccountId32Aliases, AllowUnpaidExecutionFrom, ChildParachainAsNative,
 	ChildParachainConvertsVia, ChildSystemParachainAsSuperuser, FixedRateOfFungible,
-	FixedWeightBounds, FrameTransactionalProcessor, FungibleAdapter, IsConcrete,
+	FixedWeightBounds, FrameTransactionalProcessor, FungibleAdapter, IsConcrete, MintLocation,
 	SignedAccountId32AsNative, SignedToAccountId32, SovereignSignedViaLocation,
 };
 use xcm_executor::{Config, XcmExecutor};
@@ -94,13 +94,20 @@ parameter_types! {
 	pub const ThisNetwork: NetworkId = NetworkId::ByGenesis([0; 32]);
 	pub const AnyNetwork: Option<NetworkId> = None;
 	pub UniversalLocation: InteriorLocation = ThisNetwork::get().into();
+	pub CheckAccount: AccountId = XcmPallet::check_account();
+	pub LocalCheckAccount: Option<(AccountId, MintLocation)> = Some((CheckAccount::get(), MintLocation::Local));
 }
 
 pub type SovereignAccountOf =
 	(ChildParachainConvertsVia<ParaId, AccountId>, AccountId32Aliases<ThisNetwork, AccountId>);
 
-pub type LocalAssetTransactor =
-	FungibleAdapter<Balances, IsConcrete<TokenLocation>, SovereignAccountOf, AccountId, ()>;
+pub type LocalAssetTransactor = FungibleAdapter<
+	Balances,
+	IsConcrete<TokenLocation>,
+	SovereignAccountOf,
+	AccountId,
+	LocalCheckAccount,
+>;
 
 type LocalOriginConverter = (
 	SovereignSignedViaLocation<SovereignAccountOf, RuntimeOrigin>,
@@ -116,6 +123,16 @@ parameter_types! {
 	pub const MaxAssetsIntoHolding: u32 = 64;
 }
 
+parameter_types! {
+	pub ParaATeleport: (AssetFilter, Location) =
+		(Wild(AllOf { id: AssetId(TokenLocation::get()), fun: WildFungible }), Parachain(1).into());
+}
+
+/// Parachain A is trusted to teleport the relay token, while the parachains only accept it as a
+/// reserve asset. Teleports are tracked in the checking account, so this mismatch must not allow
+/// creating relay tokens.
+pub type TrustedTeleporters = xcm_builder::Case<ParaATeleport>;
+
 pub type XcmRouter = super::RelayChainXcmRouter;
 pub type Barrier = AllowUnpaidExecutionFrom<Everything>;
 
@@ -127,16 +144,16 @@ impl Config for XcmConfig {
 	type AssetTransactor = LocalAssetTransactor;
 	type OriginConverter = LocalOriginConverter;
 	type IsReserve = ();
-	type IsTeleporter = ();
+	type IsTeleporter = TrustedTeleporters;
 	type UniversalLocation = UniversalLocation;
 	type Barrier = Barrier;
 	type Weigher = FixedWeightBounds<BaseXcmWeight, RuntimeCall, MaxInstructions>;
 	type Trader = FixedRateOfFungible<KsmPerSecondPerByte, ()>;
 	type ResponseHandler = ();
-	type AssetTrap = ();
+	type AssetTrap = XcmPallet;
 	type AssetLocker = ();
 	type AssetExchanger = ();
-	type AssetClaims = ();
+	type AssetClaims = XcmPallet;
 	type SubscriptionService = ();
 	type PalletInstancesInfo = ();
 	type FeeManager = ();
@@ -0,0 +1,65 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::*;
+use frame_support::traits::fungible::Mutate;
+
+const ALICE: AccountId = AccountId::new([0u8; 32]);
+const AMOUNT: u128 = 123;
+
+/// Reserve transfers `AMOUNT` of the relay token from the relay chain to `ALICE` on `ParaB`.
+///
+/// `ParaA` is a trusted teleporter of the relay token, so the relay chain would teleport to it.
+fn reserve_transfer_to_para_b() {
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::limited_reserve_transfer_assets(
+			relay_chain::RuntimeOrigin::signed(ALICE),
+			Box::new(Parachain(2).into()),
+			Box::new(AccountId32 { network: None, id: ALICE.into() }.into()),
+			Box::new((Here, AMOUNT).into()),
+			0,
+			Unlimited,
+		));
+	});
+	ParaB::execute_with(|| {
+		assert_eq!(parachain::Balances::free_balance(&ALICE), INITIAL_BALANCE + AMOUNT);
+	});
+}
+
+#[test]
+fn reserve_transfers_conserve_assets() {
+	MockNet::reset();
+	let invariants = Invariants::new();
+
+	reserve_transfer_to_para_b();
+
+	invariants.check();
+}
+
+#[test]
+#[should_panic(expected = "parachain 2 created assets")]
+fn duplicated_assets_are_caught() {
+	MockNet::reset();
+	let invariants = Invariants::new();
+
+	reserve_transfer_to_para_b();
+	// The transferred assets are deposited a second time on the parachain.
+	ParaB::execute_with(|| {
+		assert_ok!(parachain::Balances::mint_into(&ALICE, AMOUNT));
+	});
+
+	invariants.check();
+}
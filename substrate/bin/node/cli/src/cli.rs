@@ -103,6 +103,9 @@ pub enum Subcommand {
 	/// Export the state of a given block into a chain spec.
 	ExportState(sc_cli::ExportStateCmd),
 
+	/// Export the state of a given block into the chain spec of a parachain.
+	SoloToPara(frame_solo_to_para_cli::SoloToParaCmd),
+
 	/// Import blocks.
 	ImportBlocks(sc_cli::ImportBlocksCmd),
 
@@ -1033,6 +1033,7 @@ mod tests {
 							block_size_limit: None,
 							storage_proof_recorder: None,
 							extra_extensions: Default::default(),
+							min_transaction_priority: None,
 						},
 					)
 					.await
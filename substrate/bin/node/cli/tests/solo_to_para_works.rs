@@ -0,0 +1,80 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+#![cfg(unix)]
+
+use assert_cmd::cargo::cargo_bin;
+use polkadot_sdk::sp_core::{hexdisplay::HexDisplay, twox_128};
+use std::{fs, process::Command};
+use tempfile::tempdir;
+
+use substrate_cli_test_utils as common;
+
+const ALICE: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+fn hex(data: &[u8]) -> String {
+	format!("0x{}", HexDisplay::from(&data))
+}
+
+#[tokio::test]
+async fn solo_to_para_works() {
+	let base_path = tempdir().expect("could not create a temp dir");
+
+	common::run_node_for_a_while(base_path.path(), &["--dev", "--no-hardware-benchmarks"]).await;
+
+	let runtime = base_path.path().join("parachain.wasm");
+	let spec = base_path.path().join("spec.json");
+	let checklist = base_path.path().join("checklist.md");
+	// The kitchensink runtime stands in for the parachain runtime.
+	let code = kitchensink_runtime::WASM_BINARY.expect("Development wasm binary is not available");
+	fs::write(&runtime, code).unwrap();
+
+	let status = Command::new(cargo_bin("substrate-node"))
+		.args(&["solo-to-para", "--dev", "-d"])
+		.arg(base_path.path())
+		.args(&["--para-id", "2000", "--relay-chain", "rococo-local", "--runtime"])
+		.arg(&runtime)
+		.args(&["--collator", &format!("{ALICE}={ALICE}"), "--output"])
+		.arg(&spec)
+		.arg("--checklist")
+		.arg(&checklist)
+		.status()
+		.unwrap();
+	assert!(status.success());
+
+	let spec: serde_json::Value = serde_json::from_slice(&fs::read(spec).unwrap()).unwrap();
+	assert_eq!(spec["para_id"], 2000);
+	assert_eq!(spec["relay_chain"], "rococo-local");
+
+	let storage = spec["genesis"]["raw"]["top"].as_object().unwrap();
+	assert_eq!(storage[&hex(b":code")], hex(code));
+	let parachain_id = [twox_128(b"ParachainInfo"), twox_128(b"ParachainId")].concat();
+	assert_eq!(storage[&hex(&parachain_id)], hex(&2000u32.to_le_bytes()));
+	// The storage of BABE is dropped, and initialized again from the genesis of the runtime since
+	// it is part of it.
+	let babe_version = [twox_128(b"Babe"), twox_128(b":__STORAGE_VERSION__:")].concat();
+	assert!(storage.contains_key(&hex(&babe_version)));
+	assert!(!storage.contains_key(&hex(b":grandpa_authorities")));
+
+	let checklist = fs::read_to_string(checklist).unwrap();
+	assert!(checklist.contains("- [x] `Babe`: dropped"));
+	assert!(checklist.contains("- [x] `Babe`: initialized from the parachain genesis"));
+	assert!(checklist.contains("- [x] `Session`: rewritten"));
+	assert!(checklist.contains("- [ ] `Staking`: kept, to drop or migrate"));
+	assert!(checklist.contains("- [x] `Balances`: kept"));
+}
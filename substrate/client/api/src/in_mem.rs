@@ -419,8 +419,18 @@ impl<Block: BlockT> blockchain::Backend<Block> for Blockchain<Block> {
 		Ok(self.storage.read().leaves.hashes())
 	}
 
-	fn children(&self, _parent_hash: Block::Hash) -> sp_blockchain::Result<Vec<Block::Hash>> {
-		unimplemented!()
+	fn children(&self, parent_hash: Block::Hash) -> sp_blockchain::Result<Vec<Block::Hash>> {
+		let mut children = self
+			.storage
+			.read()
+			.blocks
+			.iter()
+			.filter(|(_, block)| *block.header().parent_hash() == parent_hash)
+			.map(|(hash, _)| *hash)
+			.collect::<Vec<_>>();
+		// The blocks are not stored in any particular order.
+		children.sort();
+		Ok(children)
 	}
 
 	fn indexed_transaction(&self, _hash: Block::Hash) -> sp_blockchain::Result<Option<Vec<u8>>> {
@@ -871,4 +881,22 @@ mod tests {
 			Err(sp_blockchain::Error::BadJustification(_)),
 		));
 	}
+
+	#[test]
+	fn children_are_returned() {
+		let blockchain = test_blockchain();
+		let fork = Header::new(
+			2,
+			H256::from_low_u64_be(1),
+			H256::from_low_u64_be(0),
+			header(1).hash(),
+			Default::default(),
+		);
+		blockchain.insert(fork.hash(), fork.clone(), None, None, NewBlockState::Normal).unwrap();
+
+		let mut children = vec![header(2).hash(), fork.hash()];
+		children.sort();
+		assert_eq!(blockchain.children(header(1).hash()).unwrap(), children);
+		assert_eq!(blockchain.children(header(3).hash()).unwrap(), vec![]);
+	}
 }
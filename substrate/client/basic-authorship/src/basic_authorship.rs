@@ -39,6 +39,7 @@ use sp_core::traits::SpawnNamed;
 use sp_inherents::InherentData;
 use sp_runtime::{
 	traits::{BlakeTwo256, Block as BlockT, Hash as HashT, Header as HeaderT},
+	transaction_validity::TransactionPriority,
 	ExtrinsicInclusionMode, Percent, SaturatedConversion,
 };
 use std::{pin::Pin, sync::Arc, time};
@@ -287,6 +288,7 @@ where
 			block_size_limit,
 			storage_proof_recorder,
 			extra_extensions,
+			min_transaction_priority,
 		} = args;
 		// leave some time for evaluation and block finalization (10%)
 		let deadline = (self.now)() + max_duration - max_duration / 10;
@@ -305,7 +307,13 @@ where
 		let mode = block_builder.extrinsic_inclusion_mode();
 		let end_reason = match mode {
 			ExtrinsicInclusionMode::AllExtrinsics =>
-				self.apply_extrinsics(&mut block_builder, deadline, block_size_limit).await?,
+				self.apply_extrinsics(
+					&mut block_builder,
+					deadline,
+					block_size_limit,
+					min_transaction_priority,
+				)
+				.await?,
 			ExtrinsicInclusionMode::OnlyInherents => EndProposingReason::TransactionForbidden,
 		};
 		let (block, storage_changes) = block_builder.build()?.into_inner();
@@ -375,6 +383,7 @@ where
 		block_builder: &mut sc_block_builder::BlockBuilder<'_, Block, C>,
 		deadline: time::Instant,
 		block_size_limit: Option<usize>,
+		min_transaction_priority: Option<TransactionPriority>,
 	) -> Result<EndProposingReason, sp_blockchain::Error> {
 		// proceed with transactions
 		// We calculate soft deadline used only in case we start skipping transactions.
@@ -384,6 +393,7 @@ where
 		let soft_deadline =
 			now + time::Duration::from_micros(self.soft_deadline_percent.mul_floor(left_micros));
 		let mut skipped = 0;
+		let mut deferred = 0;
 		let mut unqueue_invalid = TxInvalidityReportMap::new();
 		let mut limit_hit_reason: Option<EndProposingReason> = None;
 
@@ -418,6 +428,13 @@ where
 				break limit_hit_reason.unwrap_or(EndProposingReason::HitDeadline)
 			}
 
+			if min_transaction_priority.is_some_and(|min| *pending_tx.priority() < min) {
+				// Skip the transaction and the ones depending on it, they stay in the pool.
+				pending_iterator.report_invalid(&pending_tx);
+				deferred += 1;
+				continue
+			}
+
 			let pending_tx_data = (**pending_tx.data()).clone();
 			let pending_tx_hash = pending_tx.hash().clone();
 
@@ -504,6 +521,15 @@ where
 			);
 		}
 
+		if deferred > 0 {
+			debug!(
+				target: LOG_TARGET,
+				"Deferred {} transactions with a priority below {:?}.",
+				deferred,
+				min_transaction_priority,
+			);
+		}
+
 		self.transaction_pool
 			.report_invalid(Some(self.parent_hash), unqueue_invalid)
 			.await;
@@ -1136,4 +1162,48 @@ mod tests {
 			"Not enough calls to current time, which indicates the test might have ended because of deadline, not soft deadline"
 		);
 	}
+	#[test]
+	fn should_defer_transactions_below_min_transaction_priority() {
+		let client = Arc::new(substrate_test_runtime_client::new());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let txpool = Arc::from(BasicPool::new_full(
+			Default::default(),
+			true.into(),
+			None,
+			spawner.clone(),
+			client.clone(),
+		));
+		let genesis_hash = client.info().genesis_hash;
+		let genesis_header = client.expect_header(genesis_hash).expect("there should be header");
+
+		let extrinsics = [
+			(Sr25519Keyring::Alice, 10),
+			(Sr25519Keyring::Bob, 100),
+			(Sr25519Keyring::Charlie, 1000),
+		]
+		.into_iter()
+		.map(|(signer, priority)| {
+			ExtrinsicBuilder::new_call_with_priority(priority).signer(signer.pair()).build()
+		})
+		.collect::<Vec<_>>();
+
+		block_on(txpool.submit_at(genesis_hash, SOURCE, extrinsics)).unwrap();
+		block_on(txpool.maintain(chain_event(genesis_header.clone())));
+
+		let mut proposer_factory =
+			ProposerFactory::new(spawner.clone(), client.clone(), txpool.clone(), None, None);
+		let proposer = block_on(proposer_factory.init(&genesis_header)).unwrap();
+
+		let block = block_on(proposer.propose_block(ProposeArgs {
+			max_duration: time::Duration::from_secs(300),
+			min_transaction_priority: Some(100),
+			..Default::default()
+		}))
+		.map(|r| r.block)
+		.unwrap();
+
+		// The transaction with the lowest priority is deferred, but stays in the pool.
+		assert_eq!(block.extrinsics().len(), 2);
+		assert_eq!(txpool.ready().count(), 3);
+	}
 }
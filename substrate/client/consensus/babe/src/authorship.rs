@@ -34,7 +34,7 @@ use sp_keystore::KeystorePtr;
 
 /// Calculates the primary selection threshold for a given authority, taking
 /// into account `c` (`1 - c` represents the probability of a slot being empty).
-pub(super) fn calculate_primary_threshold(
+pub fn calculate_primary_threshold(
 	c: (u64, u64),
 	authorities: &[(AuthorityId, BabeAuthorityWeight)],
 	authority_index: usize,
@@ -99,7 +99,7 @@ pub(super) fn calculate_primary_threshold(
 /// Get the expected secondary author for the given slot and with given
 /// authorities. This should always assign the slot to some authority unless the
 /// authorities list is empty.
-pub(super) fn secondary_slot_author(
+pub fn secondary_slot_author(
 	slot: Slot,
 	authorities: &[(AuthorityId, BabeAuthorityWeight)],
 	randomness: Randomness,
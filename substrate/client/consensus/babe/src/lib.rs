@@ -153,10 +153,10 @@ mod tests;
 const LOG_TARGET: &str = "babe";
 
 /// VRF context used for slots claiming lottery.
-const AUTHORING_SCORE_VRF_CONTEXT: &[u8] = b"substrate-babe-vrf";
+pub const AUTHORING_SCORE_VRF_CONTEXT: &[u8] = b"substrate-babe-vrf";
 
 /// VRF output length for slots claiming lottery.
-const AUTHORING_SCORE_LENGTH: usize = 16;
+pub const AUTHORING_SCORE_LENGTH: usize = 16;
 
 /// BABE epoch information
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
@@ -615,6 +615,7 @@ where
 				block_size_limit: None,
 				storage_proof_recorder: None,
 				extra_extensions: Default::default(),
+				min_transaction_priority: None,
 			};
 
 			let proposal = match proposer.propose(propose_args).await {
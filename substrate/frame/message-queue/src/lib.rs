@@ -205,7 +205,7 @@ pub mod weights;
 
 extern crate alloc;
 
-use alloc::{vec, vec::Vec};
+use alloc::{collections::BTreeSet, vec, vec::Vec};
 use codec::{Codec, ConstEncodedLen, Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
 use core::{fmt::Debug, ops::Deref};
 use frame_support::{
@@ -214,7 +214,7 @@ use frame_support::{
 	traits::{
 		BatchesFootprints, Defensive, DefensiveSaturating, DefensiveTruncateFrom, EnqueueMessage,
 		ExecuteOverweightError, Footprint, ProcessMessage, ProcessMessageError, QueueFootprint,
-		QueueFootprintQuery, QueuePausedQuery, ServiceQueues,
+		QueueFootprintQuery, QueueInspect, QueuePausedQuery, ServiceQueues, TransformOrigin,
 	},
 	BoundedSlice, CloneNoBound, DefaultNoBound,
 };
@@ -224,7 +224,7 @@ use scale_info::TypeInfo;
 use sp_arithmetic::traits::{BaseArithmetic, Unsigned};
 use sp_core::{defer, H256};
 use sp_runtime::{
-	traits::{One, Zero},
+	traits::{Convert, One, Zero},
 	SaturatedConversion, Saturating, TransactionOutcome,
 };
 use sp_weights::WeightMeter;
@@ -418,6 +418,22 @@ where
 	fn is_complete(&self) -> bool {
 		self.remaining.is_zero()
 	}
+
+	/// Return the indices of the unprocessed messages before the heap position `end`.
+	fn unprocessed_indices_before(&self, end: usize) -> Vec<u32> {
+		let header_len: usize = ItemHeader::<Size>::max_encoded_len().saturated_into();
+		let end = end.min(self.heap.len());
+		let (mut pos, mut index, mut indices) = (0usize, 0u32, Vec::new());
+		while pos < end {
+			let Ok(h) = ItemHeader::<Size>::decode(&mut &self.heap[pos..]) else { break };
+			if !h.is_processed {
+				indices.push(index);
+			}
+			pos.saturating_accrue(header_len.saturating_add(h.payload_len.into() as usize));
+			index.saturating_inc();
+		}
+		indices
+	}
 }
 
 /// A single link in the double-linked Ready Ring list.
@@ -492,6 +508,18 @@ pub trait ForceSetHead<O> {
 	fn force_set_head(weight: &mut WeightMeter, origin: &O) -> Result<bool, ()>;
 }
 
+/// Allows to service the queue of a specific origin.
+pub trait ServiceQueue<O> {
+	/// Services the queue of `origin`, regardless of the `ServiceHead`.
+	///
+	/// - `weight_limit`: The maximum amount of dynamic weight that this call can use.
+	///
+	/// Returns the dynamic weight used by this call; is never greater than `weight_limit`. Should
+	/// only be called in top-level runtime entry points like `on_initialize`, like
+	/// [`ServiceQueues::service_queues`].
+	fn service_queue(weight_limit: Weight, origin: &O) -> Weight;
+}
+
 #[frame_support::pallet]
 pub mod pallet {
 	use super::*;
@@ -1683,6 +1711,29 @@ impl<T: Config> ForceSetHead<MessageOriginOf<T>> for Pallet<T> {
 	}
 }
 
+impl<T: Config> ServiceQueue<MessageOriginOf<T>> for Pallet<T> {
+	fn service_queue(weight_limit: Weight, origin: &MessageOriginOf<T>) -> Weight {
+		let mut weight = WeightMeter::with_limit(weight_limit);
+		let Some(overweight_limit) = Self::max_message_weight(weight_limit) else {
+			return weight.consumed()
+		};
+
+		let _ = with_service_mutex(|| {
+			Pallet::<T>::service_queue(origin.clone(), &mut weight, overweight_limit)
+		});
+		weight.consumed()
+	}
+}
+
+/// Service the queue of an [`EnqueueMessage`] with a transformed origin via `C::convert`.
+impl<E: ServiceQueue<O>, O, N: Clone, C: Convert<N, O>> ServiceQueue<N>
+	for TransformOrigin<E, O, N, C>
+{
+	fn service_queue(weight_limit: Weight, origin: &N) -> Weight {
+		E::service_queue(weight_limit, &C::convert(origin.clone()))
+	}
+}
+
 /// Run a closure that errors on re-entrance. Meant to be used by anything that services queues.
 pub(crate) fn with_service_mutex<F: FnOnce() -> R, R>(f: F) -> Result<R, ()> {
 	// Holds the singleton token instance.
@@ -1874,3 +1925,45 @@ impl<T: Config> QueueFootprintQuery<MessageOriginOf<T>> for Pallet<T> {
 		BookStateFor::<T>::get(&origin).into()
 	}
 }
+
+impl<T: Config> QueueInspect<MessageOriginOf<T>> for Pallet<T> {
+	fn ready_queues() -> Vec<MessageOriginOf<T>> {
+		let mut queues = Vec::new();
+		// Origins are not `Ord`, so the visited set is keyed by their encoding.
+		let mut visited = BTreeSet::new();
+		let mut next = ServiceHead::<T>::get();
+		while let Some(origin) = next.take() {
+			let Some(neighbours) = BookStateFor::<T>::get(&origin).ready_neighbours else {
+				defensive!("Queues in the ready ring must have neighbours");
+				break
+			};
+			visited.insert(origin.encode());
+			queues.push(origin);
+			if !visited.contains(&neighbours.next.encode()) {
+				next = Some(neighbours.next);
+			}
+		}
+		queues
+	}
+
+	fn overweight_messages(origin: MessageOriginOf<T>) -> Vec<(u32, u32)> {
+		let book = BookStateFor::<T>::get(&origin);
+		// Overweight messages were skipped, so they are before the first message ready to be
+		// processed.
+		let mut messages = Pages::<T>::iter_prefix(&origin)
+			.filter(|(page_index, _)| *page_index <= book.begin)
+			.flat_map(|(page_index, page)| {
+				let end = if page_index < book.begin {
+					page.heap.len()
+				} else {
+					page.first.into() as usize
+				};
+				page.unprocessed_indices_before(end)
+					.into_iter()
+					.map(move |index| (page_index, index))
+			})
+			.collect::<Vec<_>>();
+		messages.sort();
+		messages
+	}
+}
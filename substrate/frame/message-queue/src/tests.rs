@@ -1155,6 +1155,48 @@ fn execute_overweight_works() {
 	});
 }
 
+#[test]
+fn queue_inspect_works() {
+	use MessageOrigin::*;
+	build_and_execute::<Test>(|| {
+		set_weight("bump_service_head", 1.into_weight());
+		set_weight("service_queue_base", 1.into_weight());
+		set_weight("service_page_base_completion", 1.into_weight());
+		assert!(<MessageQueue as QueueInspect<_>>::ready_queues().is_empty());
+
+		MessageQueue::enqueue_message(msg("weight=200"), Here);
+		MessageQueue::enqueue_message(msg("a"), Everywhere(1));
+		MessageQueue::enqueue_message(msg("b"), Everywhere(2));
+		assert_eq!(
+			<MessageQueue as QueueInspect<_>>::ready_queues(),
+			vec![Here, Everywhere(1), Everywhere(2)]
+		);
+		assert!(MessageQueue::overweight_messages(Here).is_empty());
+
+		// Mark the first message as permanently overweight.
+		MessageQueue::service_queues(4.into_weight());
+		assert_eq!(MessageQueue::overweight_messages(Here), vec![(0, 0)]);
+		assert_eq!(
+			<MessageQueue as QueueInspect<_>>::ready_queues(),
+			vec![Everywhere(1), Everywhere(2)]
+		);
+
+		// Messages enqueued later are ready, unlike the overweight message.
+		MessageQueue::enqueue_message(msg("c"), Here);
+		assert_eq!(MessageQueue::overweight_messages(Here), vec![(0, 0)]);
+		assert_eq!(
+			<MessageQueue as QueueInspect<_>>::ready_queues(),
+			vec![Everywhere(1), Everywhere(2), Here]
+		);
+
+		assert_ok!(<MessageQueue as ServiceQueues>::execute_overweight(
+			200.into_weight(),
+			(Here, 0, 0)
+		));
+		assert!(MessageQueue::overweight_messages(Here).is_empty());
+	});
+}
+
 #[test]
 fn permanently_overweight_book_unknits() {
 	use MessageOrigin::*;
@@ -2142,6 +2184,34 @@ fn force_set_head_works() {
 	});
 }
 
+#[test]
+fn service_queue_only_services_the_given_queue() {
+	use MessageOrigin::*;
+	build_and_execute::<Test>(|| {
+		for _ in 0..3 {
+			MessageQueue::enqueue_message(msg("A"), Here);
+			MessageQueue::enqueue_message(msg("B"), There);
+		}
+		assert_ring(&[Here, There]);
+
+		let consumed = <MessageQueue as ServiceQueue<_>>::service_queue(2.into_weight(), &There);
+		assert_eq!(consumed, 2.into_weight());
+		assert_eq!(MessagesProcessed::take(), vec![(b"B".to_vec(), There), (b"B".to_vec(), There)]);
+		// The service head is untouched.
+		assert_eq!(ServiceHead::<Test>::get(), Some(Here));
+
+		// The queue is unknit from the ready ring once empty.
+		<MessageQueue as ServiceQueue<_>>::service_queue(10.into_weight(), &There);
+		assert_eq!(MessagesProcessed::take(), vec![(b"B".to_vec(), There)]);
+		assert_ring(&[Here]);
+
+		// Nothing happens for empty queues.
+		let _guard = StorageNoopGuard::new();
+		<MessageQueue as ServiceQueue<_>>::service_queue(10.into_weight(), &There);
+		assert!(MessagesProcessed::get().is_empty());
+	});
+}
+
 fn check_get_batches_footprints(
 	origin: MessageOrigin,
 	sizes: &[u32],
@@ -186,6 +186,35 @@ impl<T: frame_system::Config> cumulus_pallet_xcmp_queue::WeightInfo for WeightIn
 			.saturating_add(T::DbWeight::get().reads(1_u64))
 			.saturating_add(T::DbWeight::get().writes(1_u64))
 	}
+	/// Storage: `XcmpQueue::InboundChannelSchedules` (r:1 w:1)
+	/// Proof: `XcmpQueue::InboundChannelSchedules` (`max_values`: Some(1), `max_size`: Some(373), added: 868, mode: `MaxEncodedLen`)
+	fn set_channel_schedule() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `472`
+		//  Estimated: `1858`
+		// Minimum execution time: 5_561_000 picoseconds.
+		Weight::from_parts(6_749_000, 1858)
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+	/// Storage: `XcmpQueue::InboundChannelSchedules` (r:1 w:0)
+	/// Proof: `XcmpQueue::InboundChannelSchedules` (`max_values`: Some(1), `max_size`: Some(373), added: 868, mode: `MaxEncodedLen`)
+	/// Storage: `MessageQueue::BookStateFor` (r:16 w:0)
+	/// Proof: `MessageQueue::BookStateFor` (`max_values`: None, `max_size`: Some(52), added: 2527, mode: `MaxEncodedLen`)
+	/// The range of component `c` is `[0, 16]`.
+	fn service_scheduled_channels(c: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `142 + c * (97 ±0)`
+		//  Estimated: `1858 + c * (2527 ±0)`
+		// Minimum execution time: 2_910_000 picoseconds.
+		Weight::from_parts(3_268_504, 0)
+			.saturating_add(Weight::from_parts(0, 1858))
+			// Standard Error: 2_341
+			.saturating_add(Weight::from_parts(1_903_177, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 2527).saturating_mul(c.into()))
+	}
 	/// The range of component `n` is `[0, 92]`.
 	fn take_first_concatenated_xcm(n: u32, ) -> Weight {
 		// Proof Size summary in bytes:
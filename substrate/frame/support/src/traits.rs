@@ -121,7 +121,7 @@ mod messages;
 pub use messages::{
 	BatchFootprint, BatchesFootprints, EnqueueMessage, EnqueueWithOrigin, ExecuteOverweightError,
 	HandleMessage, NoopServiceQueues, ProcessMessage, ProcessMessageError, QueueFootprint,
-	QueueFootprintQuery, QueuePausedQuery, ServiceQueues, TransformOrigin,
+	QueueFootprintQuery, QueueInspect, QueuePausedQuery, ServiceQueues, TransformOrigin,
 };
 
 mod safe_mode;
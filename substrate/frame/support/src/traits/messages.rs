@@ -298,6 +298,33 @@ impl<Origin: MaxEncodedLen> QueueFootprintQuery<Origin> for () {
 	}
 }
 
+/// Provides insight into the order queues are serviced in and into their overweight messages.
+///
+/// Both methods iterate storage without a bound (the whole ready ring, respectively every page
+/// of a queue) and must therefore only be called from runtime APIs, never from extrinsics or
+/// hooks.
+pub trait QueueInspect<Origin> {
+	/// Return the origins of the queues with messages ready to be serviced, in the order they will
+	/// be serviced in.
+	fn ready_queues() -> Vec<Origin>;
+
+	/// Return the overweight messages of the given queue as `(page, index)` pairs.
+	///
+	/// Overweight messages are skipped when servicing the queue and stay in it until executed
+	/// explicitly.
+	fn overweight_messages(origin: Origin) -> Vec<(u32, u32)>;
+}
+
+impl<Origin> QueueInspect<Origin> for () {
+	fn ready_queues() -> Vec<Origin> {
+		Vec::new()
+	}
+
+	fn overweight_messages(_: Origin) -> Vec<(u32, u32)> {
+		Vec::new()
+	}
+}
+
 /// Transform the origin of an [`EnqueueMessage`] via `C::convert`.
 pub struct TransformOrigin<E, O, N, C>(PhantomData<(E, O, N, C)>);
 impl<E: EnqueueMessage<O>, O: MaxEncodedLen, N: MaxEncodedLen, C: Convert<N, O>> EnqueueMessage<N>
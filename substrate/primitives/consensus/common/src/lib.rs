@@ -28,6 +28,7 @@ use sp_api::ProofRecorder;
 use sp_externalities::Extensions;
 use sp_runtime::{
 	traits::{Block as BlockT, HashingFor},
+	transaction_validity::TransactionPriority,
 	Digest,
 };
 
@@ -118,6 +119,11 @@ pub struct ProposeArgs<B: BlockT> {
 	pub storage_proof_recorder: Option<ProofRecorder<B>>,
 	/// Extra extensions for the runtime environment.
 	pub extra_extensions: Extensions,
+	/// Optional minimum priority of the transactions to include in the produced block.
+	///
+	/// When set, transactions with a lower priority are left in the transaction pool for a later
+	/// block.
+	pub min_transaction_priority: Option<TransactionPriority>,
 }
 
 impl<B: BlockT> Default for ProposeArgs<B> {
@@ -129,6 +135,7 @@ impl<B: BlockT> Default for ProposeArgs<B> {
 			block_size_limit: Default::default(),
 			storage_proof_recorder: Default::default(),
 			extra_extensions: Default::default(),
+			min_transaction_priority: Default::default(),
 		}
 	}
 }
@@ -0,0 +1,654 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Migration of the state of a solochain to the genesis of a parachain.
+//!
+//! The [`SoloToParaCmd`] is meant to be added to the CLI of solochain nodes, as it reads the state
+//! from their database. The produced chain spec starts the parachain from the state of the
+//! solochain at the given block, with:
+//!
+//! - the code replaced by the parachain runtime,
+//! - the storage of the solochain consensus pallets (BABE, GRANDPA, ...) removed,
+//! - the session keys rewritten to only hold the Aura keys of the collators,
+//! - the Aura authorities, the invulnerable collators and the parachain id set,
+//! - the pallets of the parachain runtime without storage on the solochain, e.g. `ParachainSystem`,
+//!   initialized from the default genesis of the parachain runtime, including their
+//!   `:__STORAGE_VERSION__:`.
+//!
+//! Everything else is kept as is. The accompanying checklist lists what was done to the storage
+//! of every pallet, and the pallets that are left to the operator to drop or migrate, e.g. the
+//! ones missing from the parachain runtime.
+
+use clap::Parser;
+use codec::{Decode, Encode};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use sc_chain_spec::{ChainSpec, GenesisConfigBuilderRuntimeCaller};
+use sc_cli::{BlockNumberOrHash, DatabaseParams, PruningParams, SharedParams};
+use sc_client_api::{backend::Backend, HeaderBackend, StorageProvider, UsageProvider};
+use sc_executor::WasmExecutor;
+use sp_api::{Metadata, ProvideRuntimeApi};
+use sp_core::{
+	blake2_256,
+	crypto::{AccountId32, KeyTypeId},
+	hexdisplay::HexDisplay,
+	storage::{well_known_keys, Storage},
+	traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode},
+	twox_128, twox_64, OpaqueMetadata,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_state_machine::BasicExternalities;
+use std::{
+	collections::BTreeMap,
+	fmt::{self, Debug, Display},
+	fs,
+	io::{self, Write},
+	path::PathBuf,
+	str::FromStr,
+	sync::Arc,
+};
+
+/// Pallets of the solochain consensus, which are replaced by Aura on a parachain.
+const CONSENSUS_PALLETS: &[&str] = &[
+	"AuthorityDiscovery",
+	"Authorship",
+	"Babe",
+	"Beefy",
+	"BeefyMmrLeaf",
+	"Grandpa",
+	"Historical",
+	"ImOnline",
+	"Mmr",
+	"MmrLeaf",
+	"Offences",
+];
+
+/// Pallets managing the validators of the solochain, which have no effect on a parachain.
+const VALIDATOR_SET_PALLETS: &[&str] = &[
+	"DelegatedStaking",
+	"ElectionProviderMultiPhase",
+	"FastUnstake",
+	"Mixnet",
+	"NominationPools",
+	"Staking",
+	"VoterList",
+];
+
+/// The storage key of the GRANDPA authorities.
+const GRANDPA_AUTHORITIES_KEY: &[u8] = b":grandpa_authorities";
+
+/// The key type of Aura keys.
+const AURA_KEY_TYPE: KeyTypeId = KeyTypeId(*b"aura");
+
+/// A collator of the parachain.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Collator {
+	/// The account of the collator, used as its validator id.
+	pub account: AccountId32,
+	/// The Aura key of the collator.
+	pub aura_key: [u8; 32],
+}
+
+impl FromStr for Collator {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (account, aura_key) = s
+			.split_once('=')
+			.ok_or_else(|| format!("Expected `ACCOUNT=AURA_KEY`, got `{s}`"))?;
+
+		Ok(Self {
+			account: AccountId32::from_str(account).map_err(|e| format!("{e}: `{account}`"))?,
+			aura_key: AccountId32::from_str(aura_key)
+				.map_err(|e| format!("{e}: `{aura_key}`"))?
+				.into(),
+		})
+	}
+}
+
+/// The `solo-to-para` command used to turn the state of a solochain at a given block into the chain
+/// spec of a parachain.
+#[derive(Debug, Clone, Parser)]
+pub struct SoloToParaCmd {
+	/// Block hash or number. Defaults to the best block.
+	#[arg(value_name = "HASH or NUMBER")]
+	pub input: Option<BlockNumberOrHash>,
+
+	/// The id of the parachain.
+	#[arg(long)]
+	pub para_id: u32,
+
+	/// The id of the relay chain spec the parachain is registered on.
+	#[arg(long)]
+	pub relay_chain: String,
+
+	/// The wasm blob of the parachain runtime.
+	///
+	/// The pallets of the runtime without storage on the solochain are initialized from its
+	/// default genesis.
+	#[arg(long, value_name = "PATH")]
+	pub runtime: PathBuf,
+
+	/// The initial collators of the parachain, given by their account and Aura key, in hex or
+	/// SS58.
+	#[arg(long = "collator", value_name = "ACCOUNT=AURA_KEY", required = true)]
+	pub collators: Vec<Collator>,
+
+	/// Additional pallets whose storage is removed.
+	#[arg(long = "drop-pallet", value_name = "PALLET")]
+	pub drop_pallets: Vec<String>,
+
+	/// Consensus pallets whose storage is kept, although removed by default.
+	#[arg(long = "keep-pallet", value_name = "PALLET")]
+	pub keep_pallets: Vec<String>,
+
+	/// Output file of the chain spec, or stdout if unspecified.
+	#[arg(long, value_name = "PATH")]
+	pub output: Option<PathBuf>,
+
+	/// Output file of the migration checklist, or stderr if unspecified.
+	#[arg(long, value_name = "PATH")]
+	pub checklist: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub pruning_params: PruningParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: DatabaseParams,
+}
+
+impl SoloToParaCmd {
+	/// Run the `solo-to-para` command.
+	pub async fn run<B, BA, C>(
+		&self,
+		client: Arc<C>,
+		mut input_spec: Box<dyn ChainSpec>,
+	) -> sc_cli::Result<()>
+	where
+		B: BlockT,
+		C: UsageProvider<B> + StorageProvider<B, BA> + HeaderBackend<B> + ProvideRuntimeApi<B>,
+		C::Api: Metadata<B>,
+		BA: Backend<B>,
+		<B::Hash as FromStr>::Err: Debug,
+		<<B::Header as HeaderT>::Number as FromStr>::Err: Debug,
+	{
+		let block_id = self.input.as_ref().map(|b| b.parse()).transpose()?;
+		let hash = match block_id {
+			Some(id) => client.expect_block_hash_from_id(&id)?,
+			None => client.usage_info().chain.best_hash,
+		};
+
+		let metadata = client
+			.runtime_api()
+			.metadata(hash)
+			.map_err(|e| format!("Failed to fetch the runtime metadata: {e}"))?;
+		let pallets = pallets_from_metadata(&metadata)?;
+
+		let parachain = ParachainRuntime::new(fs::read(&self.runtime)?)?;
+		let mut storage = sc_service::chain_ops::export_raw_state(client, hash)?;
+		let checklist = self.migrate(&mut storage, &pallets, &parachain);
+		input_spec.set_storage(storage);
+
+		let json = sc_service::chain_ops::build_spec(&*input_spec, true)?;
+		let mut spec = serde_json::from_str::<serde_json::Value>(&json)
+			.map_err(|e| format!("Failed to parse the chain spec: {e}"))?;
+		// The chain spec extensions of parachains.
+		spec["relay_chain"] = self.relay_chain.clone().into();
+		spec["para_id"] = self.para_id.into();
+		let json = serde_json::to_string_pretty(&spec)
+			.map_err(|e| format!("Failed to serialize the chain spec: {e}"))?;
+
+		match &self.output {
+			Some(output) => fs::write(output, json)?,
+			None => io::stdout().write_all(json.as_bytes())?,
+		}
+		match &self.checklist {
+			Some(path) => fs::write(path, checklist.to_string())?,
+			None => io::stderr().write_all(checklist.to_string().as_bytes())?,
+		}
+
+		Ok(())
+	}
+
+	/// Migrates the solochain `storage` to the genesis storage of the parachain.
+	fn migrate(
+		&self,
+		storage: &mut Storage,
+		pallets: &[PalletStorage],
+		parachain: &ParachainRuntime,
+	) -> Checklist {
+		let mut checklist = Checklist::default();
+
+		storage.top.insert(well_known_keys::CODE.to_vec(), parachain.code.clone());
+		storage.top.remove(GRANDPA_AUTHORITIES_KEY);
+
+		for pallet in pallets {
+			let prefix = twox_128(pallet.prefix.as_bytes());
+			let keys = storage.top.keys().filter(|key| key.starts_with(&prefix)).count();
+			if keys == 0 {
+				continue
+			}
+
+			let is_consensus = CONSENSUS_PALLETS.contains(&pallet.name.as_str());
+			let is_dropped = (is_consensus || self.drop_pallets.contains(&pallet.name)) &&
+				!self.keep_pallets.contains(&pallet.name);
+
+			let (action, note) = if is_dropped {
+				storage.top.retain(|key, _| !key.starts_with(&prefix));
+				(
+					Action::Dropped,
+					if is_consensus { "Replaced by Aura on the parachain." } else { "" },
+				)
+			} else if pallet.name == "Session" {
+				self.rewrite_session_keys(storage, &pallet.prefix);
+				(
+					Action::Rewritten,
+					"The session keys of the collators only hold their Aura key, and their validator \
+					 id is their account.",
+				)
+			} else if VALIDATOR_SET_PALLETS.contains(&pallet.name.as_str()) {
+				(Action::Review, "The validators of the solochain have no effect on a parachain.")
+			} else if !parachain.has_pallet(&pallet.prefix) {
+				(Action::Review, "The pallet isn't in the parachain runtime.")
+			} else {
+				(Action::Kept, "")
+			};
+
+			checklist.0.push(ChecklistEntry {
+				pallet: pallet.name.clone(),
+				action,
+				keys,
+				note: note.into(),
+			});
+		}
+
+		let known = pallets
+			.iter()
+			.map(|pallet| twox_128(pallet.prefix.as_bytes()))
+			.collect::<Vec<_>>();
+		let mut unknown = BTreeMap::<[u8; 16], usize>::new();
+		for key in storage.top.keys().filter(|key| !key.starts_with(b":")) {
+			let prefix = key.get(..16).and_then(|prefix| <[u8; 16]>::try_from(prefix).ok());
+			if let Some(prefix) = prefix.filter(|prefix| !known.contains(prefix)) {
+				*unknown.entry(prefix).or_default() += 1;
+			}
+		}
+		for (prefix, keys) in unknown {
+			checklist.0.push(ChecklistEntry {
+				pallet: format!("0x{}", HexDisplay::from(&prefix)),
+				action: Action::Review,
+				keys,
+				note: "The storage prefix isn't in the metadata of the runtime.".into(),
+			});
+		}
+
+		// The pallets of the parachain runtime without storage, e.g. because they are missing from
+		// the solochain, start from their genesis as on a new chain.
+		for pallet in &parachain.pallets {
+			let prefix = twox_128(pallet.prefix.as_bytes());
+			if storage.top.keys().any(|key| key.starts_with(&prefix)) {
+				continue
+			}
+			let genesis = parachain
+				.genesis
+				.top
+				.iter()
+				.filter(|(key, _)| key.starts_with(&prefix))
+				.map(|(key, value)| (key.clone(), value.clone()))
+				.collect::<Vec<_>>();
+			if genesis.is_empty() {
+				continue
+			}
+
+			checklist.0.push(ChecklistEntry {
+				pallet: pallet.name.clone(),
+				action: Action::Initialized,
+				keys: genesis.len(),
+				note: String::new(),
+			});
+			storage.top.extend(genesis);
+		}
+
+		let aura_keys = self.collators.iter().map(|collator| collator.aura_key).collect::<Vec<_>>();
+		let accounts = self.collators.iter().map(|collator| &collator.account).collect::<Vec<_>>();
+		let added = [
+			(("Aura", "Authorities"), aura_keys.encode()),
+			(("AuraExt", "Authorities"), aura_keys.encode()),
+			(("CollatorSelection", "Invulnerables"), accounts.encode()),
+			(("ParachainInfo", "ParachainId"), self.para_id.encode()),
+		];
+		for ((pallet, item), value) in added {
+			storage.top.insert(storage_key(pallet, item), value);
+			checklist.0.push(ChecklistEntry {
+				pallet: format!("{pallet}::{item}"),
+				action: Action::Added,
+				keys: 1,
+				note: String::new(),
+			});
+		}
+
+		checklist
+	}
+
+	/// Replaces the session keys of the solochain validators by the Aura keys of the collators.
+	fn rewrite_session_keys(&self, storage: &mut Storage, prefix: &str) {
+		let current_index = storage_key(prefix, "CurrentIndex");
+		let session_prefix = twox_128(prefix.as_bytes());
+		storage
+			.top
+			.retain(|key, _| !key.starts_with(&session_prefix) || *key == current_index);
+
+		let accounts = self.collators.iter().map(|collator| &collator.account).collect::<Vec<_>>();
+		let queued_keys = self
+			.collators
+			.iter()
+			.map(|collator| (&collator.account, collator.aura_key))
+			.collect::<Vec<_>>();
+		storage.top.insert(storage_key(prefix, "Validators"), accounts.encode());
+		storage.top.insert(storage_key(prefix, "QueuedKeys"), queued_keys.encode());
+
+		for collator in &self.collators {
+			let mut next_keys = storage_key(prefix, "NextKeys");
+			next_keys.extend(twox_64_concat(&collator.account.encode()));
+			storage.top.insert(next_keys, collator.aura_key.encode());
+
+			let mut key_owner = storage_key(prefix, "KeyOwner");
+			key_owner.extend(twox_64_concat(&(AURA_KEY_TYPE, collator.aura_key.to_vec()).encode()));
+			storage.top.insert(key_owner, collator.account.encode());
+		}
+	}
+}
+
+impl sc_cli::CliConfiguration for SoloToParaCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn pruning_params(&self) -> Option<&PruningParams> {
+		Some(&self.pruning_params)
+	}
+
+	fn database_params(&self) -> Option<&DatabaseParams> {
+		Some(&self.database_params)
+	}
+}
+
+/// What the migration did to the storage of a pallet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+	/// The storage was kept as is.
+	Kept,
+	/// The storage was kept, but should be dropped or migrated by the operator.
+	Review,
+	/// The storage was removed.
+	Dropped,
+	/// The storage was rewritten.
+	Rewritten,
+	/// The storage was written for the parachain.
+	Added,
+	/// The storage was initialized from the genesis of the parachain runtime.
+	Initialized,
+}
+
+/// An entry of the [`Checklist`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChecklistEntry {
+	/// The name of the pallet or storage item, or the hex storage prefix of unknown pallets.
+	pub pallet: String,
+	/// What the migration did to the storage.
+	pub action: Action,
+	/// The number of storage keys of the solochain concerned.
+	pub keys: usize,
+	/// What the operator should know about the action.
+	pub note: String,
+}
+
+/// The pallet storage migrated by the [`SoloToParaCmd`], and what is left to the operator.
+///
+/// Displayed as a markdown checklist, in which only the entries to review are left unchecked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Checklist(pub Vec<ChecklistEntry>);
+
+impl Display for Checklist {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		writeln!(f, "# Solo to para migration checklist")?;
+		writeln!(f)?;
+		for entry in &self.0 {
+			let (checked, action) = match entry.action {
+				Action::Kept => ('x', "kept"),
+				Action::Review => (' ', "kept, to drop or migrate"),
+				Action::Dropped => ('x', "dropped"),
+				Action::Rewritten => ('x', "rewritten"),
+				Action::Added => ('x', "added"),
+				Action::Initialized => ('x', "initialized from the parachain genesis"),
+			};
+			write!(f, "- [{checked}] `{}`: {action} ({} keys)", entry.pallet, entry.keys)?;
+			if !entry.note.is_empty() {
+				write!(f, ". {}", entry.note)?;
+			}
+			writeln!(f)?;
+		}
+
+		Ok(())
+	}
+}
+
+/// The parachain runtime the solochain is migrated to.
+struct ParachainRuntime {
+	code: Vec<u8>,
+	pallets: Vec<PalletStorage>,
+	/// The storage built from the default genesis config of the runtime.
+	genesis: Storage,
+}
+
+impl ParachainRuntime {
+	/// Reads the pallets of the runtime `code` from its metadata, and builds its default genesis.
+	fn new(code: Vec<u8>) -> sc_cli::Result<Self> {
+		let executor = WasmExecutor::<sp_io::SubstrateHostFunctions>::builder()
+			.with_allow_missing_host_functions(true)
+			.build();
+		let code_fetcher = WrappedRuntimeCode(code.as_slice().into());
+		let runtime_code = RuntimeCode {
+			code_fetcher: &code_fetcher,
+			heap_pages: None,
+			hash: blake2_256(&code).to_vec(),
+		};
+		let metadata = executor
+			.call(
+				&mut BasicExternalities::new_empty(),
+				&runtime_code,
+				"Metadata_metadata",
+				&[],
+				CallContext::Offchain,
+			)
+			.0
+			.map_err(|e| format!("Failed to fetch the metadata of the parachain runtime: {e}"))?;
+		let metadata = OpaqueMetadata::decode(&mut &metadata[..])
+			.map_err(|e| format!("Failed to decode the metadata of the parachain runtime: {e}"))?;
+		let pallets = pallets_from_metadata(&metadata)?;
+
+		let genesis = {
+			let builder = GenesisConfigBuilderRuntimeCaller::<()>::new(&code);
+			builder
+				.get_default_config()
+				.and_then(|config| builder.get_storage_for_config(config))
+				.map_err(|e| format!("Failed to build the genesis of the parachain runtime: {e}"))?
+		};
+
+		Ok(Self { code, pallets, genesis })
+	}
+
+	fn has_pallet(&self, prefix: &str) -> bool {
+		self.pallets.iter().any(|pallet| pallet.prefix == prefix)
+	}
+}
+
+/// The storage of a pallet, as described by the runtime metadata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PalletStorage {
+	name: String,
+	prefix: String,
+}
+
+/// Collects the pallets from metadata of any version from 14 on, which only differ in their types.
+macro_rules! collect_pallets {
+	($metadata:ident) => {
+		$metadata
+			.pallets
+			.iter()
+			.filter_map(|pallet| {
+				let storage = pallet.storage.as_ref()?;
+				Some(PalletStorage { name: pallet.name.clone(), prefix: storage.prefix.clone() })
+			})
+			.collect()
+	};
+}
+
+fn pallets_from_metadata(encoded: &[u8]) -> sc_cli::Result<Vec<PalletStorage>> {
+	let metadata = RuntimeMetadataPrefixed::decode(&mut &encoded[..])
+		.map_err(|e| format!("Failed to decode the runtime metadata: {e}"))?;
+	match metadata.1 {
+		RuntimeMetadata::V14(metadata) => Ok(collect_pallets!(metadata)),
+		RuntimeMetadata::V15(metadata) => Ok(collect_pallets!(metadata)),
+		RuntimeMetadata::V16(metadata) => Ok(collect_pallets!(metadata)),
+		other => Err(format!("Unsupported metadata version {}", other.version()).into()),
+	}
+}
+
+fn storage_key(pallet: &str, item: &str) -> Vec<u8> {
+	[twox_128(pallet.as_bytes()), twox_128(item.as_bytes())].concat()
+}
+
+fn twox_64_concat(data: &[u8]) -> Vec<u8> {
+	twox_64(data).iter().chain(data).copied().collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn cmd() -> SoloToParaCmd {
+		SoloToParaCmd::parse_from([
+			"solo-to-para",
+			"--para-id",
+			"2000",
+			"--relay-chain",
+			"rococo-local",
+			"--runtime",
+			"parachain.wasm",
+			"--collator",
+			"5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY=\
+			 0x0101010101010101010101010101010101010101010101010101010101010101",
+			"--keep-pallet",
+			"Offences",
+		])
+	}
+
+	fn pallet(name: &str) -> PalletStorage {
+		PalletStorage { name: name.into(), prefix: name.into() }
+	}
+
+	#[test]
+	fn solochain_storage_is_migrated() {
+		let cmd = cmd();
+		let mut storage = Storage::default();
+		for (pallet, item) in [
+			("Babe", "Authorities"),
+			("Offences", "Reports"),
+			("Balances", "TotalIssuance"),
+			("Staking", "Validators"),
+			("Session", "CurrentIndex"),
+			("Session", "DisabledValidators"),
+		] {
+			storage.top.insert(storage_key(pallet, item), vec![1]);
+		}
+		storage.top.insert(storage_key("Unknown", "Item"), vec![1]);
+		storage.top.insert(GRANDPA_AUTHORITIES_KEY.to_vec(), vec![1]);
+
+		let mut genesis = Storage::default();
+		for (pallet, item) in [
+			("Balances", "TotalIssuance"),
+			("ParachainSystem", ":__STORAGE_VERSION__:"),
+			("ParachainSystem", "LastRelayChainBlockNumber"),
+		] {
+			genesis.top.insert(storage_key(pallet, item), vec![2]);
+		}
+		let parachain = ParachainRuntime {
+			code: vec![42],
+			pallets: ["Balances", "Session", "Staking", "ParachainSystem"].map(pallet).to_vec(),
+			genesis,
+		};
+
+		let pallets = ["Babe", "Offences", "Balances", "Staking", "Session"].map(pallet);
+		let checklist = cmd.migrate(&mut storage, &pallets, &parachain);
+
+		assert_eq!(storage.top[well_known_keys::CODE], vec![42]);
+		assert_eq!(storage.top[&storage_key("Balances", "TotalIssuance")], vec![1]);
+		assert_eq!(storage.top[&storage_key("ParachainSystem", ":__STORAGE_VERSION__:")], vec![2]);
+		assert!(!storage.top.contains_key(GRANDPA_AUTHORITIES_KEY));
+		assert!(!storage.top.contains_key(&storage_key("Babe", "Authorities")));
+		assert!(storage.top.contains_key(&storage_key("Offences", "Reports")));
+		assert!(storage.top.contains_key(&storage_key("Session", "CurrentIndex")));
+		assert!(!storage.top.contains_key(&storage_key("Session", "DisabledValidators")));
+		assert_eq!(storage.top[&storage_key("ParachainInfo", "ParachainId")], 2000u32.encode());
+		assert_eq!(storage.top[&storage_key("Aura", "Authorities")], vec![[1u8; 32]].encode());
+
+		let actions = checklist
+			.0
+			.iter()
+			.map(|entry| (entry.pallet.as_str(), entry.action))
+			.take(7)
+			.collect::<Vec<_>>();
+		assert_eq!(
+			actions,
+			vec![
+				("Babe", Action::Dropped),
+				("Offences", Action::Review),
+				("Balances", Action::Kept),
+				("Staking", Action::Review),
+				("Session", Action::Rewritten),
+				(format!("0x{}", HexDisplay::from(&twox_128(b"Unknown"))).as_str(), Action::Review),
+				("ParachainSystem", Action::Initialized),
+			],
+		);
+	}
+
+	#[test]
+	fn session_keys_are_rewritten() {
+		let cmd = cmd();
+		let collator = &cmd.collators[0];
+		let mut storage = Storage::default();
+		cmd.rewrite_session_keys(&mut storage, "Session");
+
+		let mut next_keys = storage_key("Session", "NextKeys");
+		next_keys.extend(twox_64_concat(collator.account.as_ref()));
+		assert_eq!(storage.top[&next_keys], collator.aura_key.to_vec());
+		assert_eq!(
+			storage.top[&storage_key("Session", "Validators")],
+			vec![collator.account.clone()].encode(),
+		);
+		assert_eq!(
+			storage.top[&storage_key("Session", "QueuedKeys")],
+			vec![(collator.account.clone(), collator.aura_key)].encode(),
+		);
+	}
+}
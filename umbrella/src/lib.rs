@@ -72,6 +72,10 @@ pub use bridge_hub_test_utils;
 #[cfg(feature = "bridge-runtime-common")]
 pub use bridge_runtime_common;
 
+/// Simulates the block production of the slot-based collator to tune elastic scaling.
+#[cfg(feature = "cumulus-client-block-production-simulator")]
+pub use cumulus_client_block_production_simulator;
+
 /// Parachain bootnodes registration and discovery.
 #[cfg(feature = "cumulus-client-bootnodes")]
 pub use cumulus_client_bootnodes;
@@ -84,6 +88,10 @@ pub use cumulus_client_cli;
 #[cfg(feature = "cumulus-client-collator")]
 pub use cumulus_client_collator;
 
+/// RPC for inspecting the blocks built by Cumulus collators.
+#[cfg(feature = "cumulus-client-collator-rpc")]
+pub use cumulus_client_collator_rpc;
+
 /// AURA consensus algorithm for parachains.
 #[cfg(feature = "cumulus-client-consensus-aura")]
 pub use cumulus_client_consensus_aura;
@@ -100,11 +108,19 @@ pub use cumulus_client_consensus_relay_chain;
 #[cfg(feature = "cumulus-client-network")]
 pub use cumulus_client_network;
 
+/// Places on-demand coretime orders on the relay chain for a parachain.
+#[cfg(feature = "cumulus-client-on-demand")]
+pub use cumulus_client_on_demand;
+
 /// Inherent that needs to be present in every parachain block. Contains messages and a relay
 /// chain storage-proof.
 #[cfg(feature = "cumulus-client-parachain-inherent")]
 pub use cumulus_client_parachain_inherent;
 
+/// Analyzes what the PoV of parachain blocks is made of.
+#[cfg(feature = "cumulus-client-pov-analyzer")]
+pub use cumulus_client_pov_analyzer;
+
 /// Parachain PoV recovery.
 #[cfg(feature = "cumulus-client-pov-recovery")]
 pub use cumulus_client_pov_recovery;
@@ -113,6 +129,10 @@ pub use cumulus_client_pov_recovery;
 #[cfg(feature = "cumulus-client-service")]
 pub use cumulus_client_service;
 
+/// Pallet for reporting equivocations of Aura collators.
+#[cfg(feature = "cumulus-pallet-aura-equivocation")]
+pub use cumulus_pallet_aura_equivocation;
+
 /// AURA consensus extension pallet for parachains.
 #[cfg(feature = "cumulus-pallet-aura-ext")]
 pub use cumulus_pallet_aura_ext;
@@ -190,6 +210,10 @@ pub use cumulus_relay_chain_inprocess_interface;
 #[cfg(feature = "cumulus-relay-chain-interface")]
 pub use cumulus_relay_chain_interface;
 
+/// Implementation of the RelayChainInterface trait verifying the data of an untrusted source.
+#[cfg(feature = "cumulus-relay-chain-light-client-interface")]
+pub use cumulus_relay_chain_light_client_interface;
+
 /// Minimal node implementation to be used in tandem with RPC or light-client mode.
 #[cfg(feature = "cumulus-relay-chain-minimal-node")]
 pub use cumulus_relay_chain_minimal_node;
@@ -252,6 +276,10 @@ pub use frame_metadata_hash_extension;
 #[cfg(feature = "frame-remote-externalities")]
 pub use frame_remote_externalities;
 
+/// CLI for migrating the state of a FRAME solochain to the genesis of a parachain.
+#[cfg(feature = "frame-solo-to-para-cli")]
+pub use frame_solo_to_para_cli;
+
 /// Support code for the runtime.
 #[cfg(feature = "frame-support")]
 pub use frame_support;
@@ -811,6 +839,10 @@ pub use pallet_xcm_bridge_hub_router;
 #[cfg(feature = "pallet-xcm-precompiles")]
 pub use pallet_xcm_precompiles;
 
+/// A pallet for throttling incoming XCM programs per origin.
+#[cfg(feature = "pallet-xcm-rate-limiter")]
+pub use pallet_xcm_rate_limiter;
+
 /// Logic which is common to all parachain runtimes.
 #[cfg(feature = "parachains-common")]
 pub use parachains_common;
@@ -1551,6 +1583,10 @@ pub use tracing_gum;
 #[cfg(feature = "tracing-gum-proc-macro")]
 pub use tracing_gum_proc_macro;
 
+/// Dry-runs XCM routes across multiple chains using the XCM runtime APIs.
+#[cfg(feature = "xcm-dry-run")]
+pub use xcm_dry_run;
+
 /// Test kit to emulate XCM program execution.
 #[cfg(feature = "xcm-emulator")]
 pub use xcm_emulator;